@@ -0,0 +1,425 @@
+//! Game review: replays a recorded game and grades every move.
+//!
+//! Each position of the game is searched once at the configured level. The
+//! score of a played move is recovered from the search of the following
+//! position, so a game of `n` moves costs `n + 1` searches rather than one
+//! multi-PV search per ply.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use num_format::{Locale, ToFormattedString};
+use reversi_core::{
+    board::Board,
+    disc::Disc,
+    formats::{GameRecord, transcript::format_move},
+    level::get_level,
+    search::{Search, SearchRunOptions},
+    square::Square,
+};
+
+use serde_json::{Value, json};
+
+use crate::config::{EngineConfig, OutputFormat};
+use crate::solve::format_time;
+
+/// Disc loss at or above which a move is marked as an inaccuracy (`?!`).
+const INACCURACY_THRESHOLD: f32 = 2.0;
+/// Disc loss at or above which a move is marked as a mistake (`?`).
+const MISTAKE_THRESHOLD: f32 = 4.0;
+/// Disc loss at or above which a move is marked as a blunder (`??`).
+const BLUNDER_THRESHOLD: f32 = 8.0;
+
+const PLY_WIDTH: usize = 3;
+const MOVE_WIDTH: usize = 4;
+const SCORE_WIDTH: usize = 6;
+const LOSS_WIDTH: usize = 5;
+const MARK_WIDTH: usize = 4;
+
+/// Evaluation of a single position, from the side to move's perspective.
+struct PositionEval {
+    score: f32,
+    best_move: Option<Square>,
+    pv: Vec<Square>,
+    nodes: u64,
+    elapsed: Duration,
+}
+
+/// Review of a single played move.
+struct MoveReview {
+    ply: usize,
+    side: Disc,
+    played: Square,
+    /// Score after the played move, from the mover's perspective.
+    played_score: f32,
+    best_move: Option<Square>,
+    /// Score of the best move, from the mover's perspective.
+    best_score: f32,
+    /// Principal variation of the search before the move.
+    pv: Vec<Square>,
+    nodes: u64,
+    elapsed: Duration,
+}
+
+impl MoveReview {
+    /// Discs given away relative to the best move; never negative.
+    fn loss(&self) -> f32 {
+        (self.best_score - self.played_score).max(0.0)
+    }
+
+    fn mark(&self) -> &'static str {
+        let loss = self.loss();
+        if loss >= BLUNDER_THRESHOLD {
+            "??"
+        } else if loss >= MISTAKE_THRESHOLD {
+            "?"
+        } else if loss >= INACCURACY_THRESHOLD {
+            "?!"
+        } else {
+            ""
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "ply": self.ply,
+            "side": if self.side == Disc::White { "white" } else { "black" },
+            "move": self.played.to_string(),
+            "score": self.played_score,
+            "best_move": self.best_move.map(|sq| sq.to_string()),
+            "best_score": self.best_score,
+            "loss": self.loss(),
+            "mark": self.mark(),
+            "pv": self.pv.iter().map(Square::to_string).collect::<Vec<_>>(),
+            "nodes": self.nodes,
+            "time_ms": self.elapsed.as_secs_f64() * 1000.0,
+        })
+    }
+}
+
+/// Per-player totals printed after the move table.
+#[derive(Default)]
+struct PlayerSummary {
+    moves: usize,
+    total_loss: f32,
+    accurate: usize,
+    inaccuracies: usize,
+    mistakes: usize,
+    blunders: usize,
+}
+
+impl PlayerSummary {
+    fn update(&mut self, review: &MoveReview) {
+        let loss = review.loss();
+        self.moves += 1;
+        self.total_loss += loss;
+        if loss < INACCURACY_THRESHOLD {
+            self.accurate += 1;
+        }
+        match review.mark() {
+            "??" => self.blunders += 1,
+            "?" => self.mistakes += 1,
+            "?!" => self.inaccuracies += 1,
+            _ => {}
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "moves": self.moves,
+            "accuracy": self.accuracy(),
+            "average_loss": self.average_loss(),
+            "inaccuracies": self.inaccuracies,
+            "mistakes": self.mistakes,
+            "blunders": self.blunders,
+        })
+    }
+
+    /// Share of moves that lost less than [`INACCURACY_THRESHOLD`] discs.
+    fn accuracy(&self) -> f64 {
+        if self.moves == 0 {
+            0.0
+        } else {
+            self.accurate as f64 * 100.0 / self.moves as f64
+        }
+    }
+
+    fn average_loss(&self) -> f64 {
+        if self.moves == 0 {
+            0.0
+        } else {
+            self.total_loss as f64 / self.moves as f64
+        }
+    }
+}
+
+pub fn analyze(
+    file_path: &Path,
+    config: &EngineConfig,
+    output: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let record = GameRecord::parse(&fs::read_to_string(file_path)?)?;
+    let final_state = record.replay()?;
+
+    let search_options = config.search_options();
+    if output == OutputFormat::Table {
+        print_header(file_path, &record, config.level);
+    }
+
+    let mut search = Search::new(&search_options);
+    search.init();
+
+    let plies: Vec<(Square, Board, Disc)> = final_state
+        .move_history()
+        .iter()
+        .filter_map(|&(sq, board, side)| sq.map(|sq| (sq, board, side)))
+        .collect();
+
+    let start_time = Instant::now();
+    let mut total_nodes: u64 = 0;
+    let mut evals = Vec::with_capacity(plies.len() + 1);
+    for &(_, board, side) in &plies {
+        let eval = evaluate(&mut search, &board, config);
+        total_nodes += eval.nodes;
+        evals.push((eval, side));
+    }
+
+    let final_board = final_state.board();
+    let final_eval = if final_state.is_game_over() {
+        PositionEval {
+            score: final_board.solve(final_board.get_empty_count()) as f32,
+            best_move: None,
+            pv: Vec::new(),
+            nodes: 0,
+            elapsed: Duration::ZERO,
+        }
+    } else {
+        let eval = evaluate(&mut search, final_board, config);
+        total_nodes += eval.nodes;
+        eval
+    };
+    evals.push((final_eval, final_state.side_to_move()));
+    let elapsed = start_time.elapsed();
+
+    let reviews: Vec<MoveReview> = plies
+        .iter()
+        .enumerate()
+        .map(|(i, &(played, _, side))| {
+            let (before, _) = &evals[i];
+            let (after, after_side) = &evals[i + 1];
+            let played_score = if *after_side == side {
+                after.score
+            } else {
+                -after.score
+            };
+            MoveReview {
+                ply: i + 1,
+                side,
+                played,
+                played_score,
+                best_move: before.best_move,
+                best_score: before.score,
+                pv: before.pv.clone(),
+                nodes: before.nodes,
+                elapsed: before.elapsed,
+            }
+        })
+        .collect();
+
+    let (black, white) = summarize(&reviews);
+    match output {
+        OutputFormat::Table => {
+            print_reviews(&reviews);
+            print_summary(&black, &white, elapsed, total_nodes);
+        }
+        OutputFormat::Json => {
+            let document = json!({
+                "file": file_path.display().to_string(),
+                "level": config.level,
+                "black": record.black_name,
+                "white": record.white_name,
+                "moves": reviews.iter().map(MoveReview::to_json).collect::<Vec<_>>(),
+                "summary": {
+                    "black": black.to_json(),
+                    "white": white.to_json(),
+                },
+                "time_ms": elapsed.as_secs_f64() * 1000.0,
+                "nodes": total_nodes,
+            });
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+    }
+    Ok(())
+}
+
+fn evaluate(search: &mut Search, board: &Board, config: &EngineConfig) -> PositionEval {
+    let options = SearchRunOptions::with_level(get_level(config.level), config.selectivity);
+    let start_time = Instant::now();
+    let result = search.run(board, &options);
+    PositionEval {
+        score: result.score().unwrap_or(0.0),
+        best_move: result.best_move(),
+        pv: result.pv_line().to_vec(),
+        nodes: result.n_nodes(),
+        elapsed: start_time.elapsed(),
+    }
+}
+
+fn summarize(reviews: &[MoveReview]) -> (PlayerSummary, PlayerSummary) {
+    let mut black = PlayerSummary::default();
+    let mut white = PlayerSummary::default();
+    for review in reviews {
+        match review.side {
+            Disc::White => white.update(review),
+            _ => black.update(review),
+        }
+    }
+    (black, white)
+}
+
+fn print_header(file_path: &Path, record: &GameRecord, level: usize) {
+    let file_name = file_path
+        .file_name()
+        .unwrap_or(file_path.as_os_str())
+        .to_string_lossy();
+    println!(
+        "Neural Reversi v{} ({})",
+        env!("CARGO_PKG_VERSION"),
+        env!("TARGET")
+    );
+    println!();
+    println!("- File:      {file_name}");
+    if let Some(name) = &record.black_name {
+        println!("- Black:     {name}");
+    }
+    if let Some(name) = &record.white_name {
+        println!("- White:     {name}");
+    }
+    println!("- Moves:     {}", record.moves.len());
+    println!("- Level:     {level}");
+    println!();
+}
+
+fn print_reviews(reviews: &[MoveReview]) {
+    print_row("#", "Move", "Score", "Best", "Score", "Loss", "");
+    println!(
+        "|{}|{}|{}|{}|{}|{}|{}|",
+        "-".repeat(PLY_WIDTH + 2),
+        "-".repeat(MOVE_WIDTH + 2),
+        "-".repeat(SCORE_WIDTH + 2),
+        "-".repeat(MOVE_WIDTH + 2),
+        "-".repeat(SCORE_WIDTH + 2),
+        "-".repeat(LOSS_WIDTH + 2),
+        "-".repeat(MARK_WIDTH + 2),
+    );
+    for review in reviews {
+        print_row(
+            review.ply,
+            format_move(review.played, review.side),
+            format!("{:+.1}", review.played_score),
+            review
+                .best_move
+                .map_or("--".to_string(), |sq| format_move(sq, review.side)),
+            format!("{:+.1}", review.best_score),
+            format!("{:.1}", review.loss()),
+            review.mark(),
+        );
+    }
+    println!();
+}
+
+fn print_summary(
+    black: &PlayerSummary,
+    white: &PlayerSummary,
+    elapsed: Duration,
+    total_nodes: u64,
+) {
+    for (name, summary) in [("Black", black), ("White", white)] {
+        println!(
+            "{name}: accuracy {:.1}% ({}/{}), avg loss {:.2}, inaccuracies {}, mistakes {}, blunders {}",
+            summary.accuracy(),
+            summary.accurate,
+            summary.moves,
+            summary.average_loss(),
+            summary.inaccuracies,
+            summary.mistakes,
+            summary.blunders
+        );
+    }
+    println!(
+        "Time: {}  Nodes: {}",
+        format_time(elapsed),
+        total_nodes.to_formatted_string(&Locale::en)
+    );
+}
+
+fn print_row(
+    ply: impl Display,
+    played: impl Display,
+    played_score: impl Display,
+    best: impl Display,
+    best_score: impl Display,
+    loss: impl Display,
+    mark: impl Display,
+) {
+    println!(
+        "| {ply:>PLY_WIDTH$} | {played:^MOVE_WIDTH$} | {played_score:>SCORE_WIDTH$} | {best:^MOVE_WIDTH$} | {best_score:>SCORE_WIDTH$} | {loss:>LOSS_WIDTH$} | {mark:<MARK_WIDTH$} |"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(played_score: f32, best_score: f32) -> MoveReview {
+        MoveReview {
+            ply: 1,
+            side: Disc::Black,
+            played: Square::F5,
+            played_score,
+            best_move: Some(Square::F5),
+            best_score,
+            pv: vec![Square::F5],
+            nodes: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn marks_follow_loss_thresholds() {
+        assert_eq!(review(0.0, 1.0).mark(), "");
+        assert_eq!(review(0.0, 2.0).mark(), "?!");
+        assert_eq!(review(-2.0, 2.0).mark(), "?");
+        assert_eq!(review(-10.0, 0.0).mark(), "??");
+    }
+
+    #[test]
+    fn loss_is_never_negative() {
+        // A search can score the played move above its own best line.
+        assert_eq!(review(3.0, 1.0).loss(), 0.0);
+    }
+
+    #[test]
+    fn summary_counts_accuracy_and_marks() {
+        let mut summary = PlayerSummary::default();
+        for r in [review(0.0, 0.0), review(0.0, 5.0), review(0.0, 9.0)] {
+            summary.update(&r);
+        }
+        assert_eq!(summary.moves, 3);
+        assert_eq!(summary.accurate, 1);
+        assert_eq!(summary.mistakes, 1);
+        assert_eq!(summary.blunders, 1);
+        assert!((summary.average_loss() - 14.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn json_review_lists_move_and_pv() {
+        let value = review(-2.0, 2.0).to_json();
+        assert_eq!(value["move"], "f5");
+        assert_eq!(value["side"], "black");
+        assert_eq!(value["mark"], "?");
+        assert_eq!(value["pv"], json!(["f5"]));
+    }
+}
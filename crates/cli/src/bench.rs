@@ -0,0 +1,159 @@
+//! Fixed-position search benchmark.
+//!
+//! Searches a canned set of positions at a fixed depth and reports the total
+//! node count as a signature, in the spirit of Stockfish's `bench`. With a
+//! single search thread the signature is deterministic, so a changed value
+//! flags a functional change in search or evaluation, while the N/s figure
+//! tracks performance.
+
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use num_format::{Locale, ToFormattedString};
+use reversi_core::{
+    level::Level,
+    obf::ObfPosition,
+    search::{Search, SearchRunOptions},
+    types::Depth,
+};
+
+use crate::config::EngineConfig;
+use crate::solve::format_time;
+
+/// Default search depth used when `--depth` is not given.
+pub const DEFAULT_BENCH_DEPTH: Depth = 12;
+
+/// Positions searched by the benchmark, in OBF notation.
+///
+/// The set spans the opening, the midgame (from `problem/small-35.txt`) and
+/// the late midgame (from the FFO and hard endgame suites), so both the
+/// midgame and endgame search paths contribute to the signature.
+const BENCH_POSITIONS: &[&str] = &[
+    "---------------------------OX------XO--------------------------- X",
+    "--O--------OOX----XXXXX---XXXXXO--OXXOO---XXOO-X--XOOO---------- O",
+    "-----------O---X---OXXXX---OXXOX---OXXXX---OOXXX---OXO-----OOOO- O",
+    "------------------XXXOOO--XXXXXO--XXOOXO-OOXOOOO--XX-O-----X---- O",
+    "------------OX------XX-----XOXXO-OOOOXX---OXOXXX---OXO----OOOOO- O",
+    "-XXXXX--O-XXOO--OOXXXO--OXOOOX--OXOOXXX-OOOXXX----OX-X-------X-- O",
+    "--XXXX--O-XXXX--OOXOXXX-OOOOOXOOOOXOXO--OOOXOO--O-XO------------ O",
+    "--XXXXX--OOOXX-O-OOOXXOX-OXOXOXXOXXXOXXX--XOXOXX-XXXOOO--OOOOO-- X",
+    "-XXXXXX---XOOOO--XOXXOOX-OOOOOOOOOOOXXOOOOOXXOOX--XXOO----XXXXX- X",
+    "----OX----OOXX---OOOXX-XOOXXOOOOOXXOXXOOOXXXOOOOOXXXXOXO--OOOOOX X",
+    "-XXXXXX-X-XXXOO-XOXXXOOXXXOXOOOX-OXOOXXX--OOOXXX--OOXX----XOXXO- X",
+];
+
+const NUM_WIDTH: usize = 3;
+const EMPTIES_WIDTH: usize = 7;
+const SCORE_WIDTH: usize = 5;
+const MOVE_WIDTH: usize = 4;
+const NODES_WIDTH: usize = 15;
+const TIME_WIDTH: usize = 11;
+
+/// Runs the benchmark at `depth` and prints per-position and total results.
+pub fn bench(config: &EngineConfig, depth: Depth) -> Result<(), Box<dyn std::error::Error>> {
+    let search_options = config.search_options();
+    let mut search = Search::new(&search_options);
+    let level = Level {
+        mid_depth: depth,
+        end_depth: [depth; 4],
+    };
+
+    println!(
+        "Neural Reversi v{} ({})",
+        env!("CARGO_PKG_VERSION"),
+        env!("TARGET")
+    );
+    println!();
+    println!("- Positions: {}", BENCH_POSITIONS.len());
+    println!("- Depth:     {depth}");
+    println!("- Hash size: {} MB", search_options.tt_mb_size);
+    println!("- Threads:   {}", search_options.n_threads);
+    println!();
+
+    print_row("#", "Empties", "Score", "Move", "Nodes", "Time");
+    println!(
+        "|{}|{}|{}|{}|{}|{}|",
+        "-".repeat(NUM_WIDTH + 2),
+        "-".repeat(EMPTIES_WIDTH + 2),
+        "-".repeat(SCORE_WIDTH + 2),
+        "-".repeat(MOVE_WIDTH + 2),
+        "-".repeat(NODES_WIDTH + 2),
+        "-".repeat(TIME_WIDTH + 2),
+    );
+
+    let mut total_time = Duration::ZERO;
+    let mut total_nodes: u64 = 0;
+
+    for (i, line) in BENCH_POSITIONS.iter().enumerate() {
+        let pos = ObfPosition::parse(line)?.ok_or("empty bench position")?;
+
+        // Each position starts from a cold table so the signature does not
+        // depend on the order in which positions are searched.
+        search.init();
+        let options = SearchRunOptions::with_level(level, config.selectivity);
+        let start_time = Instant::now();
+        let result = search.run(&pos.board, &options);
+        let elapsed = start_time.elapsed();
+
+        total_time += elapsed;
+        total_nodes += result.n_nodes();
+
+        print_row(
+            i + 1,
+            pos.board.get_empty_count(),
+            result
+                .score()
+                .map_or("--".to_string(), |s| format!("{:+03}", s.round() as i32)),
+            result
+                .best_move()
+                .map_or("--".to_string(), |sq| sq.to_string()),
+            result.n_nodes().to_formatted_string(&Locale::en),
+            format_time(elapsed),
+        );
+    }
+
+    let total_secs = total_time.as_secs_f64();
+    let nps = if total_secs > 0.0 {
+        total_nodes as f64 / total_secs
+    } else {
+        0.0
+    };
+
+    println!();
+    println!("Total time (ms) : {}", total_time.as_millis());
+    println!("Nodes searched  : {total_nodes}");
+    println!("Nodes/second    : {}", nps.round() as u64);
+
+    Ok(())
+}
+
+fn print_row(
+    num: impl Display,
+    empties: impl Display,
+    score: impl Display,
+    mv: impl Display,
+    nodes: impl Display,
+    time: impl Display,
+) {
+    println!(
+        "| {num:>NUM_WIDTH$} | {empties:>EMPTIES_WIDTH$} | {score:^SCORE_WIDTH$} | {mv:^MOVE_WIDTH$} | {nodes:>NODES_WIDTH$} | {time:>TIME_WIDTH$} |"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench_positions_parse_and_are_playable() {
+        for line in BENCH_POSITIONS {
+            let pos = ObfPosition::parse(line)
+                .expect("bench position parses")
+                .expect("bench position is not blank");
+            assert!(
+                pos.board.has_legal_moves(),
+                "side to move must have a legal move: {line}"
+            );
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Opening book maintenance commands.
+//!
+//! Thin front end over [`reversi_core::book`]: builds books from game
+//! collections, merges them, probes positions and exports entries as OBF.
+//!
+//! Games are read from WTHOR databases (`.wtb`), self-play records written
+//! by `datagen` (`.bin`), or text files holding one transcript or GGF game
+//! per line.
+
+use std::cmp::Reverse;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use reversi_core::{
+    board::Board,
+    book::{Book, BookEntry},
+    disc::Disc,
+    formats::{
+        GameRecord,
+        selfplay::{self, GAME_SCORE_UNAVAILABLE, MoveKind, SelfplayRecord},
+        wthor,
+    },
+    obf::{self, ObfPosition},
+    square::Square,
+};
+
+/// Builds a book from game files.
+///
+/// Games that fail to parse or replay are reported and skipped.
+pub fn build(
+    inputs: &[PathBuf],
+    output: &Path,
+    max_ply: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut book = Book::new();
+    let mut games = 0usize;
+    for input in inputs {
+        for (location, record) in read_games(input)? {
+            match record.and_then(|record| book.add_game(&record, max_ply)) {
+                Ok(()) => games += 1,
+                Err(e) => eprintln!("{}:{location}: {e}", input.display()),
+            }
+        }
+    }
+    book.save(output)?;
+    println!(
+        "Built {} from {games} games: {} positions",
+        output.display(),
+        book.len()
+    );
+    Ok(())
+}
+
+/// A game read from a file, or the error reading it, with its location.
+type LabeledGame = (String, Result<GameRecord, String>);
+
+/// Reads the games of `path`, choosing the format by its extension, each
+/// labeled with its line or game number for error messages.
+fn read_games(path: &Path) -> Result<Vec<LabeledGame>, String> {
+    let error = |e: String| format!("{}: {e}", path.display());
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    let games = match ext.as_deref() {
+        Some("wtb") => wthor::read(&fs::read(path).map_err(|e| error(e.to_string()))?),
+        Some("bin") => selfplay_games(&fs::read(path).map_err(|e| error(e.to_string()))?),
+        _ => {
+            let text = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+            return Ok(text
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| ((i + 1).to_string(), GameRecord::parse(line)))
+                .collect());
+        }
+    };
+    Ok(games
+        .map_err(error)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, game)| (format!("game {}", i + 1), Ok(game)))
+        .collect())
+}
+
+/// Splits `datagen` self-play records into games.
+///
+/// A game is a run of records with the same game id and consecutive plies.
+/// The move played from each position is rebuilt from the next record's
+/// board, since opening records store the search's best move rather than
+/// the random move the game went on with. The stored move is only trusted
+/// for the last record of a game, and dropped there if it is an opening move.
+fn selfplay_games(bytes: &[u8]) -> Result<Vec<GameRecord>, String> {
+    let record_size = selfplay::RECORD_SIZE as usize;
+    if !bytes.len().is_multiple_of(record_size) {
+        return Err(format!(
+            "Self-play file of {} bytes is not a whole number of {record_size}-byte records",
+            bytes.len()
+        ));
+    }
+    let records = bytes
+        .chunks_exact(record_size)
+        .enumerate()
+        .map(|(i, chunk)| {
+            SelfplayRecord::from_bytes(chunk.try_into().unwrap())
+                .map_err(|e| format!("Self-play record {}: {e}", i + 1))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut games: Vec<GameRecord> = Vec::new();
+    let mut continues = false;
+    for (i, record) in records.iter().enumerate() {
+        if !continues {
+            let mut game = GameRecord::from_position(record.board, record.side_to_move);
+            game.result =
+                (record.game_score != GAME_SCORE_UNAVAILABLE).then(|| match record.side_to_move {
+                    Disc::Black => i32::from(record.game_score),
+                    _ => -i32::from(record.game_score),
+                });
+            games.push(game);
+        }
+
+        let next = records.get(i + 1).filter(|next| {
+            next.game_id == record.game_id && record.ply.checked_add(1) == Some(next.ply)
+        });
+        let played = match next {
+            Some(next) => record.played_move(next),
+            None => (record.move_kind != MoveKind::Opening).then_some(record.sq),
+        };
+        if let Some(sq) = played.filter(|&sq| sq != Square::None) {
+            games.last_mut().unwrap().moves.push(sq);
+        }
+        // A next record that does not follow by one move starts a new game
+        continues = next.is_some() && played.is_some();
+    }
+    Ok(games)
+}
+
+/// Merges several books into one.
+pub fn merge(inputs: &[PathBuf], output: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut book = Book::new();
+    for input in inputs {
+        book.merge(&Book::load(input)?);
+    }
+    book.save(output)?;
+    println!(
+        "Merged {} books into {}: {} positions",
+        inputs.len(),
+        output.display(),
+        book.len()
+    );
+    Ok(())
+}
+
+/// Prints the weighted book moves for a position.
+///
+/// The position is given either as a move transcript from the initial
+/// position or as an OBF string; with neither, the initial position is used.
+pub fn probe(
+    book_path: &Path,
+    moves: Option<&str>,
+    obf: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let book = Book::load(book_path)?;
+    let (board, side_to_move) = match (moves, obf) {
+        (_, Some(obf)) => {
+            let pos = ObfPosition::parse(obf)?.ok_or("empty OBF position")?;
+            (pos.board, pos.side_to_move)
+        }
+        (Some(moves), None) => {
+            let state = GameRecord::parse(moves)?.replay()?;
+            (*state.board(), state.side_to_move())
+        }
+        (None, None) => (Board::new(), Disc::Black),
+    };
+
+    println!("{}", board.to_string_as_board(side_to_move));
+    println!();
+
+    let Some(book_moves) = book.probe(&board) else {
+        println!("Position not in book");
+        return Ok(());
+    };
+
+    let total: u64 = book_moves.iter().map(|m| m.count as u64).sum();
+    println!("| Move |  Count | Weight |  Score |");
+    println!("|:----:|-------:|-------:|-------:|");
+    for mv in book_moves {
+        println!(
+            "| {:^4} | {:>6} | {:>5.1}% | {:>6} |",
+            mv.sq.to_string(),
+            mv.count,
+            mv.count as f64 * 100.0 / total as f64,
+            mv.average_score()
+                .map_or("--".to_string(), |s| format!("{s:+.1}"))
+        );
+    }
+    Ok(())
+}
+
+/// Writes every book position with scored moves as an OBF line, listing
+/// moves by descending average outcome.
+pub fn export(book_path: &Path, output: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let book = Book::load(book_path)?;
+    let mut writer = BufWriter::new(File::create(output)?);
+    let mut written = 0usize;
+    for (board, entry) in book.iter() {
+        if let Some(line) = format_obf(board, entry) {
+            writeln!(writer, "{line}")?;
+            written += 1;
+        }
+    }
+    writer.flush()?;
+    println!("Exported {written} positions to {}", output.display());
+    Ok(())
+}
+
+fn format_obf(board: &Board, entry: &BookEntry) -> Option<String> {
+    let mut scored: Vec<(String, i32)> = entry
+        .moves
+        .iter()
+        .filter_map(|m| {
+            m.average_score()
+                .map(|s| (m.sq.to_string().to_uppercase(), s.round() as i32))
+        })
+        .collect();
+    if scored.is_empty() {
+        return None;
+    }
+    scored.sort_by_key(|&(_, score)| Reverse(score));
+
+    let mut line = obf::format_board_header(board, entry.side_to_move);
+    for (sq, score) in scored {
+        line.push_str(&format!("; {sq}:{score:+}"));
+    }
+    line.push(';');
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reversi_core::book::DEFAULT_BOOK_PLY;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("cli-book-{}-{name}", std::process::id()))
+    }
+
+    fn built_book(name: &str, inputs: &[PathBuf]) -> Book {
+        let output = temp_path(&format!("{name}.book"));
+        build(inputs, &output, DEFAULT_BOOK_PLY).unwrap();
+        let book = Book::load(&output).unwrap();
+        fs::remove_file(&output).unwrap();
+        book
+    }
+
+    /// Encodes a game from the initial position as self-play records the way
+    /// `datagen` writes them: the first `opening_plies` records are opening
+    /// moves that store another legal move as the search's best move.
+    fn selfplay_records(
+        transcript: &str,
+        game_id: u16,
+        black_score: i8,
+        opening_plies: usize,
+    ) -> Vec<u8> {
+        let record = GameRecord::parse(transcript).unwrap();
+        let state = record.replay().unwrap();
+        let mut bytes = Vec::new();
+        let plies = state
+            .move_history()
+            .iter()
+            .filter_map(|&(sq, board, side)| sq.map(|sq| (sq, board, side)));
+        for (ply, (sq, board, side)) in plies.enumerate() {
+            let (move_kind, sq) = if ply < opening_plies {
+                let best = board.get_moves().iter().find(|&best| best != sq);
+                (MoveKind::Opening, best.unwrap_or(sq))
+            } else {
+                (MoveKind::Search, sq)
+            };
+            let game_score = if side == Disc::Black || black_score == GAME_SCORE_UNAVAILABLE {
+                black_score
+            } else {
+                -black_score
+            };
+            let record = SelfplayRecord {
+                game_id,
+                ply: ply as u8,
+                board,
+                score: 0.0,
+                game_score,
+                side_to_move: side,
+                move_kind,
+                sq,
+            };
+            bytes.extend(record.to_bytes());
+        }
+        bytes
+    }
+
+    fn book_move(book: &Book, board: &Board, sq: &str) -> (u32, Option<f32>) {
+        let sq: Square = sq.parse().unwrap();
+        let mv = book
+            .probe(board)
+            .unwrap()
+            .iter()
+            .find(|mv| mv.sq == sq)
+            .unwrap();
+        (mv.count, mv.average_score())
+    }
+
+    #[test]
+    fn builds_from_wthor_database() {
+        let games: Vec<GameRecord> = [("f5d6c3", 10), ("f5f6e6", -4), ("d3c5", 0)]
+            .into_iter()
+            .map(|(transcript, result)| GameRecord {
+                result: Some(result),
+                ..GameRecord::parse(transcript).unwrap()
+            })
+            .collect();
+        let input = temp_path("games.wtb");
+        fs::write(&input, wthor::write(&games, 2024).unwrap()).unwrap();
+        let book = built_book("wthor", std::slice::from_ref(&input));
+        fs::remove_file(&input).unwrap();
+
+        let initial = Board::new();
+        assert_eq!(book_move(&book, &initial, "f5"), (2, Some(3.0)));
+        assert_eq!(book_move(&book, &initial, "d3"), (1, Some(0.0)));
+        let after_f5 = initial.make_move("f5".parse().unwrap());
+        assert_eq!(book_move(&book, &after_f5, "f6"), (1, Some(4.0)));
+    }
+
+    #[test]
+    fn builds_from_selfplay_records() {
+        let mut bytes = selfplay_records("f5d6c3d3", 7, 6, 2);
+        bytes.extend(selfplay_records("f5f6", 8, GAME_SCORE_UNAVAILABLE, 2));
+        bytes.extend(selfplay_records("d3c3", 8, -2, 0));
+        let input = temp_path("selfplay.bin");
+        fs::write(&input, &bytes).unwrap();
+        let book = built_book("selfplay", std::slice::from_ref(&input));
+
+        // The two games with id 8 are split by the restarting ply
+        let initial = Board::new();
+        assert_eq!(book_move(&book, &initial, "f5"), (2, Some(6.0)));
+        assert_eq!(book_move(&book, &initial, "d3"), (1, Some(-2.0)));
+
+        // Opening records are booked with the move played, not the stored
+        // best move, and an opening move ending a game is dropped
+        let after_f5 = initial.make_move(Square::F5);
+        let replies = book.probe(&after_f5).unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].sq, Square::D6);
+
+        // A truncated file is rejected
+        fs::write(&input, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(read_games(&input).is_err());
+        fs::remove_file(&input).unwrap();
+    }
+
+    #[test]
+    fn exported_lines_parse_as_obf() {
+        let mut book = Book::new();
+        for (transcript, result) in [("f5d6", 8), ("f5f6", -2), ("d3", 0)] {
+            let mut record = GameRecord::parse(transcript).unwrap();
+            record.result = Some(result);
+            book.add_game(&record, DEFAULT_BOOK_PLY).unwrap();
+        }
+
+        let (board, entry) = book.iter().find(|(b, _)| **b == Board::new()).unwrap();
+        let line = format_obf(board, entry).unwrap();
+        let pos = ObfPosition::parse(&line).unwrap().unwrap();
+        assert_eq!(pos.board, Board::new());
+        assert_eq!(pos.side_to_move, Disc::Black);
+        assert_eq!(pos.expected_score(), Some(3));
+    }
+}
@@ -1,9 +1,23 @@
 //! Resolved engine configuration shared by every CLI mode.
+//!
+//! Engine parameters are taken from the command line first, then from the
+//! user's configuration file, then from the built-in defaults.
 
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 
+use clap::ValueEnum;
+use reversi_core::level::MAX_LEVEL;
 use reversi_core::probcut::Selectivity;
 use reversi_core::search::options::SearchOptions;
+use serde::{Deserialize, Serialize};
+
+/// Transposition table size used when neither the CLI nor the config file sets one.
+pub const DEFAULT_HASH_SIZE: usize = 512;
+/// Search level used when neither the CLI nor the config file sets one.
+pub const DEFAULT_LEVEL: usize = 21;
+/// Largest accepted transposition table size in MB.
+pub const MAX_HASH_SIZE: usize = 16384;
 
 /// Engine parameters resolved from CLI arguments.
 ///
@@ -41,3 +55,274 @@ impl EngineConfig {
             .with_eval_paths(self.eval_file.as_deref(), self.eval_sm_file.as_deref())
     }
 }
+
+/// Engine defaults read from `config.toml`.
+///
+/// Keys mirror the long CLI flags, e.g.
+///
+/// ```toml
+/// hash-size = 1024
+/// level = 24
+/// threads = 8
+/// eval-file = "weights/eval.zst"
+/// ```
+///
+/// Relative weight paths are resolved against the directory holding the file.
+/// TUI appearance lives in a `[tui]` table; see [`TuiConfig`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FileConfig {
+    pub hash_size: Option<usize>,
+    pub level: Option<usize>,
+    pub selectivity: Option<u8>,
+    pub threads: Option<usize>,
+    pub eval_file: Option<PathBuf>,
+    pub eval_sm_file: Option<PathBuf>,
+    pub tui: TuiConfig,
+}
+
+/// Color theme of the TUI.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Bright colors for dark terminal backgrounds.
+    #[default]
+    Dark,
+    /// Dark colors for light terminal backgrounds.
+    Light,
+}
+
+/// Glyphs used to draw discs on the TUI board.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiscStyle {
+    /// `●` and `○`.
+    #[default]
+    Unicode,
+    /// `X` and `O`, for fonts without the circles.
+    Ascii,
+}
+
+/// TUI appearance, read from and saved to the `[tui]` table:
+///
+/// ```toml
+/// [tui]
+/// theme = "light"
+/// disc-style = "ascii"
+/// flip-board = true
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TuiConfig {
+    pub theme: Theme,
+    pub disc_style: DiscStyle,
+    /// Show the board from White's side.
+    pub flip_board: bool,
+}
+
+impl TuiConfig {
+    /// Writes these settings to the `[tui]` table of the configuration file
+    /// at `path`, editing that table in place so that the rest of the file,
+    /// comments and formatting included, is kept. The file and its directory
+    /// are created if missing.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let mut document = match fs::read_to_string(path) {
+            Ok(text) => text
+                .parse::<toml_edit::DocumentMut>()
+                .map_err(|e| format!("{}: {e}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => toml_edit::DocumentMut::new(),
+            Err(e) => return Err(format!("Failed to read {}: {e}", path.display())),
+        };
+        let table = document
+            .entry("tui")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut()
+            .ok_or_else(|| format!("{}: tui is not a table", path.display()))?;
+
+        let settings = toml::Table::try_from(self).map_err(|e| e.to_string())?;
+        for (key, value) in settings {
+            let mut value = value
+                .to_string()
+                .parse::<toml_edit::Value>()
+                .map_err(|e| e.to_string())?;
+            // Replace existing settings in place to keep their comments
+            match table.get_mut(&key).and_then(|item| item.as_value_mut()) {
+                Some(existing) => {
+                    *value.decor_mut() = existing.decor().clone();
+                    *existing = value;
+                }
+                None => {
+                    table.insert(&key, toml_edit::Item::Value(value));
+                }
+            }
+        }
+
+        if let Some(dir) = path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        fs::write(path, document.to_string())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+    }
+}
+
+impl FileConfig {
+    /// Returns `$XDG_CONFIG_HOME/neural-reversi/config.toml`, falling back to
+    /// `~/.config/neural-reversi/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .or_else(|| std::env::var_os("USERPROFILE"))
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })?;
+        Some(base.join("neural-reversi").join("config.toml"))
+    }
+
+    /// Loads the configuration file.
+    ///
+    /// An explicitly given `path` must exist; a missing file at the default
+    /// location simply yields an empty configuration.
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let text = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let mut config = Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for file in [&mut config.eval_file, &mut config.eval_sm_file]
+            .into_iter()
+            .flatten()
+        {
+            if file.is_relative() {
+                *file = base.join(&*file);
+            }
+        }
+        Ok(config)
+    }
+
+    /// Parses and range-checks the contents of a configuration file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(text).map_err(|e| e.to_string())?;
+        if let Some(hash_size) = config.hash_size
+            && !(1..=MAX_HASH_SIZE).contains(&hash_size)
+        {
+            return Err(format!(
+                "hash-size must be between 1 and {MAX_HASH_SIZE}, got {hash_size}"
+            ));
+        }
+        if let Some(level) = config.level
+            && !(1..=MAX_LEVEL).contains(&level)
+        {
+            return Err(format!(
+                "level must be between 1 and {MAX_LEVEL}, got {level}"
+            ));
+        }
+        if let Some(selectivity) = config.selectivity
+            && selectivity > 3
+        {
+            return Err(format!(
+                "selectivity must be between 0 and 3, got {selectivity}"
+            ));
+        }
+        Ok(config)
+    }
+}
+
+/// Output format of the batch modes (`solve`, `analyze`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable markdown tables.
+    #[default]
+    Table,
+    /// A single JSON document on stdout.
+    Json,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_kebab_case_keys() {
+        let config = FileConfig::parse(
+            "hash-size = 1024\nlevel = 24\nthreads = 4\neval-file = \"eval.zst\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.hash_size, Some(1024));
+        assert_eq!(config.level, Some(24));
+        assert_eq!(config.threads, Some(4));
+        assert_eq!(config.eval_file, Some(PathBuf::from("eval.zst")));
+        assert_eq!(config.selectivity, None);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_keys_and_out_of_range_values() {
+        assert!(FileConfig::parse("hash_size = 1024").is_err());
+        assert!(FileConfig::parse("level = 0").is_err());
+        assert!(FileConfig::parse("selectivity = 4").is_err());
+        assert!(FileConfig::parse("").is_ok());
+        assert!(FileConfig::parse("[tui]\ntheme = \"sepia\"").is_err());
+    }
+
+    #[test]
+    fn tui_settings_are_saved_next_to_engine_settings() {
+        let path = std::env::temp_dir().join(format!("tui-config-{}.toml", std::process::id()));
+        fs::write(&path, "level = 24\n").unwrap();
+
+        let tui = TuiConfig {
+            theme: Theme::Light,
+            disc_style: DiscStyle::Ascii,
+            flip_board: true,
+        };
+        let saved = tui.save(&path);
+        let config = FileConfig::load(Some(&path));
+        let _ = fs::remove_file(&path);
+
+        saved.unwrap();
+        let config = config.unwrap();
+        assert_eq!(config.level, Some(24));
+        assert_eq!(config.tui, tui);
+    }
+
+    #[test]
+    fn saving_tui_settings_keeps_comments() {
+        let path = std::env::temp_dir().join(format!("tui-comments-{}.toml", std::process::id()));
+        let original = "\
+# Engine defaults
+level = 24 # strong enough
+
+[tui]
+# Readable on the office monitor
+theme = \"dark\" # or light
+";
+        fs::write(&path, original).unwrap();
+
+        let tui = TuiConfig {
+            theme: Theme::Light,
+            disc_style: DiscStyle::Ascii,
+            flip_board: false,
+        };
+        let saved = tui.save(&path);
+        let text = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+
+        saved.unwrap();
+        let text = text.unwrap();
+        assert!(text.starts_with("# Engine defaults\nlevel = 24 # strong enough\n"));
+        assert!(text.contains("# Readable on the office monitor\ntheme = \"light\" # or light\n"));
+        let config = FileConfig::parse(&text).unwrap();
+        assert_eq!(config.level, Some(24));
+        assert_eq!(config.tui, tui);
+    }
+}
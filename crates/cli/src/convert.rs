@@ -0,0 +1,225 @@
+//! Conversion between game and position file formats.
+//!
+//! Games are read into [`GameRecord`]s and written back out in the target
+//! format. OBF files hold positions rather than games: reading one yields
+//! move-less records, and writing one emits the position reached at the end
+//! of each game, which turns a list of openings into a test suite.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::Datelike;
+use clap::ValueEnum;
+use reversi_core::{
+    board::Board,
+    disc::Disc,
+    formats::{GameRecord, ggf, transcript, wthor},
+    obf::{self, ObfPosition},
+};
+
+/// File formats understood by `convert`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FileFormat {
+    /// One move transcript per line, e.g. `f5d6c3`.
+    Transcript,
+    /// One OBF position per line.
+    Obf,
+    /// GGF game records.
+    Ggf,
+    /// Binary WTHOR database (`.wtb`).
+    Wthor,
+}
+
+impl FileFormat {
+    /// Guesses the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "txt" | "trn" => Some(Self::Transcript),
+            "obf" => Some(Self::Obf),
+            "ggf" => Some(Self::Ggf),
+            "wtb" => Some(Self::Wthor),
+            _ => None,
+        }
+    }
+}
+
+pub fn convert(
+    input: &Path,
+    output: Option<&Path>,
+    from: Option<FileFormat>,
+    to: FileFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let from = from
+        .or_else(|| FileFormat::from_path(input))
+        .ok_or("cannot infer the input format from the file name; pass --from")?;
+
+    let records = if from == FileFormat::Wthor {
+        wthor::read(&fs::read(input)?)?
+    } else {
+        read_text(&fs::read_to_string(input)?, from)
+    };
+
+    let mut skipped = 0usize;
+    let bytes = if to == FileFormat::Wthor {
+        if output.is_none() {
+            return Err("WTHOR output is binary; pass --output".into());
+        }
+        let year = records
+            .iter()
+            .find_map(|r| r.date.as_deref().and_then(parse_year))
+            .unwrap_or_else(|| chrono::Local::now().year() as u16);
+        wthor::write(&records, year)?
+    } else {
+        let mut text = String::new();
+        for (i, record) in records.iter().enumerate() {
+            match format_record(record, to) {
+                Ok(line) => {
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+                Err(e) => {
+                    eprintln!("record {}: {e}", i + 1);
+                    skipped += 1;
+                }
+            }
+        }
+        text.into_bytes()
+    };
+
+    match output {
+        Some(path) => fs::write(path, bytes)?,
+        None => io::stdout().write_all(&bytes)?,
+    }
+    eprintln!(
+        "Converted {} of {} records",
+        records.len() - skipped,
+        records.len()
+    );
+    Ok(())
+}
+
+/// Reads the records of a text format, reporting and skipping bad entries.
+fn read_text(text: &str, format: FileFormat) -> Vec<GameRecord> {
+    let entries: Vec<(usize, &str)> = match format {
+        FileFormat::Ggf => split_ggf(text),
+        _ => text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .collect(),
+    };
+
+    let mut records = Vec::new();
+    for (line_num, entry) in entries {
+        let parsed = match format {
+            FileFormat::Obf => ObfPosition::parse(entry)
+                .map(|pos| pos.map(|pos| GameRecord::from_position(pos.board, pos.side_to_move))),
+            FileFormat::Ggf => ggf::parse(entry).map(Some),
+            _ if entry.trim().is_empty() => Ok(None),
+            _ => transcript::parse(entry).map(|moves| Some(GameRecord::from_moves(moves))),
+        };
+        match parsed {
+            Ok(Some(record)) => records.push(record),
+            Ok(None) => {}
+            Err(e) => eprintln!("line {line_num}: {e}"),
+        }
+    }
+    records
+}
+
+/// Splits a GGF file into `(;` ... `;)` blocks, which may span lines or
+/// share one, tagged with the line each block starts on.
+fn split_ggf(text: &str) -> Vec<(usize, &str)> {
+    let mut games = Vec::new();
+    let mut rest = text;
+    let mut offset = 0;
+    while let Some(start) = rest.find("(;") {
+        let Some(len) = rest[start..].find(";)") else {
+            break;
+        };
+        let end = start + len + 2;
+        let line_num = text[..offset + start].matches('\n').count() + 1;
+        games.push((line_num, &rest[start..end]));
+        offset += end;
+        rest = &rest[end..];
+    }
+    games
+}
+
+fn format_record(record: &GameRecord, format: FileFormat) -> Result<String, String> {
+    match format {
+        FileFormat::Transcript => {
+            if record.board != Board::new() || record.side_to_move != Disc::Black {
+                return Err("transcripts must start from the initial position".to_string());
+            }
+            record.replay()?;
+            Ok(transcript::format(&record.moves))
+        }
+        FileFormat::Obf => {
+            let state = record.replay()?;
+            Ok(obf::format_board_header(
+                state.board(),
+                state.side_to_move(),
+            ))
+        }
+        FileFormat::Ggf => ggf::format(record),
+        FileFormat::Wthor => unreachable!("WTHOR is written as a whole file"),
+    }
+}
+
+/// Extracts a leading four-digit year from a date such as `2003.12.15`.
+fn parse_year(date: &str) -> Option<u16> {
+    date.get(..4)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_to_obf_emits_the_final_position() {
+        let records = read_text("f5d6\n\nf5f6\n", FileFormat::Transcript);
+        assert_eq!(records.len(), 2);
+
+        let line = format_record(&records[0], FileFormat::Obf).unwrap();
+        let pos = ObfPosition::parse(&line).unwrap().unwrap();
+        let expected = records[0].replay().unwrap();
+        assert_eq!(pos.board, *expected.board());
+        assert_eq!(pos.side_to_move, Disc::Black);
+    }
+
+    #[test]
+    fn obf_positions_cannot_become_transcripts() {
+        let records = read_text(
+            "---------------------------OX------XXX-------------------------- O\n",
+            FileFormat::Obf,
+        );
+        assert_eq!(records.len(), 1);
+        assert!(format_record(&records[0], FileFormat::Transcript).is_err());
+        assert!(format_record(&records[0], FileFormat::Ggf).is_ok());
+    }
+
+    #[test]
+    fn ggf_blocks_are_split_across_lines() {
+        let text = "(;GM[Othello]B[f5];) (;GM[Othello]\nB[d3];)\n(;GM[Othello]B[c4];)";
+        let blocks = split_ggf(text);
+        let lines: Vec<usize> = blocks.iter().map(|&(line, _)| line).collect();
+        assert_eq!(lines, [1, 1, 3]);
+        assert_eq!(read_text(text, FileFormat::Ggf).len(), 3);
+    }
+
+    #[test]
+    fn formats_follow_file_extensions() {
+        assert_eq!(
+            FileFormat::from_path(Path::new("WTH_2001.WTB")),
+            Some(FileFormat::Wthor)
+        );
+        assert_eq!(
+            FileFormat::from_path(Path::new("suite.obf")),
+            Some(FileFormat::Obf)
+        );
+        assert_eq!(FileFormat::from_path(Path::new("games")), None);
+    }
+}
@@ -5,15 +5,18 @@
 
 use std::fmt::Write;
 
-use reversi_core::{board::Board, disc::Disc, game_state, square::Square};
+use reversi_core::{board::Board, disc::Disc, formats::GameRecord, game_state, square::Square};
 
 /// Represents the state of a Reversi/Othello game with CLI-specific features.
 ///
 /// This is a thin wrapper around the core `GameState` that adds
-/// text-based display functionality for GTP output.
+/// text-based display functionality for GTP output and a redo list for
+/// stepping back and forth through a game.
 pub struct GameState {
     /// Core game state
     core: game_state::GameState,
+    /// Moves taken back with [`Self::back`], most recent last
+    redo: Vec<Square>,
 }
 
 impl Default for GameState {
@@ -30,6 +33,7 @@ impl GameState {
     pub fn new() -> Self {
         Self {
             core: game_state::GameState::new(),
+            redo: Vec::new(),
         }
     }
 
@@ -37,9 +41,31 @@ impl GameState {
     pub fn from_board(board: Board, side_to_move: Disc) -> Self {
         Self {
             core: game_state::GameState::from_board(board, side_to_move),
+            redo: Vec::new(),
         }
     }
 
+    /// Creates a game state by replaying a game record.
+    pub fn from_record(record: &GameRecord) -> Result<Self, String> {
+        Ok(Self {
+            core: record.replay()?,
+            redo: Vec::new(),
+        })
+    }
+
+    /// Returns the whole game as a record, including moves taken back with
+    /// [`Self::back`].
+    pub fn to_record(&self) -> GameRecord {
+        let (board, side_to_move) = match self.core.move_history().first() {
+            Some(&(_, board, side)) => (board, side),
+            None => (*self.core.board(), self.core.side_to_move()),
+        };
+        let mut record = GameRecord::from_position(board, side_to_move);
+        record.moves = self.move_history();
+        record.moves.extend(self.redo.iter().rev());
+        record
+    }
+
     /// Creates a game state by replaying a sequence of moves from the initial position.
     ///
     /// Automatically handles passes when a player has no legal moves.
@@ -77,6 +103,13 @@ impl GameState {
         self.core
             .make_move(sq)
             .expect("Attempted to make illegal move");
+        // Replaying the next move keeps the rest of the line; any other
+        // move starts a new one.
+        if self.redo.last() == Some(&sq) {
+            self.redo.pop();
+        } else {
+            self.redo.clear();
+        }
     }
 
     /// Executes a pass move (switching players without placing a piece).
@@ -163,11 +196,59 @@ impl GameState {
         result
     }
 
-    /// Undoes the last move if possible.
+    /// Undoes the last move if possible, discarding the redo list.
     pub fn undo(&mut self) -> bool {
+        self.redo.clear();
         self.core.undo()
     }
 
+    /// Takes back the last move of `side` and everything played after it,
+    /// discarding the redo list. Returns `false` if `side` has not moved.
+    pub fn takeback(&mut self, side: Disc) -> bool {
+        self.redo.clear();
+        self.core.takeback(side)
+    }
+
+    /// Steps back over the last move, and any pass that followed it, keeping
+    /// the move for [`Self::forward`]. Returns `false` at the start of the game.
+    pub fn back(&mut self) -> bool {
+        while let Some(&(sq, _, _)) = self.core.move_history().last() {
+            self.core.undo();
+            if let Some(sq) = sq {
+                self.redo.push(sq);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Replays the next move taken back with [`Self::back`]. Returns `false`
+    /// at the end of the line.
+    pub fn forward(&mut self) -> bool {
+        let Some(sq) = self.redo.pop() else {
+            return false;
+        };
+        if !self.core.board().has_legal_moves() && !self.core.is_game_over() {
+            let _ = self.core.make_pass();
+        }
+        self.core
+            .make_move(sq)
+            .expect("Redo list holds legal moves");
+        true
+    }
+
+    /// Returns whether moves taken back can be replayed.
+    pub fn can_forward(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Returns the number of moves played so far and in the whole line,
+    /// excluding passes.
+    pub fn ply(&self) -> (usize, usize) {
+        let played = self.move_history().len();
+        (played, played + self.redo.len())
+    }
+
     /// Returns the last move played.
     ///
     /// Returns `None` if the last move was a pass (including automatic passes
@@ -213,6 +294,36 @@ mod tests {
         assert_eq!(state.move_history(), vec![sq("d3")]);
     }
 
+    #[test]
+    fn back_and_forward_walk_the_game() {
+        let moves = [sq("f5"), sq("d6"), sq("c3")];
+        let mut state = GameState::from_moves(&moves).unwrap();
+        let end = *state.board();
+
+        assert!(state.back());
+        assert!(state.back());
+        assert_eq!(state.ply(), (1, 3));
+        assert_eq!(state.to_record().moves, moves);
+
+        assert!(state.forward());
+        assert!(state.forward());
+        assert!(!state.forward());
+        assert_eq!(*state.board(), end);
+
+        while state.back() {}
+        assert_eq!(*state.board(), Board::new());
+        assert_eq!(state.ply(), (0, 3));
+    }
+
+    #[test]
+    fn a_new_move_discards_the_redo_list() {
+        let mut state = GameState::from_moves(&[sq("f5"), sq("d6")]).unwrap();
+        state.back();
+        state.make_move(sq("f6"));
+        assert!(!state.can_forward());
+        assert_eq!(state.to_record().moves, vec![sq("f5"), sq("f6")]);
+    }
+
     #[test]
     fn from_moves_rejects_illegal_first_move() {
         assert!(GameState::from_moves(&[sq("a1")]).is_err());
@@ -10,16 +10,22 @@
 
 use reversi_core::{
     disc::Disc,
-    level::{MAX_LEVEL, get_level},
+    level::{Level, MAX_LEVEL, get_level},
     probcut::Selectivity,
     search::{self, SearchRunOptions, time_control::TimeControlMode},
     square::Square,
+    types::Scoref,
 };
 
+mod log;
+
+pub use log::GtpLog;
+
 use crate::config::EngineConfig;
 use crate::game::GameState;
 use std::env;
 use std::io::{self, BufRead, Write};
+use std::time::Instant;
 
 /// Represents a parsed GTP command with its arguments.
 ///
@@ -67,6 +73,18 @@ pub enum Command {
         time: u64,
         stones: u32,
     },
+    /// Reports the static evaluation and shallow per-move scores of the
+    /// current position (`nr-eval [level]`, a Neural Reversi extension)
+    NrEval(usize),
+    /// Reports the score of the last generated move (`nr-score`, a Neural
+    /// Reversi extension)
+    NrScore,
+    /// Fixes the midgame search depth of generated moves, or restores the
+    /// level with 0 (`nr-depth`, a Neural Reversi extension)
+    NrDepth(u32),
+    /// Limits generated moves to a node budget, or lifts the limit with 0
+    /// (`nr-nodes`, a Neural Reversi extension)
+    NrNodes(u64),
     /// Represents an unknown or malformed command
     Unknown(String),
 }
@@ -175,11 +193,38 @@ impl Command {
                     Command::Unknown(cmd.to_string())
                 }
             }
+            "nr-eval" => match args {
+                [] => Command::NrEval(NR_EVAL_LEVEL),
+                [level] => match level.parse::<usize>() {
+                    Ok(level) => Command::NrEval(level),
+                    Err(_) => Command::Unknown(cmd.to_string()),
+                },
+                _ => Command::Unknown(cmd.to_string()),
+            },
+            "nr-score" => Command::NrScore,
+            "nr-depth" => match args {
+                [depth] => match depth.parse::<u32>() {
+                    Ok(depth) => Command::NrDepth(depth),
+                    Err(_) => Command::Unknown(cmd.to_string()),
+                },
+                _ => Command::Unknown(cmd.to_string()),
+            },
+            "nr-nodes" => match args {
+                [nodes] => match nodes.parse::<u64>() {
+                    Ok(nodes) => Command::NrNodes(nodes),
+                    Err(_) => Command::Unknown(cmd.to_string()),
+                },
+                _ => Command::Unknown(cmd.to_string()),
+            },
             _ => Command::Unknown(cmd.to_string()),
         }
     }
 }
 
+/// Search level of the per-move scores reported by `nr-eval` when no level
+/// is given.
+const NR_EVAL_LEVEL: usize = 4;
+
 /// List of all supported GTP command names.
 /// Used for the `list_commands` response and command validation.
 const COMMAND_NAMES: &[&str] = &[
@@ -198,6 +243,10 @@ const COMMAND_NAMES: &[&str] = &[
     "set_level",
     "time_settings",
     "time_left",
+    "nr-eval",
+    "nr-score",
+    "nr-depth",
+    "nr-nodes",
 ];
 
 /// Represents a GTP response that can be either successful or an error.
@@ -255,6 +304,14 @@ pub struct GtpEngine {
     black_byo_stones_left: u32,
     /// Remaining stones in the current byo-yomi period for White
     white_byo_stones_left: u32,
+    /// Session log, when `--log-file` is given
+    log: Option<GtpLog>,
+    /// Score of the last `genmove` search, from the mover's perspective
+    last_score: Option<Scoref>,
+    /// Midgame depth set with `nr-depth`, overriding the level and time control
+    fixed_depth: Option<u32>,
+    /// Node budget per move set with `nr-nodes`, overriding the level and time control
+    max_nodes: Option<u64>,
 }
 
 impl GtpEngine {
@@ -280,9 +337,30 @@ impl GtpEngine {
             white_in_byoyomi: false,
             black_byo_stones_left: 0,
             white_byo_stones_left: 0,
+            log: None,
+            last_score: None,
+            fixed_depth: None,
+            max_nodes: None,
         })
     }
 
+    /// Records the GTP dialogue and search summaries to `log`.
+    pub fn with_log(mut self, log: GtpLog) -> Self {
+        self.log = Some(log);
+        self
+    }
+
+    /// Writes to the session log, if any. Logging is disabled after the
+    /// first write error so a full disk cannot interrupt a game.
+    fn write_log(&mut self, write: impl FnOnce(&mut GtpLog) -> io::Result<()>) {
+        if let Some(log) = self.log.as_mut()
+            && let Err(e) = write(log)
+        {
+            eprintln!("Error writing GTP log, logging disabled: {e}");
+            self.log = None;
+        }
+    }
+
     /// Runs the main GTP command loop.
     ///
     /// This method reads commands from stdin, processes them, and writes
@@ -305,6 +383,7 @@ impl GtpEngine {
                     if input.is_empty() || input.starts_with('#') {
                         continue;
                     }
+                    self.write_log(|log| log.incoming(input));
 
                     let (id, cmd, args) = self.parse_input_line(input);
                     if cmd.is_empty() {
@@ -315,10 +394,12 @@ impl GtpEngine {
                     let is_quit = matches!(command, Command::Quit);
                     let response = self.handle_command(command);
 
-                    if let Err(e) = self.output_response(&mut stdout, id, &response) {
+                    let output = self.format_response(id, &response);
+                    if let Err(e) = write!(stdout, "{output}").and_then(|()| stdout.flush()) {
                         eprintln!("Error writing output: {e}");
                         break;
                     }
+                    self.write_log(|log| log.outgoing(&output));
 
                     if is_quit {
                         break;
@@ -388,7 +469,7 @@ impl GtpEngine {
         (id, cmd, args)
     }
 
-    /// Outputs a GTP response to stdout with proper formatting.
+    /// Formats a GTP response for output.
     ///
     /// Formats the response according to GTP protocol:
     /// - Includes command ID if present
@@ -396,29 +477,20 @@ impl GtpEngine {
     /// - Follows with double newline for protocol compliance
     ///
     /// # Arguments
-    /// * `stdout` - Output stream to write to
     /// * `id` - Optional command ID to include in response
     /// * `response` - The response to output
-    fn output_response(
-        &self,
-        stdout: &mut impl Write,
-        id: Option<usize>,
-        response: &GtpResponse,
-    ) -> io::Result<()> {
+    fn format_response(&self, id: Option<usize>, response: &GtpResponse) -> String {
         let response_str = response.to_string();
         match id {
             Some(id) => {
                 if let GtpResponse::Success(_) = response {
-                    write!(stdout, "={} {}\n\n", id, &response_str[2..])
+                    format!("={} {}\n\n", id, &response_str[2..])
                 } else {
-                    write!(stdout, "?{} {}\n\n", id, &response_str[2..])
+                    format!("?{} {}\n\n", id, &response_str[2..])
                 }
             }
-            None => {
-                writeln!(stdout, "{response_str}\n")
-            }
-        }?;
-        stdout.flush()
+            None => format!("{response_str}\n\n"),
+        }
     }
 
     /// Processes a parsed command and returns the appropriate response.
@@ -456,6 +528,10 @@ impl GtpEngine {
                 time,
                 stones,
             } => self.handle_time_left(&color, time, stones),
+            Command::NrEval(level) => self.handle_nr_eval(level),
+            Command::NrScore => self.handle_nr_score(),
+            Command::NrDepth(depth) => self.handle_nr_depth(depth),
+            Command::NrNodes(nodes) => self.handle_nr_nodes(nodes),
             Command::Unknown(cmd) => GtpResponse::Error(format!("unknown command: {cmd}")),
         }
     }
@@ -534,6 +610,7 @@ impl GtpEngine {
     fn handle_clear_board(&mut self) -> GtpResponse {
         self.game = GameState::new();
         self.search.init();
+        self.last_score = None;
         GtpResponse::Success("".to_string())
     }
 
@@ -589,6 +666,7 @@ impl GtpEngine {
             return GtpResponse::Error(msg);
         }
 
+        self.last_score = None;
         if !self.game.board().has_legal_moves() {
             self.game.make_pass();
             return GtpResponse::Success("pass".to_string());
@@ -596,16 +674,33 @@ impl GtpEngine {
 
         // Determine time control mode for this move. If no time control is set,
         // fall back to depth-limited search based on the configured level so
-        // `genmove` returns promptly instead of thinking indefinitely.
+        // `genmove` returns promptly instead of thinking indefinitely. A depth
+        // fixed with `nr-depth` or a node budget set with `nr-nodes` takes
+        // precedence over both; a node budget alone searches as deep as it allows.
         let time_control = self.get_current_time_control();
-        let options = match time_control {
-            TimeControlMode::Infinite => {
+        let options = match (self.fixed_depth, self.max_nodes, time_control) {
+            (Some(mid_depth), _, _) => {
+                let level = Level {
+                    mid_depth,
+                    ..get_level(self.level.min(MAX_LEVEL))
+                };
+                SearchRunOptions::with_level(level, self.selectivity)
+            }
+            (None, Some(_), _) => {
+                SearchRunOptions::with_level(Level::unlimited(), self.selectivity)
+            }
+            (None, None, TimeControlMode::Infinite) => {
                 let level_idx = self.level.min(MAX_LEVEL);
                 SearchRunOptions::with_level(get_level(level_idx), self.selectivity)
             }
-            mode => SearchRunOptions::with_time(mode, self.selectivity),
-        };
+            (None, None, mode) => SearchRunOptions::with_time(mode, self.selectivity),
+        }
+        .max_nodes(self.max_nodes);
+        let start_time = Instant::now();
         let result = self.search.run(self.game.board(), &options);
+        let elapsed = start_time.elapsed();
+        self.write_log(|log| log.search(&result, elapsed));
+        self.last_score = result.score();
 
         if let Some(computer_move) = result.best_move() {
             self.game.make_move(computer_move);
@@ -882,6 +977,79 @@ impl GtpEngine {
         GtpResponse::Success("".to_string())
     }
 
+    /// Handles the `nr-eval` extension command.
+    ///
+    /// Reports the static network evaluation of the current position and
+    /// the score of every legal move from a shallow search, all in discs
+    /// from the side to move's perspective. The game is left unchanged.
+    ///
+    /// The first line holds the static evaluation; each following line
+    /// holds one move and its score, best first:
+    ///
+    /// ```text
+    /// = eval +1.25
+    /// d3 +2.00
+    /// c4 +0.50
+    /// ```
+    ///
+    /// # Arguments
+    /// * `level` - Search level of the per-move scores (1 to [`MAX_LEVEL`])
+    fn handle_nr_eval(&mut self, level: usize) -> GtpResponse {
+        if level == 0 || level > MAX_LEVEL {
+            return GtpResponse::Error(format!("level must be between 1 and {MAX_LEVEL}"));
+        }
+
+        let board = *self.game.board();
+        let mut response = format!("eval {:+.2}", self.search.static_eval(&board));
+        if board.has_legal_moves() {
+            let options =
+                SearchRunOptions::with_level(get_level(level), self.selectivity).multi_pv(true);
+            let result = self.search.run(&board, &options);
+            for pv in result.pv_moves() {
+                response.push_str(&format!("\n{} {:+.2}", pv.sq, pv.score));
+            }
+        }
+        GtpResponse::Success(response)
+    }
+
+    /// Handles the `nr-score` extension command.
+    ///
+    /// Reports the score, in discs from the perspective of the side that
+    /// moved, of the search behind the last `genmove` (e.g. `= +4.50`).
+    /// Fails if the last `genmove` passed or no move has been generated
+    /// since `clear_board`.
+    fn handle_nr_score(&self) -> GtpResponse {
+        match self.last_score {
+            Some(score) => GtpResponse::Success(format!("{score:+.2}")),
+            None => GtpResponse::Error("no generated move to score".to_string()),
+        }
+    }
+
+    /// Handles the `nr-depth` extension command.
+    ///
+    /// Makes `genmove` search every move to the given midgame depth,
+    /// regardless of the level and time control, so that engines can be
+    /// given depth handicaps. The endgame depth of the level still applies.
+    /// A depth of 0 restores the normal behavior.
+    fn handle_nr_depth(&mut self, depth: u32) -> GtpResponse {
+        if depth > 60 {
+            return GtpResponse::Error("depth must be between 0 and 60".to_string());
+        }
+        self.fixed_depth = (depth > 0).then_some(depth);
+        GtpResponse::Success("".to_string())
+    }
+
+    /// Handles the `nr-nodes` extension command.
+    ///
+    /// Makes `genmove` stop every search after the given number of nodes,
+    /// regardless of the level and time control. With a single search thread
+    /// the moves then do not depend on the machine's speed or load, so that
+    /// strength comparisons are reproducible. A budget of 0 lifts the limit.
+    fn handle_nr_nodes(&mut self, nodes: u64) -> GtpResponse {
+        self.max_nodes = (nodes > 0).then_some(nodes);
+        GtpResponse::Success("".to_string())
+    }
+
     /// Checks if a command name is in the list of supported commands.
     ///
     /// # Arguments
@@ -986,6 +1154,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_nr_eval_with_optional_level() {
+        assert!(matches!(
+            Command::from_str_with_args("nr-eval", &[]),
+            Command::NrEval(NR_EVAL_LEVEL)
+        ));
+        assert!(matches!(
+            Command::from_str_with_args("nr-eval", &["12"]),
+            Command::NrEval(12)
+        ));
+        assert!(matches!(
+            Command::from_str_with_args("nr-eval", &["deep"]),
+            Command::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn parses_nr_score() {
+        assert!(matches!(
+            Command::from_str_with_args("nr-score", &[]),
+            Command::NrScore
+        ));
+    }
+
+    #[test]
+    fn parses_nr_depth() {
+        assert!(matches!(
+            Command::from_str_with_args("nr-depth", &["8"]),
+            Command::NrDepth(8)
+        ));
+        assert!(matches!(
+            Command::from_str_with_args("nr-depth", &[]),
+            Command::Unknown(_)
+        ));
+        assert!(matches!(
+            Command::from_str_with_args("nr-depth", &["-1"]),
+            Command::Unknown(_)
+        ));
+    }
+
+    #[test]
+    fn parses_nr_nodes() {
+        assert!(matches!(
+            Command::from_str_with_args("nr-nodes", &["100000"]),
+            Command::NrNodes(100_000)
+        ));
+        assert!(matches!(
+            Command::from_str_with_args("nr-nodes", &["1e6"]),
+            Command::Unknown(_)
+        ));
+    }
+
     #[test]
     fn unknown_command_is_unknown() {
         assert!(matches!(
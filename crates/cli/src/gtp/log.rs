@@ -0,0 +1,108 @@
+//! Timestamped log of a GTP session.
+//!
+//! Every line received from the controller is written with a `<` marker,
+//! every response with `>`, and search summaries with `#`, e.g.
+//!
+//! ```text
+//! 2025-01-01 12:00:00.000 < 3 genmove b
+//! 2025-01-01 12:00:01.250 # search depth=18@95% score=+4.12 nodes=1,234,567 time=1.248s nps=989,236 pv=f5 d6 c3
+//! 2025-01-01 12:00:01.250 > =3 f5
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use num_format::{Locale, ToFormattedString};
+use reversi_core::search::search_result::SearchResult;
+
+/// Appends GTP traffic and search summaries to a log file.
+pub struct GtpLog {
+    writer: BufWriter<File>,
+}
+
+impl GtpLog {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Records a line received from the controller.
+    pub fn incoming(&mut self, line: &str) -> io::Result<()> {
+        self.write_line('<', line)
+    }
+
+    /// Records a response sent to the controller. Multi-line responses are
+    /// logged one line at a time.
+    pub fn outgoing(&mut self, response: &str) -> io::Result<()> {
+        for line in response.trim_end().lines() {
+            self.write_line('>', line)?;
+        }
+        Ok(())
+    }
+
+    /// Records the outcome of a `genmove` search.
+    pub fn search(&mut self, result: &SearchResult, elapsed: Duration) -> io::Result<()> {
+        let line = format_search_summary(result, elapsed);
+        self.write_line('#', &line)
+    }
+
+    fn write_line(&mut self, marker: char, text: &str) -> io::Result<()> {
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+        writeln!(self.writer, "{timestamp} {marker} {text}")?;
+        self.writer.flush()
+    }
+}
+
+fn format_search_summary(result: &SearchResult, elapsed: Duration) -> String {
+    let depth = if result.get_probability() == 100 {
+        result.depth().to_string()
+    } else {
+        format!("{}@{}%", result.depth(), result.get_probability())
+    };
+    let score = result
+        .score()
+        .map_or("--".to_string(), |s| format!("{s:+.2}"));
+    let secs = elapsed.as_secs_f64();
+    let nps = if secs > 0.0 {
+        (result.n_nodes() as f64 / secs).round() as u64
+    } else {
+        0
+    };
+    let pv: Vec<String> = result.pv_line().iter().map(|sq| sq.to_string()).collect();
+    format!(
+        "search depth={depth} score={score} nodes={} time={secs:.3}s nps={} pv={}",
+        result.n_nodes().to_formatted_string(&Locale::en),
+        nps.to_formatted_string(&Locale::en),
+        pv.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn responses_are_logged_line_by_line() {
+        let path = std::env::temp_dir().join(format!("gtp-log-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let mut log = GtpLog::open(&path).unwrap();
+            log.incoming("1 showboard").unwrap();
+            log.outgoing("=1 \n  A B\n1 - -\n\n").unwrap();
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let markers: Vec<&str> = text
+            .lines()
+            .map(|line| line.split(' ').nth(2).unwrap())
+            .collect();
+        assert_eq!(markers, ["<", ">", ">", ">"]);
+        assert!(text.lines().next().unwrap().ends_with("< 1 showboard"));
+    }
+}
@@ -1,16 +1,26 @@
+mod analyze;
+mod bench;
+mod book;
 mod config;
+mod convert;
 mod game;
 mod ggs;
 mod gtp;
+mod perft;
+mod serve;
+mod shell;
 mod solve;
 mod tui;
 
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
-use config::EngineConfig;
+use config::{
+    DEFAULT_HASH_SIZE, DEFAULT_LEVEL, EngineConfig, FileConfig, MAX_HASH_SIZE, OutputFormat,
+};
 use reversi_core::level::MAX_LEVEL;
 use reversi_core::probcut::Selectivity;
+use reversi_core::types::Depth;
 
 fn parse_usize_range<const LO: usize, const HI: usize>(s: &str) -> Result<usize, String> {
     let v: usize = s.parse().map_err(|e| format!("{e}"))?;
@@ -25,28 +35,25 @@ fn parse_usize_range<const LO: usize, const HI: usize>(s: &str) -> Result<usize,
 struct EngineParams {
     #[arg(
         long,
-        default_value = "512",
-        value_parser = parse_usize_range::<1, 16384>,
-        help = "Transposition table size in MB"
+        value_parser = parse_usize_range::<1, MAX_HASH_SIZE>,
+        help = "Transposition table size in MB [default: 512]"
     )]
-    hash_size: usize,
+    hash_size: Option<usize>,
 
     #[arg(
         short,
         long,
-        default_value = "21",
         value_parser = parse_usize_range::<1, MAX_LEVEL>,
-        help = "Search level (affects midgame search depth)"
+        help = "Search level (affects midgame search depth) [default: 21]"
     )]
-    level: usize,
+    level: Option<usize>,
 
     #[arg(
         long,
-        default_value = "0",
         value_parser = clap::value_parser!(u8).range(0..=3),
-        help = "Search selectivity for ProbCut pruning (0: 73%, 1: 95%, 2: 99%, 3: 100%)"
+        help = "Search selectivity for ProbCut pruning (0: 73%, 1: 95%, 2: 99%, 3: 100%) [default: 0]"
     )]
-    selectivity: u8,
+    selectivity: Option<u8>,
 
     #[arg(long, help = "Number of search threads [default: CPU count]")]
     threads: Option<usize>,
@@ -68,15 +75,20 @@ struct EngineParams {
     eval_sm_file: Option<PathBuf>,
 }
 
-impl From<EngineParams> for EngineConfig {
-    fn from(params: EngineParams) -> Self {
+impl EngineParams {
+    /// Fills every parameter not given on the command line from the config
+    /// file, then from the built-in defaults.
+    fn resolve(self, file: &FileConfig) -> EngineConfig {
         EngineConfig {
-            hash_size: params.hash_size,
-            level: params.level,
-            selectivity: Selectivity::from_u8(params.selectivity),
-            threads: params.threads,
-            eval_file: params.eval_file,
-            eval_sm_file: params.eval_sm_file,
+            hash_size: self
+                .hash_size
+                .or(file.hash_size)
+                .unwrap_or(DEFAULT_HASH_SIZE),
+            level: self.level.or(file.level).unwrap_or(DEFAULT_LEVEL),
+            selectivity: Selectivity::from_u8(self.selectivity.or(file.selectivity).unwrap_or(0)),
+            threads: self.threads.or(file.threads),
+            eval_file: self.eval_file.or_else(|| file.eval_file.clone()),
+            eval_sm_file: self.eval_sm_file.or_else(|| file.eval_sm_file.clone()),
         }
     }
 }
@@ -89,6 +101,15 @@ struct Cli {
     #[command(subcommand)]
     command: Option<SubCommands>,
 
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        help = "Config file with default engine parameters [default: ~/.config/neural-reversi/config.toml]"
+    )]
+    config: Option<PathBuf>,
+
     #[command(flatten)]
     engine_params: EngineParams,
 }
@@ -97,6 +118,37 @@ struct Cli {
 enum SubCommands {
     #[command(about = "Start the GTP (Go Text Protocol) interface for engine communication")]
     Gtp {
+        #[arg(
+            long,
+            value_name = "FILE",
+            value_hint = clap::ValueHint::FilePath,
+            help = "Append timestamped GTP dialogue and search summaries to this file"
+        )]
+        log_file: Option<PathBuf>,
+
+        #[command(flatten)]
+        engine_params: EngineParams,
+    },
+    #[command(about = "Serve bestmove/analyze/solve as JSON-RPC over HTTP and WebSocket")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1", help = "Address to listen on")]
+        host: String,
+
+        #[arg(short, long, default_value_t = 8080, help = "Port to listen on")]
+        port: u16,
+
+        #[arg(
+            long,
+            value_name = "ORIGIN",
+            help = "Origin allowed to make browser requests, e.g. http://localhost:5173 (repeatable, * for any)"
+        )]
+        allow_origin: Vec<String>,
+
+        #[command(flatten)]
+        engine_params: EngineParams,
+    },
+    #[command(about = "Start an interactive engine shell with UCI-style commands")]
+    Shell {
         #[command(flatten)]
         engine_params: EngineParams,
     },
@@ -117,9 +169,106 @@ enum SubCommands {
         )]
         all_moves: bool,
 
+        #[arg(
+            long,
+            value_name = "DISCS",
+            help = "Exit with an error if the mean absolute score error exceeds this value"
+        )]
+        max_mae: Option<f64>,
+
+        #[arg(
+            long,
+            value_name = "PERCENT",
+            help = "Exit with an error if the best-move rate falls below this percentage"
+        )]
+        min_best_move: Option<f64>,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Table,
+            help = "Output format"
+        )]
+        output: OutputFormat,
+
+        #[command(flatten)]
+        engine_params: EngineParams,
+    },
+    #[command(about = "Review a recorded game and grade every move")]
+    Analyze {
+        #[arg(help = "Path to a move transcript or GGF game record")]
+        file: PathBuf,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Table,
+            help = "Output format"
+        )]
+        output: OutputFormat,
+
+        #[command(flatten)]
+        engine_params: EngineParams,
+    },
+    #[command(about = "Search a fixed set of positions and print a node-count signature")]
+    Bench {
+        #[arg(
+            long,
+            default_value_t = bench::DEFAULT_BENCH_DEPTH,
+            value_parser = clap::value_parser!(Depth).range(1..=60),
+            help = "Search depth used for every position"
+        )]
+        depth: Depth,
+
         #[command(flatten)]
         engine_params: EngineParams,
     },
+    #[command(about = "Convert between transcript, OBF, GGF and WTHOR files")]
+    Convert {
+        #[arg(help = "Input file")]
+        input: PathBuf,
+
+        #[arg(short, long, help = "Output file [default: stdout]")]
+        output: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Input format [default: inferred from the file extension]"
+        )]
+        from: Option<convert::FileFormat>,
+
+        #[arg(long, value_enum, help = "Output format")]
+        to: convert::FileFormat,
+    },
+    #[command(about = "Count move-generation leaf nodes, split by root move")]
+    Perft {
+        #[arg(
+            short,
+            long,
+            value_parser = clap::value_parser!(u32).range(0..=20),
+            help = "Number of plies to expand (passes do not count)"
+        )]
+        depth: u32,
+
+        #[arg(
+            long,
+            conflicts_with = "moves",
+            help = "Start position as an OBF string"
+        )]
+        obf: Option<String>,
+
+        #[arg(
+            long,
+            help = "Start position as a move transcript from the initial position"
+        )]
+        moves: Option<String>,
+    },
+    #[command(about = "Build, merge, probe and export opening books")]
+    Book {
+        #[command(subcommand)]
+        command: BookCommands,
+    },
     #[command(about = "Connect to a GGS server (default localhost:5000) and play via /os")]
     Ggs {
         #[arg(
@@ -159,26 +308,178 @@ enum SubCommands {
     ShowLicenses,
 }
 
+#[derive(Debug, Subcommand)]
+enum BookCommands {
+    #[command(about = "Build a book from WTHOR (.wtb), self-play (.bin) or transcript/GGF files")]
+    Build {
+        #[arg(
+            required = true,
+            help = "Game files to read, by extension: .wtb WTHOR, .bin datagen self-play, otherwise one transcript or GGF game per line"
+        )]
+        inputs: Vec<PathBuf>,
+
+        #[arg(short, long, help = "Path of the book file to write")]
+        output: PathBuf,
+
+        #[arg(
+            long,
+            default_value_t = reversi_core::book::DEFAULT_BOOK_PLY,
+            help = "Number of plies from each game added to the book"
+        )]
+        max_ply: usize,
+    },
+    #[command(about = "Merge several books into one")]
+    Merge {
+        #[arg(required = true, help = "Book files to merge")]
+        inputs: Vec<PathBuf>,
+
+        #[arg(short, long, help = "Path of the merged book file to write")]
+        output: PathBuf,
+    },
+    #[command(about = "Print the weighted book moves for a position")]
+    Probe {
+        #[arg(help = "Path to the book file")]
+        book: PathBuf,
+
+        #[arg(
+            long,
+            conflicts_with = "obf",
+            help = "Position as a move transcript from the initial position"
+        )]
+        moves: Option<String>,
+
+        #[arg(long, help = "Position as an OBF string")]
+        obf: Option<String>,
+    },
+    #[command(about = "Export book positions with scored moves as OBF lines")]
+    Export {
+        #[arg(help = "Path to the book file")]
+        book: PathBuf,
+
+        #[arg(short, long, help = "Path of the OBF file to write")]
+        output: PathBuf,
+    },
+}
+
 fn main() {
     let args = Cli::parse();
+    let file_config = FileConfig::load(args.config.as_deref()).unwrap_or_else(|err| {
+        eprintln!("Invalid config file: {err}");
+        std::process::exit(1);
+    });
     match args.command {
-        Some(SubCommands::Gtp { engine_params }) => {
-            let config = EngineConfig::from(engine_params);
+        Some(SubCommands::Gtp {
+            log_file,
+            engine_params,
+        }) => {
+            let config = engine_params.resolve(&file_config);
             let mut gtp_engine = gtp::GtpEngine::new(&config).unwrap_or_else(|err| {
                 eprintln!("Failed to initialize engine: {err}");
                 std::process::exit(1);
             });
+            if let Some(path) = log_file {
+                let log = gtp::GtpLog::open(&path).unwrap_or_else(|err| {
+                    eprintln!("Failed to open log file {}: {err}", path.display());
+                    std::process::exit(1);
+                });
+                gtp_engine = gtp_engine.with_log(log);
+            }
             gtp_engine.run();
         }
+        Some(SubCommands::Serve {
+            host,
+            port,
+            allow_origin,
+            engine_params,
+        }) => {
+            let config = engine_params.resolve(&file_config);
+            if let Err(e) = serve::serve(&host, port, allow_origin, &config) {
+                eprintln!("Server error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some(SubCommands::Shell { engine_params }) => {
+            let config = engine_params.resolve(&file_config);
+            if let Err(e) = shell::Shell::new(config).run() {
+                eprintln!("Shell error: {e}");
+                std::process::exit(1);
+            }
+        }
         Some(SubCommands::Solve {
             file,
             exact,
             all_moves,
+            max_mae,
+            min_best_move,
+            output,
             engine_params,
         }) => {
-            let config = EngineConfig::from(engine_params);
-            if let Err(e) = solve::solve(&file, &config, exact, all_moves) {
+            let config = engine_params.resolve(&file_config);
+            let thresholds = solve::RegressionThresholds {
+                max_mae,
+                min_best_move,
+            };
+            if let Err(e) = solve::solve(&file, &config, exact, all_moves, &thresholds, output) {
                 eprintln!("Error solving game: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some(SubCommands::Analyze {
+            file,
+            output,
+            engine_params,
+        }) => {
+            let config = engine_params.resolve(&file_config);
+            if let Err(e) = analyze::analyze(&file, &config, output) {
+                eprintln!("Error analyzing game: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some(SubCommands::Bench {
+            depth,
+            mut engine_params,
+        }) => {
+            // A single thread keeps the node-count signature deterministic.
+            engine_params.threads.get_or_insert(1);
+            let config = engine_params.resolve(&file_config);
+            if let Err(e) = bench::bench(&config, depth) {
+                eprintln!("Benchmark error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some(SubCommands::Convert {
+            input,
+            output,
+            from,
+            to,
+        }) => {
+            if let Err(e) = convert::convert(&input, output.as_deref(), from, to) {
+                eprintln!("Conversion error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some(SubCommands::Perft { depth, obf, moves }) => {
+            if let Err(e) = perft::perft(depth, obf.as_deref(), moves.as_deref()) {
+                eprintln!("Perft error: {e}");
+                std::process::exit(1);
+            }
+        }
+        Some(SubCommands::Book { command }) => {
+            let result = match command {
+                BookCommands::Build {
+                    inputs,
+                    output,
+                    max_ply,
+                } => book::build(&inputs, &output, max_ply),
+                BookCommands::Merge { inputs, output } => book::merge(&inputs, &output),
+                BookCommands::Probe { book, moves, obf } => {
+                    book::probe(&book, moves.as_deref(), obf.as_deref())
+                }
+                BookCommands::Export { book, output } => book::export(&book, &output),
+            };
+            if let Err(e) = result {
+                eprintln!("Book error: {e}");
+                std::process::exit(1);
             }
         }
         Some(SubCommands::Ggs {
@@ -188,7 +489,7 @@ fn main() {
             user,
             engine_params,
         }) => {
-            let config = EngineConfig::from(engine_params);
+            let config = engine_params.resolve(&file_config);
             if let Err(e) = ggs::run_ggs(&script, &host, port, &user, &config) {
                 eprintln!("GGS session error: {e}");
                 std::process::exit(1);
@@ -210,8 +511,9 @@ fn main() {
             print!("{}", include_str!("../THIRD_PARTY_LICENSES.txt"));
         }
         None => {
-            let config = EngineConfig::from(args.engine_params);
-            tui::run(&config).unwrap_or_else(|err| {
+            let config = args.engine_params.resolve(&file_config);
+            let config_path = args.config.clone().or_else(FileConfig::default_path);
+            tui::run(&config, file_config.tui, config_path).unwrap_or_else(|err| {
                 eprintln!("Failed to initialize UI: {err}");
             });
         }
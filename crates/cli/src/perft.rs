@@ -0,0 +1,74 @@
+//! Move generation check: counts the leaf nodes of the game tree.
+//!
+//! Prints the node count below each root move (`divide`) so a mismatch on a
+//! new SIMD backend can be narrowed down to a single subtree.
+
+use std::time::Instant;
+
+use num_format::{Locale, ToFormattedString};
+use reversi_core::{board::Board, disc::Disc, formats::GameRecord, obf::ObfPosition, perft};
+
+use crate::solve::format_time;
+
+/// Runs perft to `depth` from the position given as an OBF string or a move
+/// transcript, or from the initial position when neither is given.
+pub fn perft(
+    depth: u32,
+    obf: Option<&str>,
+    moves: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (board, side_to_move) = match (obf, moves) {
+        (Some(obf), _) => {
+            let pos = ObfPosition::parse(obf)?.ok_or("empty OBF position")?;
+            (pos.board, pos.side_to_move)
+        }
+        (None, Some(moves)) => {
+            let state = GameRecord::parse(moves)?.replay()?;
+            (*state.board(), state.side_to_move())
+        }
+        (None, None) => (Board::new(), Disc::Black),
+    };
+
+    println!("{}", board.to_string_as_board(side_to_move));
+    println!();
+
+    let mover = if board.has_legal_moves() {
+        side_to_move
+    } else {
+        side_to_move.opposite()
+    };
+
+    let start_time = Instant::now();
+    let mut total: u64 = 0;
+    for (sq, nodes) in perft::divide(&board, depth) {
+        let name = sq.to_string();
+        let name = if mover == Disc::White {
+            name.to_uppercase()
+        } else {
+            name
+        };
+        println!("{name}: {}", nodes.to_formatted_string(&Locale::en));
+        total += nodes;
+    }
+    if depth == 0 || total == 0 {
+        // Depth 0, or a finished game: the root itself is the only node.
+        total = perft::perft_board(&board, depth);
+    }
+    let elapsed = start_time.elapsed();
+
+    let secs = elapsed.as_secs_f64();
+    let nps = if secs > 0.0 {
+        (total as f64 / secs).round() as u64
+    } else {
+        0
+    };
+    println!();
+    println!("Depth           : {depth}");
+    println!(
+        "Nodes           : {}",
+        total.to_formatted_string(&Locale::en)
+    );
+    println!("Time            : {}", format_time(elapsed));
+    println!("Nodes/second    : {}", nps.to_formatted_string(&Locale::en));
+    Ok(())
+}
@@ -0,0 +1,353 @@
+//! Analysis server: the native engine behind JSON-RPC 2.0.
+//!
+//! Requests are accepted two ways on the same port:
+//!
+//! - plain HTTP: `POST /` with a JSON-RPC request body, answered with a
+//!   single JSON-RPC response;
+//! - WebSocket: any number of requests per connection, each answered by
+//!   `progress` notifications for every completed iteration followed by the
+//!   final response.
+//!
+//! Methods are `bestmove`, `analyze` (every legal move) and `solve` (exact
+//! score); see [`rpc::Params`] for the parameters. The engine is shared, so
+//! requests from all clients are searched one at a time.
+//!
+//! Browser requests carry an `Origin` header; only origins given with
+//! `--allow-origin` are served, so other web pages cannot drive the engine.
+//! Requests without an `Origin`, such as from scripts, are always served.
+
+mod http;
+mod rpc;
+
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Instant;
+
+use reversi_core::{
+    board::Board,
+    disc::Disc,
+    formats::transcript::format_move,
+    level::{Level, get_level},
+    probcut::Selectivity,
+    search::{
+        Search, SearchRunOptions, search_result::SearchResult, time_control::TimeControlMode,
+    },
+    square::Square,
+};
+use serde_json::{Value, json};
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use crate::config::EngineConfig;
+use rpc::{INVALID_PARAMS, Method, Request, RpcError};
+
+/// Engine state shared by every connection.
+struct Engine {
+    search: Mutex<Search>,
+    level: usize,
+    selectivity: Selectivity,
+    /// Origins allowed to make browser requests; `*` allows any.
+    allowed_origins: Vec<String>,
+}
+
+impl Engine {
+    fn lock(&self) -> MutexGuard<'_, Search> {
+        // A panicking search leaves the engine usable for the next request.
+        self.search.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Returns whether `origin` is one of `allowed_origins`, or any is allowed.
+fn origin_allowed(allowed_origins: &[String], origin: &str) -> bool {
+    allowed_origins
+        .iter()
+        .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+}
+
+/// Listens on `host:port` until the process is terminated, serving browser
+/// requests from `allowed_origins` only.
+pub fn serve(
+    host: &str,
+    port: u16,
+    allowed_origins: Vec<String>,
+    config: &EngineConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let engine = Arc::new(Engine {
+        search: Mutex::new(Search::new(&config.search_options())),
+        level: config.level,
+        selectivity: config.selectivity,
+        allowed_origins,
+    });
+
+    let listener = TcpListener::bind((host, port))?;
+    eprintln!("Listening on {}", listener.local_addr()?);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Connection error: {e}");
+                continue;
+            }
+        };
+        let engine = Arc::clone(&engine);
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &engine) {
+                eprintln!("Connection error: {e}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(stream: TcpStream, engine: &Engine) -> Result<(), Box<dyn std::error::Error>> {
+    let mut reader = BufReader::new(&stream);
+    let request = http::read_head(&mut reader)?;
+
+    let origin = request.header("origin");
+    if let Some(origin) = origin
+        && !origin_allowed(&engine.allowed_origins, origin)
+    {
+        http::write_response(
+            &stream,
+            "403 Forbidden",
+            "text/plain",
+            "Origin not allowed; see --allow-origin\n",
+            None,
+        )?;
+        return Ok(());
+    }
+
+    if request.is_websocket_upgrade() {
+        http::write_websocket_accept(&stream, &request)?;
+        // Frames the client sent right after its handshake are already buffered.
+        let buffered = reader.buffer().to_vec();
+        drop(reader);
+        let socket = WebSocket::from_partially_read(stream, buffered, Role::Server, None);
+        return handle_websocket(socket, engine);
+    }
+
+    let respond = |status, content_type, body: &str| {
+        http::write_response(&stream, status, content_type, body, origin)
+    };
+    match request.method.as_str() {
+        "OPTIONS" => respond("204 No Content", "text/plain", "")?,
+        "POST" if request.path != "/" => respond("404 Not Found", "text/plain", "POST to /\n")?,
+        "POST" => {
+            let body = http::read_body(&mut reader, &request)?;
+            let response = dispatch(&body, engine, None);
+            respond("200 OK", "application/json", &response.to_string())?;
+        }
+        _ => respond(
+            "405 Method Not Allowed",
+            "text/plain",
+            "POST a JSON-RPC request or open a WebSocket\n",
+        )?,
+    }
+    Ok(())
+}
+
+fn handle_websocket(
+    mut socket: WebSocket<TcpStream>,
+    engine: &Engine,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let text = match socket.read()? {
+            Message::Text(text) => text,
+            Message::Close(_) => return Ok(()),
+            _ => continue,
+        };
+
+        let (tx, rx) = mpsc::channel::<Value>();
+        let response = thread::scope(|scope| {
+            let worker = scope.spawn(|| dispatch(text.as_str(), engine, Some(tx)));
+            // The channel closes once the search, and its callback, are dropped.
+            for notification in rx {
+                if socket
+                    .send(Message::text(notification.to_string()))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            worker.join()
+        });
+        let response = response.unwrap_or_else(|_| {
+            rpc::failure(&Value::Null, &RpcError::new(-32603, "search panicked"))
+        });
+        socket.send(Message::text(response.to_string()))?;
+    }
+}
+
+/// Parses and runs one request, returning the JSON-RPC response. Progress
+/// notifications go to `progress` when given.
+fn dispatch(text: &str, engine: &Engine, progress: Option<Sender<Value>>) -> Value {
+    let request = match rpc::parse_request(text) {
+        Ok(request) => request,
+        Err((id, error)) => return rpc::failure(&id, &error),
+    };
+    match execute(&request, engine, progress) {
+        Ok(result) => rpc::success(&request.id, result),
+        Err(error) => rpc::failure(&request.id, &error),
+    }
+}
+
+fn execute(
+    request: &Request,
+    engine: &Engine,
+    progress: Option<Sender<Value>>,
+) -> Result<Value, RpcError> {
+    let (board, side_to_move) = request.params.position()?;
+    let level = request.params.level(engine.level)?;
+
+    if !board.has_legal_moves() {
+        if board.switch_players().has_legal_moves() {
+            return Ok(json!({ "move": "pass" }));
+        }
+        return Ok(json!({
+            "score": board.solve(board.get_empty_count()),
+            "move": null,
+            "game_over": true,
+        }));
+    }
+
+    let options = match (request.method, request.params.time_ms) {
+        (Method::Solve, _) => SearchRunOptions::with_level(Level::perfect(), engine.selectivity),
+        (Method::BestMove, Some(ms)) => SearchRunOptions::with_time(
+            TimeControlMode::Byoyomi {
+                time_per_move_ms: ms,
+            },
+            engine.selectivity,
+        ),
+        (Method::Analyze, Some(_)) => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                "'time_ms' is only supported by bestmove",
+            ));
+        }
+        _ => SearchRunOptions::with_level(get_level(level), engine.selectivity),
+    };
+    let mut options = options.multi_pv(request.method == Method::Analyze);
+    if let Some(tx) = progress {
+        let id = request.id.clone();
+        let tx = Mutex::new(tx);
+        options = options.callback(move |p| {
+            let params = json!({
+                "depth": p.depth,
+                "probability": p.probability,
+                "score": p.score,
+                "move": format_move(p.best_move, side_to_move),
+                "nodes": p.nodes,
+                "pv": format_pv(&board, side_to_move, &p.pv_line),
+            });
+            if let Ok(tx) = tx.lock() {
+                let _ = tx.send(rpc::progress(&id, params));
+            }
+        });
+    }
+
+    let start_time = Instant::now();
+    let result = {
+        let mut search = engine.lock();
+        search.init();
+        search.run(&board, &options)
+    };
+    let elapsed = start_time.elapsed();
+
+    Ok(format_result(
+        request.method,
+        &board,
+        side_to_move,
+        &result,
+        elapsed.as_secs_f64(),
+    ))
+}
+
+fn format_result(
+    method: Method,
+    board: &Board,
+    side: Disc,
+    result: &SearchResult,
+    secs: f64,
+) -> Value {
+    let mut value = json!({
+        "move": result.best_move().map(|sq| format_move(sq, side)),
+        "score": result.score(),
+        "depth": result.depth(),
+        "probability": result.get_probability(),
+        "pv": format_pv(board, side, result.pv_line()),
+        "nodes": result.n_nodes(),
+        "time_ms": secs * 1000.0,
+    });
+    if method == Method::Analyze {
+        value["moves"] = result
+            .pv_moves()
+            .iter()
+            .map(|m| {
+                json!({
+                    "move": format_move(m.sq, side),
+                    "score": m.score,
+                    "pv": format_pv(board, side, &m.pv_line),
+                })
+            })
+            .collect();
+    }
+    value
+}
+
+/// Formats a principal variation from `board`, alternating case with the
+/// mover and inserting explicit passes.
+fn format_pv(board: &Board, side_to_move: Disc, pv: &[Square]) -> Vec<String> {
+    let mut tokens = Vec::with_capacity(pv.len());
+    let mut current = *board;
+    let mut side = side_to_move;
+    for &sq in pv {
+        if !current.has_legal_moves() {
+            tokens.push(if side == Disc::White { "PS" } else { "ps" }.to_string());
+            current = current.switch_players();
+            side = side.opposite();
+        }
+        if !current.is_legal_move(sq) {
+            break;
+        }
+        tokens.push(format_move(sq, side));
+        current = current.make_move(sq);
+        side = side.opposite();
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_listed_origins_are_allowed() {
+        let allowed = ["http://localhost:5173".to_string()];
+        assert!(origin_allowed(&allowed, "http://localhost:5173"));
+        assert!(origin_allowed(&allowed, "HTTP://LOCALHOST:5173"));
+        assert!(!origin_allowed(&allowed, "http://localhost:8000"));
+        assert!(!origin_allowed(&[], "http://localhost:5173"));
+        assert!(origin_allowed(&["*".to_string()], "https://example.com"));
+    }
+
+    #[test]
+    fn pv_alternates_case_and_marks_passes() {
+        let board = Board::new();
+        assert_eq!(
+            format_pv(&board, Disc::Black, &[Square::F5, Square::D6]),
+            ["f5", "D6"]
+        );
+
+        // Black has no move here while White does.
+        let board = Board::from_bitboards(0x000000000000ff00, 0x00000000000000ff);
+        let reply = board.switch_players().get_moves().lsb_square().unwrap();
+        assert_eq!(
+            format_pv(&board, Disc::Black, &[reply]),
+            ["ps".to_string(), reply.to_string().to_uppercase()]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Just enough HTTP/1.1 to accept one JSON-RPC POST per connection, or to
+//! upgrade it to a WebSocket.
+
+use std::io::{self, BufRead, Read, Write};
+use std::net::TcpStream;
+
+/// Largest request head accepted, in bytes.
+const MAX_HEAD: usize = 16 << 10;
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY: usize = 1 << 20;
+
+/// A request line and its headers.
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    /// Returns the value of the first header called `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns whether the request asks for a WebSocket upgrade.
+    pub fn is_websocket_upgrade(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Reads the request line and headers, up to and including the blank line
+/// that ends them.
+pub fn read_head(reader: &mut impl BufRead) -> io::Result<HttpRequest> {
+    let mut limited = reader.take(MAX_HEAD as u64);
+    let mut line = String::new();
+    limited.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or("/").to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if limited.read_line(&mut line)? == 0 {
+            return Err(invalid("request head too large or incomplete"));
+        }
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+    })
+}
+
+/// Reads the body announced by the `Content-Length` of `request`.
+pub fn read_body(reader: &mut impl BufRead, request: &HttpRequest) -> io::Result<String> {
+    let content_length: usize = request
+        .header("content-length")
+        .map_or(Ok(0), str::parse)
+        .map_err(|_| invalid("bad Content-Length"))?;
+    if content_length > MAX_BODY {
+        return Err(invalid("request body too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body).map_err(|_| invalid("body is not UTF-8"))
+}
+
+/// Writes a complete response and closes the exchange.
+///
+/// `allow_origin` is the origin of a cross-origin request permitted by
+/// `--allow-origin`, echoed so that the browser hands it the response.
+pub fn write_response(
+    mut stream: &TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+    allow_origin: Option<&str>,
+) -> io::Result<()> {
+    let cors = allow_origin.map_or_else(String::new, |origin| {
+        format!(
+            "Access-Control-Allow-Origin: {origin}\r\n\
+             Access-Control-Allow-Methods: POST, OPTIONS\r\n\
+             Access-Control-Allow-Headers: Content-Type\r\n\
+             Vary: Origin\r\n"
+        )
+    });
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         {cors}\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Accepts the WebSocket upgrade `request` by writing the handshake response.
+pub fn write_websocket_accept(mut stream: &TcpStream, request: &HttpRequest) -> io::Result<()> {
+    let key = request
+        .header("sec-websocket-key")
+        .ok_or_else(|| invalid("missing Sec-WebSocket-Key"))?;
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        tungstenite::handshake::derive_accept_key(key.as_bytes())
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_head_then_body() {
+        let mut request: &[u8] =
+            b"POST / HTTP/1.1\r\nHost: x\r\nContent-Length: 2\r\nOrigin: http://a\r\n\r\n{}";
+        let head = read_head(&mut request).unwrap();
+        assert_eq!((head.method.as_str(), head.path.as_str()), ("POST", "/"));
+        assert_eq!(head.header("ORIGIN"), Some("http://a"));
+        assert!(!head.is_websocket_upgrade());
+        assert_eq!(read_body(&mut request, &head).unwrap(), "{}");
+    }
+
+    #[test]
+    fn detects_websocket_upgrades() {
+        // A head split over several reads is still read in full
+        let mut request = io::BufReader::with_capacity(
+            8,
+            &b"GET /ws HTTP/1.1\r\nHost: x\r\nUpgrade: WebSocket\r\nConnection: Upgrade\r\n\r\n"[..],
+        );
+        assert!(read_head(&mut request).unwrap().is_websocket_upgrade());
+    }
+
+    #[test]
+    fn rejects_oversized_heads() {
+        let mut request = b"GET / HTTP/1.1\r\n".to_vec();
+        request.extend(b"X-Padding: 0\r\n".repeat(MAX_HEAD / 8));
+        request.extend(b"Upgrade: websocket\r\n\r\n");
+        assert!(read_head(&mut request.as_slice()).is_err());
+    }
+}
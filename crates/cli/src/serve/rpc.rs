@@ -0,0 +1,203 @@
+//! JSON-RPC 2.0 request parsing and response framing.
+
+use reversi_core::{
+    board::Board, disc::Disc, formats::GameRecord, level::MAX_LEVEL, obf::ObfPosition,
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// Engine operations exposed over RPC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Best move at a level or within a time budget.
+    BestMove,
+    /// Scores of every legal move.
+    Analyze,
+    /// Exact endgame solve.
+    Solve,
+}
+
+impl Method {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bestmove" => Some(Self::BestMove),
+            "analyze" => Some(Self::Analyze),
+            "solve" => Some(Self::Solve),
+            _ => None,
+        }
+    }
+}
+
+/// Parameters shared by every method.
+///
+/// The position is given either as an OBF string or as a move transcript
+/// from the initial position; with neither, the initial position is used.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    pub obf: Option<String>,
+    pub moves: Option<String>,
+    pub level: Option<usize>,
+    /// Time budget for `bestmove`, in milliseconds. Overrides `level`.
+    pub time_ms: Option<u64>,
+}
+
+impl Params {
+    /// Resolves the requested position.
+    pub fn position(&self) -> Result<(Board, Disc), RpcError> {
+        let invalid = |e: String| RpcError::new(INVALID_PARAMS, e);
+        match (&self.obf, &self.moves) {
+            (Some(_), Some(_)) => Err(invalid("give either 'obf' or 'moves', not both".into())),
+            (Some(obf), None) => {
+                let pos = ObfPosition::parse(obf)
+                    .map_err(invalid)?
+                    .ok_or_else(|| invalid("empty OBF position".into()))?;
+                Ok((pos.board, pos.side_to_move))
+            }
+            (None, Some(moves)) => {
+                let state = GameRecord::parse(moves)
+                    .and_then(|record| record.replay())
+                    .map_err(invalid)?;
+                Ok((*state.board(), state.side_to_move()))
+            }
+            (None, None) => Ok((Board::new(), Disc::Black)),
+        }
+    }
+
+    /// Returns the requested level, if valid.
+    pub fn level(&self, default: usize) -> Result<usize, RpcError> {
+        match self.level {
+            None => Ok(default),
+            Some(level @ 1..=MAX_LEVEL) => Ok(level),
+            Some(level) => Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("level must be between 1 and {MAX_LEVEL}, got {level}"),
+            )),
+        }
+    }
+}
+
+/// A parsed request.
+#[derive(Debug)]
+pub struct Request {
+    pub id: Value,
+    pub method: Method,
+    pub params: Params,
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Parses a request body. On failure, returns the request id (`null` when
+/// it could not be read) together with the error to report.
+pub fn parse_request(text: &str) -> Result<Request, (Value, RpcError)> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|e| (Value::Null, RpcError::new(PARSE_ERROR, e.to_string())))?;
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    let fail = |code, message: String| (id.clone(), RpcError::new(code, message));
+
+    if value.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Err(fail(
+            INVALID_REQUEST,
+            "expected \"jsonrpc\": \"2.0\"".into(),
+        ));
+    }
+    let name = value
+        .get("method")
+        .and_then(Value::as_str)
+        .ok_or_else(|| fail(INVALID_REQUEST, "missing method".into()))?;
+    let method = Method::from_name(name)
+        .ok_or_else(|| fail(METHOD_NOT_FOUND, format!("unknown method '{name}'")))?;
+    let params = match value.get("params") {
+        None | Some(Value::Null) => Params::default(),
+        Some(params) => serde_json::from_value(params.clone())
+            .map_err(|e| fail(INVALID_PARAMS, e.to_string()))?,
+    };
+
+    Ok(Request { id, method, params })
+}
+
+pub fn success(id: &Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+pub fn failure(id: &Value, error: &RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// A `progress` notification sent over WebSocket while a search runs.
+pub fn progress(id: &Value, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": "progress", "params": { "id": id, "progress": params } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reversi_core::square::Square;
+
+    #[test]
+    fn parses_methods_and_params() {
+        let request = parse_request(
+            r#"{"jsonrpc":"2.0","id":7,"method":"bestmove","params":{"moves":"f5","level":10}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.id, json!(7));
+        assert_eq!(request.method, Method::BestMove);
+        assert_eq!(request.params.level(21), Ok(10));
+        let (board, side) = request.params.position().unwrap();
+        assert_eq!(board, Board::new().make_move(Square::F5));
+        assert_eq!(side, Disc::White);
+    }
+
+    #[test]
+    fn reports_standard_error_codes() {
+        let code = |text: &str| parse_request(text).unwrap_err().1.code;
+        assert_eq!(code("{"), PARSE_ERROR);
+        assert_eq!(code(r#"{"id":1,"method":"solve"}"#), INVALID_REQUEST);
+        assert_eq!(
+            code(r#"{"jsonrpc":"2.0","id":1,"method":"ponder"}"#),
+            METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            code(r#"{"jsonrpc":"2.0","id":1,"method":"solve","params":{"depth":3}}"#),
+            INVALID_PARAMS
+        );
+    }
+
+    #[test]
+    fn rejects_bad_positions_and_levels() {
+        let params = Params {
+            moves: Some("f5f5".into()),
+            ..Params::default()
+        };
+        assert_eq!(params.position().unwrap_err().code, INVALID_PARAMS);
+
+        let params = Params {
+            level: Some(MAX_LEVEL + 1),
+            ..Params::default()
+        };
+        assert!(params.level(21).is_err());
+    }
+}
@@ -0,0 +1,486 @@
+//! Interactive engine shell.
+//!
+//! A line-oriented REPL with UCI-style commands for poking at the engine
+//! without a GUI:
+//!
+//! ```text
+//! > position startpos moves f5 d6 c3
+//! > setoption hash 1024
+//! > go depth 24
+//! info depth 12 score +2.31 nodes 184,220 pv d3 c4 ...
+//! > stop
+//! bestmove d3
+//! ```
+//!
+//! `go` runs in a background thread so `stop` can interrupt it.
+
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use num_format::{Locale, ToFormattedString};
+use reversi_core::{
+    disc::Disc,
+    formats::transcript::format_move,
+    level::{Level, MAX_LEVEL, get_level},
+    obf::ObfPosition,
+    probcut::Selectivity,
+    search::{
+        Search, SearchProgress, SearchRunOptions, threading::ThreadPool,
+        time_control::TimeControlMode,
+    },
+    square::Square,
+    types::Depth,
+};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use crate::config::{EngineConfig, MAX_HASH_SIZE};
+use crate::game::GameState;
+
+const HELP: &str = "\
+Commands:
+  position startpos [moves <m1> <m2> ...]   Set up a position from the start
+  position obf <board> <side>               Set up a position from an OBF string
+  play <move>                               Play a move on the current position
+  undo                                      Take back the last move
+  go [depth <n> | level <n> | movetime <ms> | infinite]
+                                            Search the current position
+  stop                                      Stop the running search
+  setoption <name> <value>                  Set hash, threads, level, selectivity or multipv
+  newgame                                   Clear search caches and reset the board
+  d                                         Show the board
+  help                                      Show this help
+  quit                                      Exit the shell";
+
+/// Search limit given to `go`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoLimit {
+    /// Search at the configured level.
+    Default,
+    /// Fixed depth in both midgame and endgame.
+    Depth(Depth),
+    /// A level from the level table.
+    Level(usize),
+    /// Fixed time per move, in milliseconds.
+    MoveTime(u64),
+    /// Search until `stop`.
+    Infinite,
+}
+
+/// A parsed shell command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShellCommand {
+    /// Start position followed by a list of moves.
+    PositionStart(Vec<Square>),
+    /// Position given as an OBF string.
+    PositionObf(String),
+    Play(Square),
+    Undo,
+    Go(GoLimit),
+    Stop,
+    SetOption {
+        name: String,
+        value: String,
+    },
+    NewGame,
+    Display,
+    Help,
+    Quit,
+}
+
+impl ShellCommand {
+    /// Parses one input line. Returns `Ok(None)` for blank lines.
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let Some((&cmd, args)) = tokens.split_first() else {
+            return Ok(None);
+        };
+
+        let command = match cmd.to_ascii_lowercase().as_str() {
+            "position" => parse_position(args)?,
+            "play" => match args {
+                [mv] => Self::Play(mv.parse::<Square>().map_err(|e| e.to_string())?),
+                _ => return Err("usage: play <move>".to_string()),
+            },
+            "undo" => Self::Undo,
+            "go" => Self::Go(parse_go(args)?),
+            "stop" => Self::Stop,
+            "setoption" => match args {
+                // UCI spells it `setoption name <id> value <x>`; accept both forms.
+                ["name", name, "value", value] | [name, value] => Self::SetOption {
+                    name: name.to_ascii_lowercase(),
+                    value: value.to_string(),
+                },
+                _ => return Err("usage: setoption <name> <value>".to_string()),
+            },
+            "newgame" | "ucinewgame" => Self::NewGame,
+            "d" | "board" => Self::Display,
+            "help" | "?" => Self::Help,
+            "quit" | "exit" => Self::Quit,
+            other => return Err(format!("unknown command '{other}' (type 'help')")),
+        };
+        Ok(Some(command))
+    }
+}
+
+fn parse_position(args: &[&str]) -> Result<ShellCommand, String> {
+    match args {
+        ["startpos"] => Ok(ShellCommand::PositionStart(Vec::new())),
+        ["startpos", "moves", moves @ ..] => {
+            let transcript: String = moves.concat();
+            let moves = Square::parse_sequence(&transcript).map_err(|e| e.to_string())?;
+            Ok(ShellCommand::PositionStart(moves))
+        }
+        ["obf", rest @ ..] if !rest.is_empty() => Ok(ShellCommand::PositionObf(rest.join(" "))),
+        _ => Err("usage: position startpos [moves ...] | position obf <board> <side>".to_string()),
+    }
+}
+
+fn parse_go(args: &[&str]) -> Result<GoLimit, String> {
+    fn number<T: std::str::FromStr>(value: &str, what: &str) -> Result<T, String> {
+        value
+            .parse()
+            .map_err(|_| format!("invalid {what}: '{value}'"))
+    }
+
+    match args {
+        [] => Ok(GoLimit::Default),
+        ["infinite"] => Ok(GoLimit::Infinite),
+        ["depth", n] => match number::<Depth>(n, "depth")? {
+            d @ 1..=60 => Ok(GoLimit::Depth(d)),
+            d => Err(format!("depth must be between 1 and 60, got {d}")),
+        },
+        ["level", n] => match number::<usize>(n, "level")? {
+            l @ 1..=MAX_LEVEL => Ok(GoLimit::Level(l)),
+            l => Err(format!("level must be between 1 and {MAX_LEVEL}, got {l}")),
+        },
+        ["movetime", ms] => Ok(GoLimit::MoveTime(number(ms, "movetime")?)),
+        _ => Err("usage: go [depth <n> | level <n> | movetime <ms> | infinite]".to_string()),
+    }
+}
+
+/// A search running on a worker thread.
+struct Worker {
+    handle: JoinHandle<Search>,
+    pool: Arc<ThreadPool>,
+}
+
+/// State of an interactive shell session.
+pub struct Shell {
+    config: EngineConfig,
+    game: GameState,
+    /// `None` while a worker thread owns the engine.
+    search: Option<Search>,
+    worker: Option<Worker>,
+    multi_pv: bool,
+}
+
+impl Shell {
+    pub fn new(config: EngineConfig) -> Self {
+        let search = Search::new(&config.search_options());
+        Self {
+            config,
+            game: GameState::new(),
+            search: Some(search),
+            worker: None,
+            multi_pv: false,
+        }
+    }
+
+    /// Reads and executes commands until `quit` or end of input.
+    pub fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut editor = DefaultEditor::new()?;
+        println!(
+            "Neural Reversi v{} shell. Type 'help' for commands.",
+            env!("CARGO_PKG_VERSION")
+        );
+
+        loop {
+            let line = match editor.readline("> ") {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => {
+                    self.stop();
+                    continue;
+                }
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(e.into()),
+            };
+            let _ = editor.add_history_entry(line.as_str());
+
+            match ShellCommand::parse(&line) {
+                Ok(Some(ShellCommand::Quit)) => break,
+                Ok(Some(command)) => {
+                    if let Err(e) = self.execute(command) {
+                        println!("error: {e}");
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => println!("error: {e}"),
+            }
+        }
+
+        self.stop();
+        self.reclaim(true);
+        Ok(())
+    }
+
+    fn execute(&mut self, command: ShellCommand) -> Result<(), String> {
+        match command {
+            ShellCommand::Stop => {
+                self.stop();
+                return Ok(());
+            }
+            ShellCommand::Help => {
+                println!("{HELP}");
+                return Ok(());
+            }
+            ShellCommand::Display => {
+                println!("{}", self.game.board_string());
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        if !self.reclaim(false) {
+            return Err("search in progress; use 'stop' first".to_string());
+        }
+
+        match command {
+            ShellCommand::PositionStart(moves) => self.game = GameState::from_moves(&moves)?,
+            ShellCommand::PositionObf(obf) => {
+                let pos = ObfPosition::parse(&obf)?.ok_or("empty OBF position")?;
+                self.game = GameState::from_board(pos.board, pos.side_to_move);
+            }
+            ShellCommand::Play(sq) => {
+                if !self.game.board().is_legal_move(sq) {
+                    return Err(format!("illegal move: {sq}"));
+                }
+                self.game.make_move(sq);
+            }
+            ShellCommand::Undo => {
+                if !self.game.undo() {
+                    return Err("no move to undo".to_string());
+                }
+            }
+            ShellCommand::Go(limit) => self.go(limit)?,
+            ShellCommand::SetOption { name, value } => self.set_option(&name, &value)?,
+            ShellCommand::NewGame => {
+                self.game = GameState::new();
+                if let Some(search) = self.search.as_mut() {
+                    search.init();
+                }
+            }
+            ShellCommand::Stop | ShellCommand::Help | ShellCommand::Display => unreachable!(),
+            ShellCommand::Quit => {}
+        }
+        Ok(())
+    }
+
+    /// Takes the engine back from a finished worker. With `block`, waits for
+    /// the worker to finish. Returns whether the engine is available.
+    fn reclaim(&mut self, block: bool) -> bool {
+        if let Some(worker) = self.worker.take() {
+            if !block && !worker.handle.is_finished() {
+                self.worker = Some(worker);
+                return false;
+            }
+            match worker.handle.join() {
+                Ok(search) => self.search = Some(search),
+                Err(_) => {
+                    println!("error: search thread panicked; restarting engine");
+                    self.search = Some(Search::new(&self.config.search_options()));
+                }
+            }
+        }
+        true
+    }
+
+    fn stop(&self) {
+        if let Some(worker) = &self.worker {
+            worker.pool.abort_search();
+        }
+    }
+
+    fn go(&mut self, limit: GoLimit) -> Result<(), String> {
+        let board = *self.game.board();
+        if !board.has_legal_moves() {
+            return Err(if board.switch_players().has_legal_moves() {
+                "side to move must pass".to_string()
+            } else {
+                "game is over".to_string()
+            });
+        }
+
+        let selectivity = self.config.selectivity;
+        let options = match limit {
+            GoLimit::Default => {
+                SearchRunOptions::with_level(get_level(self.config.level), selectivity)
+            }
+            GoLimit::Level(level) => SearchRunOptions::with_level(get_level(level), selectivity),
+            GoLimit::Depth(depth) => SearchRunOptions::with_level(
+                Level {
+                    mid_depth: depth,
+                    end_depth: [depth; 4],
+                },
+                selectivity,
+            ),
+            GoLimit::MoveTime(ms) => SearchRunOptions::with_time(
+                TimeControlMode::Byoyomi {
+                    time_per_move_ms: ms,
+                },
+                selectivity,
+            ),
+            GoLimit::Infinite => {
+                SearchRunOptions::with_time(TimeControlMode::Infinite, selectivity)
+            }
+        };
+        let side = self.game.side_to_move();
+        let options = options
+            .multi_pv(self.multi_pv)
+            .callback(move |progress| println!("{}", format_info(&progress, side)));
+
+        let mut search = self.search.take().expect("engine owned by the shell");
+        let pool = search.thread_pool();
+        let handle = thread::spawn(move || {
+            let result = search.run(&board, &options);
+            for pv_move in result.pv_moves().iter().skip(1) {
+                println!(
+                    "info move {} score {:+.2}",
+                    format_move(pv_move.sq, side),
+                    pv_move.score
+                );
+            }
+            match result.best_move() {
+                Some(sq) => println!("bestmove {}", format_move(sq, side)),
+                None => println!("bestmove none"),
+            }
+            search
+        });
+        self.worker = Some(Worker { handle, pool });
+        Ok(())
+    }
+
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let parse = |what: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid {what}: '{value}'"))
+        };
+        let search = self.search.as_mut().expect("engine owned by the shell");
+        match name {
+            "hash" => {
+                let mb = parse("hash size")?;
+                if !(1..=MAX_HASH_SIZE).contains(&mb) {
+                    return Err(format!("hash must be between 1 and {MAX_HASH_SIZE} MB"));
+                }
+                search.resize_tt(mb);
+                self.config.hash_size = mb;
+            }
+            "threads" => {
+                let threads = parse("thread count")?;
+                if threads == 0 {
+                    return Err("threads must be at least 1".to_string());
+                }
+                self.config.threads = Some(threads);
+                *search = Search::new(&self.config.search_options());
+            }
+            "level" => {
+                let level = parse("level")?;
+                if !(1..=MAX_LEVEL).contains(&level) {
+                    return Err(format!("level must be between 1 and {MAX_LEVEL}"));
+                }
+                self.config.level = level;
+            }
+            "selectivity" => match value.parse::<u8>() {
+                Ok(s @ 0..=3) => self.config.selectivity = Selectivity::from_u8(s),
+                _ => return Err("selectivity must be between 0 and 3".to_string()),
+            },
+            "multipv" => {
+                self.multi_pv = match value.to_ascii_lowercase().as_str() {
+                    "on" | "true" | "1" => true,
+                    "off" | "false" | "0" => false,
+                    _ => return Err("multipv must be on or off".to_string()),
+                }
+            }
+            other => return Err(format!("unknown option '{other}'")),
+        }
+        println!("{name} = {value}");
+        Ok(())
+    }
+}
+
+fn format_info(progress: &SearchProgress, side: Disc) -> String {
+    let depth = if progress.probability == 100 {
+        progress.depth.to_string()
+    } else {
+        format!("{}@{}%", progress.depth, progress.probability)
+    };
+    let pv: Vec<String> = progress.pv_line.iter().map(|&sq| sq.to_string()).collect();
+    format!(
+        "info depth {depth} score {:+.2} nodes {} move {} pv {}",
+        progress.score,
+        progress.nodes.to_formatted_string(&Locale::en),
+        format_move(progress.best_move, side),
+        pv.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> ShellCommand {
+        ShellCommand::parse(line).unwrap().unwrap()
+    }
+
+    #[test]
+    fn parses_position_commands() {
+        assert_eq!(
+            parse("position startpos"),
+            ShellCommand::PositionStart(vec![])
+        );
+        assert_eq!(
+            parse("position startpos moves f5 d6"),
+            ShellCommand::PositionStart(vec![Square::F5, Square::D6])
+        );
+        assert_eq!(
+            parse("position startpos moves f5d6"),
+            ShellCommand::PositionStart(vec![Square::F5, Square::D6])
+        );
+        assert!(
+            matches!(parse("position obf --- X"), ShellCommand::PositionObf(s) if s == "--- X")
+        );
+        assert!(ShellCommand::parse("position").is_err());
+    }
+
+    #[test]
+    fn parses_go_limits() {
+        assert_eq!(parse("go"), ShellCommand::Go(GoLimit::Default));
+        assert_eq!(parse("go depth 24"), ShellCommand::Go(GoLimit::Depth(24)));
+        assert_eq!(parse("go level 10"), ShellCommand::Go(GoLimit::Level(10)));
+        assert_eq!(
+            parse("go movetime 500"),
+            ShellCommand::Go(GoLimit::MoveTime(500))
+        );
+        assert_eq!(parse("go infinite"), ShellCommand::Go(GoLimit::Infinite));
+        assert!(ShellCommand::parse("go depth 0").is_err());
+        assert!(ShellCommand::parse("go level 99").is_err());
+        assert!(ShellCommand::parse("go fast").is_err());
+    }
+
+    #[test]
+    fn parses_setoption_in_both_forms() {
+        let expected = ShellCommand::SetOption {
+            name: "hash".to_string(),
+            value: "1024".to_string(),
+        };
+        assert_eq!(parse("setoption hash 1024"), expected);
+        assert_eq!(parse("setoption name Hash value 1024"), expected);
+    }
+
+    #[test]
+    fn blank_and_unknown_lines() {
+        assert_eq!(ShellCommand::parse("   ").unwrap(), None);
+        assert!(ShellCommand::parse("frobnicate").is_err());
+    }
+}
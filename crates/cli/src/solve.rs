@@ -15,7 +15,10 @@ use reversi_core::{
     square::Square,
 };
 
-use crate::config::EngineConfig;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::config::{EngineConfig, OutputFormat};
 
 const NUM_WIDTH: usize = 5;
 const DEPTH_WIDTH: usize = 6;
@@ -25,18 +28,155 @@ const NODES_WIDTH: usize = 19;
 const NPS_WIDTH: usize = 13;
 const PV_WIDTH: usize = 23;
 
+const EXPECTED_WIDTH: usize = 8;
+const CHECK_WIDTH: usize = 4;
+
+/// Regression limits checked against the aggregate results of a solve run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RegressionThresholds {
+    /// Largest acceptable mean absolute score error.
+    pub max_mae: Option<f64>,
+    /// Smallest acceptable share of best moves, in percent.
+    pub min_best_move: Option<f64>,
+}
+
+/// Result of solving one position, compared against its OBF expectations.
+///
+/// Serialized as one element of the `positions` array in JSON output.
+#[derive(Serialize)]
+struct PositionReport {
+    position: usize,
+    depth: String,
+    /// Score from the perspective of the side to move in the input.
+    score: Option<i32>,
+    pv: Vec<String>,
+    nodes: u64,
+    time_ms: f64,
+    nps: u64,
+    /// Every legal move with its own score, when `--all-moves` is given.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    moves: Vec<MoveReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected_score: Option<i32>,
+    /// Absolute difference from the expected score, when one is listed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score_error: Option<i32>,
+    /// Whether the chosen move is among the listed best moves, when the
+    /// position lists move scores.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_move_hit: Option<bool>,
+    #[serde(skip)]
+    elapsed: Duration,
+}
+
+#[derive(Serialize)]
+struct MoveReport {
+    score: i32,
+    pv: Vec<String>,
+}
+
+/// Aggregated accuracy against the expectations listed in the input file.
+#[derive(Default)]
+struct AccuracyStats {
+    score_errors: Vec<i32>,
+    best_move_hits: usize,
+    best_move_total: usize,
+}
+
+impl AccuracyStats {
+    fn update(&mut self, report: &PositionReport) {
+        if let Some(error) = report.score_error {
+            self.score_errors.push(error);
+        }
+        if let Some(hit) = report.best_move_hit {
+            self.best_move_total += 1;
+            self.best_move_hits += usize::from(hit);
+        }
+    }
+
+    fn mae(&self) -> Option<f64> {
+        (!self.score_errors.is_empty()).then(|| {
+            self.score_errors.iter().map(|&e| e as f64).sum::<f64>()
+                / self.score_errors.len() as f64
+        })
+    }
+
+    fn best_move_rate(&self) -> Option<f64> {
+        (self.best_move_total > 0)
+            .then(|| self.best_move_hits as f64 * 100.0 / self.best_move_total as f64)
+    }
+
+    fn print(&self) {
+        if let Some(mae) = self.mae() {
+            println!(
+                "Score MAE: {mae:.2} ({} positions)",
+                self.score_errors.len()
+            );
+        }
+        if let Some(rate) = self.best_move_rate() {
+            println!(
+                "Best move: {rate:.1}% ({}/{})",
+                self.best_move_hits, self.best_move_total
+            );
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "mae": self.mae(),
+            "scored_positions": self.score_errors.len(),
+            "best_move_rate": self.best_move_rate(),
+            "best_move_hits": self.best_move_hits,
+            "best_move_total": self.best_move_total,
+        })
+    }
+
+    /// Returns a description of every threshold this run fails.
+    fn regressions(&self, thresholds: &RegressionThresholds) -> Vec<String> {
+        let mut failures = Vec::new();
+        if let (Some(limit), Some(mae)) = (thresholds.max_mae, self.mae())
+            && mae > limit
+        {
+            failures.push(format!("score MAE {mae:.2} exceeds {limit:.2}"));
+        }
+        if let (Some(limit), Some(rate)) = (thresholds.min_best_move, self.best_move_rate())
+            && rate < limit
+        {
+            failures.push(format!("best move rate {rate:.1}% is below {limit:.1}%"));
+        }
+        failures
+    }
+}
+
 pub fn solve(
     file_path: &Path,
     config: &EngineConfig,
     exact: bool,
     all_moves: bool,
+    thresholds: &RegressionThresholds,
+    output: OutputFormat,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let file = File::open(file_path)?;
     let reader = BufReader::new(file);
 
+    let mut positions = Vec::new();
+    for (line_num, line) in reader.lines().enumerate() {
+        match ObfPosition::parse(&line?) {
+            Ok(Some(pos)) => positions.push((line_num + 1, pos)),
+            Ok(None) => {}
+            Err(e) => eprintln!("Error parsing line {}: {}", line_num + 1, e),
+        }
+    }
+    let has_expectations = positions
+        .iter()
+        .any(|(_, pos)| pos.expected_score().is_some());
+    let table = output == OutputFormat::Table;
+
     let search_options = config.search_options();
 
-    print_header(file_path, &search_options);
+    if table {
+        print_header(file_path, &search_options);
+    }
 
     let mut search = Search::new(&search_options);
     let level_config = if exact {
@@ -45,38 +185,35 @@ pub fn solve(
         get_level(config.level)
     };
 
-    if !all_moves {
-        print_table_header();
+    if table && !all_moves {
+        print_table_header(has_expectations);
     }
 
     let mut total_time = Duration::ZERO;
     let mut total_nodes: u64 = 0;
+    let mut accuracy = AccuracyStats::default();
+    let mut reports = Vec::with_capacity(positions.len());
 
-    for (line_num, line) in reader.lines().enumerate() {
-        let raw = line?;
-        let pos = match ObfPosition::parse(&raw) {
-            Ok(Some(pos)) => pos,
-            Ok(None) => continue,
-            Err(e) => {
-                eprintln!("Error parsing line {}: {}", line_num + 1, e);
-                continue;
-            }
-        };
-        let (elapsed, nodes) = solve_position(
+    for (position_num, pos) in &positions {
+        let report = solve_position(
             &mut search,
-            pos.board,
-            pos.side_to_move,
+            pos,
             level_config,
             config.selectivity,
-            line_num + 1,
+            *position_num,
             all_moves,
         );
-        total_time += elapsed;
-        total_nodes += nodes;
-        if all_moves {
-            print_position_stats(elapsed, nodes);
-            println!();
+        total_time += report.elapsed;
+        total_nodes += report.nodes;
+        accuracy.update(&report);
+        if table {
+            if all_moves {
+                print_all_moves_report(&report, has_expectations);
+            } else {
+                print_report_row(&report, has_expectations);
+            }
         }
+        reports.push(report);
     }
 
     let total_secs = total_time.as_secs_f64();
@@ -85,7 +222,21 @@ pub fn solve(
     } else {
         0.0
     };
-    if all_moves {
+    if !table {
+        let document = json!({
+            "file": file_path.display().to_string(),
+            "hash_mb": search_options.tt_mb_size,
+            "threads": search_options.n_threads,
+            "positions": reports,
+            "total": {
+                "time_ms": total_secs * 1000.0,
+                "nodes": total_nodes,
+                "nps": total_nps.round() as u64,
+            },
+            "accuracy": has_expectations.then(|| accuracy.to_json()),
+        });
+        println!("{}", serde_json::to_string_pretty(&document)?);
+    } else if all_moves {
         println!(
             "Total: time {}, nodes {}, n/s {}",
             format_time(total_time),
@@ -101,19 +252,32 @@ pub fn solve(
             total_nodes.to_formatted_string(&Locale::en),
             (total_nps.round() as u64).to_formatted_string(&Locale::en),
             "",
+            has_expectations.then(|| ("".to_string(), "".to_string())),
         );
     }
-    println!();
+
+    if table {
+        println!();
+        if has_expectations {
+            accuracy.print();
+            println!();
+        }
+    }
+
+    let regressions = accuracy.regressions(thresholds);
+    if !regressions.is_empty() {
+        return Err(format!("regression: {}", regressions.join("; ")).into());
+    }
 
     Ok(())
 }
 
-fn print_table_header() {
-    println!(
+fn print_table_header(has_expectations: bool) {
+    let mut header = format!(
         "| {:^NUM_WIDTH$} | {:^DEPTH_WIDTH$} | {:^SCORE_WIDTH$} | {:^TIME_WIDTH$} | {:^NODES_WIDTH$} | {:^NPS_WIDTH$} | {:^PV_WIDTH$} |",
         "#", "Depth", "Score", "Time", "Nodes", "N/s", "Principal Variation"
     );
-    println!(
+    let mut separator = format!(
         "| {} | {} | {} | {} | {} | {} | {} |",
         markdown_align_right(NUM_WIDTH),
         markdown_align_center(DEPTH_WIDTH),
@@ -123,6 +287,19 @@ fn print_table_header() {
         markdown_align_right(NPS_WIDTH),
         markdown_align_left(PV_WIDTH),
     );
+    if has_expectations {
+        header.push_str(&format!(
+            " {:^EXPECTED_WIDTH$} | {:^CHECK_WIDTH$} |",
+            "Expected", "Move"
+        ));
+        separator.push_str(&format!(
+            " {} | {} |",
+            markdown_align_center(EXPECTED_WIDTH),
+            markdown_align_center(CHECK_WIDTH)
+        ));
+    }
+    println!("{header}");
+    println!("{separator}");
 }
 
 fn print_all_moves_table_header() {
@@ -137,18 +314,39 @@ fn print_all_moves_table_header() {
     );
 }
 
-fn print_position_stats(elapsed: Duration, nodes: u64) {
-    let nps = if elapsed.as_secs_f64() > 0.0 {
-        nodes as f64 / elapsed.as_secs_f64()
-    } else {
-        0.0
-    };
+fn print_report_row(report: &PositionReport, has_expectations: bool) {
+    print_row(
+        report.position,
+        &report.depth,
+        format_score(report.score),
+        format_time(report.elapsed),
+        report.nodes.to_formatted_string(&Locale::en),
+        report.nps.to_formatted_string(&Locale::en),
+        format_pv(&report.pv),
+        has_expectations.then(|| format_check(report)),
+    );
+}
+
+fn print_all_moves_report(report: &PositionReport, has_expectations: bool) {
+    println!("Position #{}  Depth: {}", report.position, report.depth);
+    print_all_moves_table_header();
+    if report.moves.is_empty() {
+        print_all_moves_row(format_score(report.score), format_pv(&report.pv));
+    }
+    for mv in &report.moves {
+        print_all_moves_row(format_score(Some(mv.score)), format_pv(&mv.pv));
+    }
     println!(
         "Time: {}  Nodes: {}  N/s: {}",
-        format_time(elapsed),
-        nodes.to_formatted_string(&Locale::en),
-        (nps.round() as u64).to_formatted_string(&Locale::en)
+        format_time(report.elapsed),
+        report.nodes.to_formatted_string(&Locale::en),
+        report.nps.to_formatted_string(&Locale::en)
     );
+    if has_expectations {
+        let (expected, check) = format_check(report);
+        println!("Expected error: {expected}  Best move: {check}");
+    }
+    println!();
 }
 
 fn print_header(file_path: &Path, options: &SearchOptions) {
@@ -168,35 +366,64 @@ fn print_header(file_path: &Path, options: &SearchOptions) {
     println!();
 }
 
+/// Formats the expectation columns: the absolute score error and whether
+/// the chosen move is one of the listed best moves.
+fn format_check(report: &PositionReport) -> (String, String) {
+    let expected = report.score_error.map_or("--".to_string(), |e| {
+        if e == 0 {
+            "ok".to_string()
+        } else {
+            format!("±{e}")
+        }
+    });
+    let check = match report.best_move_hit {
+        Some(true) => "ok",
+        Some(false) => "miss",
+        None => "--",
+    };
+    (expected, check.to_string())
+}
+
+fn format_score(score: Option<i32>) -> String {
+    score.map_or("--".to_string(), |s| format!("{s:+03}"))
+}
+
+fn format_pv(pv: &[String]) -> String {
+    if pv.is_empty() {
+        "--".to_string()
+    } else {
+        pv.join(" ")
+    }
+}
+
 fn solve_position(
     search: &mut Search,
-    board: Board,
-    side_to_move: Disc,
+    pos: &ObfPosition,
     level: Level,
     selectivity: Selectivity,
     position_num: usize,
     all_moves: bool,
-) -> (Duration, u64) {
+) -> PositionReport {
+    let board = pos.board;
+    let side_to_move = pos.side_to_move;
     let is_pass = !board.has_legal_moves();
 
     if is_pass && !board.switch_players().has_legal_moves() {
         let score = board.solve(board.get_empty_count());
-        if all_moves {
-            println!("Position #{}  Depth: END", position_num);
-            print_all_moves_table_header();
-            print_all_moves_row(format!("{:+03}", score), "--");
-            return (Duration::ZERO, 0);
-        }
-        print_row(
-            position_num,
-            "END",
-            format!("{:+03}", score),
-            format_time(Duration::ZERO),
-            "0",
-            "0",
-            "--",
-        );
-        return (Duration::ZERO, 0);
+        return PositionReport {
+            position: position_num,
+            depth: "END".to_string(),
+            score: Some(score),
+            pv: Vec::new(),
+            nodes: 0,
+            time_ms: 0.0,
+            nps: 0,
+            moves: Vec::new(),
+            expected_score: pos.expected_score(),
+            score_error: pos.expected_score().map(|e| (score - e).abs()),
+            best_move_hit: None,
+            elapsed: Duration::ZERO,
+        };
     }
     let search_board = if is_pass {
         board.switch_players()
@@ -227,58 +454,67 @@ fn solve_position(
     } else {
         side_to_move
     };
-
-    if all_moves && !result.pv_moves().is_empty() {
-        println!("Position #{}  Depth: {}", position_num, depth);
-        print_all_moves_table_header();
-        for pv_move in result.pv_moves() {
-            let score = if is_pass {
-                -(pv_move.score as i32)
-            } else {
-                pv_move.score as i32
-            };
-            let pv_string = if pv_move.pv_line.is_empty() {
-                format_root_move(pv_move.sq, move_side, is_pass, side_to_move)
-            } else {
-                format_pv_with_passes(&board, side_to_move, &pv_move.pv_line, 8)
-            };
-
-            print_all_moves_row(format!("{:+03}", score), pv_string);
+    let signed = |score: f32| {
+        if is_pass {
+            -(score as i32)
+        } else {
+            score as i32
         }
-
-        return (elapsed, result.n_nodes());
-    }
-    let result_score = result.score().expect("search returned no legal move");
-    let score = if is_pass {
-        -(result_score as i32)
-    } else {
-        result_score as i32
     };
-    let pv_string = if result.pv_line().is_empty() {
+
+    let signed_score = result.score().map(signed);
+    let pv = if result.pv_line().is_empty() {
         result
             .best_move()
-            .map_or("--".to_string(), |m| format_square(m, move_side))
+            .map(|m| format_root_move(m, move_side, is_pass, side_to_move))
+            .unwrap_or_default()
     } else {
         format_pv_with_passes(&board, side_to_move, result.pv_line(), 8)
     };
+    let moves = if all_moves {
+        result
+            .pv_moves()
+            .iter()
+            .map(|pv_move| MoveReport {
+                score: signed(pv_move.score),
+                pv: if pv_move.pv_line.is_empty() {
+                    format_root_move(pv_move.sq, move_side, is_pass, side_to_move)
+                } else {
+                    format_pv_with_passes(&board, side_to_move, &pv_move.pv_line, 8)
+                },
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
 
-    print_row(
-        position_num,
+    PositionReport {
+        position: position_num,
         depth,
-        format!("{:+03}", score),
-        format_time(elapsed),
-        result.n_nodes().to_formatted_string(&Locale::en),
-        (nodes_per_sec.round() as u64).to_formatted_string(&Locale::en),
-        pv_string,
-    );
-
-    (elapsed, result.n_nodes())
+        score: signed_score,
+        pv,
+        nodes: result.n_nodes(),
+        time_ms: elapsed.as_secs_f64() * 1000.0,
+        nps: nodes_per_sec.round() as u64,
+        moves,
+        expected_score: pos.expected_score(),
+        score_error: pos
+            .expected_score()
+            .zip(signed_score)
+            .map(|(e, s)| (s - e).abs()),
+        best_move_hit: (!pos.is_pass() && !is_pass)
+            .then(|| result.best_move())
+            .flatten()
+            .map(|sq| pos.best_moves().any(|best| best == sq)),
+        elapsed,
+    }
 }
 
 fn print_all_moves_row(score: impl Display, pv: impl Display) {
     println!("| {score:^SCORE_WIDTH$} | {pv:<PV_WIDTH$} |");
 }
 
+#[allow(clippy::too_many_arguments)]
 fn print_row(
     num: impl Display,
     depth: impl Display,
@@ -287,10 +523,17 @@ fn print_row(
     nodes: impl Display,
     nps: impl Display,
     pv: impl Display,
+    check: Option<(String, String)>,
 ) {
-    println!(
+    let mut row = format!(
         "| {num:>NUM_WIDTH$} | {depth:^DEPTH_WIDTH$} | {score:^SCORE_WIDTH$} | {time:>TIME_WIDTH$} | {nodes:>NODES_WIDTH$} | {nps:>NPS_WIDTH$} | {pv:<PV_WIDTH$} |"
     );
+    if let Some((expected, mv)) = check {
+        row.push_str(&format!(
+            " {expected:^EXPECTED_WIDTH$} | {mv:^CHECK_WIDTH$} |"
+        ));
+    }
+    println!("{row}");
 }
 
 fn markdown_align_right(width: usize) -> String {
@@ -310,53 +553,42 @@ fn format_pv_with_passes(
     side_to_move: Disc,
     pv_line: &[Square],
     max_tokens: usize,
-) -> String {
-    let push_token = |s: &mut String, t: &str| {
-        if !s.is_empty() {
-            s.push(' ');
-        }
-        s.push_str(t);
-    };
-
-    let mut result = String::new();
+) -> Vec<String> {
+    let mut tokens = Vec::new();
     let mut current = *board;
     let mut side = side_to_move;
-    let mut count = 0;
 
     for &sq in pv_line {
-        if count >= max_tokens {
+        if tokens.len() >= max_tokens {
             break;
         }
         if !current.has_legal_moves() {
-            push_token(&mut result, format_pass(side));
+            tokens.push(format_pass(side).to_string());
             current = current.switch_players();
             side = side.opposite();
-            count += 1;
-            if count >= max_tokens {
+            if tokens.len() >= max_tokens {
                 break;
             }
         }
-        push_token(&mut result, &format_square(sq, side));
+        tokens.push(format_square(sq, side));
         current = current.make_move(sq);
         side = side.opposite();
-        count += 1;
     }
 
-    result
+    tokens
 }
 
-fn format_root_move(sq: Square, move_side: Disc, is_pass: bool, side_to_move: Disc) -> String {
+fn format_root_move(sq: Square, move_side: Disc, is_pass: bool, side_to_move: Disc) -> Vec<String> {
     if !is_pass {
-        return format_square(sq, move_side);
+        return vec![format_square(sq, move_side)];
     }
-
-    let mut result = String::from(format_pass(side_to_move));
-    result.push(' ');
-    result.push_str(&format_square(sq, move_side));
-    result
+    vec![
+        format_pass(side_to_move).to_string(),
+        format_square(sq, move_side),
+    ]
 }
 
-fn format_time(duration: Duration) -> String {
+pub(crate) fn format_time(duration: Duration) -> String {
     let total_secs = duration.as_secs();
     let frac_secs = duration.as_secs_f64() % 60.0;
     if total_secs >= 3600 {
@@ -381,3 +613,85 @@ fn format_square(sq: Square, side: Disc) -> String {
 fn format_pass(side: Disc) -> &'static str {
     if side == Disc::White { "PS" } else { "ps" }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(score_error: Option<i32>, best_move_hit: Option<bool>) -> PositionReport {
+        PositionReport {
+            position: 1,
+            depth: "END".to_string(),
+            score: Some(0),
+            pv: Vec::new(),
+            nodes: 0,
+            time_ms: 0.0,
+            nps: 0,
+            moves: Vec::new(),
+            expected_score: None,
+            score_error,
+            best_move_hit,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn accuracy_stats_skip_positions_without_expectations() {
+        let mut stats = AccuracyStats::default();
+        stats.update(&outcome(Some(2), Some(true)));
+        stats.update(&outcome(Some(4), Some(false)));
+        stats.update(&outcome(None, None));
+
+        assert_eq!(stats.mae(), Some(3.0));
+        assert_eq!(stats.best_move_rate(), Some(50.0));
+    }
+
+    #[test]
+    fn regressions_report_each_violated_threshold() {
+        let mut stats = AccuracyStats::default();
+        stats.update(&outcome(Some(4), Some(false)));
+
+        let lenient = RegressionThresholds {
+            max_mae: Some(4.0),
+            min_best_move: Some(0.0),
+        };
+        assert!(stats.regressions(&lenient).is_empty());
+
+        let strict = RegressionThresholds {
+            max_mae: Some(1.0),
+            min_best_move: Some(90.0),
+        };
+        assert_eq!(stats.regressions(&strict).len(), 2);
+    }
+
+    #[test]
+    fn thresholds_are_ignored_without_expectations() {
+        let stats = AccuracyStats::default();
+        let strict = RegressionThresholds {
+            max_mae: Some(0.0),
+            min_best_move: Some(100.0),
+        };
+        assert!(stats.regressions(&strict).is_empty());
+    }
+
+    #[test]
+    fn json_report_omits_missing_expectations() {
+        let value = serde_json::to_value(outcome(None, None)).unwrap();
+        assert_eq!(value["depth"], "END");
+        assert!(value.get("score_error").is_none());
+        assert!(value.get("moves").is_none());
+        assert!(value.get("elapsed").is_none());
+
+        let value = serde_json::to_value(outcome(Some(2), Some(true))).unwrap();
+        assert_eq!(value["score_error"], 2);
+        assert_eq!(value["best_move_hit"], true);
+    }
+
+    #[test]
+    fn pv_tokens_include_passes() {
+        let board = Board::new();
+        let tokens = format_pv_with_passes(&board, Disc::Black, &[Square::F5, Square::D6], 8);
+        assert_eq!(tokens, ["f5", "D6"]);
+        assert_eq!(format_pv(&[]), "--");
+    }
+}
@@ -4,18 +4,30 @@
 //! supporting keyboard navigation, mouse input, and real-time game updates.
 
 mod app;
+mod clock;
 mod event;
+mod game_file;
 mod parse;
 mod render;
+mod search_trace;
+mod theme;
 mod widgets;
 
-use crate::config::EngineConfig;
+use std::path::PathBuf;
+
+use crate::config::{EngineConfig, TuiConfig};
 
 use app::App;
 
 /// Runs the TUI, handling user input and game state.
-pub fn run(config: &EngineConfig) -> Result<(), String> {
-    let app = App::new(config)?;
+///
+/// Appearance changes made in the UI are saved to `config_path`.
+pub fn run(
+    config: &EngineConfig,
+    appearance: TuiConfig,
+    config_path: Option<PathBuf>,
+) -> Result<(), String> {
+    let app = App::new(config, appearance, config_path)?;
 
     let terminal = ratatui::init();
     let result = app.run(terminal);
@@ -1,8 +1,10 @@
 //! Application state and main loop for the TUI.
 
-use std::sync::mpsc::{self, Receiver};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ratatui::DefaultTerminal;
 use reversi_core::board::Board;
@@ -10,15 +12,21 @@ use reversi_core::disc::Disc;
 use reversi_core::level;
 use reversi_core::probcut::Selectivity;
 use reversi_core::search::search_result::{PvMove, SearchResult};
-use reversi_core::search::{self, SearchRunOptions};
+use reversi_core::search::threading::ThreadPool;
+use reversi_core::search::time_control::TimeControlMode;
+use reversi_core::search::{self, SearchProgress, SearchRunOptions};
 use reversi_core::square::Square;
 
-use crate::config::EngineConfig;
+use crate::config::{DiscStyle, EngineConfig, Theme, TuiConfig};
 use crate::game::GameState;
 
+use super::clock::{GameClock, TimeControl};
 use super::event::{self, Event};
+use super::game_file;
 use super::parse;
 use super::render;
+use super::search_trace::SearchTrace;
+use super::theme::{Glyphs, Palette};
 
 /// Game mode configuration determining which players are controlled by AI.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +42,15 @@ pub enum GameMode {
 }
 
 impl GameMode {
+    /// Returns the side played by the human in a game against the AI.
+    pub fn human_side(&self) -> Option<Disc> {
+        match self {
+            GameMode::HumanVsAi => Some(Disc::Black),
+            GameMode::AiVsHuman => Some(Disc::White),
+            GameMode::AiVsAi | GameMode::HumanVsHuman => None,
+        }
+    }
+
     /// Returns whether the AI should play for the given side.
     pub fn is_ai_turn(&self, side: Disc) -> bool {
         matches!(
@@ -134,6 +151,42 @@ impl BoardEditTab {
     }
 }
 
+/// Action of the save/load dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAction {
+    /// Write the current game to a file
+    Save,
+    /// Replace the current game with one read from a file
+    Load,
+}
+
+impl FileAction {
+    /// Returns the other action.
+    pub fn toggle(self) -> Self {
+        match self {
+            FileAction::Save => FileAction::Load,
+            FileAction::Load => FileAction::Save,
+        }
+    }
+
+    /// Returns the display name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileAction::Save => "Save",
+            FileAction::Load => "Load",
+        }
+    }
+}
+
+/// Direction of a step through the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Navigation {
+    Back,
+    Forward,
+    Start,
+    End,
+}
+
 /// UI mode for handling different interaction states.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UiMode {
@@ -147,10 +200,16 @@ pub enum UiMode {
     LevelSelect,
     /// Mode selection dialog
     ModeSelect,
+    /// Time control selection dialog
+    ClockSelect,
+    /// Appearance options dialog
+    Options,
     /// Confirming quit
     ConfirmQuit,
     /// Board editor dialog
     BoardEdit,
+    /// Save/load dialog
+    FileDialog,
 }
 
 /// Payload sent back from a worker thread: the borrowed [`search::Search`] is
@@ -172,6 +231,23 @@ fn spawn_search_worker(
     rx
 }
 
+/// Continuous analysis running on a worker thread.
+struct Analysis {
+    /// Position being analyzed
+    board: Board,
+    /// Returns the engine once the search stops
+    receiver: Receiver<SearchChannelItem>,
+    /// Progress reported after every completed iteration
+    progress: Receiver<SearchProgress>,
+    /// Thread pool of the running search, used to abort it
+    pool: Arc<ThreadPool>,
+    /// Whether the search has been asked to stop
+    stopping: bool,
+}
+
+/// Level of the shallow search behind quick hints.
+const QUICK_HINT_LEVEL: usize = 6;
+
 /// Parses a level string from the level-select dialog, returning the level
 /// only when it is within the valid `1..=MAX_LEVEL` range.
 fn parse_level_input(input: &str) -> Option<usize> {
@@ -206,6 +282,10 @@ pub struct App {
     pub ai_thinking: bool,
     /// Last AI search result for display
     pub last_ai_result: Option<SearchResult>,
+    /// Progress of the running AI search, with the time since it started
+    ai_progress: Option<Receiver<(Duration, SearchProgress)>>,
+    /// Iterations of the running or last AI search
+    ai_trace: SearchTrace,
     /// Hint search result receiver; also returns the borrowed `Search` instance.
     /// Present while a worker thread is still running, even after the user
     /// dismisses the dialog — the `Search` must be returned before the next
@@ -217,6 +297,8 @@ pub struct App {
     pub hint_thinking: bool,
     /// Current hint results
     pub hints: Vec<PvMove>,
+    /// Quick hint: the position it was computed for, the move and its score
+    quick_hint: Option<(Board, Square, f32)>,
     /// Status message to display
     pub status_message: Option<String>,
     /// Input buffer for level selection
@@ -235,11 +317,39 @@ pub struct App {
     pub board_edit_focus: u8,
     /// Board editor validation error message
     pub board_edit_error: Option<String>,
+    /// Clocks of a timed game; `None` for untimed play
+    pub clock: Option<GameClock>,
+    /// Selected entry in the clock dialog: 0 is untimed, then the presets
+    pub clock_selection: usize,
+    /// Theme, disc style and board orientation
+    pub appearance: TuiConfig,
+    /// Configuration file the appearance is saved to
+    config_path: Option<PathBuf>,
+    /// Selected row in the options dialog
+    pub options_selection: usize,
+    /// Current save/load dialog action
+    pub file_action: FileAction,
+    /// Save/load dialog path input buffer
+    pub file_input: String,
+    /// Save/load dialog error message
+    pub file_error: Option<String>,
+    /// Whether the analysis panel is shown and analysis runs when possible
+    pub analysis_enabled: bool,
+    /// Running analysis, if any. While present, the worker owns `search`.
+    analysis: Option<Analysis>,
+    /// Latest analysis progress and the position it belongs to
+    analysis_progress: Option<(Board, SearchProgress)>,
+    /// Position whose analysis ran to completion, so it is not restarted
+    analysis_finished: Option<Board>,
 }
 
 impl App {
     /// Creates a new App instance.
-    pub fn new(config: &EngineConfig) -> Result<Self, String> {
+    pub fn new(
+        config: &EngineConfig,
+        appearance: TuiConfig,
+        config_path: Option<PathBuf>,
+    ) -> Result<Self, String> {
         let search = search::Search::new(&config.search_options());
 
         Ok(Self {
@@ -254,9 +364,12 @@ impl App {
             ai_receiver: None,
             ai_thinking: false,
             last_ai_result: None,
+            ai_progress: None,
+            ai_trace: SearchTrace::new(),
             hint_receiver: None,
             hint_thinking: false,
             hints: Vec::new(),
+            quick_hint: None,
             status_message: None,
             level_input: String::new(),
             mode_selection: 0,
@@ -266,6 +379,18 @@ impl App {
             board_edit_side: Disc::Black,
             board_edit_focus: 0,
             board_edit_error: None,
+            clock: None,
+            clock_selection: 0,
+            appearance,
+            config_path,
+            options_selection: 0,
+            file_action: FileAction::Save,
+            file_input: String::new(),
+            file_error: None,
+            analysis_enabled: false,
+            analysis: None,
+            analysis_progress: None,
+            analysis_finished: None,
         })
     }
 
@@ -279,9 +404,16 @@ impl App {
             self.check_ai_result();
             // Check for hint results
             self.check_hint_result();
+            // Charge the clocks and detect timeouts
+            self.update_clock();
+            // Start, stop or collect continuous analysis
+            self.update_analysis();
 
-            // Trigger AI move if it's AI's turn
+            // Trigger AI move if it's AI's turn, unless the user has stepped
+            // back into the game to review it
             if !self.ai_thinking
+                && !self.game.can_forward()
+                && !self.is_time_over()
                 && !self.game.board().is_game_over()
                 && self.mode.is_ai_turn(self.game.side_to_move())
                 && self.ui_mode == UiMode::Normal
@@ -293,13 +425,16 @@ impl App {
             terminal.draw(|frame| render::render(frame, &self))?;
 
             // Handle events with timeout for responsive updates
-            let timeout = if self.ai_thinking || self.hint_thinking {
+            let timeout = if self.ai_thinking || self.hint_thinking || self.analysis.is_some() {
                 Duration::from_millis(50)
             } else {
                 Duration::from_millis(100)
             };
 
-            let text_input = matches!(self.ui_mode, UiMode::BoardEdit | UiMode::LevelSelect);
+            let text_input = matches!(
+                self.ui_mode,
+                UiMode::BoardEdit | UiMode::LevelSelect | UiMode::FileDialog
+            );
             if let Some(event) = event::poll_event(timeout, text_input)? {
                 self.handle_event(event);
             }
@@ -309,6 +444,8 @@ impl App {
             }
         }
 
+        self.stop_analysis();
+
         // Disable mouse capture on exit
         crossterm::execute!(std::io::stdout(), crossterm::event::DisableMouseCapture)?;
 
@@ -323,8 +460,11 @@ impl App {
             UiMode::Hints => self.handle_hints_event(event),
             UiMode::LevelSelect => self.handle_level_select_event(event),
             UiMode::ModeSelect => self.handle_mode_select_event(event),
+            UiMode::ClockSelect => self.handle_clock_select_event(event),
+            UiMode::Options => self.handle_options_event(event),
             UiMode::ConfirmQuit => self.handle_confirm_quit_event(event),
             UiMode::BoardEdit => self.handle_board_edit_event(event),
+            UiMode::FileDialog => self.handle_file_dialog_event(event),
         }
     }
 
@@ -337,23 +477,19 @@ impl App {
             Event::Quit => {
                 self.ui_mode = UiMode::ConfirmQuit;
             }
-            Event::CursorUp if self.cursor.0 > 0 => {
-                self.cursor.0 -= 1;
-            }
-            Event::CursorDown if self.cursor.0 < 7 => {
-                self.cursor.0 += 1;
-            }
-            Event::CursorLeft if self.cursor.1 > 0 => {
-                self.cursor.1 -= 1;
-            }
-            Event::CursorRight if self.cursor.1 < 7 => {
-                self.cursor.1 += 1;
-            }
+            Event::CursorUp => self.move_cursor(-1, 0),
+            Event::CursorDown => self.move_cursor(1, 0),
+            Event::CursorLeft => self.move_cursor(0, -1),
+            Event::CursorRight => self.move_cursor(0, 1),
             Event::Select => {
                 self.try_make_move_at_cursor();
             }
             Event::Click(row, col) if row < 8 && col < 8 => {
-                self.cursor = (row, col);
+                self.cursor = if self.appearance.flip_board {
+                    (7 - row, 7 - col)
+                } else {
+                    (row, col)
+                };
                 self.try_make_move_at_cursor();
             }
             Event::Undo => {
@@ -365,6 +501,12 @@ impl App {
             Event::Hint => {
                 self.show_hints();
             }
+            Event::QuickHint => {
+                self.show_quick_hint();
+            }
+            Event::Takeback => {
+                self.takeback();
+            }
             Event::Go => {
                 self.force_ai_move();
             }
@@ -376,8 +518,51 @@ impl App {
                 self.level_input = self.level.to_string();
                 self.ui_mode = UiMode::LevelSelect;
             }
-            Event::EditBoard if self.is_engine_available() => {
-                self.open_board_editor();
+            Event::ChangeClock => {
+                self.clock_selection = self
+                    .clock
+                    .as_ref()
+                    .and_then(|clock| {
+                        TimeControl::PRESETS
+                            .iter()
+                            .position(|&control| control == clock.control())
+                    })
+                    .map_or(0, |i| i + 1);
+                self.ui_mode = UiMode::ClockSelect;
+            }
+            Event::EditBoard => {
+                self.stop_analysis();
+                if self.is_engine_available() {
+                    self.open_board_editor();
+                }
+            }
+            Event::Options => {
+                self.options_selection = 0;
+                self.ui_mode = UiMode::Options;
+            }
+            Event::FlipBoard => {
+                self.appearance.flip_board = !self.appearance.flip_board;
+                self.save_appearance();
+            }
+            Event::FileDialog => {
+                self.file_action = FileAction::Save;
+                self.file_error = None;
+                self.ui_mode = UiMode::FileDialog;
+            }
+            Event::HistoryBack => self.navigate(Navigation::Back),
+            Event::HistoryForward => self.navigate(Navigation::Forward),
+            Event::HistoryStart => self.navigate(Navigation::Start),
+            Event::HistoryEnd => self.navigate(Navigation::End),
+            Event::ToggleAnalysis => {
+                self.analysis_enabled = !self.analysis_enabled;
+                self.status_message = Some(
+                    if self.analysis_enabled {
+                        "Analysis on"
+                    } else {
+                        "Analysis off"
+                    }
+                    .to_string(),
+                );
             }
             _ => {}
         }
@@ -492,6 +677,107 @@ impl App {
         }
     }
 
+    /// Handles events in time control selection mode.
+    fn handle_clock_select_event(&mut self, event: Event) {
+        match event {
+            Event::ForceQuit => {
+                self.should_quit = true;
+            }
+            Event::Quit => {
+                self.ui_mode = UiMode::Normal;
+            }
+            Event::CursorUp if self.clock_selection > 0 => {
+                self.clock_selection -= 1;
+            }
+            Event::CursorDown if self.clock_selection < TimeControl::PRESETS.len() => {
+                self.clock_selection += 1;
+            }
+            Event::Select => {
+                self.clock = self
+                    .clock_selection
+                    .checked_sub(1)
+                    .map(|i| GameClock::new(TimeControl::PRESETS[i]));
+                self.status_message = Some(match &self.clock {
+                    Some(clock) => format!("Clock: {}", clock.control().label()),
+                    None => "Clock off".to_string(),
+                });
+                self.ui_mode = UiMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles events in the appearance options dialog. Changes apply
+    /// immediately and are saved when the dialog closes.
+    fn handle_options_event(&mut self, event: Event) {
+        match event {
+            Event::ForceQuit => {
+                self.should_quit = true;
+            }
+            Event::Quit | Event::Options => {
+                self.ui_mode = UiMode::Normal;
+                self.save_appearance();
+            }
+            Event::CursorUp if self.options_selection > 0 => {
+                self.options_selection -= 1;
+            }
+            Event::CursorDown if self.options_selection < 2 => {
+                self.options_selection += 1;
+            }
+            Event::Select | Event::CursorLeft | Event::CursorRight => {
+                let appearance = &mut self.appearance;
+                match self.options_selection {
+                    0 => {
+                        appearance.theme = match appearance.theme {
+                            Theme::Dark => Theme::Light,
+                            Theme::Light => Theme::Dark,
+                        }
+                    }
+                    1 => {
+                        appearance.disc_style = match appearance.disc_style {
+                            DiscStyle::Unicode => DiscStyle::Ascii,
+                            DiscStyle::Ascii => DiscStyle::Unicode,
+                        }
+                    }
+                    _ => appearance.flip_board = !appearance.flip_board,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Saves the appearance settings to the configuration file.
+    fn save_appearance(&mut self) {
+        let Some(ref path) = self.config_path else {
+            return;
+        };
+        self.status_message = Some(match self.appearance.save(path) {
+            Ok(()) => format!("Saved settings to {}", path.display()),
+            Err(e) => e,
+        });
+    }
+
+    /// Returns the colors of the current theme.
+    pub fn palette(&self) -> Palette {
+        Palette::new(self.appearance.theme)
+    }
+
+    /// Returns the glyphs of the current disc style.
+    pub fn glyphs(&self) -> Glyphs {
+        Glyphs::new(self.appearance.disc_style)
+    }
+
+    /// Moves the board cursor by one square in screen directions, which are
+    /// reversed on a flipped board.
+    fn move_cursor(&mut self, d_row: isize, d_col: isize) {
+        let sign = if self.appearance.flip_board { -1 } else { 1 };
+        let row = self.cursor.0 as isize + d_row * sign;
+        let col = self.cursor.1 as isize + d_col * sign;
+        if (0..8).contains(&row) && (0..8).contains(&col) {
+            self.cursor = (row as usize, col as usize);
+        }
+    }
+
     /// Handles events in quit confirmation mode.
     fn handle_confirm_quit_event(&mut self, event: Event) {
         match event {
@@ -528,11 +814,19 @@ impl App {
 
     /// Tries to make a move at the current cursor position.
     fn try_make_move_at_cursor(&mut self) {
+        self.stop_analysis();
         if !self.is_engine_available() {
             self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
             return;
         }
 
+        // Charge the time spent up to now before accepting the move
+        self.update_clock();
+        if self.is_time_over() {
+            self.status_message = Some("Time is up!".to_string());
+            return;
+        }
+
         if self.game.board().is_game_over() {
             self.status_message = Some("Game is over!".to_string());
             return;
@@ -541,6 +835,7 @@ impl App {
         let sq = Square::from_file_rank(self.cursor.1 as u8, self.cursor.0 as u8);
         if self.game.board().is_legal_move(sq) {
             self.game.make_move(sq);
+            self.update_clock();
             self.last_ai_result = None;
             self.status_message = None;
         } else {
@@ -550,27 +845,18 @@ impl App {
 
     /// Undoes the last move.
     fn undo_move(&mut self) {
+        self.stop_analysis();
         if !self.is_engine_available() {
             self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
             return;
         }
 
-        // Undo twice if playing against AI (undo AI's move and player's move)
-        let undo_count = if matches!(self.mode, GameMode::HumanVsAi | GameMode::AiVsHuman) {
-            2
-        } else {
-            1
+        // Against the AI, undo the AI's reply together with the player's move
+        let undone = match self.mode.human_side() {
+            Some(human) => self.game.takeback(human),
+            None => self.game.undo(),
         };
 
-        let mut undone = false;
-        for _ in 0..undo_count {
-            if self.game.undo() {
-                undone = true;
-            } else {
-                break;
-            }
-        }
-
         if undone {
             self.last_ai_result = None;
             self.status_message = Some("Move undone".to_string());
@@ -579,14 +865,76 @@ impl App {
         }
     }
 
+    /// Steps through the game without discarding moves, so the line can be
+    /// replayed.
+    fn navigate(&mut self, navigation: Navigation) {
+        self.stop_analysis();
+        if !self.is_engine_available() {
+            self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
+            return;
+        }
+
+        let moved = match navigation {
+            Navigation::Back => self.game.back(),
+            Navigation::Forward => self.game.forward(),
+            Navigation::Start => {
+                let moved = self.game.back();
+                while self.game.back() {}
+                moved
+            }
+            Navigation::End => {
+                let moved = self.game.forward();
+                while self.game.forward() {}
+                moved
+            }
+        };
+
+        if moved {
+            self.last_ai_result = None;
+            self.hints.clear();
+            let (played, total) = self.game.ply();
+            self.status_message = Some(format!("Move {played}/{total}"));
+        }
+    }
+
+    /// Takes back the last move pair: the player's last move and the reply
+    /// to it. In human-vs-human games the side to move gets its move back.
+    fn takeback(&mut self) {
+        self.stop_analysis();
+        if !self.is_engine_available() {
+            self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
+            return;
+        }
+
+        let side = match self.mode {
+            GameMode::AiVsAi => {
+                self.status_message = Some("No human moves to take back".to_string());
+                return;
+            }
+            mode => mode
+                .human_side()
+                .unwrap_or_else(|| self.game.side_to_move()),
+        };
+
+        if self.game.takeback(side) {
+            self.last_ai_result = None;
+            self.hints.clear();
+            self.status_message = Some("Took back last move pair".to_string());
+        } else {
+            self.status_message = Some("Nothing to take back".to_string());
+        }
+    }
+
     /// Starts a new game.
     fn new_game(&mut self) {
+        self.stop_analysis();
         let Some(search) = self.search.as_mut() else {
             self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
             return;
         };
         self.game = GameState::new();
         search.init();
+        self.reset_clock();
         self.last_ai_result = None;
         self.hints.clear();
         self.cursor = (3, 3);
@@ -595,6 +943,7 @@ impl App {
 
     /// Shows move hints (starts background search).
     fn show_hints(&mut self) {
+        self.stop_analysis();
         if !self.is_engine_available() {
             self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
             return;
@@ -613,6 +962,43 @@ impl App {
         self.start_hint_search();
     }
 
+    /// Runs a quick shallow search and highlights the suggested move.
+    fn show_quick_hint(&mut self) {
+        self.stop_analysis();
+        let board = *self.game.board();
+        let Some(search) = self.search.as_mut() else {
+            self.status_message = Some(Self::engine_busy_message(self.ai_thinking));
+            return;
+        };
+
+        if board.is_game_over() {
+            self.status_message = Some("Game is over!".to_string());
+            return;
+        }
+
+        if !board.has_legal_moves() {
+            self.status_message = Some("No legal moves".to_string());
+            return;
+        }
+
+        // Shallow enough to answer within a frame, so no worker thread
+        let level = QUICK_HINT_LEVEL.min(self.level);
+        let options = SearchRunOptions::with_level(level::get_level(level), self.selectivity);
+        let result = search.run(&board, &options);
+        if let (Some(sq), Some(score)) = (result.best_move(), result.score()) {
+            self.quick_hint = Some((board, sq, score));
+            self.cursor = (sq.rank(), sq.file());
+            self.status_message = Some(format!("Hint: {sq} ({score:+.2})"));
+        }
+    }
+
+    /// Returns the quick hint for the current position, if one was asked for.
+    pub fn quick_hint(&self) -> Option<(Square, f32)> {
+        self.quick_hint
+            .filter(|(board, _, _)| board == self.game.board())
+            .map(|(_, sq, score)| (sq, score))
+    }
+
     /// Starts hint search in a background thread.
     fn start_hint_search(&mut self) {
         let Some(search) = self.search.take() else {
@@ -629,17 +1015,22 @@ impl App {
 
     /// Forces AI to make a move.
     fn force_ai_move(&mut self) {
+        self.stop_analysis();
         if !self.is_engine_available() {
             return;
         }
 
-        if !self.game.board().is_game_over() && self.game.board().has_legal_moves() {
+        if !self.game.board().is_game_over()
+            && self.game.board().has_legal_moves()
+            && !self.is_time_over()
+        {
             self.start_ai_search();
         }
     }
 
     /// Starts AI search in a background thread.
     fn start_ai_search(&mut self) {
+        self.stop_analysis();
         let Some(search) = self.search.take() else {
             // A previous worker thread still owns the engine (e.g. a cancelled
             // hint search that has not yet returned). Skip this tick; the main
@@ -647,7 +1038,20 @@ impl App {
             return;
         };
 
-        let options = SearchRunOptions::with_level(level::get_level(self.level), self.selectivity);
+        let options = match &self.clock {
+            Some(clock) => SearchRunOptions::with_time(
+                clock.search_mode(self.game.side_to_move()),
+                self.selectivity,
+            ),
+            None => SearchRunOptions::with_level(level::get_level(self.level), self.selectivity),
+        };
+        let (tx, progress) = mpsc::channel();
+        let started = Instant::now();
+        let options = options.callback(move |p| {
+            let _ = tx.send((started.elapsed(), p));
+        });
+        self.ai_progress = Some(progress);
+        self.ai_trace = SearchTrace::new();
         self.ai_receiver = Some(spawn_search_worker(search, *self.game.board(), options));
         self.ai_thinking = true;
     }
@@ -736,6 +1140,77 @@ impl App {
         }
     }
 
+    /// Handles events in the save/load dialog.
+    fn handle_file_dialog_event(&mut self, event: Event) {
+        match event {
+            Event::ForceQuit => {
+                self.should_quit = true;
+            }
+            Event::Quit => {
+                self.ui_mode = UiMode::Normal;
+            }
+            Event::Tab | Event::BackTab => {
+                self.file_action = self.file_action.toggle();
+                self.file_error = None;
+            }
+            Event::Select => {
+                self.apply_file_dialog();
+            }
+            Event::Char(c) if !c.is_control() => {
+                self.file_error = None;
+                if self.file_input.len() < 256 {
+                    self.file_input.push(c);
+                }
+            }
+            Event::Backspace => {
+                self.file_error = None;
+                self.file_input.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// Saves or loads the game named in the save/load dialog.
+    fn apply_file_dialog(&mut self) {
+        let input = self.file_input.trim().to_string();
+        if input.is_empty() {
+            self.file_error = Some("Enter a file name".to_string());
+            return;
+        }
+        let path = Path::new(&input);
+
+        match self.file_action {
+            FileAction::Save => match game_file::save_game(path, &self.game) {
+                Ok(()) => {
+                    self.status_message = Some(format!("Saved {}", path.display()));
+                    self.ui_mode = UiMode::Normal;
+                }
+                Err(e) => self.file_error = Some(e),
+            },
+            FileAction::Load => {
+                self.stop_analysis();
+                if !self.is_engine_available() {
+                    self.file_error = Some(Self::engine_busy_message(self.ai_thinking));
+                    return;
+                }
+                match game_file::load_game(path) {
+                    Ok(game) => {
+                        self.game = game;
+                        if let Some(ref mut search) = self.search {
+                            search.init();
+                        }
+                        self.reset_clock();
+                        self.last_ai_result = None;
+                        self.hints.clear();
+                        self.status_message = Some(format!("Loaded {}", path.display()));
+                        self.ui_mode = UiMode::Normal;
+                    }
+                    Err(e) => self.file_error = Some(e),
+                }
+            }
+        }
+    }
+
     /// Returns a mutable reference to the currently active input buffer.
     fn active_board_edit_input(&mut self) -> &mut String {
         if self.board_edit_tab == BoardEditTab::Bitboard && self.board_edit_focus == 1 {
@@ -816,6 +1291,7 @@ impl App {
                 if let Some(ref mut search) = self.search {
                     search.init();
                 }
+                self.reset_clock();
                 self.last_ai_result = None;
                 self.hints.clear();
                 self.ui_mode = UiMode::Normal;
@@ -827,18 +1303,186 @@ impl App {
         }
     }
 
-    /// Checks for AI search results.
+    /// Checks for AI search progress and results.
     fn check_ai_result(&mut self) {
+        self.collect_ai_progress();
         if let Some(ref rx) = self.ai_receiver
             && let Ok((search, result)) = rx.try_recv()
         {
             self.search = Some(search);
             self.ai_thinking = false;
             self.ai_receiver = None;
+            // Iterations reported after the last frame
+            self.collect_ai_progress();
+            self.ai_progress = None;
             let best_move = result.best_move();
             self.last_ai_result = Some(result);
-            if let Some(mv) = best_move {
+            // A move found after the engine's flag fell does not count
+            self.update_clock();
+            if let Some(mv) = best_move
+                && !self.is_time_over()
+            {
                 self.game.make_move(mv);
+                self.update_clock();
+            }
+        }
+    }
+
+    /// Adds the iterations reported by the running AI search to its trace.
+    fn collect_ai_progress(&mut self) {
+        if let Some(ref rx) = self.ai_progress {
+            for (elapsed, progress) in rx.try_iter() {
+                self.ai_trace.push(elapsed, &progress);
+            }
+        }
+    }
+
+    /// Returns the iterations of the running AI search, or of the last one
+    /// while its result is shown.
+    pub fn ai_trace(&self) -> Option<&SearchTrace> {
+        (self.ai_thinking || self.last_ai_result.is_some()).then_some(&self.ai_trace)
+    }
+
+    /// Returns true once either side has run out of time.
+    pub fn is_time_over(&self) -> bool {
+        self.clock
+            .as_ref()
+            .is_some_and(|clock| clock.flagged().is_some())
+    }
+
+    /// Restarts the clocks with full time, keeping the time control.
+    fn reset_clock(&mut self) {
+        if let Some(clock) = self.clock.as_mut() {
+            *clock = GameClock::new(clock.control());
+        }
+    }
+
+    /// Charges the clocks for the current position and reports a timeout.
+    fn update_clock(&mut self) {
+        let Some(clock) = self.clock.as_mut() else {
+            return;
+        };
+        let board = self.game.board();
+        let position = (!board.is_game_over()).then(|| (board, self.game.side_to_move()));
+        if let Some(side) = clock.update(position) {
+            let name = if side == Disc::Black {
+                "Black"
+            } else {
+                "White"
+            };
+            self.status_message = Some(format!("{name} ran out of time"));
+        }
+    }
+
+    /// Returns the latest analysis progress for the current position.
+    pub fn analysis_progress(&self) -> Option<&SearchProgress> {
+        self.analysis_progress
+            .as_ref()
+            .filter(|(board, _)| board == self.game.board())
+            .map(|(_, progress)| progress)
+    }
+
+    /// Returns true while analysis of the current position is running.
+    pub fn is_analyzing(&self) -> bool {
+        self.analysis
+            .as_ref()
+            .is_some_and(|analysis| !analysis.stopping && analysis.board == *self.game.board())
+    }
+
+    /// Returns true once the current position has been analyzed to the end.
+    pub fn is_analysis_complete(&self) -> bool {
+        self.analysis_finished == Some(*self.game.board())
+    }
+
+    /// Returns the position analysis should currently run on, if any.
+    /// Analysis only uses the engine when nothing else needs it: never on
+    /// the AI's turn, in a dialog, or in a finished position.
+    fn analysis_target(&self) -> Option<Board> {
+        let board = *self.game.board();
+        let wanted = self.analysis_enabled
+            && self.ui_mode == UiMode::Normal
+            && !self.mode.is_ai_turn(self.game.side_to_move())
+            && board.has_legal_moves();
+        wanted.then_some(board)
+    }
+
+    /// Collects analysis progress, stops analysis of a stale position and
+    /// starts analysis of the current one when the engine is free.
+    fn update_analysis(&mut self) {
+        let target = self.analysis_target();
+
+        if let Some(analysis) = self.analysis.as_mut() {
+            while let Ok(progress) = analysis.progress.try_recv() {
+                self.analysis_progress = Some((analysis.board, progress));
+            }
+            match analysis.receiver.try_recv() {
+                Ok((search, _)) => {
+                    if !analysis.stopping {
+                        self.analysis_finished = Some(analysis.board);
+                    }
+                    self.search = Some(search);
+                    self.analysis = None;
+                }
+                Err(TryRecvError::Empty) => {
+                    if target != Some(analysis.board) {
+                        // Re-sent every frame: an abort issued before the
+                        // worker starts its search is cleared by the search.
+                        analysis.pool.abort_search();
+                        analysis.stopping = true;
+                    }
+                    return;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.analysis = None;
+                    return;
+                }
+            }
+        }
+
+        if let Some(board) = target
+            && self.analysis_finished != Some(board)
+        {
+            self.start_analysis(board);
+        }
+    }
+
+    /// Starts an open-ended search of `board` in a background thread.
+    fn start_analysis(&mut self, board: Board) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+
+        let (tx, progress) = mpsc::channel();
+        let options = SearchRunOptions::with_time(TimeControlMode::Infinite, self.selectivity)
+            .callback(move |p| {
+                let _ = tx.send(p);
+            });
+        let pool = search.thread_pool();
+        self.analysis = Some(Analysis {
+            board,
+            receiver: spawn_search_worker(search, board, options),
+            progress,
+            pool,
+            stopping: false,
+        });
+        self.analysis_finished = None;
+    }
+
+    /// Stops any running analysis and waits for the engine to come back, so
+    /// that the caller can use it.
+    fn stop_analysis(&mut self) {
+        let Some(analysis) = self.analysis.take() else {
+            return;
+        };
+        loop {
+            analysis.pool.abort_search();
+            match analysis.receiver.recv_timeout(Duration::from_millis(10)) {
+                Ok((search, _)) => {
+                    self.search = Some(search);
+                    return;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
             }
         }
     }
@@ -0,0 +1,297 @@
+//! Chess clocks for timed games.
+//!
+//! A [`GameClock`] runs the clock of the side to move and charges the time
+//! whenever the position changes, so engine moves, human moves and passes
+//! are all timed the same way.
+
+use std::time::Instant;
+
+use reversi_core::board::Board;
+use reversi_core::disc::Disc;
+use reversi_core::search::time_control::TimeControlMode;
+
+/// Time control of a timed game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeControl {
+    /// A time bank per side, increased after every move.
+    Fischer { main_ms: u64, increment_ms: u64 },
+    /// A time bank per side, then a fixed allowance for every move.
+    Byoyomi { main_ms: u64, byoyomi_ms: u64 },
+}
+
+impl TimeControl {
+    /// Time controls offered in the clock dialog.
+    pub const PRESETS: [TimeControl; 5] = [
+        TimeControl::Fischer {
+            main_ms: 60_000,
+            increment_ms: 1_000,
+        },
+        TimeControl::Fischer {
+            main_ms: 300_000,
+            increment_ms: 3_000,
+        },
+        TimeControl::Fischer {
+            main_ms: 900_000,
+            increment_ms: 10_000,
+        },
+        TimeControl::Byoyomi {
+            main_ms: 0,
+            byoyomi_ms: 10_000,
+        },
+        TimeControl::Byoyomi {
+            main_ms: 300_000,
+            byoyomi_ms: 30_000,
+        },
+    ];
+
+    /// Returns a short description such as `Fischer 5:00 +3s`.
+    pub fn label(&self) -> String {
+        match *self {
+            TimeControl::Fischer {
+                main_ms,
+                increment_ms,
+            } => format!("Fischer {} +{}s", format_time(main_ms), increment_ms / 1000),
+            TimeControl::Byoyomi {
+                main_ms: 0,
+                byoyomi_ms,
+            } => format!("Byoyomi {}s", byoyomi_ms / 1000),
+            TimeControl::Byoyomi {
+                main_ms,
+                byoyomi_ms,
+            } => format!("Byoyomi {} +{}s", format_time(main_ms), byoyomi_ms / 1000),
+        }
+    }
+
+    fn main_ms(&self) -> u64 {
+        match *self {
+            TimeControl::Fischer { main_ms, .. } | TimeControl::Byoyomi { main_ms, .. } => main_ms,
+        }
+    }
+}
+
+/// Time left on a clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeLeft {
+    /// Time left in the main bank.
+    Main(u64),
+    /// Main time is spent; time left for the current move.
+    Byoyomi(u64),
+    /// The side has run out of time.
+    Flagged,
+}
+
+/// Clocks of both sides in a timed game.
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    control: TimeControl,
+    /// Main time left for Black and White
+    main_ms: [u64; 2],
+    /// Side whose clock is running, the position it is thinking on, and
+    /// when it started
+    running: Option<(Disc, Board, Instant)>,
+    /// Side that ran out of time
+    flagged: Option<Disc>,
+}
+
+fn index(side: Disc) -> usize {
+    if side == Disc::White { 1 } else { 0 }
+}
+
+impl GameClock {
+    /// Creates stopped clocks holding the full main time.
+    pub fn new(control: TimeControl) -> Self {
+        Self {
+            control,
+            main_ms: [control.main_ms(); 2],
+            running: None,
+            flagged: None,
+        }
+    }
+
+    /// Returns the time control.
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Returns the side that ran out of time, if any.
+    pub fn flagged(&self) -> Option<Disc> {
+        self.flagged
+    }
+
+    /// Returns the side whose clock is running.
+    pub fn running_side(&self) -> Option<Disc> {
+        self.running.map(|(side, _, _)| side)
+    }
+
+    /// Brings the clocks up to date with the game: charges the previous
+    /// mover once the position changes, starts the clock of `side` on
+    /// `board`, and flags a side whose time is up. Pass `None` to stop the
+    /// clocks, e.g. when the game is over. Returns the side that just
+    /// flagged.
+    pub fn update(&mut self, position: Option<(&Board, Disc)>) -> Option<Disc> {
+        if self.flagged.is_some() {
+            return None;
+        }
+
+        if let Some((side, board, started)) = self.running {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            let moved = position.is_none_or(|(b, s)| *b != board || s != side);
+            if moved {
+                self.running = None;
+                if !self.charge(side, elapsed_ms) {
+                    self.flagged = Some(side);
+                    return self.flagged;
+                }
+            } else if self.time_left_after(side, elapsed_ms) == TimeLeft::Flagged {
+                self.running = None;
+                self.flagged = Some(side);
+                return self.flagged;
+            }
+        }
+
+        if self.running.is_none()
+            && let Some((board, side)) = position
+        {
+            self.running = Some((side, *board, Instant::now()));
+        }
+        None
+    }
+
+    /// Returns the time currently left for `side`.
+    pub fn time_left(&self, side: Disc) -> TimeLeft {
+        if self.flagged == Some(side) {
+            return TimeLeft::Flagged;
+        }
+        let elapsed_ms = match self.running {
+            Some((running, _, started)) if running == side => started.elapsed().as_millis() as u64,
+            _ => 0,
+        };
+        self.time_left_after(side, elapsed_ms)
+    }
+
+    /// Returns the engine time control for `side` to move now.
+    pub fn search_mode(&self, side: Disc) -> TimeControlMode {
+        let main_time_ms = self.main_ms[index(side)];
+        match self.control {
+            TimeControl::Fischer { increment_ms, .. } => TimeControlMode::Fischer {
+                main_time_ms,
+                increment_ms,
+            },
+            TimeControl::Byoyomi { byoyomi_ms, .. } => TimeControlMode::JapaneseByo {
+                main_time_ms,
+                time_per_move_ms: byoyomi_ms,
+            },
+        }
+    }
+
+    /// Time left for `side` after spending `elapsed_ms` on the current move.
+    fn time_left_after(&self, side: Disc, elapsed_ms: u64) -> TimeLeft {
+        let main_ms = self.main_ms[index(side)];
+        match self.control {
+            TimeControl::Fischer { .. } if elapsed_ms < main_ms => {
+                TimeLeft::Main(main_ms - elapsed_ms)
+            }
+            TimeControl::Fischer { .. } => TimeLeft::Flagged,
+            TimeControl::Byoyomi { .. } if elapsed_ms < main_ms => {
+                TimeLeft::Main(main_ms - elapsed_ms)
+            }
+            TimeControl::Byoyomi { byoyomi_ms, .. } => {
+                let overflow = elapsed_ms - main_ms;
+                if overflow < byoyomi_ms {
+                    TimeLeft::Byoyomi(byoyomi_ms - overflow)
+                } else {
+                    TimeLeft::Flagged
+                }
+            }
+        }
+    }
+
+    /// Charges a completed move to `side`. Returns `false` if it overran.
+    fn charge(&mut self, side: Disc, elapsed_ms: u64) -> bool {
+        let left = self.time_left_after(side, elapsed_ms);
+        let main_ms = &mut self.main_ms[index(side)];
+        match (left, self.control) {
+            (TimeLeft::Flagged, _) => return false,
+            (TimeLeft::Main(ms), TimeControl::Fischer { increment_ms, .. }) => {
+                *main_ms = ms + increment_ms;
+            }
+            (TimeLeft::Main(ms), _) => *main_ms = ms,
+            (TimeLeft::Byoyomi(_), _) => *main_ms = 0,
+        }
+        true
+    }
+}
+
+/// Formats milliseconds as `m:ss`, or `s.s` under ten seconds.
+pub fn format_time(ms: u64) -> String {
+    if ms < 10_000 {
+        format!("{}.{}", ms / 1000, ms % 1000 / 100)
+    } else {
+        let secs = ms / 1000;
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fischer_adds_the_increment_and_flags_overruns() {
+        let mut clock = GameClock::new(TimeControl::Fischer {
+            main_ms: 10_000,
+            increment_ms: 2_000,
+        });
+        assert!(clock.charge(Disc::Black, 4_000));
+        assert_eq!(clock.time_left(Disc::Black), TimeLeft::Main(8_000));
+        assert_eq!(clock.time_left(Disc::White), TimeLeft::Main(10_000));
+        assert!(!clock.charge(Disc::White, 10_000));
+    }
+
+    #[test]
+    fn byoyomi_starts_after_the_main_time() {
+        let mut clock = GameClock::new(TimeControl::Byoyomi {
+            main_ms: 5_000,
+            byoyomi_ms: 3_000,
+        });
+        assert_eq!(
+            clock.time_left_after(Disc::Black, 6_000),
+            TimeLeft::Byoyomi(2_000)
+        );
+        assert!(clock.charge(Disc::Black, 6_000));
+        assert_eq!(clock.time_left(Disc::Black), TimeLeft::Byoyomi(3_000));
+        assert!(clock.charge(Disc::Black, 2_900));
+        assert!(!clock.charge(Disc::Black, 3_000));
+        assert_eq!(
+            clock.search_mode(Disc::Black),
+            TimeControlMode::JapaneseByo {
+                main_time_ms: 0,
+                time_per_move_ms: 3_000
+            }
+        );
+    }
+
+    #[test]
+    fn update_runs_the_clock_of_the_side_to_move() {
+        let mut clock = GameClock::new(TimeControl::PRESETS[0]);
+        let board = Board::new();
+        assert_eq!(clock.update(Some((&board, Disc::Black))), None);
+        assert_eq!(clock.running_side(), Some(Disc::Black));
+
+        let next = board.make_move(reversi_core::square::Square::F5);
+        clock.update(Some((&next, Disc::White)));
+        assert_eq!(clock.running_side(), Some(Disc::White));
+
+        clock.update(None);
+        assert_eq!(clock.running_side(), None);
+    }
+
+    #[test]
+    fn times_are_formatted_compactly() {
+        assert_eq!(format_time(300_000), "5:00");
+        assert_eq!(format_time(61_500), "1:01");
+        assert_eq!(format_time(9_870), "9.8");
+        assert_eq!(TimeControl::PRESETS[1].label(), "Fischer 5:00 +3s");
+        assert_eq!(TimeControl::PRESETS[3].label(), "Byoyomi 10s");
+    }
+}
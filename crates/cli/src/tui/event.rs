@@ -31,14 +31,36 @@ pub enum Event {
     NewGame,
     /// Show hints
     Hint,
+    /// Highlight the move suggested by a quick search
+    QuickHint,
+    /// Take back the last move pair
+    Takeback,
     /// Force AI to move
     Go,
     /// Change game mode
     ChangeMode,
     /// Change AI level
     ChangeLevel,
+    /// Change the time control
+    ChangeClock,
+    /// Open the appearance options dialog
+    Options,
+    /// View the board from the other side
+    FlipBoard,
     /// Open board editor
     EditBoard,
+    /// Toggle the continuous analysis panel
+    ToggleAnalysis,
+    /// Open the save/load dialog
+    FileDialog,
+    /// Step back one move in the game
+    HistoryBack,
+    /// Step forward one move in the game
+    HistoryForward,
+    /// Jump to the start of the game
+    HistoryStart,
+    /// Jump to the end of the game
+    HistoryEnd,
     /// Tab key (for switching tabs/fields)
     Tab,
     /// Shift+Tab (for switching tabs/fields backward)
@@ -102,6 +124,14 @@ pub fn poll_event(timeout: Duration, text_input: bool) -> std::io::Result<Option
             {
                 return Ok(Some(Event::ForceQuit));
             }
+            // Shift+Left/Right step through the game; plain arrows move the cursor
+            if key.modifiers.contains(KeyModifiers::SHIFT) && !text_input {
+                match key.code {
+                    KeyCode::Left => return Ok(Some(Event::HistoryBack)),
+                    KeyCode::Right => return Ok(Some(Event::HistoryForward)),
+                    _ => {}
+                }
+            }
             if text_input {
                 Ok(Some(map_key_event_text_input(key.code)))
             } else {
@@ -144,9 +174,20 @@ fn map_key_event(code: KeyCode) -> Event {
         KeyCode::Char('u') => Event::Undo,
         KeyCode::Char('n') => Event::NewGame,
         KeyCode::Char('i') => Event::Hint,
+        KeyCode::Char('t') => Event::QuickHint,
+        KeyCode::Char('b') => Event::Takeback,
         KeyCode::Char('g') => Event::Go,
         KeyCode::Char('m') => Event::ChangeMode,
         KeyCode::Char('v') => Event::ChangeLevel,
+        KeyCode::Char('c') => Event::ChangeClock,
+        KeyCode::Char('o') => Event::Options,
+        KeyCode::Char('r') => Event::FlipBoard,
+        KeyCode::Char('x') => Event::ToggleAnalysis,
+        KeyCode::Char('f') => Event::FileDialog,
+
+        // Move navigation
+        KeyCode::Home => Event::HistoryStart,
+        KeyCode::End => Event::HistoryEnd,
 
         // Tab key
         KeyCode::Tab => Event::Tab,
@@ -0,0 +1,78 @@
+//! Saving and loading games from the TUI.
+//!
+//! Files ending in `.ggf` hold a GGF record; anything else is a plain move
+//! transcript. Loading accepts either form and reads the first game.
+
+use std::fs;
+use std::path::Path;
+
+use reversi_core::board::Board;
+use reversi_core::disc::Disc;
+use reversi_core::formats::{GameRecord, ggf, transcript};
+
+use crate::game::GameState;
+
+/// Returns whether `path` names a GGF file.
+fn is_ggf(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ggf"))
+}
+
+/// Writes the whole game, including moves stepped back over, to `path`.
+pub fn save_game(path: &Path, game: &GameState) -> Result<(), String> {
+    let record = game.to_record();
+    let text = if is_ggf(path) {
+        ggf::format(&record)?
+    } else if record.board == Board::new() && record.side_to_move == Disc::Black {
+        transcript::format(&record.moves)
+    } else {
+        return Err("Games from an edited position can only be saved as .ggf".to_string());
+    };
+    fs::write(path, text + "\n").map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Reads the first game in `path`.
+pub fn load_game(path: &Path) -> Result<GameState, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let entry = match text.find("(;") {
+        Some(start) => {
+            let len = text[start..].find(";)").ok_or("Unterminated GGF record")?;
+            &text[start..start + len + 2]
+        }
+        None => text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .ok_or("File holds no game")?,
+    };
+    GameState::from_record(&GameRecord::parse(entry)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reversi_core::square::Square;
+
+    #[test]
+    fn saved_games_load_back() {
+        let moves = Square::parse_sequence("f5d6c3d3").unwrap();
+        let mut game = GameState::from_moves(&moves).unwrap();
+        game.back();
+
+        for ext in ["txt", "ggf"] {
+            let path = std::env::temp_dir().join(format!("tui-game-{}.{ext}", std::process::id()));
+            save_game(&path, &game).unwrap();
+            let loaded = load_game(&path);
+            let _ = fs::remove_file(&path);
+            assert_eq!(loaded.unwrap().move_history(), moves, "{ext}");
+        }
+    }
+
+    #[test]
+    fn edited_positions_need_ggf() {
+        let board = Board::new().make_move(Square::F5);
+        let game = GameState::from_board(board, Disc::White);
+        let path = std::env::temp_dir().join(format!("tui-edited-{}.txt", std::process::id()));
+        assert!(save_game(&path, &game).is_err());
+    }
+}
@@ -3,14 +3,16 @@
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 use reversi_core::disc::Disc;
 
-use super::app::{App, BoardEditTab, GameMode, UiMode};
-use super::widgets::BoardWidget;
+use super::app::{App, BoardEditTab, FileAction, GameMode, UiMode};
+use super::clock::TimeControl;
+use super::widgets::{BoardWidget, ClockWidget, EvalBar, SearchGraph};
+use crate::config::{DiscStyle, Theme};
 
 /// Main render function.
 pub fn render(frame: &mut Frame, app: &App) {
@@ -24,40 +26,44 @@ pub fn render(frame: &mut Frame, app: &App) {
     ])
     .split(area);
 
-    render_title(frame, main_layout[0]);
+    render_title(frame, main_layout[0], app);
     render_content(frame, main_layout[1], app);
     render_help_bar(frame, main_layout[2], app);
 
     // Render overlays based on UI mode
     match app.ui_mode {
-        UiMode::HintsLoading => render_hints_loading_popup(frame),
+        UiMode::HintsLoading => render_hints_loading_popup(frame, app),
         UiMode::Hints => render_hints_popup(frame, app),
         UiMode::LevelSelect => render_level_dialog(frame, app),
         UiMode::ModeSelect => render_mode_dialog(frame, app),
-        UiMode::ConfirmQuit => render_quit_dialog(frame),
+        UiMode::ClockSelect => render_clock_dialog(frame, app),
+        UiMode::Options => render_options_dialog(frame, app),
+        UiMode::ConfirmQuit => render_quit_dialog(frame, app),
         UiMode::BoardEdit => render_board_edit_dialog(frame, app),
+        UiMode::FileDialog => render_file_dialog(frame, app),
         UiMode::Normal => {}
     }
 }
 
 /// Renders the title bar.
-fn render_title(frame: &mut Frame, area: Rect) {
+fn render_title(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = app.palette();
     let title = Paragraph::new(Line::from(vec![
         Span::styled(
             " Neural Reversi ",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(palette.accent)
                 .add_modifier(Modifier::BOLD),
         ),
         Span::styled(
             concat!("v", env!("CARGO_PKG_VERSION")),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(palette.muted),
         ),
     ]))
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
+            .border_style(Style::default().fg(palette.muted)),
     );
     frame.render_widget(title, area);
 }
@@ -65,42 +71,67 @@ fn render_title(frame: &mut Frame, area: Rect) {
 /// Renders the main content area (board + info panel).
 fn render_content(frame: &mut Frame, area: Rect, app: &App) {
     let content_layout = Layout::horizontal([
-        Constraint::Length(42), // Board area
-        Constraint::Min(20),    // Info panel
+        Constraint::Length(42),                                        // Board area
+        Constraint::Min(20),                                           // Info panel
+        Constraint::Length(if app.analysis_enabled { 34 } else { 0 }), // Analysis panel
     ])
     .split(area);
 
     render_board(frame, content_layout[0], app);
     render_info_panel(frame, content_layout[1], app);
+    if app.analysis_enabled {
+        render_analysis_panel(frame, content_layout[2], app);
+    }
 }
 
 /// Renders the game board.
 fn render_board(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = app.palette();
     let board_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(palette.muted))
         .title(" Board ");
 
     let inner_area = board_block.inner(area);
     frame.render_widget(board_block, area);
 
     let board_widget = BoardWidget::new(app.game.board(), app.game.side_to_move())
+        .style(palette, app.glyphs())
+        .flipped(app.appearance.flip_board)
         .cursor(app.cursor.0, app.cursor.1)
-        .last_move(app.game.last_move());
+        .last_move(app.game.last_move())
+        .hint(app.quick_hint().map(|(sq, _)| sq));
 
     frame.render_widget(board_widget, inner_area);
 }
 
 /// Renders the information panel.
 fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = app.palette();
     let info_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::DarkGray))
+        .border_style(Style::default().fg(palette.muted))
         .title(" Info ");
 
-    let inner_area = info_block.inner(area);
+    let mut inner_area = info_block.inner(area);
     frame.render_widget(info_block, area);
 
+    // Clocks of a timed game
+    if let Some(ref clock) = app.clock {
+        let layout =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).split(inner_area);
+        frame.render_widget(ClockWidget::new(clock, palette, app.glyphs()), layout[0]);
+        inner_area = layout[1];
+    }
+
+    // Speed and depth of the running or last AI search
+    if let Some(trace) = app.ai_trace() {
+        let layout =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(5)]).split(inner_area);
+        frame.render_widget(SearchGraph::new(trace, palette), layout[1]);
+        inner_area = layout[0];
+    }
+
     let mut lines = Vec::new();
 
     // Game status
@@ -110,9 +141,16 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
     lines.push(Line::from(""));
 
     // Turn indicator
+    let glyphs = app.glyphs();
     let turn_text = match side_to_move {
-        Disc::Black => Span::styled("Black's turn (●)", Style::default().fg(Color::Green)),
-        Disc::White => Span::styled("White's turn (○)", Style::default().fg(Color::Yellow)),
+        Disc::Black => Span::styled(
+            format!("Black's turn ({})", glyphs.black),
+            Style::default().fg(palette.black),
+        ),
+        Disc::White => Span::styled(
+            format!("White's turn ({})", glyphs.white),
+            Style::default().fg(palette.white),
+        ),
         _ => Span::raw(""),
     };
     lines.push(Line::from(turn_text));
@@ -123,13 +161,13 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
         Span::raw("Black: "),
         Span::styled(
             format!("{:2}", black_count),
-            Style::default().fg(Color::Green),
+            Style::default().fg(palette.black),
         ),
         Span::raw("  "),
         Span::raw("White: "),
         Span::styled(
             format!("{:2}", white_count),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(palette.white),
         ),
     ]));
     lines.push(Line::from(""));
@@ -137,23 +175,54 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
     // Game info
     lines.push(Line::from(vec![
         Span::raw("Level: "),
-        Span::styled(format!("{}", app.level), Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!("{}", app.level),
+            Style::default().fg(palette.accent),
+        ),
     ]));
     lines.push(Line::from(vec![
         Span::raw("Mode:  "),
-        Span::styled(app.mode.as_str(), Style::default().fg(Color::Cyan)),
+        Span::styled(app.mode.as_str(), Style::default().fg(palette.accent)),
     ]));
 
     // Last move
     if let Some(last_sq) = app.game.last_move() {
         lines.push(Line::from(vec![
             Span::raw("Last:  "),
-            Span::styled(format!("{}", last_sq), Style::default().fg(Color::Magenta)),
+            Span::styled(
+                format!("{}", last_sq),
+                Style::default().fg(palette.highlight),
+            ),
         ]));
     } else {
         lines.push(Line::from(vec![
             Span::raw("Last:  "),
-            Span::styled("--", Style::default().fg(Color::DarkGray)),
+            Span::styled("--", Style::default().fg(palette.muted)),
+        ]));
+    }
+
+    // Quick hint
+    if let Some((sq, score)) = app.quick_hint() {
+        lines.push(Line::from(vec![
+            Span::raw("Hint:  "),
+            Span::styled(format!("{sq} "), Style::default().fg(palette.accent)),
+            Span::styled(
+                format!("({score:+.2})"),
+                Style::default().fg(palette.score(score)),
+            ),
+        ]));
+    }
+
+    // Position within a game being reviewed
+    if app.game.can_forward() {
+        let (played, total) = app.game.ply();
+        lines.push(Line::from(vec![
+            Span::raw("Move:  "),
+            Span::styled(
+                format!("{played}/{total}"),
+                Style::default().fg(palette.highlight),
+            ),
+            Span::styled(" (reviewing)", Style::default().fg(palette.muted)),
         ]));
     }
 
@@ -165,7 +234,7 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
     if !history.is_empty() {
         lines.push(Line::from(Span::styled(
             "History:",
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(palette.accent),
         )));
 
         // Format moves with colors: green for Black, yellow for White
@@ -185,9 +254,9 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
             }
 
             let color = if is_black {
-                Color::Green
+                palette.black
             } else {
-                Color::Yellow
+                palette.white
             };
             current_spans.push(Span::styled(move_str, Style::default().fg(color)));
             current_len += move_len;
@@ -207,13 +276,13 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
         lines.push(Line::from(Span::styled(
             "AI Thinking...",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(palette.busy)
                 .add_modifier(Modifier::SLOW_BLINK),
         )));
     } else if let Some(ref result) = app.last_ai_result {
         lines.push(Line::from(Span::styled(
             "Last AI Search:",
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(palette.accent),
         )));
         lines.push(Line::from(vec![
             Span::raw("  Depth: "),
@@ -223,7 +292,7 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
                 } else {
                     format!("{}@{}%", result.depth(), result.get_probability())
                 },
-                Style::default().fg(Color::White),
+                Style::default().fg(palette.text),
             ),
         ]));
         let score = result.score().unwrap_or(0.0);
@@ -231,36 +300,51 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
             Span::raw("  Eval:  "),
             Span::styled(
                 format!("{score:+.2}"),
-                Style::default().fg(if score >= 0.0 {
-                    Color::Green
-                } else {
-                    Color::Red
-                }),
+                Style::default().fg(palette.score(score)),
             ),
         ]));
         lines.push(Line::from(vec![
             Span::raw("  Nodes: "),
             Span::styled(
                 format!("{}", result.n_nodes()),
-                Style::default().fg(Color::White),
+                Style::default().fg(palette.text),
             ),
         ]));
     }
 
+    // Timeout
+    if let Some(flagged) = app.clock.as_ref().and_then(|clock| clock.flagged()) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "*** Time Out ***",
+            Style::default()
+                .fg(palette.bad)
+                .add_modifier(Modifier::BOLD),
+        )));
+        let winner = if flagged == Disc::Black {
+            Span::styled("White wins on time!", Style::default().fg(palette.white))
+        } else {
+            Span::styled("Black wins on time!", Style::default().fg(palette.black))
+        };
+        lines.push(Line::from(winner));
+    }
+
     // Game over status
-    if app.game.board().is_game_over() {
+    if app.game.board().is_game_over() && !app.is_time_over() {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             "*** Game Over ***",
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(palette.bad)
+                .add_modifier(Modifier::BOLD),
         )));
 
         let winner = if black_count > white_count {
-            Span::styled("Black wins!", Style::default().fg(Color::Green))
+            Span::styled("Black wins!", Style::default().fg(palette.black))
         } else if white_count > black_count {
-            Span::styled("White wins!", Style::default().fg(Color::Yellow))
+            Span::styled("White wins!", Style::default().fg(palette.white))
         } else {
-            Span::styled("Draw!", Style::default().fg(Color::Cyan))
+            Span::styled("Draw!", Style::default().fg(palette.accent))
         };
         lines.push(Line::from(winner));
     }
@@ -270,7 +354,7 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             msg.as_str(),
-            Style::default().fg(Color::Gray),
+            Style::default().fg(palette.status),
         )));
     }
 
@@ -278,8 +362,122 @@ fn render_info_panel(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(info, inner_area);
 }
 
+/// Renders the continuous analysis panel.
+fn render_analysis_panel(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = app.palette();
+    let analysis_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(palette.muted))
+        .title(" Analysis ");
+
+    let inner_area = analysis_block.inner(area);
+    frame.render_widget(analysis_block, area);
+
+    let layout = Layout::vertical([
+        Constraint::Length(1), // Spacer
+        Constraint::Length(1), // Eval bar
+        Constraint::Min(0),    // Details
+    ])
+    .split(inner_area);
+
+    let Some(progress) = app.analysis_progress() else {
+        let status = if app.is_analyzing() {
+            Span::styled(
+                "Analyzing...",
+                Style::default()
+                    .fg(palette.busy)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            )
+        } else if app.game.board().is_game_over() {
+            Span::styled("Game over", Style::default().fg(palette.muted))
+        } else {
+            Span::styled("Waiting for the engine", Style::default().fg(palette.muted))
+        };
+        frame.render_widget(Paragraph::new(Line::from(status)), layout[2]);
+        return;
+    };
+
+    let side_to_move = app.game.side_to_move();
+    let black_score = if side_to_move == Disc::White {
+        -progress.score
+    } else {
+        progress.score
+    };
+    frame.render_widget(
+        EvalBar::new(black_score).colors(palette.black, palette.white),
+        layout[1],
+    );
+
+    let depth = if progress.probability == 100 {
+        format!("{}", progress.depth)
+    } else {
+        format!("{}@{}%", progress.depth, progress.probability)
+    };
+    let pv_str: String = progress
+        .pv_line
+        .iter()
+        .map(|sq| format!("{sq}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                format!("{} Black", app.glyphs().black),
+                Style::default().fg(palette.black),
+            ),
+            Span::raw(" ".repeat((inner_area.width as usize).saturating_sub(14))),
+            Span::styled(
+                format!("White {}", app.glyphs().white),
+                Style::default().fg(palette.white),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Depth: "),
+            Span::styled(depth, Style::default().fg(palette.text)),
+        ]),
+        Line::from(vec![
+            Span::raw("Eval:  "),
+            Span::styled(
+                format!("{:+.2}", progress.score),
+                Style::default().fg(palette.score(progress.score)),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Best:  "),
+            Span::styled(
+                format!("{}", progress.best_move),
+                Style::default().fg(palette.accent),
+            ),
+        ]),
+        Line::from(vec![
+            Span::raw("Nodes: "),
+            Span::styled(
+                format!("{}", progress.nodes),
+                Style::default().fg(palette.text),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("PV:", Style::default().fg(palette.accent))),
+        Line::from(pv_str),
+    ];
+
+    if app.is_analysis_complete() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Analysis complete",
+            Style::default().fg(palette.muted),
+        )));
+    }
+
+    let details = Paragraph::new(lines).wrap(Wrap { trim: false });
+    frame.render_widget(details, layout[2]);
+}
+
 /// Renders the help bar at the bottom.
 fn render_help_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let palette = app.palette();
     let help_items = if app.ai_thinking {
         vec![("", "AI is thinking...")]
     } else {
@@ -288,10 +486,18 @@ fn render_help_bar(frame: &mut Frame, area: Rect, app: &App) {
             ("U", "Undo"),
             ("N", "New"),
             ("I", "Hint"),
+            ("T", "Quick Hint"),
+            ("B", "Takeback"),
             ("G", "Go"),
             ("M", "Mode"),
             ("V", "Level"),
+            ("C", "Clock"),
+            ("O", "Options"),
+            ("R", "Flip"),
             ("E", "Edit"),
+            ("F", "File"),
+            ("S-←→", "Step"),
+            ("X", "Analyze"),
             ("Q", "Quit"),
         ]
     };
@@ -302,7 +508,7 @@ fn render_help_bar(frame: &mut Frame, area: Rect, app: &App) {
             vec![
                 Span::styled(
                     format!(" [{key}] "),
-                    Style::default().fg(Color::Black).bg(Color::Cyan),
+                    Style::default().fg(palette.on_accent).bg(palette.accent),
                 ),
                 Span::raw(format!("{desc} ")),
             ]
@@ -312,13 +518,14 @@ fn render_help_bar(frame: &mut Frame, area: Rect, app: &App) {
     let help = Paragraph::new(Line::from(spans)).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::DarkGray)),
+            .border_style(Style::default().fg(palette.muted)),
     );
     frame.render_widget(help, area);
 }
 
 /// Renders the hints loading popup.
-fn render_hints_loading_popup(frame: &mut Frame) {
+fn render_hints_loading_popup(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
     let area = centered_rect(40, 20, frame.area());
     frame.render_widget(Clear, area);
 
@@ -327,13 +534,13 @@ fn render_hints_loading_popup(frame: &mut Frame) {
         Line::from(Span::styled(
             "Computing Hints...",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(palette.busy)
                 .add_modifier(Modifier::SLOW_BLINK),
         )),
         Line::from(""),
         Line::from(Span::styled(
             "Press Esc to cancel",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(palette.muted),
         )),
     ];
 
@@ -342,7 +549,7 @@ fn render_hints_loading_popup(frame: &mut Frame) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(palette.accent))
                 .title(" Hints "),
         );
     frame.render_widget(popup, area);
@@ -350,6 +557,7 @@ fn render_hints_loading_popup(frame: &mut Frame) {
 
 /// Renders the hints popup.
 fn render_hints_popup(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
     let area = centered_rect(60, 50, frame.area());
     frame.render_widget(Clear, area);
 
@@ -358,7 +566,7 @@ fn render_hints_popup(frame: &mut Frame, app: &App) {
         Line::from(Span::styled(
             "Move Hints",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(palette.accent)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
@@ -383,15 +591,11 @@ fn render_hints_popup(frame: &mut Frame, app: &App) {
             Span::raw(format!("  {:2}  ", i + 1)),
             Span::styled(
                 format!("  {}   ", hint.sq),
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(palette.accent),
             ),
             Span::styled(
                 format!("{:+6.2} ", hint.score),
-                Style::default().fg(if hint.score >= 0.0 {
-                    Color::Green
-                } else {
-                    Color::Red
-                }),
+                Style::default().fg(palette.score(hint.score)),
             ),
             Span::raw(pv_str),
         ]));
@@ -400,13 +604,13 @@ fn render_hints_popup(frame: &mut Frame, app: &App) {
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Press Enter or Esc to close",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(palette.muted),
     )));
 
     let popup = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(palette.accent))
             .title(" Hints "),
     );
     frame.render_widget(popup, area);
@@ -414,6 +618,7 @@ fn render_hints_popup(frame: &mut Frame, app: &App) {
 
 /// Renders the level selection dialog.
 fn render_level_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
     let area = centered_rect(40, 20, frame.area());
     frame.render_widget(Clear, area);
 
@@ -422,13 +627,16 @@ fn render_level_dialog(frame: &mut Frame, app: &App) {
         Line::from(Span::styled(
             "Set AI Level",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(palette.accent)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
             Span::raw("Current: "),
-            Span::styled(format!("{}", app.level), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("{}", app.level),
+                Style::default().fg(palette.highlight),
+            ),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -440,21 +648,21 @@ fn render_level_dialog(frame: &mut Frame, app: &App) {
                     format!("{}_", app.level_input)
                 },
                 Style::default()
-                    .fg(Color::White)
+                    .fg(palette.text)
                     .add_modifier(Modifier::RAPID_BLINK),
             ),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "Enter: Confirm  Esc: Cancel",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(palette.muted),
         )),
     ];
 
     let dialog = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(palette.accent))
             .title(" Level "),
     );
     frame.render_widget(dialog, area);
@@ -462,6 +670,7 @@ fn render_level_dialog(frame: &mut Frame, app: &App) {
 
 /// Renders the mode selection dialog.
 fn render_mode_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
     let area = centered_rect(45, 30, frame.area());
     frame.render_widget(Clear, area);
 
@@ -470,7 +679,7 @@ fn render_mode_dialog(frame: &mut Frame, app: &App) {
         Line::from(Span::styled(
             "Select Game Mode",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(palette.accent)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
@@ -485,36 +694,237 @@ fn render_mode_dialog(frame: &mut Frame, app: &App) {
 
         let style = if is_selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(palette.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(palette.text)
         };
 
         lines.push(Line::from(vec![
             Span::styled(format!("{prefix}{}. ", i + 1), style),
             Span::styled(mode.as_str(), style),
-            Span::styled(suffix, Style::default().fg(Color::DarkGray)),
+            Span::styled(suffix, Style::default().fg(palette.muted)),
         ]));
     }
 
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "↑↓: Select  Enter: Confirm  Esc: Cancel",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(palette.muted),
     )));
 
     let dialog = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(palette.accent))
             .title(" Mode "),
     );
     frame.render_widget(dialog, area);
 }
 
+/// Renders the save/load dialog.
+fn render_file_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
+    let area = centered_rect(60, 30, frame.area());
+    frame.render_widget(Clear, area);
+
+    let tab_spans: Vec<Span> = [FileAction::Save, FileAction::Load]
+        .iter()
+        .flat_map(|action| {
+            let style = if *action == app.file_action {
+                Style::default()
+                    .fg(palette.on_accent)
+                    .bg(palette.accent)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(palette.text)
+            };
+            vec![
+                Span::styled(format!(" {} ", action.as_str()), style),
+                Span::raw(" "),
+            ]
+        })
+        .collect();
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(tab_spans),
+        Line::from(""),
+        Line::from(Span::styled(
+            "File (.ggf for GGF, otherwise transcript):",
+            Style::default().fg(palette.accent),
+        )),
+        Line::from(vec![
+            Span::raw("  > "),
+            Span::styled(
+                format!("{}_", app.file_input),
+                Style::default()
+                    .fg(palette.text)
+                    .add_modifier(Modifier::RAPID_BLINK),
+            ),
+        ]),
+    ];
+
+    if let Some(ref err) = app.file_error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            err.as_str(),
+            Style::default().fg(palette.bad),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Tab: Save/Load  Enter: Apply  Esc: Cancel",
+        Style::default().fg(palette.muted),
+    )));
+
+    let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(palette.accent))
+            .title(" File "),
+    );
+    frame.render_widget(dialog, area);
+}
+
+/// Renders the time control selection dialog.
+fn render_clock_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
+    let area = centered_rect(45, 35, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Select Time Control",
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let current = app.clock.as_ref().map(|clock| clock.control());
+    let entries = std::iter::once(None).chain(TimeControl::PRESETS.iter().copied().map(Some));
+    for (i, control) in entries.enumerate() {
+        let is_selected = i == app.clock_selection;
+        let is_current = control == current;
+
+        let prefix = if is_selected { "▶ " } else { "  " };
+        let suffix = if is_current { " (current)" } else { "" };
+        let label = control.map_or("Off".to_string(), |control| control.label());
+
+        let style = if is_selected {
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(palette.text)
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(format!("{prefix}{label}"), style),
+            Span::styled(suffix, Style::default().fg(palette.muted)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Clocks start over when a control is chosen",
+        Style::default().fg(palette.muted),
+    )));
+    lines.push(Line::from(Span::styled(
+        "↑↓: Select  Enter: Confirm  Esc: Cancel",
+        Style::default().fg(palette.muted),
+    )));
+
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(palette.accent))
+            .title(" Clock "),
+    );
+    frame.render_widget(dialog, area);
+}
+
+/// Renders the appearance options dialog.
+fn render_options_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
+    let area = centered_rect(45, 30, frame.area());
+    frame.render_widget(Clear, area);
+
+    let appearance = &app.appearance;
+    let entries = [
+        (
+            "Theme",
+            match appearance.theme {
+                Theme::Dark => "Dark",
+                Theme::Light => "Light",
+            },
+        ),
+        (
+            "Discs",
+            match appearance.disc_style {
+                DiscStyle::Unicode => "Unicode",
+                DiscStyle::Ascii => "ASCII",
+            },
+        ),
+        (
+            "Board",
+            if appearance.flip_board {
+                "White's side"
+            } else {
+                "Black's side"
+            },
+        ),
+    ];
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Appearance",
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    for (i, (name, value)) in entries.iter().enumerate() {
+        let is_selected = i == app.options_selection;
+        let prefix = if is_selected { "▶ " } else { "  " };
+        let style = if is_selected {
+            Style::default()
+                .fg(palette.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(palette.text)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{prefix}{name:<7}"), style),
+            Span::styled(format!("◀ {value} ▶"), style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "↑↓: Select  ←→/Enter: Change  Esc: Close",
+        Style::default().fg(palette.muted),
+    )));
+
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(palette.accent))
+            .title(" Options "),
+    );
+    frame.render_widget(dialog, area);
+}
+
 /// Renders the quit confirmation dialog.
-fn render_quit_dialog(frame: &mut Frame) {
+fn render_quit_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
     let area = centered_rect(40, 15, frame.area());
     frame.render_widget(Clear, area);
 
@@ -523,7 +933,7 @@ fn render_quit_dialog(frame: &mut Frame) {
         Line::from(Span::styled(
             "Quit Neural Reversi?",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(palette.busy)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
@@ -535,7 +945,7 @@ fn render_quit_dialog(frame: &mut Frame) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Yellow))
+                .border_style(Style::default().fg(palette.busy))
                 .title(" Confirm "),
         );
     frame.render_widget(dialog, area);
@@ -560,6 +970,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
 
 /// Renders the board editor dialog.
 fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
+    let palette = app.palette();
     let area = centered_rect(70, 55, frame.area());
     frame.render_widget(Clear, area);
 
@@ -572,11 +983,11 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
             let is_active = *tab == app.board_edit_tab;
             let style = if is_active {
                 Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Cyan)
+                    .fg(palette.on_accent)
+                    .bg(palette.accent)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(palette.text)
             };
             vec![
                 Span::styled(format!(" {} ", tab.as_str()), style),
@@ -592,14 +1003,14 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
         BoardEditTab::Moves => {
             lines.push(Line::from(Span::styled(
                 "Enter move sequence (e.g. f5d6c3d3c4):",
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(palette.accent),
             )));
             lines.push(Line::from(vec![
                 Span::raw("  > "),
                 Span::styled(
                     format!("{}_", app.board_edit_input),
                     Style::default()
-                        .fg(Color::White)
+                        .fg(palette.text)
                         .add_modifier(Modifier::RAPID_BLINK),
                 ),
             ]));
@@ -607,14 +1018,14 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
         BoardEditTab::BoardString => {
             lines.push(Line::from(Span::styled(
                 "Enter 64-char board string (X/O/-):",
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(palette.accent),
             )));
             lines.push(Line::from(vec![
                 Span::raw("  > "),
                 Span::styled(
                     format!("{}_", app.board_edit_input),
                     Style::default()
-                        .fg(Color::White)
+                        .fg(palette.text)
                         .add_modifier(Modifier::RAPID_BLINK),
                 ),
             ]));
@@ -623,34 +1034,34 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
                 Span::styled(
                     format!("{}/64", app.board_edit_input.len()),
                     Style::default().fg(if app.board_edit_input.len() == 64 {
-                        Color::Green
+                        palette.good
                     } else {
-                        Color::Yellow
+                        palette.busy
                     }),
                 ),
             ]));
             lines.push(Line::from(""));
-            render_side_selector(&mut lines, app.board_edit_side);
+            render_side_selector(&mut lines, app.board_edit_side, app);
         }
         BoardEditTab::Bitboard => {
             let player_style = if app.board_edit_focus == 0 {
                 Style::default()
-                    .fg(Color::White)
+                    .fg(palette.text)
                     .add_modifier(Modifier::RAPID_BLINK)
             } else {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(palette.muted)
             };
             let opponent_style = if app.board_edit_focus == 1 {
                 Style::default()
-                    .fg(Color::White)
+                    .fg(palette.text)
                     .add_modifier(Modifier::RAPID_BLINK)
             } else {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(palette.muted)
             };
 
             lines.push(Line::from(Span::styled(
                 "Enter bitboard hex values:",
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(palette.accent),
             )));
             lines.push(Line::from(vec![
                 Span::raw("  Player:   > "),
@@ -661,7 +1072,7 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
                 Span::styled(format!("{}_", app.board_edit_input2), opponent_style),
             ]));
             lines.push(Line::from(""));
-            render_side_selector(&mut lines, app.board_edit_side);
+            render_side_selector(&mut lines, app.board_edit_side, app);
         }
     }
 
@@ -670,7 +1081,7 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             err.as_str(),
-            Style::default().fg(Color::Red),
+            Style::default().fg(palette.bad),
         )));
     }
 
@@ -678,40 +1089,42 @@ fn render_board_edit_dialog(frame: &mut Frame, app: &App) {
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
         "Tab: Switch  ←→: Side  Enter: Apply  Esc: Cancel",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(palette.muted),
     )));
 
     let dialog = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(palette.accent))
             .title(" Board Editor "),
     );
     frame.render_widget(dialog, area);
 }
 
 /// Renders the side-to-move selector line.
-fn render_side_selector(lines: &mut Vec<Line<'_>>, side: Disc) {
+fn render_side_selector(lines: &mut Vec<Line<'_>>, side: Disc, app: &App) {
+    let palette = app.palette();
+    let glyphs = app.glyphs();
     let black_style = if side == Disc::Black {
         Style::default()
-            .fg(Color::Green)
+            .fg(palette.black)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(palette.muted)
     };
     let white_style = if side == Disc::White {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(palette.white)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(palette.muted)
     };
 
     lines.push(Line::from(vec![
         Span::raw("  Side: "),
-        Span::styled("● Black", black_style),
+        Span::styled(format!("{} Black", glyphs.black), black_style),
         Span::raw("  "),
-        Span::styled("○ White", white_style),
+        Span::styled(format!("{} White", glyphs.white), white_style),
         Span::raw("  (←→ to toggle)"),
     ]));
 }
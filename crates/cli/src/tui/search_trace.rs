@@ -0,0 +1,123 @@
+//! Per-iteration statistics of an engine search.
+//!
+//! The engine reports [`SearchProgress`] after every completed iteration. A
+//! [`SearchTrace`] records when each report arrived so that the search graph
+//! can show how speed and depth evolved while the time manager ran the
+//! search.
+
+use std::time::Duration;
+
+use reversi_core::search::SearchProgress;
+use reversi_core::types::Depth;
+
+/// Statistics of one completed iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchSample {
+    /// Time since the search started
+    pub elapsed: Duration,
+    /// Depth completed
+    pub depth: Depth,
+    /// Selectivity probability of the iteration, in percent
+    pub probability: i32,
+    /// Nodes searched since the search started
+    pub nodes: u64,
+}
+
+impl SearchSample {
+    /// Returns the average nodes per second up to this iteration.
+    pub fn nps(&self) -> u64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            (self.nodes as f64 / secs).round() as u64
+        } else {
+            0
+        }
+    }
+}
+
+/// Iterations of a single search, in the order they completed.
+#[derive(Debug, Clone, Default)]
+pub struct SearchTrace {
+    samples: Vec<SearchSample>,
+}
+
+impl SearchTrace {
+    /// Creates an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an iteration reported `elapsed` after the search started.
+    pub fn push(&mut self, elapsed: Duration, progress: &SearchProgress) {
+        self.samples.push(SearchSample {
+            elapsed,
+            depth: progress.depth,
+            probability: progress.probability,
+            nodes: progress.nodes,
+        });
+    }
+
+    /// Returns the latest iteration.
+    pub fn last(&self) -> Option<&SearchSample> {
+        self.samples.last()
+    }
+
+    /// Returns the average nodes per second after each iteration.
+    pub fn nps(&self) -> Vec<u64> {
+        self.samples.iter().map(SearchSample::nps).collect()
+    }
+
+    /// Returns the depth completed by each iteration.
+    pub fn depths(&self) -> Vec<u64> {
+        self.samples.iter().map(|s| s.depth as u64).collect()
+    }
+}
+
+/// Formats a rate compactly, e.g. `950`, `12.3K` or `4.56M`.
+pub fn format_rate(n: u64) -> String {
+    match n {
+        0..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", n as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.2}M", n as f64 / 1e6),
+        _ => format!("{:.2}G", n as f64 / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reversi_core::square::Square;
+
+    fn progress(depth: Depth, nodes: u64) -> SearchProgress {
+        SearchProgress {
+            depth,
+            target_depth: 20,
+            score: 0.0,
+            best_move: Square::F5,
+            probability: 100,
+            nodes,
+            pv_line: vec![Square::F5],
+            is_endgame: false,
+            counters: Default::default(),
+        }
+    }
+
+    #[test]
+    fn trace_records_depth_and_average_speed() {
+        let mut trace = SearchTrace::new();
+        trace.push(Duration::ZERO, &progress(1, 10));
+        trace.push(Duration::from_millis(500), &progress(2, 1_000));
+        trace.push(Duration::from_secs(2), &progress(3, 8_000));
+
+        assert_eq!(trace.depths(), vec![1, 2, 3]);
+        assert_eq!(trace.nps(), vec![0, 2_000, 4_000]);
+        assert_eq!(trace.last().map(|s| s.nodes), Some(8_000));
+    }
+
+    #[test]
+    fn rates_are_formatted_compactly() {
+        assert_eq!(format_rate(950), "950");
+        assert_eq!(format_rate(12_345), "12.3K");
+        assert_eq!(format_rate(4_560_000), "4.56M");
+    }
+}
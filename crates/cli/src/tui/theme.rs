@@ -0,0 +1,120 @@
+//! Color palettes and disc glyphs for the configurable TUI appearance.
+
+use ratatui::style::Color;
+use reversi_core::disc::Disc;
+
+use crate::config::{DiscStyle, Theme};
+
+/// Colors used throughout the TUI, by role.
+#[derive(Debug, Clone, Copy)]
+pub struct Palette {
+    /// Titles, labels and selected entries
+    pub accent: Color,
+    /// Borders and secondary text
+    pub muted: Color,
+    /// Emphasized values and input fields
+    pub text: Color,
+    /// Status line
+    pub status: Color,
+    /// Black's discs and labels
+    pub black: Color,
+    /// White's discs and labels
+    pub white: Color,
+    /// Favorable scores
+    pub good: Color,
+    /// Unfavorable scores and errors
+    pub bad: Color,
+    /// Thinking indicators and warnings
+    pub busy: Color,
+    /// Last move and byoyomi time
+    pub highlight: Color,
+    /// Text drawn on an accent background
+    pub on_accent: Color,
+    /// Background of the board cursor
+    pub cursor_bg: Color,
+    /// Background of the last move
+    pub last_move_bg: Color,
+}
+
+impl Palette {
+    /// Returns the palette of `theme`.
+    pub fn new(theme: Theme) -> Self {
+        match theme {
+            Theme::Dark => Self {
+                accent: Color::Cyan,
+                muted: Color::DarkGray,
+                text: Color::White,
+                status: Color::Gray,
+                black: Color::Green,
+                white: Color::Yellow,
+                good: Color::Green,
+                bad: Color::Red,
+                busy: Color::Yellow,
+                highlight: Color::Magenta,
+                on_accent: Color::Black,
+                cursor_bg: Color::DarkGray,
+                last_move_bg: Color::Rgb(50, 50, 80),
+            },
+            Theme::Light => Self {
+                accent: Color::Blue,
+                muted: Color::DarkGray,
+                text: Color::Black,
+                status: Color::DarkGray,
+                black: Color::Black,
+                white: Color::Magenta,
+                good: Color::Green,
+                bad: Color::Red,
+                busy: Color::Red,
+                highlight: Color::Blue,
+                on_accent: Color::White,
+                cursor_bg: Color::Gray,
+                last_move_bg: Color::Rgb(200, 200, 235),
+            },
+        }
+    }
+
+    /// Returns the color of `side`'s discs and labels.
+    pub fn side(&self, side: Disc) -> Color {
+        if side == Disc::White {
+            self.white
+        } else {
+            self.black
+        }
+    }
+
+    /// Returns the color of a score from the mover's point of view.
+    pub fn score(&self, score: f32) -> Color {
+        if score >= 0.0 { self.good } else { self.bad }
+    }
+}
+
+/// Glyphs drawn on the board.
+#[derive(Debug, Clone, Copy)]
+pub struct Glyphs {
+    pub black: &'static str,
+    pub white: &'static str,
+    /// Empty square where the side to move may play
+    pub legal: &'static str,
+    /// Move suggested by a quick hint
+    pub hint: &'static str,
+}
+
+impl Glyphs {
+    /// Returns the glyphs of `style`.
+    pub fn new(style: DiscStyle) -> Self {
+        match style {
+            DiscStyle::Unicode => Self {
+                black: "●",
+                white: "○",
+                legal: "·",
+                hint: "◆",
+            },
+            DiscStyle::Ascii => Self {
+                black: "X",
+                white: "O",
+                legal: ".",
+                hint: "*",
+            },
+        }
+    }
+}
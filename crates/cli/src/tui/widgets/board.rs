@@ -3,12 +3,14 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::Widget,
 };
 use reversi_core::{board::Board, disc::Disc, square::Square};
 
+use crate::tui::theme::{Glyphs, Palette};
+
 /// Widget for rendering the Reversi game board.
 pub struct BoardWidget<'a> {
     /// The game board to render
@@ -19,8 +21,16 @@ pub struct BoardWidget<'a> {
     cursor: (usize, usize),
     /// Last move played
     last_move: Option<Square>,
+    /// Suggested move to highlight
+    hint: Option<Square>,
     /// Whether to show legal moves
     show_legal_moves: bool,
+    /// Colors to draw with
+    palette: Palette,
+    /// Disc glyphs
+    glyphs: Glyphs,
+    /// Whether the board is shown from White's side (rotated 180 degrees)
+    flipped: bool,
 }
 
 impl<'a> BoardWidget<'a> {
@@ -31,7 +41,11 @@ impl<'a> BoardWidget<'a> {
             side_to_move,
             cursor: (0, 0),
             last_move: None,
+            hint: None,
             show_legal_moves: true,
+            palette: Palette::new(Default::default()),
+            glyphs: Glyphs::new(Default::default()),
+            flipped: false,
         }
     }
 
@@ -47,6 +61,25 @@ impl<'a> BoardWidget<'a> {
         self
     }
 
+    /// Sets the suggested move to highlight.
+    pub fn hint(mut self, sq: Option<Square>) -> Self {
+        self.hint = sq;
+        self
+    }
+
+    /// Sets the colors and disc glyphs.
+    pub fn style(mut self, palette: Palette, glyphs: Glyphs) -> Self {
+        self.palette = palette;
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Sets whether the board is shown from White's side.
+    pub fn flipped(mut self, flipped: bool) -> Self {
+        self.flipped = flipped;
+        self
+    }
+
     /// Sets whether to show legal moves.
     #[allow(dead_code)]
     pub fn show_legal_moves(mut self, show: bool) -> Self {
@@ -64,26 +97,23 @@ impl Widget for BoardWidget<'_> {
 
         let legal_moves = self.board.get_moves();
 
+        let palette = self.palette;
+        // Maps a display row or column to a board rank or file
+        let orient = |i: usize| if self.flipped { 7 - i } else { i };
+
         // Column headers
-        let header = Line::from(vec![
-            Span::raw("    "),
-            Span::styled("a", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("b", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("c", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("d", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("e", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("f", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("g", Style::default().fg(Color::Cyan)),
-            Span::raw("   "),
-            Span::styled("h", Style::default().fg(Color::Cyan)),
-        ]);
-        buf.set_line(area.x, area.y, &header, area.width);
+        let mut header = vec![Span::raw("    ")];
+        for col in 0..8 {
+            if col > 0 {
+                header.push(Span::raw("   "));
+            }
+            let file = (b'a' + orient(col) as u8) as char;
+            header.push(Span::styled(
+                file.to_string(),
+                Style::default().fg(palette.accent),
+            ));
+        }
+        buf.set_line(area.x, area.y, &Line::from(header), area.width);
 
         // Top border
         let top_border = "  ┌───┬───┬───┬───┬───┬───┬───┬───┐";
@@ -94,38 +124,45 @@ impl Widget for BoardWidget<'_> {
             let y = area.y + 2 + (row as u16) * 2;
 
             // Row number and cells
-            let row_num = format!("{} │", row + 1);
-            buf.set_string(area.x, y, &row_num, Style::default().fg(Color::Cyan));
+            let row_num = format!("{} │", orient(row) + 1);
+            buf.set_string(area.x, y, &row_num, Style::default().fg(palette.accent));
 
             for col in 0..8 {
-                let sq = Square::from_file_rank(col as u8, row as u8);
+                let sq = Square::from_file_rank(orient(col) as u8, orient(row) as u8);
                 let piece = self.board.get_disc_at(sq, self.side_to_move);
                 let is_legal = legal_moves.contains(sq);
-                let is_cursor = self.cursor == (row, col);
+                let is_cursor = self.cursor == (orient(row), orient(col));
                 let is_last_move = self.last_move == Some(sq);
+                let is_hint = self.hint == Some(sq);
 
                 // Determine cell content and style
-                let (content, mut style) = match piece {
-                    Disc::Black => (" ● ", Style::default().fg(Color::Green)),
-                    Disc::White => (" ○ ", Style::default().fg(Color::Yellow)),
+                let (glyph, mut style) = match piece {
+                    Disc::Black => (self.glyphs.black, Style::default().fg(palette.black)),
+                    Disc::White => (self.glyphs.white, Style::default().fg(palette.white)),
+                    Disc::Empty if is_hint => (
+                        self.glyphs.hint,
+                        Style::default()
+                            .fg(palette.accent)
+                            .add_modifier(Modifier::BOLD),
+                    ),
                     Disc::Empty if is_legal && self.show_legal_moves => {
-                        (" · ", Style::default().fg(Color::DarkGray))
+                        (self.glyphs.legal, Style::default().fg(palette.muted))
                     }
-                    Disc::Empty => ("   ", Style::default()),
+                    Disc::Empty => (" ", Style::default()),
                 };
 
                 // Apply cursor highlight
                 if is_cursor {
-                    style = style.bg(Color::DarkGray).add_modifier(Modifier::BOLD);
+                    style = style.bg(palette.cursor_bg).add_modifier(Modifier::BOLD);
                 }
 
                 // Apply last move highlight
                 if is_last_move {
-                    style = style.bg(Color::Rgb(50, 50, 80));
+                    style = style.bg(palette.last_move_bg);
                 }
 
                 let x = area.x + 3 + (col as u16) * 4;
-                buf.set_string(x, y, content, style);
+                buf.set_string(x, y, format!(" {glyph} "), style);
 
                 // Cell separator
                 if col < 7 {
@@ -154,7 +191,7 @@ impl Widget for BoardWidget<'_> {
             area.x,
             area.y + 18,
             &cursor_info,
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(palette.accent),
         );
     }
 }
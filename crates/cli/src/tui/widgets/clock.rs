@@ -0,0 +1,88 @@
+//! Chess clock widget showing the time left for both sides.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use reversi_core::disc::Disc;
+
+use crate::tui::clock::{GameClock, TimeLeft, format_time};
+use crate::tui::theme::{Glyphs, Palette};
+
+/// Widget for rendering the clocks of a timed game side by side.
+pub struct ClockWidget<'a> {
+    /// Clocks to render
+    clock: &'a GameClock,
+    /// Colors to draw with
+    palette: Palette,
+    /// Disc glyphs for the side labels
+    glyphs: Glyphs,
+}
+
+impl<'a> ClockWidget<'a> {
+    /// Creates a new clock widget.
+    pub fn new(clock: &'a GameClock, palette: Palette, glyphs: Glyphs) -> Self {
+        Self {
+            clock,
+            palette,
+            glyphs,
+        }
+    }
+
+    /// Renders the clock face of one side.
+    fn render_face(&self, side: Disc, area: Rect, buf: &mut Buffer) {
+        let palette = self.palette;
+        let name = match side {
+            Disc::White => format!(" White {} ", self.glyphs.white),
+            _ => format!(" Black {} ", self.glyphs.black),
+        };
+        let color = palette.side(side);
+        let running = self.clock.running_side() == Some(side);
+
+        let (text, style) = match self.clock.time_left(side) {
+            TimeLeft::Main(ms) => (format_time(ms), Style::default().fg(palette.text)),
+            TimeLeft::Byoyomi(ms) => (
+                format!("BY {}", format_time(ms)),
+                Style::default().fg(palette.highlight),
+            ),
+            TimeLeft::Flagged => (
+                "FLAG".to_string(),
+                Style::default()
+                    .fg(palette.bad)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        };
+        let style = if running {
+            style.add_modifier(Modifier::BOLD)
+        } else {
+            style
+        };
+
+        let border_style = if running {
+            Style::default().fg(color)
+        } else {
+            Style::default().fg(palette.muted)
+        };
+        let face = Paragraph::new(Line::from(Span::styled(text, style)))
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title(Span::styled(name, Style::default().fg(color))),
+            );
+        face.render(area, buf);
+    }
+}
+
+impl Widget for ClockWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let faces = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+        self.render_face(Disc::Black, faces[0], buf);
+        self.render_face(Disc::White, faces[1], buf);
+    }
+}
@@ -0,0 +1,83 @@
+//! Evaluation bar widget showing which side the engine favors.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+/// Scale of the squashing curve, in discs. An advantage of this size fills
+/// roughly 88% of the bar.
+const SCORE_SCALE: f32 = 16.0;
+
+/// Horizontal bar split between Black (left) and White (right) in
+/// proportion to an evaluation from Black's point of view.
+pub struct EvalBar {
+    /// Evaluation in discs, positive when Black is ahead
+    black_score: f32,
+    /// Colors of Black's and White's shares
+    colors: (Color, Color),
+}
+
+impl EvalBar {
+    /// Creates a bar for an evaluation from Black's point of view.
+    pub fn new(black_score: f32) -> Self {
+        Self {
+            black_score,
+            colors: (Color::Green, Color::Yellow),
+        }
+    }
+
+    /// Sets the colors of Black's and White's shares.
+    pub fn colors(mut self, black: Color, white: Color) -> Self {
+        self.colors = (black, white);
+        self
+    }
+}
+
+/// Returns how many of `width` cells belong to Black.
+///
+/// Scores are squashed with `tanh` so that small midgame advantages still
+/// move the bar while decisive ones saturate it.
+fn black_cells(black_score: f32, width: u16) -> u16 {
+    let share = 0.5 + 0.5 * (black_score / SCORE_SCALE).tanh();
+    (share * width as f32).round() as u16
+}
+
+impl Widget for EvalBar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let black = black_cells(self.black_score, area.width);
+        for x in 0..area.width {
+            let color = if x < black {
+                self.colors.0
+            } else {
+                self.colors.1
+            };
+            buf.set_string(area.x + x, area.y, " ", Style::default().bg(color));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn even_score_splits_the_bar() {
+        assert_eq!(black_cells(0.0, 20), 10);
+    }
+
+    #[test]
+    fn bar_follows_the_score_and_saturates() {
+        assert!(black_cells(4.0, 20) > 10);
+        assert!(black_cells(-4.0, 20) < 10);
+        assert!(black_cells(8.0, 20) >= black_cells(4.0, 20));
+        assert_eq!(black_cells(64.0, 20), 20);
+        assert_eq!(black_cells(-64.0, 20), 0);
+    }
+}
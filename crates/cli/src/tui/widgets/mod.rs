@@ -1,5 +1,11 @@
 //! Custom widgets for the TUI.
 
 mod board;
+mod clock;
+mod eval_bar;
+mod search_graph;
 
 pub use board::BoardWidget;
+pub use clock::ClockWidget;
+pub use eval_bar::EvalBar;
+pub use search_graph::SearchGraph;
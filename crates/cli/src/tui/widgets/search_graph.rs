@@ -0,0 +1,101 @@
+//! Sparkline graph of the speed and depth of an engine search.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Paragraph, Sparkline, Widget},
+};
+
+use crate::tui::clock::format_time;
+use crate::tui::search_trace::{SearchTrace, format_rate};
+use crate::tui::theme::Palette;
+
+/// Width of the row labels.
+const LABEL_WIDTH: u16 = 6;
+
+/// Widget plotting the average NPS and the completed depth of every
+/// iteration of a search, one column per iteration.
+pub struct SearchGraph<'a> {
+    /// Iterations to plot
+    trace: &'a SearchTrace,
+    /// Colors to draw with
+    palette: Palette,
+}
+
+impl<'a> SearchGraph<'a> {
+    /// Creates a new search graph.
+    pub fn new(trace: &'a SearchTrace, palette: Palette) -> Self {
+        Self { trace, palette }
+    }
+
+    /// Renders one labelled sparkline, keeping the latest iterations when
+    /// there are more than columns.
+    fn render_row(&self, label: &str, data: &[u64], style: Style, area: Rect, buf: &mut Buffer) {
+        let [label_area, graph_area] =
+            Layout::horizontal([Constraint::Length(LABEL_WIDTH), Constraint::Min(0)]).areas(area);
+        Paragraph::new(Span::styled(label, Style::default().fg(self.palette.muted)))
+            .render(label_area, buf);
+
+        let skip = data.len().saturating_sub(graph_area.width as usize);
+        Sparkline::default()
+            .data(data[skip..].iter().copied())
+            .style(style)
+            .render(graph_area, buf);
+    }
+}
+
+impl Widget for SearchGraph<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let palette = self.palette;
+        let [summary_area, nps_area, depth_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(2),
+        ])
+        .areas(area);
+
+        let summary = match self.trace.last() {
+            Some(last) => {
+                let depth = if last.probability == 100 {
+                    last.depth.to_string()
+                } else {
+                    format!("{}@{}%", last.depth, last.probability)
+                };
+                Line::from(vec![
+                    Span::styled("Search ", Style::default().fg(palette.accent)),
+                    Span::styled(format!("d{depth} "), Style::default().fg(palette.text)),
+                    Span::styled(
+                        format!("{} nps ", format_rate(last.nps())),
+                        Style::default().fg(palette.text),
+                    ),
+                    Span::styled(
+                        format_time(last.elapsed.as_millis() as u64),
+                        Style::default().fg(palette.muted),
+                    ),
+                ])
+            }
+            None => Line::from(Span::styled(
+                "Search: waiting for the first iteration",
+                Style::default().fg(palette.muted),
+            )),
+        };
+        Paragraph::new(summary).render(summary_area, buf);
+
+        self.render_row(
+            "NPS",
+            &self.trace.nps(),
+            Style::default().fg(palette.good),
+            nps_area,
+            buf,
+        );
+        self.render_row(
+            "Depth",
+            &self.trace.depths(),
+            Style::default().fg(palette.highlight),
+            depth_area,
+            buf,
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Symmetry augmentation module.
+//!
+//! Copies binary record files to a new directory, expanding every record into
+//! its variants under the selected symmetries of the board, all 8 by default.
+//! The board and the move square are transformed together, so that the pattern
+//! features computed from an augmented board and its move stay consistent; all
+//! other fields are kept.
+//!
+//! An output file holds the records of its input file once per symmetry, each
+//! pass in the original order, so the records of a game stay consecutive. With
+//! deduplication, a variant whose board equals an earlier variant of the same
+//! record is dropped, which removes the identical variants of symmetric
+//! positions such as the initial one. Repeated positions of different records
+//! are all kept.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use glob::glob;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use reversi_core::bitboard::Bitboard;
+use reversi_core::board::Board;
+use reversi_core::square::Square;
+
+use crate::record::{GameRecord, read_records_from_file, write_records_to_file};
+
+/// A symmetry of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Symmetry {
+    /// The board as recorded.
+    Identity,
+    /// Rotation by 90 degrees clockwise.
+    Rotate90,
+    /// Rotation by 180 degrees.
+    Rotate180,
+    /// Rotation by 270 degrees clockwise.
+    Rotate270,
+    /// Mirror across the vertical axis.
+    FlipHorizontal,
+    /// Mirror across the horizontal axis.
+    FlipVertical,
+    /// Mirror across the A1-H8 diagonal.
+    FlipDiagA1h8,
+    /// Mirror across the A8-H1 diagonal.
+    FlipDiagA8h1,
+}
+
+impl Symmetry {
+    /// The 8 symmetries of the board, starting with the identity.
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipVertical,
+        Symmetry::FlipDiagA1h8,
+        Symmetry::FlipDiagA8h1,
+    ];
+
+    /// Applies the symmetry to a bitboard.
+    fn apply(self, b: Bitboard) -> Bitboard {
+        match self {
+            Symmetry::Identity => b,
+            Symmetry::Rotate90 => b.rotate_90_clockwise(),
+            Symmetry::Rotate180 => b.rotate_180_clockwise(),
+            Symmetry::Rotate270 => b.rotate_270_clockwise(),
+            Symmetry::FlipHorizontal => b.flip_horizontal(),
+            Symmetry::FlipVertical => b.flip_vertical(),
+            Symmetry::FlipDiagA1h8 => b.flip_diag_a1h8(),
+            Symmetry::FlipDiagA8h1 => b.flip_diag_a8h1(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AugmentStats {
+    records: u64,
+    written: u64,
+    dropped_duplicate: u64,
+}
+
+/// Augments every file matching `pattern` in `input_dir` into a file of the
+/// same name in `output_dir`.
+///
+/// # Arguments
+///
+/// * `input_dir` - Directory containing input files
+/// * `output_dir` - Directory where augmented files will be written
+/// * `pattern` - Glob pattern to match input files (e.g., "*.bin")
+/// * `symmetries` - Symmetries to emit, in output order
+/// * `dedup` - Drop variants whose board equals an earlier variant of the
+///   same record
+///
+/// # Returns
+///
+/// Returns `Ok(())` on success, or an error if file operations fail.
+pub fn execute(
+    input_dir: &str,
+    output_dir: &str,
+    pattern: &str,
+    symmetries: &[Symmetry],
+    dedup: bool,
+) -> anyhow::Result<()> {
+    if symmetries.is_empty() {
+        anyhow::bail!("At least one symmetry must be selected");
+    }
+    let input_dir_path = Path::new(input_dir);
+    let output_dir_path = Path::new(output_dir);
+    if output_dir_path.exists()
+        && fs::canonicalize(input_dir_path)? == fs::canonicalize(output_dir_path)?
+    {
+        anyhow::bail!("Output directory must differ from the input directory");
+    }
+    fs::create_dir_all(output_dir_path)?;
+
+    let input_files = find_input_files(input_dir_path, pattern)?;
+    if input_files.is_empty() {
+        println!("No input files found – nothing to do.");
+        return Ok(());
+    }
+
+    println!("Input folder  : {input_dir:?}");
+    println!("Output folder : {output_dir:?}");
+    println!("Input files   : {}", input_files.len());
+    println!("Symmetries    : {symmetries:?}");
+    println!("Dedup         : {}", if dedup { "yes" } else { "no" });
+    println!("----------------------------------------");
+
+    let pb = ProgressBar::with_draw_target(
+        Some(input_files.len() as u64),
+        ProgressDrawTarget::stderr_with_hz(10),
+    );
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} files ETA:{eta_precise}",
+        )
+        .map_err(io::Error::other)?
+        .progress_chars("#>-"),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let mut stats = AugmentStats::default();
+
+    for path in &input_files {
+        let output_path = output_dir_path.join(path.file_name().unwrap_or_default());
+        augment_file(path, &output_path, symmetries, dedup, &mut stats)?;
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    println!("------------- Summary -------------");
+    println!("Records       : {}", stats.records);
+    println!("Written       : {}", stats.written);
+    println!("  duplicate   : {}", stats.dropped_duplicate);
+    println!("-----------------------------------");
+    Ok(())
+}
+
+fn find_input_files(dir: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let full_pattern = dir.join(pattern).to_string_lossy().into_owned();
+    let paths = glob(&full_pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", full_pattern, e))?;
+    let mut files = Vec::new();
+    for entry in paths {
+        match entry {
+            Ok(path) if path.is_file() => files.push(path),
+            Ok(_) => {}
+            Err(e) => eprintln!(
+                "Warning: Failed to access path matched by glob ({}): {}",
+                e.path().display(),
+                e
+            ),
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Writes the symmetric variants of the records of `path` to `output_path`.
+fn augment_file(
+    path: &Path,
+    output_path: &Path,
+    symmetries: &[Symmetry],
+    dedup: bool,
+    stats: &mut AugmentStats,
+) -> io::Result<()> {
+    let records = read_records_from_file(path)?;
+    stats.records += records.len() as u64;
+
+    let augmented = augment_records(&records, symmetries, dedup, stats);
+    stats.written += augmented.len() as u64;
+
+    let temp_path = output_path.with_extension("bin.tmp");
+    match fs::remove_file(&temp_path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    write_records_to_file(&temp_path, &augmented)?;
+    fs::rename(&temp_path, output_path)
+}
+
+/// Expands `records` into one pass per symmetry. With `dedup`, a variant is
+/// dropped if an earlier symmetry maps its record to the same board.
+fn augment_records(
+    records: &[GameRecord],
+    symmetries: &[Symmetry],
+    dedup: bool,
+    stats: &mut AugmentStats,
+) -> Vec<GameRecord> {
+    let mut augmented = Vec::with_capacity(records.len() * symmetries.len());
+    for (i, &symmetry) in symmetries.iter().enumerate() {
+        for record in records {
+            let variant = transform_record(record, symmetry);
+            if dedup
+                && symmetries[..i]
+                    .iter()
+                    .any(|&earlier| transform_board(&record.board, earlier) == variant.board)
+            {
+                stats.dropped_duplicate += 1;
+                continue;
+            }
+            augmented.push(variant);
+        }
+    }
+    augmented
+}
+
+fn transform_board(board: &Board, symmetry: Symmetry) -> Board {
+    Board::from_bitboards(
+        symmetry.apply(board.player()),
+        symmetry.apply(board.opponent()),
+    )
+}
+
+/// Applies `symmetry` to the board and move square of `record`.
+fn transform_record(record: &GameRecord, symmetry: Symmetry) -> GameRecord {
+    let board = transform_board(&record.board, symmetry);
+    let sq = if record.sq == Square::None {
+        Square::None
+    } else {
+        symmetry
+            .apply(record.sq.bitboard())
+            .lsb_square()
+            .expect("symmetries map a square to a square")
+    };
+    GameRecord {
+        board,
+        sq,
+        ..record.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{GAME_SCORE_UNAVAILABLE, MoveKind};
+    use reversi_core::disc::Disc;
+
+    fn record(board: Board, side_to_move: Disc, sq: Square) -> GameRecord {
+        GameRecord {
+            game_id: 3,
+            ply: 60 - board.get_empty_count() as u8,
+            board,
+            score: 1.5,
+            game_score: GAME_SCORE_UNAVAILABLE,
+            side_to_move,
+            move_kind: MoveKind::Search,
+            sq,
+        }
+    }
+
+    /// The records of a short game, each holding the move played from it.
+    fn game(transcript: &[Square]) -> Vec<GameRecord> {
+        let mut board = Board::new();
+        let mut side = Disc::Black;
+        let mut records = Vec::new();
+        for &sq in transcript {
+            records.push(record(board, side, sq));
+            board = board.make_move(sq);
+            side = side.opposite();
+        }
+        records
+    }
+
+    #[test]
+    fn transforms_board_and_move_together() {
+        let records = game(&[Square::F5, Square::D6, Square::C3, Square::D3]);
+        for symmetry in Symmetry::ALL {
+            let variants: Vec<GameRecord> = records
+                .iter()
+                .map(|record| transform_record(record, symmetry))
+                .collect();
+            for (record, variant) in records.iter().zip(&variants) {
+                assert_eq!(
+                    variant.sq.bitboard(),
+                    symmetry.apply(record.sq.bitboard()),
+                    "{symmetry:?}"
+                );
+                assert!(variant.board.is_legal_move(variant.sq), "{symmetry:?}");
+                // Playing the transformed move gives the transformed position
+                assert_eq!(
+                    variant.board.make_move(variant.sq),
+                    transform_board(&record.board.make_move(record.sq), symmetry),
+                    "{symmetry:?}"
+                );
+                assert_eq!(variant.score, record.score);
+                assert_eq!(variant.side_to_move, record.side_to_move);
+            }
+            // The transformed game still replays move by move
+            for pair in variants.windows(2) {
+                assert_eq!(pair[0].played_move(&pair[1]), Some(pair[0].sq));
+            }
+        }
+    }
+
+    #[test]
+    fn dedup_drops_identical_variants_of_one_record_only() {
+        // The initial position has 2 distinct variants, the one after F5 has 8
+        let records = vec![
+            record(Board::new(), Disc::Black, Square::F5),
+            record(Board::new(), Disc::Black, Square::F5),
+            record(Board::new().make_move(Square::F5), Disc::White, Square::D6),
+        ];
+        let mut stats = AugmentStats::default();
+        let augmented = augment_records(&records, &Symmetry::ALL, true, &mut stats);
+        assert_eq!(stats.dropped_duplicate, 2 * 6);
+        // Repeated positions of different records are kept
+        assert_eq!(augmented.len(), 2 * 2 + 8);
+
+        let mut stats = AugmentStats::default();
+        let augmented = augment_records(&records, &Symmetry::ALL, false, &mut stats);
+        assert_eq!(augmented.len(), 3 * 8);
+        assert_eq!(stats.dropped_duplicate, 0);
+    }
+
+    #[test]
+    fn emits_only_the_selected_symmetries() {
+        let records = game(&[Square::F5, Square::D6]);
+        let symmetries = [Symmetry::FlipVertical, Symmetry::Identity];
+        let mut stats = AugmentStats::default();
+        let augmented = augment_records(&records, &symmetries, false, &mut stats);
+        assert_eq!(augmented.len(), 4);
+        assert_eq!(
+            augmented[0].sq,
+            transform_record(&records[0], Symmetry::FlipVertical).sq
+        );
+        assert_eq!(augmented[2].board, records[0].board);
+        assert_eq!(augmented[3].sq, Square::D6);
+    }
+}
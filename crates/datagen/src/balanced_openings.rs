@@ -0,0 +1,135 @@
+//! Balanced-openings module.
+//!
+//! Enumerates every unique opening of a given number of plies, scores the
+//! resulting positions with the search algorithm, and writes the openings whose
+//! score is close to equal as move transcripts, one per line, in the format
+//! read by match-runner's `--opening-file`.
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use reversi_core::board::Board;
+use reversi_core::level::Level;
+use reversi_core::move_list::MoveList;
+use reversi_core::probcut::Selectivity;
+use reversi_core::search::options::SearchOptions;
+use reversi_core::search::{self, SearchRunOptions};
+use reversi_core::square::Square;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Writes the openings of exactly `depth` plies whose score lies within
+/// `max_score` discs of equal to `output`.
+///
+/// Openings reaching the same position up to symmetry are written once.
+/// Positions where the side to move has to pass are skipped.
+pub fn execute(
+    depth: u8,
+    max_score: f32,
+    hash_size: usize,
+    level: Level,
+    selectivity: Selectivity,
+    output: &str,
+) -> io::Result<()> {
+    if let Some(parent) = Path::new(output).parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    println!("Enumerating openings of {depth} plies...");
+    let mut openings: Vec<(Board, Vec<Square>)> = Vec::new();
+    let mut visited = HashSet::new();
+    enumerate_openings(
+        &Board::new(),
+        depth,
+        &mut Vec::with_capacity(depth as usize),
+        &mut visited,
+        &mut openings,
+    );
+    drop(visited);
+    println!("Found {} unique openings", openings.len());
+
+    let options = SearchOptions::new(hash_size);
+    let mut search = search::Search::new(&options);
+    let run_options = SearchRunOptions::with_level(level, selectivity);
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    let mut balanced = 0usize;
+
+    let pb = ProgressBar::with_draw_target(
+        Some(openings.len() as u64),
+        ProgressDrawTarget::stderr_with_hz(10),
+    );
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({per_sec}) ETA:{eta_precise}",
+        )
+        .map_err(io::Error::other)?
+        .progress_chars("#>-"),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    for (board, moves) in &openings {
+        let result = search.run(board, &run_options);
+        let score = result.score().expect("search returned no legal move");
+
+        if score.abs() <= max_score {
+            for sq in moves {
+                write!(writer, "{sq}")?;
+            }
+            writeln!(writer)?;
+            balanced += 1;
+        }
+        pb.inc(1);
+    }
+
+    writer.flush()?;
+    pb.finish_and_clear();
+
+    println!(
+        "Done. {} of {} openings within ±{} discs.",
+        balanced,
+        openings.len(),
+        max_score
+    );
+    Ok(())
+}
+
+/// Recursively collects the move sequences of `depth` plies leading to unique
+/// positions, where the side to move has a legal move.
+///
+/// Positions are canonicalized via [`Board::unique`] so that symmetric variants
+/// are treated as duplicates. Forced passes are played implicitly and do not
+/// count as plies.
+fn enumerate_openings(
+    board: &Board,
+    depth: u8,
+    path: &mut Vec<Square>,
+    visited: &mut HashSet<Board>,
+    openings: &mut Vec<(Board, Vec<Square>)>,
+) {
+    if !visited.insert(board.unique()) {
+        return;
+    }
+
+    let move_list = MoveList::new(board);
+    if move_list.count() == 0 {
+        let next = board.switch_players();
+        if depth > 0 && next.has_legal_moves() {
+            enumerate_openings(&next, depth, path, visited, openings);
+        }
+        return;
+    }
+
+    if depth == 0 {
+        openings.push((*board, path.clone()));
+        return;
+    }
+
+    for m in move_list.iter() {
+        let next = board.make_move_with_flipped(m.flipped, m.sq);
+        path.push(m.sq);
+        enumerate_openings(&next, depth - 1, path, visited, openings);
+        path.pop();
+    }
+}
@@ -0,0 +1,569 @@
+//! Compressed training data format.
+//!
+//! A binpack file stores the same records as the fixed-size `.bin` format in a
+//! fraction of the space. Consecutive records of a game form a chain: only the
+//! first board of a chain is stored in full, and each following board is
+//! reconstructed by playing the previous record's move, passing when the
+//! opponent has no legal move. Scores are quantized to
+//! 1/[`SCORE_SCALE`] disc and the game score is stored once per chain.
+//!
+//! Layout: the magic bytes and version, then a sequence of independent zstd
+//! frames with content checksums, each holding whole chains:
+//!
+//! ```text
+//! chain  := player u64 | opponent u64 | game_id u16 | ply u8 | side_to_move u8
+//!           | game_score i8 | count u16 | entry * count
+//! entry  := sq u8 | move_kind u8 | score i16
+//! ```
+//!
+//! All integers are little-endian. The game score is from the side to move of
+//! the chain's first record and flips with the side to move.
+
+use anyhow::{Context, bail};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use glob::glob;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use reversi_core::bitboard::Bitboard;
+use reversi_core::board::Board;
+use reversi_core::disc::Disc;
+use reversi_core::square::Square;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::record::{
+    GAME_SCORE_UNAVAILABLE, GameRecord, MoveKind, read_records_from_file, write_records_to_file,
+};
+
+/// Magic bytes at the start of every binpack file
+const MAGIC: &[u8; 4] = b"NRBP";
+
+/// Format version following the magic bytes
+const VERSION: u8 = 1;
+
+/// File extension of binpack files
+pub const EXTENSION: &str = "binpack";
+
+/// Scores are stored in units of 1/SCORE_SCALE disc
+pub const SCORE_SCALE: f32 = 64.0;
+
+/// Uncompressed size after which a zstd frame is closed
+const FRAME_SIZE: usize = 1 << 20;
+
+/// zstd compression level of the frames
+const COMPRESSION_LEVEL: i32 = 19;
+
+/// Maximum number of records of a chain, limited by its `count` field
+const MAX_CHAIN_LEN: usize = u16::MAX as usize;
+
+/// Converts every `.bin` file matching `pattern` in `input_dir` to a binpack
+/// file with the same stem in `output_dir`.
+pub fn pack(input_dir: &str, output_dir: &str, pattern: &str) -> anyhow::Result<()> {
+    let files = find_files(Path::new(input_dir), pattern)?;
+    fs::create_dir_all(output_dir)?;
+    println!("Packing {} files...", files.len());
+
+    let pb = progress_bar(files.len())?;
+    let sizes = files
+        .par_iter()
+        .map(|input| {
+            let output = output_path(input, output_dir, EXTENSION);
+            let records = read_records_from_file(input)
+                .with_context(|| format!("failed to read {}", input.display()))?;
+            write_binpack(&output, &records)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            pb.inc(1);
+            Ok((fs::metadata(input)?.len(), fs::metadata(&output)?.len()))
+        })
+        .collect::<anyhow::Result<Vec<(u64, u64)>>>()?;
+    pb.finish_and_clear();
+
+    let (input_bytes, output_bytes) = sizes
+        .iter()
+        .fold((0, 0), |(i, o), &(input, output)| (i + input, o + output));
+    println!(
+        "Packed {} files: {} -> {} bytes ({:.1}%)",
+        files.len(),
+        input_bytes,
+        output_bytes,
+        output_bytes as f64 * 100.0 / input_bytes.max(1) as f64
+    );
+    Ok(())
+}
+
+/// Converts every binpack file matching `pattern` in `input_dir` back to a
+/// `.bin` file with the same stem in `output_dir`.
+pub fn unpack(input_dir: &str, output_dir: &str, pattern: &str) -> anyhow::Result<()> {
+    let files = find_files(Path::new(input_dir), pattern)?;
+    fs::create_dir_all(output_dir)?;
+    println!("Unpacking {} files...", files.len());
+
+    let pb = progress_bar(files.len())?;
+    let records = files
+        .par_iter()
+        .map(|input| {
+            let output = output_path(input, output_dir, "bin");
+            let records = read_binpack(input)
+                .with_context(|| format!("failed to read {}", input.display()))?;
+            // Records are appended, so replace any previous output
+            match fs::remove_file(&output) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+            write_records_to_file(&output, &records)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            pb.inc(1);
+            Ok(records.len())
+        })
+        .collect::<anyhow::Result<Vec<usize>>>()?;
+    pb.finish_and_clear();
+
+    println!(
+        "Unpacked {} records from {} files",
+        records.iter().sum::<usize>(),
+        files.len()
+    );
+    Ok(())
+}
+
+/// Writes records to a binpack file, replacing it if it exists.
+pub fn write_binpack(path: &Path, records: &[GameRecord]) -> io::Result<()> {
+    write_chains(path, records, MAX_CHAIN_LEN)
+}
+
+/// Writes records to a binpack file in chains of at most `max_chain_len`
+/// records.
+fn write_chains(path: &Path, records: &[GameRecord], max_chain_len: usize) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(MAGIC)?;
+    writer.write_u8(VERSION)?;
+
+    let mut compressor = zstd::bulk::Compressor::new(COMPRESSION_LEVEL)?;
+    compressor.set_parameter(zstd::zstd_safe::CParameter::ChecksumFlag(true))?;
+    let mut frame = Vec::with_capacity(FRAME_SIZE);
+    let mut rest = records;
+    while !rest.is_empty() {
+        let len = chain_len(rest, max_chain_len);
+        write_chain(&mut frame, &rest[..len])?;
+        rest = &rest[len..];
+        if frame.len() >= FRAME_SIZE || rest.is_empty() {
+            writer.write_all(&compressor.compress(&frame)?)?;
+            frame.clear();
+        }
+    }
+    writer.flush()
+}
+
+/// Reads all records from a binpack file.
+pub fn read_binpack(path: &Path) -> io::Result<Vec<GameRecord>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut header = [0u8; 5];
+    reader.read_exact(&mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(invalid_data("not a binpack file".to_owned()));
+    }
+    if header[4] != VERSION {
+        return Err(invalid_data(format!(
+            "unsupported binpack version {}",
+            header[4]
+        )));
+    }
+
+    let mut decoder = zstd::Decoder::with_buffer(reader)?;
+    let mut records = Vec::new();
+    loop {
+        // A missing chain header means the end of the data
+        let mut first = [0u8; 1];
+        if decoder.read(&mut first)? == 0 {
+            break;
+        }
+        read_chain(&mut first.chain(&mut decoder), &mut records)?;
+    }
+    Ok(records)
+}
+
+/// Number of leading records that can be stored as one chain of at most
+/// `max_len` records.
+fn chain_len(records: &[GameRecord], max_len: usize) -> usize {
+    let mut len = 1;
+    while len < records.len()
+        && len < max_len
+        && next_record_board(&records[len - 1])
+            == Some((records[len].board, records[len].side_to_move))
+        && follows(&records[len - 1], &records[len], &records[0])
+    {
+        len += 1;
+    }
+    len
+}
+
+/// Whether `next` has the fields a chain starting at `first` derives for the
+/// record after `prev`.
+fn follows(prev: &GameRecord, next: &GameRecord, first: &GameRecord) -> bool {
+    next.game_id == first.game_id
+        && next.ply == prev.ply.wrapping_add(1)
+        && next.game_score
+            == game_score_for(first.game_score, first.side_to_move, next.side_to_move)
+}
+
+/// Board and side to move after the move of `record`, if it is legal.
+///
+/// The board is from the perspective of the player to move, which is the
+/// opponent unless the opponent has to pass.
+fn next_record_board(record: &GameRecord) -> Option<(Board, Disc)> {
+    let sq = record.sq;
+    if sq == Square::None || !record.board.is_legal_move(sq) {
+        return None;
+    }
+    let next = record.board.make_move(sq);
+    if next.has_legal_moves() || !next.switch_players().has_legal_moves() {
+        Some((next, record.side_to_move.opposite()))
+    } else {
+        Some((next.switch_players(), record.side_to_move))
+    }
+}
+
+/// Game score from the perspective of `side`, given the game score from the
+/// perspective of `first_side`.
+fn game_score_for(game_score: i8, first_side: Disc, side: Disc) -> i8 {
+    if game_score == GAME_SCORE_UNAVAILABLE || side == first_side {
+        game_score
+    } else {
+        -game_score
+    }
+}
+
+fn quantize_score(score: f32) -> i16 {
+    (score * SCORE_SCALE)
+        .round()
+        .clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+fn write_chain(writer: &mut impl Write, chain: &[GameRecord]) -> io::Result<()> {
+    let first = &chain[0];
+    writer.write_u64::<LittleEndian>(first.board.player().bits())?;
+    writer.write_u64::<LittleEndian>(first.board.opponent().bits())?;
+    writer.write_u16::<LittleEndian>(first.game_id)?;
+    writer.write_u8(first.ply)?;
+    writer.write_u8(if first.side_to_move == Disc::Black {
+        0
+    } else {
+        1
+    })?;
+    writer.write_i8(first.game_score)?;
+    writer.write_u16::<LittleEndian>(chain.len() as u16)?;
+    for record in chain {
+        writer.write_u8(record.sq as u8)?;
+        writer.write_u8(record.move_kind as u8)?;
+        writer.write_i16::<LittleEndian>(quantize_score(record.score))?;
+    }
+    Ok(())
+}
+
+fn read_chain(reader: &mut impl Read, records: &mut Vec<GameRecord>) -> io::Result<()> {
+    let player = reader.read_u64::<LittleEndian>()?;
+    let opponent = reader.read_u64::<LittleEndian>()?;
+    if player & opponent != 0 {
+        return Err(invalid_data("overlapping bitboards".to_owned()));
+    }
+    let game_id = reader.read_u16::<LittleEndian>()?;
+    let ply = reader.read_u8()?;
+    let side_to_move = if reader.read_u8()? == 0 {
+        Disc::Black
+    } else {
+        Disc::White
+    };
+    let game_score = reader.read_i8()?;
+    let count = reader.read_u16::<LittleEndian>()?;
+
+    let mut board = Board::from_bitboards(Bitboard::new(player), Bitboard::new(opponent));
+    let mut side = side_to_move;
+    for i in 0..count {
+        let sq_byte = reader.read_u8()?;
+        let kind_byte = reader.read_u8()?;
+        let score = reader.read_i16::<LittleEndian>()?;
+
+        let sq = Square::from_u8(sq_byte)
+            .ok_or_else(|| invalid_data(format!("Invalid square: {sq_byte}")))?;
+        let move_kind = MoveKind::from_u8(kind_byte)
+            .ok_or_else(|| invalid_data(format!("Invalid move kind: {kind_byte}")))?;
+        let record = GameRecord {
+            game_id,
+            ply: ply.wrapping_add(i as u8),
+            board,
+            score: f32::from(score) / SCORE_SCALE,
+            game_score: game_score_for(game_score, side_to_move, side),
+            side_to_move: side,
+            move_kind,
+            sq,
+        };
+        if i + 1 < count {
+            (board, side) = next_record_board(&record)
+                .ok_or_else(|| invalid_data(format!("Illegal move in chain: {sq_byte}")))?;
+        }
+        records.push(record);
+    }
+    Ok(())
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn find_files(dir: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let full_pattern = dir.join(pattern).to_string_lossy().into_owned();
+    let paths = glob(&full_pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", full_pattern, e))?;
+    let mut files = Vec::new();
+    for entry in paths {
+        match entry {
+            Ok(path) if path.is_file() => files.push(path),
+            Ok(_) => {}
+            Err(e) => eprintln!(
+                "Warning: Failed to access path matched by glob ({}): {}",
+                e.path().display(),
+                e
+            ),
+        }
+    }
+    if files.is_empty() {
+        bail!("No files match '{full_pattern}'");
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Path in `output_dir` with the file stem of `input` and `extension`.
+fn output_path(input: &Path, output_dir: &str, extension: &str) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    Path::new(output_dir).join(format!("{stem}.{extension}"))
+}
+
+fn progress_bar(len: usize) -> io::Result<ProgressBar> {
+    let pb = ProgressBar::new(len as u64);
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} files ETA:{eta_precise}",
+        )
+        .map_err(io::Error::other)?
+        .progress_chars("#>-"),
+    );
+    Ok(pb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{RngExt, SeedableRng, rngs::SmallRng};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "datagen-binpack-{}-{name}.{EXTENSION}",
+            std::process::id()
+        ))
+    }
+
+    /// Plays a random game, recording every move with a random score.
+    fn random_game(
+        game_id: u16,
+        rng: &mut SmallRng,
+        game_score_available: bool,
+    ) -> Vec<GameRecord> {
+        let mut board = Board::new();
+        let mut side = Disc::Black;
+        let mut records = Vec::new();
+        loop {
+            if !board.has_legal_moves() {
+                if !board.switch_players().has_legal_moves() {
+                    break;
+                }
+                board = board.switch_players();
+                side = side.opposite();
+            }
+            let moves: Vec<Square> = board.get_moves().iter().collect();
+            let sq = moves[rng.random_range(0..moves.len())];
+            records.push(GameRecord {
+                game_id,
+                ply: records.len() as u8,
+                board,
+                score: rng.random_range(-64.0..64.0),
+                game_score: 0,
+                side_to_move: side,
+                move_kind: MoveKind::from_u8(rng.random_range(0..6)).unwrap(),
+                sq,
+            });
+            board = board.make_move(sq);
+            side = side.opposite();
+        }
+
+        let diff = board.get_player_count() as i8 - board.get_opponent_count() as i8;
+        let black_score = if side == Disc::Black { diff } else { -diff };
+        for record in &mut records {
+            record.game_score = if !game_score_available {
+                GAME_SCORE_UNAVAILABLE
+            } else if record.side_to_move == Disc::Black {
+                black_score
+            } else {
+                -black_score
+            };
+        }
+        records
+    }
+
+    fn quantized(score: f32) -> f32 {
+        f32::from(quantize_score(score)) / SCORE_SCALE
+    }
+
+    fn round_trip(name: &str, records: &[GameRecord], max_chain_len: usize) -> Vec<GameRecord> {
+        let path = temp_path(name);
+        write_chains(&path, records, max_chain_len).unwrap();
+        let unpacked = read_binpack(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(unpacked.len(), records.len());
+        for (record, unpacked) in records.iter().zip(&unpacked) {
+            assert_eq!(unpacked.game_id, record.game_id);
+            assert_eq!(unpacked.ply, record.ply);
+            assert_eq!(unpacked.board, record.board);
+            assert_eq!(unpacked.score, quantized(record.score));
+            assert_eq!(unpacked.game_score, record.game_score);
+            assert_eq!(unpacked.side_to_move, record.side_to_move);
+            assert_eq!(unpacked.move_kind, record.move_kind);
+            assert_eq!(unpacked.sq, record.sq);
+        }
+        unpacked
+    }
+
+    /// Number of chains `records` are stored in.
+    fn chain_count(records: &[GameRecord], max_chain_len: usize) -> usize {
+        let mut rest = records;
+        let mut count = 0;
+        while !rest.is_empty() {
+            rest = &rest[chain_len(rest, max_chain_len)..];
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn games_round_trip_as_one_chain_each() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let games: Vec<Vec<GameRecord>> = (0..200)
+            .map(|id| random_game(id, &mut rng, id % 4 != 0))
+            .collect();
+        let has_pass = games
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .any(|w| w[0].game_id == w[1].game_id && w[0].side_to_move == w[1].side_to_move);
+        assert!(has_pass, "no forced pass in the test games");
+        let records: Vec<GameRecord> = games.into_iter().flatten().collect();
+
+        assert_eq!(chain_count(&records, MAX_CHAIN_LEN), 200);
+        round_trip("games", &records, MAX_CHAIN_LEN);
+    }
+
+    #[test]
+    fn chain_breaks_on_game_id_change_and_ply_gap() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let mut records = random_game(1, &mut rng, true);
+        for record in &mut records[20..] {
+            record.game_id = 2;
+        }
+        assert_eq!(chain_len(&records, MAX_CHAIN_LEN), 20);
+        round_trip("game-id", &records, MAX_CHAIN_LEN);
+
+        let mut records = random_game(1, &mut rng, true);
+        for record in &mut records[30..] {
+            record.ply += 3;
+        }
+        assert_eq!(chain_len(&records, MAX_CHAIN_LEN), 30);
+        round_trip("ply-gap", &records, MAX_CHAIN_LEN);
+    }
+
+    #[test]
+    fn chains_split_at_max_len() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let records = random_game(1, &mut rng, true);
+        assert_eq!(chain_len(&records, 7), 7);
+        assert_eq!(chain_count(&records, 7), records.len().div_ceil(7));
+        round_trip("split", &records, 7);
+    }
+
+    #[test]
+    fn scores_are_quantized_to_1_64_disc() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let mut records = random_game(1, &mut rng, false);
+        let scores = [0.0, 1.0 / 64.0, -3.5, 0.01, 12.3456, -0.992, 1000.0];
+        for (record, score) in records.iter_mut().zip(scores) {
+            record.score = score;
+        }
+        let unpacked = round_trip("scores", &records, MAX_CHAIN_LEN);
+
+        assert_eq!(unpacked[0].score, 0.0);
+        assert_eq!(unpacked[1].score, 1.0 / 64.0);
+        assert_eq!(unpacked[2].score, -3.5);
+        assert_eq!(unpacked[3].score, 1.0 / 64.0);
+        assert_eq!(unpacked[4].score, 790.0 / 64.0);
+        assert_eq!(unpacked[5].score, -63.0 / 64.0);
+        assert_eq!(unpacked[6].score, f32::from(i16::MAX) / SCORE_SCALE);
+        for (record, unpacked) in records.iter().zip(&unpacked).skip(7) {
+            assert!((record.score - unpacked.score).abs() <= 0.5 / SCORE_SCALE);
+        }
+    }
+
+    /// Writes a binpack file of one frame holding `content`.
+    fn write_raw(path: &Path, content: &[u8]) {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        bytes.extend(zstd::bulk::compress(content, COMPRESSION_LEVEL).unwrap());
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn rejects_truncated_and_corrupt_files() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        let records: Vec<GameRecord> = (0..20)
+            .flat_map(|id| random_game(id, &mut rng, true))
+            .collect();
+        let path = temp_path("corrupt");
+        write_binpack(&path, &records).unwrap();
+        let bytes = fs::read(&path).unwrap();
+
+        fs::write(&path, &bytes[..bytes.len() - 10]).unwrap();
+        assert!(read_binpack(&path).is_err());
+
+        let mut corrupt = bytes.clone();
+        corrupt[bytes.len() / 2] ^= 0x55;
+        fs::write(&path, &corrupt).unwrap();
+        assert!(read_binpack(&path).is_err());
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        fs::write(&path, &corrupt).unwrap();
+        assert!(read_binpack(&path).is_err());
+
+        // Frames that decompress but hold invalid chains
+        let mut chain = Vec::new();
+        write_chain(&mut chain, &records[..10]).unwrap();
+        let mut truncated_chain = chain.clone();
+        truncated_chain.truncate(chain.len() - 2);
+        write_raw(&path, &truncated_chain);
+        assert!(read_binpack(&path).is_err());
+
+        let mut illegal_move = chain.clone();
+        // The square of the first entry, after the 23 byte chain header
+        illegal_move[23] = records[0].board.player().iter().next().unwrap() as u8;
+        write_raw(&path, &illegal_move);
+        assert!(read_binpack(&path).is_err());
+
+        let mut overlapping = chain;
+        overlapping[8..16].copy_from_slice(&records[0].board.player().bits().to_le_bytes());
+        write_raw(&path, &overlapping);
+        assert!(read_binpack(&path).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
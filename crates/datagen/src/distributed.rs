@@ -0,0 +1,379 @@
+//! Distributed self-play.
+//!
+//! A coordinator splits a self-play run into batches of games and hands them
+//! out over TCP to worker processes, which may run on other machines. A worker
+//! plays its batch like `selfplay` and sends the records back with a SHA-256
+//! checksum; the coordinator verifies it and writes the batch as one shard.
+//! The protocol consists of text lines, with the records of a shard sent as
+//! raw bytes after its `PUT` line:
+//!
+//! ```text
+//! worker:      GET
+//! coordinator: JOB <batch> <first_game> <num_games> <seed> | WAIT | DONE
+//! worker:      PUT <batch> <num_bytes> <sha256>
+//! coordinator: OK | ERR <reason>
+//! ```
+//!
+//! A batch goes back to the queue when the connection of its worker closes
+//! before the shard arrives or the shard is rejected. Shards are written via a
+//! `.tmp` sibling and renamed, and existing shards are skipped, so restarting
+//! the coordinator with the same arguments continues the run. There is no
+//! authentication, so the coordinator must only be reachable from trusted
+//! machines.
+
+use anyhow::bail;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::manifest::Manifest;
+use crate::record::{GameRecord, RECORD_SIZE, write_records};
+use crate::selfplay::{self, SelfplaySettings};
+
+/// Number of digits used in output file naming
+const FILE_ID_DIGITS: usize = 5;
+
+/// Maximum number of records of a game
+const MAX_RECORDS_PER_GAME: u64 = 60;
+
+/// Time a worker waits before asking again while the last batches are played
+const WAIT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Interval at which the coordinator checks for new connections
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Batches not written yet.
+struct Batches {
+    pending: VecDeque<usize>,
+    remaining: usize,
+}
+
+struct Coordinator {
+    prefix: String,
+    output_dir: PathBuf,
+    num_games: usize,
+    games_per_batch: usize,
+    seed: u64,
+    batches: Mutex<Batches>,
+    pb: ProgressBar,
+}
+
+/// Runs the coordinator of a distributed self-play run until every batch has
+/// been written.
+///
+/// # Arguments
+///
+/// * `listen` - Address to accept worker connections on
+/// * `num_games` - Number of games to generate
+/// * `games_per_batch` - Number of games of each batch and output file
+/// * `seed` - Base seed of the games' random number generators
+/// * `prefix` - Output file prefix
+/// * `output_dir` - Directory for output files
+pub fn coordinate(
+    listen: &str,
+    num_games: u32,
+    games_per_batch: u32,
+    seed: Option<u64>,
+    prefix: &str,
+    output_dir: &str,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(output_dir)?;
+
+    let manifest_path = Path::new(output_dir).join(format!("{prefix}.manifest"));
+    let seed = match Manifest::load(&manifest_path)? {
+        Some(manifest) => {
+            manifest.expect("command", "coordinator")?;
+            manifest.expect("games", num_games)?;
+            manifest.expect("games_per_batch", games_per_batch)?;
+            if let Some(seed) = seed {
+                manifest.expect("seed", seed)?;
+            }
+            manifest.get("seed")?
+        }
+        None => {
+            let seed = seed.unwrap_or_else(rand::random);
+            let mut manifest = Manifest::new(&manifest_path);
+            manifest.set("command", "coordinator");
+            manifest.set("games", num_games);
+            manifest.set("games_per_batch", games_per_batch);
+            manifest.set("seed", seed);
+            manifest.save()?;
+            seed
+        }
+    };
+
+    let num_batches = (num_games as usize).div_ceil(games_per_batch as usize);
+    let mut coordinator = Coordinator {
+        prefix: prefix.to_owned(),
+        output_dir: PathBuf::from(output_dir),
+        num_games: num_games as usize,
+        games_per_batch: games_per_batch as usize,
+        seed,
+        batches: Mutex::new(Batches {
+            pending: VecDeque::new(),
+            remaining: 0,
+        }),
+        pb: ProgressBar::with_draw_target(
+            Some(num_batches as u64),
+            ProgressDrawTarget::stderr_with_hz(10),
+        ),
+    };
+    let pending: VecDeque<usize> = (0..num_batches)
+        .filter(|&batch| !coordinator.shard_path(batch).exists())
+        .collect();
+    println!(
+        "{} of {num_batches} batches to play (seed {seed}), listening on {listen}",
+        pending.len()
+    );
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    coordinator.pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} batches ETA:{eta_precise}",
+        )
+        .map_err(io::Error::other)?
+        .progress_chars("#>-"),
+    );
+    coordinator
+        .pb
+        .set_position((num_batches - pending.len()) as u64);
+    *coordinator.batches.get_mut().unwrap() = Batches {
+        remaining: pending.len(),
+        pending,
+    };
+    let coordinator = Arc::new(coordinator);
+
+    // Poll for connections, so that the coordinator can stop once all
+    // batches are written while idle workers are still connected
+    let listener = TcpListener::bind(listen)?;
+    listener.set_nonblocking(true)?;
+    while coordinator.batches.lock().unwrap().remaining > 0 {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                stream.set_nonblocking(false)?;
+                let coordinator = Arc::clone(&coordinator);
+                thread::spawn(move || {
+                    if let Err(e) = coordinator.serve(stream) {
+                        coordinator
+                            .pb
+                            .println(format!("Warning: worker {peer} disconnected: {e}"));
+                    }
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_INTERVAL),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    coordinator.pb.finish_and_clear();
+    println!("All {num_batches} batches written");
+    Ok(())
+}
+
+impl Coordinator {
+    fn shard_path(&self, batch: usize) -> PathBuf {
+        self.output_dir
+            .join(format!("{}_{batch:0FILE_ID_DIGITS$}.bin", self.prefix))
+    }
+
+    /// Returns the first game and the number of games of `batch`.
+    fn batch_games(&self, batch: usize) -> (usize, usize) {
+        let first_game = batch * self.games_per_batch;
+        (
+            first_game,
+            self.games_per_batch.min(self.num_games - first_game),
+        )
+    }
+
+    /// Serves the requests of one worker, returning its batch to the queue if
+    /// the connection ends before the batch is written.
+    fn serve(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = stream;
+        let mut held = None;
+        let result = self.handle_requests(&mut reader, &mut writer, &mut held);
+        if let Some(batch) = held {
+            self.batches.lock().unwrap().pending.push_front(batch);
+            self.pb
+                .println(format!("Batch {batch} returned to the queue"));
+        }
+        result
+    }
+
+    fn handle_requests(
+        &self,
+        reader: &mut impl BufRead,
+        writer: &mut impl Write,
+        held: &mut Option<usize>,
+    ) -> io::Result<()> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["GET"] => {
+                    let mut batches = self.batches.lock().unwrap();
+                    if let Some(batch) = held.take() {
+                        batches.pending.push_front(batch);
+                    }
+                    if let Some(batch) = batches.pending.pop_front() {
+                        let (first_game, num_games) = self.batch_games(batch);
+                        *held = Some(batch);
+                        writeln!(writer, "JOB {batch} {first_game} {num_games} {}", self.seed)?;
+                    } else if batches.remaining > 0 {
+                        writeln!(writer, "WAIT")?;
+                    } else {
+                        writeln!(writer, "DONE")?;
+                    }
+                }
+                ["PUT", batch, num_bytes, checksum] => {
+                    let (Ok(batch), Ok(num_bytes)) = (batch.parse(), num_bytes.parse::<u64>())
+                    else {
+                        return Err(invalid_data(format!("invalid request: {}", line.trim())));
+                    };
+                    if *held != Some(batch) {
+                        return Err(invalid_data(format!("batch {batch} is not assigned")));
+                    }
+                    let (_, num_games) = self.batch_games(batch);
+                    if num_bytes > num_games as u64 * MAX_RECORDS_PER_GAME * RECORD_SIZE {
+                        return Err(invalid_data(format!(
+                            "batch {batch} is too large ({num_bytes} bytes)"
+                        )));
+                    }
+
+                    let mut shard = vec![0u8; num_bytes as usize];
+                    reader.read_exact(&mut shard)?;
+                    let actual = format!("{:x}", Sha256::digest(&shard));
+                    if actual != *checksum {
+                        *held = None;
+                        self.batches.lock().unwrap().pending.push_front(batch);
+                        writeln!(writer, "ERR checksum mismatch")?;
+                        continue;
+                    }
+                    if num_bytes % RECORD_SIZE != 0 {
+                        *held = None;
+                        self.batches.lock().unwrap().pending.push_front(batch);
+                        writeln!(writer, "ERR incomplete record")?;
+                        continue;
+                    }
+
+                    self.write_shard(batch, &shard)?;
+                    *held = None;
+                    self.batches.lock().unwrap().remaining -= 1;
+                    self.pb.inc(1);
+                    writeln!(writer, "OK")?;
+                }
+                _ => return Err(invalid_data(format!("invalid request: {}", line.trim()))),
+            }
+            writer.flush()?;
+        }
+    }
+
+    fn write_shard(&self, batch: usize, shard: &[u8]) -> io::Result<()> {
+        let path = self.shard_path(batch);
+        let temp_path = path.with_extension("bin.tmp");
+        {
+            let mut file = File::create(&temp_path)?;
+            file.write_all(shard)?;
+            file.sync_all()?;
+        }
+        fs::rename(&temp_path, &path)
+    }
+}
+
+/// Plays batches of the coordinator at `server` until the run is complete.
+///
+/// # Arguments
+///
+/// * `server` - Address of the coordinator
+/// * `settings` - Search and worker pool settings of the games
+pub fn work(server: &str, settings: &SelfplaySettings) -> anyhow::Result<()> {
+    let stream = TcpStream::connect(server)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    println!("Connected to {server}");
+
+    let mut line = String::new();
+    loop {
+        writeln!(writer, "GET")?;
+        writer.flush()?;
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            bail!("Coordinator closed the connection");
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["JOB", batch, first_game, num_games, seed] => {
+                let (Ok(batch), Ok(first_game), Ok(num_games), Ok(seed)) = (
+                    batch.parse::<usize>(),
+                    first_game.parse(),
+                    num_games.parse(),
+                    seed.parse(),
+                ) else {
+                    bail!("Invalid job: {}", line.trim());
+                };
+                println!(
+                    "Batch {batch}: games {first_game}..{}",
+                    first_game + num_games
+                );
+
+                let mut records: Vec<GameRecord> = Vec::new();
+                let mut game_id = 0u16;
+                selfplay::play_games(
+                    first_game,
+                    num_games,
+                    seed,
+                    settings,
+                    selfplay::random_opening,
+                    |played| {
+                        records.extend(
+                            played
+                                .records
+                                .into_iter()
+                                .map(|record| GameRecord { game_id, ..record }),
+                        );
+                        game_id = game_id.wrapping_add(1);
+                        Ok(())
+                    },
+                )?;
+
+                let mut shard = Vec::with_capacity(records.len() * RECORD_SIZE as usize);
+                write_records(&mut shard, &records)?;
+                let checksum = format!("{:x}", Sha256::digest(&shard));
+                writeln!(writer, "PUT {batch} {} {checksum}", shard.len())?;
+                writer.write_all(&shard)?;
+                writer.flush()?;
+
+                line.clear();
+                reader.read_line(&mut line)?;
+                match line.trim() {
+                    "OK" => {}
+                    "" => bail!("Coordinator closed the connection"),
+                    reply => eprintln!("Warning: batch {batch} rejected: {reply}"),
+                }
+            }
+            ["WAIT"] => thread::sleep(WAIT_INTERVAL),
+            ["DONE"] => {
+                println!("All batches are done");
+                return Ok(());
+            }
+            _ => bail!("Invalid reply: {}", line.trim()),
+        }
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,323 @@
+//! NumPy export module.
+//!
+//! Writes the records of binary data files as a directory of `.npy` arrays,
+//! one per record field, so that training pipelines can open them with
+//! `numpy.load(path, mmap_mode="r")` instead of parsing the record format.
+//! Row `i` of every array belongs to the same record, and records keep the
+//! order of the sorted input files.
+//!
+//! | File              | dtype    | Shape                       |
+//! |-------------------|----------|-----------------------------|
+//! | `player.npy`      | `<u8`    | `(n,)`                      |
+//! | `opponent.npy`    | `<u8`    | `(n,)`                      |
+//! | `score.npy`       | `<f4`    | `(n,)`                      |
+//! | `game_score.npy`  | `\|i1`   | `(n,)`                      |
+//! | `ply.npy`         | `\|u1`   | `(n,)`                      |
+//! | `move_kind.npy`   | `\|u1`   | `(n,)`                      |
+//! | `sq.npy`          | `\|u1`   | `(n,)`                      |
+//! | `side_to_move.npy`| `\|u1`   | `(n,)`                      |
+//! | `game_id.npy`     | `<u2`    | `(n,)`                      |
+//! | `features.npy`    | `<u2`    | `(n, NUM_PATTERN_FEATURES)` |
+//!
+//! `features.npy` is only written on request and holds the pattern indices of
+//! [`set_features`] from the side to move's perspective.
+
+use anyhow::bail;
+use byteorder::{LittleEndian, WriteBytesExt};
+use glob::glob;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use reversi_core::disc::Disc;
+use reversi_core::eval::pattern_feature::{NUM_PATTERN_FEATURES, set_features};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::manifest::{self, Manifest};
+use crate::record::{GameRecord, RECORD_SIZE, read_records_from_file};
+
+/// Magic string and format version 1.0 of `.npy` files
+const NPY_MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+
+/// Alignment of the start of the array data in `.npy` files
+const NPY_ALIGNMENT: usize = 64;
+
+/// File name of the checkpoint manifest in the output directory
+const MANIFEST_FILE: &str = "export.manifest";
+
+/// Exports the records of every file matching `pattern` in `input_dir` to
+/// `.npy` arrays in `output_dir`.
+///
+/// The arrays are flushed and the progress is checkpointed to a manifest
+/// after every input file, so an interrupted export can be resumed.
+///
+/// # Arguments
+///
+/// * `input_dir` - Directory containing binary data files
+/// * `output_dir` - Directory where the arrays are written
+/// * `pattern` - Glob pattern to match input files (e.g., "*.bin")
+/// * `features` - Whether to also write the pattern features of each board
+/// * `resume` - Whether to continue the export recorded in the manifest
+pub fn execute(
+    input_dir: &str,
+    output_dir: &str,
+    pattern: &str,
+    features: bool,
+    resume: bool,
+) -> anyhow::Result<()> {
+    let input_files = find_input_files(Path::new(input_dir), pattern)?;
+    let mut num_records = 0u64;
+    for path in &input_files {
+        let file_size = fs::metadata(path)?.len();
+        if file_size % RECORD_SIZE != 0 {
+            bail!(
+                "File size {} is not a multiple of RECORD_SIZE {} for file {}",
+                file_size,
+                RECORD_SIZE,
+                path.display()
+            );
+        }
+        num_records += file_size / RECORD_SIZE;
+    }
+
+    let output_dir = Path::new(output_dir);
+    fs::create_dir_all(output_dir)?;
+
+    let mut manifest = Manifest::new(&output_dir.join(MANIFEST_FILE));
+    manifest.set("command", "export");
+    manifest.set("input_dir", input_dir);
+    manifest.set("pattern", pattern);
+    manifest.set("features", features);
+    manifest.set("input_files", input_files.len());
+    manifest.set("num_records", num_records);
+
+    let mut files_completed = 0;
+    let mut records_written = None;
+    if resume && let Some(recorded) = Manifest::load(manifest.path())? {
+        for key in [
+            "command",
+            "input_dir",
+            "pattern",
+            "features",
+            "input_files",
+            "num_records",
+        ] {
+            recorded.expect(key, manifest.get::<String>(key)?)?;
+        }
+        files_completed = recorded.get("files_completed")?;
+        records_written = Some(recorded.get("records_written")?);
+        println!(
+            "Resuming after {files_completed} of {} files",
+            input_files.len()
+        );
+    }
+
+    println!(
+        "Exporting {num_records} records from {} files to {}",
+        input_files.len(),
+        output_dir.display()
+    );
+    let mut columns = Columns::open(output_dir, num_records, features, records_written)?;
+    if records_written.is_none() {
+        manifest.set("files_completed", 0);
+        manifest.set("records_written", 0);
+        manifest.save()?;
+    }
+
+    let pb = ProgressBar::with_draw_target(
+        Some(input_files.len() as u64),
+        ProgressDrawTarget::stderr_with_hz(10),
+    );
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} files ETA:{eta_precise}",
+        )
+        .map_err(io::Error::other)?
+        .progress_chars("#>-"),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb.set_position(files_completed as u64);
+
+    let mut records_written = records_written.unwrap_or(0);
+    for (i, path) in input_files.iter().enumerate().skip(files_completed) {
+        let records = read_records_from_file(path)?;
+        for record in &records {
+            columns.write(record)?;
+        }
+        columns.flush()?;
+
+        records_written += records.len() as u64;
+        manifest.set("files_completed", i + 1);
+        manifest.set("records_written", records_written);
+        manifest.save()?;
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+    Ok(())
+}
+
+/// Writers of the exported arrays.
+struct Columns {
+    player: BufWriter<File>,
+    opponent: BufWriter<File>,
+    score: BufWriter<File>,
+    game_score: BufWriter<File>,
+    ply: BufWriter<File>,
+    move_kind: BufWriter<File>,
+    sq: BufWriter<File>,
+    side_to_move: BufWriter<File>,
+    game_id: BufWriter<File>,
+    features: Option<BufWriter<File>>,
+    patterns: [u16; NUM_PATTERN_FEATURES],
+}
+
+impl Columns {
+    /// Creates the arrays for `num_records` records, or reopens them after
+    /// `records_written` records when resuming.
+    fn open(
+        output_dir: &Path,
+        num_records: u64,
+        features: bool,
+        records_written: Option<u64>,
+    ) -> io::Result<Self> {
+        let shape = format!("({num_records},)");
+        let column = |name: &str, descr: &str, row_size: u64| {
+            open_array(output_dir, name, descr, &shape, row_size, records_written)
+        };
+        Ok(Self {
+            player: column("player", "<u8", 8)?,
+            opponent: column("opponent", "<u8", 8)?,
+            score: column("score", "<f4", 4)?,
+            game_score: column("game_score", "|i1", 1)?,
+            ply: column("ply", "|u1", 1)?,
+            move_kind: column("move_kind", "|u1", 1)?,
+            sq: column("sq", "|u1", 1)?,
+            side_to_move: column("side_to_move", "|u1", 1)?,
+            game_id: column("game_id", "<u2", 2)?,
+            features: if features {
+                Some(open_array(
+                    output_dir,
+                    "features",
+                    "<u2",
+                    &format!("({num_records}, {NUM_PATTERN_FEATURES})"),
+                    2 * NUM_PATTERN_FEATURES as u64,
+                    records_written,
+                )?)
+            } else {
+                None
+            },
+            patterns: [0; NUM_PATTERN_FEATURES],
+        })
+    }
+
+    fn write(&mut self, record: &GameRecord) -> io::Result<()> {
+        self.player
+            .write_u64::<LittleEndian>(record.board.player().bits())?;
+        self.opponent
+            .write_u64::<LittleEndian>(record.board.opponent().bits())?;
+        self.score.write_f32::<LittleEndian>(record.score)?;
+        self.game_score.write_i8(record.game_score)?;
+        self.ply.write_u8(record.ply)?;
+        self.move_kind.write_u8(record.move_kind as u8)?;
+        self.sq.write_u8(record.sq as u8)?;
+        self.side_to_move
+            .write_u8(if record.side_to_move == Disc::Black {
+                0
+            } else {
+                1
+            })?;
+        self.game_id.write_u16::<LittleEndian>(record.game_id)?;
+        if let Some(writer) = self.features.as_mut() {
+            set_features(&record.board, &mut self.patterns);
+            for &pattern in &self.patterns {
+                writer.write_u16::<LittleEndian>(pattern)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for writer in [
+            &mut self.player,
+            &mut self.opponent,
+            &mut self.score,
+            &mut self.game_score,
+            &mut self.ply,
+            &mut self.move_kind,
+            &mut self.sq,
+            &mut self.side_to_move,
+            &mut self.game_id,
+        ]
+        .into_iter()
+        .chain(self.features.as_mut())
+        {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Opens the array `name` for appending rows of `row_size` bytes.
+///
+/// A new array starts with its header. When resuming, the array is truncated
+/// to its header and the `rows_written` rows of the checkpoint.
+fn open_array(
+    output_dir: &Path,
+    name: &str,
+    descr: &str,
+    shape: &str,
+    row_size: u64,
+    rows_written: Option<u64>,
+) -> io::Result<BufWriter<File>> {
+    let path = output_dir.join(format!("{name}.npy"));
+    let mut header = Vec::new();
+    write_npy_header(&mut header, descr, shape)?;
+
+    let file = match rows_written {
+        Some(rows) => {
+            manifest::truncate_to(&path, header.len() as u64 + rows * row_size)?;
+            OpenOptions::new().append(true).open(&path)?
+        }
+        None => {
+            let mut file = File::create(&path)?;
+            file.write_all(&header)?;
+            file
+        }
+    };
+    Ok(BufWriter::new(file))
+}
+
+fn find_input_files(dir: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let full_pattern = dir.join(pattern).to_string_lossy().into_owned();
+    let mut files: Vec<PathBuf> = glob(&full_pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", full_pattern, e))?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .collect();
+    if files.is_empty() {
+        bail!("No input files found in {}", dir.display());
+    }
+    // Sorted, so that the row order does not depend on the file system
+    files.sort();
+    Ok(files)
+}
+
+/// Writes a version 1.0 `.npy` header for a C-order array.
+///
+/// The header is padded with spaces so that the array data starts at a
+/// multiple of [`NPY_ALIGNMENT`] bytes, as the format requires.
+fn write_npy_header(writer: &mut impl Write, descr: &str, shape: &str) -> io::Result<()> {
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+    // Magic, version and the u16 header length precede the header
+    let prefix_len = NPY_MAGIC.len() + 2;
+    let padded_len = (prefix_len + header.len() + 1).next_multiple_of(NPY_ALIGNMENT);
+    header.extend(std::iter::repeat_n(
+        ' ',
+        padded_len - prefix_len - header.len() - 1,
+    ));
+    header.push('\n');
+
+    writer.write_all(NPY_MAGIC)?;
+    writer.write_u16::<LittleEndian>(header.len() as u16)?;
+    writer.write_all(header.as_bytes())
+}
@@ -0,0 +1,249 @@
+//! Record filtering module.
+//!
+//! Copies binary record files to a new directory, keeping only the records that
+//! pass the configured filters: a ply range, caps on the absolute search and
+//! game scores, removal of positions already seen in this run, and random
+//! sampling. Each input file produces an output file of the same name with the
+//! surviving records in their original order.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use glob::glob;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use rand::{RngExt, SeedableRng, rngs::SmallRng};
+use reversi_core::bitboard::Bitboard;
+use reversi_core::board::Board;
+
+use crate::record::{self, GAME_SCORE_OFFSET, GAME_SCORE_UNAVAILABLE, PLY_OFFSET, SCORE_OFFSET};
+
+/// Size of each game record in bytes
+const RECORD_SIZE: usize = record::RECORD_SIZE as usize;
+
+pub(crate) struct FilterCriteria {
+    pub min_ply: u8,
+    pub max_ply: u8,
+    /// Maximum absolute search score in discs
+    pub max_score: Option<f32>,
+    /// Maximum absolute game score in discs; unavailable game scores pass
+    pub max_game_score: Option<u8>,
+    /// Drop records whose board passed the filters above in an earlier record
+    pub dedup: bool,
+    /// Share of the remaining records to keep
+    pub sample_ratio: f64,
+    pub seed: u64,
+}
+
+#[derive(Default)]
+struct FilterStats {
+    records: u64,
+    kept: u64,
+    dropped_ply: u64,
+    dropped_score: u64,
+    dropped_game_score: u64,
+    dropped_duplicate: u64,
+    dropped_sample: u64,
+}
+
+/// Filters the records of every file matching `pattern` in `input_dir` into
+/// files of the same name in `output_dir`.
+///
+/// # Arguments
+///
+/// * `input_dir` - Directory containing input files
+/// * `output_dir` - Directory where filtered files will be written
+/// * `pattern` - Glob pattern to match input files (e.g., "*.bin")
+/// * `criteria` - Filters applied to each record
+///
+/// # Returns
+///
+/// Returns `Ok(())` on success, or an error if file operations fail.
+pub fn execute(
+    input_dir: &str,
+    output_dir: &str,
+    pattern: &str,
+    criteria: FilterCriteria,
+) -> anyhow::Result<()> {
+    let input_dir_path = Path::new(input_dir);
+    let output_dir_path = Path::new(output_dir);
+    if output_dir_path.exists()
+        && fs::canonicalize(input_dir_path)? == fs::canonicalize(output_dir_path)?
+    {
+        anyhow::bail!("Output directory must differ from the input directory");
+    }
+    fs::create_dir_all(output_dir_path)?;
+
+    let input_files = find_input_files(input_dir_path, pattern)?;
+    if input_files.is_empty() {
+        println!("No input files found – nothing to do.");
+        return Ok(());
+    }
+
+    println!("Input folder  : {input_dir:?}");
+    println!("Output folder : {output_dir:?}");
+    println!("Input files   : {}", input_files.len());
+    println!(
+        "Ply range     : {}..={}",
+        criteria.min_ply, criteria.max_ply
+    );
+    match criteria.max_score {
+        Some(t) => println!("Max |score|   : {t}"),
+        None => println!("Max |score|   : off"),
+    }
+    match criteria.max_game_score {
+        Some(t) => println!("Max |game|    : {t}"),
+        None => println!("Max |game|    : off"),
+    }
+    println!(
+        "Dedup         : {}",
+        if criteria.dedup { "yes" } else { "no" }
+    );
+    println!(
+        "Sample ratio  : {} (seed {})",
+        criteria.sample_ratio, criteria.seed
+    );
+    println!("----------------------------------------");
+
+    let pb = ProgressBar::with_draw_target(
+        Some(input_files.len() as u64),
+        ProgressDrawTarget::stderr_with_hz(10),
+    );
+    pb.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} files ETA:{eta_precise}",
+        )
+        .map_err(io::Error::other)?
+        .progress_chars("#>-"),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+
+    let mut rng = SmallRng::seed_from_u64(criteria.seed);
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut stats = FilterStats::default();
+
+    for path in &input_files {
+        let output_path = output_dir_path.join(path.file_name().unwrap_or_default());
+        filter_file(
+            path,
+            &output_path,
+            &criteria,
+            &mut seen,
+            &mut rng,
+            &mut stats,
+        )?;
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+
+    println!("------------- Summary -------------");
+    println!("Records       : {}", stats.records);
+    println!("Kept          : {}", stats.kept);
+    println!("Dropped       : {}", stats.records - stats.kept);
+    println!("  ply         : {}", stats.dropped_ply);
+    println!("  score       : {}", stats.dropped_score);
+    println!("  game_score  : {}", stats.dropped_game_score);
+    println!("  duplicate   : {}", stats.dropped_duplicate);
+    println!("  sample      : {}", stats.dropped_sample);
+    println!("-----------------------------------");
+    Ok(())
+}
+
+fn find_input_files(dir: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let full_pattern = dir.join(pattern).to_string_lossy().into_owned();
+    let paths = glob(&full_pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", full_pattern, e))?;
+    let mut files = Vec::new();
+    for entry in paths {
+        match entry {
+            Ok(path) if path.is_file() => files.push(path),
+            Ok(_) => {}
+            Err(e) => eprintln!(
+                "Warning: Failed to access path matched by glob ({}): {}",
+                e.path().display(),
+                e
+            ),
+        }
+    }
+    // Sorted, so that deduplication and sampling are reproducible
+    files.sort();
+    Ok(files)
+}
+
+/// Writes the records of `path` that pass `criteria` to `output_path`.
+fn filter_file(
+    path: &Path,
+    output_path: &Path,
+    criteria: &FilterCriteria,
+    seen: &mut HashSet<u64>,
+    rng: &mut SmallRng,
+    stats: &mut FilterStats,
+) -> io::Result<()> {
+    let file_size = fs::metadata(path)?.len();
+    if file_size == 0 || file_size % RECORD_SIZE as u64 != 0 {
+        eprintln!(
+            "Warning: {} skipped (size {} is not a multiple of RECORD_SIZE {})",
+            path.display(),
+            file_size,
+            RECORD_SIZE
+        );
+        return Ok(());
+    }
+
+    let mut bytes = Vec::with_capacity(file_size as usize);
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    let mut kept = Vec::with_capacity(bytes.len());
+    for chunk in bytes.chunks_exact(RECORD_SIZE) {
+        stats.records += 1;
+
+        let ply = chunk[PLY_OFFSET];
+        if ply < criteria.min_ply || ply > criteria.max_ply {
+            stats.dropped_ply += 1;
+            continue;
+        }
+        if let Some(cap) = criteria.max_score {
+            let score_bytes: [u8; 4] = chunk[SCORE_OFFSET..SCORE_OFFSET + 4]
+                .try_into()
+                .expect("4-byte score slice");
+            if f32::from_le_bytes(score_bytes).abs() > cap {
+                stats.dropped_score += 1;
+                continue;
+            }
+        }
+        if let Some(cap) = criteria.max_game_score {
+            let game_score = chunk[GAME_SCORE_OFFSET] as i8;
+            if game_score != GAME_SCORE_UNAVAILABLE && game_score.unsigned_abs() > cap {
+                stats.dropped_game_score += 1;
+                continue;
+            }
+        }
+        if criteria.dedup {
+            let player_bits = u64::from_le_bytes(chunk[0..8].try_into().unwrap());
+            let opponent_bits = u64::from_le_bytes(chunk[8..16].try_into().unwrap());
+            let board =
+                Board::from_bitboards(Bitboard::new(player_bits), Bitboard::new(opponent_bits));
+            if !seen.insert(board.hash()) {
+                stats.dropped_duplicate += 1;
+                continue;
+            }
+        }
+        if criteria.sample_ratio < 1.0 && !rng.random_bool(criteria.sample_ratio) {
+            stats.dropped_sample += 1;
+            continue;
+        }
+
+        kept.extend_from_slice(chunk);
+        stats.kept += 1;
+    }
+
+    let temp_path = output_path.with_extension("bin.tmp");
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&kept)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp_path, output_path)
+}
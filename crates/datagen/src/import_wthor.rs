@@ -0,0 +1,151 @@
+//! WTHOR import module.
+//!
+//! Converts WTHOR game databases (`.wtb`) into the shared binary record format,
+//! so that human games can supplement self-play data. Every position before a
+//! move becomes a record whose `game_score` is the recorded game result. The
+//! `score` is either that result as well or, with a search level, the score
+//! of a search of the position.
+
+use anyhow::bail;
+use glob::glob;
+use reversi_core::disc::Disc;
+use reversi_core::formats::wthor;
+use reversi_core::game_state::GameState;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::record::{GameRecord, MoveKind, write_records_to_file};
+use crate::rescore::Rescorer;
+
+/// Number of digits used in output file naming
+const FILE_ID_DIGITS: usize = 5;
+
+/// Imports the games of `input`, a WTHOR file or a directory of `.wtb` files.
+///
+/// # Arguments
+///
+/// * `input` - WTHOR file, or directory containing them
+/// * `output_dir` - Directory for output files
+/// * `prefix` - Output file prefix
+/// * `games_per_file` - Maximum games per output file
+/// * `rescorer` - Search used to score the positions, if any
+pub fn execute(
+    input: &str,
+    output_dir: &str,
+    prefix: &str,
+    games_per_file: u32,
+    rescorer: Option<&Rescorer>,
+) -> anyhow::Result<()> {
+    let input_files = find_input_files(Path::new(input))?;
+    fs::create_dir_all(output_dir)?;
+
+    let mut games: Vec<Vec<GameRecord>> = Vec::new();
+    let mut skipped = 0usize;
+    for path in &input_files {
+        let bytes = fs::read(path)?;
+        let records =
+            wthor::read(&bytes).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+        for (i, record) in records.iter().enumerate() {
+            match game_positions(record) {
+                Ok(positions) => games.push(positions),
+                Err(e) => {
+                    eprintln!("Warning: {} game {} skipped: {e}", path.display(), i + 1);
+                    skipped += 1;
+                }
+            }
+        }
+    }
+    println!(
+        "Read {} games from {} files ({skipped} skipped)",
+        games.len(),
+        input_files.len()
+    );
+
+    for (file_id, file_games) in games.chunks(games_per_file as usize).enumerate() {
+        let mut records = Vec::new();
+        for (game_id, game) in file_games.iter().enumerate() {
+            records.extend(game.iter().map(|record| GameRecord {
+                game_id: game_id as u16,
+                ..record.clone()
+            }));
+        }
+
+        let output_path =
+            Path::new(output_dir).join(format!("{prefix}_{file_id:0FILE_ID_DIGITS$}.bin"));
+        match rescorer {
+            Some(rescorer) => rescorer.rescore_records(&output_path, &records, &output_path)?,
+            None => {
+                // Records are appended, so replace any previous output
+                match fs::remove_file(&output_path) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                    _ => {}
+                }
+                write_records_to_file(&output_path, &records)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn find_input_files(input: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if !input.is_dir() {
+        return Ok(vec![input.to_path_buf()]);
+    }
+
+    let full_pattern = input.join("*").to_string_lossy().into_owned();
+    let mut files: Vec<PathBuf> = glob(&full_pattern)
+        .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", full_pattern, e))?
+        .filter_map(Result::ok)
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("wtb"))
+        })
+        .collect();
+    if files.is_empty() {
+        bail!("No .wtb files found in {}", input.display());
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Replays a game and returns a record for each position before a move.
+fn game_positions(game: &reversi_core::formats::GameRecord) -> Result<Vec<GameRecord>, String> {
+    let result = game
+        .result
+        .ok_or_else(|| "missing game result".to_string())?;
+    let black_score = result.clamp(-64, 64) as i8;
+
+    let mut state = GameState::from_board(game.board, game.side_to_move);
+    let mut records = Vec::with_capacity(game.moves.len());
+    for (i, &sq) in game.moves.iter().enumerate() {
+        if !state.board().has_legal_moves() && !state.is_game_over() {
+            state.make_pass()?;
+        }
+
+        let board = *state.board();
+        let side_to_move = state.side_to_move();
+        let game_score = if side_to_move == Disc::Black {
+            black_score
+        } else {
+            -black_score
+        };
+        records.push(GameRecord {
+            game_id: 0,
+            ply: 60 - board.get_empty_count() as u8,
+            board,
+            score: f32::from(game_score),
+            game_score,
+            side_to_move,
+            move_kind: MoveKind::Imported,
+            sq,
+        });
+
+        state
+            .make_move(sq)
+            .map_err(|_| format!("illegal move at position {}: {sq}", i + 1))?;
+    }
+    Ok(records)
+}
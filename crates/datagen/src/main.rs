@@ -1,20 +1,45 @@
+mod augment;
+mod balanced_openings;
+mod binpack;
+mod distributed;
+mod export;
+mod filter;
+mod import_wthor;
+mod manifest;
 mod opening;
+mod opponent;
 mod overwrite_scores;
+mod policy;
 mod probcut;
+mod probcut_fit;
 mod record;
+mod rescore;
 mod score_openings;
 mod selfplay;
 mod shuffle;
+mod solve;
 
 use clap::{Parser, Subcommand};
-use reversi_core::level::Level;
+use match_runner::engine::EngineProtocol;
+use reversi_core::level::{Level, MAX_LEVEL, get_level};
 use reversi_core::probcut::Selectivity;
 use reversi_core::types::Depth;
 
+use crate::filter::FilterCriteria;
 use crate::shuffle::FilterConfig;
 
+/// Seed of the commands whose runs are not otherwise seeded at random
+const DEFAULT_SEED: u64 = 42;
+
 #[derive(Parser, Debug)]
 struct Cli {
+    #[arg(
+        long,
+        global = true,
+        help = "Seed of all random number generators of the command, for reproducible runs [default: random for selfplay and coordinator, 42 otherwise]"
+    )]
+    seed: Option<u64>,
+
     #[command(subcommand)]
     command: SubCommands,
 }
@@ -51,22 +76,171 @@ enum SubCommands {
         #[arg(long)]
         openings: Option<String>,
 
-        #[arg(long, default_value = "false")]
+        #[arg(
+            long,
+            default_value = "false",
+            help = "Continue the run recorded in the manifest of the output directory"
+        )]
         resume: bool,
+
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=256),
+            help = "Number of games played concurrently")]
+        threads: u32,
+
+        #[arg(long, default_value = "20", value_parser = clap::value_parser!(u8).range(1..=60),
+            help = "Randomize searched moves before this ply with --temperature or --epsilon")]
+        noise_plies: u8,
+
+        #[arg(long, value_parser = parse_temperature,
+            help = "Sample moves from the softmax of the root move scores with this temperature (in discs)")]
+        temperature: Option<f32>,
+
+        #[arg(long, value_parser = parse_probability,
+            help = "Probability of playing a uniformly random move instead of the searched one")]
+        epsilon: Option<f32>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Write the root move scores of every position to .policy files, searching all root moves"
+        )]
+        policy: bool,
+
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..),
+            help = "Play under time control with this main time per player in milliseconds, instead of --mid-depth and --end-depth")]
+        main_time: Option<u64>,
+
+        #[arg(
+            long,
+            requires = "main_time",
+            conflicts_with = "byoyomi",
+            help = "Fischer increment added after every move in milliseconds"
+        )]
+        increment: Option<u64>,
+
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..),
+            help = "Byoyomi time per move in milliseconds once the main time has run out")]
+        byoyomi: Option<u64>,
+
+        #[arg(
+            long,
+            value_name = "COMMAND",
+            help = "Command line of an external engine playing one side of some games"
+        )]
+        opponent: Option<String>,
+
+        #[arg(long, value_enum, default_value_t = EngineProtocol::Gtp, requires = "opponent",
+            help = "Protocol spoken by the opponent engine")]
+        opponent_protocol: EngineProtocol,
+
+        #[arg(
+            long,
+            value_name = "COMMAND",
+            requires = "opponent",
+            help = "Command sent to the opponent engine after startup, in its protocol (repeatable)"
+        )]
+        opponent_init: Vec<String>,
+
+        #[arg(long, default_value = "0.5", value_parser = parse_probability, requires = "opponent",
+            help = "Probability of playing a game against the opponent engine")]
+        opponent_rate: f32,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            conflicts_with_all = ["main_time", "byoyomi", "opponent"],
+            help = "Search with one thread and no state shared between games, so that the seed determines the output"
+        )]
+        deterministic: bool,
+    },
+    Coordinator {
+        #[arg(
+            long,
+            default_value = "0.0.0.0:7878",
+            help = "Address to accept worker connections on"
+        )]
+        listen: String,
+
+        #[arg(long)]
+        games: u32,
+
+        #[arg(long, default_value = "10000", value_parser = clap::value_parser!(u32).range(1..=65536),
+            help = "Number of games handed out at once and written to each output file")]
+        games_per_batch: u32,
+
+        #[arg(long, help = "Output file prefix [default: hostname]")]
+        prefix: Option<String>,
+
+        #[arg(short, long)]
+        output_dir: String,
+    },
+    Worker {
+        #[arg(long, help = "Address of the coordinator")]
+        server: String,
+
+        #[arg(long, default_value = "512")]
+        hash_size: usize,
+
+        #[arg(long, default_value = "12", value_parser = clap::value_parser!(u32).range(1..=60),
+            help = "Midgame search depth")]
+        mid_depth: u32,
+
+        #[arg(long, default_value = "21", value_parser = parse_end_depth,
+            help = "Endgame search depth. Single value for all selectivities, or 4 comma-separated values (Level1,Level2,Level3,None)")]
+        end_depth: [Depth; 4],
+
+        #[arg(long, default_value = "0", value_parser = clap::value_parser!(u8).range(0..=3))]
+        selectivity: u8,
+
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=256),
+            help = "Number of games played concurrently")]
+        threads: u32,
+
+        #[arg(long, default_value = "20", value_parser = clap::value_parser!(u8).range(1..=60),
+            help = "Randomize searched moves before this ply with --temperature or --epsilon")]
+        noise_plies: u8,
+
+        #[arg(long, value_parser = parse_temperature,
+            help = "Sample moves from the softmax of the root move scores with this temperature (in discs)")]
+        temperature: Option<f32>,
+
+        #[arg(long, value_parser = parse_probability,
+            help = "Probability of playing a uniformly random move instead of the searched one")]
+        epsilon: Option<f32>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Search with one thread and no state shared between games, so that the coordinator's seed determines the output"
+        )]
+        deterministic: bool,
     },
     Opening {
         #[arg(short, long)]
         depth: Depth,
     },
     Probcut {
-        #[arg(short, long)]
-        input: String,
+        #[arg(short, long, required_unless_present = "fit_only")]
+        input: Option<String>,
 
         #[arg(short, long)]
         output: String,
 
         #[arg(long, default_value = "false")]
         endgame: bool,
+
+        #[arg(long, help = "Write a CSV report of the fit per ply and depth pair")]
+        report: Option<String>,
+
+        #[arg(long, help = "Write the fitted parameter table as Rust source")]
+        rust_output: Option<String>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Fit the samples already in --output instead of generating them"
+        )]
+        fit_only: bool,
     },
     Shuffle {
         #[arg(short, long)]
@@ -78,12 +252,13 @@ enum SubCommands {
         #[arg(short = 'p', long, default_value = "*.bin")]
         pattern: String,
 
-        #[arg(short = 'c', long, default_value_t = 10)]
-        files_per_chunk: usize,
-
         #[arg(short = 'n', long)]
         num_output_files: Option<usize>,
 
+        #[arg(long, default_value_t = 2048, value_parser = clap::value_parser!(u64).range(16..),
+            help = "Approximate memory budget in MiB")]
+        memory_mb: u64,
+
         #[arg(short = 'm', long, default_value_t = 0)]
         min_ply: u8,
 
@@ -97,7 +272,7 @@ enum SubCommands {
         #[arg(
             long,
             default_value_t = false,
-            help = "Drop records whose move was chosen randomly (is_random = true)."
+            help = "Drop records whose move was chosen randomly (non-zero random move flag)."
         )]
         drop_random: bool,
 
@@ -107,6 +282,107 @@ enum SubCommands {
             help = "Keep all records with ply >= this value, bypassing --drop-random and --max-score-diff filters."
         )]
         keep_above_ply: Option<u8>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Continue the shuffle recorded in the manifest of the output directory"
+        )]
+        resume: bool,
+    },
+    Filter {
+        #[arg(short, long)]
+        input_dir: String,
+
+        #[arg(short, long)]
+        output_dir: String,
+
+        #[arg(short = 'p', long, default_value = "*.bin")]
+        pattern: String,
+
+        #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=60),
+            help = "Drop records before this ply")]
+        min_ply: u8,
+
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u8).range(0..=60),
+            help = "Drop records after this ply")]
+        max_ply: u8,
+
+        #[arg(long, value_parser = parse_score_diff_threshold,
+            help = "Drop records whose |score| exceeds this many discs")]
+        max_score: Option<f32>,
+
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=64),
+            help = "Drop records whose |game_score| exceeds this many discs. Records with unavailable game_score are kept.")]
+        max_game_score: Option<u8>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Drop records whose board already occurred earlier in the run, across all files"
+        )]
+        dedup: bool,
+
+        #[arg(long, default_value_t = 1.0, value_parser = parse_sample_ratio,
+            help = "Keep this share of the records that pass the other filters, chosen at random")]
+        sample_ratio: f64,
+    },
+    Augment {
+        #[arg(short, long)]
+        input_dir: String,
+
+        #[arg(short, long)]
+        output_dir: String,
+
+        #[arg(short = 'p', long, default_value = "*.bin")]
+        pattern: String,
+
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_values_t = augment::Symmetry::ALL,
+            help = "Comma-separated symmetries to emit, in output order"
+        )]
+        symmetries: Vec<augment::Symmetry>,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Drop variants whose board equals an earlier variant of the same record"
+        )]
+        dedup: bool,
+    },
+    ImportWthor {
+        #[arg(
+            short,
+            long,
+            help = "WTHOR file, or directory of .wtb files, to import"
+        )]
+        input: String,
+
+        #[arg(short, long)]
+        output_dir: String,
+
+        #[arg(long, default_value = "wthor")]
+        prefix: String,
+
+        #[arg(long, default_value = "10000")]
+        games_per_file: u32,
+
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=MAX_LEVEL as i64),
+            help = "Score positions with a search at this level instead of the game result")]
+        level: Option<u8>,
+
+        #[arg(long, default_value = "0", value_parser = clap::value_parser!(u8).range(0..=3))]
+        selectivity: u8,
+
+        #[arg(long, default_value = "512")]
+        hash_size: usize,
+
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=256),
+            help = "Number of positions searched concurrently")]
+        threads: u32,
     },
     ScoreOpenings {
         #[arg(long, value_parser = clap::value_parser!(u8).range(1..=20),
@@ -130,6 +406,145 @@ enum SubCommands {
         #[arg(short, long)]
         output: String,
     },
+    BalancedOpenings {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=20),
+            help = "Number of plies of each opening")]
+        depth: u8,
+
+        #[arg(long, default_value = "2", value_parser = parse_score_diff_threshold,
+            help = "Keep openings whose score is within this many discs of equal")]
+        max_score: f32,
+
+        #[arg(long, default_value = "512")]
+        hash_size: usize,
+
+        #[arg(long, default_value = "16", value_parser = clap::value_parser!(u32).range(1..=60),
+            help = "Midgame search depth")]
+        mid_depth: u32,
+
+        #[arg(long, default_value = "24", value_parser = parse_end_depth,
+            help = "Endgame search depth. Single value for all selectivities, or 4 comma-separated values")]
+        end_depth: [Depth; 4],
+
+        #[arg(long, default_value = "0", value_parser = clap::value_parser!(u8).range(0..=3))]
+        selectivity: u8,
+
+        #[arg(
+            short,
+            long,
+            help = "Opening file to write, one move sequence per line"
+        )]
+        output: String,
+    },
+    Pack {
+        #[arg(short, long)]
+        input_dir: String,
+
+        #[arg(short, long)]
+        output_dir: String,
+
+        #[arg(short = 'p', long, default_value = "*.bin")]
+        pattern: String,
+    },
+    Unpack {
+        #[arg(short, long)]
+        input_dir: String,
+
+        #[arg(short, long)]
+        output_dir: String,
+
+        #[arg(short = 'p', long, default_value = "*.binpack")]
+        pattern: String,
+    },
+    Export {
+        #[arg(short, long)]
+        input_dir: String,
+
+        #[arg(short, long)]
+        output_dir: String,
+
+        #[arg(short = 'p', long, default_value = "*.bin")]
+        pattern: String,
+
+        #[arg(long, help = "Also write the pattern features of each board")]
+        features: bool,
+
+        #[arg(
+            long,
+            default_value_t = false,
+            help = "Continue the export recorded in the manifest of the output directory"
+        )]
+        resume: bool,
+    },
+    Rescore {
+        #[arg(
+            short,
+            long,
+            help = "Binary file, or directory of binary files, to rescore"
+        )]
+        input: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Output file, or directory when --input is a directory"
+        )]
+        output: String,
+
+        #[arg(short = 'p', long, default_value = "*.bin")]
+        pattern: String,
+
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=MAX_LEVEL as i64),
+            help = "Search level of the new scores")]
+        level: u8,
+
+        #[arg(long, default_value = "0", value_parser = clap::value_parser!(u8).range(0..=3))]
+        selectivity: u8,
+
+        #[arg(long, default_value = "512")]
+        hash_size: usize,
+
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=256),
+            help = "Number of positions searched concurrently")]
+        threads: u32,
+    },
+    Solve {
+        #[arg(
+            short,
+            long,
+            help = "Binary file, or directory of binary files, to take positions from"
+        )]
+        input: String,
+
+        #[arg(
+            short,
+            long,
+            help = "Output file, or directory when --input is a directory"
+        )]
+        output: String,
+
+        #[arg(short = 'p', long, default_value = "*.bin")]
+        pattern: String,
+
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=60),
+            help = "Minimum number of empty squares of the positions to solve")]
+        min_empties: u32,
+
+        #[arg(long, default_value = "20", value_parser = clap::value_parser!(u32).range(1..=60),
+            help = "Maximum number of empty squares of the positions to solve")]
+        max_empties: u32,
+
+        #[arg(long, default_value_t = 1.0, value_parser = parse_sample_ratio,
+            help = "Solve this share of the eligible positions, chosen at random")]
+        sample_ratio: f64,
+
+        #[arg(long, default_value = "512")]
+        hash_size: usize,
+
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=256),
+            help = "Number of positions solved concurrently")]
+        threads: u32,
+    },
     OverwriteScores {
         #[arg(
             short,
@@ -154,6 +569,30 @@ fn parse_score_diff_threshold(s: &str) -> Result<f32, String> {
     Ok(v)
 }
 
+fn parse_temperature(s: &str) -> Result<f32, String> {
+    let v: f32 = s.parse().map_err(|e| format!("invalid f32 '{s}': {e}"))?;
+    if !v.is_finite() || v <= 0.0 {
+        return Err(format!("expected a positive finite number, got {v}"));
+    }
+    Ok(v)
+}
+
+fn parse_probability(s: &str) -> Result<f32, String> {
+    let v: f32 = s.parse().map_err(|e| format!("invalid f32 '{s}': {e}"))?;
+    if !(0.0..=1.0).contains(&v) {
+        return Err(format!("expected a probability between 0 and 1, got {v}"));
+    }
+    Ok(v)
+}
+
+fn parse_sample_ratio(s: &str) -> Result<f64, String> {
+    let v: f64 = s.parse().map_err(|e| format!("invalid f64 '{s}': {e}"))?;
+    if !(v > 0.0 && v <= 1.0) {
+        return Err(format!("expected a ratio in (0, 1], got {v}"));
+    }
+    Ok(v)
+}
+
 fn parse_end_depth(s: &str) -> Result<[Depth; 4], String> {
     let values: Vec<Depth> = s
         .split(',')
@@ -179,6 +618,7 @@ fn parse_end_depth(s: &str) -> Result<[Depth; 4], String> {
 
 fn main() {
     let args = Cli::parse();
+    let seed = args.seed;
     match args.command {
         SubCommands::Selfplay {
             games,
@@ -191,21 +631,62 @@ fn main() {
             output_dir,
             openings,
             resume,
+            threads,
+            noise_plies,
+            temperature,
+            epsilon,
+            policy,
+            main_time,
+            increment,
+            byoyomi,
+            opponent,
+            opponent_protocol,
+            opponent_init,
+            opponent_rate,
+            deterministic,
         } => {
             let prefix =
                 prefix.unwrap_or_else(|| gethostname::gethostname().to_string_lossy().into_owned());
-            let level = Level {
-                mid_depth,
-                end_depth,
+            let settings = selfplay::SelfplaySettings {
+                hash_size,
+                level: Level {
+                    mid_depth,
+                    end_depth,
+                },
+                selectivity: Selectivity::from_u8(selectivity),
+                threads: threads as usize,
+                seed,
+                noise: selfplay::MoveNoise {
+                    plies: noise_plies,
+                    temperature,
+                    epsilon,
+                },
+                policy,
+                time_control: match (main_time, byoyomi) {
+                    (main_time, Some(time_per_move_ms)) => Some(selfplay::TimeControl::Byoyomi {
+                        main_time_ms: main_time.unwrap_or(0),
+                        time_per_move_ms,
+                    }),
+                    (Some(main_time_ms), None) => Some(selfplay::TimeControl::Fischer {
+                        main_time_ms,
+                        increment_ms: increment.unwrap_or(0),
+                    }),
+                    (None, None) => None,
+                },
+                opponent: opponent.map(|command| opponent::OpponentSettings {
+                    command,
+                    protocol: opponent_protocol,
+                    init_commands: opponent_init,
+                    rate: opponent_rate,
+                }),
+                deterministic,
             };
             if let Some(openings_path) = openings {
                 selfplay::execute_with_openings(
                     &openings_path,
                     resume,
                     games_per_file,
-                    hash_size,
-                    level,
-                    Selectivity::from_u8(selectivity),
+                    &settings,
                     &prefix,
                     &output_dir,
                 )
@@ -213,16 +694,63 @@ fn main() {
             } else {
                 selfplay::execute(
                     games,
+                    resume,
                     games_per_file,
-                    hash_size,
-                    level,
-                    Selectivity::from_u8(selectivity),
+                    &settings,
                     &prefix,
                     &output_dir,
                 )
                 .expect("Failed to execute selfplay");
             }
         }
+        SubCommands::Coordinator {
+            listen,
+            games,
+            games_per_batch,
+            prefix,
+            output_dir,
+        } => {
+            let prefix =
+                prefix.unwrap_or_else(|| gethostname::gethostname().to_string_lossy().into_owned());
+            distributed::coordinate(&listen, games, games_per_batch, seed, &prefix, &output_dir)
+                .expect("Failed to execute coordinator");
+        }
+        SubCommands::Worker {
+            server,
+            hash_size,
+            mid_depth,
+            end_depth,
+            selectivity,
+            threads,
+            noise_plies,
+            temperature,
+            epsilon,
+            deterministic,
+        } => {
+            if seed.is_some() {
+                eprintln!("Warning: --seed is ignored by workers, the coordinator's seed is used");
+            }
+            let settings = selfplay::SelfplaySettings {
+                hash_size,
+                level: Level {
+                    mid_depth,
+                    end_depth,
+                },
+                selectivity: Selectivity::from_u8(selectivity),
+                threads: threads as usize,
+                seed: None,
+                noise: selfplay::MoveNoise {
+                    plies: noise_plies,
+                    temperature,
+                    epsilon,
+                },
+                policy: false,
+                time_control: None,
+                opponent: None,
+                deterministic,
+            };
+            distributed::work(&server, &settings).expect("Failed to execute worker");
+        }
         SubCommands::Opening { depth } => {
             opening::generate(depth);
         }
@@ -230,24 +758,34 @@ fn main() {
             input,
             output,
             endgame,
+            report,
+            rust_output,
+            fit_only,
         } => {
-            if endgame {
-                probcut::execute_endgame(&input, &output)
-                    .expect("Failed to execute probcut endgame");
-            } else {
-                probcut::execute(&input, &output).expect("Failed to execute probcut");
+            if let Some(input) = input.filter(|_| !fit_only) {
+                if endgame {
+                    probcut::execute_endgame(&input, &output)
+                        .expect("Failed to execute probcut endgame");
+                } else {
+                    probcut::execute(&input, &output).expect("Failed to execute probcut");
+                }
+            }
+            if report.is_some() || rust_output.is_some() {
+                probcut_fit::execute(&output, endgame, report.as_deref(), rust_output.as_deref())
+                    .expect("Failed to fit probcut parameters");
             }
         }
         SubCommands::Shuffle {
             input_dir,
             output_dir,
             pattern,
-            files_per_chunk,
             num_output_files,
+            memory_mb,
             min_ply,
             max_score_diff,
             drop_random,
             keep_above_ply,
+            resume,
         } => {
             let filter = FilterConfig {
                 min_ply,
@@ -259,12 +797,74 @@ fn main() {
                 &input_dir,
                 &output_dir,
                 &pattern,
-                files_per_chunk,
                 num_output_files,
+                memory_mb,
+                seed.unwrap_or(DEFAULT_SEED),
                 filter,
+                resume,
             )
             .unwrap();
         }
+        SubCommands::Filter {
+            input_dir,
+            output_dir,
+            pattern,
+            min_ply,
+            max_ply,
+            max_score,
+            max_game_score,
+            dedup,
+            sample_ratio,
+        } => {
+            let criteria = FilterCriteria {
+                min_ply,
+                max_ply,
+                max_score,
+                max_game_score,
+                dedup,
+                sample_ratio,
+                seed: seed.unwrap_or(DEFAULT_SEED),
+            };
+            filter::execute(&input_dir, &output_dir, &pattern, criteria)
+                .expect("Failed to execute filter");
+        }
+        SubCommands::Augment {
+            input_dir,
+            output_dir,
+            pattern,
+            symmetries,
+            dedup,
+        } => {
+            augment::execute(&input_dir, &output_dir, &pattern, &symmetries, dedup)
+                .expect("Failed to execute augment");
+        }
+        SubCommands::ImportWthor {
+            input,
+            output_dir,
+            prefix,
+            games_per_file,
+            level,
+            selectivity,
+            hash_size,
+            threads,
+        } => {
+            let rescorer = level.map(|level| {
+                rescore::Rescorer::new(
+                    hash_size,
+                    get_level(level as usize),
+                    Selectivity::from_u8(selectivity),
+                    threads as usize,
+                )
+            });
+            import_wthor::execute(
+                &input,
+                &output_dir,
+                &prefix,
+                games_per_file,
+                rescorer.as_ref(),
+            )
+            .expect("Failed to execute import-wthor");
+        }
         SubCommands::ScoreOpenings {
             depth,
             hash_size,
@@ -286,6 +886,99 @@ fn main() {
             )
             .expect("Failed to execute score-openings");
         }
+        SubCommands::BalancedOpenings {
+            depth,
+            max_score,
+            hash_size,
+            mid_depth,
+            end_depth,
+            selectivity,
+            output,
+        } => {
+            let level = Level {
+                mid_depth,
+                end_depth,
+            };
+            balanced_openings::execute(
+                depth,
+                max_score,
+                hash_size,
+                level,
+                Selectivity::from_u8(selectivity),
+                &output,
+            )
+            .expect("Failed to execute balanced-openings");
+        }
+        SubCommands::Pack {
+            input_dir,
+            output_dir,
+            pattern,
+        } => {
+            binpack::pack(&input_dir, &output_dir, &pattern).expect("Failed to execute pack");
+        }
+        SubCommands::Unpack {
+            input_dir,
+            output_dir,
+            pattern,
+        } => {
+            binpack::unpack(&input_dir, &output_dir, &pattern).expect("Failed to execute unpack");
+        }
+        SubCommands::Export {
+            input_dir,
+            output_dir,
+            pattern,
+            features,
+            resume,
+        } => {
+            export::execute(&input_dir, &output_dir, &pattern, features, resume)
+                .expect("Failed to execute export");
+        }
+        SubCommands::Rescore {
+            input,
+            output,
+            pattern,
+            level,
+            selectivity,
+            hash_size,
+            threads,
+        } => {
+            rescore::execute(
+                &input,
+                &output,
+                &pattern,
+                hash_size,
+                get_level(level as usize),
+                Selectivity::from_u8(selectivity),
+                threads as usize,
+            )
+            .expect("Failed to execute rescore");
+        }
+        SubCommands::Solve {
+            input,
+            output,
+            pattern,
+            min_empties,
+            max_empties,
+            sample_ratio,
+            hash_size,
+            threads,
+        } => {
+            let selection = solve::SolveSelection {
+                min_empties,
+                max_empties,
+                sample_ratio,
+                seed: seed.unwrap_or(DEFAULT_SEED),
+            };
+            solve::execute(
+                &input,
+                &output,
+                &pattern,
+                &selection,
+                hash_size,
+                threads as usize,
+            )
+            .expect("Failed to execute solve");
+        }
         SubCommands::OverwriteScores {
             source,
             target_dir,
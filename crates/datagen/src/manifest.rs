@@ -0,0 +1,131 @@
+//! Checkpoint manifests of resumable subcommands.
+//!
+//! A manifest is a small text file of `key=value` lines stored next to the
+//! output of a job. It records the parameters the job was started with and how
+//! far it got, so that an interrupted job can continue where it stopped. The
+//! file is rewritten via a `.tmp` sibling that is synced and renamed, so a job
+//! killed while saving leaves the previous checkpoint intact.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub(crate) struct Manifest {
+    path: PathBuf,
+    entries: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// Creates an empty manifest to be saved at `path`.
+    pub(crate) fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the manifest at `path`, or returns `None` if there is none.
+    pub(crate) fn load(path: &Path) -> io::Result<Option<Self>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut entries = BTreeMap::new();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid manifest line in {}: {line}", path.display()),
+                )
+            })?;
+            entries.insert(key.to_owned(), value.to_owned());
+        }
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            entries,
+        }))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Parses the value of `key`, failing if it is missing or invalid.
+    pub(crate) fn get<T: FromStr>(&self, key: &str) -> io::Result<T> {
+        self.entries
+            .get(key)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Missing or invalid '{key}' in {}", self.path.display()),
+                )
+            })
+    }
+
+    pub(crate) fn set(&mut self, key: &str, value: impl Display) {
+        self.entries.insert(key.to_owned(), value.to_string());
+    }
+
+    /// Fails unless the manifest records `value` for `key`, so that a job is
+    /// not resumed with parameters other than those it was started with.
+    pub(crate) fn expect(&self, key: &str, value: impl Display) -> io::Result<()> {
+        let value = value.to_string();
+        match self.entries.get(key) {
+            Some(recorded) if *recorded == value => Ok(()),
+            recorded => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Cannot resume: {} records {key}={}, but this run uses {value}",
+                    self.path.display(),
+                    recorded.map_or("<missing>", String::as_str)
+                ),
+            )),
+        }
+    }
+
+    /// Atomically replaces the manifest file with the current entries.
+    pub(crate) fn save(&self) -> io::Result<()> {
+        let mut text = String::new();
+        for (key, value) in &self.entries {
+            text.push_str(key);
+            text.push('=');
+            text.push_str(value);
+            text.push('\n');
+        }
+
+        let temp_path = self.path.with_extension("manifest.tmp");
+        {
+            let mut file = File::create(&temp_path)?;
+            file.write_all(text.as_bytes())?;
+            file.sync_all()?;
+        }
+        fs::rename(&temp_path, &self.path)
+    }
+}
+
+/// Truncates `path` to `len` bytes, discarding output written after the last
+/// checkpoint. A missing file is only accepted when `len` is zero.
+pub(crate) fn truncate_to(path: &Path, len: u64) -> io::Result<()> {
+    match fs::OpenOptions::new().write(true).open(path) {
+        Ok(file) => {
+            if file.metadata()?.len() < len {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} is shorter than its checkpoint of {len} bytes",
+                        path.display()
+                    ),
+                ));
+            }
+            file.set_len(len)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound && len == 0 => Ok(()),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,156 @@
+//! External opponent engines for self-play.
+//!
+//! An opponent is an engine process speaking GTP or the Edax protocol, driven
+//! through the match runner's [`Engine`] interface. It plays one side of a
+//! share of the self-play games, so that the data also covers positions the
+//! engine's own search avoids. The opponent is told every move of such a
+//! game, including the opening and passes, and is asked with `genmove` for
+//! the moves of its side. Positions where it moves are still searched for
+//! their score, so the records of both sides carry the engine's labels.
+
+use match_runner::config::split_command;
+use match_runner::engine::{self, Engine, EngineProtocol};
+use reversi_core::board::Board;
+use reversi_core::disc::Disc;
+use reversi_core::square::Square;
+use std::io;
+use std::time::Duration;
+
+/// Command line and share of games of the opponent engine.
+pub struct OpponentSettings {
+    /// Engine command line, program followed by its arguments
+    pub command: String,
+    pub protocol: EngineProtocol,
+    /// Engine commands sent after startup, e.g. to set its level
+    pub init_commands: Vec<String>,
+    /// Probability of playing a game against the opponent
+    pub rate: f32,
+}
+
+impl OpponentSettings {
+    /// Starts an opponent engine process.
+    pub(crate) fn start(&self) -> io::Result<Opponent> {
+        let (executable, args) = split_command(&self.command);
+        // Run in the current directory, so that the command line behaves as in a shell
+        let mut engine = engine::start(self.protocol, &executable, &args, Some(".".into()))
+            .map_err(|e| io::Error::other(format!("Failed to start opponent: {e}")))?;
+        engine
+            .initialize(&self.init_commands)
+            .map_err(io::Error::other)?;
+        Ok(Opponent { engine })
+    }
+}
+
+/// A running opponent engine.
+pub(crate) struct Opponent {
+    engine: Box<dyn Engine>,
+}
+
+impl Opponent {
+    pub(crate) fn name(&self) -> String {
+        self.engine.name()
+    }
+
+    /// Resets the engine to the initial position.
+    pub(crate) fn new_game(&mut self) -> io::Result<()> {
+        self.engine.clear_board().map_err(io::Error::other)
+    }
+
+    /// Plays the move of `side` on the engine's board, where `None` is a pass.
+    pub(crate) fn play(&mut self, side: Disc, sq: Option<Square>) -> io::Result<()> {
+        let mv = sq.map_or_else(|| "pass".to_string(), |sq| sq.to_string());
+        self.engine.play(color(side), &mv).map_err(io::Error::other)
+    }
+
+    /// Asks the engine for the move of `side` on `board`, which must have a
+    /// legal move. The engine plays the move on its own board.
+    pub(crate) fn genmove(&mut self, side: Disc, board: &Board) -> io::Result<Square> {
+        let mv = self
+            .engine
+            .genmove(color(side), Duration::ZERO)
+            .map_err(io::Error::other)?;
+        match mv.parse::<Square>() {
+            Ok(sq) if board.is_legal_move(sq) => Ok(sq),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} played the illegal move '{mv}'", self.engine.name()),
+            )),
+        }
+    }
+
+    /// Kills the engine process and starts it again.
+    pub(crate) fn restart(&mut self) -> io::Result<()> {
+        self.engine.restart().map_err(io::Error::other)
+    }
+}
+
+/// The opponent's side of one game.
+///
+/// When the opponent fails, the rest of the game is played by the search and
+/// the engine is restarted for later games, or dropped if it cannot be.
+pub(crate) struct OpponentGame<'a> {
+    opponent: &'a mut Option<Opponent>,
+    side: Option<Disc>,
+}
+
+impl<'a> OpponentGame<'a> {
+    /// Starts a game in which `opponent` plays `side`, or none if either is `None`.
+    pub(crate) fn new(opponent: &'a mut Option<Opponent>, side: Option<Disc>) -> Self {
+        let mut game = Self {
+            opponent,
+            side: None,
+        };
+        if let Some(side) = side
+            && let Some(engine) = game.opponent.as_mut()
+        {
+            match engine.new_game() {
+                Ok(()) => game.side = Some(side),
+                Err(e) => game.fail(e),
+            }
+        }
+        game
+    }
+
+    /// Side played by the opponent, if it still takes part in the game.
+    pub(crate) fn side(&self) -> Option<Disc> {
+        self.side
+    }
+
+    /// Tells the opponent about a move it did not choose itself.
+    pub(crate) fn tell(&mut self, mover: Disc, sq: Option<Square>) {
+        if self.side.is_some()
+            && let Some(engine) = self.opponent.as_mut()
+            && let Err(e) = engine.play(mover, sq)
+        {
+            self.fail(e);
+        }
+    }
+
+    /// Returns the opponent's move if it plays `mover` on `board`.
+    pub(crate) fn genmove(&mut self, mover: Disc, board: &Board) -> Option<Square> {
+        if self.side != Some(mover) {
+            return None;
+        }
+        let result = self.opponent.as_mut()?.genmove(mover, board);
+        result.map_err(|e| self.fail(e)).ok()
+    }
+
+    fn fail(&mut self, error: io::Error) {
+        self.side = None;
+        eprintln!("Warning: opponent failed, playing the rest of the game with search: {error}");
+        if let Some(engine) = self.opponent.as_mut()
+            && let Err(e) = engine.restart()
+        {
+            eprintln!("Warning: failed to restart the opponent, playing without it: {e}");
+            *self.opponent = None;
+        }
+    }
+}
+
+fn color(side: Disc) -> &'static str {
+    if side == Disc::Black {
+        "black"
+    } else {
+        "white"
+    }
+}
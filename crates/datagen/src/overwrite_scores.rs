@@ -1,7 +1,7 @@
 //! Overwrite-scores module.
 //!
 //! Reads a source binary record file, builds a map from canonical board
-//! ([`Board::unique`]) to `(score, game_score, is_random)`, then scans every
+//! ([`Board::unique`]) to `(score, game_score, move_kind)`, then scans every
 //! file matching a glob pattern in a target directory and overwrites those
 //! three fields of each record whose canonical board appears in the source.
 
@@ -17,7 +17,8 @@ use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::record::{
-    GAME_SCORE_OFFSET, IS_RANDOM_OFFSET, RECORD_SIZE, SCORE_OFFSET, read_records_from_file,
+    GAME_SCORE_OFFSET, IS_RANDOM_OFFSET, MoveKind, RECORD_SIZE, SCORE_OFFSET,
+    read_records_from_file,
 };
 
 const RECORD_SIZE_USIZE: usize = RECORD_SIZE as usize;
@@ -26,10 +27,10 @@ const RECORD_SIZE_USIZE: usize = RECORD_SIZE as usize;
 struct Patch {
     score: Scoref,
     game_score: i8,
-    is_random: bool,
+    move_kind: MoveKind,
 }
 
-/// Overwrites the `score`, `game_score`, and `is_random` fields of records in
+/// Overwrites the `score`, `game_score`, and `move_kind` fields of records in
 /// `target_dir` whose canonical board matches a record in `source`.
 pub fn execute(source: &str, target_dir: &str, pattern: &str) -> anyhow::Result<()> {
     let source_path = Path::new(source);
@@ -45,7 +46,7 @@ pub fn execute(source: &str, target_dir: &str, pattern: &str) -> anyhow::Result<
         let patch = Patch {
             score: r.score,
             game_score: r.game_score,
-            is_random: r.is_random,
+            move_kind: r.move_kind,
         };
         if score_map.insert(r.board.unique(), patch).is_some() {
             duplicates += 1;
@@ -148,7 +149,7 @@ fn update_file(path: &Path, score_map: &HashMap<Board, Patch>) -> io::Result<(u6
             let score_bytes = patch.score.to_le_bytes();
             bytes[offset + SCORE_OFFSET..offset + SCORE_OFFSET + 4].copy_from_slice(&score_bytes);
             bytes[offset + GAME_SCORE_OFFSET] = patch.game_score as u8;
-            bytes[offset + IS_RANDOM_OFFSET] = u8::from(patch.is_random);
+            bytes[offset + IS_RANDOM_OFFSET] = patch.move_kind as u8;
             updated += 1;
         }
     }
@@ -0,0 +1,58 @@
+//! Policy target files.
+//!
+//! Self-play run with policy targets writes a `.policy` file next to each
+//! `.bin` file. Entry `i` of the policy file belongs to record `i` of the
+//! binary file and holds the multi-PV search score of every root move, so a
+//! policy head can be trained on the softmax of the scores:
+//!
+//! ```text
+//! entry := score i16 * 64
+//! ```
+//!
+//! Scores are little-endian, indexed by square (A1 = 0 .. H8 = 63), from the
+//! side to move's perspective and in units of 1/[`SCORE_SCALE`] disc. Squares
+//! that are not legal moves hold [`NO_SCORE`].
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use reversi_core::search::search_result::SearchResult;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::binpack::SCORE_SCALE;
+
+/// Size of each policy entry in bytes
+pub const POLICY_ENTRY_SIZE: u64 = 128;
+
+/// Score of squares that are not legal moves
+pub const NO_SCORE: i16 = i16::MIN;
+
+/// Root move scores of a position, indexed by square
+pub type MoveScores = [i16; 64];
+
+/// Returns the policy file belonging to the binary file `record_path`.
+pub fn policy_path(record_path: &Path) -> PathBuf {
+    record_path.with_extension("policy")
+}
+
+/// Collects the root move scores of a multi-PV search.
+pub fn move_scores(result: &SearchResult) -> MoveScores {
+    let mut scores = [NO_SCORE; 64];
+    for pv_move in result.pv_moves() {
+        // Saturates far beyond the score range, so no sentinel is produced
+        scores[pv_move.sq.index()] = (pv_move.score * SCORE_SCALE).round() as i16;
+    }
+    scores
+}
+
+/// Appends policy entries to a file with a single write.
+pub fn write_move_scores_to_file(path: &Path, entries: &[MoveScores]) -> io::Result<()> {
+    let mut buffer = Vec::with_capacity(entries.len() * POLICY_ENTRY_SIZE as usize);
+    for scores in entries {
+        for &score in scores {
+            buffer.write_i16::<LittleEndian>(score)?;
+        }
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(&buffer)
+}
@@ -0,0 +1,466 @@
+//! ProbCut parameter fitting.
+//!
+//! Fits the model of `reversi_core::probcut` to the samples written by the
+//! `probcut` command:
+//!
+//! ```text
+//! mean  = a + b·shallow + c·deep
+//! sigma = exp(a' + b'·shallow + c'·deep)
+//! ```
+//!
+//! where `mean` and `sigma` describe `deep_score - shallow_score`. The mean is
+//! fitted by least squares over all samples, and the sigma by least squares of
+//! the log standard deviation of each depth pair, weighted by its sample count.
+//! Midgame parameters are fitted per ply, endgame parameters over all plies.
+//!
+//! The report has one row per ply and depth pair with the classic ProbCut
+//! regression `deep_score = slope * shallow_score + intercept` and its residual
+//! sigma, the observed mean and standard deviation of the difference, and the
+//! fitted model's prediction of both, so residuals can be plotted directly.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use reversi_core::types::Depth;
+
+/// Log sigma of plies without enough samples to fit, ln(1e-8) as for ply 0
+/// of the tables
+const DEGENERATE_STD_INTERCEPT: f64 = -18.420680743952367;
+
+/// Number of plies of the midgame parameter table
+const NUM_PLY: usize = 60;
+
+/// Samples of one ply and depth pair.
+#[derive(Default, Clone)]
+struct PairStats {
+    samples: u64,
+    sum_shallow: f64,
+    sum_deep: f64,
+    sum_shallow_sq: f64,
+    sum_shallow_deep: f64,
+    sum_deep_sq: f64,
+}
+
+impl PairStats {
+    fn add(&mut self, shallow: f64, deep: f64) {
+        self.samples += 1;
+        self.sum_shallow += shallow;
+        self.sum_deep += deep;
+        self.sum_shallow_sq += shallow * shallow;
+        self.sum_shallow_deep += shallow * deep;
+        self.sum_deep_sq += deep * deep;
+    }
+
+    fn merge(&mut self, other: &PairStats) {
+        self.samples += other.samples;
+        self.sum_shallow += other.sum_shallow;
+        self.sum_deep += other.sum_deep;
+        self.sum_shallow_sq += other.sum_shallow_sq;
+        self.sum_shallow_deep += other.sum_shallow_deep;
+        self.sum_deep_sq += other.sum_deep_sq;
+    }
+
+    fn diff_mean(&self) -> f64 {
+        (self.sum_deep - self.sum_shallow) / self.samples as f64
+    }
+
+    fn diff_std(&self) -> f64 {
+        let n = self.samples as f64;
+        let mean_sq = (self.sum_deep_sq - 2.0 * self.sum_shallow_deep + self.sum_shallow_sq) / n;
+        (mean_sq - self.diff_mean().powi(2)).max(0.0).sqrt()
+    }
+
+    /// Returns the slope, intercept and residual sigma of the regression of
+    /// the deep score on the shallow score, if the shallow scores vary.
+    fn regression(&self) -> Option<(f64, f64, f64)> {
+        let n = self.samples as f64;
+        let var_shallow = self.sum_shallow_sq - self.sum_shallow * self.sum_shallow / n;
+        if self.samples < 2 || var_shallow <= f64::EPSILON * self.sum_shallow_sq.max(1.0) {
+            return None;
+        }
+        let cov = self.sum_shallow_deep - self.sum_shallow * self.sum_deep / n;
+        let var_deep = self.sum_deep_sq - self.sum_deep * self.sum_deep / n;
+        let slope = cov / var_shallow;
+        let intercept = (self.sum_deep - slope * self.sum_shallow) / n;
+        let sigma = ((var_deep - slope * cov) / n).max(0.0).sqrt();
+        Some((slope, intercept, sigma))
+    }
+}
+
+/// Samples by ply and `(shallow_depth, deep_depth)`.
+type Samples = BTreeMap<usize, BTreeMap<(Depth, Depth), PairStats>>;
+
+/// Fitted parameters, with coefficients for `[1, shallow, deep]`.
+struct Params {
+    mean: [f64; 3],
+    std: [f64; 3],
+}
+
+impl Params {
+    /// Parameters of a ply without data: no correction and a negligible sigma.
+    const DEGENERATE: Params = Params {
+        mean: [0.0; 3],
+        std: [DEGENERATE_STD_INTERCEPT, 0.0, 0.0],
+    };
+
+    fn mean(&self, shallow: f64, deep: f64) -> f64 {
+        self.mean[0] + self.mean[1] * shallow + self.mean[2] * deep
+    }
+
+    fn sigma(&self, shallow: f64, deep: f64) -> f64 {
+        (self.std[0] + self.std[1] * shallow + self.std[2] * deep).exp()
+    }
+
+    /// Fits the model to the depth pairs of `pairs`, or returns `None` if they
+    /// do not determine it.
+    fn fit<'a>(
+        pairs: impl Iterator<Item = (&'a (Depth, Depth), &'a PairStats)> + Clone,
+    ) -> Option<Self> {
+        let features = |&(shallow, deep): &(Depth, Depth)| [1.0, shallow as f64, deep as f64];
+        let mean = weighted_least_squares(
+            pairs
+                .clone()
+                .map(|(depths, stats)| (features(depths), stats.diff_mean(), stats.samples)),
+        )?;
+        let std = weighted_least_squares(
+            pairs
+                .filter(|(_, stats)| stats.samples >= 2 && stats.diff_std() > 0.0)
+                .map(|(depths, stats)| (features(depths), stats.diff_std().ln(), stats.samples)),
+        )?;
+        Some(Params { mean, std })
+    }
+}
+
+/// Fits the samples CSV written by the `probcut` command and writes the report
+/// and the Rust source of the parameters.
+///
+/// # Arguments
+///
+/// * `samples_path` - CSV file of samples
+/// * `endgame` - Whether the samples are endgame samples, fitted over all plies
+/// * `report_path` - Output CSV file of the per-pair report
+/// * `rust_path` - Output Rust source file with the parameter table
+pub fn execute(
+    samples_path: &str,
+    endgame: bool,
+    report_path: Option<&str>,
+    rust_path: Option<&str>,
+) -> io::Result<()> {
+    let samples = read_samples(Path::new(samples_path))?;
+    let total_samples: u64 = samples
+        .values()
+        .flat_map(|pairs| pairs.values())
+        .map(|stats| stats.samples)
+        .sum();
+    println!(
+        "Fitting {total_samples} samples of {} plies from {samples_path}",
+        samples.len()
+    );
+
+    // The fitted parameters of each ply, or of all plies for the endgame
+    let params: Vec<Params> = if endgame {
+        let mut pairs: BTreeMap<(Depth, Depth), PairStats> = BTreeMap::new();
+        for (&depths, stats) in samples.values().flat_map(|pairs| pairs.iter()) {
+            pairs.entry(depths).or_default().merge(stats);
+        }
+        vec![Params::fit(pairs.iter()).unwrap_or_else(|| {
+            eprintln!("Warning: the samples do not determine the endgame parameters");
+            Params::DEGENERATE
+        })]
+    } else {
+        (0..NUM_PLY)
+            .map(|ply| {
+                samples
+                    .get(&ply)
+                    .and_then(|pairs| Params::fit(pairs.iter()))
+                    .unwrap_or_else(|| {
+                        if samples.contains_key(&ply) {
+                            eprintln!("Warning: the samples do not determine ply {ply}");
+                        }
+                        Params::DEGENERATE
+                    })
+            })
+            .collect()
+    };
+    let params_of = |ply: usize| &params[if endgame { 0 } else { ply }];
+
+    if let Some(path) = report_path {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(
+            writer,
+            "ply,shallow_depth,deep_depth,samples,slope,intercept,sigma,diff_mean,diff_std,model_mean,model_sigma"
+        )?;
+        for (&ply, pairs) in &samples {
+            let params = params_of(ply);
+            for (&(shallow, deep), stats) in pairs {
+                let regression = stats.regression().map_or_else(
+                    || ",,".to_string(),
+                    |(slope, intercept, sigma)| format!("{slope},{intercept},{sigma}"),
+                );
+                writeln!(
+                    writer,
+                    "{ply},{shallow},{deep},{},{regression},{},{},{},{}",
+                    stats.samples,
+                    stats.diff_mean(),
+                    stats.diff_std(),
+                    params.mean(shallow as f64, deep as f64),
+                    params.sigma(shallow as f64, deep as f64)
+                )?;
+            }
+        }
+        writer.flush()?;
+        println!("Report written to {path}");
+    }
+
+    if let Some(path) = rust_path {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(
+            writer,
+            "// Generated by `datagen probcut` from {total_samples} samples of {samples_path}."
+        )?;
+        writeln!(writer)?;
+        if endgame {
+            writeln!(writer, "/// Statistical parameters for endgame ProbCut.")?;
+            writeln!(writer, "#[rustfmt::skip]")?;
+            write!(writer, "const PROBCUT_ENDGAME_PARAMS: ProbcutParams = ")?;
+            write_params(&mut writer, &params[0], "")?;
+            writeln!(writer, ";")?;
+        } else {
+            writeln!(
+                writer,
+                "/// Statistical parameters for midgame ProbCut indexed by ply."
+            )?;
+            writeln!(writer, "#[rustfmt::skip]")?;
+            writeln!(
+                writer,
+                "const PROBCUT_PARAMS: [ProbcutParams; {NUM_PLY}] = ["
+            )?;
+            for params in &params {
+                write!(writer, "    ")?;
+                write_params(&mut writer, params, "    ")?;
+                writeln!(writer, ",")?;
+            }
+            writeln!(writer, "];")?;
+        }
+        writer.flush()?;
+        println!("Rust source written to {path}");
+    }
+    Ok(())
+}
+
+/// Reads the samples CSV, keyed by ply and depth pair.
+fn read_samples(path: &Path) -> io::Result<Samples> {
+    let invalid = |line_no: usize, message: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}:{}: {message}", path.display(), line_no + 1),
+        )
+    };
+
+    let reader = BufReader::new(File::open(path)?);
+    let mut samples = Samples::new();
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("ply") {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        let [
+            ply,
+            shallow_depth,
+            shallow_score,
+            deep_depth,
+            deep_score,
+            ..,
+        ] = fields[..]
+        else {
+            return Err(invalid(line_no, "expected at least 5 fields"));
+        };
+        let ply: usize = ply.parse().map_err(|_| invalid(line_no, "invalid ply"))?;
+        let depths = (
+            shallow_depth
+                .parse()
+                .map_err(|_| invalid(line_no, "invalid shallow depth"))?,
+            deep_depth
+                .parse()
+                .map_err(|_| invalid(line_no, "invalid deep depth"))?,
+        );
+        let shallow_score: f64 = shallow_score
+            .parse()
+            .map_err(|_| invalid(line_no, "invalid shallow score"))?;
+        let deep_score: f64 = deep_score
+            .parse()
+            .map_err(|_| invalid(line_no, "invalid deep score"))?;
+        if ply >= NUM_PLY {
+            return Err(invalid(line_no, "ply out of range"));
+        }
+        samples
+            .entry(ply)
+            .or_default()
+            .entry(depths)
+            .or_default()
+            .add(shallow_score, deep_score);
+    }
+    Ok(samples)
+}
+
+/// Writes `params` as a `ProbcutParams` literal, indenting its fields by
+/// `indent` on top of one level.
+fn write_params(writer: &mut impl Write, params: &Params, indent: &str) -> io::Result<()> {
+    writeln!(writer, "ProbcutParams {{")?;
+    let fields = [
+        ("mean_intercept", params.mean[0]),
+        ("mean_coef_shallow", params.mean[1]),
+        ("mean_coef_deep", params.mean[2]),
+        ("std_intercept", params.std[0]),
+        ("std_coef_shallow", params.std[1]),
+        ("std_coef_deep", params.std[2]),
+    ];
+    for (name, value) in fields {
+        writeln!(writer, "{indent}    {name}: {value:.10},")?;
+    }
+    write!(writer, "{indent}}}")
+}
+
+/// Solves the weighted least squares problem of `(features, target, weight)`
+/// points, or returns `None` if the features do not determine a solution.
+fn weighted_least_squares(points: impl Iterator<Item = ([f64; 3], f64, u64)>) -> Option<[f64; 3]> {
+    let mut a = [[0.0f64; 3]; 3];
+    let mut b = [0.0f64; 3];
+    for (x, y, weight) in points {
+        let w = weight as f64;
+        for i in 0..3 {
+            for j in 0..3 {
+                a[i][j] += w * x[i] * x[j];
+            }
+            b[i] += w * x[i] * y;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting on the normal equations
+    let scale = a.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= scale * 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..3 {
+            let factor = a[row][col] / a[col][col];
+            let pivot_row = a[col];
+            for (v, p) in a[row].iter_mut().zip(pivot_row).skip(col) {
+                *v -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0f64; 3];
+    for row in (0..3).rev() {
+        let rest: f64 = (row + 1..3).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Samples whose deep score is `slope * shallow + intercept`, off by
+    /// `+sigma` and `-sigma` in turn, so the residuals have exactly `sigma`
+    /// as their standard deviation.
+    fn linear_samples(slope: f64, intercept: f64, sigma: f64) -> PairStats {
+        let mut stats = PairStats::default();
+        for shallow in -20..=20 {
+            let shallow = f64::from(shallow);
+            for noise in [sigma, -sigma] {
+                stats.add(shallow, slope * shallow + intercept + noise);
+            }
+        }
+        stats
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn regression_recovers_line_and_sigma() {
+        let (slope, intercept, sigma) = linear_samples(1.1, -0.75, 2.5).regression().unwrap();
+        assert_close(slope, 1.1);
+        assert_close(intercept, -0.75);
+        assert_close(sigma, 2.5);
+    }
+
+    #[test]
+    fn regression_needs_varying_shallow_scores() {
+        let mut stats = PairStats::default();
+        assert!(stats.regression().is_none());
+        stats.add(3.0, 4.0);
+        assert!(stats.regression().is_none());
+        stats.add(3.0, 6.0);
+        assert!(stats.regression().is_none());
+        stats.add(5.0, 6.0);
+        assert!(stats.regression().is_some());
+    }
+
+    #[test]
+    fn fit_recovers_model() {
+        let model = Params {
+            mean: [0.4, -0.05, 0.02],
+            std: [1.2, -0.08, 0.03],
+        };
+        let mut pairs = BTreeMap::new();
+        for shallow in 0..6 {
+            for deep in shallow + 2..shallow + 10 {
+                let (s, d) = (f64::from(shallow), f64::from(deep));
+                let (mean, sigma) = (model.mean(s, d), model.sigma(s, d));
+                let mut stats = PairStats::default();
+                for score in -8..8 {
+                    let score = f64::from(score);
+                    for noise in [sigma, -sigma] {
+                        stats.add(score, score + mean + noise);
+                    }
+                }
+                pairs.insert((shallow as Depth, deep as Depth), stats);
+            }
+        }
+
+        let fitted = Params::fit(pairs.iter()).unwrap();
+        for i in 0..3 {
+            assert_close(fitted.mean[i], model.mean[i]);
+            assert_close(fitted.std[i], model.std[i]);
+        }
+    }
+
+    #[test]
+    fn fit_needs_independent_depths() {
+        let stats = linear_samples(1.0, 0.5, 1.0);
+
+        // A single depth pair cannot separate the three coefficients
+        let single = BTreeMap::from([((2, 6), stats.clone())]);
+        assert!(Params::fit(single.iter()).is_none());
+
+        // Neither can pairs whose deep depth follows from the shallow one
+        let fixed_gap: BTreeMap<(Depth, Depth), PairStats> = (1..6)
+            .map(|shallow| ((shallow, shallow + 4), stats.clone()))
+            .collect();
+        assert!(Params::fit(fixed_gap.iter()).is_none());
+
+        // Pairs with a single sample each give the mean but no sigma
+        let mut one_sample = PairStats::default();
+        one_sample.add(0.0, 1.0);
+        let sparse: BTreeMap<(Depth, Depth), PairStats> = [(1, 3), (1, 5), (2, 4), (3, 7)]
+            .into_iter()
+            .map(|depths| (depths, one_sample.clone()))
+            .collect();
+        assert!(Params::fit(sparse.iter()).is_none());
+    }
+}
@@ -0,0 +1,256 @@
+//! Rescore module.
+//!
+//! Re-evaluates the positions of existing binary record files with a new
+//! search level, replacing the `score` of each record while keeping all other
+//! fields. Better labels can thus be produced without regenerating the games.
+//! Output files are written in input order, so an interrupted run resumes
+//! after the last record already written.
+
+use anyhow::bail;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use reversi_core::level::Level;
+use reversi_core::probcut::Selectivity;
+use reversi_core::search::options::SearchOptions;
+use reversi_core::search::{self, SearchRunOptions};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use crate::record::{
+    GameRecord, MoveKind, RECORD_SIZE, read_records_from_file, truncate_incomplete_record,
+    write_records_to_file,
+};
+
+/// Number of records handed to a worker at a time and appended at once
+const CHUNK_SIZE: usize = 256;
+
+/// Rescores the records of `input`, a file or a directory of files matching
+/// `pattern`, writing the files of the same name to `output`.
+///
+/// # Arguments
+///
+/// * `input` - Binary record file, or directory containing them
+/// * `output` - Output file, or directory when `input` is a directory
+/// * `pattern` - Glob pattern of the files in an input directory
+/// * `hash_size` - Transposition table size in MB of each worker's search
+/// * `level` - Search level of the new scores
+/// * `selectivity` - Search selectivity of the new scores
+/// * `threads` - Number of positions searched concurrently
+pub fn execute(
+    input: &str,
+    output: &str,
+    pattern: &str,
+    hash_size: usize,
+    level: Level,
+    selectivity: Selectivity,
+    threads: usize,
+) -> anyhow::Result<()> {
+    let files = file_pairs(Path::new(input), Path::new(output), pattern)?;
+    let rescorer = Rescorer::new(hash_size, level, selectivity, threads);
+
+    for (input_path, output_path) in &files {
+        let records = read_records_from_file(input_path)?;
+        rescorer.rescore_records(input_path, &records, output_path)?;
+    }
+    Ok(())
+}
+
+/// Pool of searches that rescores records into an output file.
+pub(crate) struct Rescorer {
+    options: SearchOptions,
+    level: Level,
+    selectivity: Selectivity,
+    threads: usize,
+    /// Whether the searches solve the positions exactly, so that their
+    /// results also label the game result and the move
+    solve: bool,
+}
+
+impl Rescorer {
+    /// Creates a rescorer searching `threads` positions concurrently, each
+    /// with a transposition table of `hash_size` MB.
+    pub(crate) fn new(
+        hash_size: usize,
+        level: Level,
+        selectivity: Selectivity,
+        threads: usize,
+    ) -> Self {
+        // Split the available cores between the workers' searches
+        let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+        let options = SearchOptions::new(hash_size).with_threads(Some((cpus / threads).max(1)));
+        Self {
+            options,
+            level,
+            selectivity,
+            threads,
+            solve: false,
+        }
+    }
+
+    /// Creates a rescorer solving the positions exactly. Besides the score, it
+    /// replaces the game result with the solved score and the move with the
+    /// solver's best move, which is the outcome and a move of perfect play.
+    pub(crate) fn solver(hash_size: usize, threads: usize) -> Self {
+        Self {
+            solve: true,
+            ..Self::new(hash_size, Level::perfect(), Selectivity::None, threads)
+        }
+    }
+
+    /// Writes `records` with new scores to `output_path`, resuming after the
+    /// records already written. `source` names the records in progress output.
+    pub(crate) fn rescore_records(
+        &self,
+        source: &Path,
+        records: &[GameRecord],
+        output_path: &Path,
+    ) -> anyhow::Result<()> {
+        let (options, level, selectivity, solve) =
+            (&self.options, self.level, self.selectivity, self.solve);
+
+        // Remove any trailing incomplete record left by a previous interrupted run
+        truncate_incomplete_record(output_path)?;
+        let done = match fs::metadata(output_path) {
+            Ok(metadata) => (metadata.len() / RECORD_SIZE) as usize,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        if done >= records.len() {
+            println!("{}: already done", source.display());
+            return Ok(());
+        }
+        if done > 0 {
+            println!(
+                "{}: resuming after {done} of {} records",
+                source.display(),
+                records.len()
+            );
+        } else {
+            println!("{}: {} records", source.display(), records.len());
+        }
+
+        let chunks: Vec<&[GameRecord]> = records[done..].chunks(CHUNK_SIZE).collect();
+        let threads = self.threads.clamp(1, chunks.len());
+
+        let pb = ProgressBar::with_draw_target(
+            Some((records.len() - done) as u64),
+            ProgressDrawTarget::stderr_with_hz(10),
+        );
+        pb.set_style(
+            ProgressStyle::with_template(
+                "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} ({per_sec}) ETA:{eta_precise}",
+            )
+            .map_err(io::Error::other)?
+            .progress_chars("#>-"),
+        );
+        pb.enable_steady_tick(Duration::from_millis(100));
+
+        let next_chunk = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        let (sender, receiver) = mpsc::channel::<(usize, Vec<GameRecord>)>();
+
+        let result = thread::scope(|scope| {
+            for _ in 0..threads {
+                let sender = sender.clone();
+                let (chunks, next_chunk, stop) = (&chunks, &next_chunk, &stop);
+                scope.spawn(move || {
+                    let mut search = search::Search::new(options);
+                    let run_options = SearchRunOptions::with_level(level, selectivity);
+
+                    while !stop.load(Ordering::Relaxed) {
+                        let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                        let Some(chunk) = chunks.get(index) else {
+                            break;
+                        };
+                        let rescored = chunk
+                            .iter()
+                            .map(|record| {
+                                let result = search.run(&record.board, &run_options);
+                                match (result.score(), result.best_move()) {
+                                    (Some(score), Some(sq)) if solve => GameRecord {
+                                        score,
+                                        game_score: score.round() as i8,
+                                        move_kind: MoveKind::Search,
+                                        sq,
+                                        ..record.clone()
+                                    },
+                                    (score, _) => GameRecord {
+                                        score: score.unwrap_or(record.score),
+                                        ..record.clone()
+                                    },
+                                }
+                            })
+                            .collect();
+                        if sender.send((index, rescored)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            // Append chunks in input order, holding back those that completed
+            // ahead of an earlier chunk
+            let mut pending: BTreeMap<usize, Vec<GameRecord>> = BTreeMap::new();
+            let mut next_to_write = 0;
+            for (index, rescored) in receiver {
+                pending.insert(index, rescored);
+                while let Some(rescored) = pending.remove(&next_to_write) {
+                    if let Err(e) = write_records_to_file(output_path, &rescored) {
+                        stop.store(true, Ordering::Relaxed);
+                        return Err(e);
+                    }
+                    pb.inc(rescored.len() as u64);
+                    next_to_write += 1;
+                }
+            }
+            Ok(())
+        });
+        pb.finish_and_clear();
+        Ok(result?)
+    }
+}
+
+/// Lists the input files with the output file each is rescored into.
+pub(crate) fn file_pairs(
+    input: &Path,
+    output: &Path,
+    pattern: &str,
+) -> anyhow::Result<Vec<(PathBuf, PathBuf)>> {
+    let pairs = if input.is_dir() {
+        let full_pattern = input.join(pattern).to_string_lossy().into_owned();
+        let paths = glob::glob(&full_pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid glob pattern '{}': {}", full_pattern, e))?;
+        fs::create_dir_all(output)?;
+        let mut pairs = Vec::new();
+        for path in paths.filter_map(Result::ok).filter(|p| p.is_file()) {
+            let file_name = path.file_name().unwrap_or_default().to_owned();
+            pairs.push((path, output.join(file_name)));
+        }
+        pairs.sort();
+        pairs
+    } else {
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        vec![(input.to_path_buf(), output.to_path_buf())]
+    };
+
+    for (input_path, output_path) in &pairs {
+        if output_path.exists() && fs::canonicalize(input_path)? == fs::canonicalize(output_path)? {
+            bail!(
+                "Output {} would overwrite its input; choose another output",
+                output_path.display()
+            );
+        }
+    }
+    if pairs.is_empty() {
+        bail!("No input files found in {}", input.display());
+    }
+    Ok(pairs)
+}
@@ -20,7 +20,7 @@ use std::io;
 use std::path::Path;
 use std::time::Duration;
 
-use crate::record::{GameRecord, read_records_from_file, write_records_to_file};
+use crate::record::{GameRecord, MoveKind, read_records_from_file, write_records_to_file};
 
 /// Enumerates all unique positions reachable within `depth` plies and scores each one.
 ///
@@ -99,7 +99,7 @@ pub fn execute(
             score,
             game_score: score.round() as i8,
             side_to_move,
-            is_random: false,
+            move_kind: MoveKind::Search,
             sq: result.best_move().unwrap_or(Square::A1),
         });
         new_count += 1;
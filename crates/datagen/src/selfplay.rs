@@ -4,25 +4,36 @@
 //! It supports both random game generation and generation from predefined opening
 //! sequences, with configurable search parameters.
 
-use rand::RngExt;
+use rand::rngs::SmallRng;
 use rand::seq::IteratorRandom;
+use rand::{RngExt, SeedableRng};
 use regex::Regex;
 use reversi_core::board::Board;
+use reversi_core::disc::Disc;
 use reversi_core::game_state::GameState;
 use reversi_core::level::Level;
 use reversi_core::probcut::Selectivity;
 use reversi_core::search::options::SearchOptions;
+use reversi_core::search::search_result::SearchResult;
+use reversi_core::search::time_control::TimeControlMode;
 use reversi_core::search::{self, SearchRunOptions};
 use reversi_core::square::Square;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
 use std::time::Instant;
 
+use crate::manifest::{self, Manifest};
 use crate::opening;
+use crate::opponent::{Opponent, OpponentGame, OpponentSettings};
+use crate::policy::{self, MoveScores, POLICY_ENTRY_SIZE};
 use crate::record::{
-    GameRecord, read_last_game_id, truncate_incomplete_record, write_records_to_file,
+    GameRecord, MoveKind, RECORD_SIZE, read_last_game_id, truncate_incomplete_record,
+    write_records_to_file,
 };
 
 /// Minimum number of random moves at the start of each game
@@ -37,6 +48,15 @@ const FILE_ID_DIGITS: usize = 5;
 /// Maximum size of the record cache
 const MAX_CACHE_SIZE: usize = 1_000_000;
 
+/// Searched opening positions, with their policy entry when recorded
+type RecordCache = HashMap<Board, (GameRecord, Option<Box<MoveScores>>)>;
+
+/// Records of a played game, and the policy entry of each record when recorded
+pub(crate) struct PlayedGame {
+    pub records: Vec<GameRecord>,
+    pub move_scores: Vec<MoveScores>,
+}
+
 /// Tracks file rotation state across games to avoid re-scanning the output directory.
 struct FileState {
     prefix: String,
@@ -44,10 +64,12 @@ struct FileState {
     games_per_file: u32,
     file_id: u32,
     game_id: u16,
+    /// Whether policy files are written next to the record files
+    policy: bool,
 }
 
 impl FileState {
-    fn new(prefix: &str, output_dir: &str, games_per_file: u32) -> io::Result<Self> {
+    fn new(prefix: &str, output_dir: &str, games_per_file: u32, policy: bool) -> io::Result<Self> {
         let escaped_prefix = regex::escape(prefix);
         let pattern = format!(r"^{escaped_prefix}_\d{{{FILE_ID_DIGITS}}}\.bin$");
         let re = Regex::new(&pattern).unwrap();
@@ -93,6 +115,7 @@ impl FileState {
             games_per_file,
             file_id,
             game_id,
+            policy,
         })
     }
 
@@ -131,9 +154,340 @@ impl FileState {
         Ok(total)
     }
 
-    fn write_records(&mut self, game_records: &[GameRecord]) -> io::Result<()> {
+    fn write_game(&mut self, game: &PlayedGame) -> io::Result<()> {
         let file_path = self.file_path(self.file_id);
-        write_records_to_file(&file_path, game_records)
+        if self.policy {
+            policy::write_move_scores_to_file(&policy::policy_path(&file_path), &game.move_scores)?;
+        }
+        write_records_to_file(&file_path, &game.records)
+    }
+
+    /// Restores the state of a checkpoint, discarding any games written
+    /// after it.
+    fn from_manifest(
+        prefix: &str,
+        output_dir: &str,
+        games_per_file: u32,
+        policy: bool,
+        manifest: &Manifest,
+    ) -> io::Result<Self> {
+        let state = Self {
+            prefix: prefix.to_owned(),
+            output_dir: output_dir.to_owned(),
+            games_per_file,
+            file_id: manifest.get("file_id")?,
+            game_id: manifest.get("game_id")?,
+            policy,
+        };
+        let file_path = state.file_path(state.file_id);
+        let file_len: u64 = manifest.get("file_len")?;
+        manifest::truncate_to(&file_path, file_len)?;
+        // A game written after rotating to the next file is not checkpointed yet
+        let mut orphans = vec![state.file_path(state.file_id + 1)];
+        if policy {
+            manifest::truncate_to(
+                &policy::policy_path(&file_path),
+                file_len / RECORD_SIZE * POLICY_ENTRY_SIZE,
+            )?;
+            orphans.push(policy::policy_path(&orphans[0]));
+        }
+        for orphan in orphans {
+            match fs::remove_file(orphan) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(state)
+    }
+
+    /// Records the output position in `manifest`.
+    fn checkpoint(&self, manifest: &mut Manifest) -> io::Result<()> {
+        let file_len = match fs::metadata(self.file_path(self.file_id)) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        manifest.set("file_id", self.file_id);
+        manifest.set("game_id", self.game_id);
+        manifest.set("file_len", file_len);
+        Ok(())
+    }
+}
+
+/// Progress of a self-play run, saved in its manifest after every game.
+///
+/// Game `i` draws its random choices from a generator seeded with `seed + i`,
+/// so the seed and the number of completed games are all the random state
+/// needed to continue a run.
+struct SelfplayRun {
+    manifest: Manifest,
+    file_state: FileState,
+    seed: u64,
+    games_completed: usize,
+}
+
+impl SelfplayRun {
+    /// Starts a run writing to `output_dir`, or resumes the one recorded in
+    /// its manifest.
+    ///
+    /// `source` identifies the games being played, so that a run is not
+    /// resumed with other games. Without a manifest, a resumed run skips as
+    /// many games as the existing output files contain.
+    fn open(
+        prefix: &str,
+        output_dir: &str,
+        games_per_file: u32,
+        settings: &SelfplaySettings,
+        resume: bool,
+        source: &str,
+    ) -> io::Result<Self> {
+        let (seed, policy) = (settings.seed, settings.policy);
+        let manifest_path = Path::new(output_dir).join(format!("{prefix}.manifest"));
+
+        if resume && let Some(mut manifest) = Manifest::load(&manifest_path)? {
+            manifest.expect("command", "selfplay")?;
+            manifest.expect("source", source)?;
+            manifest.expect("games_per_file", games_per_file)?;
+            manifest.expect("policy", policy)?;
+            if let Some(seed) = seed {
+                manifest.expect("seed", seed)?;
+            }
+            // Only a run that was deterministic throughout is reproducible
+            if settings.deterministic {
+                manifest.expect("deterministic", true)?;
+            } else {
+                manifest.set("deterministic", false);
+            }
+            let file_state =
+                FileState::from_manifest(prefix, output_dir, games_per_file, policy, &manifest)?;
+            let run = Self {
+                seed: manifest.get("seed")?,
+                games_completed: manifest.get("games_completed")?,
+                manifest,
+                file_state,
+            };
+            println!(
+                "Resuming after {} games from {}",
+                run.games_completed,
+                run.manifest.path().display()
+            );
+            return Ok(run);
+        }
+
+        let file_state = FileState::new(prefix, output_dir, games_per_file, policy)?;
+        let games_completed = if resume {
+            let total = file_state.total_games()?;
+            if total > 0 {
+                println!("No manifest found, resuming after the {total} games written");
+            }
+            total
+        } else {
+            0
+        };
+
+        let mut manifest = Manifest::new(&manifest_path);
+        manifest.set("command", "selfplay");
+        manifest.set("source", source);
+        manifest.set("games_per_file", games_per_file);
+        manifest.set("policy", policy);
+        manifest.set("deterministic", settings.deterministic);
+        let mut run = Self {
+            manifest,
+            file_state,
+            seed: seed.unwrap_or_else(rand::random),
+            games_completed,
+        };
+        run.manifest.set("seed", run.seed);
+        run.save()?;
+        Ok(run)
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        self.manifest.set("games_completed", self.games_completed);
+        self.file_state.checkpoint(&mut self.manifest)?;
+        self.manifest.save()
+    }
+}
+
+/// Search and worker pool settings shared by all self-play games.
+pub struct SelfplaySettings {
+    /// Transposition table size in MB of each worker's search
+    pub hash_size: usize,
+    pub level: Level,
+    pub selectivity: Selectivity,
+    /// Number of games played concurrently
+    pub threads: usize,
+    /// Base seed of the games' random number generators
+    pub seed: Option<u64>,
+    /// Randomization of the searched moves early in the game
+    pub noise: MoveNoise,
+    /// Whether to write the root move scores of every position to policy files
+    pub policy: bool,
+    /// Clock of the searched moves, replacing `level` for them
+    pub time_control: Option<TimeControl>,
+    /// External engine playing one side of a share of the games
+    pub opponent: Option<OpponentSettings>,
+    /// Whether each game only depends on the seed and its index, searching
+    /// with one thread and without the cache of opening positions
+    pub deterministic: bool,
+}
+
+/// Time control of both players, as in tournament games.
+///
+/// Each player's clock starts with `main_time_ms`. Under Fischer time control,
+/// `increment_ms` is added after every move. Under byoyomi, a player whose
+/// main time has run out gets `time_per_move_ms` for each move.
+#[derive(Clone, Copy)]
+pub enum TimeControl {
+    Fischer {
+        main_time_ms: u64,
+        increment_ms: u64,
+    },
+    Byoyomi {
+        main_time_ms: u64,
+        time_per_move_ms: u64,
+    },
+}
+
+/// Remaining time of a player below which the search is skipped
+const MIN_TIME_FOR_SEARCH_MS: u64 = 15;
+
+/// Remaining time of one player under a [`TimeControl`].
+struct Clock {
+    control: TimeControl,
+    main_time_ms: u64,
+    in_byoyomi: bool,
+}
+
+impl Clock {
+    fn new(control: TimeControl) -> Self {
+        let main_time_ms = match control {
+            TimeControl::Fischer { main_time_ms, .. }
+            | TimeControl::Byoyomi { main_time_ms, .. } => main_time_ms,
+        };
+        Self {
+            control,
+            main_time_ms,
+            in_byoyomi: main_time_ms == 0,
+        }
+    }
+
+    /// Returns the time control the search manages its time with.
+    fn mode(&self) -> TimeControlMode {
+        match self.control {
+            TimeControl::Fischer { increment_ms, .. } => TimeControlMode::Fischer {
+                main_time_ms: self.main_time_ms,
+                increment_ms,
+            },
+            TimeControl::Byoyomi {
+                time_per_move_ms, ..
+            } if self.in_byoyomi => TimeControlMode::Byoyomi { time_per_move_ms },
+            TimeControl::Byoyomi {
+                time_per_move_ms, ..
+            } => TimeControlMode::JapaneseByo {
+                main_time_ms: self.main_time_ms,
+                time_per_move_ms,
+            },
+        }
+    }
+
+    /// Returns the time available for the next move.
+    fn available_ms(&self) -> u64 {
+        match self.control {
+            TimeControl::Fischer { .. } => self.main_time_ms,
+            TimeControl::Byoyomi {
+                time_per_move_ms, ..
+            } => self.main_time_ms.max(time_per_move_ms),
+        }
+    }
+
+    /// Charges the time of a move, returning `false` if it overstepped the
+    /// time available. The clock then continues from zero main time.
+    fn charge(&mut self, elapsed_ms: u64) -> bool {
+        match self.control {
+            TimeControl::Fischer { increment_ms, .. } => {
+                let in_time = elapsed_ms <= self.main_time_ms;
+                self.main_time_ms = self.main_time_ms.saturating_sub(elapsed_ms) + increment_ms;
+                in_time
+            }
+            TimeControl::Byoyomi {
+                time_per_move_ms, ..
+            } => {
+                if self.in_byoyomi {
+                    return elapsed_ms <= time_per_move_ms;
+                }
+                if elapsed_ms <= self.main_time_ms {
+                    self.main_time_ms -= elapsed_ms;
+                    return true;
+                }
+                let overtime = elapsed_ms - self.main_time_ms;
+                self.main_time_ms = 0;
+                self.in_byoyomi = true;
+                overtime <= time_per_move_ms
+            }
+        }
+    }
+}
+
+/// Randomization of the moves played after the opening, for more diverse games.
+///
+/// Up to ply `plies`, a move is chosen uniformly at random with probability
+/// `epsilon`, and otherwise sampled from the softmax of the root move scores
+/// with `temperature`. Moves chosen this way are recorded with their
+/// [`MoveKind`], while the score stays the search score of the position.
+#[derive(Clone, Copy)]
+pub struct MoveNoise {
+    /// Number of plies from the start of the game that are randomized
+    pub plies: u8,
+    /// Softmax temperature in discs
+    pub temperature: Option<f32>,
+    /// Probability of playing a uniformly random move
+    pub epsilon: Option<f32>,
+}
+
+impl MoveNoise {
+    /// Whether a move at `ply` is randomized.
+    fn applies(&self, ply: u8) -> bool {
+        ply < self.plies && (self.temperature.is_some() || self.epsilon.is_some())
+    }
+
+    /// Chooses the move to play instead of the best move, if any.
+    ///
+    /// `result` must come from a multi-PV search when a temperature is set.
+    fn choose(
+        &self,
+        board: &Board,
+        result: &SearchResult,
+        rng: &mut SmallRng,
+    ) -> Option<(Square, MoveKind)> {
+        if let Some(epsilon) = self.epsilon
+            && rng.random_bool(f64::from(epsilon))
+        {
+            return Some((random_move(board, rng), MoveKind::Epsilon));
+        }
+
+        let temperature = f64::from(self.temperature?);
+        let moves = result.pv_moves();
+        if moves.is_empty() {
+            return None;
+        }
+        let best = moves
+            .iter()
+            .map(|m| f64::from(m.score))
+            .fold(f64::NEG_INFINITY, f64::max);
+        // Shift by the best score so the weights cannot overflow
+        let weights: Vec<f64> = moves
+            .iter()
+            .map(|m| ((f64::from(m.score) - best) / temperature).exp())
+            .collect();
+        let mut pick = rng.random_range(0.0..weights.iter().sum::<f64>());
+        for (m, weight) in moves.iter().zip(&weights) {
+            if pick < *weight {
+                return Some((m.sq, MoveKind::Temperature));
+            }
+            pick -= weight;
+        }
+        moves.last().map(|m| (m.sq, MoveKind::Temperature))
     }
 }
 
@@ -142,10 +496,9 @@ impl FileState {
 /// # Arguments
 ///
 /// * `num_games` - Number of games to generate
+/// * `resume` - Whether to continue the run recorded in the manifest
 /// * `games_per_file` - Maximum games per output file
-/// * `hash_size` - Transposition table size in MB
-/// * `level` - Search level configuration
-/// * `selectivity` - Search selectivity parameter
+/// * `settings` - Search and worker pool settings
 /// * `prefix` - Output file prefix
 /// * `output_dir` - Directory for output files
 ///
@@ -154,49 +507,33 @@ impl FileState {
 /// Returns `Ok(())` on success, or an error if file operations fail.
 pub fn execute(
     num_games: u32,
+    resume: bool,
     games_per_file: u32,
-    hash_size: usize,
-    level: Level,
-    selectivity: Selectivity,
+    settings: &SelfplaySettings,
     prefix: &str,
     output_dir: &str,
 ) -> io::Result<()> {
     fs::create_dir_all(output_dir)?;
 
-    let options = SearchOptions::new(hash_size);
-
-    let mut search = search::Search::new(&options);
-    let mut record_cache: HashMap<Board, GameRecord> = HashMap::new();
-    let mut file_state = FileState::new(prefix, output_dir, games_per_file)?;
+    let mut run = SelfplayRun::open(
+        prefix,
+        output_dir,
+        games_per_file,
+        settings,
+        resume,
+        &format!("random:{num_games}"),
+    )?;
 
-    for _ in 0..num_games {
-        if file_state.is_full() {
-            file_state.rotate();
-        }
-        let game_id = file_state.next_game_id();
-
-        // Generate random opening for this game
-        let mut rng = rand::rng();
-        let num_random = std::cmp::min(
-            rng.random_range(MIN_RANDOM_MOVES..MAX_RANDOM_MOVES),
-            rng.random_range(MIN_RANDOM_MOVES..MAX_RANDOM_MOVES),
-        );
-        let opening_sequence = generate_random_opening(num_random);
-
-        // Play the game using the common function
-        let game_records = play_game(
-            &opening_sequence,
-            &mut search,
-            level,
-            selectivity,
-            game_id,
-            &mut record_cache,
-        );
+    run_games(num_games as usize, settings, &mut run, random_opening)
+}
 
-        // Save the game records
-        file_state.write_records(&game_records)?;
-    }
-    Ok(())
+/// Generates the random opening of a game, biased towards shorter openings.
+pub(crate) fn random_opening(_index: usize, rng: &mut SmallRng) -> Vec<Square> {
+    let num_random = std::cmp::min(
+        rng.random_range(MIN_RANDOM_MOVES..MAX_RANDOM_MOVES),
+        rng.random_range(MIN_RANDOM_MOVES..MAX_RANDOM_MOVES),
+    );
+    generate_random_opening(num_random, rng)
 }
 
 /// Executes self-play using predefined opening sequences.
@@ -204,36 +541,25 @@ pub fn execute(
 /// # Arguments
 ///
 /// * `openings_path` - Path to file containing opening sequences
-/// * `resume` - Whether to resume from last processed opening
+/// * `resume` - Whether to continue the run recorded in the manifest
 /// * `games_per_file` - Maximum games per output file
-/// * `hash_size` - Transposition table size in MB
-/// * `level` - Search level configuration
-/// * `selectivity` - Search selectivity parameter
+/// * `settings` - Search and worker pool settings
 /// * `prefix` - Output file prefix
 /// * `output_dir` - Directory for output files
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` on success, or an error if operations fail.
-#[allow(clippy::too_many_arguments)]
 pub fn execute_with_openings(
     openings_path: &str,
     resume: bool,
     games_per_file: u32,
-    hash_size: usize,
-    level: Level,
-    selectivity: Selectivity,
+    settings: &SelfplaySettings,
     prefix: &str,
     output_dir: &str,
 ) -> io::Result<()> {
     fs::create_dir_all(output_dir)?;
 
-    let options = SearchOptions::new(hash_size);
-
-    let mut search = search::Search::new(&options);
-    let mut record_cache: HashMap<Board, GameRecord> = HashMap::new();
-    let mut file_state = FileState::new(prefix, output_dir, games_per_file)?;
-
     let opening_sequences = opening::load_openings(openings_path)?;
 
     // If no openings, exit early
@@ -244,35 +570,153 @@ pub fn execute_with_openings(
         ));
     }
 
-    // Determine the starting opening index from the last written record
-    let start_index = if resume {
-        let total = file_state.total_games()?;
-        if total > 0 {
-            println!("Resuming from opening index: {total}");
-        }
-        total
+    let mut run = SelfplayRun::open(
+        prefix,
+        output_dir,
+        games_per_file,
+        settings,
+        resume,
+        &format!("openings:{openings_path}"),
+    )?;
+
+    run_games(
+        opening_sequences.len(),
+        settings,
+        &mut run,
+        |index, _: &mut SmallRng| opening_sequences[index].clone(),
+    )
+}
+
+/// Plays the games of `run` not completed yet, up to `total_games`, writing
+/// each game followed by a checkpoint, so an interrupted run can resume
+/// exactly after its last checkpointed game.
+fn run_games<F>(
+    total_games: usize,
+    settings: &SelfplaySettings,
+    run: &mut SelfplayRun,
+    opening_for: F,
+) -> io::Result<()>
+where
+    F: Fn(usize, &mut SmallRng) -> Vec<Square> + Sync,
+{
+    let first_index = run.games_completed;
+    let num_games = total_games.saturating_sub(first_index);
+    play_games(
+        first_index,
+        num_games,
+        run.seed,
+        settings,
+        opening_for,
+        |mut played| {
+            let file_state = &mut run.file_state;
+            if file_state.is_full() {
+                file_state.rotate();
+            }
+            let game_id = file_state.next_game_id();
+            for record in played.records.iter_mut() {
+                record.game_id = game_id;
+            }
+            run.games_completed += 1;
+            file_state.write_game(&played)?;
+            run.save()
+        },
+    )
+}
+
+/// Plays games `first_index..first_index + num_games` on a pool of workers
+/// and passes them to `write` in index order.
+///
+/// Each worker owns its search and record cache, so the only shared state is
+/// the next game index and the channel to the writer. Game `i` draws its
+/// random choices from a generator seeded with `seed + i`. Finished games are
+/// written from the calling thread, holding back those that completed ahead
+/// of an earlier game, and the workers stop at the first write error.
+pub(crate) fn play_games<F, W>(
+    first_index: usize,
+    num_games: usize,
+    base_seed: u64,
+    settings: &SelfplaySettings,
+    opening_for: F,
+    mut write: W,
+) -> io::Result<()>
+where
+    F: Fn(usize, &mut SmallRng) -> Vec<Square> + Sync,
+    W: FnMut(PlayedGame) -> io::Result<()>,
+{
+    let threads = settings.threads.clamp(1, num_games.max(1));
+    println!("Playing {num_games} games on {threads} threads (seed {base_seed})");
+
+    // Split the available cores between the workers' searches, unless a
+    // single thread must keep the searches reproducible
+    let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+    let search_threads = if settings.deterministic {
+        1
     } else {
-        0
+        (cpus / threads).max(1)
     };
+    let options = SearchOptions::new(settings.hash_size).with_threads(Some(search_threads));
 
-    for opening_sequence in opening_sequences.iter().skip(start_index) {
-        if file_state.is_full() {
-            file_state.rotate();
-        }
-        let game_id = file_state.next_game_id();
-        let game_records = play_game(
-            opening_sequence,
-            &mut search,
-            level,
-            selectivity,
-            game_id,
-            &mut record_cache,
-        );
-
-        // Save the game records
-        file_state.write_records(&game_records)?;
+    // Each worker drives its own opponent process
+    let mut opponents = Vec::with_capacity(threads);
+    for _ in 0..threads {
+        opponents.push(settings.opponent.as_ref().map(|o| o.start()).transpose()?);
     }
-    Ok(())
+    if let Some(Some(opponent)) = opponents.first() {
+        println!("Opponent: {}", opponent.name());
+    }
+
+    let next_game = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel::<(usize, PlayedGame)>();
+
+    thread::scope(|scope| {
+        for mut opponent in opponents {
+            let sender = sender.clone();
+            let (options, next_game, stop, opening_for) =
+                (&options, &next_game, &stop, &opening_for);
+            scope.spawn(move || {
+                let mut search = search::Search::new(options);
+                let mut record_cache = RecordCache::new();
+
+                while !stop.load(Ordering::Relaxed) {
+                    let game = next_game.fetch_add(1, Ordering::Relaxed);
+                    if game >= num_games {
+                        break;
+                    }
+                    let index = first_index + game;
+                    let mut rng = SmallRng::seed_from_u64(base_seed.wrapping_add(index as u64));
+                    let opening_sequence = opening_for(index, &mut rng);
+                    let played = play_game(
+                        &opening_sequence,
+                        &mut search,
+                        settings,
+                        index,
+                        &mut record_cache,
+                        &mut opponent,
+                        &mut rng,
+                    );
+                    if sender.send((game, played)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending: BTreeMap<usize, PlayedGame> = BTreeMap::new();
+        let mut next_to_write = 0;
+        for (game, played) in receiver {
+            pending.insert(game, played);
+            while let Some(played) = pending.remove(&next_to_write) {
+                if let Err(e) = write(played) {
+                    stop.store(true, Ordering::Relaxed);
+                    return Err(e);
+                }
+                next_to_write += 1;
+            }
+        }
+        Ok(())
+    })
 }
 
 /// Generates a random opening sequence for a game.
@@ -284,11 +728,12 @@ pub fn execute_with_openings(
 ///
 /// * `num_moves` - The number of random moves to generate. May be fewer
 ///   if the game ends before this many moves have been played.
+/// * `rng` - Random number generator choosing the moves
 ///
 /// # Returns
 ///
 /// A `Vec<Square>` containing the sequence of moves.
-fn generate_random_opening(num_moves: u8) -> Vec<Square> {
+fn generate_random_opening(num_moves: u8, rng: &mut SmallRng) -> Vec<Square> {
     let mut opening = Vec::new();
     let mut game = GameState::new();
 
@@ -308,7 +753,7 @@ fn generate_random_opening(num_moves: u8) -> Vec<Square> {
             }
         }
 
-        let sq = random_move(g.board());
+        let sq = random_move(g.board(), rng);
         opening.push(sq);
         if g.make_move(sq).is_err() {
             return false;
@@ -331,27 +776,46 @@ fn generate_random_opening(num_moves: u8) -> Vec<Square> {
 ///
 /// * `opening_sequence` - Sequence of moves to play at the start
 /// * `search` - Search engine instance
-/// * `lv` - Search level
-/// * `selectivity` - Search selectivity
-/// * `game_id` - Game identifier for logging
+/// * `settings` - Search level or time control, selectivity and move randomization
+/// * `index` - Index of the game in this run, for logging
 /// * `record_cache` - Cache for game records to avoid redundant searches
+/// * `opponent` - External engine of this worker, if configured
+/// * `rng` - Random number generator for the move randomization and the
+///   opponent's games
 ///
 /// # Returns
 ///
-/// Returns a vector of game records for the played game.
+/// Returns the game records for the played game, with their policy entries
+/// when `settings.policy` is set.
 fn play_game(
     opening_sequence: &[Square],
     search: &mut search::Search,
-    lv: Level,
-    selectivity: Selectivity,
-    game_id: u16,
-    record_cache: &mut HashMap<Board, GameRecord>,
-) -> Vec<GameRecord> {
+    settings: &SelfplaySettings,
+    index: usize,
+    record_cache: &mut RecordCache,
+    opponent: &mut Option<Opponent>,
+    rng: &mut SmallRng,
+) -> PlayedGame {
+    let selectivity = settings.selectivity;
     let game_start = Instant::now();
     search.init();
 
+    // Drawn whether or not the engine is running, so that games do not depend
+    // on an earlier failure of it
+    let opponent_side = settings.opponent.as_ref().and_then(|o| {
+        (rng.random::<f32>() < o.rate).then(|| {
+            if rng.random::<bool>() {
+                Disc::Black
+            } else {
+                Disc::White
+            }
+        })
+    });
+    let mut opponent = OpponentGame::new(opponent, opponent_side);
+
     let mut game = GameState::new();
     let mut game_records = Vec::new();
+    let mut move_scores = Vec::new();
 
     // Play opening moves
     for &sq in opening_sequence {
@@ -361,6 +825,7 @@ fn play_game(
 
         // Handle pass moves
         if !game.board().has_legal_moves() {
+            opponent.tell(game.side_to_move(), None);
             let _ = game.make_pass();
             if game.is_game_over() {
                 break;
@@ -370,31 +835,41 @@ fn play_game(
         let board = *game.board();
         let side_to_move = game.side_to_move();
 
-        let mut record = if let Some(cached_record) = record_cache.get(&board) {
-            cached_record.clone()
+        // A cached record was searched with the transposition table of another
+        // game, so deterministic runs search every position again
+        let cached = (!settings.deterministic)
+            .then(|| record_cache.get(&board))
+            .flatten();
+        let (record, scores) = if let Some(cached) = cached {
+            cached.clone()
         } else {
-            let options = SearchRunOptions::with_level(lv, selectivity);
-            let result = search.run(&board, &options);
+            let result = search_at_level(search, &board, settings, settings.policy, rng);
             let ply = 60 - board.get_empty_count() as u8;
             let score = result.score().expect("search returned no legal move");
 
             let record = GameRecord {
-                game_id,
+                game_id: 0,
                 ply,
                 board,
                 score,
                 game_score: 0,
                 side_to_move,
-                is_random: true,
+                move_kind: MoveKind::Opening,
                 sq: result.best_move().unwrap_or(sq),
             };
-            record_cache.insert(board, record.clone());
-            record
+            let scores = settings
+                .policy
+                .then(|| Box::new(policy::move_scores(&result)));
+            if !settings.deterministic {
+                record_cache.insert(board, (record.clone(), scores.clone()));
+            }
+            (record, scores)
         };
-        record.game_id = game_id;
 
         game_records.push(record);
+        move_scores.extend(scores.map(|scores| *scores));
 
+        opponent.tell(side_to_move, Some(sq));
         let _ = game.make_move(sq);
     }
 
@@ -403,34 +878,77 @@ fn play_game(
     }
 
     // Continue playing with search
+    let mut clocks = settings
+        .time_control
+        .map(|control| [Clock::new(control), Clock::new(control)]);
+    let mut timeouts = 0;
     while !game.is_game_over() {
         if !game.board().has_legal_moves() {
+            opponent.tell(game.side_to_move(), None);
             let _ = game.make_pass();
             continue;
         }
 
         let board = *game.board();
         let side_to_move = game.side_to_move();
-        let options = SearchRunOptions::with_level(lv, selectivity);
-        let result = search.run(&board, &options);
-
         let ply = 60 - board.get_empty_count() as u8;
+        // The opponent's positions are only searched for their score
+        let opponent_moves = opponent.side() == Some(side_to_move);
+        let noisy = !opponent_moves && settings.noise.applies(ply);
+        let multi_pv = settings.policy || (noisy && settings.noise.temperature.is_some());
+        let result = match clocks.as_mut() {
+            Some([black, white]) if !opponent_moves => {
+                let clock = if side_to_move == Disc::Black {
+                    black
+                } else {
+                    white
+                };
+                let start = Instant::now();
+                let result = if clock.available_ms() < MIN_TIME_FOR_SEARCH_MS {
+                    search.quick_move(&board)
+                } else {
+                    let options =
+                        SearchRunOptions::with_time(clock.mode(), selectivity).multi_pv(multi_pv);
+                    search.run(&board, &options)
+                };
+                if !clock.charge(start.elapsed().as_millis() as u64) {
+                    timeouts += 1;
+                }
+                result
+            }
+            _ => search_at_level(search, &board, settings, multi_pv, rng),
+        };
+        if settings.policy {
+            move_scores.push(policy::move_scores(&result));
+        }
+
         let best_move = result.best_move().expect("search returned no legal move");
         let score = result.score().expect("search returned no legal move");
+        let (sq, move_kind) = match opponent.genmove(side_to_move, &board) {
+            Some(sq) => (sq, MoveKind::Opponent),
+            None => {
+                let chosen = noisy
+                    .then(|| settings.noise.choose(&board, &result, rng))
+                    .flatten()
+                    .unwrap_or((best_move, MoveKind::Search));
+                opponent.tell(side_to_move, Some(chosen.0));
+                chosen
+            }
+        };
 
         let record = GameRecord {
-            game_id,
+            game_id: 0,
             ply,
             board,
             score,
             game_score: 0,
             side_to_move,
-            is_random: false,
-            sq: best_move,
+            move_kind,
+            sq,
         };
         game_records.push(record);
 
-        let _ = game.make_move(best_move);
+        let _ = game.make_move(sq);
     }
 
     // Calculate final game scores
@@ -448,24 +966,55 @@ fn play_game(
         }
     }
 
-    let random_moves = game_records.iter().filter(|r| r.is_random).count();
+    let random_moves = game_records.iter().filter(|r| r.is_random()).count();
     let total_moves = game_records.len();
     let black_score = game_records.first().map_or(0, |r| r.game_score);
     let duration = game_start.elapsed();
+    let timeouts = if clocks.is_some() {
+        format!(", {timeouts} timeouts")
+    } else {
+        String::new()
+    };
+    let opponent = match opponent_side {
+        Some(Disc::Black) => ", opponent black",
+        Some(_) => ", opponent white",
+        None => "",
+    };
     println!(
-        "Game {}: score {:+}, moves {}/{} (random/total), {:.2}s",
-        game_id + 1,
+        "Game {}: score {:+}, moves {}/{} (random/total), {:.2}s{timeouts}{opponent}",
+        index + 1,
         black_score,
         random_moves,
         total_moves,
         duration.as_secs_f64()
     );
 
-    game_records
+    PlayedGame {
+        records: game_records,
+        move_scores,
+    }
+}
+
+/// Searches `board` at the level of `settings`.
+///
+/// The search answers the initial position with a move drawn from an unseeded
+/// generator, which deterministic runs replace with a move drawn from `rng`.
+fn search_at_level(
+    search: &mut search::Search,
+    board: &Board,
+    settings: &SelfplaySettings,
+    multi_pv: bool,
+    rng: &mut SmallRng,
+) -> SearchResult {
+    if settings.deterministic && !multi_pv && board.get_empty_count() == 60 {
+        return SearchResult::new_random_move(random_move(board, rng));
+    }
+    let options =
+        SearchRunOptions::with_level(settings.level, settings.selectivity).multi_pv(multi_pv);
+    search.run(board, &options)
 }
 
 /// Selects a random legal move from the current board position.
-fn random_move(board: &Board) -> Square {
-    let mut rng = rand::rng();
-    board.get_moves().iter().choose(&mut rng).unwrap()
+fn random_move(board: &Board, rng: &mut SmallRng) -> Square {
+    board.get_moves().iter().choose(rng).unwrap()
 }
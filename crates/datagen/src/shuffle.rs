@@ -1,21 +1,37 @@
 //! Game record shuffling module.
 //!
-//! This module provides functionality to shuffle and redistribute game records
-//! from multiple input files into a new set of output files. This is useful for
-//! preparing training data by randomizing the order of game records and potentially
-//! redistributing them across a different number of files.
+//! This module shuffles the game records of multiple input files into a new
+//! set of output files with an external shuffle whose memory use is bounded
+//! by a budget. This is useful for preparing training data by randomizing the
+//! order of game records and potentially redistributing them across a
+//! different number of files.
+//!
+//! The shuffle runs in two passes. The scatter pass sends every record that
+//! passes the filters to a uniformly random bucket file in a temporary
+//! directory. The gather pass loads one bucket at a time, shuffles it in
+//! memory and appends it to the output, which is split evenly into the output
+//! files. Concatenating shuffled buckets of random content yields a uniformly
+//! random permutation of all records, so records of a game or input file are
+//! not kept together. The result only depends on the inputs, the filters, the
+//! number of output files, the memory budget and the seed.
+//!
+//! Progress is checkpointed to a manifest in the output directory whenever the
+//! bucket buffers are flushed, which may happen within an input file, and
+//! after every gathered bucket, so an interrupted run can be resumed with the
+//! same arguments.
 
 use std::{
-    fs::{File, OpenOptions, metadata},
-    io::{self, BufReader, BufWriter, Read, Write},
+    fs::{self, File, OpenOptions, metadata},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
     path::{Path, PathBuf},
     time::Duration,
 };
 
 use glob::glob;
-use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
-use rand::{SeedableRng, rngs::SmallRng, seq::SliceRandom};
+use indicatif::{HumanBytes, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use rand::{RngExt, SeedableRng, rngs::SmallRng, seq::SliceRandom};
 
+use crate::manifest::{self, Manifest};
 use crate::record::{
     self, GAME_SCORE_OFFSET, GAME_SCORE_UNAVAILABLE, IS_RANDOM_OFFSET, PLY_OFFSET, SCORE_OFFSET,
 };
@@ -30,6 +46,46 @@ pub(crate) struct FilterConfig {
     pub keep_above_ply: Option<u8>,
 }
 
+impl FilterConfig {
+    /// Records the filter parameters in `manifest`.
+    fn describe(&self, manifest: &mut Manifest) {
+        manifest.set("min_ply", self.min_ply);
+        manifest.set("max_score_diff", format!("{:?}", self.max_score_diff));
+        manifest.set("drop_random", self.drop_random);
+        manifest.set("keep_above_ply", format!("{:?}", self.keep_above_ply));
+    }
+
+    /// Whether `record` passes the filters, counting a dropped record in `stats`.
+    fn accepts(&self, record: &Record, stats: &mut FilterStats) -> bool {
+        let ply = record[PLY_OFFSET];
+        if ply < self.min_ply {
+            stats.dropped_min_ply += 1;
+            return false;
+        }
+        let dominated = self.keep_above_ply.is_none_or(|threshold| ply < threshold);
+        if dominated {
+            if self.drop_random && record[IS_RANDOM_OFFSET] != 0 {
+                stats.dropped_random += 1;
+                return false;
+            }
+            if let Some(threshold) = self.max_score_diff {
+                let game_score = record[GAME_SCORE_OFFSET] as i8;
+                if game_score != GAME_SCORE_UNAVAILABLE {
+                    let score_bytes: [u8; 4] = record[SCORE_OFFSET..SCORE_OFFSET + 4]
+                        .try_into()
+                        .expect("4-byte score slice");
+                    let score = f32::from_le_bytes(score_bytes);
+                    if (score - f32::from(game_score)).abs() > threshold {
+                        stats.dropped_score_diff += 1;
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
 #[derive(Default)]
 struct FilterStats {
     dropped_min_ply: u64,
@@ -40,8 +96,11 @@ struct FilterStats {
 /// Buffer size for reading files (in number of records)
 const READ_BUFFER_RECORDS: usize = 4096;
 
-/// Random seed for reproducible shuffling
-const SHUFFLE_SEED: u64 = 42;
+/// File name of the checkpoint manifest in the output directory
+const MANIFEST_FILE: &str = "shuffle.manifest";
+
+/// Directory of the bucket files in the output directory
+const BUCKET_DIR: &str = "shuffle_buckets";
 
 /// Number of digits used in output file naming
 const OUTPUT_FILE_DIGITS: usize = 5;
@@ -49,6 +108,68 @@ const OUTPUT_FILE_DIGITS: usize = 5;
 /// Represents a single game record as a fixed-size byte array
 type Record = [u8; RECORD_SIZE];
 
+/// Progress of a shuffle, as saved in its manifest.
+struct Progress {
+    files_scattered: usize,
+    /// Records of the input file `files_scattered` already scattered,
+    /// including the dropped ones
+    records_scattered: u64,
+    /// Records written to each bucket
+    bucket_records: Vec<u64>,
+    buckets_gathered: usize,
+    /// Records written to each output file
+    output_records: Vec<u64>,
+    stats: FilterStats,
+}
+
+impl Progress {
+    fn new(num_buckets: usize, num_output_files: usize) -> Self {
+        Self {
+            files_scattered: 0,
+            records_scattered: 0,
+            bucket_records: vec![0; num_buckets],
+            buckets_gathered: 0,
+            output_records: vec![0; num_output_files],
+            stats: FilterStats::default(),
+        }
+    }
+
+    fn load(manifest: &Manifest, num_buckets: usize, num_output_files: usize) -> io::Result<Self> {
+        let mut progress = Self::new(num_buckets, num_output_files);
+        progress.files_scattered = manifest.get("files_scattered")?;
+        progress.records_scattered = manifest.get("records_scattered")?;
+        progress.buckets_gathered = manifest.get("buckets_gathered")?;
+        for (i, count) in progress.bucket_records.iter_mut().enumerate() {
+            *count = manifest.get(&format!("bucket_{i:0OUTPUT_FILE_DIGITS$}"))?;
+        }
+        for (i, count) in progress.output_records.iter_mut().enumerate() {
+            *count = manifest.get(&format!("records_{i:0OUTPUT_FILE_DIGITS$}"))?;
+        }
+        progress.stats.dropped_min_ply = manifest.get("dropped_min_ply")?;
+        progress.stats.dropped_random = manifest.get("dropped_random")?;
+        progress.stats.dropped_score_diff = manifest.get("dropped_score_diff")?;
+        Ok(progress)
+    }
+
+    fn save(&self, manifest: &mut Manifest) -> io::Result<()> {
+        manifest.set("files_scattered", self.files_scattered);
+        manifest.set("records_scattered", self.records_scattered);
+        manifest.set("buckets_gathered", self.buckets_gathered);
+        for (i, count) in self.bucket_records.iter().enumerate() {
+            manifest.set(&format!("bucket_{i:0OUTPUT_FILE_DIGITS$}"), count);
+        }
+        for (i, count) in self.output_records.iter().enumerate() {
+            manifest.set(&format!("records_{i:0OUTPUT_FILE_DIGITS$}"), count);
+        }
+        manifest.set("dropped_min_ply", self.stats.dropped_min_ply);
+        manifest.set("dropped_random", self.stats.dropped_random);
+        manifest.set("dropped_score_diff", self.stats.dropped_score_diff);
+        #[cfg(test)]
+        tests::interrupt_before_save()?;
+        manifest.save()
+    }
+}
+
 /// Shuffles and redistributes game records from input files.
 ///
 /// # Arguments
@@ -56,42 +177,147 @@ type Record = [u8; RECORD_SIZE];
 /// * `input_dir` - Directory containing input files to shuffle
 /// * `output_dir` - Directory where shuffled files will be written
 /// * `pattern` - Glob pattern to match input files (e.g., "*.bin")
-/// * `files_per_chunk` - Number of input files to process in each chunk
 /// * `num_output_files` - Number of output files to create (defaults to input file count)
+/// * `memory_mb` - Approximate memory budget in MiB of the bucket buffers and of
+///   each bucket
+/// * `seed` - Seed of the shuffle
+/// * `filter` - Filters applied to the records
+/// * `resume` - Whether to continue the run recorded in the manifest
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` on success, or an error if file operations fail.
+#[allow(clippy::too_many_arguments)]
 pub fn execute(
     input_dir: &str,
     output_dir: &str,
     pattern: &str,
-    files_per_chunk: usize,
     num_output_files: Option<usize>,
+    memory_mb: u64,
+    seed: u64,
     filter: FilterConfig,
+    resume: bool,
 ) -> anyhow::Result<()> {
-    let mut stats = FilterStats::default();
+    shuffle(
+        input_dir,
+        output_dir,
+        pattern,
+        num_output_files,
+        memory_mb * 1024 * 1024,
+        seed,
+        filter,
+        resume,
+    )
+}
 
+/// Shuffles with a memory budget of `memory_bytes`, see [`execute`].
+#[allow(clippy::too_many_arguments)]
+fn shuffle(
+    input_dir: &str,
+    output_dir: &str,
+    pattern: &str,
+    num_output_files: Option<usize>,
+    memory_bytes: u64,
+    seed: u64,
+    filter: FilterConfig,
+    resume: bool,
+) -> anyhow::Result<()> {
     let input_dir_path = Path::new(input_dir);
     let output_dir_path = Path::new(output_dir);
+    let bucket_dir = output_dir_path.join(BUCKET_DIR);
 
     // Create output directory if it doesn't exist
-    std::fs::create_dir_all(output_dir_path)?;
+    fs::create_dir_all(output_dir_path)?;
 
-    let mut rng = SmallRng::seed_from_u64(SHUFFLE_SEED);
-    let input_files = find_input_files(input_dir_path, pattern, &mut rng)?;
+    let input_files = find_input_files(input_dir_path, pattern)?;
     if input_files.is_empty() {
         println!("No input files found – nothing to do.");
         return Ok(());
     }
 
+    let mut input_bytes = 0;
+    for path in &input_files {
+        input_bytes += metadata(path)?.len();
+    }
+    // A whole bucket is loaded to shuffle it. Buckets of half the budget
+    // leave room for the random variation of their sizes.
+    let num_buckets = input_bytes.div_ceil((memory_bytes / 2).max(1)).max(1) as usize;
     let num_output_files = num_output_files.unwrap_or(input_files.len()).max(1);
 
+    let mut expected = Manifest::new(&output_dir_path.join(MANIFEST_FILE));
+    expected.set("command", "shuffle");
+    expected.set("input_dir", input_dir);
+    expected.set("pattern", pattern);
+    expected.set("input_files", input_files.len());
+    expected.set("num_output_files", num_output_files);
+    expected.set("buckets", num_buckets);
+    expected.set("memory_bytes", memory_bytes);
+    expected.set("seed", seed);
+    filter.describe(&mut expected);
+
+    let (mut manifest, mut progress) = match Manifest::load(expected.path())? {
+        Some(manifest) if resume => {
+            for key in [
+                "command",
+                "input_dir",
+                "pattern",
+                "input_files",
+                "num_output_files",
+                "buckets",
+                "memory_bytes",
+                "seed",
+                "min_ply",
+                "max_score_diff",
+                "drop_random",
+                "keep_above_ply",
+            ] {
+                manifest.expect(key, expected.get::<String>(key)?)?;
+            }
+            let progress = Progress::load(&manifest, num_buckets, num_output_files)?;
+            println!(
+                "Resuming after {} of {} files and {} records scattered and {} of {num_buckets} buckets gathered",
+                progress.files_scattered,
+                input_files.len(),
+                progress.records_scattered,
+                progress.buckets_gathered
+            );
+            (manifest, progress)
+        }
+        _ => {
+            // Start over, discarding the buckets of an earlier run
+            match fs::remove_dir_all(&bucket_dir) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+            (expected, Progress::new(num_buckets, num_output_files))
+        }
+    };
+    fs::create_dir_all(&bucket_dir)?;
+
+    // Discard records appended after the checkpoint, or the output of an
+    // earlier run when starting over
+    for (i, &count) in progress.bucket_records.iter().enumerate() {
+        if i >= progress.buckets_gathered {
+            manifest::truncate_to(
+                &bucket_file_path(&bucket_dir, i),
+                count * RECORD_SIZE as u64,
+            )?;
+        }
+    }
+    for (i, &count) in progress.output_records.iter().enumerate() {
+        manifest::truncate_to(
+            &output_file_path(output_dir_path, i),
+            count * RECORD_SIZE as u64,
+        )?;
+    }
+
     println!("Input  folder : {input_dir:?}");
     println!("Output folder : {output_dir:?}");
     println!("Input files   : {}", input_files.len());
     println!("Output files  : {num_output_files}");
-    println!("Files/chunk   : {files_per_chunk}");
+    println!("Memory        : {}", HumanBytes(memory_bytes));
+    println!("Buckets       : {num_buckets}");
+    println!("Seed          : {seed}");
     println!("Min ply       : {}", filter.min_ply);
     println!(
         "Drop random   : {}",
@@ -107,80 +333,222 @@ pub fn execute(
     }
     println!("----------------------------------------");
 
-    let mp = MultiProgress::with_draw_target(ProgressDrawTarget::stderr_with_hz(10));
-    let chunk_pb = mp.add(ProgressBar::new(
-        input_files.len().div_ceil(files_per_chunk) as u64,
-    ));
-    chunk_pb.set_style(
-        ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] chunks {pos}/{len} ETA:{eta_precise}",
-        )?
-        .progress_chars("#>-"),
-    );
-    chunk_pb.enable_steady_tick(Duration::from_millis(100));
-
-    let mut records_per_output_file = vec![0u64; num_output_files];
-    let mut total_records: u64 = 0;
-    let mut total_bytes: u64 = 0;
-
-    for (chunk_id, chunk) in input_files.chunks(files_per_chunk).enumerate() {
-        let mut chunk_records: Vec<Record> = Vec::new();
-
-        for path in chunk {
-            read_records(path, &mut chunk_records, &filter, &mut stats)?;
-        }
-
-        chunk_records.shuffle(&mut rng);
-
-        distribute_records(
-            output_dir_path,
-            &chunk_records,
-            &mut records_per_output_file,
-            chunk_id,
-        )?;
-
-        total_records += chunk_records.len() as u64;
-        total_bytes += (chunk_records.len() * RECORD_SIZE) as u64;
-        chunk_pb.set_message(format!(
-            "total {total_records} recs / {}",
-            HumanBytes(total_bytes)
-        ));
-        chunk_pb.inc(1);
-    }
-
-    chunk_pb.finish_with_message("done");
-    mp.clear()?;
-
+    scatter(
+        &input_files,
+        &bucket_dir,
+        memory_bytes,
+        seed,
+        &filter,
+        &mut progress,
+        &mut manifest,
+    )?;
+    gather(
+        output_dir_path,
+        &bucket_dir,
+        seed.wrapping_add(input_files.len() as u64),
+        &mut progress,
+        &mut manifest,
+    )?;
+    fs::remove_dir_all(&bucket_dir)?;
+
+    let total_records: u64 = progress.output_records.iter().sum();
+    let stats = &progress.stats;
     println!("------------- Summary -------------");
     println!(
         "Total records : {}  ({})",
         total_records,
-        HumanBytes(total_bytes)
+        HumanBytes(total_records * RECORD_SIZE as u64)
     );
     let total_dropped = stats.dropped_min_ply + stats.dropped_random + stats.dropped_score_diff;
     println!("Dropped       : {total_dropped} recs");
     println!("  min_ply     : {}", stats.dropped_min_ply);
     println!("  random      : {}", stats.dropped_random);
     println!("  score_diff  : {}", stats.dropped_score_diff);
-    for (i, record_count) in records_per_output_file.iter().enumerate() {
+    for (i, record_count) in progress.output_records.iter().enumerate() {
         println!("shuffled_{i:0OUTPUT_FILE_DIGITS$}.bin : {record_count} recs");
     }
     println!("-----------------------------------");
     Ok(())
 }
 
-/// Finds and shuffles input files matching the given pattern.
+fn progress_bar(len: usize, unit: &str) -> anyhow::Result<ProgressBar> {
+    let pb =
+        ProgressBar::with_draw_target(Some(len as u64), ProgressDrawTarget::stderr_with_hz(10));
+    pb.set_style(
+        ProgressStyle::with_template(&format!(
+            "{{spinner:.green}} [{{elapsed_precise}}] [{{wide_bar:.cyan/blue}}] {unit} {{pos}}/{{len}} ETA:{{eta_precise}}"
+        ))?
+        .progress_chars("#>-"),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+    Ok(pb)
+}
+
+/// Sends the records of the input files not scattered yet to random buckets.
+///
+/// Records are buffered per bucket and appended to the bucket files as soon
+/// as the buffers reach `memory_bytes`, even within an input file, and once
+/// after the last file. Each flush is a checkpoint at a record of an input
+/// file. The buckets of the records of input file `i` from record `r` on are
+/// drawn from a generator seeded from `seed + i` and `r`, where `r` is the
+/// start of the file or the record after a flush, so a resumed run draws the
+/// same buckets as an uninterrupted one.
+fn scatter(
+    input_files: &[PathBuf],
+    bucket_dir: &Path,
+    memory_bytes: u64,
+    seed: u64,
+    filter: &FilterConfig,
+    progress: &mut Progress,
+    manifest: &mut Manifest,
+) -> anyhow::Result<()> {
+    let pb = progress_bar(input_files.len(), "scatter files")?;
+    pb.set_position(progress.files_scattered as u64);
+
+    let num_buckets = progress.bucket_records.len();
+    let mut buffers: Vec<Vec<u8>> = vec![Vec::new(); num_buckets];
+    let mut buffered = 0u64;
+    let first_file = progress.files_scattered;
+    for (i, path) in input_files.iter().enumerate().skip(first_file) {
+        let skip = if i == first_file {
+            progress.records_scattered
+        } else {
+            0
+        };
+        let mut rng = scatter_rng(seed, i, skip);
+        read_records(path, skip, |index, record| {
+            if !filter.accepts(record, &mut progress.stats) {
+                return Ok(());
+            }
+            buffers[rng.random_range(0..num_buckets)].extend_from_slice(record);
+            buffered += RECORD_SIZE as u64;
+            if buffered >= memory_bytes {
+                flush_buckets(bucket_dir, &mut buffers, progress)?;
+                buffered = 0;
+                progress.files_scattered = i;
+                progress.records_scattered = index + 1;
+                progress.save(manifest)?;
+                rng = scatter_rng(seed, i, index + 1);
+            }
+            Ok(())
+        })?;
+        pb.inc(1);
+    }
+
+    if first_file < input_files.len() {
+        flush_buckets(bucket_dir, &mut buffers, progress)?;
+        progress.files_scattered = input_files.len();
+        progress.records_scattered = 0;
+        progress.save(manifest)?;
+    }
+    pb.finish_and_clear();
+    Ok(())
+}
+
+/// Generator of the buckets of input file `file_index` from record `record` on.
+fn scatter_rng(seed: u64, file_index: usize, record: u64) -> SmallRng {
+    SmallRng::seed_from_u64(seed.wrapping_add(file_index as u64) ^ record.rotate_left(32))
+}
+
+/// Appends the buffered records to their bucket files and clears the buffers.
+fn flush_buckets(
+    bucket_dir: &Path,
+    buffers: &mut [Vec<u8>],
+    progress: &mut Progress,
+) -> io::Result<()> {
+    for (bucket, buffer) in buffers.iter_mut().enumerate() {
+        if buffer.is_empty() {
+            continue;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(bucket_file_path(bucket_dir, bucket))?;
+        file.write_all(buffer)?;
+        progress.bucket_records[bucket] += (buffer.len() / RECORD_SIZE) as u64;
+        buffer.clear();
+    }
+    Ok(())
+}
+
+/// Shuffles the buckets not gathered yet one at a time and appends them to
+/// the output files.
+///
+/// The buckets form one sequence of all records, which is split into output
+/// files of equal size up to one record. Bucket `b` is shuffled with a
+/// generator seeded with `seed + b`.
+fn gather(
+    output_dir: &Path,
+    bucket_dir: &Path,
+    seed: u64,
+    progress: &mut Progress,
+    manifest: &mut Manifest,
+) -> anyhow::Result<()> {
+    let num_buckets = progress.bucket_records.len();
+    let num_output_files = progress.output_records.len() as u64;
+    let total_records: u64 = progress.bucket_records.iter().sum();
+    // Output file `k` holds the records from `starts[k]` to `starts[k + 1]`
+    let starts: Vec<u64> = (0..=num_output_files)
+        .map(|k| k * total_records / num_output_files)
+        .collect();
+
+    let pb = progress_bar(num_buckets, "gather buckets")?;
+    pb.set_position(progress.buckets_gathered as u64);
+
+    let mut position: u64 = progress.bucket_records[..progress.buckets_gathered]
+        .iter()
+        .sum();
+    for bucket in progress.buckets_gathered..num_buckets {
+        let path = bucket_file_path(bucket_dir, bucket);
+        let mut records: Vec<Record> = Vec::new();
+        if progress.bucket_records[bucket] > 0 {
+            read_records(&path, 0, |_, record| {
+                records.push(*record);
+                Ok(())
+            })?;
+        }
+        records.shuffle(&mut SmallRng::seed_from_u64(
+            seed.wrapping_add(bucket as u64),
+        ));
+
+        let mut written = 0;
+        while written < records.len() {
+            let global = position + written as u64;
+            let file_index = starts.partition_point(|&start| start <= global) - 1;
+            let end = ((starts[file_index + 1] - position) as usize).min(records.len());
+            write_records(
+                &output_file_path(output_dir, file_index),
+                &records[written..end],
+            )?;
+            progress.output_records[file_index] += (end - written) as u64;
+            written = end;
+        }
+        position += records.len() as u64;
+
+        progress.buckets_gathered = bucket + 1;
+        progress.save(manifest)?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+    Ok(())
+}
+
+/// Finds input files matching the given pattern.
 ///
 /// # Arguments
 ///
 /// * `dir` - Directory to search for files
 /// * `pattern` - Glob pattern to match files
-/// * `rng` - Random number generator for shuffling file order
 ///
 /// # Returns
 ///
-/// Returns a vector of file paths in random order.
-fn find_input_files(dir: &Path, pattern: &str, rng: &mut SmallRng) -> anyhow::Result<Vec<PathBuf>> {
+/// Returns a vector of file paths, sorted so that the shuffle does not
+/// depend on the file system.
+fn find_input_files(dir: &Path, pattern: &str) -> anyhow::Result<Vec<PathBuf>> {
     let full_pattern = dir.join(pattern).to_string_lossy().into_owned();
     let mut file_paths = Vec::new();
 
@@ -198,7 +566,7 @@ fn find_input_files(dir: &Path, pattern: &str, rng: &mut SmallRng) -> anyhow::Re
             ),
         }
     }
-    file_paths.shuffle(rng);
+    file_paths.sort();
     Ok(file_paths)
 }
 
@@ -207,16 +575,17 @@ fn find_input_files(dir: &Path, pattern: &str, rng: &mut SmallRng) -> anyhow::Re
 /// # Arguments
 ///
 /// * `path` - Path to the binary file to read
-/// * `out` - Vector to append the read records to
+/// * `skip` - Number of records to skip at the start of the file
+/// * `emit` - Called with the index in the file and the content of every
+///   record after the skipped ones
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` on success, or an I/O error if reading fails.
+/// Returns `Ok(())` on success, or the first I/O error of reading or of `emit`.
 fn read_records(
     path: &Path,
-    out: &mut Vec<Record>,
-    filter: &FilterConfig,
-    stats: &mut FilterStats,
+    skip: u64,
+    mut emit: impl FnMut(u64, &Record) -> io::Result<()>,
 ) -> io::Result<()> {
     let md = metadata(path)?;
     if md.len() == 0 || md.len() % RECORD_SIZE as u64 != 0 {
@@ -228,90 +597,188 @@ fn read_records(
         return Ok(());
     }
 
-    let file = File::open(path)?;
+    let total_records = md.len() / RECORD_SIZE as u64;
+    let skip = skip.min(total_records);
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(skip * RECORD_SIZE as u64))?;
     let mut reader = BufReader::new(file);
     let mut buffer = vec![0u8; RECORD_SIZE * READ_BUFFER_RECORDS];
 
     // `md.len()` is guaranteed to be a multiple of RECORD_SIZE by the check above,
     // so we can read in exact record-batch-sized chunks without losing trailing bytes.
-    let mut records_remaining = (md.len() / RECORD_SIZE as u64) as usize;
-    while records_remaining > 0 {
-        let batch = records_remaining.min(READ_BUFFER_RECORDS);
+    let mut index = skip;
+    while index < total_records {
+        let batch = (total_records - index).min(READ_BUFFER_RECORDS as u64) as usize;
         let batch_bytes = batch * RECORD_SIZE;
         reader.read_exact(&mut buffer[..batch_bytes])?;
         for chunk in buffer[..batch_bytes].chunks_exact(RECORD_SIZE) {
-            let ply = chunk[PLY_OFFSET];
-            if ply < filter.min_ply {
-                stats.dropped_min_ply += 1;
-                continue;
-            }
-            let dominated = filter
-                .keep_above_ply
-                .is_none_or(|threshold| ply < threshold);
-            if dominated {
-                if filter.drop_random && chunk[IS_RANDOM_OFFSET] != 0 {
-                    stats.dropped_random += 1;
-                    continue;
-                }
-                if let Some(threshold) = filter.max_score_diff {
-                    let game_score = chunk[GAME_SCORE_OFFSET] as i8;
-                    if game_score != GAME_SCORE_UNAVAILABLE {
-                        let score_bytes: [u8; 4] = chunk[SCORE_OFFSET..SCORE_OFFSET + 4]
-                            .try_into()
-                            .expect("4-byte score slice");
-                        let score = f32::from_le_bytes(score_bytes);
-                        if (score - f32::from(game_score)).abs() > threshold {
-                            stats.dropped_score_diff += 1;
-                            continue;
-                        }
-                    }
-                }
-            }
-            out.push(chunk.try_into().expect("slice length == RECORD_SIZE"));
+            emit(
+                index,
+                chunk.try_into().expect("slice length == RECORD_SIZE"),
+            )?;
+            index += 1;
         }
-        records_remaining -= batch;
     }
     Ok(())
 }
 
-fn distribute_records(
-    output_dir: &Path,
-    records: &[Record],
-    records_per_file: &mut [u64],
-    chunk_offset: usize,
-) -> io::Result<()> {
-    if records_per_file.is_empty() {
-        return Ok(());
+/// Appends records to a file.
+fn write_records(path: &Path, records: &[Record]) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut writer = BufWriter::new(file);
+    for record in records {
+        writer.write_all(record)?;
     }
+    writer.flush()
+}
 
-    let num_output_files = records_per_file.len();
-    let base_records_per_file = records.len() / num_output_files;
-    let extra_records = records.len() % num_output_files;
+fn bucket_file_path(bucket_dir: &Path, index: usize) -> PathBuf {
+    bucket_dir.join(format!("bucket_{index:0OUTPUT_FILE_DIGITS$}.bin"))
+}
 
-    let mut record_index = 0;
-    for file_index in 0..num_output_files {
-        let output_file_index = (file_index + chunk_offset) % num_output_files;
-        let records_to_write = base_records_per_file + usize::from(file_index < extra_records);
-        if records_to_write == 0 {
-            continue;
+fn output_file_path(output_dir: &Path, index: usize) -> PathBuf {
+    output_dir.join(format!("shuffled_{index:0OUTPUT_FILE_DIGITS$}.bin"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    thread_local! {
+        /// Checkpoints saved before the next save fails, to simulate an
+        /// interruption after the buckets or outputs were appended to
+        static SAVES_BEFORE_INTERRUPT: Cell<Option<usize>> = const { Cell::new(None) };
+    }
+
+    pub(super) fn interrupt_before_save() -> io::Result<()> {
+        SAVES_BEFORE_INTERRUPT.with(|saves| match saves.get() {
+            Some(0) => {
+                saves.set(None);
+                Err(io::Error::other("interrupted"))
+            }
+            Some(n) => {
+                saves.set(Some(n - 1));
+                Ok(())
+            }
+            None => Ok(()),
+        })
+    }
+
+    const MIN_PLY: u8 = 5;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("datagen-shuffle-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Writes input files of distinct records, numbered in their first bytes.
+    fn write_inputs(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        let mut id = 0u64;
+        for (i, count) in [100, 250, 40].into_iter().enumerate() {
+            let records: Vec<Record> = (0..count)
+                .map(|_| {
+                    let mut record = [0u8; RECORD_SIZE];
+                    record[..8].copy_from_slice(&id.to_le_bytes());
+                    record[PLY_OFFSET] = (id % 60) as u8;
+                    id += 1;
+                    record
+                })
+                .collect();
+            write_records(&dir.join(format!("input_{i}.bin")), &records).unwrap();
         }
+    }
 
-        let output_path = output_dir.join(format!(
-            "shuffled_{output_file_index:0OUTPUT_FILE_DIGITS$}.bin"
-        ));
-        let output_file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&output_path)?;
-        let mut writer = BufWriter::new(output_file);
+    fn run(input: &Path, output: &Path, seed: u64, resume: bool) -> anyhow::Result<()> {
+        shuffle(
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+            "*.bin",
+            Some(3),
+            32 * RECORD_SIZE as u64,
+            seed,
+            FilterConfig {
+                min_ply: MIN_PLY,
+                max_score_diff: None,
+                drop_random: false,
+                keep_above_ply: None,
+            },
+            resume,
+        )
+    }
+
+    /// Concatenated content of the output files.
+    fn read_output(output: &Path) -> Vec<u8> {
+        (0..3)
+            .flat_map(|i| fs::read(output_file_path(output, i)).unwrap())
+            .collect()
+    }
 
-        for record in &records[record_index..record_index + records_to_write] {
-            writer.write_all(record)?;
+    fn record_ids(content: &[u8]) -> Vec<u64> {
+        content
+            .chunks_exact(RECORD_SIZE)
+            .map(|record| u64::from_le_bytes(record[..8].try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn small_budget_shuffle_is_a_permutation() {
+        let dir = test_dir("permutation");
+        write_inputs(&dir.join("in"));
+        run(&dir.join("in"), &dir.join("out"), 7, false).unwrap();
+
+        let ids = record_ids(&read_output(&dir.join("out")));
+        let expected: Vec<u64> = (0..390).filter(|id| id % 60 >= MIN_PLY as u64).collect();
+        assert_ne!(ids, expected);
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, expected);
+        assert!(!dir.join("out").join(BUCKET_DIR).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn same_seed_gives_same_output() {
+        let dir = test_dir("seed");
+        write_inputs(&dir.join("in"));
+        run(&dir.join("in"), &dir.join("a"), 7, false).unwrap();
+        run(&dir.join("in"), &dir.join("b"), 7, false).unwrap();
+        run(&dir.join("in"), &dir.join("c"), 8, false).unwrap();
+
+        assert_eq!(read_output(&dir.join("a")), read_output(&dir.join("b")));
+        assert_ne!(read_output(&dir.join("a")), read_output(&dir.join("c")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        let dir = test_dir("resume");
+        write_inputs(&dir.join("in"));
+        run(&dir.join("in"), &dir.join("full"), 7, false).unwrap();
+
+        // Interrupt every few checkpoints, within input files and while
+        // gathering, and resume until the shuffle completes
+        let mut interruptions = 0;
+        let mut resume = false;
+        loop {
+            SAVES_BEFORE_INTERRUPT.with(|saves| saves.set(Some(3)));
+            match run(&dir.join("in"), &dir.join("resumed"), 7, resume) {
+                Ok(()) => break,
+                Err(_) => interruptions += 1,
+            }
+            resume = true;
         }
-        writer.flush()?;
+        SAVES_BEFORE_INTERRUPT.with(|saves| saves.set(None));
 
-        records_per_file[output_file_index] += records_to_write as u64;
-        record_index += records_to_write;
+        assert!(interruptions > 3);
+        assert_eq!(
+            read_output(&dir.join("full")),
+            read_output(&dir.join("resumed"))
+        );
+        fs::remove_dir_all(&dir).unwrap();
     }
-    Ok(())
 }
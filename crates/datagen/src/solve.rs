@@ -0,0 +1,91 @@
+//! Solve module.
+//!
+//! Solves the endgame positions of existing binary record files exactly,
+//! producing perfectly labeled data for training the endgame phases of the
+//! network. Positions within the requested range of empty squares are taken
+//! from each file, symmetric duplicates across the run are dropped and an
+//! optional share is sampled at random. Each selected record is then labeled
+//! with the solved score, which also replaces the game result, and with the
+//! solver's best move.
+//!
+//! The selection only depends on the inputs and the options, so an interrupted
+//! run resumes like `rescore`, after the last record already written.
+
+use anyhow::bail;
+use rand::{RngExt, SeedableRng, rngs::SmallRng};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::record::{GameRecord, read_records_from_file};
+use crate::rescore::{Rescorer, file_pairs};
+
+/// Range and sampling of the positions to solve.
+pub struct SolveSelection {
+    /// Minimum number of empty squares of a position
+    pub min_empties: u32,
+    /// Maximum number of empty squares of a position
+    pub max_empties: u32,
+    /// Share of the eligible positions to solve
+    pub sample_ratio: f64,
+    /// Random seed of the sampling
+    pub seed: u64,
+}
+
+/// Solves the endgame positions of `input`, a file or a directory of files
+/// matching `pattern`, writing the files of the same name to `output`.
+///
+/// # Arguments
+///
+/// * `input` - Binary record file, or directory containing them
+/// * `output` - Output file, or directory when `input` is a directory
+/// * `pattern` - Glob pattern of the files in an input directory
+/// * `selection` - Positions to solve
+/// * `hash_size` - Transposition table size in MB of each worker's search
+/// * `threads` - Number of positions solved concurrently
+pub fn execute(
+    input: &str,
+    output: &str,
+    pattern: &str,
+    selection: &SolveSelection,
+    hash_size: usize,
+    threads: usize,
+) -> anyhow::Result<()> {
+    if selection.min_empties > selection.max_empties {
+        bail!(
+            "Minimum empties {} exceeds maximum empties {}",
+            selection.min_empties,
+            selection.max_empties
+        );
+    }
+    let files = file_pairs(Path::new(input), Path::new(output), pattern)?;
+    let solver = Rescorer::solver(hash_size, threads);
+
+    let mut rng = SmallRng::seed_from_u64(selection.seed);
+    let mut seen = HashSet::new();
+    let (mut total, mut selected_total) = (0usize, 0usize);
+
+    for (input_path, output_path) in &files {
+        let records = read_records_from_file(input_path)?;
+        total += records.len();
+        let selected: Vec<GameRecord> = records
+            .into_iter()
+            .filter(|record| {
+                let n_empties = record.board.get_empty_count();
+                (selection.min_empties..=selection.max_empties).contains(&n_empties)
+                    && record.board.has_legal_moves()
+                    && seen.insert(record.board.unique().hash())
+                    && (selection.sample_ratio >= 1.0 || rng.random_bool(selection.sample_ratio))
+            })
+            .collect();
+        selected_total += selected.len();
+
+        if selected.is_empty() {
+            println!("{}: no positions to solve", input_path.display());
+            continue;
+        }
+        solver.rescore_records(input_path, &selected, output_path)?;
+    }
+
+    println!("Solved {selected_total} of {total} positions");
+    Ok(())
+}
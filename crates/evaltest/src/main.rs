@@ -8,12 +8,17 @@
 //! - Solving accuracy (correct score and best move)
 //! - Search performance (time and nodes)
 //! - Move selection quality (best move percentage)
+//!
+//! Cases are searched to a fixed depth, or under a wall-clock budget per case
+//! to measure the depth and accuracy reached at tournament-like time limits.
 
+mod report;
 mod test_case;
 
 use clap::Parser;
 use colored::*;
 use num_format::{Locale, ToFormattedString};
+use report::{CaseReport, OutputFormat, RunReport, Tolerances};
 use reversi_core::{
     self,
     board::Board,
@@ -25,13 +30,16 @@ use reversi_core::{
         options::SearchOptions,
         search_counters::SearchCounters,
         search_result::{PvMove, SearchResult},
+        time_control::TimeControlMode,
     },
     square::Square,
     types::{Depth, Scoref},
 };
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use test_case::{TestCase, find_problem_dir, load_all_problems, load_problems};
+use test_case::{TestCase, find_problem_dir, load_all_problems, load_problems, load_suites};
 
 /// Score tolerance levels for evaluation
 const SCORE_TOLERANCE_PERFECT: Scoref = 3.0;
@@ -54,9 +62,28 @@ struct IterationData {
     tt_fill: f64,
 }
 
+/// Time and nodes of one measured run of a test case
+#[derive(Debug, Default, Clone, Copy)]
+struct RunTiming {
+    elapsed: std::time::Duration,
+    nodes: u64,
+}
+
+impl RunTiming {
+    fn nps(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.nodes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Result of a single test case execution
 #[derive(Debug)]
 struct TestResult {
+    /// Time and nodes, averaged over the measured runs
     elapsed: std::time::Duration,
     nodes: u64,
     score: Scoref,
@@ -67,6 +94,20 @@ struct TestResult {
     move_accuracy: MoveAccuracy,
     counters: SearchCounters,
     pv_moves: Vec<PvMove>,
+    /// Time and nodes of each measured run
+    runs: Vec<RunTiming>,
+}
+
+impl TestResult {
+    /// Standard deviations of the time in seconds and of the NPS over the
+    /// measured runs, if there was more than one
+    fn run_spread(&self) -> Option<(f64, f64)> {
+        (self.runs.len() > 1).then(|| {
+            let times: Vec<f64> = self.runs.iter().map(|r| r.elapsed.as_secs_f64()).collect();
+            let nps: Vec<f64> = self.runs.iter().map(RunTiming::nps).collect();
+            (mean_stddev(&times).1, mean_stddev(&nps).1)
+        })
+    }
 }
 
 /// Move accuracy classification
@@ -78,12 +119,34 @@ enum MoveAccuracy {
     Other,
 }
 
+/// How far each test case is searched
+#[derive(Clone, Copy)]
+enum CaseLimit {
+    /// Fixed search depth
+    Level(Level),
+    /// Wall-clock budget in milliseconds, allocated like a fixed time per move
+    Time(u64),
+}
+
+impl CaseLimit {
+    fn run_options(self, selectivity: Selectivity) -> SearchRunOptions {
+        match self {
+            CaseLimit::Level(level) => SearchRunOptions::with_level(level, selectivity),
+            CaseLimit::Time(time_per_move_ms) => SearchRunOptions::with_time(
+                TimeControlMode::Byoyomi { time_per_move_ms },
+                selectivity,
+            ),
+        }
+    }
+}
+
 /// Statistics collector for aggregating search results across all test cases
 #[derive(Default)]
 struct SearchStats {
     total_time: std::time::Duration,
     total_nodes: u64,
     total_count: usize,
+    total_depth: u64,
     score_differences: Vec<Scoref>,
     best_move_count: usize,
     top2_move_count: usize,
@@ -92,24 +155,26 @@ struct SearchStats {
     good_score_count: usize,
     acceptable_score_count: usize,
     total_counters: SearchCounters,
+    /// Totals of each measured run over all test cases
+    run_totals: Vec<RunTiming>,
     mpv_score_differences: Vec<Scoref>,
     mpv_perfect_count: usize,
     mpv_good_count: usize,
     mpv_acceptable_count: usize,
 }
 
-/// Mean and population standard deviation of a slice of error samples.
+/// Mean and population standard deviation of a slice of samples.
 /// Returns `(0.0, 0.0)` for an empty slice.
-fn mean_stddev(samples: &[Scoref]) -> (f64, f64) {
+fn mean_stddev<T: Copy + Into<f64>>(samples: &[T]) -> (f64, f64) {
     if samples.is_empty() {
         return (0.0, 0.0);
     }
     let n = samples.len() as f64;
-    let mean = samples.iter().map(|&d| d as f64).sum::<f64>() / n;
+    let mean = samples.iter().map(|&d| d.into()).sum::<f64>() / n;
     let variance = samples
         .iter()
         .map(|&d| {
-            let diff = d as f64 - mean;
+            let diff = d.into() - mean;
             diff * diff
         })
         .sum::<f64>()
@@ -117,6 +182,17 @@ fn mean_stddev(samples: &[Scoref]) -> (f64, f64) {
     (mean, variance.sqrt())
 }
 
+/// Add the run timings of `runs` to `totals`, run by run
+fn add_run_timings(totals: &mut Vec<RunTiming>, runs: &[RunTiming]) {
+    if totals.len() < runs.len() {
+        totals.resize(runs.len(), RunTiming::default());
+    }
+    for (total, run) in totals.iter_mut().zip(runs) {
+        total.elapsed += run.elapsed;
+        total.nodes += run.nodes;
+    }
+}
+
 /// Round duration to 0.01ms precision for consistent display
 fn round_duration(elapsed: std::time::Duration) -> std::time::Duration {
     let rounded_micros = (elapsed.as_secs_f64() * 10000.0).round() as u64 * 100;
@@ -143,6 +219,7 @@ impl SearchStats {
         self.total_time += round_duration(result.elapsed);
         self.total_nodes += result.nodes;
         self.total_count += 1;
+        self.total_depth += u64::from(result.depth);
 
         self.score_differences.push(result.score_difference);
 
@@ -173,6 +250,7 @@ impl SearchStats {
         }
 
         self.total_counters.merge(&result.counters);
+        add_run_timings(&mut self.run_totals, &result.runs);
     }
 
     /// Accumulate per-move error statistics against OBF-listed expected scores.
@@ -222,6 +300,7 @@ impl SearchStats {
         self.total_time += other.total_time;
         self.total_nodes += other.total_nodes;
         self.total_count += other.total_count;
+        self.total_depth += other.total_depth;
         self.score_differences.extend(&other.score_differences);
         self.best_move_count += other.best_move_count;
         self.top2_move_count += other.top2_move_count;
@@ -230,6 +309,7 @@ impl SearchStats {
         self.good_score_count += other.good_score_count;
         self.acceptable_score_count += other.acceptable_score_count;
         self.total_counters.merge(&other.total_counters);
+        add_run_timings(&mut self.run_totals, &other.run_totals);
         self.mpv_score_differences
             .extend(&other.mpv_score_differences);
         self.mpv_perfect_count += other.mpv_perfect_count;
@@ -251,7 +331,7 @@ impl SearchStats {
             0
         };
 
-        let stats = [
+        let mut stats = vec![
             (
                 "Total time",
                 format!("{:.4}s", self.total_time.as_secs_f64()),
@@ -261,6 +341,10 @@ impl SearchStats {
                 self.total_nodes.to_formatted_string(&Locale::en),
             ),
             ("NPS", nps.to_formatted_string(&Locale::en)),
+            (
+                "Mean depth",
+                format!("{:.1}", self.total_depth as f64 / self.total_count as f64),
+            ),
             (
                 "Best move",
                 format_ratio(self.best_move_count, self.total_count),
@@ -288,6 +372,29 @@ impl SearchStats {
             ("MAE", format!("{mean_diff:.2}")),
             ("Std Dev.", format!("{std_dev:.2}")),
         ];
+        if self.run_totals.len() > 1 {
+            let times: Vec<f64> = self
+                .run_totals
+                .iter()
+                .map(|r| r.elapsed.as_secs_f64())
+                .collect();
+            let nps: Vec<f64> = self.run_totals.iter().map(RunTiming::nps).collect();
+            let (time_mean, time_std) = mean_stddev(&times);
+            let (nps_mean, nps_std) = mean_stddev(&nps);
+            let run_stats = [
+                ("Runs", self.run_totals.len().to_string()),
+                ("Time/run", format!("{time_mean:.4}s ± {time_std:.4}s")),
+                (
+                    "NPS/run",
+                    format!(
+                        "{} ± {}",
+                        (nps_mean.round() as u64).to_formatted_string(&Locale::en),
+                        (nps_std.round() as u64).to_formatted_string(&Locale::en)
+                    ),
+                ),
+            ];
+            stats.splice(3..3, run_stats);
+        }
 
         let max_label_len = stats
             .iter()
@@ -378,15 +485,26 @@ impl SearchStats {
     }
 }
 
-/// Print the table header for test results
-fn print_header(num_width: usize) {
+/// Print the table header for test results, with standard deviation columns
+/// for time and NPS when each case is run more than once
+fn print_header(num_width: usize, spread: bool) {
+    let (time_sd, nps_sd) = if spread {
+        (format!(" {:^8} |", "σ(s)"), format!(" {:^11} |", "σ NPS"))
+    } else {
+        (String::new(), String::new())
+    };
     println!(
-        "| {:^num_width$} | {:^6} | {:^9} | {:^15} | {:^13} | {:^8} | {:^6} | {:<32} |",
+        "| {:^num_width$} | {:^6} | {:^9} |{time_sd} {:^15} | {:^13} |{nps_sd} {:^8} | {:^6} | {:<32} |",
         "#", "Depth", "Time(s)", "Nodes", "NPS", "Line", "Score", "Expected"
     );
     let dashes = "-".repeat(num_width);
+    let (time_sd, nps_sd) = if spread {
+        ("---------:|", "------------:|")
+    } else {
+        ("", "")
+    };
     println!(
-        "|{dashes}-:|-------:|----------:|----------------:|--------------:|:---------|-------:|:---------------------------------|"
+        "|{dashes}-:|-------:|----------:|{time_sd}----------------:|--------------:|{nps_sd}:---------|-------:|:---------------------------------|"
     );
 }
 
@@ -438,7 +556,7 @@ fn colorize_move(move_str: String, accuracy: MoveAccuracy) -> colored::ColoredSt
 fn execute_test_case(
     test_case: &TestCase,
     search: &mut search::Search,
-    level: Level,
+    limit: CaseLimit,
     selectivity: Selectivity,
     verbose: bool,
     multipv: bool,
@@ -457,7 +575,7 @@ fn execute_test_case(
     } else {
         None
     };
-    let mut options = SearchRunOptions::with_level(level, selectivity).multi_pv(multipv);
+    let mut options = limit.run_options(selectivity).multi_pv(multipv);
     if let Some(ref iters) = iterations {
         let iter_clone = iters.clone();
         let tt = search.tt().clone();
@@ -505,6 +623,10 @@ fn execute_test_case(
         move_accuracy,
         counters: result.counters(),
         pv_moves: result.pv_moves().to_vec(),
+        runs: vec![RunTiming {
+            elapsed,
+            nodes: result.n_nodes(),
+        }],
     };
 
     let verbose_data = iterations
@@ -546,9 +668,19 @@ fn print_test_result(test_case: &TestCase, result: &TestResult, num_width: usize
         test_case.expected_score(),
         test_case.expected_moves_str()
     );
+    let (time_sd, nps_sd) = match result.run_spread() {
+        Some((time_sd, nps_sd)) => (
+            format!(" {time_sd:>8.4} |"),
+            format!(
+                " {:>11} |",
+                (nps_sd.round() as u64).to_formatted_string(&Locale::en)
+            ),
+        ),
+        None => (String::new(), String::new()),
+    };
 
     println!(
-        "| {:>num_width$} | {:^6} | {:>9.4} | {:>15} | {:>13} | {:<8} | {:>6} | {:<32} |",
+        "| {:>num_width$} | {:^6} | {:>9.4} |{time_sd} {:>15} | {:>13} |{nps_sd} {:<8} | {:>6} | {:<32} |",
         test_case.line_number,
         depth_str,
         rounded_secs,
@@ -827,6 +959,13 @@ fn print_verbose_test_case(
         rounded_secs,
         nps.to_formatted_string(&Locale::en),
     );
+    if let Some((time_sd, nps_sd)) = result.run_spread() {
+        println!(
+            "- runs: {}  time σ: {time_sd:.4}s  NPS σ: {}",
+            result.runs.len(),
+            (nps_sd.round() as u64).to_formatted_string(&Locale::en),
+        );
+    }
     println!("- TT fill: {}", tt_fill);
     println!();
 }
@@ -963,21 +1102,113 @@ fn print_multipv_test_result(test_case: &TestCase, result: &TestResult) {
     println!();
 }
 
-/// Execute a section of test cases and return aggregated statistics
+/// Search settings and display mode shared by all sections
+struct SectionOptions {
+    limit: CaseLimit,
+    selectivity: Selectivity,
+    verbose: bool,
+    multipv: bool,
+    /// Print the markdown tables; otherwise only collect the case reports
+    table: bool,
+    /// Number of measured runs of each case
+    runs: u32,
+    /// Number of unmeasured runs of each case before the measured ones
+    warmup: u32,
+}
+
+/// Execute a test case `warmup` times unmeasured and then `runs` times,
+/// returning the last run with its time and nodes averaged over the runs
+fn execute_test_case_runs(
+    test_case: &TestCase,
+    search: &mut search::Search,
+    options: &SectionOptions,
+) -> (TestResult, Vec<IterationData>) {
+    let run = |search: &mut search::Search| {
+        execute_test_case(
+            test_case,
+            search,
+            options.limit,
+            options.selectivity,
+            options.verbose,
+            options.multipv,
+        )
+    };
+    for _ in 0..options.warmup {
+        run(search);
+    }
+
+    let mut runs = Vec::with_capacity(options.runs as usize);
+    let mut last = None;
+    for _ in 0..options.runs.max(1) {
+        let (result, verbose_data) = run(search);
+        runs.extend_from_slice(&result.runs);
+        last = Some((result, verbose_data));
+    }
+    let (mut result, verbose_data) = last.expect("at least one run");
+    let n = runs.len() as u32;
+    result.elapsed = runs.iter().map(|r| r.elapsed).sum::<std::time::Duration>() / n;
+    result.nodes = runs.iter().map(|r| r.nodes).sum::<u64>() / u64::from(n);
+    result.runs = runs;
+    (result, verbose_data)
+}
+
+/// Build the machine-readable report of a test case result
+fn case_report(set: &str, test_case: &TestCase, result: &TestResult) -> CaseReport {
+    let time_ms = result.elapsed.as_secs_f64() * 1000.0;
+    let (time_sd, nps_sd) = result.run_spread().unwrap_or_default();
+    CaseReport {
+        set: set.to_string(),
+        line: test_case.line_number,
+        depth: result.depth,
+        probability: result.selectivity.probability(),
+        score: result.score,
+        expected_score: test_case.expected_score(),
+        score_error: result.score_difference,
+        best_move: if test_case.is_pass() {
+            "PS".to_string()
+        } else {
+            result
+                .pv_line
+                .first()
+                .map_or_else(|| "-".to_string(), |sq| format!("{sq:?}"))
+        },
+        move_rank: match result.move_accuracy {
+            MoveAccuracy::Best => Some(0),
+            MoveAccuracy::SecondBest => Some(1),
+            MoveAccuracy::ThirdBest => Some(2),
+            MoveAccuracy::Other => None,
+        },
+        nodes: result.nodes,
+        time_ms,
+        nps: report::nps(result.nodes, time_ms),
+        time_ms_sd: time_sd * 1000.0,
+        nps_sd: nps_sd.round() as u64,
+    }
+}
+
+/// Execute a section of test cases and return aggregated statistics along
+/// with the report of each case
 fn execute_section(
     section_name: &str,
     test_cases: &[TestCase],
     search: &mut search::Search,
-    level: Level,
-    selectivity: Selectivity,
-    verbose: bool,
-    multipv: bool,
-) -> SearchStats {
-    println!("\n## {section_name} ({} cases)\n", test_cases.len());
+    options: &SectionOptions,
+) -> (SearchStats, Vec<CaseReport>) {
+    let SectionOptions {
+        verbose,
+        multipv,
+        table,
+        runs,
+        ..
+    } = *options;
+    if table {
+        println!("\n## {section_name} ({} cases)\n", test_cases.len());
+    }
 
     let mut stats = SearchStats::default();
+    let mut reports = Vec::with_capacity(test_cases.len());
 
-    let num_width = if verbose || multipv {
+    let num_width = if verbose || multipv || !table {
         0
     } else {
         let max_num = test_cases
@@ -986,14 +1217,17 @@ fn execute_section(
             .max()
             .unwrap_or(0);
         let nw = max_num.to_string().len().max(3);
-        print_header(nw);
+        print_header(nw, runs > 1);
         nw
     };
 
     for test_case in test_cases {
-        let (result, verbose_data) =
-            execute_test_case(test_case, search, level, selectivity, verbose, multipv);
+        let (result, verbose_data) = execute_test_case_runs(test_case, search, options);
         stats.update(&result);
+        reports.push(case_report(section_name, test_case, &result));
+        if !table {
+            continue;
+        }
         if multipv {
             stats.update_multipv(&result, test_case);
             print_multipv_test_result(test_case, &result);
@@ -1004,8 +1238,10 @@ fn execute_section(
         }
     }
 
-    stats.print(verbose, multipv);
-    stats
+    if table {
+        stats.print(verbose, multipv);
+    }
+    (stats, reports)
 }
 
 /// Command line arguments for the evaluation test runner
@@ -1037,6 +1273,12 @@ struct Args {
     #[arg(long)]
     problem_dir: Option<String>,
 
+    /// Custom test suite to run: an OBF or EPD-style (.epd) file, or a
+    /// directory of them. Can be specified multiple times. When given without
+    /// --problem, only the suites are run.
+    #[arg(long)]
+    suite: Vec<PathBuf>,
+
     /// Enable verbose output with iterative deepening progress and search statistics
     #[arg(short, long)]
     verbose: bool,
@@ -1047,30 +1289,85 @@ struct Args {
     /// both are set.
     #[arg(long)]
     multipv: bool,
+
+    /// Output format: markdown tables, or a JSON document or CSV rows with one
+    /// entry per case on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    /// Search each case under a wall-clock budget in milliseconds instead of
+    /// to a fixed depth, allocated like a fixed time per move in a game
+    #[arg(long, value_name = "MS", conflicts_with = "depth", value_parser = clap::value_parser!(u64).range(1..))]
+    time_per_case: Option<u64>,
+
+    /// Number of measured runs of each case. Time and NPS are averaged over
+    /// the runs and reported with their standard deviations.
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+
+    /// Number of unmeasured runs of each case before the measured runs
+    #[arg(long, default_value = "0")]
+    warmup: u32,
+
+    /// JSON output of an earlier run to compare against. Exits with status 1
+    /// if a case loses accuracy or the totals exceed the tolerances.
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Largest accepted increase of the total nodes over the baseline, in percent
+    #[arg(long, default_value = "5", requires = "baseline")]
+    max_nodes_increase: f64,
+
+    /// Largest accepted increase of the total time over the baseline, in percent
+    #[arg(long, default_value = "10", requires = "baseline")]
+    max_time_increase: f64,
 }
 
 fn main() {
     let args = Args::parse();
 
-    let problem_dir = if let Some(ref dir) = args.problem_dir {
-        let path = std::path::PathBuf::from(dir);
-        if !path.is_dir() {
-            eprintln!("Error: Problem directory not found: {dir}");
-            std::process::exit(1);
-        }
-        path
-    } else {
-        find_problem_dir().unwrap_or_else(|| {
-            eprintln!("Error: Cannot find problem directory. Use --problem-dir to specify.");
+    let table = args.output == OutputFormat::Table;
+    if !table && (args.verbose || args.multipv) {
+        eprintln!("Error: --verbose and --multipv require --output table");
+        std::process::exit(1);
+    }
+    let baseline = args.baseline.as_deref().map(|path| {
+        RunReport::load(path).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
             std::process::exit(1);
         })
-    };
+    });
+
+    let mut problem_sets = if args.suite.is_empty() || !args.problem.is_empty() {
+        let problem_dir = if let Some(ref dir) = args.problem_dir {
+            let path = std::path::PathBuf::from(dir);
+            if !path.is_dir() {
+                eprintln!("Error: Problem directory not found: {dir}");
+                std::process::exit(1);
+            }
+            path
+        } else {
+            find_problem_dir().unwrap_or_else(|| {
+                eprintln!("Error: Cannot find problem directory. Use --problem-dir to specify.");
+                std::process::exit(1);
+            })
+        };
 
-    let problem_sets = if args.problem.is_empty() {
-        load_all_problems(&problem_dir)
+        if args.problem.is_empty() {
+            load_all_problems(&problem_dir)
+        } else {
+            load_problems(&args.problem, &problem_dir)
+        }
     } else {
-        load_problems(&args.problem, &problem_dir)
+        Vec::new()
     };
+    match load_suites(&args.suite) {
+        Ok(suites) => problem_sets.extend(suites),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
 
     if problem_sets.is_empty() || problem_sets.iter().all(|ps| ps.cases.is_empty()) {
         eprintln!("Error: No test cases found");
@@ -1079,29 +1376,84 @@ fn main() {
 
     let search_options = SearchOptions::new(args.hash_size as usize).with_threads(args.threads);
     let mut search = search::Search::new(&search_options);
-    let level = Level {
-        mid_depth: args.depth,
-        end_depth: [args.depth; 4],
+    let limit = match args.time_per_case {
+        Some(ms) => CaseLimit::Time(ms),
+        None => CaseLimit::Level(Level {
+            mid_depth: args.depth,
+            end_depth: [args.depth; 4],
+        }),
+    };
+    let section_options = SectionOptions {
+        limit,
+        selectivity: Selectivity::from_u8(args.selectivity),
+        verbose: args.verbose,
+        multipv: args.multipv,
+        table,
+        runs: args.runs,
+        warmup: args.warmup,
     };
-    let selectivity = Selectivity::from_u8(args.selectivity);
 
     let mut overall_stats = SearchStats::default();
+    let mut case_reports = Vec::new();
 
     for problem_set in &problem_sets {
-        let stats = execute_section(
+        let (stats, reports) = execute_section(
             &problem_set.name,
             &problem_set.cases,
             &mut search,
-            level,
-            selectivity,
-            args.verbose,
-            args.multipv,
+            &section_options,
         );
         overall_stats.merge(&stats);
+        case_reports.extend(reports);
     }
 
-    if problem_sets.len() > 1 {
+    if table && problem_sets.len() > 1 {
         println!("\n## Overall ({} cases)", overall_stats.total_count);
         overall_stats.print(args.verbose, args.multipv);
     }
+
+    let run_report = RunReport::new(
+        args.depth,
+        args.time_per_case,
+        args.selectivity,
+        search_options.tt_mb_size,
+        search_options.n_threads,
+        case_reports,
+    )
+    .with_runs(args.runs, args.warmup);
+    let mut stdout = std::io::stdout().lock();
+    let written = match args.output {
+        OutputFormat::Table => Ok(()),
+        OutputFormat::Json => run_report.write_json(&mut stdout),
+        OutputFormat::Csv => run_report.write_csv(&mut stdout),
+    };
+    if let Err(e) = written.and_then(|()| stdout.flush()) {
+        eprintln!("Error: Cannot write results: {e}");
+        std::process::exit(1);
+    }
+    drop(stdout);
+
+    if let Some(baseline) = baseline {
+        let tolerances = Tolerances {
+            nodes: args.max_nodes_increase,
+            time: args.max_time_increase,
+        };
+        // Keep stdout machine-readable outside of table mode
+        let compared = if table {
+            report::compare(&baseline, &run_report, &tolerances, &mut std::io::stdout())
+        } else {
+            report::compare(&baseline, &run_report, &tolerances, &mut std::io::stderr())
+        };
+        match compared {
+            Ok(regressions) if regressions.is_empty() => {}
+            Ok(regressions) => {
+                eprintln!("Error: regression: {}", regressions.join("; "));
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: Cannot write comparison: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
 }
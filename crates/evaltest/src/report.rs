@@ -0,0 +1,378 @@
+//! Machine-readable results and baseline comparison.
+//!
+//! A run can be written as a single JSON document or as CSV rows, one per
+//! test case. The JSON document doubles as a baseline: a later run loads it
+//! with `--baseline` and is compared against it case by case, so changes in
+//! score correctness, nodes and time become visible and regressions fail the
+//! run with a nonzero exit status.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use reversi_core::types::{Depth, Scoref};
+
+/// Score error changes below this are rounding noise of the 0.1 disc scores.
+const SCORE_ERROR_EPSILON: Scoref = 0.05;
+
+/// Output format of a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable markdown tables.
+    #[default]
+    Table,
+    /// A single JSON document on stdout, usable as a baseline.
+    Json,
+    /// One CSV row per test case on stdout.
+    Csv,
+}
+
+/// Result of one test case in machine-readable form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseReport {
+    /// Name of the problem set the case belongs to.
+    pub set: String,
+    /// Line number within the problem file.
+    pub line: usize,
+    pub depth: Depth,
+    /// Selectivity of the deepest iteration in percent.
+    pub probability: i32,
+    /// Searched score from the side to move's perspective.
+    pub score: Scoref,
+    pub expected_score: i32,
+    /// Absolute difference between the searched and the expected score.
+    pub score_error: Scoref,
+    /// First move of the principal variation, or `PS` for pass positions.
+    #[serde(rename = "move")]
+    pub best_move: String,
+    /// Rank of the move among the expected moves (0 is a best move), if it is
+    /// among the top three.
+    pub move_rank: Option<usize>,
+    /// Nodes, time and NPS, averaged over the measured runs.
+    pub nodes: u64,
+    pub time_ms: f64,
+    pub nps: u64,
+    /// Standard deviations of the time and NPS over the measured runs.
+    #[serde(default)]
+    pub time_ms_sd: f64,
+    #[serde(default)]
+    pub nps_sd: u64,
+}
+
+/// Search settings and results of a run.
+#[derive(Serialize, Deserialize)]
+pub struct RunReport {
+    pub depth: Depth,
+    /// Wall-clock budget of each case in milliseconds, replacing `depth`.
+    #[serde(default)]
+    pub time_per_case_ms: Option<u64>,
+    pub selectivity: u8,
+    pub hash_size: usize,
+    pub threads: usize,
+    /// Number of measured and of unmeasured warmup runs of each case.
+    #[serde(default = "default_runs")]
+    pub runs: u32,
+    #[serde(default)]
+    pub warmup: u32,
+    pub cases: Vec<CaseReport>,
+    pub total: Totals,
+}
+
+fn default_runs() -> u32 {
+    1
+}
+
+/// Aggregate results of a run.
+#[derive(Serialize, Deserialize)]
+pub struct Totals {
+    pub cases: usize,
+    pub nodes: u64,
+    pub time_ms: f64,
+    pub nps: u64,
+    pub best_moves: usize,
+    pub exact_scores: usize,
+}
+
+impl Totals {
+    fn of(cases: &[CaseReport]) -> Self {
+        let nodes = cases.iter().map(|c| c.nodes).sum();
+        let time_ms = cases.iter().map(|c| c.time_ms).sum();
+        Self {
+            cases: cases.len(),
+            nodes,
+            time_ms,
+            nps: nps(nodes, time_ms),
+            best_moves: cases.iter().filter(|c| c.move_rank == Some(0)).count(),
+            exact_scores: cases
+                .iter()
+                .filter(|c| c.score_error < SCORE_ERROR_EPSILON)
+                .count(),
+        }
+    }
+}
+
+/// Nodes per second of `nodes` searched in `time_ms`, or 0 without time.
+pub fn nps(nodes: u64, time_ms: f64) -> u64 {
+    if time_ms > 0.0 {
+        (nodes as f64 * 1000.0 / time_ms).round() as u64
+    } else {
+        0
+    }
+}
+
+impl RunReport {
+    pub fn new(
+        depth: Depth,
+        time_per_case_ms: Option<u64>,
+        selectivity: u8,
+        hash_size: usize,
+        threads: usize,
+        cases: Vec<CaseReport>,
+    ) -> Self {
+        let total = Totals::of(&cases);
+        Self {
+            depth,
+            time_per_case_ms,
+            selectivity,
+            hash_size,
+            threads,
+            runs: 1,
+            warmup: 0,
+            cases,
+            total,
+        }
+    }
+
+    pub fn with_runs(self, runs: u32, warmup: u32) -> Self {
+        Self {
+            runs,
+            warmup,
+            ..self
+        }
+    }
+
+    /// Loads a report written with `--output json`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+        serde_json::from_str(&content).map_err(|e| format!("Cannot parse {}: {e}", path.display()))
+    }
+
+    /// Describes how far the cases were searched.
+    fn limit(&self) -> String {
+        match self.time_per_case_ms {
+            Some(ms) => format!("{ms}ms per case"),
+            None => format!("depth {}", self.depth),
+        }
+    }
+
+    pub fn write_json(&self, out: &mut impl Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, self)?;
+        writeln!(out)
+    }
+
+    pub fn write_csv(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "set,line,depth,probability,score,expected_score,score_error,move,move_rank,nodes,time_ms,nps,time_ms_sd,nps_sd"
+        )?;
+        for c in &self.cases {
+            writeln!(
+                out,
+                "{},{},{},{},{:.1},{},{:.1},{},{},{},{:.3},{},{:.3},{}",
+                csv_field(&c.set),
+                c.line,
+                c.depth,
+                c.probability,
+                c.score,
+                c.expected_score,
+                c.score_error,
+                c.best_move,
+                c.move_rank.map_or_else(String::new, |r| r.to_string()),
+                c.nodes,
+                c.time_ms,
+                c.nps,
+                c.time_ms_sd,
+                c.nps_sd
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a CSV field if it contains a separator or a quote.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Largest accepted increases of the totals over the baseline, in percent.
+pub struct Tolerances {
+    pub nodes: f64,
+    pub time: f64,
+}
+
+/// Compares `current` against `baseline` case by case, printing the
+/// differences as a markdown section to `out`, and returns the regressions.
+///
+/// A case regresses when its score error grows or it loses its best move.
+/// Nodes and time only regress in total, over the cases of both runs, since
+/// single cases are too short to measure reliably.
+pub fn compare(
+    baseline: &RunReport,
+    current: &RunReport,
+    tolerances: &Tolerances,
+    out: &mut impl Write,
+) -> io::Result<Vec<String>> {
+    let baseline_cases: HashMap<(&str, usize), &CaseReport> = baseline
+        .cases
+        .iter()
+        .map(|c| ((c.set.as_str(), c.line), c))
+        .collect();
+
+    writeln!(out, "\n## Baseline comparison\n")?;
+    if baseline.limit() != current.limit() || baseline.selectivity != current.selectivity {
+        writeln!(
+            out,
+            "Warning: the baseline was run at {} and selectivity {}, this run at {} and selectivity {}\n",
+            baseline.limit(),
+            baseline.selectivity,
+            current.limit(),
+            current.selectivity
+        )?;
+    }
+    writeln!(
+        out,
+        "| Set | # | Nodes | ΔNodes | Time(ms) | ΔTime | Score error | Move |"
+    )?;
+    writeln!(
+        out,
+        "|:----|--:|------:|-------:|---------:|------:|:------------|:-----|"
+    )?;
+
+    let mut regressions = Vec::new();
+    let mut matched = 0;
+    let (mut base_nodes, mut base_time, mut cur_nodes, mut cur_time) = (0u64, 0.0, 0u64, 0.0);
+    for case in &current.cases {
+        let Some(base) = baseline_cases.get(&(case.set.as_str(), case.line)) else {
+            writeln!(
+                out,
+                "| {} | {} | {} | new | {:.1} | new | {:.1} | {} |",
+                case.set,
+                case.line,
+                case.nodes,
+                case.time_ms,
+                case.score_error,
+                format_rank(case.move_rank)
+            )?;
+            continue;
+        };
+        matched += 1;
+        base_nodes += base.nodes;
+        base_time += base.time_ms;
+        cur_nodes += case.nodes;
+        cur_time += case.time_ms;
+
+        let mut marks = Vec::new();
+        if case.score_error > base.score_error + SCORE_ERROR_EPSILON {
+            marks.push(format!(
+                "score error {:.1} -> {:.1}",
+                base.score_error, case.score_error
+            ));
+        }
+        if base.move_rank == Some(0) && case.move_rank != Some(0) {
+            marks.push(format!(
+                "best move {} -> {}",
+                base.best_move, case.best_move
+            ));
+        }
+        let flag = if marks.is_empty() { "" } else { " **!**" };
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {:.1} | {} | {:.1} -> {:.1}{flag} | {} -> {} |",
+            case.set,
+            case.line,
+            case.nodes,
+            format_change(base.nodes as f64, case.nodes as f64),
+            case.time_ms,
+            format_change(base.time_ms, case.time_ms),
+            base.score_error,
+            case.score_error,
+            format_rank(base.move_rank),
+            format_rank(case.move_rank),
+        )?;
+        regressions.extend(
+            marks
+                .into_iter()
+                .map(|m| format!("{} #{}: {m}", case.set, case.line)),
+        );
+    }
+
+    let missing = baseline.cases.len() - matched;
+    writeln!(out)?;
+    writeln!(out, "- Cases compared: {matched}")?;
+    if missing > 0 {
+        writeln!(out, "- Baseline cases not run: {missing}")?;
+    }
+    writeln!(
+        out,
+        "- Total nodes: {base_nodes} -> {cur_nodes} ({})",
+        format_change(base_nodes as f64, cur_nodes as f64)
+    )?;
+    writeln!(
+        out,
+        "- Total time: {base_time:.1}ms -> {cur_time:.1}ms ({})",
+        format_change(base_time, cur_time)
+    )?;
+
+    if let Some(change) = percent_change(base_nodes as f64, cur_nodes as f64)
+        && change > tolerances.nodes
+    {
+        regressions.push(format!(
+            "total nodes increased by {change:.1}% (limit {:.1}%)",
+            tolerances.nodes
+        ));
+    }
+    if let Some(change) = percent_change(base_time, cur_time)
+        && change > tolerances.time
+    {
+        regressions.push(format!(
+            "total time increased by {change:.1}% (limit {:.1}%)",
+            tolerances.time
+        ));
+    }
+
+    if regressions.is_empty() {
+        writeln!(out, "- Regressions: none")?;
+    } else {
+        writeln!(out, "- Regressions: {}", regressions.len())?;
+        for r in &regressions {
+            writeln!(out, "  - {r}")?;
+        }
+    }
+    Ok(regressions)
+}
+
+/// Change from `before` to `after` in percent, if `before` is positive.
+fn percent_change(before: f64, after: f64) -> Option<f64> {
+    (before > 0.0).then(|| (after - before) / before * 100.0)
+}
+
+fn format_change(before: f64, after: f64) -> String {
+    percent_change(before, after).map_or_else(|| "-".to_string(), |c| format!("{c:+.1}%"))
+}
+
+fn format_rank(rank: Option<usize>) -> &'static str {
+    match rank {
+        Some(0) => "best",
+        Some(1) => "2nd",
+        Some(2) => "3rd",
+        _ => "other",
+    }
+}
@@ -3,9 +3,20 @@
 //! Per-line parsing lives in [`reversi_core::obf`]; this module wraps the
 //! parsed [`ObfPosition`] in a [`TestCase`] (line number + invariant that
 //! test data is present), and handles file/directory/preset discovery.
+//!
+//! Custom suites may also use an EPD-style format (`.epd` files), which lists
+//! only the best moves and the expected score of each position:
+//!
+//! ```text
+//! <board64> <side> bm <move> [<move> ...]; ce <score>; id "<name>";
+//! ```
+//!
+//! `bm` is omitted for pass positions, and other operations such as `id` are
+//! ignored.
 
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use reversi_core::{board::Board, disc::Disc, obf::ObfPosition, square::Square};
 
@@ -132,21 +143,67 @@ pub fn load_all_problems(problem_dir: &Path) -> Vec<ProblemSet> {
     entries.iter().filter_map(|p| load_problem_set(p)).collect()
 }
 
-fn load_problem_set(path: &Path) -> Option<ProblemSet> {
-    match parse_obf_file(path) {
-        Ok(cases) => Some(ProblemSet {
-            name: path
-                .file_stem()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .into_owned(),
-            cases,
-        }),
-        Err(e) => {
-            eprintln!("Warning: Failed to load {}: {e}", path.display());
-            None
+/// Load custom suites. Each path is an OBF or EPD-style file, or a directory
+/// whose `.obf` and `.epd` files are loaded, sorted by filename.
+///
+/// Unlike the bundled problem sets, suites are named explicitly, so a path
+/// that is missing, holds no suite files or fails to parse is an error.
+pub fn load_suites(paths: &[PathBuf]) -> Result<Vec<ProblemSet>, String> {
+    let mut problem_sets = Vec::new();
+    for path in paths {
+        for file in resolve_suite(path)? {
+            problem_sets.push(read_problem_set(&file)?);
         }
     }
+    Ok(problem_sets)
+}
+
+fn resolve_suite(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let read_dir = std::fs::read_dir(path)
+        .map_err(|e| format!("Cannot read suite {}: {e}", path.display()))?;
+    let mut entries: Vec<_> = read_dir
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("obf" | "epd")
+            )
+            .then_some(path)
+        })
+        .collect();
+    if entries.is_empty() {
+        return Err(format!(
+            "Suite directory {} has no .obf or .epd files",
+            path.display()
+        ));
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+fn load_problem_set(path: &Path) -> Option<ProblemSet> {
+    read_problem_set(path)
+        .inspect_err(|e| eprintln!("Warning: Failed to load {}: {e}", path.display()))
+        .ok()
+}
+
+fn read_problem_set(path: &Path) -> Result<ProblemSet, String> {
+    let parse_line = if path.extension().and_then(|e| e.to_str()) == Some("epd") {
+        parse_epd_line
+    } else {
+        ObfPosition::parse
+    };
+    Ok(ProblemSet {
+        name: path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        cases: parse_problem_file(path, parse_line)?,
+    })
 }
 
 fn resolve_specifier(spec: &str, problem_dir: &Path) -> Vec<PathBuf> {
@@ -185,7 +242,10 @@ fn resolve_specifier(spec: &str, problem_dir: &Path) -> Vec<PathBuf> {
     }
 }
 
-fn parse_obf_file(path: &Path) -> Result<Vec<TestCase>, String> {
+fn parse_problem_file(
+    path: &Path,
+    parse_line: fn(&str) -> Result<Option<ObfPosition>, String>,
+) -> Result<Vec<TestCase>, String> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
 
@@ -193,7 +253,7 @@ fn parse_obf_file(path: &Path) -> Result<Vec<TestCase>, String> {
     for (line_idx, line) in content.lines().enumerate() {
         let line_number = line_idx + 1;
         let report = |e: String| format!("{}:{line_number}: {e}", path.display());
-        let Some(pos) = ObfPosition::parse(line).map_err(report)? else {
+        let Some(pos) = parse_line(line).map_err(report)? else {
             continue;
         };
         cases.push(TestCase::new(line_number, pos).map_err(report)?);
@@ -201,6 +261,67 @@ fn parse_obf_file(path: &Path) -> Result<Vec<TestCase>, String> {
     Ok(cases)
 }
 
+/// Parses an EPD-style line, `<board64> <side> bm <moves>; ce <score>; ...`.
+///
+/// Returns `Ok(None)` for blank lines and `%` comments.
+fn parse_epd_line(line: &str) -> Result<Option<ObfPosition>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('%') {
+        return Ok(None);
+    }
+
+    let (board_str, rest) = line
+        .split_at_checked(64)
+        .ok_or_else(|| format!("Board header too short: '{line}'"))?;
+    let rest = rest.trim_start();
+    let side_len = rest.chars().next().map_or(0, char::len_utf8);
+    let (side, operations) = rest.split_at(side_len);
+    let header = ObfPosition::parse(&format!("{board_str} {side}"))?
+        .ok_or_else(|| "Missing board header".to_string())?;
+
+    let mut best_moves = Vec::new();
+    let mut score = None;
+    for operation in operations.split(';') {
+        let operation = operation.trim();
+        let (opcode, operands) = operation
+            .split_once(char::is_whitespace)
+            .unwrap_or((operation, ""));
+        match opcode {
+            "bm" => {
+                for operand in operands.split_whitespace() {
+                    let sq = Square::from_str(operand)
+                        .map_err(|e| format!("Invalid move '{operand}': {e}"))?;
+                    if !header.board.is_legal_move(sq) {
+                        return Err(format!("Illegal best move '{operand}'"));
+                    }
+                    best_moves.push(sq);
+                }
+            }
+            "ce" => {
+                let value = operands.trim();
+                score = Some(
+                    value
+                        .trim_start_matches('+')
+                        .parse()
+                        .map_err(|e| format!("Invalid score '{value}': {e}"))?,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let score = score.ok_or_else(|| "Missing ce operation".to_string())?;
+    if best_moves.is_empty() && header.board.has_legal_moves() {
+        return Err("Missing bm operation".to_string());
+    }
+    Ok(Some(ObfPosition::from_best_moves(
+        header.board,
+        header.side_to_move,
+        &best_moves,
+        score,
+    )))
+}
+
 /// Find the problem directory by searching, in order:
 /// 1. `<exe-dir>/problem`
 /// 2. `./problem`
@@ -227,3 +348,89 @@ pub fn find_problem_dir() -> Option<PathBuf> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Initial position, Black to move: legal moves are D3, C4, F5 and E6.
+    const INITIAL: &str = "---------------------------OX------XO--------------------------- X";
+    /// Black to move has no legal move, White does.
+    const PASS: &str = "OOOOOOOOXXXXXXXX------------------------------------------------ X";
+
+    fn parse_case(line: &str) -> Result<TestCase, String> {
+        TestCase::new(1, parse_epd_line(line)?.expect("a position"))
+    }
+
+    #[test]
+    fn parses_best_moves_and_score() {
+        let case = parse_case(&format!(r#"{INITIAL} bm f5 d3; ce 2; id "start";"#)).unwrap();
+        assert_eq!(case.side_to_move(), Disc::Black);
+        assert_eq!(case.expected_score(), 2);
+        assert_eq!(case.rank_of(Square::F5), Some(0));
+        assert_eq!(case.rank_of(Square::D3), Some(0));
+        assert_eq!(case.rank_of(Square::C4), None);
+        assert!(!case.is_pass());
+    }
+
+    #[test]
+    fn accepts_signed_scores_and_any_operation_order() {
+        let case = parse_case(&format!("{INITIAL} ce +4; bm e6")).unwrap();
+        assert_eq!(case.expected_score(), 4);
+        let case = parse_case(&format!("{INITIAL} bm c4; ce -10;")).unwrap();
+        assert_eq!(case.expected_score(), -10);
+    }
+
+    #[test]
+    fn pass_positions_need_no_best_move() {
+        let case = parse_case(&format!("{PASS} ce -64;")).unwrap();
+        assert!(case.is_pass());
+        assert_eq!(case.expected_score(), -64);
+    }
+
+    #[test]
+    fn rejects_invalid_operations() {
+        let error = |line: String| parse_epd_line(&line).unwrap_err();
+        assert!(error(format!("{INITIAL} bm a1; ce 0;")).contains("Illegal best move"));
+        assert!(error(format!("{INITIAL} bm z9; ce 0;")).contains("Invalid move"));
+        assert!(error(format!("{INITIAL} bm f5;")).contains("Missing ce"));
+        assert!(error(format!("{INITIAL} ce 0;")).contains("Missing bm"));
+        assert!(error(format!("{INITIAL} bm f5; ce many;")).contains("Invalid score"));
+        assert!(parse_epd_line("---- X bm f5; ce 0;").is_err());
+    }
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        assert!(parse_epd_line("").unwrap().is_none());
+        assert!(parse_epd_line("   ").unwrap().is_none());
+        assert!(parse_epd_line("% FFO #40, bm a2").unwrap().is_none());
+    }
+
+    #[test]
+    fn missing_suite_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("evaltest-suite-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // An empty directory and a missing path are errors
+        assert!(load_suites(std::slice::from_ref(&dir)).is_err());
+        assert!(load_suites(&[dir.join("missing.epd")]).is_err());
+
+        let suite = dir.join("custom.epd");
+        std::fs::write(
+            &suite,
+            format!("% custom suite\n{INITIAL} bm f5; ce 0;\n\n{PASS} ce -64;\n"),
+        )
+        .unwrap();
+        let sets = load_suites(std::slice::from_ref(&dir)).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].name, "custom");
+        let lines: Vec<usize> = sets[0].cases.iter().map(|c| c.line_number).collect();
+        assert_eq!(lines, [2, 4]);
+
+        // A suite that fails to parse is an error too
+        std::fs::write(&suite, format!("{INITIAL} bm a1; ce 0;\n")).unwrap();
+        assert!(load_suites(std::slice::from_ref(&suite)).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,369 @@
+//! Game Clock: both players' clocks for a timed game, kept in the backend so
+//! the time shown to the user and the time handed to the engine's time
+//! manager come from the same source.
+//!
+//! The clock is plain state driven by explicit instants, so the Fischer and
+//! byoyomi accounting is tested without sleeping. `lib.rs` owns the
+//! commands, the tick thread and the events.
+
+use std::time::Instant;
+
+use reversi_core::disc::Disc;
+use reversi_core::search::time_control::TimeControlMode;
+use serde::{Deserialize, Serialize};
+
+/// Time control of a timed game.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum TimeControl {
+    /// A time bank per side, increased after every move.
+    Fischer { main_ms: u64, increment_ms: u64 },
+    /// A time bank per side, then a fixed allowance for every move.
+    Byoyomi { main_ms: u64, byoyomi_ms: u64 },
+}
+
+impl TimeControl {
+    fn main_ms(self) -> u64 {
+        match self {
+            TimeControl::Fischer { main_ms, .. } | TimeControl::Byoyomi { main_ms, .. } => main_ms,
+        }
+    }
+}
+
+/// Time left on one side's clock.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TimeLeft {
+    Main(u64),
+    Byoyomi(u64),
+    Flagged,
+}
+
+/// Snapshot of both clocks sent with every tick.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockPayload {
+    /// Time left for the current move: main time, or byoyomi once main time
+    /// is spent
+    pub black_ms: u64,
+    pub white_ms: u64,
+    pub black_in_byoyomi: bool,
+    pub white_in_byoyomi: bool,
+    /// Side whose clock is running: "black", "white" or null
+    pub running: Option<&'static str>,
+    /// Side that ran out of time: "black", "white" or null
+    pub flagged: Option<&'static str>,
+}
+
+/// Clocks saved with a session, with the move in progress charged.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockSnapshot {
+    pub control: TimeControl,
+    /// Main time left for Black and White
+    pub black_ms: u64,
+    pub white_ms: u64,
+    /// Side whose clock was running: "black", "white" or null
+    pub running: Option<String>,
+    /// Side that ran out of time: "black", "white" or null
+    pub flagged: Option<String>,
+}
+
+/// Clocks of both sides.
+#[derive(Clone, Debug)]
+pub struct GameClock {
+    control: TimeControl,
+    /// Main time left for Black and White
+    main_ms: [u64; 2],
+    /// Side whose clock is running and when it started
+    running: Option<(Disc, Instant)>,
+    flagged: Option<Disc>,
+}
+
+fn index(side: Disc) -> usize {
+    if side == Disc::White { 1 } else { 0 }
+}
+
+fn side_name(side: Disc) -> &'static str {
+    if side == Disc::White {
+        "white"
+    } else {
+        "black"
+    }
+}
+
+fn side_from_name(name: &str) -> Option<Disc> {
+    match name {
+        "black" => Some(Disc::Black),
+        "white" => Some(Disc::White),
+        _ => None,
+    }
+}
+
+impl GameClock {
+    /// Creates stopped clocks holding the full main time.
+    pub fn new(control: TimeControl) -> Self {
+        Self {
+            control,
+            main_ms: [control.main_ms(); 2],
+            running: None,
+            flagged: None,
+        }
+    }
+
+    /// Recreates the clocks of `snapshot`, restarting the side that was
+    /// running at `now`.
+    pub fn restore(snapshot: &ClockSnapshot, now: Instant) -> Self {
+        let running = snapshot.running.as_deref().and_then(side_from_name);
+        let flagged = snapshot.flagged.as_deref().and_then(side_from_name);
+        Self {
+            control: snapshot.control,
+            main_ms: [snapshot.black_ms, snapshot.white_ms],
+            running: running
+                .filter(|_| flagged.is_none())
+                .map(|side| (side, now)),
+            flagged,
+        }
+    }
+
+    /// Returns the clocks at `now` for saving. The move in progress is
+    /// charged to the main time; byoyomi restarts with the next move.
+    pub fn snapshot(&self, now: Instant) -> ClockSnapshot {
+        let main_left = |side| match self.time_left(side, now) {
+            TimeLeft::Main(ms) => ms,
+            TimeLeft::Byoyomi(_) | TimeLeft::Flagged => 0,
+        };
+        ClockSnapshot {
+            control: self.control,
+            black_ms: main_left(Disc::Black),
+            white_ms: main_left(Disc::White),
+            running: self.running.map(|(side, _)| side_name(side).to_string()),
+            flagged: self.flagged.map(|side| side_name(side).to_string()),
+        }
+    }
+
+    /// Returns whether a clock is running.
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Starts the clock of `side`, stopping the other one without charging
+    /// it. Does nothing once a side has flagged.
+    pub fn start(&mut self, side: Disc, now: Instant) {
+        if self.flagged.is_none() {
+            self.running = Some((side, now));
+        }
+    }
+
+    /// Ends the running side's move, charging its time and adding any
+    /// increment, and starts the opponent's clock.
+    pub fn switch(&mut self, now: Instant) {
+        if let Some((side, started)) = self.running.take()
+            && self.charge(side, elapsed_ms(started, now))
+        {
+            self.running = Some((side.opposite(), now));
+        }
+    }
+
+    /// Stops the running clock, charging the time spent without an
+    /// increment.
+    pub fn stop(&mut self, now: Instant) {
+        if let Some((side, started)) = self.running.take() {
+            let elapsed = elapsed_ms(started, now);
+            if self.time_left_after(side, elapsed) == TimeLeft::Flagged {
+                self.flagged = Some(side);
+            } else {
+                let main_ms = &mut self.main_ms[index(side)];
+                *main_ms = main_ms.saturating_sub(elapsed);
+            }
+        }
+    }
+
+    /// Flags the running side if its time is up. Returns the side that just
+    /// flagged.
+    pub fn check_flag(&mut self, now: Instant) -> Option<Disc> {
+        let (side, started) = self.running?;
+        if self.time_left_after(side, elapsed_ms(started, now)) == TimeLeft::Flagged {
+            self.running = None;
+            self.flagged = Some(side);
+            return self.flagged;
+        }
+        None
+    }
+
+    /// Returns the engine time control for the side whose clock is running
+    /// at `now`.
+    pub fn search_mode(&self, now: Instant) -> Option<TimeControlMode> {
+        let (side, started) = self.running?;
+        let main_time_ms = self.main_ms[index(side)].saturating_sub(elapsed_ms(started, now));
+        Some(match self.control {
+            TimeControl::Fischer { increment_ms, .. } => TimeControlMode::Fischer {
+                main_time_ms,
+                increment_ms,
+            },
+            TimeControl::Byoyomi { byoyomi_ms, .. } => {
+                let overflow = elapsed_ms(started, now).saturating_sub(self.main_ms[index(side)]);
+                TimeControlMode::JapaneseByo {
+                    main_time_ms,
+                    time_per_move_ms: byoyomi_ms.saturating_sub(overflow),
+                }
+            }
+        })
+    }
+
+    /// Returns a snapshot of both clocks at `now`.
+    pub fn payload(&self, now: Instant) -> ClockPayload {
+        let left = |side| match self.time_left(side, now) {
+            TimeLeft::Main(ms) => (ms, false),
+            TimeLeft::Byoyomi(ms) => (ms, true),
+            TimeLeft::Flagged => (0, false),
+        };
+        let (black_ms, black_in_byoyomi) = left(Disc::Black);
+        let (white_ms, white_in_byoyomi) = left(Disc::White);
+        ClockPayload {
+            black_ms,
+            white_ms,
+            black_in_byoyomi,
+            white_in_byoyomi,
+            running: self.running.map(|(side, _)| side_name(side)),
+            flagged: self.flagged.map(side_name),
+        }
+    }
+
+    fn time_left(&self, side: Disc, now: Instant) -> TimeLeft {
+        if self.flagged == Some(side) {
+            return TimeLeft::Flagged;
+        }
+        let elapsed = match self.running {
+            Some((running, started)) if running == side => elapsed_ms(started, now),
+            _ => 0,
+        };
+        self.time_left_after(side, elapsed)
+    }
+
+    /// Time left for `side` after spending `elapsed_ms` on the current move.
+    fn time_left_after(&self, side: Disc, elapsed_ms: u64) -> TimeLeft {
+        let main_ms = self.main_ms[index(side)];
+        if elapsed_ms < main_ms {
+            return TimeLeft::Main(main_ms - elapsed_ms);
+        }
+        match self.control {
+            TimeControl::Byoyomi { byoyomi_ms, .. } if elapsed_ms - main_ms < byoyomi_ms => {
+                TimeLeft::Byoyomi(byoyomi_ms - (elapsed_ms - main_ms))
+            }
+            _ => TimeLeft::Flagged,
+        }
+    }
+
+    /// Charges a completed move to `side`. Returns `false`, flagging the
+    /// side, if the move overran.
+    fn charge(&mut self, side: Disc, elapsed_ms: u64) -> bool {
+        let left = self.time_left_after(side, elapsed_ms);
+        let main_ms = &mut self.main_ms[index(side)];
+        match (left, self.control) {
+            (TimeLeft::Flagged, _) => {
+                self.flagged = Some(side);
+                return false;
+            }
+            (TimeLeft::Main(ms), TimeControl::Fischer { increment_ms, .. }) => {
+                *main_ms = ms + increment_ms;
+            }
+            (TimeLeft::Main(ms), _) => *main_ms = ms,
+            (TimeLeft::Byoyomi(_), _) => *main_ms = 0,
+        }
+        true
+    }
+}
+
+fn elapsed_ms(started: Instant, now: Instant) -> u64 {
+    now.saturating_duration_since(started).as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn after(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn fischer_switch_adds_the_increment() {
+        let t0 = Instant::now();
+        let mut clock = GameClock::new(TimeControl::Fischer {
+            main_ms: 10_000,
+            increment_ms: 2_000,
+        });
+        clock.start(Disc::Black, t0);
+        clock.switch(after(t0, 4_000));
+
+        let payload = clock.payload(after(t0, 5_000));
+        assert_eq!(payload.black_ms, 8_000);
+        assert_eq!(payload.white_ms, 9_000);
+        assert_eq!(payload.running, Some("white"));
+        assert_eq!(
+            clock.search_mode(after(t0, 5_000)),
+            Some(TimeControlMode::Fischer {
+                main_time_ms: 9_000,
+                increment_ms: 2_000
+            })
+        );
+    }
+
+    #[test]
+    fn byoyomi_follows_the_main_time_and_flags_overruns() {
+        let t0 = Instant::now();
+        let mut clock = GameClock::new(TimeControl::Byoyomi {
+            main_ms: 5_000,
+            byoyomi_ms: 3_000,
+        });
+        clock.start(Disc::Black, t0);
+        let payload = clock.payload(after(t0, 6_000));
+        assert_eq!((payload.black_ms, payload.black_in_byoyomi), (2_000, true));
+
+        clock.switch(after(t0, 6_000));
+        clock.switch(after(t0, 7_000));
+        assert_eq!(clock.payload(after(t0, 7_000)).black_ms, 3_000);
+
+        assert_eq!(clock.check_flag(after(t0, 9_999)), None);
+        assert_eq!(clock.check_flag(after(t0, 10_000)), Some(Disc::Black));
+        let payload = clock.payload(after(t0, 10_000));
+        assert_eq!((payload.running, payload.flagged), (None, Some("black")));
+    }
+
+    #[test]
+    fn stop_charges_without_an_increment() {
+        let t0 = Instant::now();
+        let mut clock = GameClock::new(TimeControl::Fischer {
+            main_ms: 10_000,
+            increment_ms: 2_000,
+        });
+        clock.start(Disc::White, t0);
+        clock.stop(after(t0, 1_500));
+        assert!(!clock.is_running());
+        assert_eq!(clock.search_mode(after(t0, 1_500)), None);
+        assert_eq!(clock.payload(after(t0, 9_000)).white_ms, 8_500);
+    }
+
+    #[test]
+    fn restore_resumes_a_snapshot() {
+        let t0 = Instant::now();
+        let mut clock = GameClock::new(TimeControl::Fischer {
+            main_ms: 10_000,
+            increment_ms: 2_000,
+        });
+        clock.start(Disc::Black, t0);
+        clock.switch(after(t0, 1_000));
+        let snapshot = clock.snapshot(after(t0, 4_000));
+        assert_eq!((snapshot.black_ms, snapshot.white_ms), (11_000, 7_000));
+
+        let t1 = after(t0, 60_000);
+        let restored = GameClock::restore(&snapshot, t1);
+        let payload = restored.payload(after(t1, 500));
+        assert_eq!((payload.white_ms, payload.running), (6_500, Some("white")));
+    }
+}
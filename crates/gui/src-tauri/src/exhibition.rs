@@ -0,0 +1,177 @@
+//! Exhibition: two engines of the app playing each other.
+//!
+//! Each side gets its own engine instance, so the sides can differ in level
+//! and weight files. The game itself runs on match-runner's game logic,
+//! with the engines behind a plain move-choosing closure so the loop can be
+//! tested without weights. `lib.rs` owns the engines, the thread and the
+//! events.
+
+use match_runner::game::GameState;
+use match_runner::match_runner::MatchResult;
+use reversi_core::board::Board;
+use reversi_core::disc::Disc;
+use reversi_core::search::options::SearchOptions;
+use reversi_core::square::Square;
+use reversi_core::types::Scoref;
+use serde::Deserialize;
+
+/// Transposition table size of an exhibition engine unless configured.
+const DEFAULT_HASH_MB: usize = 64;
+
+/// Configuration of one exhibition engine.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineConfig {
+    pub level: usize,
+    /// Main network weights; the bundled weights when omitted
+    pub eval_path: Option<String>,
+    /// Small network weights; the bundled weights when omitted
+    pub eval_sm_path: Option<String>,
+    /// Transposition table size in MiB
+    pub hash_size: Option<usize>,
+    /// Search threads; half the CPUs when omitted, so both engines fit
+    pub threads: Option<usize>,
+}
+
+impl EngineConfig {
+    /// Returns the options to create this engine with.
+    pub fn search_options(&self) -> SearchOptions {
+        let cpus = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+        SearchOptions::new(self.hash_size.unwrap_or(DEFAULT_HASH_MB).clamp(1, 16384))
+            .with_threads(Some(self.threads.unwrap_or((cpus / 2).max(1))))
+            .with_eval_paths(self.eval_path.as_ref(), self.eval_sm_path.as_ref())
+    }
+}
+
+/// A move chosen by an engine.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EngineMove {
+    pub sq: Square,
+    /// Score for the mover, in discs
+    pub score: Scoref,
+    pub depth: u32,
+}
+
+/// A move of the exhibition game, as reported to the UI.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayedMove {
+    pub side: Disc,
+    /// Square played, `None` for a pass
+    pub sq: Option<Square>,
+    /// The engine's move, `None` for passes and opening moves
+    pub engine_move: Option<EngineMove>,
+    pub black_discs: u32,
+    pub white_discs: u32,
+}
+
+/// Plays a game from the standard start through `opening`, then asks
+/// `choose` for every move until the game ends, reporting each move to
+/// `on_move`. Returns `None` if `is_cancelled` stops the game first.
+///
+/// # Errors
+///
+/// Returns `Err` if an opening move is illegal or `choose` fails or picks
+/// an illegal move.
+pub fn play<C, O, K>(
+    opening: &[Square],
+    mut choose: C,
+    mut on_move: O,
+    is_cancelled: K,
+) -> Result<Option<MatchResult>, String>
+where
+    C: FnMut(Disc, &Board) -> Result<EngineMove, String>,
+    O: FnMut(&PlayedMove),
+    K: Fn() -> bool,
+{
+    let mut game = GameState::new();
+    let mut report = |game: &GameState, side, sq, engine_move| {
+        let (black_discs, white_discs) = game.get_score();
+        on_move(&PlayedMove {
+            side,
+            sq,
+            engine_move,
+            black_discs,
+            white_discs,
+        });
+    };
+
+    for &sq in opening {
+        if !game.board().has_legal_moves() {
+            let side = game.side_to_move();
+            game.make_move(None)?;
+            report(&game, side, None, None);
+        }
+        let side = game.side_to_move();
+        game.make_move(Some(sq))
+            .map_err(|e| format!("Opening: {e}"))?;
+        report(&game, side, Some(sq), None);
+    }
+
+    while !game.is_game_over() {
+        if is_cancelled() {
+            return Ok(None);
+        }
+        let side = game.side_to_move();
+        if !game.board().has_legal_moves() {
+            game.make_move(None)?;
+            report(&game, side, None, None);
+            continue;
+        }
+        let engine_move = choose(side, game.board())?;
+        game.make_move(Some(engine_move.sq))?;
+        report(&game, side, Some(engine_move.sq), Some(engine_move));
+    }
+
+    let (black_discs, white_discs) = game.get_score();
+    Ok(Some(MatchResult::from_disc_counts(
+        black_discs,
+        white_discs,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays the lowest legal square.
+    fn first_legal(_side: Disc, board: &Board) -> Result<EngineMove, String> {
+        let sq = board.get_moves().lsb_square().ok_or("no legal move")?;
+        Ok(EngineMove {
+            sq,
+            score: 0.0,
+            depth: 1,
+        })
+    }
+
+    #[test]
+    fn play_reports_every_move_until_the_end() {
+        let mut moves = Vec::new();
+        let result = play(&[Square::F5], first_legal, |m| moves.push(*m), || false)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(moves[0].sq, Some(Square::F5));
+        assert_eq!(moves[0].engine_move, None);
+        assert!(
+            moves[1..]
+                .iter()
+                .all(|m| m.sq.is_none() || m.engine_move.is_some())
+        );
+
+        let last = moves.last().unwrap();
+        let expected = MatchResult::from_disc_counts(last.black_discs, last.white_discs);
+        assert_eq!(
+            (result.result, result.score),
+            (expected.result, expected.score)
+        );
+    }
+
+    #[test]
+    fn play_stops_when_cancelled_and_rejects_bad_openings() {
+        assert!(play(&[], first_legal, |_| (), || true).unwrap().is_none());
+        assert!(play(&[Square::A1], first_legal, |_| (), || false).is_err());
+
+        let chooser_error = play(&[], |_, _| Err("engine gone".to_string()), |_| (), || false);
+        assert_eq!(chooser_error.err().as_deref(), Some("engine gone"));
+    }
+}
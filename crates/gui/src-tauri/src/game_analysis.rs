@@ -21,6 +21,7 @@ pub struct Analysis {
 
 /// One per-move Game Analysis result (CONTEXT.md → Game Analysis, Played
 /// Score, Score Loss).
+#[derive(Clone, Debug)]
 pub struct GameAnalysisProgress {
     pub move_index: usize,
     pub best_move: Square,
@@ -0,0 +1,301 @@
+//! Game Database: finished games saved to SQLite for later review.
+//!
+//! A saved game is replayed from the standard start before it is stored, so
+//! only complete, legal games enter the database and the final disc count
+//! is derived from the moves rather than trusted from the caller. Commands
+//! in `lib.rs` own the connection and the Tauri wiring.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use reversi_core::board::Board;
+use reversi_core::square::Square;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use serde::{Deserialize, Serialize};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    id          INTEGER PRIMARY KEY,
+    black       TEXT    NOT NULL,
+    white       TEXT    NOT NULL,
+    moves       TEXT    NOT NULL,
+    black_discs INTEGER NOT NULL,
+    white_discs INTEGER NOT NULL,
+    played_at   INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS games_played_at ON games (played_at);
+";
+
+/// Outcome of a game, from the final disc count.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GameResult {
+    BlackWin,
+    WhiteWin,
+    Draw,
+}
+
+impl GameResult {
+    /// SQL condition selecting games with this result.
+    fn condition(self) -> &'static str {
+        match self {
+            GameResult::BlackWin => "black_discs > white_discs",
+            GameResult::WhiteWin => "black_discs < white_discs",
+            GameResult::Draw => "black_discs = white_discs",
+        }
+    }
+}
+
+/// A stored game. `moves` uses the wire format of the analysis commands:
+/// one square per move and `--` for a pass.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredGame {
+    pub id: i64,
+    pub black: String,
+    pub white: String,
+    pub moves: Vec<String>,
+    pub black_discs: u32,
+    pub white_discs: u32,
+    pub result: GameResult,
+    /// Seconds since the Unix epoch when the game was saved
+    pub played_at: i64,
+}
+
+/// Filters for listing games. Unset fields match every game.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GameFilter {
+    /// Substring of either player's name, case-insensitive
+    pub player: Option<String>,
+    pub result: Option<GameResult>,
+    /// Earliest `played_at`, inclusive
+    pub since: Option<i64>,
+    /// Latest `played_at`, inclusive
+    pub until: Option<i64>,
+    /// Maximum number of games, newest first
+    pub limit: Option<u32>,
+}
+
+/// Connection to the game database.
+pub struct GameDatabase {
+    conn: Connection,
+}
+
+impl GameDatabase {
+    /// Opens the database at `path`, creating it and its tables if missing.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open game database {}: {e}", path.display()))?;
+        Self::init(conn)
+    }
+
+    /// Opens a database that lives only as long as the connection.
+    #[cfg(test)]
+    fn open_in_memory() -> Result<Self, String> {
+        Self::init(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(Self { conn })
+    }
+
+    /// Saves a finished game played from the standard start and returns its
+    /// id.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if a move is illegal or the game is not over.
+    pub fn save(&self, black: &str, white: &str, moves: &[String]) -> Result<i64, String> {
+        let (black_discs, white_discs) = final_disc_count(moves)?;
+        self.conn
+            .execute(
+                "INSERT INTO games (black, white, moves, black_discs, white_discs, played_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    black,
+                    white,
+                    moves.join(" "),
+                    black_discs,
+                    white_discs,
+                    unix_now()
+                ],
+            )
+            .map_err(db_error)?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Lists the games matching `filter`, newest first.
+    pub fn list(&self, filter: &GameFilter) -> Result<Vec<StoredGame>, String> {
+        let mut sql = "SELECT * FROM games WHERE 1 = 1".to_string();
+        if filter.player.is_some() {
+            sql.push_str(
+                " AND (instr(lower(black), lower(?1)) > 0 OR instr(lower(white), lower(?1)) > 0)",
+            );
+        }
+        if let Some(result) = filter.result {
+            sql.push_str(" AND ");
+            sql.push_str(result.condition());
+        }
+        if filter.since.is_some() {
+            sql.push_str(" AND played_at >= ?2");
+        }
+        if filter.until.is_some() {
+            sql.push_str(" AND played_at <= ?3");
+        }
+        sql.push_str(" ORDER BY played_at DESC, id DESC LIMIT ?4");
+
+        let mut stmt = self.conn.prepare(&sql).map_err(db_error)?;
+        let limit = filter.limit.map_or(-1, i64::from);
+        let rows = stmt
+            .query_map(
+                params![filter.player, filter.since, filter.until, limit],
+                read_game,
+            )
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Loads the game with `id`.
+    pub fn load(&self, id: i64) -> Result<StoredGame, String> {
+        self.conn
+            .query_row("SELECT * FROM games WHERE id = ?1", [id], read_game)
+            .optional()
+            .map_err(db_error)?
+            .ok_or_else(|| format!("No game with id {id}"))
+    }
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Game database error: {e}")
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+fn read_game(row: &Row<'_>) -> rusqlite::Result<StoredGame> {
+    let moves: String = row.get("moves")?;
+    let black_discs: u32 = row.get("black_discs")?;
+    let white_discs: u32 = row.get("white_discs")?;
+    Ok(StoredGame {
+        id: row.get("id")?,
+        black: row.get("black")?,
+        white: row.get("white")?,
+        moves: moves.split_whitespace().map(str::to_string).collect(),
+        black_discs,
+        white_discs,
+        result: match black_discs.cmp(&white_discs) {
+            std::cmp::Ordering::Greater => GameResult::BlackWin,
+            std::cmp::Ordering::Less => GameResult::WhiteWin,
+            std::cmp::Ordering::Equal => GameResult::Draw,
+        },
+        played_at: row.get("played_at")?,
+    })
+}
+
+/// Replays `moves` from the standard start and returns the final Black and
+/// White disc counts.
+fn final_disc_count(moves: &[String]) -> Result<(u32, u32), String> {
+    let mut board = Board::new();
+    let mut black_to_move = true;
+
+    for notation in moves {
+        if notation == "--" {
+            if board.has_legal_moves() || board.is_game_over() {
+                return Err("Pass recorded while a move or nothing was possible".to_string());
+            }
+            board = board.switch_players();
+        } else {
+            let square: Square = notation
+                .parse()
+                .map_err(|e| format!("Invalid move notation '{notation}': {e}"))?;
+            board = board
+                .try_make_move(square)
+                .ok_or_else(|| format!("Illegal move '{square}' in move list"))?;
+        }
+        black_to_move = !black_to_move;
+    }
+
+    if !board.is_game_over() {
+        return Err("Only finished games can be saved".to_string());
+    }
+    let (mover, other) = (board.player().count(), board.opponent().count());
+    Ok(if black_to_move {
+        (mover, other)
+    } else {
+        (other, mover)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The shortest possible game: Black wins 13-0 after nine moves.
+    fn shortest_game() -> Vec<String> {
+        ["d3", "c3", "b3", "d2", "e1", "d6", "d7", "e3", "f4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn final_disc_count_scores_a_finished_game() {
+        assert_eq!(final_disc_count(&shortest_game()), Ok((13, 0)));
+    }
+
+    #[test]
+    fn final_disc_count_rejects_unfinished_and_illegal_games() {
+        let err = final_disc_count(&shortest_game()[..4]).unwrap_err();
+        assert!(err.contains("finished"), "got: {err}");
+        let err = final_disc_count(&["a1".to_string()]).unwrap_err();
+        assert!(err.contains("Illegal move"), "got: {err}");
+    }
+
+    #[test]
+    fn saved_games_are_listed_and_loaded() {
+        let db = GameDatabase::open_in_memory().unwrap();
+        let id = db.save("Alice", "Level 10", &shortest_game()).unwrap();
+
+        let game = db.load(id).unwrap();
+        assert_eq!(game.moves, shortest_game());
+        assert_eq!(game.result, GameResult::BlackWin);
+
+        let all = db.list(&GameFilter::default()).unwrap();
+        assert_eq!(all, vec![game]);
+        assert!(db.load(id + 1).is_err());
+    }
+
+    #[test]
+    fn list_applies_filters() {
+        let db = GameDatabase::open_in_memory().unwrap();
+        db.save("Alice", "Level 10", &shortest_game()).unwrap();
+        db.save("Bob", "Level 20", &shortest_game()).unwrap();
+
+        let by_player = GameFilter {
+            player: Some("alice".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(db.list(&by_player).unwrap().len(), 1);
+
+        let white_wins = GameFilter {
+            result: Some(GameResult::WhiteWin),
+            ..Default::default()
+        };
+        assert!(db.list(&white_wins).unwrap().is_empty());
+
+        let limited = GameFilter {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(db.list(&limited).unwrap()[0].black, "Bob");
+    }
+}
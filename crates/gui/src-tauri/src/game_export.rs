@@ -0,0 +1,116 @@
+//! Game Export: the current game written in the interchange formats of
+//! other Othello software, via the core `formats` module.
+//!
+//! The UI sends the moves in the wire format of the analysis commands; the
+//! game is replayed here so that only legal games are exported and the
+//! result is recorded once the game is over. `lib.rs` owns the save dialog
+//! and the file write.
+
+use chrono::{Datelike, Local};
+use reversi_core::formats::{GameRecord, ggf, transcript, wthor};
+use reversi_core::square::Square;
+use serde::Deserialize;
+
+/// File format to export to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Ggf,
+    Wthor,
+    Transcript,
+}
+
+impl ExportFormat {
+    /// Name and extension of the save dialog's file filter.
+    pub fn filter(self) -> (&'static str, &'static str) {
+        match self {
+            ExportFormat::Ggf => ("GGF game", "ggf"),
+            ExportFormat::Wthor => ("WTHOR database", "wtb"),
+            ExportFormat::Transcript => ("Transcript", "txt"),
+        }
+    }
+}
+
+/// Builds the record of a game played from the standard start, dated
+/// today. `moves` holds one square per move and `--` for a pass.
+///
+/// # Errors
+///
+/// Returns `Err` if a move is malformed or illegal.
+pub fn build_record(
+    moves: &[String],
+    black_name: Option<String>,
+    white_name: Option<String>,
+) -> Result<GameRecord, String> {
+    let moves = moves
+        .iter()
+        .filter(|notation| notation.as_str() != "--")
+        .map(|notation| {
+            notation
+                .parse::<Square>()
+                .map_err(|e| format!("Invalid move notation '{notation}': {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut record = GameRecord::from_moves(moves);
+    let state = record.replay()?;
+    if state.is_game_over() {
+        let (black, white) = state.get_score();
+        record.result = Some(black as i32 - white as i32);
+    }
+    record.black_name = black_name;
+    record.white_name = white_name;
+    record.date = Some(Local::now().format("%Y.%m.%d").to_string());
+    Ok(record)
+}
+
+/// Encodes `record` as the contents of a `format` file.
+///
+/// # Errors
+///
+/// Returns `Err` if the format cannot hold the game.
+pub fn encode(record: &GameRecord, format: ExportFormat) -> Result<Vec<u8>, String> {
+    match format {
+        ExportFormat::Ggf => ggf::format(record).map(|text| (text + "\n").into_bytes()),
+        ExportFormat::Wthor => {
+            wthor::write(std::slice::from_ref(record), Local::now().year() as u16)
+        }
+        ExportFormat::Transcript => Ok((transcript::format(&record.moves) + "\n").into_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn moves(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn build_record_skips_passes_and_scores_finished_games() {
+        let record = build_record(
+            &moves(&["d3", "c3", "b3", "d2", "e1", "d6", "d7", "e3", "f4"]),
+            Some("Alice".to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(record.moves.len(), 9);
+        assert_eq!(record.result, Some(13));
+
+        let record = build_record(&moves(&["f5", "d6"]), None, None).unwrap();
+        assert_eq!(record.result, None);
+        assert!(build_record(&moves(&["f5", "f5"]), None, None).is_err());
+    }
+
+    #[test]
+    fn encode_writes_each_format() {
+        let record = build_record(&moves(&["f5", "d6", "c3"]), None, None).unwrap();
+        let text = |format| String::from_utf8(encode(&record, format).unwrap()).unwrap();
+        assert_eq!(text(ExportFormat::Transcript), "f5d6c3\n");
+        assert!(text(ExportFormat::Ggf).contains("B[F5]W[D6]B[C3]"));
+
+        let games = wthor::read(&encode(&record, ExportFormat::Wthor).unwrap()).unwrap();
+        assert_eq!(games[0].moves, record.moves);
+    }
+}
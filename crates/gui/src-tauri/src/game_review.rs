@@ -0,0 +1,217 @@
+//! Game Review: the post-game summary built from a Game Analysis.
+//!
+//! Every move is classified by its Score Loss with the thresholds the
+//! evaluation graph uses, and each player gets an accuracy figure. The
+//! analysis itself comes from [`crate::game_analysis`]; `review_game_command`
+//! in `lib.rs` runs it at review depth.
+
+use reversi_core::disc::Disc;
+use reversi_core::types::Scoref;
+use serde::Serialize;
+
+use crate::game_analysis::{GameAnalysisMove, GameAnalysisProgress};
+
+/// Largest Score Loss of a move counted as best, allowing for rounding.
+const BEST_MAX_LOSS: Scoref = 0.5;
+
+/// Score Loss above which a move is dubious (the graph's dubious marker).
+const DUBIOUS_MIN_LOSS: Scoref = 2.0;
+
+/// Score Loss above which a move is a blunder (the graph's blunder marker).
+const BLUNDER_MIN_LOSS: Scoref = 6.0;
+
+/// Score Loss, in discs, that cuts a move's accuracy to 1/e.
+const ACCURACY_SCALE: Scoref = 10.0;
+
+/// Quality of a move by its Score Loss.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MoveClass {
+    Best,
+    Good,
+    Dubious,
+    Blunder,
+}
+
+impl MoveClass {
+    /// Classifies a move that lost `score_loss` discs.
+    pub fn from_loss(score_loss: Scoref) -> Self {
+        if score_loss > BLUNDER_MIN_LOSS {
+            MoveClass::Blunder
+        } else if score_loss > DUBIOUS_MIN_LOSS {
+            MoveClass::Dubious
+        } else if score_loss > BEST_MAX_LOSS {
+            MoveClass::Good
+        } else {
+            MoveClass::Best
+        }
+    }
+}
+
+/// Accuracy of a move in percent: 100 for the best move, falling off
+/// exponentially with the Score Loss.
+fn move_accuracy(score_loss: Scoref) -> Scoref {
+    100.0 * (-score_loss.max(0.0) / ACCURACY_SCALE).exp()
+}
+
+/// One reviewed move.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewedMove {
+    /// Index into the reviewed move list
+    pub move_index: usize,
+    /// "black" or "white"
+    pub side: &'static str,
+    pub played_move: String,
+    pub best_move: String,
+    pub played_score: Scoref,
+    pub best_score: Scoref,
+    pub score_loss: Scoref,
+    pub depth: u32,
+    pub class: MoveClass,
+}
+
+/// Review totals for one player.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerSummary {
+    /// Moves reviewed, passes excluded
+    pub moves: u32,
+    pub best: u32,
+    pub good: u32,
+    pub dubious: u32,
+    pub blunders: u32,
+    pub average_loss: Scoref,
+    /// Mean move accuracy in percent; 100 without moves
+    pub accuracy: Scoref,
+}
+
+/// Post-game review: every move in play order and both players' totals.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameReview {
+    pub moves: Vec<ReviewedMove>,
+    pub black: PlayerSummary,
+    pub white: PlayerSummary,
+}
+
+fn summarize<'a>(moves: impl Iterator<Item = &'a ReviewedMove>) -> PlayerSummary {
+    let mut summary = PlayerSummary::default();
+    let (mut total_loss, mut total_accuracy) = (0.0, 0.0);
+    for reviewed in moves {
+        summary.moves += 1;
+        match reviewed.class {
+            MoveClass::Best => summary.best += 1,
+            MoveClass::Good => summary.good += 1,
+            MoveClass::Dubious => summary.dubious += 1,
+            MoveClass::Blunder => summary.blunders += 1,
+        }
+        total_loss += reviewed.score_loss;
+        total_accuracy += move_accuracy(reviewed.score_loss);
+    }
+    let round = |x: Scoref| (x * 10.0).round() / 10.0;
+    if summary.moves == 0 {
+        summary.accuracy = 100.0;
+    } else {
+        summary.average_loss = round(total_loss / summary.moves as Scoref);
+        summary.accuracy = round(total_accuracy / summary.moves as Scoref);
+    }
+    summary
+}
+
+/// Builds the review of `moves`, whose first move is `first_side`'s, from
+/// the Game Analysis of those moves, in any order.
+pub fn build_review(
+    first_side: Disc,
+    moves: &[GameAnalysisMove],
+    analyses: &[GameAnalysisProgress],
+) -> GameReview {
+    let side_of = |move_index: usize| {
+        if (first_side == Disc::White) != (move_index % 2 == 1) {
+            Disc::White
+        } else {
+            Disc::Black
+        }
+    };
+    let mut reviewed: Vec<ReviewedMove> = analyses
+        .iter()
+        .filter_map(|analysis| {
+            let GameAnalysisMove::Play(played) = *moves.get(analysis.move_index)? else {
+                return None;
+            };
+            Some(ReviewedMove {
+                move_index: analysis.move_index,
+                side: if side_of(analysis.move_index) == Disc::White {
+                    "white"
+                } else {
+                    "black"
+                },
+                played_move: played.to_string(),
+                best_move: analysis.best_move.to_string(),
+                played_score: analysis.played_score,
+                best_score: analysis.best_score,
+                score_loss: analysis.score_loss,
+                depth: analysis.depth,
+                class: MoveClass::from_loss(analysis.score_loss),
+            })
+        })
+        .collect();
+    reviewed.sort_by_key(|m| m.move_index);
+
+    GameReview {
+        black: summarize(reviewed.iter().filter(|m| m.side == "black")),
+        white: summarize(reviewed.iter().filter(|m| m.side == "white")),
+        moves: reviewed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reversi_core::square::Square;
+
+    fn analysis(move_index: usize, score_loss: Scoref) -> GameAnalysisProgress {
+        GameAnalysisProgress {
+            move_index,
+            best_move: Square::F5,
+            best_score: 0.0,
+            played_score: -score_loss,
+            score_loss,
+            depth: 20,
+        }
+    }
+
+    #[test]
+    fn moves_are_classified_by_score_loss() {
+        assert_eq!(MoveClass::from_loss(0.0), MoveClass::Best);
+        assert_eq!(MoveClass::from_loss(0.4), MoveClass::Best);
+        assert_eq!(MoveClass::from_loss(2.0), MoveClass::Good);
+        assert_eq!(MoveClass::from_loss(4.0), MoveClass::Dubious);
+        assert_eq!(MoveClass::from_loss(6.5), MoveClass::Blunder);
+    }
+
+    #[test]
+    fn review_attributes_moves_across_passes() {
+        let moves = [
+            GameAnalysisMove::Play(Square::F5),
+            GameAnalysisMove::Play(Square::D6),
+            GameAnalysisMove::Pass,
+            GameAnalysisMove::Play(Square::C3),
+        ];
+        // Newest first, as the analysis reports them
+        let analyses = [analysis(3, 8.0), analysis(1, 3.0), analysis(0, 0.0)];
+        let review = build_review(Disc::Black, &moves, &analyses);
+
+        let sides: Vec<_> = review
+            .moves
+            .iter()
+            .map(|m| (m.move_index, m.side))
+            .collect();
+        assert_eq!(sides, vec![(0, "black"), (1, "white"), (3, "white")]);
+        assert_eq!((review.black.moves, review.black.best), (1, 1));
+        assert_eq!(review.black.accuracy, 100.0);
+        assert_eq!((review.white.dubious, review.white.blunders), (1, 1));
+        assert_eq!(review.white.average_loss, 5.5);
+        assert!(review.white.accuracy < review.black.accuracy);
+    }
+}
@@ -0,0 +1,187 @@
+//! Search Jobs: engine requests queued in arrival order.
+//!
+//! Every engine search takes a [`JobTicket`] and waits for its turn before
+//! touching the shared engine, so concurrent commands run one after another
+//! in the order they were issued instead of racing for the engine lock.
+//! Each job has an id that tags its progress events and lets the caller
+//! cancel it while it waits or runs. `lib.rs` owns the engine and the
+//! commands.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// Identifies one search job.
+pub type JobId = u64;
+
+/// Where a cancelled job was when it was cancelled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CancelledJob {
+    /// Waiting for its turn; it will not run.
+    Pending,
+    /// Running; the caller has to abort the engine search.
+    Running,
+}
+
+#[derive(Default)]
+struct QueueState {
+    next_id: JobId,
+    /// Jobs waiting for their turn, oldest first
+    pending: VecDeque<JobId>,
+    running: Option<JobId>,
+    cancelled: HashSet<JobId>,
+}
+
+/// FIFO queue of search jobs.
+#[derive(Default)]
+pub struct JobQueue {
+    state: Mutex<QueueState>,
+    turn: Condvar,
+}
+
+/// A queued job. Dropping the ticket ends the job, whether it ran or not.
+pub struct JobTicket {
+    queue: Arc<JobQueue>,
+    id: JobId,
+}
+
+impl JobQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        // The state is updated in single steps, so it stays consistent even
+        // if a holder panicked.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues a new job behind every job already queued.
+    pub fn enqueue(self: &Arc<Self>) -> JobTicket {
+        let mut state = self.lock();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.pending.push_back(id);
+        JobTicket {
+            queue: self.clone(),
+            id,
+        }
+    }
+
+    /// Cancels job `id`. Returns `None` if no such job is queued or running.
+    pub fn cancel(&self, id: JobId) -> Option<CancelledJob> {
+        let mut state = self.lock();
+        if state.running == Some(id) {
+            state.cancelled.insert(id);
+            return Some(CancelledJob::Running);
+        }
+        let position = state.pending.iter().position(|&pending| pending == id)?;
+        state.pending.remove(position);
+        state.cancelled.insert(id);
+        drop(state);
+        self.turn.notify_all();
+        Some(CancelledJob::Pending)
+    }
+
+    /// Returns whether job `id` has been cancelled.
+    pub fn is_cancelled(&self, id: JobId) -> bool {
+        self.lock().cancelled.contains(&id)
+    }
+}
+
+impl JobTicket {
+    /// Returns the job id.
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// Blocks until every earlier job has finished and marks this job as
+    /// running.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err` if the job was cancelled while it waited.
+    pub fn wait_turn(&self) -> Result<(), String> {
+        let mut state = self.queue.lock();
+        loop {
+            if state.cancelled.contains(&self.id) {
+                return Err(format!("Search job {} was cancelled", self.id));
+            }
+            if state.running.is_none() && state.pending.front() == Some(&self.id) {
+                state.pending.pop_front();
+                state.running = Some(self.id);
+                return Ok(());
+            }
+            state = self
+                .queue
+                .turn
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Returns whether this job has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.queue.is_cancelled(self.id)
+    }
+}
+
+impl Drop for JobTicket {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        if state.running == Some(self.id) {
+            state.running = None;
+        }
+        state.pending.retain(|&pending| pending != self.id);
+        state.cancelled.remove(&self.id);
+        drop(state);
+        self.queue.turn.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn jobs_run_in_arrival_order() {
+        let queue = Arc::new(JobQueue::new());
+        let first = queue.enqueue();
+        let second = queue.enqueue();
+        assert!(second.id() > first.id());
+
+        first.wait_turn().unwrap();
+        let (tx, rx) = mpsc::channel();
+        let waiter = std::thread::spawn(move || {
+            second.wait_turn().unwrap();
+            tx.send(second.id()).unwrap();
+        });
+        assert!(rx.recv_timeout(Duration::from_millis(50)).is_err());
+
+        drop(first);
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        waiter.join().unwrap();
+    }
+
+    #[test]
+    fn cancel_skips_pending_jobs_and_flags_running_ones() {
+        let queue = Arc::new(JobQueue::new());
+        let running = queue.enqueue();
+        let pending = queue.enqueue();
+        let next = queue.enqueue();
+        running.wait_turn().unwrap();
+
+        assert_eq!(queue.cancel(pending.id()), Some(CancelledJob::Pending));
+        assert!(pending.wait_turn().is_err());
+
+        assert_eq!(queue.cancel(running.id()), Some(CancelledJob::Running));
+        assert!(running.is_cancelled());
+        drop(running);
+
+        next.wait_turn().unwrap();
+        assert!(!next.is_cancelled());
+        assert_eq!(queue.cancel(next.id() + 1), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Kibitzing: continuous analysis while two humans play.
+//!
+//! With kibitzing on, every position set by the UI is searched multi-PV
+//! until the next move arrives, for whichever side is to move. Multi-PV
+//! progress reports one root move at a time, so [`KibitzLines`] keeps the
+//! latest line of every move to stream the whole candidate list. `lib.rs`
+//! owns the search job and the events.
+
+use reversi_core::search::SearchProgress;
+use reversi_core::square::Square;
+use reversi_core::types::{Depth, Scoref};
+
+use crate::jobs::JobId;
+
+/// Kibitz switch and the search job serving it.
+#[derive(Default)]
+pub struct Kibitz {
+    enabled: bool,
+    job: Option<JobId>,
+}
+
+impl Kibitz {
+    /// Returns whether kibitzing is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables kibitzing. Returns the running kibitz job to
+    /// stop, if any.
+    pub fn set_enabled(&mut self, enabled: bool) -> Option<JobId> {
+        self.enabled = enabled;
+        self.job.take()
+    }
+
+    /// Records that job `job` analyzes the current position.
+    pub fn start(&mut self, job: JobId) {
+        self.job = Some(job);
+    }
+
+    /// Returns the kibitz job to stop before another search runs, if any.
+    pub fn take_job(&mut self) -> Option<JobId> {
+        self.job.take()
+    }
+}
+
+/// Latest result for one candidate move.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KibitzLine {
+    pub sq: Square,
+    /// Score for the side to move, in discs
+    pub score: Scoref,
+    pub depth: Depth,
+    pub pv_line: Vec<Square>,
+}
+
+/// Candidate moves of one position, best first.
+#[derive(Debug, Default)]
+pub struct KibitzLines {
+    lines: Vec<KibitzLine>,
+}
+
+impl KibitzLines {
+    /// Records the line `progress` reports for its root move.
+    pub fn update(&mut self, progress: &SearchProgress) {
+        let line = KibitzLine {
+            sq: progress.best_move,
+            score: progress.score,
+            depth: progress.depth,
+            pv_line: progress.pv_line.clone(),
+        };
+        match self.lines.iter_mut().find(|l| l.sq == line.sq) {
+            Some(existing) => *existing = line,
+            None => self.lines.push(line),
+        }
+        // Moves already searched at the current depth rank above moves
+        // still holding a shallower score.
+        self.lines
+            .sort_by(|a, b| b.depth.cmp(&a.depth).then(b.score.total_cmp(&a.score)));
+    }
+
+    /// Returns the candidates, best first.
+    pub fn lines(&self) -> &[KibitzLine] {
+        &self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(sq: Square, score: Scoref, depth: Depth) -> SearchProgress {
+        SearchProgress {
+            depth,
+            target_depth: 60,
+            score,
+            best_move: sq,
+            probability: 100,
+            nodes: 0,
+            pv_line: vec![sq],
+            is_endgame: false,
+            counters: Default::default(),
+        }
+    }
+
+    #[test]
+    fn lines_keep_the_latest_result_per_move() {
+        let mut lines = KibitzLines::default();
+        lines.update(&progress(Square::F5, 1.0, 4));
+        lines.update(&progress(Square::D6, 3.0, 4));
+        lines.update(&progress(Square::C4, -2.0, 4));
+        let order = |lines: &KibitzLines| lines.lines().iter().map(|l| l.sq).collect::<Vec<_>>();
+        assert_eq!(order(&lines), vec![Square::D6, Square::F5, Square::C4]);
+
+        // The next iteration re-scores D6 first; it leads until the others
+        // catch up.
+        lines.update(&progress(Square::D6, 0.5, 5));
+        assert_eq!(order(&lines), vec![Square::D6, Square::F5, Square::C4]);
+        lines.update(&progress(Square::F5, 2.0, 5));
+        assert_eq!(order(&lines), vec![Square::F5, Square::D6, Square::C4]);
+        assert_eq!(lines.lines().len(), 3);
+    }
+}
@@ -1,11 +1,13 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, TryLockError};
 
+use reversi_core::book::{Book, BookMove};
+use reversi_core::cpu;
 use reversi_core::disc::Disc;
 use reversi_core::level::get_level;
 use reversi_core::probcut::Selectivity;
 use reversi_core::search::options::SearchOptions;
-use reversi_core::search::search_result::SearchResult;
+use reversi_core::search::search_result::{PvMove, SearchResult};
 use reversi_core::search::{SearchRunOptions, time_control::TimeControlMode};
 use reversi_core::square::Square;
 use reversi_core::types::Scoref;
@@ -13,10 +15,32 @@ use reversi_core::{board, search};
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, State};
 
+mod clock;
+mod exhibition;
 mod game_analysis;
+mod game_db;
+mod game_export;
+mod game_review;
+mod jobs;
+mod kibitz;
+mod ponder;
+mod session;
+
+use jobs::{CancelledJob, JobId, JobQueue, JobTicket};
 
 const SELECTIVITY: Selectivity = Selectivity::Level1;
 
+/// Default level of the re-analysis behind `review_game_command`, deeper
+/// than the levels games are usually played and analyzed at.
+const REVIEW_LEVEL: usize = 18;
+
+/// Level of the shallow multi-PV search behind `hint_command`.
+const HINT_LEVEL: usize = 6;
+
+/// Most empty squares `solve_endgame_command` will solve; beyond this an
+/// exact solve takes too long to badge the position interactively.
+const SOLVE_MAX_EMPTIES: u32 = 24;
+
 /// The current game-analysis generation (CONTEXT.md → Engine Search).
 ///
 /// A monotonically increasing counter behind one interface: a run
@@ -48,10 +72,140 @@ impl GameAnalysisGeneration {
     }
 }
 
+/// A position set by `set_position_command`, searched by the engine
+/// commands when they are called without a board string.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Position {
+    board: board::Board,
+    side_to_move: Disc,
+}
+
+/// Parses a side name: `black`/`b`/`X` or `white`/`w`/`O`.
+fn parse_side(side: &str) -> Result<Disc, String> {
+    match side.trim().to_ascii_lowercase().as_str() {
+        "black" | "b" | "x" => Ok(Disc::Black),
+        "white" | "w" | "o" => Ok(Disc::White),
+        other => Err(format!("Invalid side to move: '{other}'")),
+    }
+}
+
+impl Position {
+    /// Parses a 64-character OBF board field, with `X` for Black and `O`
+    /// for White, and a side to move (see [`parse_side`]), rejecting boards
+    /// no game can reach.
+    fn parse(obf: &str, side_to_move: &str) -> Result<Self, String> {
+        let side_to_move = parse_side(side_to_move)?;
+        let board = board::Board::from_string(obf.trim(), side_to_move)
+            .map_err(|e| format!("Invalid board string: {e}"))?;
+        board
+            .validate()
+            .map_err(|e| format!("Invalid position: {e}"))?;
+        Ok(Self {
+            board,
+            side_to_move,
+        })
+    }
+}
+
+/// The game clock and whether a tick thread is serving it. Both live under
+/// one lock so the tick thread cannot exit just as a clock is restarted.
+#[derive(Default)]
+struct ClockState {
+    clock: Option<clock::GameClock>,
+    ticking: bool,
+}
+
+/// A running exhibition game and what stopping it takes.
+struct ExhibitionHandle {
+    cancelled: Arc<std::sync::atomic::AtomicBool>,
+    /// Thread pools of both engines, once created
+    thread_pools: Arc<Mutex<Vec<Arc<search::threading::ThreadPool>>>>,
+}
+
+impl ExhibitionHandle {
+    fn stop(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        if let Ok(pools) = self.thread_pools.lock() {
+            pools.iter().for_each(|pool| pool.abort_search());
+        }
+    }
+}
+
+/// Interval between `clock-tick` events while a clock runs.
+const CLOCK_TICK: std::time::Duration = std::time::Duration::from_millis(100);
+
 struct AppState {
     search: Arc<Mutex<search::Search>>,
     thread_pool: Arc<search::threading::ThreadPool>,
     game_analysis_run_id: Arc<GameAnalysisGeneration>,
+    position: Mutex<Option<Position>>,
+    /// Game database, opened on first use
+    game_db: Mutex<Option<game_db::GameDatabase>>,
+    game_db_path: std::path::PathBuf,
+    /// Opening book loaded by `load_book_command`
+    book: Mutex<Option<Book>>,
+    clock: Arc<Mutex<ClockState>>,
+    /// Queue every engine search waits in
+    jobs: Arc<JobQueue>,
+    ponder: Mutex<ponder::Ponder>,
+    kibitz: Mutex<kibitz::Kibitz>,
+    /// Game in progress, saved on exit
+    session: Mutex<Option<session::Session>>,
+    exhibition: Mutex<Option<ExhibitionHandle>>,
+    exhibition_id: AtomicU64,
+}
+
+/// Returns the engine time control of the side whose clock is running.
+fn clock_search_mode(clock: &Mutex<ClockState>) -> Option<TimeControlMode> {
+    let clock = clock.lock().ok()?;
+    clock.clock.as_ref()?.search_mode(std::time::Instant::now())
+}
+
+impl AppState {
+    fn lock_ponder(&self) -> Result<std::sync::MutexGuard<'_, ponder::Ponder>, String> {
+        self.ponder
+            .lock()
+            .map_err(|e| format!("Ponder state unavailable: {e}"))
+    }
+
+    fn lock_kibitz(&self) -> Result<std::sync::MutexGuard<'_, kibitz::Kibitz>, String> {
+        self.kibitz
+            .lock()
+            .map_err(|e| format!("Kibitz state unavailable: {e}"))
+    }
+
+    /// Cancels search job `job`, aborting the engine if it is running.
+    fn stop_job(&self, job: JobId) {
+        if self.jobs.cancel(job) == Some(CancelledJob::Running) {
+            self.thread_pool.abort_search();
+        }
+    }
+
+    /// Stops the ponder and kibitz searches, if any are queued or running.
+    fn stop_background_searches(&self) {
+        let ponder = self.ponder.lock().ok().and_then(|mut p| p.take_job());
+        let kibitz = self.kibitz.lock().ok().and_then(|mut k| k.take_job());
+        ponder
+            .into_iter()
+            .chain(kibitz)
+            .for_each(|job| self.stop_job(job));
+    }
+
+    /// Resolves the board an engine command searches: `board_string` when
+    /// given (always from the mover's perspective, `X` to move), otherwise
+    /// the stored position.
+    fn search_board(&self, board_string: Option<String>) -> Result<board::Board, String> {
+        match board_string {
+            Some(board_string) => board::Board::from_string(&board_string, Disc::Black)
+                .map_err(|e| format!("Invalid board string: {e}")),
+            None => self
+                .position
+                .lock()
+                .map_err(|e| format!("Position unavailable: {e}"))?
+                .map(|position| position.board)
+                .ok_or_else(|| "No board string given and no position set".to_string()),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -64,11 +218,14 @@ pub struct AIMoveResult {
     pub depth: u32,
     pub acc: i32,
     pub time_taken: u64,
+    /// Whether the position was the one pondered, when pondering
+    pub ponder_hit: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchProgressPayload {
+    pub job_id: JobId,
     pub best_move: String,
     pub row: i32,
     pub col: i32,
@@ -89,9 +246,45 @@ struct SolverProgressPayload {
     progress: SearchProgressPayload,
 }
 
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HintPayload {
+    pub best_move: String,
+    pub row: i32,
+    pub col: i32,
+    pub score: Scoref,
+    pub pv_line: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndgameSolvePayload {
+    pub best_move: Option<usize>,
+    /// Exact final disc difference for the side to move
+    pub score: i32,
+    pub pv_line: String,
+    pub nodes: u64,
+    pub time_taken: u64,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BookMovePayload {
+    pub best_move: String,
+    pub row: i32,
+    pub col: i32,
+    /// Number of book games that played the move
+    pub count: u32,
+    /// Share of the position's book games that played the move, in 0..=1
+    pub weight: f32,
+    /// Average final disc difference for the mover, when known
+    pub score: Option<Scoref>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GameAnalysisProgressPayload {
+    pub job_id: JobId,
     pub move_index: usize,
     pub best_move: String,
     pub best_score: Scoref,
@@ -104,8 +297,12 @@ fn round_score(score: Scoref) -> Scoref {
     (score * 10.0).round() / 10.0
 }
 
-fn build_progress_payload(progress: &search::SearchProgress) -> SearchProgressPayload {
+fn build_progress_payload(
+    progress: &search::SearchProgress,
+    job_id: JobId,
+) -> SearchProgressPayload {
     SearchProgressPayload {
+        job_id,
         depth: progress.depth,
         target_depth: progress.target_depth,
         score: round_score(progress.score),
@@ -124,6 +321,63 @@ fn build_progress_payload(progress: &search::SearchProgress) -> SearchProgressPa
     }
 }
 
+fn build_hints(pv_moves: &[PvMove], n: usize) -> Vec<HintPayload> {
+    pv_moves
+        .iter()
+        .take(n)
+        .map(|pv| HintPayload {
+            best_move: format!("{}", pv.sq),
+            row: pv.sq as i32 / 8,
+            col: pv.sq as i32 % 8,
+            score: round_score(pv.score),
+            pv_line: pv
+                .pv_line
+                .iter()
+                .map(|sq| format!("{}", sq))
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
+        .collect()
+}
+
+fn build_kibitz_candidates(lines: &[kibitz::KibitzLine]) -> Vec<KibitzCandidatePayload> {
+    lines
+        .iter()
+        .map(|line| KibitzCandidatePayload {
+            best_move: line.sq.to_string(),
+            row: line.sq as i32 / 8,
+            col: line.sq as i32 % 8,
+            score: round_score(line.score),
+            depth: line.depth,
+            pv_line: line
+                .pv_line
+                .iter()
+                .map(|sq| format!("{}", sq))
+                .collect::<Vec<_>>()
+                .join(" "),
+        })
+        .collect()
+}
+
+fn build_book_moves(moves: &[BookMove]) -> Vec<BookMovePayload> {
+    let total: u64 = moves.iter().map(|m| m.count as u64).sum();
+    moves
+        .iter()
+        .map(|m| BookMovePayload {
+            best_move: format!("{}", m.sq),
+            row: m.sq as i32 / 8,
+            col: m.sq as i32 % 8,
+            count: m.count,
+            weight: if total > 0 {
+                (m.count as f64 / total as f64) as f32
+            } else {
+                0.0
+            },
+            score: m.average_score().map(round_score),
+        })
+        .collect()
+}
+
 fn build_game_analysis(result: SearchResult) -> Result<game_analysis::Analysis, String> {
     match (result.best_move(), result.score()) {
         (Some(best_move), Some(score)) => Ok(game_analysis::Analysis {
@@ -171,33 +425,109 @@ where
         .map_err(|e| e.to_string())?
 }
 
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct KibitzCandidatePayload {
+    pub best_move: String,
+    pub row: i32,
+    pub col: i32,
+    pub score: Scoref,
+    pub depth: u32,
+    pub pv_line: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KibitzPayload {
+    pub job_id: JobId,
+    /// Side the candidates are for: "black" or "white"
+    pub side_to_move: &'static str,
+    /// Candidates, best first; scores are for `side_to_move`
+    pub candidates: Vec<KibitzCandidatePayload>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExhibitionMovePayload {
+    pub exhibition_id: u64,
+    /// "black" or "white"
+    pub side: &'static str,
+    /// Square played, or `--` for a pass
+    pub played_move: String,
+    /// Engine's score for the mover; absent for passes and opening moves
+    pub score: Option<Scoref>,
+    pub depth: Option<u32>,
+    pub black_discs: u32,
+    pub white_discs: u32,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExhibitionFinishedPayload {
+    pub exhibition_id: u64,
+    /// Absent if the game was stopped or failed
+    pub result: Option<game_db::GameResult>,
+    /// Final disc difference for Black, empties to the winner
+    pub score: Option<i32>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SearchJobPayload {
+    job_id: JobId,
+    /// Command that queued the job, e.g. `ai_move`
+    kind: &'static str,
+}
+
+/// Queues a search job and announces its id with `search-job-queued`, so
+/// the UI can match progress events and cancel the job. Ponder and kibitz
+/// searches never hold up another search: they are stopped first.
+fn queue_search_job(state: &AppState, app: &AppHandle, kind: &'static str) -> JobTicket {
+    state.stop_background_searches();
+    let ticket = state.jobs.enqueue();
+    let _ = app.emit(
+        "search-job-queued",
+        SearchJobPayload {
+            job_id: ticket.id(),
+            kind,
+        },
+    );
+    ticket
+}
+
 /// Runs one search on the single shared engine (CONTEXT.md → Engine Search):
-/// parse the board, take the engine lock, run with the caller's options, and
-/// hand the result + elapsed time to `map_result` — all inside one
-/// `spawn_blocking`. Concentrates the clone / spawn_blocking / parse / lock
+/// queue a `kind` job, wait for its turn, run on `board` with the caller's
+/// options, and hand the result + elapsed time to `map_result` — all inside
+/// one `spawn_blocking`. Concentrates the clone / spawn_blocking / queue
 /// scaffold every engine-search command otherwise repeats; callers only
 /// describe the search (options) and translate the result.
 ///
-/// `build_options` and `map_result` run on the blocking thread, so the
-/// `SearchResult` never crosses the task boundary (matching the prior
-/// per-command code).
+/// `build_options` receives the job id for tagging progress and runs once
+/// the job's turn has come, so time controls are read when the search
+/// starts rather than when it was queued. `build_options` and `map_result`
+/// run on the blocking thread, so the `SearchResult` never crosses the task
+/// boundary (matching the prior per-command code).
 async fn run_engine_search<R, B, M>(
-    search: Arc<Mutex<search::Search>>,
-    board_string: String,
+    state: &AppState,
+    app: &AppHandle,
+    kind: &'static str,
+    board: board::Board,
     build_options: B,
     map_result: M,
 ) -> Result<R, String>
 where
     R: Send + 'static,
-    B: FnOnce() -> SearchRunOptions + Send + 'static,
+    B: FnOnce(JobId) -> SearchRunOptions + Send + 'static,
     M: FnOnce(&SearchResult, u64) -> R + Send + 'static,
 {
+    let search = state.search.clone();
+    let ticket = queue_search_job(state, app, kind);
     spawn_blocking_result(move || {
-        let board = board::Board::from_string(&board_string, Disc::Black)
-            .map_err(|e| format!("Invalid board string: {e}"))?;
+        ticket.wait_turn()?;
         let start_time = std::time::Instant::now();
         let mut search_guard = lock_search(&search)?;
-        let options = build_options();
+        let options = build_options(ticket.id());
         let result = search_guard.run(&board, &options);
         let elapsed_ms = start_time.elapsed().as_millis() as u64;
         Ok(map_result(&result, elapsed_ms))
@@ -288,26 +618,62 @@ async fn abort_ai_search_command(state: State<'_, AppState>) -> Result<(), Strin
     abort_and_wait(state.thread_pool.clone()).await
 }
 
+/// Stores the position the engine commands search when they are called
+/// without a board string, restarting the kibitz analysis when it is on.
+#[tauri::command]
+fn set_position_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    obf: String,
+    side_to_move: String,
+) -> Result<(), String> {
+    let position = Position::parse(&obf, &side_to_move)?;
+    *state
+        .position
+        .lock()
+        .map_err(|e| format!("Position unavailable: {e}"))? = Some(position);
+    if state.lock_kibitz()?.is_enabled() {
+        start_kibitz(&state, &app)?;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn ai_move_command(
     state: State<'_, AppState>,
     app: AppHandle,
-    board_string: String,
+    board_string: Option<String>,
     level: usize,
     time_limit: Option<u64>,
     remaining_time: Option<u64>,
 ) -> Result<AIMoveResult, String> {
-    if remaining_time.is_none() && time_limit.is_none() {
+    let use_clock = remaining_time.is_none() && time_limit.is_none();
+    if use_clock {
         validate_level(level)?;
     }
-    run_engine_search(
-        state.search.clone(),
-        board_string,
-        move || {
+    let board = state.search_board(board_string)?;
+    let ponder_hit = state.lock_ponder()?.resolve(&board);
+    let clock = state.clock.clone();
+    let emitter = app.clone();
+    let (result, predicted) = run_engine_search(
+        &state,
+        &app,
+        "ai_move",
+        board,
+        move |job_id| {
             let callback = move |progress: search::SearchProgress| {
-                let _ = app.emit("ai-move-progress", build_progress_payload(&progress));
+                let _ = emitter.emit(
+                    "ai-move-progress",
+                    build_progress_payload(&progress, job_id),
+                );
             };
-            if let Some(remaining_ms) = remaining_time {
+            // A running backend clock decides the time for the move unless
+            // the caller passes its own limits; it is read once the job's
+            // turn has come, so time spent queued is accounted for.
+            let clock_mode = use_clock.then(|| clock_search_mode(&clock)).flatten();
+            if let Some(mode) = clock_mode {
+                SearchRunOptions::with_time(mode, SELECTIVITY).callback(callback)
+            } else if let Some(remaining_ms) = remaining_time {
                 SearchRunOptions::with_time(
                     TimeControlMode::Fischer {
                         main_time_ms: remaining_ms,
@@ -328,9 +694,9 @@ async fn ai_move_command(
                 SearchRunOptions::with_level(get_level(level), SELECTIVITY).callback(callback)
             }
         },
-        |result, elapsed_ms| {
+        move |result, elapsed_ms| {
             let best_move = result.best_move();
-            AIMoveResult {
+            let move_result = AIMoveResult {
                 best_move: best_move.map(|square| square.index()),
                 row: best_move.map(|square| square as i32 / 8).unwrap_or(-1),
                 col: best_move.map(|square| square as i32 % 8).unwrap_or(-1),
@@ -338,26 +704,181 @@ async fn ai_move_command(
                 depth: result.depth(),
                 acc: result.get_probability(),
                 time_taken: elapsed_ms,
-            }
+                ponder_hit,
+            };
+            (move_result, ponder::predict(&board, result.pv_line()))
         },
     )
-    .await
+    .await?;
+
+    if let Some(predicted) = predicted
+        && state.lock_ponder()?.is_enabled()
+    {
+        start_ponder(&state, &app, predicted)?;
+    }
+    Ok(result)
+}
+
+/// Queues an open-ended `kind` search of `board` that runs until another
+/// search is queued, and returns its job id without waiting for it.
+/// `on_progress` sees every iteration.
+fn spawn_background_search<F>(
+    state: &AppState,
+    app: &AppHandle,
+    kind: &'static str,
+    board: board::Board,
+    multi_pv: bool,
+    on_progress: F,
+) -> JobId
+where
+    F: Fn(JobId, &search::SearchProgress) + Send + Sync + 'static,
+{
+    let ticket = queue_search_job(state, app, kind);
+    let job_id = ticket.id();
+    let search = state.search.clone();
+    let thread_pool = state.thread_pool.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if ticket.wait_turn().is_err() {
+            return;
+        }
+        let Ok(mut search_guard) = lock_search(&search) else {
+            return;
+        };
+        // A stop can land before the search clears the abort flag, so the
+        // search also checks for it after every iteration.
+        let ticket = Arc::new(ticket);
+        let cancelled = ticket.clone();
+        let callback = move |progress: search::SearchProgress| {
+            if cancelled.is_cancelled() {
+                thread_pool.abort_search();
+                return;
+            }
+            on_progress(job_id, &progress);
+        };
+        let options = SearchRunOptions::with_time(TimeControlMode::Infinite, SELECTIVITY)
+            .multi_pv(multi_pv)
+            .callback(callback);
+        search_guard.run(&board, &options);
+    });
+    job_id
+}
+
+/// Searches `predicted` until another search is queued, filling the
+/// transposition table while the human thinks. Does not wait for the
+/// search.
+fn start_ponder(state: &AppState, app: &AppHandle, predicted: board::Board) -> Result<(), String> {
+    let job = spawn_background_search(state, app, "ponder", predicted, false, |_, _| ());
+    state.lock_ponder()?.start(predicted, job);
+    Ok(())
+}
+
+/// Analyzes the stored position for the side to move until another search
+/// is queued, streaming every candidate move with `kibitz-progress`. Does
+/// nothing without a position or once the game is over.
+fn start_kibitz(state: &AppState, app: &AppHandle) -> Result<(), String> {
+    let Some(position) = *state
+        .position
+        .lock()
+        .map_err(|e| format!("Position unavailable: {e}"))?
+    else {
+        return Ok(());
+    };
+    let (mut board, mut side) = (position.board, position.side_to_move);
+    if board.is_game_over() {
+        return Ok(());
+    }
+    if !board.has_legal_moves() {
+        board = board.switch_players();
+        side = side.opposite();
+    }
+    let side_to_move = if side == Disc::White {
+        "white"
+    } else {
+        "black"
+    };
+
+    let lines = Mutex::new(kibitz::KibitzLines::default());
+    let emitter = app.clone();
+    let job = spawn_background_search(
+        state,
+        app,
+        "kibitz",
+        board,
+        true,
+        move |job_id, progress| {
+            let Ok(mut lines) = lines.lock() else {
+                return;
+            };
+            lines.update(progress);
+            let _ = emitter.emit(
+                "kibitz-progress",
+                KibitzPayload {
+                    job_id,
+                    side_to_move,
+                    candidates: build_kibitz_candidates(lines.lines()),
+                },
+            );
+        },
+    );
+    state.lock_kibitz()?.start(job);
+    Ok(())
+}
+
+/// Turns kibitzing on or off. While on, the stored position is analyzed
+/// for both players and the analysis restarts whenever the position is
+/// set.
+#[tauri::command]
+fn set_kibitz_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let job = state.lock_kibitz()?.set_enabled(enabled);
+    if let Some(job) = job {
+        state.stop_job(job);
+    }
+    if enabled {
+        start_kibitz(&state, &app)?;
+    }
+    Ok(())
+}
+
+/// Turns pondering on or off and returns the ponder statistics. Turning it
+/// off stops any ponder search; turning it on takes effect after the
+/// engine's next move.
+#[tauri::command]
+fn set_ponder_command(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<ponder::PonderStats, String> {
+    let mut ponder = state.lock_ponder()?;
+    if let Some(job) = ponder.set_enabled(enabled) {
+        state.stop_job(job);
+    }
+    Ok(ponder.stats())
 }
 
 #[tauri::command]
 async fn analyze_command(
     state: State<'_, AppState>,
     app: AppHandle,
-    board_string: String,
+    board_string: Option<String>,
     level: usize,
 ) -> Result<(), String> {
     validate_level(level)?;
+    let board = state.search_board(board_string)?;
+    let emitter = app.clone();
     run_engine_search(
-        state.search.clone(),
-        board_string,
-        move || {
+        &state,
+        &app,
+        "analyze",
+        board,
+        move |job_id| {
             let callback = move |progress: search::SearchProgress| {
-                let _ = app.emit("ai-move-progress", build_progress_payload(&progress));
+                let _ = emitter.emit(
+                    "ai-move-progress",
+                    build_progress_payload(&progress, job_id),
+                );
             };
             SearchRunOptions::with_level(get_level(level), SELECTIVITY)
                 .multi_pv(true)
@@ -368,11 +889,108 @@ async fn analyze_command(
     .await
 }
 
+/// Returns the `n` best moves for `board_string` (the stored position when
+/// omitted), best first, from a shallow multi-PV search. Emits no progress
+/// events, so it can run alongside the UI of a game in progress.
+#[tauri::command]
+async fn hint_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    board_string: Option<String>,
+    n: usize,
+) -> Result<Vec<HintPayload>, String> {
+    let board = state.search_board(board_string)?;
+    run_engine_search(
+        &state,
+        &app,
+        "hint",
+        board,
+        |_job_id| SearchRunOptions::with_level(get_level(HINT_LEVEL), SELECTIVITY).multi_pv(true),
+        move |result, _elapsed_ms| build_hints(result.pv_moves(), n),
+    )
+    .await
+}
+
+/// Solves `board_string` (the stored position when omitted) exactly,
+/// emitting `endgame-solve-progress` per iteration, and returns the final
+/// disc difference with perfect play and the line that reaches it.
+///
+/// Positions with more than [`SOLVE_MAX_EMPTIES`] empty squares are
+/// rejected; a position whose side to move must pass is solved from the
+/// opponent's side and reported back from the mover's.
+#[tauri::command]
+async fn solve_endgame_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    board_string: Option<String>,
+) -> Result<EndgameSolvePayload, String> {
+    let board = state.search_board(board_string)?;
+    let empties = board.get_empty_count();
+    if empties > SOLVE_MAX_EMPTIES {
+        return Err(format!(
+            "Too many empty squares to solve: {empties} (at most {SOLVE_MAX_EMPTIES})"
+        ));
+    }
+    if board.is_game_over() {
+        return Ok(EndgameSolvePayload {
+            best_move: None,
+            score: board.solve(empties),
+            pv_line: String::new(),
+            nodes: 0,
+            time_taken: 0,
+        });
+    }
+
+    let passed = !board.has_legal_moves();
+    let searched = if passed {
+        board.switch_players()
+    } else {
+        board
+    };
+    let emitter = app.clone();
+    run_engine_search(
+        &state,
+        &app,
+        "solve_endgame",
+        searched,
+        move |job_id| {
+            let callback = move |progress: search::SearchProgress| {
+                let _ = emitter.emit(
+                    "endgame-solve-progress",
+                    build_progress_payload(&progress, job_id),
+                );
+            };
+            SearchRunOptions::with_level(solver_level(Selectivity::None), Selectivity::None)
+                .callback(callback)
+        },
+        move |result, elapsed_ms| {
+            let score = result.score().unwrap_or(0.0).round() as i32;
+            EndgameSolvePayload {
+                best_move: if passed {
+                    None
+                } else {
+                    result.best_move().map(|square| square.index())
+                },
+                score: if passed { -score } else { score },
+                pv_line: passed
+                    .then(|| "--".to_string())
+                    .into_iter()
+                    .chain(result.pv_line().iter().map(|sq| format!("{}", sq)))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                nodes: result.n_nodes(),
+                time_taken: elapsed_ms,
+            }
+        },
+    )
+    .await
+}
+
 #[tauri::command]
 async fn solver_search_command(
     state: State<'_, AppState>,
     app: AppHandle,
-    board_string: String,
+    board_string: Option<String>,
     target_selectivity: u8,
     multi_pv: bool,
     run_id: u64,
@@ -383,18 +1001,22 @@ async fn solver_search_command(
         ));
     }
 
+    let board = state.search_board(board_string)?;
+    let emitter = app.clone();
     run_engine_search(
-        state.search.clone(),
-        board_string,
-        move || {
+        &state,
+        &app,
+        "solver_search",
+        board,
+        move |job_id| {
             let selectivity = Selectivity::from_u8(target_selectivity);
             let level = solver_level(selectivity);
             let callback = move |progress: search::SearchProgress| {
-                let _ = app.emit(
+                let _ = emitter.emit(
                     "solver-progress",
                     SolverProgressPayload {
                         run_id,
-                        progress: build_progress_payload(&progress),
+                        progress: build_progress_payload(&progress, job_id),
                     },
                 );
             };
@@ -407,26 +1029,45 @@ async fn solver_search_command(
     .await
 }
 
-#[tauri::command]
-async fn analyze_game_command(
-    state: State<'_, AppState>,
-    app: AppHandle,
-    board_string: String,
-    moves: Vec<String>,
+/// Parses the starting board of a game sent with its moves: `board_string`
+/// from the first mover's perspective, or the standard start when omitted.
+fn game_start_board(board_string: Option<String>) -> Result<board::Board, String> {
+    match board_string {
+        Some(board_string) => board::Board::from_string(&board_string, Disc::Black)
+            .map_err(|e| format!("Invalid board string: {e}")),
+        None => Ok(board::Board::new()),
+    }
+}
+
+/// Runs a Game Analysis of `moves` from `initial` at `level` as one `kind`
+/// search job, so other searches wait until the whole game is analyzed.
+/// Each per-move result goes to `on_progress` as it is scored, newest move
+/// first. Cancelling the job or `is_superseded` turning true stops the run
+/// with the moves scored so far. Returns the job id and the results in play
+/// order.
+#[allow(clippy::too_many_arguments)]
+async fn run_game_analysis<S, P>(
+    state: &AppState,
+    app: &AppHandle,
+    kind: &'static str,
+    initial: board::Board,
+    moves: Vec<game_analysis::GameAnalysisMove>,
     level: usize,
-) -> Result<(), String> {
-    validate_level(level)?;
-    // Claim a unique run id. Any later claim/supersede makes the injected
-    // `is_cancelled` predicate observe a mismatch and this run bail.
-    let run_id = state.game_analysis_run_id.claim();
+    is_superseded: S,
+    mut on_progress: P,
+) -> Result<(JobId, Vec<game_analysis::GameAnalysisProgress>), String>
+where
+    S: Fn() -> bool + Send + 'static,
+    P: FnMut(JobId, &game_analysis::GameAnalysisProgress) + Send + 'static,
+{
     let search_arc = state.search.clone();
-    let current_run_id = state.game_analysis_run_id.clone();
+    let ticket = queue_search_job(state, app, kind);
 
     spawn_blocking_result(move || {
-        let initial = board::Board::from_string(&board_string, Disc::Black)
-            .map_err(|e| format!("Invalid board string: {e}"))?;
-        let moves = decode_game_analysis_moves(moves)?;
+        ticket.wait_turn()?;
+        let job_id = ticket.id();
         let options = SearchRunOptions::with_level(get_level(level), SELECTIVITY);
+        let mut scores = Vec::with_capacity(moves.len());
 
         game_analysis::analyze_game(
             initial,
@@ -439,25 +1080,120 @@ async fn analyze_game_command(
                 drop(guard);
                 build_game_analysis(result)
             },
-            || !current_run_id.is_current(run_id),
+            || is_superseded() || ticket.is_cancelled(),
             |progress| {
-                let _ = app.emit(
-                    "game-analysis-progress",
-                    GameAnalysisProgressPayload {
-                        move_index: progress.move_index,
-                        best_move: progress.best_move.to_string(),
-                        best_score: progress.best_score,
-                        played_score: progress.played_score,
-                        score_loss: progress.score_loss,
-                        depth: progress.depth,
-                    },
-                );
+                on_progress(job_id, &progress);
+                scores.push(progress);
             },
-        )
+        )?;
+
+        // Progress arrives newest move first
+        scores.reverse();
+        Ok((job_id, scores))
     })
     .await
 }
 
+fn build_game_analysis_payload(
+    job_id: JobId,
+    progress: &game_analysis::GameAnalysisProgress,
+) -> GameAnalysisProgressPayload {
+    GameAnalysisProgressPayload {
+        job_id,
+        move_index: progress.move_index,
+        best_move: progress.best_move.to_string(),
+        best_score: progress.best_score,
+        played_score: progress.played_score,
+        score_loss: progress.score_loss,
+        depth: progress.depth,
+    }
+}
+
+/// Analyzes every move of a game played from `board_string` (the standard
+/// start when omitted), emitting `game-analysis-progress` per move as it is
+/// scored, newest first, and returning all per-move scores in play order
+/// for the evaluation graph. Passes have no entry. A superseded run returns
+/// the moves scored so far.
+#[tauri::command]
+async fn analyze_game_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    board_string: Option<String>,
+    moves: Vec<String>,
+    level: usize,
+) -> Result<Vec<GameAnalysisProgressPayload>, String> {
+    validate_level(level)?;
+    let initial = game_start_board(board_string)?;
+    let moves = decode_game_analysis_moves(moves)?;
+    // Claim a unique run id. Any later claim/supersede makes the injected
+    // `is_cancelled` predicate observe a mismatch and this run bail.
+    let run_id = state.game_analysis_run_id.claim();
+    let current_run_id = state.game_analysis_run_id.clone();
+    let emitter = app.clone();
+
+    let (job_id, scores) = run_game_analysis(
+        &state,
+        &app,
+        "analyze_game",
+        initial,
+        moves,
+        level,
+        move || !current_run_id.is_current(run_id),
+        move |job_id, progress| {
+            let _ = emitter.emit(
+                "game-analysis-progress",
+                build_game_analysis_payload(job_id, progress),
+            );
+        },
+    )
+    .await?;
+    Ok(scores
+        .iter()
+        .map(|progress| build_game_analysis_payload(job_id, progress))
+        .collect())
+}
+
+/// Reviews a finished game played from `board_string` (the standard start
+/// when omitted, with `first_side` moving first, Black by default) at
+/// `level`, [`REVIEW_LEVEL`] by default: every move is re-analyzed and
+/// classified by its Score Loss, and each player gets an accuracy figure.
+/// Emits `game-review-progress` per move, newest first. A cancelled review
+/// covers the moves scored so far.
+#[tauri::command]
+async fn review_game_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    board_string: Option<String>,
+    first_side: Option<String>,
+    moves: Vec<String>,
+    level: Option<usize>,
+) -> Result<game_review::GameReview, String> {
+    let level = level.unwrap_or(REVIEW_LEVEL);
+    validate_level(level)?;
+    let first_side = first_side.as_deref().map_or(Ok(Disc::Black), parse_side)?;
+    let initial = game_start_board(board_string)?;
+    let moves = decode_game_analysis_moves(moves)?;
+    let emitter = app.clone();
+
+    let (_job_id, analyses) = run_game_analysis(
+        &state,
+        &app,
+        "review_game",
+        initial,
+        moves.clone(),
+        level,
+        || false,
+        move |job_id, progress| {
+            let _ = emitter.emit(
+                "game-review-progress",
+                build_game_analysis_payload(job_id, progress),
+            );
+        },
+    )
+    .await?;
+    Ok(game_review::build_review(first_side, &moves, &analyses))
+}
+
 #[tauri::command]
 async fn abort_game_analysis_command(state: State<'_, AppState>) -> Result<(), String> {
     // Superseding makes any in-flight run observe a mismatch and exit.
@@ -465,6 +1201,495 @@ async fn abort_game_analysis_command(state: State<'_, AppState>) -> Result<(), S
     abort_and_wait(state.thread_pool.clone()).await
 }
 
+/// Cancels search job `id`: a queued job is dropped and its command fails,
+/// a running one has its search aborted and returns what it found so far.
+#[tauri::command]
+async fn cancel_job_command(state: State<'_, AppState>, id: JobId) -> Result<(), String> {
+    match state.jobs.cancel(id) {
+        Some(CancelledJob::Pending) => Ok(()),
+        Some(CancelledJob::Running) => abort_and_wait(state.thread_pool.clone()).await,
+        None => Err(format!("No search job {id}")),
+    }
+}
+
+/// Loads the opening book probed by `book_moves_command`, replacing any
+/// book loaded before. Returns the number of positions in the book.
+#[tauri::command]
+async fn load_book_command(state: State<'_, AppState>, path: String) -> Result<usize, String> {
+    let book = spawn_blocking_result(move || {
+        Book::load(std::path::Path::new(&path)).map_err(|e| format!("Failed to load {path}: {e}"))
+    })
+    .await?;
+    let positions = book.len();
+    *state
+        .book
+        .lock()
+        .map_err(|e| format!("Book unavailable: {e}"))? = Some(book);
+    Ok(positions)
+}
+
+/// Returns the book moves for `board_string` (the stored position when
+/// omitted), most played first, so the GUI can show book lines or play a
+/// weighted book move. Empty when the position is not in the book.
+#[tauri::command]
+fn book_moves_command(
+    state: State<'_, AppState>,
+    board_string: Option<String>,
+) -> Result<Vec<BookMovePayload>, String> {
+    let board = state.search_board(board_string)?;
+    let book = state
+        .book
+        .lock()
+        .map_err(|e| format!("Book unavailable: {e}"))?;
+    let book = book.as_ref().ok_or("No opening book loaded")?;
+    Ok(book.probe(&board).map(build_book_moves).unwrap_or_default())
+}
+
+fn lock_clock(clock: &Mutex<ClockState>) -> Result<std::sync::MutexGuard<'_, ClockState>, String> {
+    clock.lock().map_err(|e| format!("Clock unavailable: {e}"))
+}
+
+/// Starts a thread emitting `clock-tick` until the clock stops, unless one
+/// is already running. Called with the clock locked.
+fn ensure_clock_ticker(shared: &Arc<Mutex<ClockState>>, state: &mut ClockState, app: AppHandle) {
+    if state.ticking {
+        return;
+    }
+    state.ticking = true;
+    let shared = shared.clone();
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(CLOCK_TICK);
+            let Ok(mut state) = shared.lock() else {
+                return;
+            };
+            let now = std::time::Instant::now();
+            let running = match state.clock.as_mut() {
+                Some(clock) => {
+                    clock.check_flag(now);
+                    let _ = app.emit("clock-tick", clock.payload(now));
+                    clock.is_running()
+                }
+                None => false,
+            };
+            if !running {
+                state.ticking = false;
+                return;
+            }
+        }
+    });
+}
+
+/// Resets both clocks to the full time of `control` and starts `side`'s.
+#[tauri::command]
+fn start_clock_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    control: clock::TimeControl,
+    side: String,
+) -> Result<clock::ClockPayload, String> {
+    let side = parse_side(&side)?;
+    let now = std::time::Instant::now();
+    let mut guard = lock_clock(&state.clock)?;
+    let mut game_clock = clock::GameClock::new(control);
+    game_clock.start(side, now);
+    let payload = game_clock.payload(now);
+    guard.clock = Some(game_clock);
+    ensure_clock_ticker(&state.clock, &mut guard, app);
+    Ok(payload)
+}
+
+/// Ends the running side's move and starts the opponent's clock.
+#[tauri::command]
+fn switch_clock_command(state: State<'_, AppState>) -> Result<clock::ClockPayload, String> {
+    let now = std::time::Instant::now();
+    let mut guard = lock_clock(&state.clock)?;
+    let game_clock = guard.clock.as_mut().ok_or("No clock started")?;
+    game_clock.switch(now);
+    Ok(game_clock.payload(now))
+}
+
+/// Stops the running clock, e.g. at the end of the game.
+#[tauri::command]
+fn stop_clock_command(state: State<'_, AppState>) -> Result<clock::ClockPayload, String> {
+    let now = std::time::Instant::now();
+    let mut guard = lock_clock(&state.clock)?;
+    let game_clock = guard.clock.as_mut().ok_or("No clock started")?;
+    game_clock.stop(now);
+    Ok(game_clock.payload(now))
+}
+
+/// Writes a game played from the standard start to a file chosen in a save
+/// dialog. `moves` holds one square per move and `--` for a pass. Returns
+/// the path written, or `None` if the dialog was cancelled.
+#[tauri::command]
+async fn export_game_command(
+    app: AppHandle,
+    format: game_export::ExportFormat,
+    moves: Vec<String>,
+    black_name: Option<String>,
+    white_name: Option<String>,
+) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let record = game_export::build_record(&moves, black_name, white_name)?;
+    let contents = game_export::encode(&record, format)?;
+    spawn_blocking_result(move || {
+        let (filter_name, extension) = format.filter();
+        let Some(path) = app
+            .dialog()
+            .file()
+            .add_filter(filter_name, &[extension])
+            .set_file_name(format!("game.{extension}"))
+            .blocking_save_file()
+        else {
+            return Ok(None);
+        };
+        let path = path
+            .into_path()
+            .map_err(|e| format!("Invalid export path: {e}"))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        Ok(Some(path.display().to_string()))
+    })
+    .await
+}
+
+/// Keeps the game in progress to save when the app exits. `None` means
+/// there is nothing to resume, e.g. after the game has ended.
+#[tauri::command]
+fn update_session_command(
+    state: State<'_, AppState>,
+    session: Option<session::Session>,
+) -> Result<(), String> {
+    *state
+        .session
+        .lock()
+        .map_err(|e| format!("Session unavailable: {e}"))? = session;
+    Ok(())
+}
+
+/// Restores the game saved when the app last exited: the stored position
+/// and the clocks, resuming the side whose clock was running. Returns the
+/// session for the UI to rebuild the board, history and settings from, or
+/// `None` if none was saved.
+#[tauri::command]
+fn restore_session_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Option<session::Session>, String> {
+    use tauri_plugin_store::StoreExt;
+
+    let store = app
+        .store(session::SESSION_STORE)
+        .map_err(|e| format!("Failed to open session store: {e}"))?;
+    let Some(value) = store.get(session::SESSION_KEY) else {
+        return Ok(None);
+    };
+    let saved: session::Session =
+        serde_json::from_value(value).map_err(|e| format!("Invalid saved session: {e}"))?;
+
+    if let Some(position) = &saved.position {
+        let position = Position::parse(&position.obf, &position.side_to_move)?;
+        *state
+            .position
+            .lock()
+            .map_err(|e| format!("Position unavailable: {e}"))? = Some(position);
+    }
+    if let Some(snapshot) = &saved.clock {
+        let game_clock = clock::GameClock::restore(snapshot, std::time::Instant::now());
+        let mut guard = lock_clock(&state.clock)?;
+        let running = game_clock.is_running();
+        guard.clock = Some(game_clock);
+        if running {
+            ensure_clock_ticker(&state.clock, &mut guard, app.clone());
+        }
+    }
+    *state
+        .session
+        .lock()
+        .map_err(|e| format!("Session unavailable: {e}"))? = Some(saved.clone());
+    Ok(Some(saved))
+}
+
+/// Writes the game in progress, with the current clocks, to the session
+/// store, or removes the saved session when no game is in progress.
+fn save_session(app: &AppHandle) -> Result<(), String> {
+    use tauri_plugin_store::StoreExt;
+
+    let state = app.state::<AppState>();
+    let session = state
+        .session
+        .lock()
+        .map_err(|e| format!("Session unavailable: {e}"))?
+        .clone();
+    let store = app
+        .store(session::SESSION_STORE)
+        .map_err(|e| format!("Failed to open session store: {e}"))?;
+    match session {
+        Some(mut session) => {
+            session.clock = lock_clock(&state.clock)?
+                .clock
+                .as_ref()
+                .map(|clock| clock.snapshot(std::time::Instant::now()));
+            let value = serde_json::to_value(session).map_err(|e| e.to_string())?;
+            store.set(session::SESSION_KEY, value);
+        }
+        None => {
+            store.delete(session::SESSION_KEY);
+        }
+    }
+    store
+        .save()
+        .map_err(|e| format!("Failed to save session: {e}"))
+}
+
+fn build_exhibition_move(
+    exhibition_id: u64,
+    played: &exhibition::PlayedMove,
+) -> ExhibitionMovePayload {
+    ExhibitionMovePayload {
+        exhibition_id,
+        side: if played.side == Disc::White {
+            "white"
+        } else {
+            "black"
+        },
+        played_move: played
+            .sq
+            .map_or_else(|| "--".to_string(), |sq| sq.to_string()),
+        score: played.engine_move.map(|m| round_score(m.score)),
+        depth: played.engine_move.map(|m| m.depth),
+        black_discs: played.black_discs,
+        white_discs: played.white_discs,
+    }
+}
+
+/// Creates an exhibition engine. A weight file that fails to load panics
+/// inside the core; it is reported as an error instead.
+fn create_exhibition_engine(config: &exhibition::EngineConfig) -> Result<search::Search, String> {
+    let options = config.search_options();
+    std::panic::catch_unwind(|| search::Search::new(&options))
+        .map_err(|_| "Failed to load the evaluation weights".to_string())
+}
+
+/// Plays one exhibition game between the two engines, emitting
+/// `exhibition-move` per move. Returns `None` if the game was stopped.
+fn run_exhibition(
+    app: &AppHandle,
+    id: u64,
+    black: &exhibition::EngineConfig,
+    white: &exhibition::EngineConfig,
+    opening: &[Square],
+    cancelled: &std::sync::atomic::AtomicBool,
+    thread_pools: &Mutex<Vec<Arc<search::threading::ThreadPool>>>,
+) -> Result<Option<match_runner::match_runner::MatchResult>, String> {
+    let mut engines = [
+        create_exhibition_engine(black)?,
+        create_exhibition_engine(white)?,
+    ];
+    thread_pools
+        .lock()
+        .map_err(|e| format!("Exhibition unavailable: {e}"))?
+        .extend(engines.iter().map(search::Search::thread_pool));
+    let levels = [get_level(black.level), get_level(white.level)];
+
+    exhibition::play(
+        opening,
+        |side, board| {
+            let i = usize::from(side == Disc::White);
+            let result =
+                engines[i].run(board, &SearchRunOptions::with_level(levels[i], SELECTIVITY));
+            let sq = result.best_move().ok_or("Engine returned no move")?;
+            Ok(exhibition::EngineMove {
+                sq,
+                score: result.score().unwrap_or(0.0),
+                depth: result.depth(),
+            })
+        },
+        |played| {
+            let _ = app.emit("exhibition-move", build_exhibition_move(id, played));
+        },
+        || cancelled.load(Ordering::Acquire),
+    )
+}
+
+/// Starts a game between two engines with their own levels and weight
+/// files, replacing any exhibition in progress, and returns its id. Moves
+/// arrive as `exhibition-move` events and the outcome as
+/// `exhibition-finished`.
+#[tauri::command]
+fn start_exhibition_command(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    black: exhibition::EngineConfig,
+    white: exhibition::EngineConfig,
+    opening: Option<String>,
+) -> Result<u64, String> {
+    validate_level(black.level)?;
+    validate_level(white.level)?;
+    let opening = Square::parse_sequence(opening.as_deref().unwrap_or(""))
+        .map_err(|e| format!("Invalid opening: {e}"))?;
+
+    let id = state.exhibition_id.fetch_add(1, Ordering::AcqRel) + 1;
+    let handle = ExhibitionHandle {
+        cancelled: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        thread_pools: Arc::new(Mutex::new(Vec::new())),
+    };
+    let (cancelled, thread_pools) = (handle.cancelled.clone(), handle.thread_pools.clone());
+    let previous = state
+        .exhibition
+        .lock()
+        .map_err(|e| format!("Exhibition unavailable: {e}"))?
+        .replace(handle);
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let outcome = run_exhibition(
+            &app,
+            id,
+            &black,
+            &white,
+            &opening,
+            &cancelled,
+            &thread_pools,
+        );
+        let (result, score, error) = match outcome {
+            Ok(Some(finished)) => {
+                let result = match finished.result {
+                    match_runner::match_runner::GameResult::BlackWin => {
+                        game_db::GameResult::BlackWin
+                    }
+                    match_runner::match_runner::GameResult::WhiteWin => {
+                        game_db::GameResult::WhiteWin
+                    }
+                    match_runner::match_runner::GameResult::Draw => game_db::GameResult::Draw,
+                };
+                (Some(result), Some(finished.score), None)
+            }
+            Ok(None) => (None, None, None),
+            Err(e) => (None, None, Some(e)),
+        };
+        let _ = app.emit(
+            "exhibition-finished",
+            ExhibitionFinishedPayload {
+                exhibition_id: id,
+                result,
+                score,
+                error,
+            },
+        );
+    });
+    Ok(id)
+}
+
+/// Stops the exhibition in progress, if any.
+#[tauri::command]
+fn stop_exhibition_command(state: State<'_, AppState>) -> Result<(), String> {
+    let handle = state
+        .exhibition
+        .lock()
+        .map_err(|e| format!("Exhibition unavailable: {e}"))?
+        .take();
+    if let Some(handle) = handle {
+        handle.stop();
+    }
+    Ok(())
+}
+
+/// Opens the game database on first use and applies `f` to it.
+fn with_game_db<T>(
+    state: &AppState,
+    f: impl FnOnce(&game_db::GameDatabase) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut guard = state
+        .game_db
+        .lock()
+        .map_err(|e| format!("Game database unavailable: {e}"))?;
+    if guard.is_none() {
+        *guard = Some(game_db::GameDatabase::open(&state.game_db_path)?);
+    }
+    f(guard.as_ref().expect("game database was just opened"))
+}
+
+/// Saves a finished game played from the standard start and returns its id.
+#[tauri::command]
+fn save_game_command(
+    state: State<'_, AppState>,
+    black: String,
+    white: String,
+    moves: Vec<String>,
+) -> Result<i64, String> {
+    with_game_db(&state, |db| db.save(&black, &white, &moves))
+}
+
+#[tauri::command]
+fn list_games_command(
+    state: State<'_, AppState>,
+    filter: Option<game_db::GameFilter>,
+) -> Result<Vec<game_db::StoredGame>, String> {
+    with_game_db(&state, |db| db.list(&filter.unwrap_or_default()))
+}
+
+#[tauri::command]
+fn load_game_command(state: State<'_, AppState>, id: i64) -> Result<game_db::StoredGame, String> {
+    with_game_db(&state, |db| db.load(id))
+}
+
+/// Hardware and build details for diagnosing performance reports.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemInfoPayload {
+    os: String,
+    arch: &'static str,
+    cpu_brand: String,
+    logical_cores: usize,
+    physical_cores: Option<usize>,
+    total_memory_mb: u64,
+    available_memory_mb: u64,
+    /// SIMD features the CPU supports
+    detected_features: Vec<&'static str>,
+    /// SIMD features the engine was compiled with
+    compiled_features: Vec<&'static str>,
+    bitboard_backend: &'static str,
+    network_backend: &'static str,
+}
+
+/// Reports the CPU's SIMD features, core count and memory along with the
+/// code paths the engine was compiled with and selected at runtime.
+#[tauri::command]
+fn system_info_command() -> SystemInfoPayload {
+    use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
+
+    const MIB: u64 = 1024 * 1024;
+    let system = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::nothing())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    let cpu_brand = system
+        .cpus()
+        .first()
+        .map(|cpu| cpu.brand().trim().to_string())
+        .unwrap_or_default();
+
+    SystemInfoPayload {
+        os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        arch: std::env::consts::ARCH,
+        cpu_brand,
+        logical_cores: std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
+        physical_cores: System::physical_core_count(),
+        total_memory_mb: system.total_memory() / MIB,
+        available_memory_mb: system.available_memory() / MIB,
+        detected_features: cpu::detected_features(),
+        compiled_features: cpu::compiled_features(),
+        bitboard_backend: cpu::bitboard_backend(),
+        network_backend: cpu::network_backend(),
+    }
+}
+
 #[tauri::command]
 fn get_app_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
@@ -495,30 +1720,71 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
+            let game_db_path = app.path().app_data_dir()?.join("games.sqlite3");
             app.manage(AppState {
                 search,
                 thread_pool,
                 game_analysis_run_id: Arc::new(GameAnalysisGeneration::new()),
+                position: Mutex::new(None),
+                game_db: Mutex::new(None),
+                game_db_path,
+                book: Mutex::new(None),
+                clock: Arc::new(Mutex::new(ClockState::default())),
+                jobs: Arc::new(JobQueue::new()),
+                ponder: Mutex::new(ponder::Ponder::default()),
+                kibitz: Mutex::new(kibitz::Kibitz::default()),
+                session: Mutex::new(None),
+                exhibition: Mutex::new(None),
+                exhibition_id: AtomicU64::new(0),
             });
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
+            set_position_command,
             ai_move_command,
             check_ai_ready_command,
             init_ai_command,
             resize_tt_command,
             abort_ai_search_command,
             analyze_command,
+            hint_command,
             analyze_game_command,
+            review_game_command,
             abort_game_analysis_command,
+            cancel_job_command,
+            set_ponder_command,
+            set_kibitz_command,
             solver_search_command,
+            solve_endgame_command,
+            load_book_command,
+            book_moves_command,
+            start_clock_command,
+            switch_clock_command,
+            stop_clock_command,
+            save_game_command,
+            list_games_command,
+            load_game_command,
+            export_game_command,
+            start_exhibition_command,
+            stop_exhibition_command,
+            update_session_command,
+            restore_session_command,
+            system_info_command,
             get_app_version,
             get_license_text,
             get_third_party_licenses_text,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::ExitRequested { .. } = event
+                && let Err(e) = save_session(app)
+            {
+                eprintln!("{e}");
+            }
+        });
 }
 
 #[cfg(test)]
@@ -568,6 +1834,79 @@ mod tests {
         assert!(err.contains("Invalid move notation"), "got: {err}");
     }
 
+    #[test]
+    fn position_parse_reads_board_from_side_to_move() {
+        let obf = "---------------------------OX------XXX--------------------------";
+        let position = Position::parse(obf, "white").unwrap();
+        assert_eq!(position.side_to_move, Disc::White);
+        assert_eq!(position.board, board::Board::new().make_move(Square::F5));
+        assert_eq!(Position::parse(obf, "O").unwrap(), position);
+    }
+
+    #[test]
+    fn position_parse_rejects_unreachable_boards_and_bad_sides() {
+        let obf = "---------------------------OX------XXX--------------------------";
+        let err = Position::parse(obf, "red").unwrap_err();
+        assert!(err.contains("Invalid side to move"), "got: {err}");
+
+        let empty_center = "---------------------------OX------X-X--------------------------";
+        let err = Position::parse(empty_center, "white").unwrap_err();
+        assert!(err.contains("Invalid position"), "got: {err}");
+    }
+
+    #[test]
+    fn build_hints_keeps_the_best_n_moves() {
+        let pv_moves = vec![
+            PvMove {
+                sq: Square::D3,
+                score: 2.04,
+                pv_line: vec![Square::D3, Square::C3],
+            },
+            PvMove {
+                sq: Square::C4,
+                score: -1.0,
+                pv_line: vec![Square::C4],
+            },
+        ];
+        let hints = build_hints(&pv_moves, 1);
+        assert_eq!(
+            hints,
+            vec![HintPayload {
+                best_move: "d3".to_string(),
+                row: 2,
+                col: 3,
+                score: 2.0,
+                pv_line: "d3 c3".to_string(),
+            }]
+        );
+        assert_eq!(build_hints(&pv_moves, 5).len(), 2);
+    }
+
+    #[test]
+    fn build_book_moves_weights_by_play_count() {
+        let moves = [
+            BookMove {
+                sq: Square::F5,
+                count: 3,
+                scored: 2,
+                score_sum: 5,
+            },
+            BookMove {
+                sq: Square::D3,
+                count: 1,
+                scored: 0,
+                score_sum: 0,
+            },
+        ];
+        let payload = build_book_moves(&moves);
+        assert_eq!(payload[0].best_move, "f5");
+        assert_eq!((payload[0].row, payload[0].col), (4, 5));
+        assert_eq!(payload[0].weight, 0.75);
+        assert_eq!(payload[0].score, Some(2.5));
+        assert_eq!(payload[1].weight, 0.25);
+        assert_eq!(payload[1].score, None);
+    }
+
     #[test]
     fn validate_level_accepts_max_level() {
         assert!(validate_level(reversi_core::level::MAX_LEVEL).is_ok());
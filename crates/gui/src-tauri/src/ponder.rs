@@ -0,0 +1,132 @@
+//! Pondering: thinking on the human's time.
+//!
+//! After the engine moves, the reply its principal variation expects is
+//! played on a copy of the board and searched until the human moves. The
+//! search only warms the transposition table; when the human plays the
+//! expected reply (a ponder hit) the next engine search starts from deeper
+//! results. `lib.rs` owns the ponder search job; this module tracks the
+//! prediction and the hit statistics.
+
+use reversi_core::board::Board;
+use reversi_core::square::Square;
+use serde::Serialize;
+
+use crate::jobs::JobId;
+
+/// Ponder statistics reported to the UI.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PonderStats {
+    pub enabled: bool,
+    /// Engine moves searched after a ponder
+    pub ponders: u32,
+    /// Ponders whose predicted reply was played
+    pub hits: u32,
+}
+
+/// Pondering switch, the position being pondered and the statistics.
+#[derive(Default)]
+pub struct Ponder {
+    enabled: bool,
+    /// Position expected at the engine's next move, from its perspective
+    predicted: Option<Board>,
+    /// Search job pondering `predicted`
+    job: Option<JobId>,
+    ponders: u32,
+    hits: u32,
+}
+
+/// Returns the position the engine expects to face next: `board` after
+/// the engine's move and the expected reply, both taken from the start of
+/// `pv_line`. Returns `None` if the line is too short or the game is over.
+pub fn predict(board: &Board, pv_line: &[Square]) -> Option<Board> {
+    let (&engine_move, &reply) = (pv_line.first()?, pv_line.get(1)?);
+    let predicted = board.try_make_move(engine_move)?.try_make_move(reply)?;
+    predicted.has_legal_moves().then_some(predicted)
+}
+
+impl Ponder {
+    /// Returns whether pondering is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables pondering. Disabling forgets the prediction and
+    /// returns the ponder job to stop, if any.
+    pub fn set_enabled(&mut self, enabled: bool) -> Option<JobId> {
+        self.enabled = enabled;
+        if enabled {
+            return None;
+        }
+        self.predicted = None;
+        self.job.take()
+    }
+
+    /// Records that job `job` ponders `predicted`.
+    pub fn start(&mut self, predicted: Board, job: JobId) {
+        self.predicted = Some(predicted);
+        self.job = Some(job);
+    }
+
+    /// Returns the ponder job to stop before another search runs, if any.
+    pub fn take_job(&mut self) -> Option<JobId> {
+        self.job.take()
+    }
+
+    /// Scores the prediction against the position the engine now faces.
+    /// Returns `None` if nothing was pondered.
+    pub fn resolve(&mut self, board: &Board) -> Option<bool> {
+        let predicted = self.predicted.take()?;
+        let hit = predicted == *board;
+        self.ponders += 1;
+        self.hits += u32::from(hit);
+        Some(hit)
+    }
+
+    /// Returns the current statistics.
+    pub fn stats(&self) -> PonderStats {
+        PonderStats {
+            enabled: self.enabled,
+            ponders: self.ponders,
+            hits: self.hits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predict_plays_the_engine_move_and_the_reply() {
+        let board = Board::new();
+        let predicted = predict(&board, &[Square::F5, Square::D6, Square::C3]).unwrap();
+        assert_eq!(predicted, board.make_move(Square::F5).make_move(Square::D6));
+        assert_eq!(predict(&board, &[Square::F5]), None);
+        assert_eq!(predict(&board, &[Square::F5, Square::A1]), None);
+    }
+
+    #[test]
+    fn resolve_counts_hits_once_per_ponder() {
+        let board = Board::new();
+        let predicted = board.make_move(Square::F5).make_move(Square::D6);
+        let mut ponder = Ponder::default();
+        assert_eq!(ponder.set_enabled(true), None);
+
+        ponder.start(predicted, 1);
+        assert_eq!(ponder.take_job(), Some(1));
+        assert_eq!(ponder.resolve(&predicted), Some(true));
+        assert_eq!(ponder.resolve(&predicted), None);
+
+        ponder.start(predicted, 2);
+        assert_eq!(ponder.resolve(&board), Some(false));
+        assert_eq!(
+            ponder.stats(),
+            PonderStats {
+                enabled: true,
+                ponders: 2,
+                hits: 1
+            }
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Session: the game in progress, saved when the app exits and offered
+//! back at the next start.
+//!
+//! The UI keeps the backend's copy current as the game goes on; the
+//! backend adds the clocks it owns and writes the session to the store on
+//! exit. Settings are stored as the UI sends them, so new settings need no
+//! backend change. `lib.rs` owns the store and the commands.
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::ClockSnapshot;
+
+/// Store file holding the session.
+pub const SESSION_STORE: &str = "session.json";
+
+/// Key of the session in [`SESSION_STORE`].
+pub const SESSION_KEY: &str = "session";
+
+/// Position the engine commands search, as given to
+/// `set_position_command`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPosition {
+    pub obf: String,
+    pub side_to_move: String,
+}
+
+/// A game in progress.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Session {
+    /// Moves from the standard start, `--` for a pass
+    pub moves: Vec<String>,
+    /// Current position
+    pub position: Option<SessionPosition>,
+    /// Engine and game settings chosen in the UI
+    pub settings: serde_json::Value,
+    /// Clocks when the app exited
+    pub clock: Option<ClockSnapshot>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TimeControl;
+
+    #[test]
+    fn sessions_round_trip_through_json() {
+        let session = Session {
+            moves: vec!["f5".to_string(), "d6".to_string()],
+            position: Some(SessionPosition {
+                obf: "-".repeat(64),
+                side_to_move: "black".to_string(),
+            }),
+            settings: serde_json::json!({ "level": 10 }),
+            clock: Some(ClockSnapshot {
+                control: TimeControl::Byoyomi {
+                    main_ms: 60_000,
+                    byoyomi_ms: 5_000,
+                },
+                black_ms: 42_000,
+                white_ms: 0,
+                running: Some("white".to_string()),
+                flagged: None,
+            }),
+        };
+        let json = serde_json::to_value(&session).unwrap();
+        assert_eq!(json["clock"]["control"]["kind"], "byoyomi");
+        assert_eq!(json["position"]["sideToMove"], "black");
+        assert_eq!(serde_json::from_value::<Session>(json).unwrap(), session);
+
+        // Sessions saved by older versions lack newer fields.
+        let old: Session = serde_json::from_str(r#"{"moves":["f5"]}"#).unwrap();
+        assert_eq!(old.clock, None);
+    }
+}
@@ -12,14 +12,35 @@
 
 export const TAURI_COMMAND = {
   checkAiReady: "check_ai_ready_command",
+  setPosition: "set_position_command",
   aiMove: "ai_move_command",
   initAi: "init_ai_command",
   resizeTt: "resize_tt_command",
   abortAiSearch: "abort_ai_search_command",
   analyze: "analyze_command",
+  hint: "hint_command",
   analyzeGame: "analyze_game_command",
+  reviewGame: "review_game_command",
   abortGameAnalysis: "abort_game_analysis_command",
+  cancelJob: "cancel_job_command",
+  setPonder: "set_ponder_command",
+  setKibitz: "set_kibitz_command",
   solverSearch: "solver_search_command",
+  solveEndgame: "solve_endgame_command",
+  startClock: "start_clock_command",
+  switchClock: "switch_clock_command",
+  stopClock: "stop_clock_command",
+  loadBook: "load_book_command",
+  bookMoves: "book_moves_command",
+  saveGame: "save_game_command",
+  listGames: "list_games_command",
+  loadGame: "load_game_command",
+  exportGame: "export_game_command",
+  startExhibition: "start_exhibition_command",
+  stopExhibition: "stop_exhibition_command",
+  updateSession: "update_session_command",
+  restoreSession: "restore_session_command",
+  systemInfo: "system_info_command",
   getAppVersion: "get_app_version",
   getLicenseText: "get_license_text",
   getThirdPartyLicensesText: "get_third_party_licenses_text",
@@ -28,5 +49,12 @@ export const TAURI_COMMAND = {
 export const TAURI_EVENT = {
   aiMoveProgress: "ai-move-progress",
   solverProgress: "solver-progress",
+  searchJobQueued: "search-job-queued",
+  kibitzProgress: "kibitz-progress",
+  exhibitionMove: "exhibition-move",
+  exhibitionFinished: "exhibition-finished",
   gameAnalysisProgress: "game-analysis-progress",
+  gameReviewProgress: "game-review-progress",
+  endgameSolveProgress: "endgame-solve-progress",
+  clockTick: "clock-tick",
 } as const;
@@ -0,0 +1,176 @@
+//! Early adjudication of decided games.
+//!
+//! A game can be ended before it is played out in two ways:
+//!
+//! - by solving the position exactly once few enough squares are empty;
+//! - by the engines' own scores, when both report the same side winning by
+//!   at least a threshold over several consecutive moves. Scores are read
+//!   with the `nr-score` GTP extension, so this only triggers between
+//!   engines that support it.
+
+use reversi_core::board::Board;
+use reversi_core::types::Score;
+use serde::{Deserialize, Serialize};
+
+/// Largest number of empty squares the solver accepts, to keep adjudication
+/// fast with its plain alpha-beta search.
+pub const MAX_SOLVE_EMPTIES: u32 = 16;
+
+/// Adjudication settings of a match.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Adjudication {
+    /// Solve positions with at most this many empty squares (0 to disable)
+    pub solve_empties: u32,
+    /// Decisive score threshold in discs, if score adjudication is enabled
+    pub score_threshold: Option<f32>,
+    /// Consecutive decisive scores needed for score adjudication
+    pub score_moves: u32,
+}
+
+impl Adjudication {
+    /// Create the score adjudicator for a new game, if enabled.
+    pub fn score_adjudicator(&self) -> Option<ScoreAdjudicator> {
+        self.score_threshold
+            .map(|threshold| ScoreAdjudicator::new(threshold, self.score_moves))
+    }
+}
+
+/// Solve `board` exactly, returning the final disc difference from the side
+/// to move's perspective with empty squares awarded to the winner.
+pub fn solve(board: &Board) -> Score {
+    negamax(board, -64, 64, false)
+}
+
+fn negamax(board: &Board, mut alpha: Score, beta: Score, passed: bool) -> Score {
+    let moves = board.get_moves();
+    if moves.is_empty() {
+        if passed {
+            return board.solve(board.get_empty_count());
+        }
+        return -negamax(&board.switch_players(), -beta, -alpha, true);
+    }
+
+    // Search replies that leave the opponent fewest moves first
+    let mut children: Vec<(u32, Board)> = moves
+        .iter()
+        .map(|sq| {
+            let next = board.make_move(sq);
+            (next.get_moves().count(), next)
+        })
+        .collect();
+    children.sort_unstable_by_key(|&(mobility, _)| mobility);
+
+    let mut best = -64;
+    for (_, next) in &children {
+        let score = -negamax(next, -beta, -alpha, false);
+        if score > best {
+            best = score;
+            if score > alpha {
+                alpha = score;
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Tracks the engines' reported scores to adjudicate clearly decided games.
+#[derive(Debug, Clone)]
+pub struct ScoreAdjudicator {
+    /// Smallest score, in discs, counted as decisive
+    threshold: f32,
+    /// Consecutive decisive scores needed
+    moves_required: u32,
+    /// Length of the current streak of decisive scores
+    streak: u32,
+    /// Black's perspective score of the last decisive report
+    last_score: f32,
+}
+
+impl ScoreAdjudicator {
+    /// Create an adjudicator requiring `moves_required` consecutive scores of
+    /// at least `threshold` discs for the same side.
+    pub fn new(threshold: f32, moves_required: u32) -> Self {
+        Self {
+            threshold,
+            moves_required: moves_required.max(1),
+            streak: 0,
+            last_score: 0.0,
+        }
+    }
+
+    /// Record the score reported after a move, from Black's perspective, or
+    /// `None` if the engine did not report one.
+    ///
+    /// Returns the adjudicated final score from Black's perspective once the
+    /// streak is long enough.
+    pub fn update(&mut self, black_score: Option<f32>) -> Option<Score> {
+        let Some(score) = black_score.filter(|score| score.abs() >= self.threshold) else {
+            self.streak = 0;
+            return None;
+        };
+
+        let same_side = self.streak > 0 && score.signum() == self.last_score.signum();
+        self.streak = if same_side { self.streak + 1 } else { 1 };
+        self.last_score = score;
+
+        (self.streak >= self.moves_required).then(|| {
+            let discs = (score.round() as Score).clamp(-64, 64);
+            // Adjudicated games are never drawn
+            if discs == 0 {
+                score.signum() as Score
+            } else {
+                discs
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reversi_core::disc::Disc;
+
+    #[test]
+    fn test_solve_finished_game() {
+        // Neither side can move: Black owns every disc and wins the empty square
+        let board = Board::from_string(&format!("-{}", "X".repeat(63)), Disc::White).unwrap();
+        assert_eq!(solve(&board), -64);
+        assert_eq!(solve(&board.switch_players()), 64);
+    }
+
+    #[test]
+    fn test_solve_small_endgame() {
+        // Black to move with one empty: taking h8 wins all discs
+        let board = Board::from_string(
+            "XXXXXXXX\
+             XXXXXXXX\
+             XXXXXXXX\
+             XXXXXXXX\
+             XXXXXXXX\
+             XXXXXXXX\
+             XXXXXXXO\
+             XXXXXXO-",
+            Disc::Black,
+        )
+        .unwrap();
+        assert_eq!(solve(&board), 64);
+    }
+
+    #[test]
+    fn test_score_adjudication_needs_a_streak() {
+        let mut adjudicator = ScoreAdjudicator::new(20.0, 3);
+        assert_eq!(adjudicator.update(Some(25.0)), None);
+        assert_eq!(adjudicator.update(Some(-30.0)), None);
+        assert_eq!(adjudicator.update(Some(-24.0)), None);
+        assert_eq!(adjudicator.update(Some(-22.4)), Some(-22));
+
+        let mut adjudicator = ScoreAdjudicator::new(20.0, 3);
+        assert_eq!(adjudicator.update(Some(30.0)), None);
+        assert_eq!(adjudicator.update(None), None);
+        assert_eq!(adjudicator.update(Some(30.0)), None);
+        assert_eq!(adjudicator.update(Some(12.0)), None);
+    }
+}
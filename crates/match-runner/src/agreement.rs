@@ -0,0 +1,193 @@
+//! Move agreement and score correlation between the engines of a match.
+//!
+//! With `--agreement-level`, the engine waiting for its opponent's move
+//! analyzes the same position with the `nr-eval` extension. Each move is then
+//! compared with the analyzing engine's best move, and the mover's score of
+//! its move with the analyzing engine's score of its best move. High agreement
+//! and correlation mean that the engines judge positions alike, e.g. that a
+//! new network differs little from the one it was trained from.
+
+use serde::{Deserialize, Serialize};
+
+/// Agreement of the moves of one engine with the other engine's analysis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveAgreement {
+    /// Moves compared with the other engine's analysis
+    pub moves: u32,
+    /// Moves that were the other engine's best move
+    pub matches: u32,
+}
+
+impl MoveAgreement {
+    /// Share of moves that matched the other engine's best move.
+    pub fn rate(&self) -> Option<f64> {
+        (self.moves > 0).then(|| f64::from(self.matches) / f64::from(self.moves))
+    }
+}
+
+/// Move agreement and paired scores of two engines.
+///
+/// The engines are called first and second; within a game they are the black
+/// and white engine, and in match statistics engine1 and engine2.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgreementStats {
+    /// Moves of the first engine compared with the second engine's analysis
+    pub first: MoveAgreement,
+    /// Moves of the second engine compared with the first engine's analysis
+    pub second: MoveAgreement,
+    /// Number of positions scored by both engines
+    pub scored: u32,
+    sum_first: f64,
+    sum_second: f64,
+    sum_first_sq: f64,
+    sum_second_sq: f64,
+    sum_product: f64,
+    sum_abs_diff: f64,
+}
+
+impl AgreementStats {
+    /// Record a move compared with the other engine's analysis.
+    ///
+    /// `scores` holds the first and second engine's score of the position,
+    /// in discs from the mover's perspective, if both reported one.
+    pub fn add_move(&mut self, first_moved: bool, matched: bool, scores: Option<(f32, f32)>) {
+        let agreement = if first_moved {
+            &mut self.first
+        } else {
+            &mut self.second
+        };
+        agreement.moves += 1;
+        agreement.matches += u32::from(matched);
+
+        if let Some((first, second)) = scores {
+            let (first, second) = (f64::from(first), f64::from(second));
+            self.scored += 1;
+            self.sum_first += first;
+            self.sum_second += second;
+            self.sum_first_sq += first * first;
+            self.sum_second_sq += second * second;
+            self.sum_product += first * second;
+            self.sum_abs_diff += (first - second).abs();
+        }
+    }
+
+    /// Add the moves and scores of `other`.
+    pub fn merge(&mut self, other: &AgreementStats) {
+        for (agreement, other) in [
+            (&mut self.first, other.first),
+            (&mut self.second, other.second),
+        ] {
+            agreement.moves += other.moves;
+            agreement.matches += other.matches;
+        }
+        self.scored += other.scored;
+        self.sum_first += other.sum_first;
+        self.sum_second += other.sum_second;
+        self.sum_first_sq += other.sum_first_sq;
+        self.sum_second_sq += other.sum_second_sq;
+        self.sum_product += other.sum_product;
+        self.sum_abs_diff += other.sum_abs_diff;
+    }
+
+    /// Exchange the roles of the two engines.
+    pub fn swapped(self) -> Self {
+        Self {
+            first: self.second,
+            second: self.first,
+            sum_first: self.sum_second,
+            sum_second: self.sum_first,
+            sum_first_sq: self.sum_second_sq,
+            sum_second_sq: self.sum_first_sq,
+            ..self
+        }
+    }
+
+    /// Whether no move has been compared.
+    pub fn is_empty(&self) -> bool {
+        self.first.moves + self.second.moves == 0
+    }
+
+    /// Share of all compared moves that matched the other engine's best move.
+    pub fn overall_rate(&self) -> Option<f64> {
+        MoveAgreement {
+            moves: self.first.moves + self.second.moves,
+            matches: self.first.matches + self.second.matches,
+        }
+        .rate()
+    }
+
+    /// Pearson correlation of the two engines' scores, or `None` with fewer
+    /// than two scored positions or if either engine's scores are constant.
+    pub fn correlation(&self) -> Option<f64> {
+        if self.scored < 2 {
+            return None;
+        }
+        let n = f64::from(self.scored);
+        let covariance = self.sum_product - self.sum_first * self.sum_second / n;
+        let var_first = self.sum_first_sq - self.sum_first * self.sum_first / n;
+        let var_second = self.sum_second_sq - self.sum_second * self.sum_second / n;
+        if var_first <= 0.0 || var_second <= 0.0 {
+            return None;
+        }
+        Some((covariance / (var_first * var_second).sqrt()).clamp(-1.0, 1.0))
+    }
+
+    /// Mean absolute difference of the two engines' scores, in discs.
+    pub fn mean_abs_diff(&self) -> Option<f64> {
+        (self.scored > 0).then(|| self.sum_abs_diff / f64::from(self.scored))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_agreement() {
+        let mut stats = AgreementStats::default();
+        assert!(stats.is_empty());
+        assert_eq!(stats.overall_rate(), None);
+
+        stats.add_move(true, true, None);
+        stats.add_move(true, false, None);
+        stats.add_move(false, true, None);
+
+        assert_eq!(
+            stats.first,
+            MoveAgreement {
+                moves: 2,
+                matches: 1
+            }
+        );
+        assert_eq!(stats.second.rate(), Some(1.0));
+        assert!((stats.overall_rate().unwrap() - 2.0 / 3.0).abs() < 1e-12);
+
+        let swapped = stats.swapped();
+        assert_eq!(swapped.first, stats.second);
+        assert_eq!(swapped.second, stats.first);
+    }
+
+    #[test]
+    fn test_score_correlation() {
+        let mut stats = AgreementStats::default();
+        stats.add_move(true, true, Some((1.0, 2.0)));
+        assert_eq!(stats.correlation(), None);
+
+        stats.add_move(false, true, Some((3.0, 6.0)));
+        stats.add_move(true, true, Some((-2.0, -4.0)));
+        assert!((stats.correlation().unwrap() - 1.0).abs() < 1e-12);
+        assert!((stats.mean_abs_diff().unwrap() - 2.0).abs() < 1e-12);
+
+        // Merging the scores with the engines swapped breaks the linear relation
+        let mut merged = stats.swapped();
+        merged.merge(&stats);
+        assert_eq!(merged.scored, 6);
+        assert!(merged.correlation().unwrap() < 1.0);
+
+        let mut opposite = AgreementStats::default();
+        for (first, second) in [(1.0, -1.0), (2.0, -2.0), (4.0, -4.0)] {
+            opposite.add_move(true, false, Some((first, second)));
+        }
+        assert!((opposite.correlation().unwrap() + 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,346 @@
+//! Match checkpointing for resuming interrupted runs.
+//!
+//! A checkpoint file starts with a header line identifying the scheduled
+//! openings by their count and a hash of the list in play order,
+//! followed by one line per completed game, appended and flushed as soon as
+//! the game ends. Resuming keeps the opening pairs whose both games are
+//! recorded and plays the rest.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use crate::agreement::AgreementStats;
+use crate::error::{MatchRunnerError, Result};
+use crate::match_runner::{GameReport, Termination};
+use crate::phase::WinPhase;
+use crate::statistics::MatchWinner;
+use crate::time_tracker::ThinkTime;
+
+const HEADER_PREFIX: &str = "# match-runner checkpoint v5 openings=";
+
+/// Append-only record of the games completed in a match.
+pub struct Checkpoint {
+    file: File,
+}
+
+impl Checkpoint {
+    /// Start a new checkpoint at `path` for the scheduled `openings`,
+    /// replacing any existing file.
+    pub fn create(path: &Path, openings: &[String]) -> Result<Self> {
+        let mut file = File::create(path)?;
+        writeln!(file, "{}", header(openings))?;
+        file.flush()?;
+        Ok(Self { file })
+    }
+
+    /// Open the checkpoint for `path` according to `resume`.
+    pub fn open(path: &Path, resume: bool, openings: &[String]) -> Result<(Self, Vec<GameReport>)> {
+        if resume {
+            Self::resume(path, openings)
+        } else {
+            Ok((Self::create(path, openings)?, Vec::new()))
+        }
+    }
+
+    /// Resume from the checkpoint at `path`, or start a new one if it does
+    /// not exist.
+    ///
+    /// Returns the checkpoint together with the games of every completed
+    /// opening pair, in the order they were played. Games of unfinished
+    /// pairs are dropped from the file so that they are played again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is malformed, or was
+    /// written for a different opening schedule, e.g. after changing the
+    /// opening file or the shuffle seed, sample or repeat options.
+    pub fn resume(path: &Path, openings: &[String]) -> Result<(Self, Vec<GameReport>)> {
+        if !path.exists() {
+            return Ok((Self::create(path, openings)?, Vec::new()));
+        }
+
+        let opening_count = openings.len();
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let recorded = lines.next().transpose()?.unwrap_or_default();
+        let recorded_count = recorded
+            .strip_prefix(HEADER_PREFIX)
+            .and_then(|rest| rest.split_whitespace().next()?.parse::<usize>().ok())
+            .ok_or_else(|| invalid(path, "missing header"))?;
+        if recorded_count != opening_count {
+            return Err(MatchRunnerError::Config(format!(
+                "Checkpoint {} was written for {recorded_count} openings, but {opening_count} are scheduled.",
+                path.display()
+            )));
+        }
+        if recorded != header(openings) {
+            return Err(MatchRunnerError::Config(format!(
+                "Checkpoint {} was written for a different opening schedule. Use the same opening file, --shuffle-seed, --sample and --repeat to resume.",
+                path.display()
+            )));
+        }
+
+        let mut games = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let report = parse_report(&line).ok_or_else(|| invalid(path, &line))?;
+            if report.opening_idx >= opening_count {
+                return Err(invalid(path, &line));
+            }
+            games.push(report);
+        }
+
+        let mut games_per_opening: HashMap<usize, u32> = HashMap::new();
+        for report in &games {
+            *games_per_opening.entry(report.opening_idx).or_default() += 1;
+        }
+        games.retain(|report| games_per_opening[&report.opening_idx] == 2);
+
+        let mut checkpoint = Self::create(path, openings)?;
+        for report in &games {
+            checkpoint.record(report)?;
+        }
+
+        Ok((checkpoint, games))
+    }
+
+    /// Append a completed game.
+    pub fn record(&mut self, report: &GameReport) -> Result<()> {
+        writeln!(self.file, "{}", format_report(report))?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// The header line for a checkpoint of `openings`.
+fn header(openings: &[String]) -> String {
+    format!(
+        "{HEADER_PREFIX}{} hash={:016x}",
+        openings.len(),
+        schedule_hash(openings)
+    )
+}
+
+/// FNV-1a hash of the openings in play order, stable across builds so that
+/// checkpoints stay valid after updating match-runner.
+fn schedule_hash(openings: &[String]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for opening in openings {
+        for byte in opening.bytes().chain(*b"\n") {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+fn invalid(path: &Path, detail: &str) -> MatchRunnerError {
+    MatchRunnerError::Config(format!("Invalid checkpoint {}: {detail}", path.display()))
+}
+
+/// Format a game as
+/// `opening color winner score termination phase engine1_time engine2_time`,
+/// where phase is `-` for draws and forfeits and each time is
+/// `total_ms max_ms moves`.
+fn format_report(report: &GameReport) -> String {
+    let winner = match report.winner {
+        MatchWinner::Engine1 => "W",
+        MatchWinner::Engine2 => "L",
+        MatchWinner::Draw => "D",
+    };
+    let format_time =
+        |time: &ThinkTime| format!("{} {} {}", time.total_ms, time.max_ms, time.moves);
+
+    format!(
+        "{} {} {} {} {} {} {} {}",
+        report.opening_idx,
+        if report.engine1_black { "B" } else { "W" },
+        winner,
+        report.score,
+        report.termination.name(),
+        report.phase.map_or("-", WinPhase::name),
+        format_time(&report.engine1_time),
+        format_time(&report.engine2_time)
+    )
+}
+
+fn parse_report(line: &str) -> Option<GameReport> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [
+        opening,
+        color,
+        winner,
+        score,
+        termination,
+        phase,
+        times @ ..,
+    ] = fields.as_slice()
+    else {
+        return None;
+    };
+    let [t1, m1, n1, t2, m2, n2] = times else {
+        return None;
+    };
+    let parse_time = |total: &str, max: &str, moves: &str| {
+        Some(ThinkTime {
+            total_ms: total.parse().ok()?,
+            max_ms: max.parse().ok()?,
+            moves: moves.parse().ok()?,
+            ..ThinkTime::default()
+        })
+    };
+
+    Some(GameReport {
+        opening_idx: opening.parse().ok()?,
+        engine1_black: match *color {
+            "B" => true,
+            "W" => false,
+            _ => return None,
+        },
+        winner: match *winner {
+            "W" => MatchWinner::Engine1,
+            "L" => MatchWinner::Engine2,
+            "D" => MatchWinner::Draw,
+            _ => return None,
+        },
+        score: score.parse().ok()?,
+        termination: match *termination {
+            "normal" => Termination::Normal,
+            "time" => Termination::TimeLoss,
+            "adjudicated" => Termination::Adjudication,
+            "crash" => Termination::Crash,
+            "illegal" => Termination::IllegalMove,
+            _ => return None,
+        },
+        phase: match *phase {
+            "-" => None,
+            name => Some(WinPhase::from_name(name)?),
+        },
+        engine1_time: parse_time(t1, m1, n1)?,
+        engine2_time: parse_time(t2, m2, n2)?,
+        games_since_restart: None,
+        incident: None,
+        agreement: AgreementStats::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(opening_idx: usize, engine1_black: bool, winner: MatchWinner) -> GameReport {
+        GameReport {
+            opening_idx,
+            engine1_black,
+            winner,
+            score: 6,
+            termination: Termination::Normal,
+            engine1_time: ThinkTime {
+                total_ms: 1200,
+                max_ms: 300,
+                moves: 27,
+                ..ThinkTime::default()
+            },
+            engine2_time: ThinkTime::default(),
+            phase: Some(WinPhase::Midgame),
+            games_since_restart: None,
+            incident: None,
+            agreement: AgreementStats::default(),
+        }
+    }
+
+    #[test]
+    fn test_report_round_trip() {
+        let game = GameReport {
+            termination: Termination::Crash,
+            score: -64,
+            phase: None,
+            ..report(3, false, MatchWinner::Engine2)
+        };
+        let parsed = parse_report(&format_report(&game)).unwrap();
+        assert_eq!(format_report(&parsed), format_report(&game));
+        let game = report(3, true, MatchWinner::Engine1);
+        let parsed = parse_report(&format_report(&game)).unwrap();
+        assert_eq!(parsed.phase, Some(WinPhase::Midgame));
+        assert!(parse_report("3 B W 6").is_none());
+        assert!(parse_report("3 X W 6 normal - 0 0 0 0 0 0").is_none());
+        assert!(parse_report("3 B W 6 forfeit - 0 0 0 0 0 0").is_none());
+        assert!(parse_report("3 B W 6 normal opening 0 0 0 0 0 0").is_none());
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "match-runner-checkpoint-{}-{name}.txt",
+            std::process::id()
+        ))
+    }
+
+    fn openings(moves: &[&str]) -> Vec<String> {
+        moves.iter().map(|moves| moves.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resume_keeps_completed_pairs() {
+        let path = temp_path("pairs");
+        let openings = openings(&["f5", "f5d6", "f5f6", "f5f4"]);
+
+        let mut checkpoint = Checkpoint::create(&path, &openings).unwrap();
+        checkpoint
+            .record(&report(1, true, MatchWinner::Engine1))
+            .unwrap();
+        checkpoint
+            .record(&report(0, true, MatchWinner::Draw))
+            .unwrap();
+        checkpoint
+            .record(&report(1, false, MatchWinner::Engine2))
+            .unwrap();
+        drop(checkpoint);
+
+        let (_, games) = Checkpoint::resume(&path, &openings).unwrap();
+        assert_eq!(games.len(), 2);
+        assert!(games.iter().all(|game| game.opening_idx == 1));
+
+        // The unfinished pair was dropped from the file as well
+        let (_, games) = Checkpoint::resume(&path, &openings).unwrap();
+        assert_eq!(games.len(), 2);
+
+        let mut more = openings.clone();
+        more.push("f5d6c3".to_string());
+        assert!(Checkpoint::resume(&path, &more).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resume_rejects_different_schedule() {
+        let path = temp_path("schedule");
+        let scheduled = openings(&["f5", "f5d6", "f5f6", "f5f4"]);
+        let mut checkpoint = Checkpoint::create(&path, &scheduled).unwrap();
+        checkpoint
+            .record(&report(0, true, MatchWinner::Engine1))
+            .unwrap();
+        drop(checkpoint);
+
+        // Same count, but shuffled with another seed or sampled from another file
+        let reordered = openings(&["f5d6", "f5", "f5f6", "f5f4"]);
+        let replaced = openings(&["f5", "f5d6", "f5f6", "f5d6c3"]);
+        for other in [reordered, replaced] {
+            assert!(matches!(
+                Checkpoint::resume(&path, &other),
+                Err(MatchRunnerError::Config(_))
+            ));
+        }
+
+        // A rejected resume leaves the checkpoint untouched
+        let (_, games) = Checkpoint::resume(&path, &scheduled).unwrap();
+        assert!(games.is_empty());
+        assert!(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .starts_with(&header(&scheduled))
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
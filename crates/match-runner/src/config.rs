@@ -4,11 +4,19 @@
 //! for the match runner engine testing tool.
 
 use clap::Parser;
+use rand::{SeedableRng, rngs::SmallRng, seq::SliceRandom};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::error::Result;
+use crate::adjudication::Adjudication;
+use crate::engine::EngineProtocol;
+use crate::error::{MatchRunnerError, Result};
+use crate::game_writer::GameFormat;
+use crate::statistics::Sprt;
+use crate::time_tracker::{CapAction, MoveTimeCap};
+use crate::tournament::TournamentMode;
 
 /// Configuration for running automated matches between two GTP engines.
 ///
@@ -27,7 +35,17 @@ use crate::error::Result;
 /// - Pure byoyomi (fixed time per move): `--main-time 0 --byoyomi-time N --byoyomi-stones 0`
 /// - Fischer (main time + increment): `--main-time M --byoyomi-time N --byoyomi-stones 0`
 /// - Japanese byo-yomi: `--main-time M --byoyomi-time N --byoyomi-stones 1`
-#[derive(Parser, Debug)]
+///
+/// # SPRT
+///
+/// Passing both `--elo0` and `--elo1` runs a sequential probability ratio
+/// test, stopping the match as soon as either hypothesis is accepted.
+///
+/// # Tournaments
+///
+/// Additional engines given with `--engine` turn the match into a tournament
+/// of two-engine matches, paired by `--tournament`.
+#[derive(Parser, Debug, Clone)]
 #[command(
     author,
     version,
@@ -42,6 +60,14 @@ pub struct Config {
     #[arg(long)]
     pub engine1_working_dir: Option<PathBuf>,
 
+    /// Command sent to the first engine after startup, in its protocol (repeatable)
+    #[arg(long, value_name = "COMMAND")]
+    pub engine1_init: Vec<String>,
+
+    /// Protocol spoken by the first engine
+    #[arg(long, value_enum, default_value_t = EngineProtocol::Gtp)]
+    pub engine1_protocol: EngineProtocol,
+
     /// Command for the second engine (program path and arguments)
     #[arg(short = '2', long)]
     pub engine2: String,
@@ -50,9 +76,66 @@ pub struct Config {
     #[arg(long)]
     pub engine2_working_dir: Option<PathBuf>,
 
-    /// Opening file (required)
-    #[arg(short, long, required = true)]
-    pub opening_file: PathBuf,
+    /// Command sent to the second engine after startup, in its protocol (repeatable)
+    #[arg(long, value_name = "COMMAND")]
+    pub engine2_init: Vec<String>,
+
+    /// Protocol spoken by the second engine
+    #[arg(long, value_enum, default_value_t = EngineProtocol::Gtp)]
+    pub engine2_protocol: EngineProtocol,
+
+    /// Fixed midgame search depth of the first engine, sent with the `nr-depth` GTP extension
+    #[arg(long, value_name = "DEPTH", value_parser = clap::value_parser!(u32).range(1..=60))]
+    pub engine1_depth: Option<u32>,
+
+    /// Node budget per move of the first engine, sent with the `nr-nodes` GTP extension
+    #[arg(long, value_name = "NODES", value_parser = clap::value_parser!(u64).range(1..))]
+    pub engine1_nodes: Option<u64>,
+
+    /// Fixed midgame search depth of the second engine, sent with the `nr-depth` GTP extension
+    #[arg(long, value_name = "DEPTH", value_parser = clap::value_parser!(u32).range(1..=60))]
+    pub engine2_depth: Option<u32>,
+
+    /// Node budget per move of the second engine, sent with the `nr-nodes` GTP extension
+    #[arg(long, value_name = "NODES", value_parser = clap::value_parser!(u64).range(1..))]
+    pub engine2_nodes: Option<u64>,
+
+    /// Node budget per move of every engine, for comparisons that do not depend on machine
+    /// load (overridden by --engine1-nodes and --engine2-nodes)
+    #[arg(long, value_name = "NODES", value_parser = clap::value_parser!(u64).range(1..))]
+    pub nodes: Option<u64>,
+
+    /// Command for an additional engine, making the match a tournament (repeatable)
+    #[arg(long = "engine", value_name = "ENGINE")]
+    pub engines: Vec<String>,
+
+    /// Pairing of a tournament: every engine against every other, or engine1 against the rest
+    #[arg(long, value_enum, default_value_t = TournamentMode::RoundRobin)]
+    pub tournament: TournamentMode,
+
+    /// Opening file (required unless playing as a remote worker)
+    #[arg(short, long, required_unless_present = "connect")]
+    pub opening_file: Option<PathBuf>,
+
+    /// Shuffle the openings with this seed
+    #[arg(long)]
+    pub shuffle_seed: Option<u64>,
+
+    /// Play only the first N openings, after shuffling
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub sample: Option<u64>,
+
+    /// Play the openings this many times, each time as a pair of games with swapped colors
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub repeat: u32,
+
+    /// Stop after this many games in this run, rounded up to whole pairs
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "connect")]
+    pub max_games: Option<u32>,
+
+    /// Stop handing out openings after this many seconds in this run, finishing the games in progress
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "connect")]
+    pub max_duration: Option<u64>,
 
     /// Main time in seconds (0 for no main time, starts in byoyomi)
     #[arg(long, default_value_t = 0)]
@@ -65,6 +148,103 @@ pub struct Config {
     /// Byoyomi stones (0: time is increment/per-move, 1+: stones per byoyomi period)
     #[arg(long, default_value_t = 0)]
     pub byoyomi_stones: u32,
+
+    /// Seconds to wait for an engine response, on top of the mover's remaining time for genmove
+    /// (0 to wait indefinitely). Engines that exceed it are restarted and forfeit the game
+    #[arg(long, default_value_t = 0)]
+    pub timeout: u64,
+
+    /// Longest wall time in milliseconds allowed for a single move, on top of the time control
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub move_time_cap: Option<u64>,
+
+    /// What happens when a move exceeds --move-time-cap: the mover loses on time, or the
+    /// violation is only counted
+    #[arg(long, value_enum, default_value_t = CapAction::Forfeit, requires = "move_time_cap")]
+    pub cap_action: CapAction,
+
+    /// Restart the engine processes after every N games played on them
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub restart_every: Option<u32>,
+
+    /// Directory for the evidence of illegal moves, created when one is played
+    #[arg(long, default_value = "illegal-moves")]
+    pub evidence_dir: PathBuf,
+
+    /// Directory for one log file per game with the timestamped GTP traffic and final board
+    #[arg(long)]
+    pub log_dir: Option<PathBuf>,
+
+    /// Number of games played simultaneously, each with its own pair of engine processes
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub concurrency: u32,
+
+    /// Also hand out openings to remote workers connecting to this address (e.g. 0.0.0.0:7878)
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["connect", "engines"])]
+    pub serve: Option<String>,
+
+    /// Play games for the coordinator at this address instead of running a match, with
+    /// --concurrency connections each using its own pair of engines
+    #[arg(long, value_name = "ADDR", conflicts_with = "engines")]
+    pub connect: Option<String>,
+
+    /// File recording every completed game, for resuming an interrupted match
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Resume the match recorded in the checkpoint file instead of starting over
+    #[arg(long, requires = "checkpoint")]
+    pub resume: bool,
+
+    /// File to write every played game to
+    #[arg(long)]
+    pub games_file: Option<PathBuf>,
+
+    /// Format of the games file (default: inferred from its extension, .wtb for WTHOR)
+    #[arg(long, value_enum, requires = "games_file")]
+    pub games_format: Option<GameFormat>,
+
+    /// JSON Lines file to append the result of every finished game to
+    #[arg(long)]
+    pub results_file: Option<PathBuf>,
+
+    /// Show a full-screen dashboard instead of the live statistics and progress bar
+    #[arg(long, conflicts_with = "connect")]
+    pub tui: bool,
+
+    /// Adjudicate games by solving them once at most this many squares are empty (0 to disable, at most 16)
+    #[arg(long, default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..=16))]
+    pub adjudicate_empties: u32,
+
+    /// Adjudicate games once both engines report scores of at least this many discs for the same side
+    /// (requires engines supporting the nr-score GTP extension)
+    #[arg(long)]
+    pub adjudicate_score: Option<f32>,
+
+    /// Consecutive moves with a decisive score needed for score adjudication
+    #[arg(long, default_value_t = 4)]
+    pub adjudicate_moves: u32,
+
+    /// Compare every move with the waiting engine's analysis at this level, for a move agreement
+    /// and score correlation report (requires engines supporting the nr-eval GTP extension)
+    #[arg(long, value_name = "LEVEL", value_parser = clap::value_parser!(u32).range(1..))]
+    pub agreement_level: Option<u32>,
+
+    /// SPRT null hypothesis Elo difference (requires --elo1)
+    #[arg(long, requires = "elo1", allow_negative_numbers = true)]
+    pub elo0: Option<f64>,
+
+    /// SPRT alternative hypothesis Elo difference (requires --elo0)
+    #[arg(long, requires = "elo0", allow_negative_numbers = true)]
+    pub elo1: Option<f64>,
+
+    /// SPRT false positive rate
+    #[arg(long, default_value_t = 0.05)]
+    pub alpha: f64,
+
+    /// SPRT false negative rate
+    #[arg(long, default_value_t = 0.05)]
+    pub beta: f64,
 }
 
 impl Config {
@@ -80,18 +260,93 @@ impl Config {
     /// Load opening positions from the configured opening file.
     ///
     /// Reads the opening file line by line, filtering out comments (lines starting with '#')
-    /// and empty lines.
+    /// and empty lines, then applies the shuffle, sample and repeat options.
     ///
     /// # Returns
     ///
-    /// A vector of opening position strings. Each string represents a sequence of moves
-    /// in algebraic notation (e.g., "f5d6c3d3c4f4").
+    /// A vector of opening position strings in the order they are played. Each string
+    /// represents a sequence of moves in algebraic notation (e.g., "f5d6c3d3c4f4").
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or if there are I/O issues.
+    /// Returns an error if the file cannot be read, if there are I/O issues, or if
+    /// the sample is larger than the opening file.
     pub fn load_openings(&self) -> Result<Vec<String>> {
-        read_opening_file(&self.opening_file)
+        let path = self
+            .opening_file
+            .as_deref()
+            .ok_or_else(|| MatchRunnerError::Config("No opening file given.".to_string()))?;
+        self.schedule_openings(read_opening_file(path)?)
+    }
+
+    /// Order the openings of the file for play: shuffle them if a seed is given,
+    /// keep the sample, and repeat the result.
+    fn schedule_openings(&self, mut openings: Vec<String>) -> Result<Vec<String>> {
+        if let Some(seed) = self.shuffle_seed {
+            openings.shuffle(&mut SmallRng::seed_from_u64(seed));
+        }
+
+        if let Some(sample) = self.sample {
+            if sample > openings.len() as u64 {
+                return Err(MatchRunnerError::Config(format!(
+                    "--sample {sample} exceeds the {} openings in the opening file.",
+                    openings.len()
+                )));
+            }
+            openings.truncate(sample as usize);
+        }
+
+        let count = openings.len() * self.repeat as usize;
+        Ok(openings.into_iter().cycle().take(count).collect())
+    }
+
+    /// Get the SPRT parameters, if SPRT is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `elo1` is not greater than `elo0` or if `alpha`
+    /// or `beta` is outside (0, 1).
+    pub fn sprt(&self) -> Result<Option<Sprt>> {
+        let (Some(elo0), Some(elo1)) = (self.elo0, self.elo1) else {
+            return Ok(None);
+        };
+
+        if elo1 <= elo0 {
+            return Err(MatchRunnerError::Config(
+                "--elo1 must be greater than --elo0.".to_string(),
+            ));
+        }
+        for (name, rate) in [("--alpha", self.alpha), ("--beta", self.beta)] {
+            if !(rate > 0.0 && rate < 1.0) {
+                return Err(MatchRunnerError::Config(format!(
+                    "{name} must be between 0 and 1."
+                )));
+            }
+        }
+
+        Ok(Some(Sprt {
+            elo0,
+            elo1,
+            alpha: self.alpha,
+            beta: self.beta,
+        }))
+    }
+
+    /// Get the adjudication settings.
+    pub fn adjudication(&self) -> Adjudication {
+        Adjudication {
+            solve_empties: self.adjudicate_empties,
+            score_threshold: self.adjudicate_score,
+            score_moves: self.adjudicate_moves,
+        }
+    }
+
+    /// Get the per-move time cap, if enabled.
+    pub fn move_time_cap(&self) -> Option<MoveTimeCap> {
+        self.move_time_cap.map(|limit_ms| MoveTimeCap {
+            limit_ms,
+            action: self.cap_action,
+        })
     }
 
     /// Parse an engine command string into program and arguments.
@@ -108,15 +363,7 @@ impl Config {
     ///
     /// A tuple containing the program path and a vector of arguments.
     pub fn parse_engine_command(&self, engine_cmd: &str) -> (String, Vec<String>) {
-        #[cfg(target_os = "windows")]
-        {
-            parse_windows_command(engine_cmd)
-        }
-
-        #[cfg(not(target_os = "windows"))]
-        {
-            parse_unix_command(engine_cmd)
-        }
+        split_command(engine_cmd)
     }
 
     /// Get the parsed command for engine 1.
@@ -136,6 +383,76 @@ impl Config {
     pub fn get_engine2_command(&self) -> (String, Vec<String>) {
         self.parse_engine_command(&self.engine2)
     }
+
+    /// Get the search limits of engine 1.
+    pub fn engine1_limits(&self) -> SearchLimits {
+        SearchLimits {
+            depth: self.engine1_depth,
+            nodes: self.engine1_nodes.or(self.nodes),
+        }
+    }
+
+    /// Get the search limits of engine 2.
+    pub fn engine2_limits(&self) -> SearchLimits {
+        SearchLimits {
+            depth: self.engine2_depth,
+            nodes: self.engine2_nodes.or(self.nodes),
+        }
+    }
+
+    /// Get the GTP commands that initialize engine 1: its init commands
+    /// followed by its search limits.
+    pub fn engine1_init_commands(&self) -> Vec<String> {
+        [self.engine1_init.clone(), self.engine1_limits().commands()].concat()
+    }
+
+    /// Get the GTP commands that initialize engine 2: its init commands
+    /// followed by its search limits.
+    pub fn engine2_init_commands(&self) -> Vec<String> {
+        [self.engine2_init.clone(), self.engine2_limits().commands()].concat()
+    }
+
+    /// Get the engine response timeout, if enabled.
+    pub fn engine_timeout(&self) -> Option<Duration> {
+        (self.timeout > 0).then(|| Duration::from_secs(self.timeout))
+    }
+
+    /// Check whether more than two engines take part.
+    pub fn is_tournament(&self) -> bool {
+        !self.engines.is_empty()
+    }
+}
+
+/// Search limits imposed on an engine through Neural Reversi GTP extensions,
+/// for handicap matches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Fixed midgame search depth (`nr-depth`)
+    pub depth: Option<u32>,
+    /// Node budget per move (`nr-nodes`)
+    pub nodes: Option<u64>,
+}
+
+impl SearchLimits {
+    /// GTP commands that apply the limits.
+    pub fn commands(&self) -> Vec<String> {
+        let depth = self.depth.map(|depth| format!("nr-depth {depth}"));
+        let nodes = self.nodes.map(|nodes| format!("nr-nodes {nodes}"));
+        depth.into_iter().chain(nodes).collect()
+    }
+
+    /// Append the limits to an engine name for reports, e.g.
+    /// `Neural Reversi (depth 8)`.
+    pub fn label(&self, name: String) -> String {
+        let depth = self.depth.map(|depth| format!("depth {depth}"));
+        let nodes = self.nodes.map(|nodes| format!("{nodes} nodes"));
+        let limits: Vec<String> = depth.into_iter().chain(nodes).collect();
+        if limits.is_empty() {
+            name
+        } else {
+            format!("{name} ({})", limits.join(", "))
+        }
+    }
 }
 
 /// Read opening positions from a file.
@@ -170,6 +487,20 @@ fn read_opening_file(path: &Path) -> Result<Vec<String>> {
     Ok(openings)
 }
 
+/// Split an engine command string into program and arguments, with the
+/// command-line parsing rules of the platform.
+pub fn split_command(cmd: &str) -> (String, Vec<String>) {
+    #[cfg(target_os = "windows")]
+    {
+        parse_windows_command(cmd)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        parse_unix_command(cmd)
+    }
+}
+
 /// Parse a command string using Unix shell-like rules.
 #[cfg(not(target_os = "windows"))]
 fn parse_unix_command(cmd: &str) -> (String, Vec<String>) {
@@ -259,11 +590,50 @@ mod tests {
             engine1: "./engine --level 10".to_string(),
             engine2: "engine2".to_string(),
             engine1_working_dir: None,
+            engine1_protocol: EngineProtocol::Gtp,
+            engine1_init: Vec::new(),
             engine2_working_dir: None,
-            opening_file: PathBuf::from("test_openings.txt"),
+            engine2_protocol: EngineProtocol::Gtp,
+            engine2_init: Vec::new(),
+            engine1_depth: None,
+            engine1_nodes: None,
+            engine2_depth: None,
+            engine2_nodes: None,
+            nodes: None,
+            engines: Vec::new(),
+            tournament: TournamentMode::RoundRobin,
+            opening_file: Some(PathBuf::from("test_openings.txt")),
+            shuffle_seed: None,
+            sample: None,
+            repeat: 1,
+            max_games: None,
+            max_duration: None,
             main_time: 0,
             byoyomi_time: 0,
             byoyomi_stones: 0,
+            timeout: 0,
+            move_time_cap: None,
+            cap_action: CapAction::Forfeit,
+            restart_every: None,
+            evidence_dir: PathBuf::from("illegal-moves"),
+            log_dir: None,
+            concurrency: 1,
+            serve: None,
+            connect: None,
+            checkpoint: None,
+            resume: false,
+            games_file: None,
+            games_format: None,
+            results_file: None,
+            tui: false,
+            adjudicate_empties: 0,
+            adjudicate_score: None,
+            adjudicate_moves: 4,
+            agreement_level: None,
+            elo0: None,
+            elo1: None,
+            alpha: 0.05,
+            beta: 0.05,
         };
 
         let (program, args) = config.parse_engine_command("./reversi_cli --level 10");
@@ -271,6 +641,183 @@ mod tests {
         assert_eq!(args, vec!["--level", "10"]);
     }
 
+    #[test]
+    fn test_schedule_openings() {
+        let args = ["match-runner", "-1", "a", "-2", "b", "-o", "openings.txt"];
+        let openings: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+
+        let config = Config::parse_from(args);
+        assert_eq!(
+            config.schedule_openings(openings.clone()).unwrap(),
+            openings
+        );
+
+        let config = Config::parse_from(args.into_iter().chain(["--sample", "3", "--repeat", "2"]));
+        assert_eq!(
+            config.schedule_openings(openings.clone()).unwrap(),
+            ["0", "1", "2", "0", "1", "2"]
+        );
+
+        let shuffled = Config::parse_from(args.into_iter().chain(["--shuffle-seed", "7"]));
+        let first = shuffled.schedule_openings(openings.clone()).unwrap();
+        assert_eq!(first, shuffled.schedule_openings(openings.clone()).unwrap());
+        assert_ne!(first, openings);
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, openings);
+
+        let config = Config::parse_from(args.into_iter().chain(["--sample", "11"]));
+        assert!(config.schedule_openings(openings).is_err());
+    }
+
+    #[test]
+    fn test_adjudication_config() {
+        let args = ["match-runner", "-1", "a", "-2", "b", "-o", "openings.txt"];
+        let adjudication = Config::parse_from(args).adjudication();
+        assert_eq!(adjudication.solve_empties, 0);
+        assert!(adjudication.score_adjudicator().is_none());
+
+        let config = Config::parse_from(args.into_iter().chain([
+            "--adjudicate-empties",
+            "12",
+            "--adjudicate-score",
+            "30",
+        ]));
+        let adjudication = config.adjudication();
+        assert_eq!(adjudication.solve_empties, 12);
+        assert_eq!(adjudication.score_threshold, Some(30.0));
+        assert_eq!(adjudication.score_moves, 4);
+
+        assert!(
+            Config::try_parse_from(args.into_iter().chain(["--adjudicate-empties", "20"])).is_err()
+        );
+    }
+
+    #[test]
+    fn test_engine_init_commands() {
+        let config = Config::parse_from([
+            "match-runner",
+            "-1",
+            "a",
+            "-2",
+            "b",
+            "-o",
+            "openings.txt",
+            "--engine1-init",
+            "set hash 64",
+            "--engine1-init",
+            "set threads 4",
+        ]);
+        assert_eq!(config.engine1_init, ["set hash 64", "set threads 4"]);
+        assert!(config.engine2_init.is_empty());
+    }
+
+    #[test]
+    fn test_engine_search_limits() {
+        let config = Config::parse_from([
+            "match-runner",
+            "-1",
+            "a",
+            "-2",
+            "b",
+            "-o",
+            "openings.txt",
+            "--engine1-init",
+            "set hash 64",
+            "--engine1-depth",
+            "8",
+            "--engine2-nodes",
+            "100000",
+        ]);
+        assert_eq!(
+            config.engine1_init_commands(),
+            ["set hash 64", "nr-depth 8"]
+        );
+        assert_eq!(config.engine2_init_commands(), ["nr-nodes 100000"]);
+        assert_eq!(
+            config.engine1_limits().label("a".to_string()),
+            "a (depth 8)"
+        );
+        assert_eq!(SearchLimits::default().label("b".to_string()), "b");
+
+        let fixed = Config::parse_from([
+            "match-runner",
+            "-1",
+            "a",
+            "-2",
+            "b",
+            "-o",
+            "openings.txt",
+            "--nodes",
+            "50000",
+            "--engine2-nodes",
+            "20000",
+        ]);
+        assert_eq!(fixed.engine1_init_commands(), ["nr-nodes 50000"]);
+        assert_eq!(fixed.engine2_init_commands(), ["nr-nodes 20000"]);
+
+        let both = SearchLimits {
+            depth: Some(12),
+            nodes: Some(5000),
+        };
+        assert_eq!(both.label("c".to_string()), "c (depth 12, 5000 nodes)");
+        assert!(
+            Config::try_parse_from([
+                "match-runner",
+                "-1",
+                "a",
+                "-2",
+                "b",
+                "-o",
+                "openings.txt",
+                "--engine1-depth",
+                "0",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_sprt_config() {
+        let mut config =
+            Config::parse_from(["match-runner", "-1", "a", "-2", "b", "-o", "openings.txt"]);
+        assert!(config.sprt().unwrap().is_none());
+
+        config = Config::parse_from([
+            "match-runner",
+            "-1",
+            "a",
+            "-2",
+            "b",
+            "-o",
+            "openings.txt",
+            "--elo0",
+            "-2",
+            "--elo1",
+            "3",
+        ]);
+        let sprt = config.sprt().unwrap().unwrap();
+        assert_eq!((sprt.elo0, sprt.elo1), (-2.0, 3.0));
+
+        config.elo1 = Some(-5.0);
+        assert!(config.sprt().is_err());
+
+        assert!(
+            Config::try_parse_from([
+                "match-runner",
+                "-1",
+                "a",
+                "-2",
+                "b",
+                "-o",
+                "o",
+                "--elo0",
+                "0"
+            ])
+            .is_err()
+        );
+    }
+
     #[test]
     #[allow(unused_variables)]
     fn test_parse_quoted_command() {
@@ -278,11 +825,50 @@ mod tests {
             engine1: "".to_string(),
             engine2: "".to_string(),
             engine1_working_dir: None,
+            engine1_protocol: EngineProtocol::Gtp,
+            engine1_init: Vec::new(),
             engine2_working_dir: None,
-            opening_file: PathBuf::from("test_openings.txt"),
+            engine2_protocol: EngineProtocol::Gtp,
+            engine2_init: Vec::new(),
+            engine1_depth: None,
+            engine1_nodes: None,
+            engine2_depth: None,
+            engine2_nodes: None,
+            nodes: None,
+            engines: Vec::new(),
+            tournament: TournamentMode::RoundRobin,
+            opening_file: Some(PathBuf::from("test_openings.txt")),
+            shuffle_seed: None,
+            sample: None,
+            repeat: 1,
+            max_games: None,
+            max_duration: None,
             main_time: 0,
             byoyomi_time: 0,
             byoyomi_stones: 0,
+            timeout: 0,
+            move_time_cap: None,
+            cap_action: CapAction::Forfeit,
+            restart_every: None,
+            evidence_dir: PathBuf::from("illegal-moves"),
+            log_dir: None,
+            concurrency: 1,
+            serve: None,
+            connect: None,
+            checkpoint: None,
+            resume: false,
+            games_file: None,
+            games_format: None,
+            results_file: None,
+            tui: false,
+            adjudicate_empties: 0,
+            adjudicate_score: None,
+            adjudicate_moves: 4,
+            agreement_level: None,
+            elo0: None,
+            elo1: None,
+            alpha: 0.05,
+            beta: 0.05,
         };
 
         // Test with quotes (behavior varies by platform)
@@ -302,11 +888,50 @@ mod tests {
             engine1: "".to_string(),
             engine2: "".to_string(),
             engine1_working_dir: None,
+            engine1_protocol: EngineProtocol::Gtp,
+            engine1_init: Vec::new(),
             engine2_working_dir: None,
-            opening_file: PathBuf::from("test_openings.txt"),
+            engine2_protocol: EngineProtocol::Gtp,
+            engine2_init: Vec::new(),
+            engine1_depth: None,
+            engine1_nodes: None,
+            engine2_depth: None,
+            engine2_nodes: None,
+            nodes: None,
+            engines: Vec::new(),
+            tournament: TournamentMode::RoundRobin,
+            opening_file: Some(PathBuf::from("test_openings.txt")),
+            shuffle_seed: None,
+            sample: None,
+            repeat: 1,
+            max_games: None,
+            max_duration: None,
             main_time: 0,
             byoyomi_time: 0,
             byoyomi_stones: 0,
+            timeout: 0,
+            move_time_cap: None,
+            cap_action: CapAction::Forfeit,
+            restart_every: None,
+            evidence_dir: PathBuf::from("illegal-moves"),
+            log_dir: None,
+            concurrency: 1,
+            serve: None,
+            connect: None,
+            checkpoint: None,
+            resume: false,
+            games_file: None,
+            games_format: None,
+            results_file: None,
+            tui: false,
+            adjudicate_empties: 0,
+            adjudicate_score: None,
+            adjudicate_moves: 4,
+            agreement_level: None,
+            elo0: None,
+            elo1: None,
+            alpha: 0.05,
+            beta: 0.05,
         };
 
         let (program, args) = config.parse_engine_command("");
@@ -321,11 +946,50 @@ mod tests {
             engine1: "".to_string(),
             engine2: "".to_string(),
             engine1_working_dir: None,
+            engine1_protocol: EngineProtocol::Gtp,
+            engine1_init: Vec::new(),
             engine2_working_dir: None,
-            opening_file: PathBuf::from("test_openings.txt"),
+            engine2_protocol: EngineProtocol::Gtp,
+            engine2_init: Vec::new(),
+            engine1_depth: None,
+            engine1_nodes: None,
+            engine2_depth: None,
+            engine2_nodes: None,
+            nodes: None,
+            engines: Vec::new(),
+            tournament: TournamentMode::RoundRobin,
+            opening_file: Some(PathBuf::from("test_openings.txt")),
+            shuffle_seed: None,
+            sample: None,
+            repeat: 1,
+            max_games: None,
+            max_duration: None,
             main_time: 0,
             byoyomi_time: 0,
             byoyomi_stones: 0,
+            timeout: 0,
+            move_time_cap: None,
+            cap_action: CapAction::Forfeit,
+            restart_every: None,
+            evidence_dir: PathBuf::from("illegal-moves"),
+            log_dir: None,
+            concurrency: 1,
+            serve: None,
+            connect: None,
+            checkpoint: None,
+            resume: false,
+            games_file: None,
+            games_format: None,
+            results_file: None,
+            tui: false,
+            adjudicate_empties: 0,
+            adjudicate_score: None,
+            adjudicate_moves: 4,
+            agreement_level: None,
+            elo0: None,
+            elo1: None,
+            alpha: 0.05,
+            beta: 0.05,
         };
 
         // Test Windows path with spaces
@@ -349,11 +1013,50 @@ mod tests {
             engine1: "".to_string(),
             engine2: "".to_string(),
             engine1_working_dir: None,
+            engine1_protocol: EngineProtocol::Gtp,
+            engine1_init: Vec::new(),
             engine2_working_dir: None,
-            opening_file: PathBuf::from("test_openings.txt"),
+            engine2_protocol: EngineProtocol::Gtp,
+            engine2_init: Vec::new(),
+            engine1_depth: None,
+            engine1_nodes: None,
+            engine2_depth: None,
+            engine2_nodes: None,
+            nodes: None,
+            engines: Vec::new(),
+            tournament: TournamentMode::RoundRobin,
+            opening_file: Some(PathBuf::from("test_openings.txt")),
+            shuffle_seed: None,
+            sample: None,
+            repeat: 1,
+            max_games: None,
+            max_duration: None,
             main_time: 0,
             byoyomi_time: 0,
             byoyomi_stones: 0,
+            timeout: 0,
+            move_time_cap: None,
+            cap_action: CapAction::Forfeit,
+            restart_every: None,
+            evidence_dir: PathBuf::from("illegal-moves"),
+            log_dir: None,
+            concurrency: 1,
+            serve: None,
+            connect: None,
+            checkpoint: None,
+            resume: false,
+            games_file: None,
+            games_format: None,
+            results_file: None,
+            tui: false,
+            adjudicate_empties: 0,
+            adjudicate_score: None,
+            adjudicate_moves: 4,
+            agreement_level: None,
+            elo0: None,
+            elo1: None,
+            alpha: 0.05,
+            beta: 0.05,
         };
 
         // Test simple backslash path
@@ -374,11 +1077,50 @@ mod tests {
             engine1: "".to_string(),
             engine2: "".to_string(),
             engine1_working_dir: None,
+            engine1_protocol: EngineProtocol::Gtp,
+            engine1_init: Vec::new(),
             engine2_working_dir: None,
-            opening_file: PathBuf::from("test_openings.txt"),
+            engine2_protocol: EngineProtocol::Gtp,
+            engine2_init: Vec::new(),
+            engine1_depth: None,
+            engine1_nodes: None,
+            engine2_depth: None,
+            engine2_nodes: None,
+            nodes: None,
+            engines: Vec::new(),
+            tournament: TournamentMode::RoundRobin,
+            opening_file: Some(PathBuf::from("test_openings.txt")),
+            shuffle_seed: None,
+            sample: None,
+            repeat: 1,
+            max_games: None,
+            max_duration: None,
             main_time: 0,
             byoyomi_time: 0,
             byoyomi_stones: 0,
+            timeout: 0,
+            move_time_cap: None,
+            cap_action: CapAction::Forfeit,
+            restart_every: None,
+            evidence_dir: PathBuf::from("illegal-moves"),
+            log_dir: None,
+            concurrency: 1,
+            serve: None,
+            connect: None,
+            checkpoint: None,
+            resume: false,
+            games_file: None,
+            games_format: None,
+            results_file: None,
+            tui: false,
+            adjudicate_empties: 0,
+            adjudicate_score: None,
+            adjudicate_moves: 4,
+            agreement_level: None,
+            elo0: None,
+            elo1: None,
+            alpha: 0.05,
+            beta: 0.05,
         };
 
         // Test escaped spaces (shell-style) - shlex interprets the escape
@@ -3,6 +3,8 @@
 //! This module handles all terminal-based user interface elements including
 //! progress visualization, real-time match statistics, and formatted output.
 
+pub mod dashboard;
+
 use crate::colors::ThemeColor;
 use crate::statistics::{MatchStatistics, MatchWinner};
 use colored::*;
@@ -245,6 +247,7 @@ impl DisplayManager {
         println!("{}{}{}", CLEAR_LINE, layout.padding, separator.subtext());
 
         self.display_score_summary(statistics, layout);
+        self.display_sprt(statistics, layout);
 
         println!("{}{}", CLEAR_LINE, layout.padding);
         println!(
@@ -270,6 +273,27 @@ impl DisplayManager {
         );
     }
 
+    fn display_sprt(&self, statistics: &MatchStatistics, layout: &DisplayLayout) {
+        let (Some(sprt), Some(llr)) = (statistics.sprt, statistics.sprt_llr()) else {
+            return;
+        };
+        let (lower, upper) = sprt.bounds();
+
+        println!(
+            "{}{}{:>width$}: {} {}",
+            CLEAR_LINE,
+            layout.padding,
+            "LLR".text(),
+            format!("{llr:+.2}").primary(),
+            format!(
+                "({lower:+.2}, {upper:+.2}) [{:.1}, {:.1}]",
+                sprt.elo0, sprt.elo1
+            )
+            .subtext(),
+            width = layout.name_width
+        );
+    }
+
     fn display_recent_games(&self, statistics: &MatchStatistics, layout: &DisplayLayout) {
         if statistics.recent_results.is_empty() {
             return;
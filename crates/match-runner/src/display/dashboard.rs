@@ -0,0 +1,678 @@
+//! Full-screen terminal dashboard for running matches.
+//!
+//! Enabled with `--tui`, the dashboard replaces the live statistics and the
+//! progress bar with one screen showing the results, Elo and LLR graphs over
+//! the games played, each engine's time usage, the games in progress and the
+//! most recent games. Pressing `q` or Ctrl-C stops the match after the games
+//! in progress, and pressing either again quits at once.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    DefaultTerminal, Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{Axis, Block, Chart, Dataset, Gauge, GraphType, Paragraph},
+};
+use reversi_core::disc::Disc;
+
+use crate::statistics::{MatchStatistics, MatchWinner, PentanomialCalculator};
+use crate::time_tracker::ThinkTime;
+
+/// Colors of the dashboard, matching the `colors` theme of the line display.
+const PRIMARY: Color = Color::Rgb(100, 210, 255);
+const SUBTEXT: Color = Color::Rgb(100, 110, 150);
+const SUCCESS: Color = Color::Rgb(80, 250, 210);
+const FAILURE: Color = Color::Rgb(255, 90, 120);
+const INFO: Color = Color::Rgb(130, 170, 255);
+const TEXT: Color = Color::Rgb(220, 230, 255);
+const WARNING: Color = Color::Rgb(255, 210, 100);
+
+/// Width of the result bars in the results panel.
+const RESULT_BAR_WIDTH: usize = 24;
+
+/// Interval at which the dashboard is redrawn while no game finishes.
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Longest opening shown in the game lists.
+const MAX_OPENING_LEN: usize = 16;
+
+/// A game being played, or an opening pair assigned to a remote worker.
+#[derive(Debug, Clone)]
+pub struct LiveGame {
+    /// Number of the game in the match, counting from 1
+    pub number: usize,
+    /// Opening the game starts from
+    pub opening: String,
+    /// Color of the first engine, or `None` for a pair played remotely
+    pub engine1_color: Option<Disc>,
+    /// Address of the remote worker playing the pair, if any
+    pub worker: Option<String>,
+    /// When the game started
+    pub started: Instant,
+}
+
+/// Games in progress across the workers of a match, keyed by opening.
+#[derive(Debug, Default)]
+pub struct LiveGames {
+    games: Mutex<BTreeMap<usize, LiveGame>>,
+}
+
+impl LiveGames {
+    /// Record that a game of the opening at `opening_idx` has started.
+    pub fn start(&self, opening_idx: usize, game: LiveGame) {
+        self.games.lock().unwrap().insert(opening_idx, game);
+    }
+
+    /// Record that the game of the opening at `opening_idx` has ended.
+    pub fn finish(&self, opening_idx: usize) {
+        self.games.lock().unwrap().remove(&opening_idx);
+    }
+
+    /// The games in progress, in opening order.
+    pub fn snapshot(&self) -> Vec<LiveGame> {
+        self.games.lock().unwrap().values().cloned().collect()
+    }
+}
+
+/// Elo estimate and LLR after a number of games.
+#[derive(Debug, Clone, Copy)]
+struct HistoryPoint {
+    games: u32,
+    elo: f64,
+    llr: Option<f64>,
+}
+
+/// What the dashboard shows apart from the live statistics.
+struct DashboardState {
+    engine_names: (String, String),
+    total_games: usize,
+    history: Vec<HistoryPoint>,
+    started: Instant,
+    /// Whether the user asked to stop the match
+    stopping: bool,
+}
+
+/// Full-screen dashboard drawn on the alternate screen until dropped.
+pub struct Dashboard {
+    terminal: DefaultTerminal,
+    state: DashboardState,
+}
+
+impl Dashboard {
+    /// Switch the terminal to the dashboard.
+    pub fn start(engine_names: &(String, String), total_games: usize) -> Self {
+        Self {
+            terminal: ratatui::init(),
+            state: DashboardState {
+                engine_names: engine_names.clone(),
+                total_games,
+                history: Vec::new(),
+                started: Instant::now(),
+                stopping: false,
+            },
+        }
+    }
+
+    /// Add the Elo estimate and LLR of `statistics` to the graphs.
+    pub fn record(&mut self, statistics: &MatchStatistics) {
+        self.state.record(statistics);
+    }
+
+    /// Redraw the dashboard.
+    pub fn draw(&mut self, statistics: &MatchStatistics, live_games: &LiveGames) -> io::Result<()> {
+        let live_games = live_games.snapshot();
+        self.terminal
+            .draw(|frame| render(frame, &self.state, statistics, &live_games))?;
+        Ok(())
+    }
+
+    /// Show that the match stops after the games in progress.
+    pub fn mark_stopping(&mut self) {
+        self.state.stopping = true;
+    }
+
+    /// Wait up to the refresh interval for a key press. Returns true if the
+    /// user asked to stop the match; asking again exits the process.
+    pub fn poll_stop(&mut self) -> io::Result<bool> {
+        if !event::poll(REFRESH_INTERVAL)? {
+            return Ok(false);
+        }
+        let Event::Key(key) = event::read()? else {
+            return Ok(false);
+        };
+        let stop = key.kind == KeyEventKind::Press
+            && (key.code == KeyCode::Char('q')
+                || (key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(KeyModifiers::CONTROL)));
+        if stop && self.state.stopping {
+            ratatui::restore();
+            std::process::exit(130);
+        }
+        self.state.stopping |= stop;
+        Ok(stop)
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+impl DashboardState {
+    fn record(&mut self, statistics: &MatchStatistics) {
+        let freq = statistics.calculate_pentanomial_frequencies();
+        self.history.push(HistoryPoint {
+            games: statistics.total_games(),
+            elo: PentanomialCalculator::calculate(&freq).elo_diff,
+            llr: statistics.sprt_llr(),
+        });
+    }
+}
+
+fn render(
+    frame: &mut Frame,
+    state: &DashboardState,
+    statistics: &MatchStatistics,
+    live_games: &[LiveGame],
+) {
+    let lists_height = live_games.len().max(statistics.recent_results.len()).max(3) as u16 + 2;
+    let [
+        progress_area,
+        summary_area,
+        graph_area,
+        lists_area,
+        help_area,
+    ] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Min(8),
+        Constraint::Length(lists_height),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    render_progress(frame, progress_area, state, statistics);
+
+    let [results_area, time_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(summary_area);
+    render_results(frame, results_area, statistics);
+    render_time_usage(frame, time_area, state, statistics);
+
+    if statistics.sprt.is_some() {
+        let [elo_area, llr_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(graph_area);
+        render_elo_graph(frame, elo_area, state);
+        render_llr_graph(frame, llr_area, state, statistics);
+    } else {
+        render_elo_graph(frame, graph_area, state);
+    }
+
+    let [live_area, recent_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(lists_area);
+    render_live_games(frame, live_area, live_games);
+    render_recent_games(frame, recent_area, statistics);
+
+    let help = if state.stopping {
+        vec![
+            Span::styled(" Stopping", Style::default().fg(WARNING)),
+            Span::styled(
+                " after the games in progress, press q again to quit now",
+                Style::default().fg(SUBTEXT),
+            ),
+        ]
+    } else {
+        vec![
+            Span::styled(" q", Style::default().fg(PRIMARY)),
+            Span::styled(
+                " stop the match after the games in progress",
+                Style::default().fg(SUBTEXT),
+            ),
+        ]
+    };
+    frame.render_widget(Paragraph::new(Line::from(help)), help_area);
+}
+
+fn panel(title: &str) -> Block<'_> {
+    Block::bordered()
+        .border_style(Style::default().fg(SUBTEXT))
+        .title(Span::styled(
+            format!(" {title} "),
+            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+        ))
+}
+
+fn render_progress(
+    frame: &mut Frame,
+    area: Rect,
+    state: &DashboardState,
+    statistics: &MatchStatistics,
+) {
+    let played = statistics.total_games() as usize;
+    let total = state.total_games.max(1);
+    let elapsed = state.started.elapsed();
+    let played_now = state.history.len();
+    let eta = (played_now > 0 && played < total)
+        .then(|| elapsed.mul_f64((total - played) as f64 / played_now as f64));
+
+    let mut label = format!("{played}/{total} games  {}", format_duration(elapsed));
+    if let Some(eta) = eta {
+        label.push_str(&format!("  ETA {}", format_duration(eta)));
+    }
+    let title = format!("{} vs {}", state.engine_names.0, state.engine_names.1);
+    frame.render_widget(
+        Gauge::default()
+            .block(panel(&title))
+            .gauge_style(Style::default().fg(INFO).bg(Color::Rgb(50, 55, 80)))
+            .ratio((played as f64 / total as f64).min(1.0))
+            .label(Span::styled(label, Style::default().fg(TEXT))),
+        area,
+    );
+}
+
+fn render_results(frame: &mut Frame, area: Rect, statistics: &MatchStatistics) {
+    let total = statistics.total_games();
+    let percentage = |count: u32| {
+        if total == 0 {
+            0.0
+        } else {
+            f64::from(count) / f64::from(total) * 100.0
+        }
+    };
+    let bar = |label: &str, count: u32, color: Color| {
+        let share = percentage(count);
+        let filled = (share / 100.0 * RESULT_BAR_WIDTH as f64).round() as usize;
+        Line::from(vec![
+            Span::styled(format!("{label:>7} "), Style::default().fg(TEXT)),
+            Span::styled("█".repeat(filled), Style::default().fg(color)),
+            Span::styled(
+                " ".repeat(RESULT_BAR_WIDTH - filled),
+                Style::default().bg(Color::Rgb(50, 55, 80)),
+            ),
+            Span::styled(
+                format!(" {share:>5.1}% ({count})"),
+                Style::default().fg(TEXT),
+            ),
+        ])
+    };
+
+    let stats = PentanomialCalculator::calculate(&statistics.calculate_pentanomial_frequencies());
+    let mut lines = vec![
+        bar("Engine1", statistics.engine1_wins, SUCCESS),
+        bar("Draws", statistics.draws, INFO),
+        bar("Engine2", statistics.engine2_wins, FAILURE),
+        Line::from(vec![
+            Span::styled("    Elo ", Style::default().fg(TEXT)),
+            Span::styled(
+                format!("{:+.1} ± {:.1}", stats.elo_diff, stats.confidence_interval),
+                Style::default().fg(PRIMARY),
+            ),
+            Span::styled(
+                format!("  LOS {:.1}%", stats.los * 100.0),
+                Style::default().fg(SUBTEXT),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  Discs ", Style::default().fg(TEXT)),
+            Span::styled(
+                format!("{:+}", statistics.total_score),
+                Style::default().fg(PRIMARY),
+            ),
+        ]),
+    ];
+    if let (Some(sprt), Some(llr)) = (statistics.sprt, statistics.sprt_llr()) {
+        let (lower, upper) = sprt.bounds();
+        lines.push(Line::from(vec![
+            Span::styled("    LLR ", Style::default().fg(TEXT)),
+            Span::styled(format!("{llr:+.2}"), Style::default().fg(PRIMARY)),
+            Span::styled(
+                format!(
+                    " ({lower:+.2}, {upper:+.2}) [{:.1}, {:.1}]",
+                    sprt.elo0, sprt.elo1
+                ),
+                Style::default().fg(SUBTEXT),
+            ),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines).block(panel("Results")), area);
+}
+
+fn render_time_usage(
+    frame: &mut Frame,
+    area: Rect,
+    state: &DashboardState,
+    statistics: &MatchStatistics,
+) {
+    let engine_lines = |name: &str, time: &ThinkTime, time_losses: u32| {
+        let mut details = vec![Span::styled(
+            format!(
+                "  {:.2}s/move, max {:.2}s, {} in {} moves",
+                time.average_ms() / 1000.0,
+                time.max_ms as f64 / 1000.0,
+                format_duration(Duration::from_millis(time.total_ms)),
+                time.moves
+            ),
+            Style::default().fg(TEXT),
+        )];
+        if time_losses > 0 {
+            details.push(Span::styled(
+                format!(", {time_losses} lost on time"),
+                Style::default().fg(WARNING),
+            ));
+        }
+        if time.cap_violations > 0 {
+            details.push(Span::styled(
+                format!(", {} over the cap", time.cap_violations),
+                Style::default().fg(WARNING),
+            ));
+        }
+        [
+            Line::from(Span::styled(
+                name.to_string(),
+                Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(details),
+        ]
+    };
+
+    let mut lines = Vec::new();
+    lines.extend(engine_lines(
+        &state.engine_names.0,
+        &statistics.engine1_think_time,
+        statistics.engine1_time_losses,
+    ));
+    lines.extend(engine_lines(
+        &state.engine_names.1,
+        &statistics.engine2_think_time,
+        statistics.engine2_time_losses,
+    ));
+    frame.render_widget(Paragraph::new(lines).block(panel("Time Usage")), area);
+}
+
+/// Axis bounds covering `values` and `extra`, with some headroom.
+fn value_bounds(values: impl Iterator<Item = f64>, extra: &[f64]) -> [f64; 2] {
+    let (min, max) = values
+        .chain(extra.iter().copied())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if min > max {
+        return [-1.0, 1.0];
+    }
+    let margin = ((max - min) * 0.1).max(1.0);
+    [min - margin, max + margin]
+}
+
+fn axis_labels(bounds: [f64; 2], precision: usize) -> Vec<Line<'static>> {
+    let middle = (bounds[0] + bounds[1]) / 2.0;
+    [bounds[0], middle, bounds[1]]
+        .iter()
+        .map(|value| Line::from(format!("{value:+.precision$}")))
+        .collect()
+}
+
+fn games_axis(state: &DashboardState) -> Axis<'static> {
+    let games = state.history.last().map_or(1, |point| point.games.max(1));
+    Axis::default()
+        .style(Style::default().fg(SUBTEXT))
+        .bounds([0.0, f64::from(games)])
+        .labels(["0".to_string(), games.to_string()])
+}
+
+fn render_elo_graph(frame: &mut Frame, area: Rect, state: &DashboardState) {
+    let points: Vec<(f64, f64)> = state
+        .history
+        .iter()
+        .map(|point| (f64::from(point.games), point.elo))
+        .collect();
+    let bounds = value_bounds(points.iter().map(|&(_, elo)| elo), &[0.0]);
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(PRIMARY))
+            .data(&points),
+    ])
+    .block(panel("Elo"))
+    .x_axis(games_axis(state))
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(SUBTEXT))
+            .bounds(bounds)
+            .labels(axis_labels(bounds, 0)),
+    );
+    frame.render_widget(chart, area);
+}
+
+fn render_llr_graph(
+    frame: &mut Frame,
+    area: Rect,
+    state: &DashboardState,
+    statistics: &MatchStatistics,
+) {
+    let Some(sprt) = statistics.sprt else {
+        return;
+    };
+    let (lower, upper) = sprt.bounds();
+    let points: Vec<(f64, f64)> = state
+        .history
+        .iter()
+        .filter_map(|point| point.llr.map(|llr| (f64::from(point.games), llr)))
+        .collect();
+    let games = f64::from(state.history.last().map_or(1, |point| point.games.max(1)));
+    let lower_line = [(0.0, lower), (games, lower)];
+    let upper_line = [(0.0, upper), (games, upper)];
+    let bounds = value_bounds(points.iter().map(|&(_, llr)| llr), &[lower, upper]);
+
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(FAILURE))
+            .data(&lower_line),
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(SUCCESS))
+            .data(&upper_line),
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(PRIMARY))
+            .data(&points),
+    ])
+    .block(panel("LLR"))
+    .x_axis(games_axis(state))
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(SUBTEXT))
+            .bounds(bounds)
+            .labels(axis_labels(bounds, 1)),
+    );
+    frame.render_widget(chart, area);
+}
+
+fn render_live_games(frame: &mut Frame, area: Rect, live_games: &[LiveGame]) {
+    let lines: Vec<Line> = live_games
+        .iter()
+        .map(|game| {
+            let (number, players) = match (game.engine1_color, &game.worker) {
+                (Some(color), _) => (game.number.to_string(), format_players(color)),
+                (None, Some(worker)) => (
+                    format!("{}-{}", game.number, game.number + 1),
+                    format!("worker {worker}"),
+                ),
+                (None, None) => (game.number.to_string(), String::new()),
+            };
+            Line::from(vec![
+                Span::styled(format!("{number:>7} "), Style::default().fg(SUBTEXT)),
+                Span::styled(
+                    format!("{:<19} ", format_opening(&game.opening)),
+                    Style::default().fg(TEXT),
+                ),
+                Span::styled(players, Style::default().fg(SUBTEXT)),
+                Span::styled(
+                    format!("  {}", format_duration(game.started.elapsed())),
+                    Style::default().fg(INFO),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(panel("In Progress")), area);
+}
+
+fn render_recent_games(frame: &mut Frame, area: Rect, statistics: &MatchStatistics) {
+    let first_number = statistics
+        .games_played
+        .saturating_sub(statistics.recent_results.len() as u32)
+        + 1;
+    let lines: Vec<Line> = statistics
+        .recent_results
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(idx, game)| {
+            let (symbol, color) = match game.winner {
+                MatchWinner::Engine1 => ("W", SUCCESS),
+                MatchWinner::Engine2 => ("L", FAILURE),
+                MatchWinner::Draw => ("D", INFO),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>7} ", first_number + idx as u32),
+                    Style::default().fg(SUBTEXT),
+                ),
+                Span::styled(
+                    symbol,
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!(" {:+3} ", game.score), Style::default().fg(color)),
+                Span::styled(
+                    format!("{:<19} ", format_opening(&game.opening)),
+                    Style::default().fg(TEXT),
+                ),
+                Span::styled(
+                    format_players(game.engine1_color),
+                    Style::default().fg(SUBTEXT),
+                ),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(panel("Recent Games")), area);
+}
+
+fn format_players(engine1_color: Disc) -> String {
+    if engine1_color == Disc::White {
+        "○ Engine1 vs Engine2 ●".to_string()
+    } else {
+        "● Engine1 vs Engine2 ○".to_string()
+    }
+}
+
+fn format_opening(opening: &str) -> String {
+    if opening.len() > MAX_OPENING_LEN {
+        format!("{}...", &opening[..MAX_OPENING_LEN])
+    } else {
+        opening.to_string()
+    }
+}
+
+/// Format a duration as `m:ss`, or `h:mm:ss` from an hour on.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statistics::Sprt;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(5)), "0:05");
+        assert_eq!(format_duration(Duration::from_secs(754)), "12:34");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn test_render_dashboard() {
+        let mut statistics = MatchStatistics::with_sprt(Sprt {
+            elo0: 0.0,
+            elo1: 5.0,
+            alpha: 0.05,
+            beta: 0.05,
+        });
+        let mut state = DashboardState {
+            engine_names: ("Alpha".to_string(), "Beta".to_string()),
+            total_games: 8,
+            history: Vec::new(),
+            started: Instant::now(),
+            stopping: false,
+        };
+        for (winner, score) in [(MatchWinner::Engine1, 10), (MatchWinner::Draw, 0)] {
+            statistics.add_result(winner, score, "f5d6".to_string(), true);
+            state.record(&statistics);
+        }
+        statistics.add_paired_result((MatchWinner::Engine1, 10), (MatchWinner::Draw, 0));
+        state.record(&statistics);
+
+        let live_games = LiveGames::default();
+        live_games.start(
+            1,
+            LiveGame {
+                number: 3,
+                opening: "f5f6".to_string(),
+                engine1_color: Some(Disc::Black),
+                worker: None,
+                started: Instant::now(),
+            },
+        );
+        live_games.start(
+            2,
+            LiveGame {
+                number: 5,
+                opening: "f5f4".to_string(),
+                engine1_color: None,
+                worker: Some("10.0.0.2:4000".to_string()),
+                started: Instant::now(),
+            },
+        );
+        live_games.finish(1);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| render(frame, &state, &statistics, &live_games.snapshot()))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+
+        assert!(screen.contains("Alpha vs Beta"));
+        assert!(screen.contains("2/8 games"));
+        assert!(screen.contains("LLR"));
+        assert!(screen.contains("5-6"));
+        assert!(screen.contains("worker 10.0.0.2:4000"));
+        assert!(!screen.contains("f5f6"));
+        assert!(screen.contains("Recent Games"));
+    }
+}
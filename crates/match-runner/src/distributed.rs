@@ -0,0 +1,596 @@
+//! Distributed match execution over a worker protocol.
+//!
+//! A match started with `--serve` plays its games locally as usual and also
+//! accepts remote workers, started with `--connect`, which play opening pairs
+//! on their own engines. Coordinator and workers exchange one JSON message
+//! per line over TCP:
+//!
+//! 1. The worker sends `hello` with the protocol version and the labeled
+//!    names of its engines, which must match the coordinator's.
+//! 2. The coordinator answers with the `settings` of the games (time control,
+//!    adjudication, agreement level and restart interval).
+//! 3. The coordinator sends `assign` with an opening, and the worker answers
+//!    with a `game` message for each of the two games of the pair, or an
+//!    `error` if a game failed. This repeats until the coordinator sends
+//!    `stop`.
+//!
+//! An opening pair is only added to the match once both of its games have
+//! been received. The openings of workers that disconnect are given back to
+//! the queue and played by the next free worker.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, Scope};
+use std::time::{Duration, Instant};
+
+use reversi_core::formats::ggf;
+use serde::{Deserialize, Serialize};
+
+use crate::adjudication::Adjudication;
+use crate::config::Config;
+use crate::display::dashboard::{LiveGame, LiveGames};
+use crate::engine::Engine;
+use crate::error::{MatchRunnerError, Result};
+use crate::match_runner::{FinishedGame, GameReport, MatchRunner, OpeningQueue, OpeningSource};
+use crate::time_tracker::{MoveTimeCap, TimeTracker};
+
+/// Version of the worker protocol, checked when a worker connects.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Interval at which the coordinator checks for new workers.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Most moves in a game, bounding the time a game can spend on its moves.
+const MAX_GAME_MOVES: u32 = 60;
+
+/// Time allowed for a worker's game on top of its time budget, covering
+/// engine restarts and the transfer of the result.
+const WORKER_GRACE: Duration = Duration::from_secs(60);
+
+/// Settings of the games of a match, sent by the coordinator to its workers.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GameSettings {
+    /// Main time in seconds
+    pub main_time: u64,
+    /// Byoyomi time in seconds
+    pub byoyomi_time: u64,
+    /// Byoyomi stones
+    pub byoyomi_stones: u32,
+    /// Adjudication settings
+    pub adjudication: Adjudication,
+    /// Level of the move agreement analysis, if enabled
+    pub agreement_level: Option<u32>,
+    /// Games between scheduled engine restarts, if enabled
+    pub restart_every: Option<u32>,
+    /// Hard limit on the wall time of a single move, if enabled
+    #[serde(default)]
+    pub move_time_cap: Option<MoveTimeCap>,
+}
+
+impl GameSettings {
+    /// Take the game settings of `config`.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            main_time: config.main_time,
+            byoyomi_time: config.byoyomi_time,
+            byoyomi_stones: config.byoyomi_stones,
+            adjudication: config.adjudication(),
+            agreement_level: config.agreement_level,
+            restart_every: config.restart_every,
+            move_time_cap: config.move_time_cap(),
+        }
+    }
+
+    /// Create a time tracker for the time control of the games.
+    pub fn time_tracker(&self) -> TimeTracker {
+        TimeTracker::new(self.main_time, self.byoyomi_time, self.byoyomi_stones)
+            .with_move_cap(self.move_time_cap)
+    }
+
+    /// Longest a game can take: the main time of both players, byoyomi on
+    /// every move, and the engine response timeout on top of every move.
+    ///
+    /// Returns `None` if the games have neither a time control nor an
+    /// engine timeout, so they can take arbitrarily long.
+    pub fn game_time_limit(&self, engine_timeout: Option<Duration>) -> Option<Duration> {
+        if self.main_time == 0 && self.byoyomi_time == 0 && engine_timeout.is_none() {
+            return None;
+        }
+        let time_control = Duration::from_secs(2 * self.main_time)
+            + Duration::from_secs(self.byoyomi_time) * MAX_GAME_MOVES;
+        Some(time_control + engine_timeout.unwrap_or_default() * MAX_GAME_MOVES + WORKER_GRACE)
+    }
+}
+
+/// A message of the worker protocol.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message {
+    /// Worker to coordinator: introduction with the labeled engine names
+    Hello {
+        version: u32,
+        engines: (String, String),
+    },
+    /// Coordinator to worker: settings of the games
+    Settings(GameSettings),
+    /// Coordinator to worker: opening to play as a pair of games
+    Assign { opening_idx: usize, opening: String },
+    /// Worker to coordinator: a finished game, with its record in GGF
+    Game { report: GameReport, record: String },
+    /// Either way: a fatal error ending the match or the connection
+    Error { message: String },
+    /// Coordinator to worker: no openings are left
+    Stop,
+}
+
+fn send(mut stream: &TcpStream, message: &Message) -> Result<()> {
+    let line = serde_json::to_string(message)
+        .map_err(|e| MatchRunnerError::Game(format!("Cannot encode message: {e}")))?;
+    writeln!(stream, "{line}")?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Read the next message, or `None` if the connection was closed.
+fn receive(reader: &mut impl BufRead) -> Result<Option<Message>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| MatchRunnerError::Game(format!("Invalid worker protocol message: {e}")))
+}
+
+/// Listen for workers on `addr`.
+pub(crate) fn listen(addr: &str) -> Result<TcpListener> {
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| MatchRunnerError::Config(format!("Cannot listen on {addr}: {e}")))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Accept workers until the opening queue is finished, serving each one on
+/// its own thread.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn accept_workers<'scope, 'env>(
+    scope: &'scope Scope<'scope, 'env>,
+    listener: TcpListener,
+    queue: &'scope OpeningQueue<'env>,
+    live_games: &'scope LiveGames,
+    engine_names: &'scope (String, String),
+    settings: &'scope GameSettings,
+    read_timeout: Option<Duration>,
+    sender: Sender<Result<FinishedGame>>,
+) {
+    while !queue.is_finished() {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    // A worker that disconnects, stalls or breaks the protocol
+                    // is dropped; its opening has already been given back.
+                    let _ = serve_worker(
+                        stream,
+                        queue,
+                        live_games,
+                        engine_names,
+                        settings,
+                        read_timeout,
+                        sender,
+                    );
+                });
+            }
+            // No pending connection, or one that failed before it was accepted
+            Err(_) => thread::sleep(ACCEPT_INTERVAL),
+        }
+    }
+}
+
+/// Hand out openings to one worker and forward its games to the match loop.
+///
+/// A worker that sends nothing for `read_timeout`, e.g. because its machine
+/// went down without closing the connection, is dropped and its opening
+/// given back.
+fn serve_worker(
+    stream: TcpStream,
+    queue: &OpeningQueue,
+    live_games: &LiveGames,
+    engine_names: &(String, String),
+    settings: &GameSettings,
+    read_timeout: Option<Duration>,
+    sender: Sender<Result<FinishedGame>>,
+) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(read_timeout)?;
+    let mut reader = BufReader::new(&stream);
+
+    let rejection = match receive(&mut reader)? {
+        Some(Message::Hello { version, .. }) if version != PROTOCOL_VERSION => Some(format!(
+            "Protocol version {version} is not supported by the coordinator (version {PROTOCOL_VERSION})"
+        )),
+        Some(Message::Hello { engines, .. }) if engines != *engine_names => Some(format!(
+            "The worker's engines are {} vs {}, but the match is {} vs {}",
+            engines.0, engines.1, engine_names.0, engine_names.1
+        )),
+        Some(Message::Hello { .. }) => None,
+        _ => return Ok(()),
+    };
+    if let Some(message) = rejection {
+        return send(&stream, &Message::Error { message });
+    }
+    send(&stream, &Message::Settings(*settings))?;
+    let peer = stream
+        .peer_addr()
+        .map_or_else(|_| "unknown".to_string(), |addr| addr.to_string());
+
+    while let Some((opening_idx, opening)) = queue.next_opening() {
+        live_games.start(
+            opening_idx,
+            LiveGame {
+                number: opening_idx * 2 + 1,
+                opening: opening.clone(),
+                engine1_color: None,
+                worker: Some(peer.clone()),
+                started: Instant::now(),
+            },
+        );
+        let games = send(
+            &stream,
+            &Message::Assign {
+                opening_idx,
+                opening,
+            },
+        )
+        .and_then(|()| receive_pair(&mut reader, opening_idx));
+        live_games.finish(opening_idx);
+        match games {
+            Ok(Ok(games)) => {
+                for game in games {
+                    if sender.send(Ok(game)).is_err() {
+                        return Ok(());
+                    }
+                }
+                queue.finish_opening(opening_idx);
+            }
+            Ok(Err(message)) => {
+                let _ = sender.send(Err(MatchRunnerError::Game(format!(
+                    "Worker {peer}: {message}"
+                ))));
+                return Ok(());
+            }
+            Err(e) => {
+                queue.give_back(opening_idx);
+                return Err(e);
+            }
+        }
+    }
+
+    send(&stream, &Message::Stop)
+}
+
+/// Read the two games of the opening pair at `opening_idx`, or the error
+/// that ended one of them.
+fn receive_pair(
+    reader: &mut impl BufRead,
+    opening_idx: usize,
+) -> Result<std::result::Result<Vec<FinishedGame>, String>> {
+    let mut games = Vec::with_capacity(2);
+    while games.len() < 2 {
+        match receive(reader)? {
+            Some(Message::Game { report, record }) if report.opening_idx == opening_idx => {
+                let record = ggf::parse(&record).map_err(MatchRunnerError::Game)?;
+                games.push(FinishedGame { report, record });
+            }
+            Some(Message::Error { message }) => return Ok(Err(message)),
+            Some(message) => {
+                return Err(MatchRunnerError::Game(format!(
+                    "Unexpected worker message: {message:?}"
+                )));
+            }
+            None => {
+                return Err(MatchRunnerError::Game(
+                    "The worker disconnected".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(Ok(games))
+}
+
+/// Games played by a remote worker.
+#[derive(Debug, Clone)]
+pub struct WorkerReport {
+    /// Address of the coordinator
+    pub coordinator: String,
+    /// Number of games played and sent to the coordinator
+    pub games: usize,
+}
+
+impl WorkerReport {
+    /// Print the number of games played to stdout.
+    pub fn print(&self) -> io::Result<()> {
+        println!(
+            "Played {} games for the coordinator at {}",
+            self.games, self.coordinator
+        );
+        Ok(())
+    }
+}
+
+/// Play games for the coordinator at `config.connect` until it has no
+/// openings left, with one connection and pair of engines per concurrent game.
+pub fn run_remote_worker(config: &Config) -> Result<WorkerReport> {
+    let coordinator = config
+        .connect
+        .clone()
+        .ok_or_else(|| MatchRunnerError::Config("No coordinator address given.".to_string()))?;
+    let runner = MatchRunner::new();
+
+    let mut engine_pairs = (0..config.concurrency)
+        .map(|_| runner.initialize_engines(config))
+        .collect::<Result<Vec<_>>>()?;
+    let (engine1_name, engine2_name) = runner.get_engine_names(&mut engine_pairs[0])?;
+    let engine_names = (
+        config.engine1_limits().label(engine1_name),
+        config.engine2_limits().label(engine2_name),
+    );
+
+    let games = thread::scope(|scope| {
+        let handles: Vec<_> = engine_pairs
+            .iter_mut()
+            .map(|engines| {
+                let (runner, coordinator, engine_names) = (&runner, &coordinator, &engine_names);
+                scope.spawn(move || {
+                    play_for_coordinator(runner, engines, coordinator, engine_names, config)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("worker thread panicked"))
+            .sum::<Result<usize>>()
+    })?;
+
+    Ok(WorkerReport { coordinator, games })
+}
+
+/// Openings assigned by the coordinator over one connection.
+struct RemoteOpenings<R> {
+    reader: Mutex<R>,
+}
+
+impl<R: BufRead> OpeningSource for RemoteOpenings<R> {
+    fn next_opening(&self) -> Option<(usize, String)> {
+        match receive(&mut *self.reader.lock().unwrap()) {
+            Ok(Some(Message::Assign {
+                opening_idx,
+                opening,
+            })) => Some((opening_idx, opening)),
+            _ => None,
+        }
+    }
+
+    fn finish_opening(&self, _opening_idx: usize) {}
+}
+
+/// Play the openings assigned over one connection to the coordinator,
+/// returning the number of games sent.
+fn play_for_coordinator(
+    runner: &MatchRunner,
+    engines: &mut (Box<dyn Engine>, Box<dyn Engine>),
+    coordinator: &str,
+    engine_names: &(String, String),
+    config: &Config,
+) -> Result<usize> {
+    let stream = TcpStream::connect(coordinator).map_err(|e| {
+        MatchRunnerError::Config(format!(
+            "Cannot connect to the coordinator at {coordinator}: {e}"
+        ))
+    })?;
+    let mut reader = BufReader::new(&stream);
+
+    let hello = Message::Hello {
+        version: PROTOCOL_VERSION,
+        engines: engine_names.clone(),
+    };
+    let settings = match send(&stream, &hello).and_then(|()| receive(&mut reader)) {
+        Ok(Some(Message::Settings(settings))) => settings,
+        Ok(Some(Message::Error { message })) => return Err(MatchRunnerError::Config(message)),
+        Ok(_) => {
+            return Err(MatchRunnerError::Config(format!(
+                "The coordinator at {coordinator} did not send the game settings"
+            )));
+        }
+        Err(e) => {
+            return Err(MatchRunnerError::Config(format!(
+                "Handshake with the coordinator at {coordinator} failed: {e}"
+            )));
+        }
+    };
+
+    let openings = RemoteOpenings {
+        reader: Mutex::new(reader),
+    };
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel::<Result<FinishedGame>>();
+        let stream = &stream;
+        let forwarder = scope.spawn(move || {
+            let mut games = 0;
+            for game in receiver {
+                let message = match game {
+                    Ok(FinishedGame { report, record }) => Message::Game {
+                        report,
+                        record: ggf::format(&record).map_err(MatchRunnerError::Game)?,
+                    },
+                    Err(e) => {
+                        let _ = send(
+                            stream,
+                            &Message::Error {
+                                message: e.to_string(),
+                            },
+                        );
+                        return Err(e);
+                    }
+                };
+                // The coordinator closes the connection once the match is over
+                if send(stream, &message).is_err() {
+                    break;
+                }
+                games += 1;
+            }
+            Ok(games)
+        });
+
+        runner.run_worker(
+            engines,
+            &openings,
+            settings.time_tracker(),
+            settings.adjudication,
+            settings.agreement_level,
+            settings.restart_every,
+            &config.evidence_dir,
+            config.log_dir.as_deref(),
+            &LiveGames::default(),
+            sender,
+        );
+        forwarder.join().expect("forwarder thread panicked")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agreement::AgreementStats;
+    use crate::match_runner::Termination;
+    use crate::statistics::MatchWinner;
+    use crate::time_tracker::ThinkTime;
+
+    #[test]
+    fn test_message_round_trip() {
+        let mut agreement = AgreementStats::default();
+        agreement.add_move(true, true, Some((2.0, 1.5)));
+        let message = Message::Game {
+            report: GameReport {
+                opening_idx: 3,
+                engine1_black: false,
+                winner: MatchWinner::Engine2,
+                score: -12,
+                termination: Termination::Adjudication,
+                engine1_time: ThinkTime {
+                    total_ms: 1500,
+                    max_ms: 400,
+                    moves: 30,
+                    cap_violations: 1,
+                    ..ThinkTime::default()
+                },
+                engine2_time: ThinkTime::default(),
+                phase: None,
+                games_since_restart: Some(2),
+                incident: None,
+                agreement,
+            },
+            record: "(;GM[Othello];)".to_string(),
+        };
+
+        let line = serde_json::to_string(&message).unwrap();
+        assert!(line.starts_with(r#"{"type":"game""#));
+        let Some(Message::Game { report, record }) =
+            receive(&mut format!("{line}\n").as_bytes()).unwrap()
+        else {
+            panic!("expected a game message");
+        };
+        assert_eq!(report.opening_idx, 3);
+        assert_eq!(report.score, -12);
+        assert_eq!(report.termination, Termination::Adjudication);
+        assert_eq!(report.engine1_time.max_ms, 400);
+        assert_eq!(report.engine1_time.cap_violations, 1);
+        assert_eq!(report.games_since_restart, Some(2));
+        assert_eq!(report.agreement, agreement);
+        assert_eq!(record, "(;GM[Othello];)");
+
+        assert!(matches!(
+            receive(&mut r#"{"type":"stop"}"#.as_bytes()).unwrap(),
+            Some(Message::Stop)
+        ));
+        assert!(receive(&mut "".as_bytes()).unwrap().is_none());
+        assert!(receive(&mut "{\"type\":\"unknown\"}\n".as_bytes()).is_err());
+    }
+
+    fn settings(main_time: u64, byoyomi_time: u64) -> GameSettings {
+        GameSettings {
+            main_time,
+            byoyomi_time,
+            byoyomi_stones: 0,
+            adjudication: Adjudication::default(),
+            agreement_level: None,
+            restart_every: None,
+            move_time_cap: None,
+        }
+    }
+
+    #[test]
+    fn test_game_time_limit() {
+        assert_eq!(settings(0, 0).game_time_limit(None), None);
+        assert_eq!(
+            settings(300, 0).game_time_limit(None),
+            Some(Duration::from_secs(600) + WORKER_GRACE)
+        );
+        assert_eq!(
+            settings(60, 2).game_time_limit(Some(Duration::from_secs(5))),
+            Some(Duration::from_secs(120 + 60 * 2 + 60 * 5) + WORKER_GRACE)
+        );
+        assert_eq!(
+            settings(0, 0).game_time_limit(Some(Duration::from_secs(1))),
+            Some(Duration::from_secs(60) + WORKER_GRACE)
+        );
+    }
+
+    #[test]
+    fn test_stalled_worker_gives_back_opening() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let engine_names = ("engine1".to_string(), "engine2".to_string());
+
+        // A worker that takes an opening and then never answers
+        let hello = Message::Hello {
+            version: PROTOCOL_VERSION,
+            engines: engine_names.clone(),
+        };
+        let worker = thread::spawn(move || {
+            let stream = TcpStream::connect(addr).unwrap();
+            send(&stream, &hello).unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut messages = Vec::new();
+            while let Ok(Some(message)) = receive(&mut reader) {
+                messages.push(message);
+            }
+            messages
+        });
+
+        let openings = ["f5".to_string(), "f5d6".to_string()];
+        let queue = OpeningQueue::new(&openings, 0..2);
+        let (sender, receiver) = mpsc::channel();
+        let (stream, _) = listener.accept().unwrap();
+        let result = serve_worker(
+            stream,
+            &queue,
+            &LiveGames::default(),
+            &engine_names,
+            &settings(0, 0),
+            Some(Duration::from_millis(200)),
+            sender,
+        );
+
+        assert!(result.is_err());
+        assert!(receiver.try_recv().is_err());
+        let messages = worker.join().unwrap();
+        assert!(matches!(
+            messages.as_slice(),
+            [Message::Settings(_), Message::Assign { opening_idx: 0, .. }]
+        ));
+        assert_eq!(queue.next_opening(), Some((0, "f5".to_string())));
+    }
+}
@@ -0,0 +1,447 @@
+//! Edax protocol adapter.
+//!
+//! Drives engines that speak the text protocol Edax uses with its `-nboard`
+//! option, so that stock Edax builds can play matches without a GTP wrapper.
+//! The protocol is line based: the engine is handshaked with `nboard 2`, the
+//! position is sent as a GGF game with `set game`, `go` is answered with
+//! `=== <move>/<eval>/<time>`, and `ping <n>` is answered with `pong <n>`
+//! once all earlier commands have been processed. Other output, such as
+//! `status` lines, is ignored.
+//!
+//! The adapter keeps the moves of the current game itself and sends the whole
+//! game before every `go`, so the engine's board never depends on whether it
+//! applied its own moves.
+
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use reversi_core::formats::{GameRecord, ggf};
+use reversi_core::square::Square;
+
+use crate::engine::{
+    Analysis, Engine, PING_TIMEOUT, ResponseReader, TranscriptEntry, engine_working_dir,
+    spawn_process,
+};
+use crate::error::{MatchRunnerError, Result};
+
+// Edax protocol commands and responses
+const EDAX_CMD_HANDSHAKE: &str = "nboard 2";
+const EDAX_CMD_PING: &str = "ping";
+const EDAX_CMD_SET_GAME: &str = "set game";
+const EDAX_CMD_GO: &str = "go";
+const EDAX_CMD_SET_DEPTH: &str = "set depth";
+const EDAX_RESPONSE_PONG: &str = "pong";
+const EDAX_RESPONSE_NAME: &str = "set myname ";
+const EDAX_RESPONSE_MOVE: &str = "=== ";
+
+// GTP extensions translated to Edax commands
+const GTP_CMD_NR_DEPTH: &str = "nr-depth";
+const GTP_CMD_NR_NODES: &str = "nr-nodes";
+
+const ERR_PROCESS_CLOSED: &str = "Process closed stdout";
+
+/// An engine process speaking the Edax protocol.
+pub struct EdaxEngine {
+    process: Child,
+    stdin: ChildStdin,
+    reader: ResponseReader,
+    executable: String,
+    args: Vec<String>,
+    working_dir: PathBuf,
+    /// Initialization commands as given, replayed after a restart
+    init_commands: Vec<String>,
+    /// Response timeout, or `None` to wait indefinitely
+    timeout: Option<Duration>,
+    /// Whether the process crashed or stopped responding
+    crashed: bool,
+    /// Commands and responses since the last `clear_board`
+    transcript: Vec<TranscriptEntry>,
+    /// Engine name, as announced with `set myname`
+    name: String,
+    /// Moves of the current game, excluding passes
+    moves: Vec<Square>,
+    /// Evaluation reported with the last generated move
+    last_score: Option<f32>,
+    /// Number of the last ping sent
+    ping: u32,
+}
+
+impl EdaxEngine {
+    /// Start an Edax protocol engine and perform the handshake.
+    ///
+    /// The engine name is taken from the `set myname` line the engine sends
+    /// in answer to the handshake, or from the executable file name if it
+    /// sends none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the engine process cannot be started or does not
+    /// answer the handshake.
+    pub fn new(executable: &str, args: &[String], working_dir: Option<PathBuf>) -> Result<Self> {
+        let working_dir = engine_working_dir(executable, working_dir);
+        let (process, stdin, reader) = spawn_process(executable, args, &working_dir)?;
+
+        let mut engine = EdaxEngine {
+            process,
+            stdin,
+            reader,
+            executable: executable.to_string(),
+            args: args.to_vec(),
+            working_dir,
+            init_commands: Vec::new(),
+            timeout: None,
+            crashed: false,
+            transcript: Vec::new(),
+            name: String::new(),
+            moves: Vec::new(),
+            last_score: None,
+            ping: 0,
+        };
+        engine.handshake()?;
+        Ok(engine)
+    }
+
+    fn handshake(&mut self) -> Result<()> {
+        self.ping += 1;
+        let pong = format!("{EDAX_RESPONSE_PONG} {}", self.ping);
+        let mut name = None;
+        self.exchange(
+            &[
+                EDAX_CMD_HANDSHAKE.to_string(),
+                format!("{EDAX_CMD_PING} {}", self.ping),
+            ],
+            self.timeout,
+            |line| {
+                if let Some(announced) = line.strip_prefix(EDAX_RESPONSE_NAME) {
+                    name = Some(announced.trim().to_string());
+                }
+                (line == pong).then_some(())
+            },
+        )?;
+
+        self.name = name.unwrap_or_else(|| {
+            Path::new(&self.executable)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| self.executable.clone())
+        });
+        Ok(())
+    }
+
+    fn send_init_commands(&mut self) -> Result<()> {
+        let mut commands = Vec::with_capacity(self.init_commands.len());
+        for command in &self.init_commands {
+            commands.push(translate_init_command(command).map_err(|e| {
+                MatchRunnerError::Engine(format!(
+                    "{}: initialization command '{command}' failed: {e}",
+                    self.name
+                ))
+            })?);
+        }
+        self.sync(commands, self.timeout)
+    }
+
+    // =============================================================================
+    // Core Communication
+    // =============================================================================
+
+    fn record(&mut self, line: String) {
+        self.transcript.push(TranscriptEntry {
+            time: Instant::now(),
+            line,
+        });
+    }
+
+    /// Send `commands` followed by a ping, and wait until the engine has
+    /// processed them.
+    fn sync(&mut self, mut commands: Vec<String>, timeout: Option<Duration>) -> Result<()> {
+        self.ping += 1;
+        let pong = format!("{EDAX_RESPONSE_PONG} {}", self.ping);
+        commands.push(format!("{EDAX_CMD_PING} {}", self.ping));
+        self.exchange(&commands, timeout, |line| (line == pong).then_some(()))
+    }
+
+    /// Send `commands` and read response lines, waiting at most `timeout`
+    /// for each, until `done` accepts one.
+    ///
+    /// Any communication failure marks the engine as crashed.
+    fn exchange<T>(
+        &mut self,
+        commands: &[String],
+        timeout: Option<Duration>,
+        done: impl FnMut(&str) -> Option<T>,
+    ) -> Result<T> {
+        self.reader.timeout = timeout;
+        let mut result = Ok(());
+        for command in commands {
+            self.record(format!("> {command}"));
+            result = writeln!(self.stdin, "{command}").and_then(|()| self.stdin.flush());
+            if result.is_err() {
+                break;
+            }
+        }
+
+        let mut lines = Vec::new();
+        let result = result
+            .map_err(MatchRunnerError::from)
+            .and_then(|()| read_until(&mut self.reader, &mut lines, done));
+        for line in lines {
+            self.record(format!("< {line}"));
+        }
+        if let Err(e) = &result {
+            self.record(format!("< ({e})"));
+        }
+
+        result.map_err(|e| {
+            self.crashed = true;
+            match e {
+                MatchRunnerError::Io(err) if err.kind() == io::ErrorKind::TimedOut => {
+                    MatchRunnerError::Engine(format!(
+                        "{} did not respond to '{}' within {:.1}s",
+                        self.name,
+                        commands.last().map(String::as_str).unwrap_or_default(),
+                        timeout.unwrap_or_default().as_secs_f64()
+                    ))
+                }
+                e => e,
+            }
+        })
+    }
+}
+
+/// Read non-empty lines, collecting them into `lines`, until `done` accepts
+/// one.
+fn read_until<T>(
+    reader: &mut dyn BufRead,
+    lines: &mut Vec<String>,
+    mut done: impl FnMut(&str) -> Option<T>,
+) -> Result<T> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(MatchRunnerError::Engine(ERR_PROCESS_CLOSED.to_string()));
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        lines.push(line.to_string());
+        if let Some(value) = done(line) {
+            return Ok(value);
+        }
+    }
+}
+
+/// Translate an initialization command to the Edax protocol.
+///
+/// The search limit extensions of Neural Reversi's GTP engine are mapped to
+/// their Edax equivalents; other commands are sent unchanged.
+fn translate_init_command(command: &str) -> std::result::Result<String, String> {
+    let mut parts = command.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some(GTP_CMD_NR_DEPTH), Some(depth)) => Ok(format!("{EDAX_CMD_SET_DEPTH} {depth}")),
+        (Some(GTP_CMD_NR_NODES), _) => {
+            Err("node limits are not supported by the Edax protocol".to_string())
+        }
+        _ => Ok(command.to_string()),
+    }
+}
+
+/// Parse the move and evaluation of a `=== <move>/<eval>/<time>` reply,
+/// given without its prefix. Returns `None` as the move for a pass.
+fn parse_move_reply(reply: &str) -> Result<(Option<Square>, Option<f32>)> {
+    let mut fields = reply.split('/');
+    let token = fields.next().unwrap_or_default().trim();
+    let mv = if ["pa", "ps", "pass"]
+        .iter()
+        .any(|pass| token.eq_ignore_ascii_case(pass))
+    {
+        None
+    } else {
+        let sq = Square::from_str(token)
+            .map_err(|_| MatchRunnerError::Engine(format!("Invalid move reply: {reply}")))?;
+        Some(sq)
+    };
+    let eval = fields.next().and_then(|eval| eval.trim().parse().ok());
+    Ok((mv, eval))
+}
+
+impl Engine for EdaxEngine {
+    /// Commands are sent as they are, except that the `nr-depth <depth>`
+    /// extension is sent as `set depth <depth>`. The protocol has no error
+    /// responses, so only `nr-nodes`, which has no equivalent, is rejected.
+    fn initialize(&mut self, commands: &[String]) -> Result<()> {
+        self.init_commands = commands.to_vec();
+        self.send_init_commands()
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    fn has_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    fn transcript(&self) -> &[TranscriptEntry] {
+        &self.transcript
+    }
+
+    fn is_responsive(&mut self) -> bool {
+        if self.crashed || !matches!(self.process.try_wait(), Ok(None)) {
+            return false;
+        }
+        self.sync(Vec::new(), Some(self.timeout.unwrap_or(PING_TIMEOUT)))
+            .is_ok()
+    }
+
+    fn restart(&mut self) -> Result<()> {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+
+        let (process, stdin, reader) =
+            spawn_process(&self.executable, &self.args, &self.working_dir)?;
+        self.process = process;
+        self.stdin = stdin;
+        self.reader = reader;
+        self.crashed = false;
+        self.handshake()?;
+        self.send_init_commands()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn clear_board(&mut self) -> Result<()> {
+        self.transcript.clear();
+        self.moves.clear();
+        self.last_score = None;
+        self.sync(Vec::new(), self.timeout)
+    }
+
+    fn play(&mut self, _color: &str, mv: &str) -> Result<()> {
+        if !mv.eq_ignore_ascii_case("pass") {
+            let sq = Square::from_str(mv)
+                .map_err(|_| MatchRunnerError::Game(format!("Invalid move: {mv}")))?;
+            self.moves.push(sq);
+        }
+        Ok(())
+    }
+
+    fn genmove(&mut self, _color: &str, think_time: Duration) -> Result<String> {
+        let game = ggf::format(&GameRecord::from_moves(self.moves.clone()))
+            .map_err(MatchRunnerError::Game)?;
+        let timeout = self.timeout.map(|timeout| timeout + think_time);
+        let reply = self.exchange(
+            &[
+                format!("{EDAX_CMD_SET_GAME} {game}"),
+                EDAX_CMD_GO.to_string(),
+            ],
+            timeout,
+            |line| line.strip_prefix(EDAX_RESPONSE_MOVE).map(str::to_string),
+        )?;
+
+        let (mv, eval) = parse_move_reply(&reply)?;
+        self.last_score = eval;
+        match mv {
+            Some(sq) => {
+                self.moves.push(sq);
+                Ok(sq.to_string())
+            }
+            None => Ok("pass".to_string()),
+        }
+    }
+
+    /// Returns the evaluation the engine sent with its last move.
+    fn score(&mut self) -> Result<Option<f32>> {
+        Ok(self.last_score)
+    }
+
+    /// Analysis is not supported over the Edax protocol.
+    fn analyze(&mut self, _level: u32) -> Result<Option<Analysis>> {
+        Ok(None)
+    }
+
+    /// The Edax protocol has no time control; the settings are ignored.
+    fn time_settings(
+        &mut self,
+        _main_time: u64,
+        _byoyomi_time: u64,
+        _byoyomi_stones: u32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn time_left(&mut self, _color: &str, _time: u64, _stones: u32) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for EdaxEngine {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_move_reply() {
+        let (mv, eval) = parse_move_reply("F5/-2.00/0.5").unwrap();
+        assert_eq!(mv, Some(Square::F5));
+        assert_eq!(eval, Some(-2.0));
+
+        let (mv, eval) = parse_move_reply("c4").unwrap();
+        assert_eq!(mv, Some(Square::C4));
+        assert_eq!(eval, None);
+
+        assert_eq!(parse_move_reply("PA").unwrap(), (None, None));
+        assert_eq!(parse_move_reply("ps/+3").unwrap(), (None, Some(3.0)));
+        assert!(parse_move_reply("z9/0").is_err());
+    }
+
+    #[test]
+    fn test_translate_init_command() {
+        assert_eq!(
+            translate_init_command("nr-depth 12").unwrap(),
+            "set depth 12"
+        );
+        assert_eq!(
+            translate_init_command("set contempt 0").unwrap(),
+            "set contempt 0"
+        );
+        assert!(translate_init_command("nr-nodes 100000").is_err());
+    }
+
+    #[test]
+    fn test_read_until_skips_other_output() {
+        let mut reader = Cursor::new("set myname Edax4.4\n\nstatus thinking\npong 3\nafter\n");
+        let mut lines = Vec::new();
+        let value = read_until(&mut reader, &mut lines, |line| {
+            (line == "pong 3").then_some(3)
+        })
+        .unwrap();
+        assert_eq!(value, 3);
+        assert_eq!(lines, ["set myname Edax4.4", "status thinking", "pong 3"]);
+    }
+
+    #[test]
+    fn test_read_until_process_closed() {
+        let mut reader = Cursor::new("status thinking\n");
+        let result = read_until(&mut reader, &mut Vec::new(), |line| {
+            line.strip_prefix("=== ").map(str::to_string)
+        });
+        assert!(result.is_err());
+    }
+}
@@ -1,15 +1,27 @@
-//! GTP (Go Text Protocol) engine communication.
+//! Engine communication.
 //!
-//! This module provides functionality for communicating with external Reversi engines
-//! that implement the GTP protocol. It handles process management, command sending,
-//! and response parsing.
+//! This module defines the [`Engine`] interface through which matches drive
+//! external Reversi engines, and implements it for engines that speak the
+//! GTP protocol. It handles process management, command sending, and response
+//! parsing. Engines speaking the Edax protocol are adapted to the same
+//! interface in [`crate::edax`].
+//!
+//! Engine output is read on a separate thread so that responses can time out,
+//! and engines that crash or stop responding can be restarted.
 
 use std::{
-    io::{BufRead, BufReader, Write},
+    io::{self, BufRead, Read, Write},
     path::{Path, PathBuf},
     process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
 };
 
+use clap::ValueEnum;
+use reversi_core::square::Square;
+
+use crate::edax::EdaxEngine;
 use crate::error::{MatchRunnerError, Result};
 
 // GTP protocol constants
@@ -22,11 +34,264 @@ const GTP_CMD_VERSION: &str = "version";
 const GTP_CMD_CLEAR_BOARD: &str = "clear_board";
 const GTP_CMD_PLAY: &str = "play";
 const GTP_CMD_GENMOVE: &str = "genmove";
+const GTP_CMD_NR_SCORE: &str = "nr-score";
+const GTP_CMD_NR_EVAL: &str = "nr-eval";
 // Error messages
 const ERR_STDIN_FAILED: &str = "Failed to open stdin";
 const ERR_STDOUT_FAILED: &str = "Failed to open stdout";
 const ERR_PROCESS_CLOSED: &str = "Process closed stdout";
 
+/// Time allowed for a liveness ping when no response timeout is configured
+pub(crate) const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A line of GTP traffic with the time it was sent or received.
+#[derive(Debug, Clone)]
+pub struct TranscriptEntry {
+    pub time: Instant,
+    /// Command prefixed with `>`, or response line prefixed with `<`
+    pub line: String,
+}
+
+/// Protocol spoken by an engine process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum EngineProtocol {
+    /// Go Text Protocol
+    #[default]
+    Gtp,
+    /// Edax text protocol, as spoken by `edax -nboard`
+    Edax,
+}
+
+/// An engine's analysis of a position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+    /// Move the engine considers best
+    pub best_move: Square,
+    /// Score of the best move, in discs from the side to move's perspective
+    pub score: f32,
+}
+
+/// A Reversi engine playing in a match.
+///
+/// Moves and colors use GTP notation (`f5`, `pass`, `black`, `white`);
+/// implementations translate them to the protocol the engine speaks.
+pub trait Engine: Send {
+    /// Send initialization commands to the engine.
+    ///
+    /// Used to configure the engine after startup, e.g. its hash size, thread
+    /// count or weight file, with the engine's own commands. The commands
+    /// are sent again whenever the engine is restarted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the command if the engine does not accept one
+    /// of the commands.
+    fn initialize(&mut self, commands: &[String]) -> Result<()>;
+
+    /// Set how long to wait for any response, or `None` to wait indefinitely.
+    ///
+    /// A command that is not answered in time marks the engine as crashed.
+    fn set_timeout(&mut self, timeout: Option<Duration>);
+
+    /// Check whether the engine crashed or stopped responding to a command.
+    fn has_crashed(&self) -> bool;
+
+    /// Get the commands and responses of the current game.
+    fn transcript(&self) -> &[TranscriptEntry];
+
+    /// Check that the engine process is running and answers a ping.
+    fn is_responsive(&mut self) -> bool;
+
+    /// Kill the engine process and start it again, replaying the
+    /// initialization commands.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process cannot be started or initialized.
+    fn restart(&mut self) -> Result<()>;
+
+    /// Get the engine's name and version.
+    fn name(&self) -> String;
+
+    /// Reset the engine to the initial position.
+    ///
+    /// Also starts a new transcript.
+    fn clear_board(&mut self) -> Result<()>;
+
+    /// Make a move on the engine's internal board.
+    ///
+    /// Informs the engine of a move made by the opponent or during opening
+    /// setup.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color making the move ("black" or "white")
+    /// * `mv` - The move in algebraic notation (e.g., "f5" or "pass")
+    fn play(&mut self, color: &str, mv: &str) -> Result<()>;
+
+    /// Request the engine to choose and play its best move.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The color for which to generate a move ("black" or "white")
+    /// * `think_time` - Time the engine may think, allowed on top of the response timeout
+    fn genmove(&mut self, color: &str, think_time: Duration) -> Result<String>;
+
+    /// Query the score of the engine's last generated move.
+    ///
+    /// Returns the score in discs from the perspective of the side that
+    /// moved, or `None` if the engine has no score to report.
+    fn score(&mut self) -> Result<Option<f32>>;
+
+    /// Analyze the position on the engine's board without playing a move,
+    /// searching at the given level.
+    ///
+    /// Returns `None` if the engine does not support analysis or the side to
+    /// move has no legal move.
+    fn analyze(&mut self, level: u32) -> Result<Option<Analysis>>;
+
+    /// Configure time control settings for the engine.
+    ///
+    /// Engines that do not support time control ignore the settings.
+    fn time_settings(
+        &mut self,
+        main_time: u64,
+        byoyomi_time: u64,
+        byoyomi_stones: u32,
+    ) -> Result<()>;
+
+    /// Update the remaining time for a player.
+    ///
+    /// Engines that do not support time control ignore the update.
+    fn time_left(&mut self, color: &str, time: u64, stones: u32) -> Result<()>;
+}
+
+/// Start an engine process speaking `protocol`.
+///
+/// # Errors
+///
+/// Returns an error if the process cannot be started or fails the initial
+/// handshake.
+pub fn start(
+    protocol: EngineProtocol,
+    executable: &str,
+    args: &[String],
+    working_dir: Option<PathBuf>,
+) -> Result<Box<dyn Engine>> {
+    Ok(match protocol {
+        EngineProtocol::Gtp => Box::new(GtpEngine::new(executable, args, working_dir)?),
+        EngineProtocol::Edax => Box::new(EdaxEngine::new(executable, args, working_dir)?),
+    })
+}
+
+/// Working directory of an engine process: `working_dir`, or by default the
+/// directory containing the executable.
+pub(crate) fn engine_working_dir(executable: &str, working_dir: Option<PathBuf>) -> PathBuf {
+    working_dir.unwrap_or_else(|| match Path::new(executable).parent() {
+        Some(parent) => parent.to_path_buf(),
+        None => PathBuf::from("."),
+    })
+}
+
+/// Start an engine process with piped stdin and stdout.
+pub(crate) fn spawn_process(
+    executable: &str,
+    args: &[String],
+    working_dir: &Path,
+) -> Result<(Child, ChildStdin, ResponseReader)> {
+    let mut process = Command::new(executable)
+        .args(args)
+        .current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+
+    let stdin = process
+        .stdin
+        .take()
+        .ok_or_else(|| MatchRunnerError::Engine(ERR_STDIN_FAILED.to_string()))?;
+    let stdout = process
+        .stdout
+        .take()
+        .ok_or_else(|| MatchRunnerError::Engine(ERR_STDOUT_FAILED.to_string()))?;
+
+    Ok((process, stdin, ResponseReader::spawn(stdout)))
+}
+
+/// Engine stdout, read on a separate thread so that reads can time out.
+pub(crate) struct ResponseReader {
+    chunks: Receiver<Vec<u8>>,
+    buffer: Vec<u8>,
+    pos: usize,
+    /// Longest wait for more output, or `None` to wait indefinitely
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl ResponseReader {
+    fn spawn(mut stdout: ChildStdout) -> Self {
+        let (sender, chunks) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match stdout.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if sender.send(buf[..n].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        ResponseReader {
+            chunks,
+            buffer: Vec::new(),
+            pos: 0,
+            timeout: None,
+        }
+    }
+}
+
+impl Read for ResponseReader {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(out.len());
+        out[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for ResponseReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.buffer.len() {
+            let chunk = match self.timeout {
+                Some(timeout) => self.chunks.recv_timeout(timeout).map_err(|e| match e {
+                    RecvTimeoutError::Timeout => Some(io::Error::from(io::ErrorKind::TimedOut)),
+                    RecvTimeoutError::Disconnected => None,
+                }),
+                None => self.chunks.recv().map_err(|_| None),
+            };
+            match chunk {
+                Ok(chunk) => {
+                    self.buffer = chunk;
+                    self.pos = 0;
+                }
+                Err(Some(e)) => return Err(e),
+                // The process closed stdout
+                Err(None) => return Ok(&[]),
+            }
+        }
+        Ok(&self.buffer[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt;
+    }
+}
+
 /// A GTP-compatible Reversi engine process.
 ///
 /// This struct manages communication with an external Reversi engine process
@@ -34,11 +299,23 @@ const ERR_PROCESS_CLOSED: &str = "Process closed stdout";
 /// sending commands, and receiving responses.
 ///
 /// The engine's stdin and stdout are stored as fields to maintain a persistent
-/// `BufReader`, preventing potential data loss from repeated buffer recreation.
+/// reader, preventing potential data loss from repeated buffer recreation.
+/// The command line is kept so that the process can be restarted.
 pub struct GtpEngine {
     process: Child,
     stdin: ChildStdin,
-    reader: BufReader<ChildStdout>,
+    reader: ResponseReader,
+    executable: String,
+    args: Vec<String>,
+    working_dir: PathBuf,
+    /// Initialization commands, replayed after a restart
+    init_commands: Vec<String>,
+    /// Response timeout, or `None` to wait indefinitely
+    timeout: Option<Duration>,
+    /// Whether the process crashed or stopped responding
+    crashed: bool,
+    /// Commands and responses since the last `clear_board`
+    transcript: Vec<TranscriptEntry>,
     /// Engine name
     name: String,
     /// Engine version
@@ -71,37 +348,20 @@ impl GtpEngine {
     /// Returns an error if the engine process cannot be started or if initial
     /// communication with the engine fails.
     pub fn new(executable: &str, args: &[String], working_dir: Option<PathBuf>) -> Result<Self> {
-        let exec_path = Path::new(executable);
-        let default_working_dir = if let Some(parent) = exec_path.parent() {
-            parent.to_path_buf()
-        } else {
-            PathBuf::from(".")
-        };
-
-        let working_dir = working_dir.unwrap_or(default_working_dir);
-
-        let mut process = Command::new(executable)
-            .args(args)
-            .current_dir(&working_dir)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::inherit())
-            .spawn()?;
-
-        let stdin = process
-            .stdin
-            .take()
-            .ok_or_else(|| MatchRunnerError::Engine(ERR_STDIN_FAILED.to_string()))?;
-        let stdout = process
-            .stdout
-            .take()
-            .ok_or_else(|| MatchRunnerError::Engine(ERR_STDOUT_FAILED.to_string()))?;
-        let reader = BufReader::new(stdout);
+        let working_dir = engine_working_dir(executable, working_dir);
+        let (process, stdin, reader) = spawn_process(executable, args, &working_dir)?;
 
         let mut engine = GtpEngine {
             process,
             stdin,
             reader,
+            executable: executable.to_string(),
+            args: args.to_vec(),
+            working_dir,
+            init_commands: Vec::new(),
+            timeout: None,
+            crashed: false,
+            transcript: Vec::new(),
             name: String::new(),
             version: String::new(),
         };
@@ -117,10 +377,31 @@ impl GtpEngine {
         Ok(engine)
     }
 
+    fn send_init_commands(&mut self) -> Result<()> {
+        for command in self.init_commands.clone() {
+            let response = self.send_command(&command)?;
+            Self::parse_success_response(&response).map_err(|_| {
+                MatchRunnerError::Engine(format!(
+                    "{}: initialization command '{command}' failed: {}",
+                    self.name(),
+                    response.trim()
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
     // =============================================================================
     // Core Communication
     // =============================================================================
 
+    fn record(&mut self, line: String) {
+        self.transcript.push(TranscriptEntry {
+            time: Instant::now(),
+            line,
+        });
+    }
+
     /// Core GTP communication logic.
     fn communicate(
         stdin: &mut dyn Write,
@@ -168,102 +449,38 @@ impl GtpEngine {
     /// Returns an error if communication with the engine fails or if the
     /// engine process terminates unexpectedly.
     pub fn send_command(&mut self, command: &str) -> Result<String> {
-        Self::communicate(&mut self.stdin, &mut self.reader, command)
+        self.send_command_within(command, self.timeout)
     }
 
-    // =============================================================================
-    // Engine Information
-    // =============================================================================
-
-    /// Get the engine's name and version.
-    ///
-    /// Returns the cached name and version that were retrieved during
-    /// engine initialization.
-    ///
-    /// # Returns
+    /// Send a GTP command, waiting at most `timeout` for the response.
     ///
-    /// A formatted string containing the engine name and version.
-    pub fn name(&self) -> String {
-        if !self.version.is_empty() {
-            format!("{} {}", self.name, self.version)
-        } else {
-            self.name.clone()
+    /// Any communication failure marks the engine as crashed.
+    fn send_command_within(&mut self, command: &str, timeout: Option<Duration>) -> Result<String> {
+        self.reader.timeout = timeout;
+        self.record(format!("> {command}"));
+        let result = Self::communicate(&mut self.stdin, &mut self.reader, command);
+        match &result {
+            Ok(response) => {
+                for line in response.lines() {
+                    self.record(format!("< {line}"));
+                }
+            }
+            Err(e) => self.record(format!("< ({e})")),
         }
-    }
-
-    // =============================================================================
-    // Game Control
-    // =============================================================================
-
-    /// Clear the engine's internal board state.
-    ///
-    /// Sends the "clear_board" GTP command to reset the engine to an
-    /// initial empty board state.
-    pub fn clear_board(&mut self) -> Result<()> {
-        let response = self.send_command(GTP_CMD_CLEAR_BOARD)?;
-        Self::parse_success_response(&response)?;
-        Ok(())
-    }
-
-    /// Make a move on the engine's internal board.
-    ///
-    /// Sends a "play" GTP command to inform the engine of a move made
-    /// by the opponent or during opening setup.
-    ///
-    /// # Arguments
-    ///
-    /// * `color` - The color making the move ("black" or "white")
-    /// * `mv` - The move in algebraic notation (e.g., "f5" or "pass")
-    pub fn play(&mut self, color: &str, mv: &str) -> Result<()> {
-        let response = self.send_command(&format!("{GTP_CMD_PLAY} {color} {mv}"))?;
-        Self::parse_success_response(&response)?;
-        Ok(())
-    }
-
-    /// Request the engine to generate a move.
-    ///
-    /// Sends a "genmove" GTP command asking the engine to choose and
-    /// return its best move for the specified color.
-    ///
-    /// # Arguments
-    ///
-    /// * `color` - The color for which to generate a move ("black" or "white")
-    pub fn genmove(&mut self, color: &str) -> Result<String> {
-        let response = self.send_command(&format!("{GTP_CMD_GENMOVE} {color}"))?;
-        Self::parse_success_response(&response)
-    }
-
-    // =============================================================================
-    // Time Control
-    // =============================================================================
-
-    /// Configure time control settings for the engine.
-    ///
-    /// Sends a "time_settings" GTP command to configure the engine's time control.
-    /// This command is optional and some engines may not support it.
-    pub fn time_settings(
-        &mut self,
-        main_time: u64,
-        byoyomi_time: u64,
-        byoyomi_stones: u32,
-    ) -> Result<()> {
-        let response = self.send_command(&format!(
-            "time_settings {main_time} {byoyomi_time} {byoyomi_stones}"
-        ))?;
-        // Ignore errors - not all engines support time_settings
-        let _ = Self::parse_optional_response(&response);
-        Ok(())
-    }
 
-    /// Update the remaining time for a player.
-    ///
-    /// Sends a "time_left" GTP command to inform the engine of the current
-    /// remaining time for a player.
-    pub fn time_left(&mut self, color: &str, time: u64, stones: u32) -> Result<()> {
-        let response = self.send_command(&format!("time_left {color} {time} {stones}"))?;
-        // Ignore errors - not all engines support time_left
-        let _ = Self::parse_optional_response(&response);
-        Ok(())
+        result.map_err(|e| {
+            self.crashed = true;
+            match e {
+                MatchRunnerError::Io(err) if err.kind() == io::ErrorKind::TimedOut => {
+                    MatchRunnerError::Engine(format!(
+                        "{} did not respond to '{command}' within {:.1}s",
+                        self.name(),
+                        timeout.unwrap_or_default().as_secs_f64()
+                    ))
+                }
+                e => e,
+            }
+        })
     }
 
     // =============================================================================
@@ -308,6 +525,20 @@ impl GtpEngine {
         }
     }
 
+    /// Parse an `nr-eval` response: a line with the static evaluation,
+    /// followed by one `<move> <score>` line per legal move, best first.
+    fn parse_analysis(response: &str) -> Option<Analysis> {
+        let best = response
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("eval"))?;
+        let mut fields = best.split_whitespace();
+        Some(Analysis {
+            best_move: fields.next()?.parse().ok()?,
+            score: fields.next()?.parse().ok()?,
+        })
+    }
+
     /// Format version string with 'v' prefix if needed.
     fn format_version(version: &str) -> String {
         if version.is_empty() {
@@ -320,6 +551,109 @@ impl GtpEngine {
     }
 }
 
+impl Engine for GtpEngine {
+    fn initialize(&mut self, commands: &[String]) -> Result<()> {
+        self.init_commands = commands.to_vec();
+        self.send_init_commands()
+    }
+
+    fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    fn has_crashed(&self) -> bool {
+        self.crashed
+    }
+
+    fn transcript(&self) -> &[TranscriptEntry] {
+        &self.transcript
+    }
+
+    fn is_responsive(&mut self) -> bool {
+        if self.crashed || !matches!(self.process.try_wait(), Ok(None)) {
+            return false;
+        }
+        let timeout = self.timeout.unwrap_or(PING_TIMEOUT);
+        self.send_command_within(GTP_CMD_NAME, Some(timeout))
+            .and_then(|response| Self::parse_success_response(&response))
+            .is_ok()
+    }
+
+    fn restart(&mut self) -> Result<()> {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+
+        let (process, stdin, reader) =
+            spawn_process(&self.executable, &self.args, &self.working_dir)?;
+        self.process = process;
+        self.stdin = stdin;
+        self.reader = reader;
+        self.crashed = false;
+        self.send_init_commands()
+    }
+
+    fn name(&self) -> String {
+        if !self.version.is_empty() {
+            format!("{} {}", self.name, self.version)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    fn clear_board(&mut self) -> Result<()> {
+        self.transcript.clear();
+        let response = self.send_command(GTP_CMD_CLEAR_BOARD)?;
+        Self::parse_success_response(&response)?;
+        Ok(())
+    }
+
+    fn play(&mut self, color: &str, mv: &str) -> Result<()> {
+        let response = self.send_command(&format!("{GTP_CMD_PLAY} {color} {mv}"))?;
+        Self::parse_success_response(&response)?;
+        Ok(())
+    }
+
+    fn genmove(&mut self, color: &str, think_time: Duration) -> Result<String> {
+        let timeout = self.timeout.map(|timeout| timeout + think_time);
+        let response = self.send_command_within(&format!("{GTP_CMD_GENMOVE} {color}"), timeout)?;
+        Self::parse_success_response(&response)
+    }
+
+    fn score(&mut self) -> Result<Option<f32>> {
+        let response = self.send_command(GTP_CMD_NR_SCORE)?;
+        Ok(Self::parse_optional_response(&response)?.parse().ok())
+    }
+
+    /// Sends the `nr-eval <level>` extension command.
+    fn analyze(&mut self, level: u32) -> Result<Option<Analysis>> {
+        let response = self.send_command(&format!("{GTP_CMD_NR_EVAL} {level}"))?;
+        Ok(Self::parse_analysis(&Self::parse_optional_response(
+            &response,
+        )?))
+    }
+
+    fn time_settings(
+        &mut self,
+        main_time: u64,
+        byoyomi_time: u64,
+        byoyomi_stones: u32,
+    ) -> Result<()> {
+        let response = self.send_command(&format!(
+            "time_settings {main_time} {byoyomi_time} {byoyomi_stones}"
+        ))?;
+        // Ignore errors - not all engines support time_settings
+        let _ = Self::parse_optional_response(&response);
+        Ok(())
+    }
+
+    fn time_left(&mut self, color: &str, time: u64, stones: u32) -> Result<()> {
+        let response = self.send_command(&format!("time_left {color} {time} {stones}"))?;
+        // Ignore errors - not all engines support time_left
+        let _ = Self::parse_optional_response(&response);
+        Ok(())
+    }
+}
+
 impl Drop for GtpEngine {
     fn drop(&mut self) {
         let _ = self.process.kill();
@@ -373,6 +707,17 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_parse_analysis() {
+        let analysis = GtpEngine::parse_analysis("eval +1.25\nd3 +2.00\nc4 +0.50").unwrap();
+        assert_eq!(analysis.best_move, Square::D3);
+        assert_eq!(analysis.score, 2.0);
+
+        // Only the static evaluation when the side to move must pass
+        assert_eq!(GtpEngine::parse_analysis("eval -3.00"), None);
+        assert_eq!(GtpEngine::parse_analysis(""), None);
+    }
+
     #[test]
     fn test_format_version_empty() {
         assert_eq!(GtpEngine::format_version(""), "");
@@ -416,6 +761,28 @@ mod tests {
         assert_eq!(result, "= line1\nline2\n");
     }
 
+    #[test]
+    fn test_response_reader_times_out() {
+        let (sender, chunks) = mpsc::channel();
+        let mut reader = ResponseReader {
+            chunks,
+            buffer: Vec::new(),
+            pos: 0,
+            timeout: Some(Duration::from_millis(10)),
+        };
+
+        sender.send(b"= ok\n\n".to_vec()).unwrap();
+        let result = GtpEngine::communicate(&mut Vec::new(), &mut reader, "cmd").unwrap();
+        assert_eq!(result, "= ok\n");
+
+        let err = GtpEngine::communicate(&mut Vec::new(), &mut reader, "cmd").unwrap_err();
+        assert!(matches!(err, MatchRunnerError::Io(e) if e.kind() == io::ErrorKind::TimedOut));
+
+        drop(sender);
+        let err = GtpEngine::communicate(&mut Vec::new(), &mut reader, "cmd").unwrap_err();
+        assert!(matches!(err, MatchRunnerError::Engine(_)));
+    }
+
     #[test]
     fn test_communicate_process_closed() {
         let response_data = b"";
@@ -3,6 +3,7 @@
 //! This module provides the GameState struct which wraps the core
 //! game state for match play.
 
+use reversi_core::board::Board;
 use reversi_core::disc::Disc;
 use reversi_core::game_state;
 use reversi_core::square::Square;
@@ -43,6 +44,11 @@ impl GameState {
         self.core.side_to_move()
     }
 
+    /// Get the current board from the perspective of the side to move.
+    pub fn board(&self) -> &Board {
+        self.core.board()
+    }
+
     /// Make a move on the board.
     ///
     /// Attempts to play the specified move for the current player. Handles both
@@ -69,6 +75,15 @@ impl GameState {
         }
     }
 
+    /// Get the moves played so far, excluding passes.
+    pub fn moves(&self) -> Vec<Square> {
+        self.core
+            .move_history()
+            .iter()
+            .filter_map(|&(sq, _, _)| sq)
+            .collect()
+    }
+
     /// Check if the game has ended.
     ///
     /// A game ends when both players pass consecutively or when the board is full.
@@ -0,0 +1,166 @@
+//! Per-game debugging output.
+//!
+//! Writes plain-text files describing a single game: a full log with the
+//! timestamped GTP traffic of both engines and the final board, and the
+//! evidence saved when an engine plays an illegal move.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use reversi_core::formats::GameRecord;
+use reversi_core::square::Square;
+
+use crate::engine::TranscriptEntry;
+use crate::error::Result;
+use crate::match_runner::{IllegalMove, MatchResult};
+
+/// Identification of a game in the match.
+pub struct GameInfo<'a> {
+    /// 1-based game number
+    pub number: usize,
+    pub opening: &'a str,
+    pub black_name: &'a str,
+    pub white_name: &'a str,
+}
+
+impl GameInfo<'_> {
+    fn header(&self) -> String {
+        format!(
+            "Game {}, opening {}\nBlack: {}\nWhite: {}\n",
+            self.number, self.opening, self.black_name, self.white_name
+        )
+    }
+}
+
+/// Write the log of a finished game to `dir` as `game-<N>.log`.
+///
+/// The log holds the result, the moves, the final board and the GTP traffic
+/// of both engines in order, timed from the first command of the game.
+pub fn write_game_log(
+    dir: &Path,
+    info: &GameInfo,
+    result: &MatchResult,
+    black_transcript: &[TranscriptEntry],
+    white_transcript: &[TranscriptEntry],
+) -> Result<()> {
+    let mut log = info.header();
+    log.push_str(&format!(
+        "Result: {:+} ({})\nMoves: {}\n",
+        result.score,
+        result.termination.name(),
+        format_moves(&result.moves)
+    ));
+
+    if let Ok(state) = GameRecord::from_moves(result.moves.clone()).replay() {
+        let (black, white) = state.get_score();
+        log.push_str(&format!(
+            "\nFinal position (X: {black}, O: {white}):\n{}\n",
+            state.board().to_string_as_board(state.side_to_move())
+        ));
+    }
+
+    log.push_str("\nGTP traffic:\n");
+    log.push_str(&format_traffic(&[
+        ("black", black_transcript),
+        ("white", white_transcript),
+    ]));
+
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(format!("game-{}.log", info.number)), log)?;
+    Ok(())
+}
+
+/// Write the evidence of an illegal move to `dir` as `game-<N>.txt`,
+/// returning a description of the incident for the final report.
+pub fn save_illegal_move(
+    dir: &Path,
+    info: &GameInfo,
+    moves: &[Square],
+    illegal_move: &IllegalMove,
+) -> Result<String> {
+    let (color, offender) = if illegal_move.black {
+        ("black", info.black_name)
+    } else {
+        ("white", info.white_name)
+    };
+
+    let mut evidence = info.header();
+    evidence.push_str(&format!(
+        "Illegal move by {color} ({offender}): {}\n\
+         Moves: {}\n\n\
+         Position before the move ({color} to move):\n{}\n",
+        illegal_move.mv,
+        format_moves(moves),
+        illegal_move.position
+    ));
+    evidence.push_str("\nGTP traffic:\n");
+    evidence.push_str(&format_traffic(&[
+        ("black", &illegal_move.black_transcript),
+        ("white", &illegal_move.white_transcript),
+    ]));
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("game-{}.txt", info.number));
+    fs::write(&path, evidence)?;
+
+    Ok(format!(
+        "Game {}: {offender} played illegal move '{}' as {color} (evidence: {})",
+        info.number,
+        illegal_move.mv,
+        path.display()
+    ))
+}
+
+fn format_moves(moves: &[Square]) -> String {
+    moves.iter().map(|sq| sq.to_string()).collect()
+}
+
+/// Merge the transcripts of several engines in time order, one line per
+/// entry with the seconds since the earliest entry and the engine's label.
+fn format_traffic(transcripts: &[(&str, &[TranscriptEntry])]) -> String {
+    let mut entries: Vec<(&str, &TranscriptEntry)> = transcripts
+        .iter()
+        .flat_map(|&(label, transcript)| transcript.iter().map(move |entry| (label, entry)))
+        .collect();
+    // Stable, so each engine's command stays before its response
+    entries.sort_by_key(|(_, entry)| entry.time);
+
+    let start = entries
+        .first()
+        .map_or_else(Instant::now, |(_, entry)| entry.time);
+    entries
+        .iter()
+        .map(|(label, entry)| {
+            let elapsed = entry.time.duration_since(start).as_secs_f64();
+            format!("[{elapsed:9.3}s] {label} {}\n", entry.line)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_traffic_merges_in_time_order() {
+        let start = Instant::now();
+        let entry = |ms, line: &str| TranscriptEntry {
+            time: start + Duration::from_millis(ms),
+            line: line.to_string(),
+        };
+        let black = [entry(0, "> genmove black"), entry(1500, "< = f5")];
+        let white = [entry(1600, "> play black f5"), entry(1600, "< =")];
+
+        let traffic = format_traffic(&[("black", &black), ("white", &white)]);
+        assert_eq!(
+            traffic,
+            "[    0.000s] black > genmove black\n\
+             [    1.500s] black < = f5\n\
+             [    1.600s] white > play black f5\n\
+             [    1.600s] white < =\n"
+        );
+        assert_eq!(format_traffic(&[]), "");
+    }
+}
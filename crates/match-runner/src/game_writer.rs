@@ -0,0 +1,197 @@
+//! Game record output.
+//!
+//! Writes every game of a match to a GGF or WTHOR file so that results can
+//! be replayed and analyzed afterwards. GGF games are appended one per line
+//! and keep player names and move times; WTHOR databases hold moves and
+//! results only, and are rewritten after each game since their header
+//! carries the game count.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use reversi_core::formats::{GameRecord, ggf, wthor};
+
+use crate::error::{MatchRunnerError, Result};
+
+/// File format for game records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GameFormat {
+    /// Generic Game Format, one game per line
+    Ggf,
+    /// WTHOR database (`.wtb`)
+    Wthor,
+}
+
+impl GameFormat {
+    /// Infer the format from a file extension, defaulting to GGF.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("wtb") => GameFormat::Wthor,
+            _ => GameFormat::Ggf,
+        }
+    }
+}
+
+/// Writes finished games to a record file.
+pub struct GameWriter {
+    path: PathBuf,
+    format: GameFormat,
+    /// Open GGF file
+    ggf_file: Option<File>,
+    /// Games written so far to a WTHOR database
+    wthor_games: Vec<GameRecord>,
+}
+
+impl GameWriter {
+    /// Open `path` for writing games in `format`.
+    ///
+    /// With `append`, games already in the file are kept; otherwise the file
+    /// is replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created, or if an existing
+    /// WTHOR database to append to cannot be read.
+    pub fn create(path: &Path, format: GameFormat, append: bool) -> Result<Self> {
+        let mut writer = Self {
+            path: path.to_path_buf(),
+            format,
+            ggf_file: None,
+            wthor_games: Vec::new(),
+        };
+
+        match format {
+            GameFormat::Ggf => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(append)
+                    .truncate(!append)
+                    .open(path)?;
+                writer.ggf_file = Some(file);
+            }
+            GameFormat::Wthor => {
+                if append && path.exists() {
+                    writer.wthor_games = wthor::read(&fs::read(path)?).map_err(|e| {
+                        MatchRunnerError::Config(format!("{}: {e}", path.display()))
+                    })?;
+                }
+                writer.write_wthor()?;
+            }
+        }
+
+        Ok(writer)
+    }
+
+    /// Write one finished game.
+    pub fn write(&mut self, record: &GameRecord) -> Result<()> {
+        match self.format {
+            GameFormat::Ggf => {
+                let line = ggf::format(record).map_err(MatchRunnerError::Game)?;
+                if let Some(file) = &mut self.ggf_file {
+                    writeln!(file, "{line}")?;
+                    file.flush()?;
+                }
+            }
+            GameFormat::Wthor => {
+                self.wthor_games.push(record.clone());
+                self.write_wthor()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_wthor(&self) -> Result<()> {
+        let bytes =
+            wthor::write(&self.wthor_games, current_year()).map_err(MatchRunnerError::Game)?;
+        fs::write(&self.path, bytes)?;
+        Ok(())
+    }
+}
+
+/// Current UTC year.
+fn current_year() -> u16 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    year_from_days((secs / 86_400) as i64)
+}
+
+/// Gregorian year of the given day since 1970-01-01.
+fn year_from_days(days: i64) -> u16 {
+    // Civil-from-days conversion with years starting on March 1st
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let year = yoe + era * 400 + i64::from(mp >= 10);
+    year as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            GameFormat::from_path(Path::new("games.ggf")),
+            GameFormat::Ggf
+        );
+        assert_eq!(
+            GameFormat::from_path(Path::new("games.WTB")),
+            GameFormat::Wthor
+        );
+        assert_eq!(GameFormat::from_path(Path::new("games")), GameFormat::Ggf);
+    }
+
+    #[test]
+    fn test_year_from_days() {
+        assert_eq!(year_from_days(0), 1970);
+        assert_eq!(year_from_days(364), 1970);
+        assert_eq!(year_from_days(365), 1971);
+        // 2024-12-31 and 2025-01-01
+        assert_eq!(year_from_days(20_088), 2024);
+        assert_eq!(year_from_days(20_089), 2025);
+    }
+
+    #[test]
+    fn test_write_games() {
+        let dir = std::env::temp_dir();
+        let record = GameRecord {
+            result: Some(4),
+            times: vec![0.0, 1.5],
+            ..GameRecord::parse("f5d6").unwrap()
+        };
+
+        for (name, format) in [("ggf", GameFormat::Ggf), ("wtb", GameFormat::Wthor)] {
+            let path = dir.join(format!("match-runner-games-{}.{name}", std::process::id()));
+            let mut writer = GameWriter::create(&path, format, false).unwrap();
+            writer.write(&record).unwrap();
+            drop(writer);
+
+            let mut writer = GameWriter::create(&path, format, true).unwrap();
+            writer.write(&record).unwrap();
+            drop(writer);
+
+            let games = match format {
+                GameFormat::Ggf => fs::read_to_string(&path)
+                    .unwrap()
+                    .lines()
+                    .map(|line| ggf::parse(line).unwrap())
+                    .collect(),
+                GameFormat::Wthor => wthor::read(&fs::read(&path).unwrap()).unwrap(),
+            };
+            assert_eq!(games.len(), 2);
+            assert_eq!(games[1].moves, record.moves);
+            assert_eq!(games[1].result, Some(4));
+            fs::remove_file(&path).unwrap();
+        }
+    }
+}
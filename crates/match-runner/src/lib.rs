@@ -1,9 +1,22 @@
+pub mod adjudication;
+pub mod agreement;
+pub mod checkpoint;
 pub mod colors;
 pub mod config;
 pub mod display;
+pub mod distributed;
+pub mod edax;
 pub mod engine;
 pub mod error;
 pub mod game;
+pub mod game_log;
+pub mod game_writer;
 pub mod match_runner;
+pub mod phase;
+pub mod results_writer;
 pub mod statistics;
 pub mod time_tracker;
+pub mod tournament;
+
+pub use match_runner::{MatchReport, run_match};
+pub use tournament::{TournamentReport, run_tournament};
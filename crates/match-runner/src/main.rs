@@ -1,33 +1,25 @@
 use std::io;
 
-mod colors;
-mod config;
-mod display;
-mod engine;
-mod error;
-mod game;
-mod match_runner;
-mod statistics;
-mod time_tracker;
-
-use config::Config;
-use error::MatchRunnerError;
-use match_runner::MatchRunner;
+use match_runner::config::Config;
+use match_runner::error::MatchRunnerError;
 
 fn main() -> io::Result<()> {
     let config = Config::parse_args();
 
-    let mut match_runner = MatchRunner::new();
+    let result = if config.connect.is_some() {
+        match_runner::distributed::run_remote_worker(&config).map(|report| report.print())
+    } else if config.is_tournament() {
+        match_runner::run_tournament(&config).map(|report| report.print())
+    } else {
+        match_runner::run_match(&config).map(|report| report.print())
+    };
 
-    if let Err(e) = match_runner.run_match(&config) {
-        match e {
-            MatchRunnerError::Io(io_err) => return Err(io_err),
-            _ => {
-                eprintln!("Error: {e}");
-                std::process::exit(1);
-            }
+    match result {
+        Ok(printed) => printed,
+        Err(MatchRunnerError::Io(io_err)) => Err(io_err),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
         }
     }
-
-    Ok(())
 }
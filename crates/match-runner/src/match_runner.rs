@@ -4,16 +4,36 @@
 //! two GTP engines, including game execution, progress tracking, and result
 //! aggregation.
 
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
 use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
 
+use crate::adjudication::{self, Adjudication};
+use crate::agreement::AgreementStats;
+use crate::checkpoint::Checkpoint;
 use crate::config::Config;
 use crate::display::DisplayManager;
-use crate::engine::GtpEngine;
+use crate::display::dashboard::{Dashboard, LiveGame, LiveGames};
+use crate::distributed::{self, GameSettings};
+use crate::engine::{self, Engine, TranscriptEntry};
 use crate::error::{MatchRunnerError, Result};
 use crate::game::GameState;
-use crate::statistics::{MatchStatistics, MatchWinner};
-use crate::time_tracker::TimeTracker;
+use crate::game_log::{self, GameInfo};
+use crate::game_writer::{GameFormat, GameWriter};
+use crate::phase::{self, WinPhase};
+use crate::results_writer::ResultsWriter;
+use crate::statistics::{MatchLimit, MatchStatistics, MatchWinner, SprtDecision};
+use crate::time_tracker::{CapAction, ThinkTime, TimeTracker};
+use reversi_core::board::Board;
 use reversi_core::disc::Disc;
+use reversi_core::formats::GameRecord;
 use reversi_core::square::Square;
 
 /// Possible outcomes of a single game.
@@ -33,6 +53,353 @@ pub struct MatchResult {
     pub result: GameResult,
     /// Score difference (black perspective)
     pub score: i32,
+    /// How the game ended
+    pub termination: Termination,
+    /// The illegal move that ended the game, if any
+    pub illegal_move: Option<IllegalMove>,
+    /// Wall time black spent on its moves
+    pub black_time: ThinkTime,
+    /// Wall time white spent on its moves
+    pub white_time: ThinkTime,
+    /// Moves played from the initial position, excluding passes
+    pub moves: Vec<Square>,
+    /// Wall time of each move in `moves` in milliseconds (0 for opening moves)
+    pub move_times_ms: Vec<u64>,
+    /// Agreement of black's (first) and white's (second) moves with the
+    /// other engine's analysis, if enabled
+    pub agreement: AgreementStats,
+}
+
+impl MatchResult {
+    /// Create the result of a game that ended with the given disc counts.
+    ///
+    /// Empty squares are awarded to the winner.
+    pub fn from_disc_counts(black_count: u32, white_count: u32) -> Self {
+        let score = match black_count.cmp(&white_count) {
+            std::cmp::Ordering::Greater => 64 - (white_count as i32) * 2,
+            std::cmp::Ordering::Less => (black_count as i32) * 2 - 64,
+            std::cmp::Ordering::Equal => 0,
+        };
+        Self::from_score(score)
+    }
+
+    /// Create the result of a game with the given final disc difference from
+    /// black's perspective.
+    pub fn from_score(score: i32) -> Self {
+        let result = match score.cmp(&0) {
+            std::cmp::Ordering::Greater => GameResult::BlackWin,
+            std::cmp::Ordering::Less => GameResult::WhiteWin,
+            std::cmp::Ordering::Equal => GameResult::Draw,
+        };
+        MatchResult {
+            result,
+            score,
+            termination: Termination::Normal,
+            illegal_move: None,
+            black_time: ThinkTime::default(),
+            white_time: ThinkTime::default(),
+            moves: Vec::new(),
+            move_times_ms: Vec::new(),
+            agreement: AgreementStats::default(),
+        }
+    }
+}
+
+impl MatchResult {
+    /// Create the result of a game forfeited by black or white, scored ±64
+    /// from black's perspective.
+    pub fn forfeit(black_forfeits: bool, termination: Termination) -> Self {
+        MatchResult {
+            termination,
+            ..Self::from_score(if black_forfeits { -64 } else { 64 })
+        }
+    }
+}
+
+/// An illegal move played by an engine, with the evidence needed to
+/// investigate it.
+#[derive(Debug, Clone)]
+pub struct IllegalMove {
+    /// The move as sent by the engine
+    pub mv: String,
+    /// Whether black played the move
+    pub black: bool,
+    /// Position before the move, with X for black and O for white
+    pub position: String,
+    /// GTP transcript of the game for black's engine
+    pub black_transcript: Vec<TranscriptEntry>,
+    /// GTP transcript of the game for white's engine
+    pub white_transcript: Vec<TranscriptEntry>,
+}
+
+/// How a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Termination {
+    /// Played out to the end
+    Normal,
+    /// Forfeited on time
+    TimeLoss,
+    /// Adjudicated before it was played out
+    Adjudication,
+    /// Forfeited by an engine that crashed or stopped responding
+    Crash,
+    /// Forfeited by an engine that played an illegal move
+    IllegalMove,
+}
+
+impl Termination {
+    /// Short lowercase name, as used in checkpoints and game logs.
+    pub fn name(self) -> &'static str {
+        match self {
+            Termination::Normal => "normal",
+            Termination::TimeLoss => "time",
+            Termination::Adjudication => "adjudicated",
+            Termination::Crash => "crash",
+            Termination::IllegalMove => "illegal",
+        }
+    }
+
+    /// Check whether the game was lost by forfeit.
+    pub fn is_forfeit(self) -> bool {
+        !matches!(self, Termination::Normal | Termination::Adjudication)
+    }
+}
+
+/// Moves and times of a game in progress, kept when the game is cut short.
+#[derive(Default)]
+struct GameProgress {
+    state: GameState,
+    black_time: ThinkTime,
+    white_time: ThinkTime,
+    move_times_ms: Vec<u64>,
+    agreement: AgreementStats,
+}
+
+impl GameProgress {
+    /// Attach the moves and times played so far to `result`.
+    fn finish(self, result: MatchResult) -> MatchResult {
+        MatchResult {
+            black_time: self.black_time,
+            white_time: self.white_time,
+            moves: self.state.moves(),
+            move_times_ms: self.move_times_ms,
+            agreement: self.agreement,
+            ..result
+        }
+    }
+}
+
+/// Outcome of a complete match between two engines.
+#[derive(Debug, Clone)]
+pub struct MatchReport {
+    /// Name reported by the first engine
+    pub engine1_name: String,
+    /// Name reported by the second engine
+    pub engine2_name: String,
+    /// Aggregated results from the first engine's perspective
+    pub statistics: MatchStatistics,
+}
+
+impl MatchReport {
+    /// Print the final match summary to stdout.
+    pub fn print(&self) -> io::Result<()> {
+        self.statistics
+            .print_final_results(&self.engine1_name, &self.engine2_name)
+    }
+}
+
+/// Run a complete match using the provided configuration.
+///
+/// Convenience wrapper around [`MatchRunner::run_match`] for embedding the
+/// match runner in other tools.
+pub fn run_match(config: &Config) -> Result<MatchReport> {
+    MatchRunner::new().run_match(config)
+}
+
+/// Result of one game from the first engine's perspective, as sent from a
+/// worker thread to the match loop and stored in checkpoints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameReport {
+    /// Index of the opening in the opening file
+    pub opening_idx: usize,
+    /// Whether the first engine played black
+    pub engine1_black: bool,
+    /// Winner of the game
+    pub winner: MatchWinner,
+    /// Disc difference for the first engine
+    pub score: i32,
+    /// How the game ended
+    pub termination: Termination,
+    /// Wall time the first engine spent on its moves
+    pub engine1_time: ThinkTime,
+    /// Wall time the second engine spent on its moves
+    pub engine2_time: ThinkTime,
+    /// Phase that decided the game, if it was won without a forfeit
+    pub phase: Option<WinPhase>,
+    /// Games the engines had played since their last scheduled restart, if
+    /// restarts are enabled; not kept in checkpoints
+    pub games_since_restart: Option<u32>,
+    /// Description of an illegal move that ended the game, not kept in checkpoints
+    pub incident: Option<String>,
+    /// Agreement of the first (engine1) and second engine's moves with the
+    /// other engine's analysis; not kept in checkpoints
+    pub agreement: AgreementStats,
+}
+
+/// A game finished by a worker thread.
+pub(crate) struct FinishedGame {
+    pub(crate) report: GameReport,
+    pub(crate) record: GameRecord,
+}
+
+/// Hands out the openings of a match to the workers playing them.
+pub(crate) trait OpeningSource {
+    /// Take the next opening to play as a pair of games, with its index in
+    /// the opening file, or `None` once the worker should stop.
+    fn next_opening(&self) -> Option<(usize, String)>;
+
+    /// Mark an opening taken with [`next_opening`](Self::next_opening) as played.
+    fn finish_opening(&self, opening_idx: usize);
+}
+
+/// Openings left to play in a match.
+///
+/// Openings taken by remote workers that disconnect are given back and
+/// played by the next free worker, so workers asking for an opening wait
+/// until every opening taken has been finished before stopping.
+pub(crate) struct OpeningQueue<'a> {
+    openings: &'a [String],
+    state: Mutex<QueueState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: VecDeque<usize>,
+    in_progress: usize,
+    stopped: bool,
+}
+
+impl<'a> OpeningQueue<'a> {
+    /// Create a queue playing the openings at the `pending` indices in order.
+    pub(crate) fn new(openings: &'a [String], pending: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            openings,
+            state: Mutex::new(QueueState {
+                pending: pending.into_iter().collect(),
+                ..QueueState::default()
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Return an unfinished opening to the front of the queue.
+    pub(crate) fn give_back(&self, opening_idx: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.pending.push_front(opening_idx);
+        state.in_progress -= 1;
+        self.changed.notify_all();
+    }
+
+    /// Stop handing out openings.
+    pub(crate) fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.changed.notify_all();
+    }
+
+    /// Check whether the queue is stopped or every opening has been played.
+    pub(crate) fn is_finished(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.stopped || (state.pending.is_empty() && state.in_progress == 0)
+    }
+}
+
+impl OpeningSource for OpeningQueue<'_> {
+    fn next_opening(&self) -> Option<(usize, String)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if state.stopped {
+                return None;
+            }
+            if let Some(opening_idx) = state.pending.pop_front() {
+                state.in_progress += 1;
+                return Some((opening_idx, self.openings[opening_idx].clone()));
+            }
+            if state.in_progress == 0 {
+                return None;
+            }
+            state = self.changed.wait(state).unwrap();
+        }
+    }
+
+    fn finish_opening(&self, _opening_idx: usize) {
+        self.state.lock().unwrap().in_progress -= 1;
+        self.changed.notify_all();
+    }
+}
+
+/// Adds game results to the match statistics, pairing the two games of each
+/// opening as they complete.
+#[derive(Default)]
+struct ResultCollector {
+    /// First game of each opening pair whose second game is still running
+    pending_pairs: HashMap<usize, (MatchWinner, i32)>,
+}
+
+impl ResultCollector {
+    /// Add a game to `statistics`. Returns true once an SPRT reaches a decision.
+    fn add(
+        &mut self,
+        statistics: &mut MatchStatistics,
+        report: &GameReport,
+        opening: &str,
+    ) -> bool {
+        statistics.add_result(
+            report.winner,
+            report.score,
+            opening.to_string(),
+            report.engine1_black,
+        );
+        statistics.add_think_times(&report.engine1_time, &report.engine2_time);
+        match report.termination {
+            Termination::Normal => {}
+            Termination::TimeLoss => statistics.add_time_loss(report.winner),
+            Termination::Adjudication => statistics.adjudicated_games += 1,
+            Termination::Crash => statistics.add_crash(report.winner),
+            Termination::IllegalMove => {
+                statistics.add_illegal_move(report.winner, report.incident.clone())
+            }
+        }
+        if let Some(phase) = report.phase {
+            statistics.add_win_phase(report.winner, phase);
+        }
+        if let Some(games_since_restart) = report.games_since_restart {
+            statistics.add_restart_cycle_result(report.winner, report.score, games_since_restart);
+        }
+        statistics.agreement.merge(&report.agreement);
+
+        let game = (report.winner, report.score);
+        let Some(first_game) = self.pending_pairs.remove(&report.opening_idx) else {
+            self.pending_pairs.insert(report.opening_idx, game);
+            return false;
+        };
+        statistics.add_paired_result(first_game, game);
+        statistics
+            .sprt_decision()
+            .is_some_and(|decision| decision != SprtDecision::Continue)
+    }
+}
+
+/// Check whether `mv`, as sent by an engine, is legal on `board`.
+///
+/// A pass is legal only when the side to move has no legal move.
+fn is_legal_move(board: &Board, mv: &str) -> bool {
+    if mv.eq_ignore_ascii_case("pass") {
+        return !board.has_legal_moves();
+    }
+    mv.parse::<Square>()
+        .is_ok_and(|sq| board.get_moves().contains(sq))
 }
 
 /// Parse an opening string into a sequence of squares.
@@ -75,9 +442,9 @@ impl MatchRunner {
     ///
     /// This is the main entry point for running automated matches. It handles:
     /// - Loading opening positions
-    /// - Initializing both engines
-    /// - Running all games with progress tracking
-    /// - Displaying final results
+    /// - Initializing one pair of engines per concurrent game
+    /// - Running all games with progress tracking, stopping early once an
+    ///   SPRT reaches a decision or `--max-games`/`--max-duration` is hit
     ///
     /// # Arguments
     ///
@@ -85,15 +452,15 @@ impl MatchRunner {
     ///
     /// # Returns
     ///
-    /// `Ok(())` on successful match completion.
+    /// A `MatchReport` with the engine names and aggregated statistics.
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The opening file is empty or invalid
-    /// - Either engine fails to start
+    /// - Any engine fails to start
     /// - Any game encounters a fatal error
-    pub fn run_match(&mut self, config: &Config) -> Result<()> {
+    pub fn run_match(&mut self, config: &Config) -> Result<MatchReport> {
         let openings = config.load_openings()?;
 
         if openings.is_empty() {
@@ -102,44 +469,175 @@ impl MatchRunner {
             ));
         }
 
-        let mut engines = self.initialize_engines(config)?;
-        let engine_names = self.get_engine_names(&mut engines)?;
-
-        // Create time tracker (mode is auto-detected from GTP time_settings parameters)
-        let mut time_tracker =
-            TimeTracker::new(config.main_time, config.byoyomi_time, config.byoyomi_stones);
-
-        let total_games = openings.len() * 2;
-        let mut statistics = MatchStatistics::new();
-
-        self.display.show_match_header()?;
+        let sprt = config.sprt()?;
+        let mut statistics = match sprt {
+            Some(sprt) => MatchStatistics::with_sprt(sprt),
+            None => MatchStatistics::new(),
+        };
+        statistics.restart_interval = config.restart_every;
+
+        // Replay the games of a resumed match and skip their openings
+        let mut collector = ResultCollector::default();
+        let mut checkpoint = None;
+        let mut decided = false;
+        let mut remaining = vec![true; openings.len()];
+        if let Some(path) = &config.checkpoint {
+            let (file, completed) = Checkpoint::open(path, config.resume, &openings)?;
+            for report in &completed {
+                decided |= collector.add(&mut statistics, report, &openings[report.opening_idx]);
+                remaining[report.opening_idx] = false;
+            }
+            checkpoint = Some(file);
+        }
+        let mut game_writer = match &config.games_file {
+            Some(path) => {
+                let format = config
+                    .games_format
+                    .unwrap_or_else(|| GameFormat::from_path(path));
+                Some(GameWriter::create(path, format, config.resume)?)
+            }
+            None => None,
+        };
+        let mut results_writer = match &config.results_file {
+            Some(path) => Some(ResultsWriter::create(path, config.resume)?),
+            None => None,
+        };
+        let pending: Vec<usize> = (0..openings.len())
+            .filter(|&idx| !decided && remaining[idx])
+            .collect();
+        // Both games of an opening are always played, so the game limit is rounded up to pairs
+        let scheduled = config.max_games.map_or(pending.len(), |max_games| {
+            pending.len().min(max_games.div_ceil(2) as usize)
+        });
+        let game_limit = (scheduled < pending.len()).then_some(scheduled as u32 * 2);
+        let queue = OpeningQueue::new(&openings, pending[..scheduled].iter().copied());
+        let listener = config
+            .serve
+            .as_deref()
+            .map(distributed::listen)
+            .transpose()?;
+
+        let mut engine_pairs = (0..config.concurrency)
+            .map(|_| self.initialize_engines(config))
+            .collect::<Result<Vec<_>>>()?;
+        let (engine1_name, engine2_name) = self.get_engine_names(&mut engine_pairs[0])?;
+        let engine_names = (
+            config.engine1_limits().label(engine1_name),
+            config.engine2_limits().label(engine2_name),
+        );
+
+        let total_games = match game_limit {
+            Some(limit) => statistics.total_games() as usize + limit as usize,
+            None => openings.len() * 2,
+        };
+
+        let (dashboard, progress_bar) = if config.tui {
+            let dashboard = Dashboard::start(&engine_names, total_games);
+            (Some(dashboard), ProgressBar::hidden())
+        } else {
+            self.display.show_match_header()?;
 
-        // Show initial empty statistics
-        self.display
-            .update_live_visualization(&statistics, &engine_names.0, &engine_names.1)?;
+            // Show initial statistics, including any resumed games
+            self.display.update_live_visualization(
+                &statistics,
+                &engine_names.0,
+                &engine_names.1,
+            )?;
 
-        let progress_bar = self.display.create_progress_bar(total_games as u64);
+            let progress_bar = self.display.create_progress_bar(total_games as u64);
+            progress_bar.set_position(statistics.total_games() as u64);
+            (None, progress_bar)
+        };
+        let live_games = LiveGames::default();
+
+        let settings = GameSettings::from_config(config);
+        let worker_timeout = settings.game_time_limit(config.engine_timeout());
+        let deadline = config
+            .max_duration
+            .map(|secs| Instant::now() + Duration::from_secs(secs));
+        let runner = &*self;
+
+        let outcome = thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+
+            if let Some(listener) = listener {
+                let (queue, live_games, engine_names, settings, sender) = (
+                    &queue,
+                    &live_games,
+                    &engine_names,
+                    &settings,
+                    sender.clone(),
+                );
+                scope.spawn(move || {
+                    distributed::accept_workers(
+                        scope,
+                        listener,
+                        queue,
+                        live_games,
+                        engine_names,
+                        settings,
+                        worker_timeout,
+                        sender,
+                    )
+                });
+            }
 
-        for (opening_idx, opening_str) in openings.iter().enumerate() {
-            if let Err(e) = self.play_opening_pair(
-                &mut engines,
+            for engines in &mut engine_pairs {
+                let sender = sender.clone();
+                let (queue, live_games, settings) = (&queue, &live_games, &settings);
+                // Create time tracker (mode is auto-detected from GTP time_settings parameters)
+                let time_tracker = settings.time_tracker();
+                scope.spawn(move || {
+                    runner.run_worker(
+                        engines,
+                        queue,
+                        time_tracker,
+                        settings.adjudication,
+                        settings.agreement_level,
+                        settings.restart_every,
+                        &config.evidence_dir,
+                        config.log_dir.as_deref(),
+                        live_games,
+                        sender,
+                    )
+                });
+            }
+            drop(sender);
+
+            let outcome = runner.collect_results(
+                receiver,
+                &mut collector,
+                checkpoint.as_mut(),
+                game_writer.as_mut(),
+                results_writer.as_mut(),
                 &mut statistics,
+                &openings,
                 &engine_names,
-                opening_str,
-                opening_idx,
                 &progress_bar,
-                &mut time_tracker,
-            ) {
-                progress_bar.finish_and_clear();
-                return Err(e);
-            }
+                dashboard,
+                &live_games,
+                &queue,
+                deadline,
+                game_limit,
+            );
+            queue.stop();
+            outcome
+        });
+
+        if let Err(e) = outcome {
+            progress_bar.finish_and_clear();
+            return Err(e);
         }
 
         progress_bar.finish_and_clear();
         self.display.clear_screen()?;
-        statistics.print_final_results(&engine_names.0, &engine_names.1)?;
 
-        Ok(())
+        let (engine1_name, engine2_name) = engine_names;
+        Ok(MatchReport {
+            engine1_name,
+            engine2_name,
+            statistics,
+        })
     }
 
     /// Execute a single game between two engines.
@@ -153,6 +651,9 @@ impl MatchRunner {
     /// * `white_engine` - Engine playing as white
     /// * `opening_moves` - Optional opening sequence in algebraic notation
     /// * `time_tracker` - Time tracker for managing time control
+    /// * `adjudication` - Settings for ending decided games early
+    /// * `agreement_level` - Level at which the waiting engine analyzes each
+    ///   move's position for the agreement report, if enabled
     ///
     /// # Returns
     ///
@@ -166,10 +667,43 @@ impl MatchRunner {
     /// - Engine communication is lost
     pub fn play_game(
         &self,
-        black_engine: &mut GtpEngine,
-        white_engine: &mut GtpEngine,
+        black_engine: &mut dyn Engine,
+        white_engine: &mut dyn Engine,
         opening_moves: Option<&str>,
         time_tracker: &mut TimeTracker,
+        adjudication: &Adjudication,
+        agreement_level: Option<u32>,
+    ) -> Result<MatchResult> {
+        let mut game = GameProgress::default();
+        match self.play_moves(
+            black_engine,
+            white_engine,
+            opening_moves,
+            time_tracker,
+            adjudication,
+            agreement_level,
+            &mut game,
+        ) {
+            // An engine that crashed or stopped responding forfeits the game
+            Err(_) if black_engine.has_crashed() || white_engine.has_crashed() => Ok(game.finish(
+                MatchResult::forfeit(black_engine.has_crashed(), Termination::Crash),
+            )),
+            result => result.map(|result| game.finish(result)),
+        }
+    }
+
+    /// Play the moves of a game, recording them in `game`, and return its
+    /// result without the move records.
+    #[allow(clippy::too_many_arguments)]
+    fn play_moves(
+        &self,
+        black_engine: &mut dyn Engine,
+        white_engine: &mut dyn Engine,
+        opening_moves: Option<&str>,
+        time_tracker: &mut TimeTracker,
+        adjudication: &Adjudication,
+        agreement_level: Option<u32>,
+        game: &mut GameProgress,
     ) -> Result<MatchResult> {
         black_engine.clear_board()?;
         white_engine.clear_board()?;
@@ -191,18 +725,30 @@ impl MatchRunner {
             )?;
         }
 
-        let mut game_state = GameState::new();
-
         if let Some(opening) = opening_moves {
-            self.apply_opening_moves(&mut game_state, black_engine, white_engine, opening)?;
+            self.apply_opening_moves(&mut game.state, black_engine, white_engine, opening)?;
         }
-
-        while !game_state.is_game_over() {
-            let is_black = game_state.side_to_move() == Disc::Black;
+        game.move_times_ms = vec![0; game.state.moves().len()];
+        let mut score_adjudicator = adjudication.score_adjudicator();
+
+        while !game.state.is_game_over() {
+            let is_black = game.state.side_to_move() == Disc::Black;
+
+            let board = game.state.board();
+            if adjudication.solve_empties > 0
+                && board.get_empty_count() <= adjudication.solve_empties
+            {
+                let score = adjudication::solve(board);
+                return Ok(MatchResult {
+                    termination: Termination::Adjudication,
+                    ..MatchResult::from_score(if is_black { score } else { -score })
+                });
+            }
             let current_color = if is_black { "black" } else { "white" };
 
             // Send time_left to both engines before move generation.
             // In byoyomi phase, this sends the remaining period time and stones.
+            let mut think_time = Duration::ZERO;
             if time_tracker.is_enabled() {
                 let (black_time, black_stones) = time_tracker.black_time_left();
                 let (white_time, white_stones) = time_tracker.white_time_left();
@@ -210,44 +756,124 @@ impl MatchRunner {
                 white_engine.time_left("black", black_time, black_stones)?;
                 black_engine.time_left("white", white_time, white_stones)?;
                 white_engine.time_left("white", white_time, white_stones)?;
+                think_time = Duration::from_secs(if is_black { black_time } else { white_time });
             }
 
+            // The waiting engine analyzes the position the mover is about to play in
+            let analysis = match agreement_level {
+                Some(level) if game.state.board().has_legal_moves() => {
+                    if is_black {
+                        white_engine.analyze(level)?
+                    } else {
+                        black_engine.analyze(level)?
+                    }
+                }
+                _ => None,
+            };
+
             // Start timing this move
             time_tracker.start_move();
 
             let mv = if is_black {
-                black_engine.genmove("black")?
+                black_engine.genmove("black", think_time)?
             } else {
-                white_engine.genmove("white")?
+                white_engine.genmove("white", think_time)?
             };
 
             // End timing and update remaining time
             let has_time = time_tracker.end_move(is_black);
+            let mover_time = if is_black {
+                &mut game.black_time
+            } else {
+                &mut game.white_time
+            };
+            mover_time.record(time_tracker.last_move_ms());
+
             if !has_time && time_tracker.is_enabled() {
                 return Ok(Self::time_loss_result(is_black));
             }
+            if let Some(action) = time_tracker.cap_exceeded() {
+                mover_time.cap_violations += 1;
+                if action == CapAction::Forfeit {
+                    return Ok(Self::time_loss_result(is_black));
+                }
+            }
+
+            if !is_legal_move(game.state.board(), &mv) {
+                return Ok(MatchResult {
+                    illegal_move: Some(IllegalMove {
+                        mv,
+                        black: is_black,
+                        position: game
+                            .state
+                            .board()
+                            .to_string_as_board(game.state.side_to_move()),
+                        black_transcript: black_engine.transcript().to_vec(),
+                        white_transcript: white_engine.transcript().to_vec(),
+                    }),
+                    ..MatchResult::forfeit(is_black, Termination::IllegalMove)
+                });
+            }
 
             self.execute_move(
-                &mut game_state,
+                &mut game.state,
                 black_engine,
                 white_engine,
                 &mv,
                 current_color,
             )?;
-        }
+            if mv.eq_ignore_ascii_case("pass") {
+                continue;
+            }
+            game.move_times_ms.push(time_tracker.last_move_ms());
 
-        let (black_count, white_count) = game_state.get_score();
-        let result = self.determine_game_result(black_count, white_count);
-        let score = self.calculate_score(black_count, white_count);
+            // Scores are reported for the side that moved
+            let mover_score = if score_adjudicator.is_some() || analysis.is_some() {
+                if is_black {
+                    black_engine.score()?
+                } else {
+                    white_engine.score()?
+                }
+            } else {
+                None
+            };
+
+            if let Some(analysis) = analysis {
+                let matched = mv.parse::<Square>().ok() == Some(analysis.best_move);
+                let scores = mover_score.map(|score| {
+                    if is_black {
+                        (score, analysis.score)
+                    } else {
+                        (analysis.score, score)
+                    }
+                });
+                game.agreement.add_move(is_black, matched, scores);
+            }
+
+            if let Some(adjudicator) = &mut score_adjudicator {
+                let score = if is_black {
+                    mover_score
+                } else {
+                    mover_score.map(|score| -score)
+                };
+                if let Some(score) = adjudicator.update(score) {
+                    return Ok(MatchResult {
+                        termination: Termination::Adjudication,
+                        ..MatchResult::from_score(score)
+                    });
+                }
+            }
+        }
 
-        Ok(MatchResult { result, score })
+        let (black_count, white_count) = game.state.get_score();
+        Ok(MatchResult::from_disc_counts(black_count, white_count))
     }
 
     fn apply_opening_moves(
         &self,
         game_state: &mut GameState,
-        black_engine: &mut GtpEngine,
-        white_engine: &mut GtpEngine,
+        black_engine: &mut dyn Engine,
+        white_engine: &mut dyn Engine,
         opening: &str,
     ) -> Result<()> {
         let moves = parse_opening_moves(opening)?;
@@ -273,15 +899,15 @@ impl MatchRunner {
     fn execute_move(
         &self,
         game_state: &mut GameState,
-        black_engine: &mut GtpEngine,
-        white_engine: &mut GtpEngine,
+        black_engine: &mut dyn Engine,
+        white_engine: &mut dyn Engine,
         mv: &str,
         current_color: &str,
     ) -> Result<()> {
         if mv.to_lowercase() == "pass" {
             game_state.make_move(None).map_err(MatchRunnerError::Game)?;
 
-            let opponent_engine = if current_color == "black" {
+            let opponent_engine: &mut dyn Engine = if current_color == "black" {
                 white_engine
             } else {
                 black_engine
@@ -294,7 +920,7 @@ impl MatchRunner {
                 .make_move(Some(square))
                 .map_err(MatchRunnerError::Game)?;
 
-            let opponent_engine = if current_color == "black" {
+            let opponent_engine: &mut dyn Engine = if current_color == "black" {
                 white_engine
             } else {
                 black_engine
@@ -311,116 +937,312 @@ impl MatchRunner {
             .map_err(|_| MatchRunnerError::Game(format!("Invalid move: {move_str}")))
     }
 
-    fn determine_game_result(&self, black_count: u32, white_count: u32) -> GameResult {
-        match black_count.cmp(&white_count) {
-            std::cmp::Ordering::Greater => GameResult::BlackWin,
-            std::cmp::Ordering::Less => GameResult::WhiteWin,
-            std::cmp::Ordering::Equal => GameResult::Draw,
-        }
-    }
-
-    fn calculate_score(&self, black_count: u32, white_count: u32) -> i32 {
-        match black_count.cmp(&white_count) {
-            std::cmp::Ordering::Greater => 64 - (white_count as i32) * 2,
-            std::cmp::Ordering::Less => (black_count as i32) * 2 - 64,
-            std::cmp::Ordering::Equal => 0,
-        }
-    }
-
     /// Create a MatchResult for a time loss.
     ///
     /// Score is from black's perspective: -64 if black lost, +64 if white lost.
     fn time_loss_result(is_black: bool) -> MatchResult {
-        if is_black {
-            MatchResult {
-                result: GameResult::WhiteWin,
-                score: -64,
-            }
-        } else {
-            MatchResult {
-                result: GameResult::BlackWin,
-                score: 64,
+        MatchResult::forfeit(is_black, Termination::TimeLoss)
+    }
+
+    /// Restart engines that crashed or no longer answer a ping.
+    fn restart_unresponsive(
+        black_engine: &mut dyn Engine,
+        white_engine: &mut dyn Engine,
+    ) -> Result<()> {
+        let engines: [&mut dyn Engine; 2] = [black_engine, white_engine];
+        for engine in engines {
+            if !engine.is_responsive() {
+                engine.restart()?;
             }
         }
+        Ok(())
     }
 
-    fn initialize_engines(&self, config: &Config) -> Result<(GtpEngine, GtpEngine)> {
+    pub(crate) fn initialize_engines(
+        &self,
+        config: &Config,
+    ) -> Result<(Box<dyn Engine>, Box<dyn Engine>)> {
         let (engine1_program, engine1_args) = config.get_engine1_command();
         let (engine2_program, engine2_args) = config.get_engine2_command();
 
-        let engine1 = GtpEngine::new(
+        let mut engine1 = engine::start(
+            config.engine1_protocol,
             &engine1_program,
             &engine1_args,
             config.engine1_working_dir.clone(),
         )?;
-        let engine2 = GtpEngine::new(
+        engine1.set_timeout(config.engine_timeout());
+        engine1.initialize(&config.engine1_init_commands())?;
+        let mut engine2 = engine::start(
+            config.engine2_protocol,
             &engine2_program,
             &engine2_args,
             config.engine2_working_dir.clone(),
         )?;
+        engine2.set_timeout(config.engine_timeout());
+        engine2.initialize(&config.engine2_init_commands())?;
 
         Ok((engine1, engine2))
     }
 
-    fn get_engine_names(&self, engines: &mut (GtpEngine, GtpEngine)) -> Result<(String, String)> {
+    pub(crate) fn get_engine_names(
+        &self,
+        engines: &mut (Box<dyn Engine>, Box<dyn Engine>),
+    ) -> Result<(String, String)> {
         let engine1_name = engines.0.name();
         let engine2_name = engines.1.name();
         Ok((engine1_name, engine2_name))
     }
 
+    /// Play opening pairs from `openings` on one pair of engines until it runs
+    /// out, sending each game result to the match loop. With `restart_every`,
+    /// both engines are restarted after that many games.
     #[allow(clippy::too_many_arguments)]
-    fn play_opening_pair(
-        &mut self,
-        engines: &mut (GtpEngine, GtpEngine),
+    pub(crate) fn run_worker(
+        &self,
+        engines: &mut (Box<dyn Engine>, Box<dyn Engine>),
+        openings: &dyn OpeningSource,
+        mut time_tracker: TimeTracker,
+        adjudication: Adjudication,
+        agreement_level: Option<u32>,
+        restart_every: Option<u32>,
+        evidence_dir: &Path,
+        log_dir: Option<&Path>,
+        live_games: &LiveGames,
+        sender: Sender<Result<FinishedGame>>,
+    ) {
+        let mut games_since_restart = 0;
+        while let Some((opening_idx, opening)) = openings.next_opening() {
+            let opening_str = opening.as_str();
+
+            for game_round in 0..2 {
+                let is_swapped = game_round == 1;
+                let game_number = opening_idx * 2 + game_round + 1;
+
+                let scheduled_restart = match restart_every {
+                    Some(interval) if games_since_restart >= interval => {
+                        games_since_restart = 0;
+                        engines.0.restart().and_then(|()| engines.1.restart())
+                    }
+                    _ => Ok(()),
+                };
+
+                let (black_engine, white_engine) = if is_swapped {
+                    (engines.1.as_mut(), engines.0.as_mut())
+                } else {
+                    (engines.0.as_mut(), engines.1.as_mut())
+                };
+
+                let black_name = black_engine.name();
+                let white_name = white_engine.name();
+
+                live_games.start(
+                    opening_idx,
+                    LiveGame {
+                        number: game_number,
+                        opening: opening.clone(),
+                        engine1_color: Some(if is_swapped { Disc::White } else { Disc::Black }),
+                        worker: None,
+                        started: Instant::now(),
+                    },
+                );
+
+                let game = scheduled_restart
+                    .and_then(|()| Self::restart_unresponsive(black_engine, white_engine))
+                    .and_then(|()| {
+                        self.play_game(
+                            black_engine,
+                            white_engine,
+                            Some(opening_str),
+                            &mut time_tracker,
+                            &adjudication,
+                            agreement_level,
+                        )
+                    })
+                    .and_then(|match_result| {
+                        let info = GameInfo {
+                            number: game_number,
+                            opening: opening_str,
+                            black_name: &black_name,
+                            white_name: &white_name,
+                        };
+                        if let Some(dir) = log_dir {
+                            game_log::write_game_log(
+                                dir,
+                                &info,
+                                &match_result,
+                                black_engine.transcript(),
+                                white_engine.transcript(),
+                            )?;
+                        }
+                        let incident = match &match_result.illegal_move {
+                            Some(illegal_move) => Some(game_log::save_illegal_move(
+                                evidence_dir,
+                                &info,
+                                &match_result.moves,
+                                illegal_move,
+                            )?),
+                            None => None,
+                        };
+                        let phase = if match_result.termination.is_forfeit() {
+                            None
+                        } else {
+                            phase::win_phase(&match_result.moves, match_result.score)
+                        };
+                        Ok(FinishedGame {
+                            record: GameRecord {
+                                black_name: Some(black_name),
+                                white_name: Some(white_name),
+                                result: Some(match_result.score),
+                                times: match_result
+                                    .move_times_ms
+                                    .iter()
+                                    .map(|&ms| ms as f64 / 1000.0)
+                                    .collect(),
+                                ..GameRecord::from_moves(match_result.moves.clone())
+                            },
+                            report: GameReport {
+                                opening_idx,
+                                engine1_black: !is_swapped,
+                                winner: self
+                                    .determine_match_winner(match_result.result, is_swapped),
+                                score: if is_swapped {
+                                    -match_result.score
+                                } else {
+                                    match_result.score
+                                },
+                                termination: match_result.termination,
+                                engine1_time: if is_swapped {
+                                    match_result.white_time
+                                } else {
+                                    match_result.black_time
+                                },
+                                engine2_time: if is_swapped {
+                                    match_result.black_time
+                                } else {
+                                    match_result.white_time
+                                },
+                                phase,
+                                games_since_restart: restart_every.map(|_| games_since_restart),
+                                incident,
+                                agreement: if is_swapped {
+                                    match_result.agreement.swapped()
+                                } else {
+                                    match_result.agreement
+                                },
+                            },
+                        })
+                    })
+                    .map_err(|e| {
+                        MatchRunnerError::Game(format!("Fatal error in game {game_number}: {e}"))
+                    });
+
+                live_games.finish(opening_idx);
+                games_since_restart += 1;
+                let failed = game.is_err();
+                if sender.send(game).is_err() || failed {
+                    return;
+                }
+            }
+            openings.finish_opening(opening_idx);
+        }
+    }
+
+    /// Aggregate game results from the workers, write and checkpoint them and keep the
+    /// live display or dashboard up to date, until all workers finish, a game fails,
+    /// or an SPRT reaches a decision. Stopping the match from the dashboard or at the
+    /// `deadline` stops handing out openings, and the games in progress are still
+    /// collected. `game_limit` is the number of games scheduled when `--max-games`
+    /// left openings unplayed.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_results(
+        &self,
+        receiver: Receiver<Result<FinishedGame>>,
+        collector: &mut ResultCollector,
+        mut checkpoint: Option<&mut Checkpoint>,
+        mut game_writer: Option<&mut GameWriter>,
+        mut results_writer: Option<&mut ResultsWriter>,
         statistics: &mut MatchStatistics,
+        openings: &[String],
         engine_names: &(String, String),
-        opening_str: &str,
-        opening_idx: usize,
         progress_bar: &ProgressBar,
-        time_tracker: &mut TimeTracker,
+        mut dashboard: Option<Dashboard>,
+        live_games: &LiveGames,
+        queue: &OpeningQueue,
+        deadline: Option<Instant>,
+        game_limit: Option<u32>,
     ) -> Result<()> {
-        let mut paired_results = Vec::new();
-
-        for game_round in 0..2 {
-            let is_swapped = game_round == 1;
-            let game_number = opening_idx * 2 + game_round + 1;
+        let mut games_collected = 0;
+        loop {
+            if let Some(deadline) = deadline
+                && statistics.limit_reached.is_none()
+                && Instant::now() >= deadline
+            {
+                statistics.limit_reached = Some(MatchLimit::Duration);
+                queue.stop();
+                if let Some(dashboard) = &mut dashboard {
+                    dashboard.mark_stopping();
+                }
+            }
 
-            let (black_engine, white_engine) = if is_swapped {
-                (&mut engines.1, &mut engines.0)
-            } else {
-                (&mut engines.0, &mut engines.1)
+            let game = match &mut dashboard {
+                // Redraw the dashboard and check for key presses while no game is waiting
+                Some(dashboard) => match receiver.try_recv() {
+                    Ok(game) => game,
+                    Err(TryRecvError::Empty) => {
+                        dashboard.draw(statistics, live_games)?;
+                        if dashboard.poll_stop()? {
+                            queue.stop();
+                        }
+                        continue;
+                    }
+                    Err(TryRecvError::Disconnected) => break,
+                },
+                // Wake up at the deadline to stop handing out openings
+                None => match deadline.filter(|_| statistics.limit_reached.is_none()) {
+                    Some(deadline) => match receiver
+                        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    {
+                        Ok(game) => game,
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match receiver.recv() {
+                        Ok(game) => game,
+                        Err(_) => break,
+                    },
+                },
             };
+            let FinishedGame { report, record } = game?;
+            if let Some(game_writer) = game_writer.as_deref_mut() {
+                game_writer.write(&record)?;
+            }
+            if let Some(checkpoint) = checkpoint.as_deref_mut() {
+                checkpoint.record(&report)?;
+            }
+            if let Some(results_writer) = results_writer.as_deref_mut() {
+                results_writer.write(&report, &openings[report.opening_idx], engine_names)?;
+            }
 
-            match self.play_game(black_engine, white_engine, Some(opening_str), time_tracker) {
-                Ok(match_result) => {
-                    let winner = self.determine_match_winner(match_result.result, is_swapped);
-                    let score = if is_swapped {
-                        -match_result.score
-                    } else {
-                        match_result.score
-                    };
-
-                    statistics.add_result(winner, score, opening_str.to_string(), !is_swapped);
-                    paired_results.push((winner, score));
-
-                    self.display.update_live_visualization(
-                        statistics,
-                        &engine_names.0,
-                        &engine_names.1,
-                    )?;
-                    progress_bar.inc(1);
-                }
-                Err(e) => {
-                    return Err(MatchRunnerError::Game(format!(
-                        "Fatal error in game {game_number}: {e}"
-                    )));
-                }
+            let decided = collector.add(statistics, &report, &openings[report.opening_idx]);
+            match &mut dashboard {
+                Some(dashboard) => dashboard.record(statistics),
+                None => self.display.update_live_visualization(
+                    statistics,
+                    &engine_names.0,
+                    &engine_names.1,
+                )?,
+            }
+            progress_bar.inc(1);
+
+            games_collected += 1;
+            if game_limit.is_some_and(|limit| games_collected >= limit) {
+                statistics.limit_reached = Some(MatchLimit::Games);
             }
-        }
 
-        // Add paired result after both games are complete
-        if paired_results.len() == 2 {
-            statistics.add_paired_result(paired_results[0], paired_results[1]);
+            if decided {
+                break;
+            }
         }
 
         Ok(())
@@ -453,47 +1275,58 @@ mod tests {
 
     #[test]
     fn test_determine_game_result_black_wins() {
-        let runner = MatchRunner::new();
-        assert_eq!(runner.determine_game_result(40, 24), GameResult::BlackWin);
+        assert_eq!(
+            MatchResult::from_disc_counts(40, 24).result,
+            GameResult::BlackWin
+        );
     }
 
     #[test]
     fn test_determine_game_result_white_wins() {
-        let runner = MatchRunner::new();
-        assert_eq!(runner.determine_game_result(20, 44), GameResult::WhiteWin);
+        assert_eq!(
+            MatchResult::from_disc_counts(20, 44).result,
+            GameResult::WhiteWin
+        );
+    }
+
+    #[test]
+    fn test_result_from_score() {
+        let result = MatchResult::from_score(-10);
+        assert_eq!(result.result, GameResult::WhiteWin);
+        assert_eq!(result.score, -10);
+        assert_eq!(result.termination, Termination::Normal);
+        assert_eq!(MatchResult::from_score(0).result, GameResult::Draw);
     }
 
     #[test]
     fn test_determine_game_result_draw() {
-        let runner = MatchRunner::new();
-        assert_eq!(runner.determine_game_result(32, 32), GameResult::Draw);
+        assert_eq!(
+            MatchResult::from_disc_counts(32, 32).result,
+            GameResult::Draw
+        );
     }
 
     #[test]
     fn test_calculate_score_black_wins() {
-        let runner = MatchRunner::new();
         // black=40, white=24: 64 - 24*2 = 16
-        assert_eq!(runner.calculate_score(40, 24), 16);
+        assert_eq!(MatchResult::from_disc_counts(40, 24).score, 16);
     }
 
     #[test]
     fn test_calculate_score_white_wins() {
-        let runner = MatchRunner::new();
         // black=20, white=44: 20*2 - 64 = -24
-        assert_eq!(runner.calculate_score(20, 44), -24);
+        assert_eq!(MatchResult::from_disc_counts(20, 44).score, -24);
     }
 
     #[test]
     fn test_calculate_score_draw() {
-        let runner = MatchRunner::new();
-        assert_eq!(runner.calculate_score(32, 32), 0);
+        assert_eq!(MatchResult::from_disc_counts(32, 32).score, 0);
     }
 
     #[test]
     fn test_calculate_score_with_empties() {
-        let runner = MatchRunner::new();
         // black=30, white=20, empties=14 -> winner gets empties: 64 - 40 = 24
-        assert_eq!(runner.calculate_score(30, 20), 24);
+        assert_eq!(MatchResult::from_disc_counts(30, 20).score, 24);
     }
 
     #[test]
@@ -501,6 +1334,19 @@ mod tests {
         let result = MatchRunner::time_loss_result(true);
         assert_eq!(result.result, GameResult::WhiteWin);
         assert_eq!(result.score, -64);
+        assert_eq!(result.termination, Termination::TimeLoss);
+        assert!(result.termination.is_forfeit());
+    }
+
+    #[test]
+    fn test_is_legal_move() {
+        let board = Board::new();
+        assert!(is_legal_move(&board, "f5"));
+        assert!(is_legal_move(&board, "F5"));
+        assert!(!is_legal_move(&board, "a1"));
+        assert!(!is_legal_move(&board, "pass"));
+        assert!(!is_legal_move(&board, "z9"));
+        assert!(!is_legal_move(&board, "resign"));
     }
 
     #[test]
@@ -587,4 +1433,27 @@ mod tests {
         // Odd-length opening strings are rejected as invalid
         assert!(parse_opening_moves("f5d").is_err());
     }
+
+    #[test]
+    fn test_opening_queue_give_back() {
+        let openings = ["f5".to_string(), "f5d6".to_string(), "f5f6".to_string()];
+        let queue = OpeningQueue::new(&openings, [0, 2]);
+
+        assert_eq!(queue.next_opening(), Some((0, "f5".to_string())));
+        assert_eq!(queue.next_opening(), Some((2, "f5f6".to_string())));
+        assert!(!queue.is_finished());
+
+        // An opening given back is handed out again before stopping
+        queue.give_back(2);
+        assert_eq!(queue.next_opening(), Some((2, "f5f6".to_string())));
+        queue.finish_opening(2);
+        queue.finish_opening(0);
+        assert!(queue.is_finished());
+        assert_eq!(queue.next_opening(), None);
+
+        let queue = OpeningQueue::new(&openings, 0..3);
+        queue.stop();
+        assert!(queue.is_finished());
+        assert_eq!(queue.next_opening(), None);
+    }
 }
@@ -0,0 +1,126 @@
+//! Attribution of won games to the phase that decided them.
+//!
+//! A win is credited to the midgame if the winner already had a won position
+//! when the game reached the endgame, and to the endgame if the position was
+//! drawn or lost at that point and the winner turned it around. The endgame
+//! position is solved exactly, so the attribution does not depend on either
+//! engine's evaluation.
+
+use reversi_core::disc::Disc;
+use reversi_core::square::Square;
+use serde::{Deserialize, Serialize};
+
+use crate::adjudication;
+use crate::game::GameState;
+
+/// Number of empty squares at which the endgame starts.
+pub const ENDGAME_EMPTIES: u32 = 14;
+
+/// Phase of the game in which a win was decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinPhase {
+    /// The winner entered the endgame with a won position
+    Midgame,
+    /// The winner entered the endgame with a drawn or lost position
+    Endgame,
+}
+
+impl WinPhase {
+    /// Short lowercase name, as used in checkpoints.
+    pub fn name(self) -> &'static str {
+        match self {
+            WinPhase::Midgame => "midgame",
+            WinPhase::Endgame => "endgame",
+        }
+    }
+
+    /// Parse a name returned by [`WinPhase::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "midgame" => Some(WinPhase::Midgame),
+            "endgame" => Some(WinPhase::Endgame),
+            _ => None,
+        }
+    }
+}
+
+/// Determine the phase that decided a game with the given moves and final
+/// score from black's perspective, or `None` for a draw.
+///
+/// Games that ended before reaching the endgame, such as adjudicated ones,
+/// are credited to the midgame.
+pub fn win_phase(moves: &[Square], black_score: i32) -> Option<WinPhase> {
+    if black_score == 0 {
+        return None;
+    }
+
+    let mut state = GameState::new();
+    for &sq in moves {
+        if state.board().get_empty_count() <= ENDGAME_EMPTIES {
+            break;
+        }
+        if !state.board().has_legal_moves() {
+            state.make_move(None).ok()?;
+        }
+        state.make_move(Some(sq)).ok()?;
+    }
+    if state.board().get_empty_count() > ENDGAME_EMPTIES {
+        return Some(WinPhase::Midgame);
+    }
+
+    let score = adjudication::solve(state.board());
+    let black_solved = if state.side_to_move() == Disc::Black {
+        score
+    } else {
+        -score
+    };
+    if black_solved.signum() == black_score.signum() {
+        Some(WinPhase::Midgame)
+    } else {
+        Some(WinPhase::Endgame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_games_are_decided_in_the_midgame() {
+        let moves = Square::parse_sequence("f5d6c3d3c4").unwrap();
+        assert_eq!(win_phase(&moves, 12), Some(WinPhase::Midgame));
+        assert_eq!(win_phase(&moves, 0), None);
+    }
+
+    #[test]
+    fn test_phase_follows_endgame_solve() {
+        // Play a full game with the first legal move each turn
+        let mut state = GameState::new();
+        let mut solved = None;
+        while !state.is_game_over() {
+            if solved.is_none() && state.board().get_empty_count() <= ENDGAME_EMPTIES {
+                let score = adjudication::solve(state.board());
+                solved = Some(if state.side_to_move() == Disc::Black {
+                    score
+                } else {
+                    -score
+                });
+            }
+            let sq = state.board().get_moves().iter().next();
+            state.make_move(sq).unwrap();
+        }
+        let moves = state.moves();
+        let solved = solved.unwrap();
+
+        let expected = |black_score: i32| {
+            if solved.signum() == black_score.signum() {
+                WinPhase::Midgame
+            } else {
+                WinPhase::Endgame
+            }
+        };
+        assert_eq!(win_phase(&moves, 10), Some(expected(10)));
+        assert_eq!(win_phase(&moves, -10), Some(expected(-10)));
+    }
+}
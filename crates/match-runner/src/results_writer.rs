@@ -0,0 +1,182 @@
+//! Machine-readable results output.
+//!
+//! Appends one JSON object per finished game to a JSON Lines file as the
+//! match runs, so that external tools can follow a match without parsing
+//! the console output.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::error::{MatchRunnerError, Result};
+use crate::match_runner::{GameReport, Termination};
+use crate::phase::WinPhase;
+use crate::statistics::MatchWinner;
+use crate::time_tracker::ThinkTime;
+
+/// One line of the results file.
+#[derive(Debug, Serialize)]
+struct ResultRecord<'a> {
+    /// 1-based game number, as in game logs
+    game: usize,
+    opening_idx: usize,
+    opening: &'a str,
+    black: &'a str,
+    white: &'a str,
+    /// Color played by the first engine, `black` or `white`
+    engine1_color: &'static str,
+    /// `engine1`, `engine2` or `draw`
+    winner: &'static str,
+    /// Disc difference for the first engine
+    score: i32,
+    termination: &'static str,
+    phase: Option<&'static str>,
+    /// Games the engines had played since their last scheduled restart
+    games_since_restart: Option<u32>,
+    engine1_time: TimeRecord,
+    engine2_time: TimeRecord,
+    /// Whether each engine lost the game on time
+    engine1_timeout: bool,
+    engine2_timeout: bool,
+    /// Unix time at which the game was recorded, in seconds
+    finished_at: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct TimeRecord {
+    total_ms: u64,
+    max_ms: u64,
+    moves: u32,
+    cap_violations: u32,
+}
+
+impl From<&ThinkTime> for TimeRecord {
+    fn from(time: &ThinkTime) -> Self {
+        Self {
+            total_ms: time.total_ms,
+            max_ms: time.max_ms,
+            moves: time.moves,
+            cap_violations: time.cap_violations,
+        }
+    }
+}
+
+/// Writes the result of every finished game to a JSON Lines file.
+pub struct ResultsWriter {
+    file: File,
+}
+
+impl ResultsWriter {
+    /// Open `path` for writing results. With `append`, existing lines are
+    /// kept; otherwise the file is replaced.
+    pub fn create(path: &Path, append: bool) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Append the result of one game, given the opening it was played from
+    /// and the names of the first and second engine.
+    pub fn write(
+        &mut self,
+        report: &GameReport,
+        opening: &str,
+        engine_names: &(String, String),
+    ) -> Result<()> {
+        let line = format_record(report, opening, engine_names, unix_time())?;
+        writeln!(self.file, "{line}")?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+fn format_record(
+    report: &GameReport,
+    opening: &str,
+    engine_names: &(String, String),
+    finished_at: f64,
+) -> Result<String> {
+    let (black, white) = if report.engine1_black {
+        (&engine_names.0, &engine_names.1)
+    } else {
+        (&engine_names.1, &engine_names.0)
+    };
+    let time_loss = report.termination == Termination::TimeLoss;
+
+    let record = ResultRecord {
+        game: report.opening_idx * 2 + if report.engine1_black { 1 } else { 2 },
+        opening_idx: report.opening_idx,
+        opening,
+        black,
+        white,
+        engine1_color: if report.engine1_black {
+            "black"
+        } else {
+            "white"
+        },
+        winner: match report.winner {
+            MatchWinner::Engine1 => "engine1",
+            MatchWinner::Engine2 => "engine2",
+            MatchWinner::Draw => "draw",
+        },
+        score: report.score,
+        termination: report.termination.name(),
+        phase: report.phase.map(WinPhase::name),
+        games_since_restart: report.games_since_restart,
+        engine1_time: (&report.engine1_time).into(),
+        engine2_time: (&report.engine2_time).into(),
+        engine1_timeout: time_loss && matches!(report.winner, MatchWinner::Engine2),
+        engine2_timeout: time_loss && matches!(report.winner, MatchWinner::Engine1),
+        finished_at,
+    };
+    serde_json::to_string(&record).map_err(|e| MatchRunnerError::Game(e.to_string()))
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_record() {
+        let report = GameReport {
+            opening_idx: 2,
+            engine1_black: false,
+            winner: MatchWinner::Engine1,
+            score: 64,
+            termination: Termination::TimeLoss,
+            engine1_time: ThinkTime {
+                total_ms: 1200,
+                max_ms: 300,
+                moves: 27,
+                ..ThinkTime::default()
+            },
+            engine2_time: ThinkTime::default(),
+            phase: None,
+            games_since_restart: Some(3),
+            incident: None,
+            agreement: Default::default(),
+        };
+        let names = ("new".to_string(), "old".to_string());
+
+        let line = format_record(&report, "f5d6", &names, 1.5).unwrap();
+        assert_eq!(
+            line,
+            r#"{"game":6,"opening_idx":2,"opening":"f5d6","black":"old","white":"new","engine1_color":"white","winner":"engine1","score":64,"termination":"time","phase":null,"games_since_restart":3,"engine1_time":{"total_ms":1200,"max_ms":300,"moves":27,"cap_violations":0},"engine2_time":{"total_ms":0,"max_ms":0,"moves":0,"cap_violations":0},"engine1_timeout":false,"engine2_timeout":true,"finished_at":1.5}"#
+        );
+    }
+}
@@ -1,9 +1,27 @@
+use crate::agreement::{AgreementStats, MoveAgreement};
 use crate::colors::ThemeColor;
+use crate::phase::WinPhase;
+use crate::time_tracker::{MOVE_TIME_BUCKETS_MS, ThinkTime};
 use colored::*;
+use rand::{RngExt, SeedableRng, rngs::SmallRng};
 use reversi_core::disc::Disc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io;
 
 const ELO_K: f64 = 400.0;
+/// Normalized Elo per unit of t-value, 800 / ln(10)
+const NELO_PER_T: f64 = 800.0 / std::f64::consts::LN_10;
+/// Number of resamples of the bootstrap confidence interval
+const BOOTSTRAP_SAMPLES: usize = 2000;
+/// Seed of the bootstrap resampling, fixed so that reports are reproducible
+const BOOTSTRAP_SEED: u64 = 0x5eed;
+/// Number of openings listed in the per-opening table of the final report
+const MAX_OPENING_ROWS: usize = 10;
+/// Pseudo-count added to each pentanomial outcome when estimating the SPRT
+/// variance, so that one-sided results such as all WW pairs keep a positive
+/// variance
+const SPRT_PSEUDO_COUNT: f64 = 0.2;
 
 #[derive(Debug, Clone)]
 pub struct MatchStatistics {
@@ -14,6 +32,84 @@ pub struct MatchStatistics {
     pub games_played: u32,
     pub recent_results: Vec<GameHistory>,
     pub paired_results: Vec<PairedResult>,
+    pub sprt: Option<Sprt>,
+    pub engine1_time_losses: u32,
+    pub engine2_time_losses: u32,
+    pub adjudicated_games: u32,
+    pub engine1_crashes: u32,
+    pub engine2_crashes: u32,
+    pub engine1_illegal_moves: u32,
+    pub engine2_illegal_moves: u32,
+    pub incidents: Vec<String>,
+    pub engine1_think_time: ThinkTime,
+    pub engine2_think_time: ThinkTime,
+    pub engine1_phase_wins: PhaseWins,
+    pub engine2_phase_wins: PhaseWins,
+    /// Results of each opening, keyed by its move sequence
+    pub opening_results: HashMap<String, ResultTally>,
+    /// Number of games between scheduled engine restarts, if enabled
+    pub restart_interval: Option<u32>,
+    /// Results in the first and second half of each restart interval
+    pub restart_cycle_results: [ResultTally; 2],
+    /// Agreement of each engine's moves with the other engine's analysis
+    pub agreement: AgreementStats,
+    /// Limit that stopped the match before every opening was played
+    pub limit_reached: Option<MatchLimit>,
+}
+
+/// Limit on the size of a match that can stop it early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchLimit {
+    /// `--max-games`
+    Games,
+    /// `--max-duration`
+    Duration,
+}
+
+impl MatchLimit {
+    /// Describe the limit for the match report.
+    pub fn description(self) -> &'static str {
+        match self {
+            MatchLimit::Games => "game limit reached",
+            MatchLimit::Duration => "time limit reached",
+        }
+    }
+}
+
+/// Wins of an engine by the phase that decided them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhaseWins {
+    pub midgame: u32,
+    pub endgame: u32,
+}
+
+/// Results of a group of games, from engine1's perspective.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResultTally {
+    pub engine1_wins: u32,
+    pub engine2_wins: u32,
+    pub draws: u32,
+    pub total_score: i32,
+}
+
+impl ResultTally {
+    pub fn add(&mut self, winner: MatchWinner, score: i32) {
+        match winner {
+            MatchWinner::Engine1 => self.engine1_wins += 1,
+            MatchWinner::Engine2 => self.engine2_wins += 1,
+            MatchWinner::Draw => self.draws += 1,
+        }
+        self.total_score += score;
+    }
+
+    pub fn games(&self) -> u32 {
+        self.engine1_wins + self.engine2_wins + self.draws
+    }
+
+    /// Points scored by engine1, counting draws as half a point.
+    pub fn engine1_points(&self) -> f64 {
+        f64::from(self.engine1_wins) + f64::from(self.draws) * 0.5
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,9 +142,47 @@ impl MatchStatistics {
             games_played: 0,
             recent_results: Vec::new(),
             paired_results: Vec::new(),
+            sprt: None,
+            engine1_time_losses: 0,
+            engine2_time_losses: 0,
+            adjudicated_games: 0,
+            engine1_crashes: 0,
+            engine2_crashes: 0,
+            engine1_illegal_moves: 0,
+            engine2_illegal_moves: 0,
+            incidents: Vec::new(),
+            engine1_think_time: ThinkTime::default(),
+            engine2_think_time: ThinkTime::default(),
+            engine1_phase_wins: PhaseWins::default(),
+            engine2_phase_wins: PhaseWins::default(),
+            opening_results: HashMap::new(),
+            restart_interval: None,
+            restart_cycle_results: [ResultTally::default(); 2],
+            agreement: AgreementStats::default(),
+            limit_reached: None,
+        }
+    }
+
+    /// Create statistics that track the given SPRT.
+    pub fn with_sprt(sprt: Sprt) -> Self {
+        Self {
+            sprt: Some(sprt),
+            ..Self::new()
         }
     }
 
+    /// Current log-likelihood ratio of the SPRT, if one is configured.
+    pub fn sprt_llr(&self) -> Option<f64> {
+        let freq = self.calculate_pentanomial_frequencies();
+        self.sprt.map(|sprt| sprt.llr(&freq))
+    }
+
+    /// Current SPRT decision, if one is configured.
+    pub fn sprt_decision(&self) -> Option<SprtDecision> {
+        let llr = self.sprt_llr()?;
+        self.sprt.map(|sprt| sprt.decision(llr))
+    }
+
     pub fn add_result(
         &mut self,
         winner: MatchWinner,
@@ -64,6 +198,11 @@ impl MatchStatistics {
         self.total_score += score;
         self.games_played += 1;
 
+        self.opening_results
+            .entry(opening.clone())
+            .or_default()
+            .add(winner, score);
+
         let engine1_color = if engine1_is_black {
             Disc::Black
         } else {
@@ -81,6 +220,88 @@ impl MatchStatistics {
         }
     }
 
+    /// Record a game forfeited on time, given the game's winner.
+    pub fn add_time_loss(&mut self, winner: MatchWinner) {
+        match winner {
+            MatchWinner::Engine1 => self.engine2_time_losses += 1,
+            MatchWinner::Engine2 => self.engine1_time_losses += 1,
+            MatchWinner::Draw => {}
+        }
+    }
+
+    /// Record a game forfeited by a crashed engine, given the game's winner.
+    pub fn add_crash(&mut self, winner: MatchWinner) {
+        match winner {
+            MatchWinner::Engine1 => self.engine2_crashes += 1,
+            MatchWinner::Engine2 => self.engine1_crashes += 1,
+            MatchWinner::Draw => {}
+        }
+    }
+
+    /// Record a game forfeited by an illegal move, given the game's winner
+    /// and a description of the incident if available.
+    pub fn add_illegal_move(&mut self, winner: MatchWinner, incident: Option<String>) {
+        match winner {
+            MatchWinner::Engine1 => self.engine2_illegal_moves += 1,
+            MatchWinner::Engine2 => self.engine1_illegal_moves += 1,
+            MatchWinner::Draw => {}
+        }
+        self.incidents.extend(incident);
+    }
+
+    /// Record the phase that decided a won game, given the game's winner.
+    pub fn add_win_phase(&mut self, winner: MatchWinner, phase: WinPhase) {
+        let wins = match winner {
+            MatchWinner::Engine1 => &mut self.engine1_phase_wins,
+            MatchWinner::Engine2 => &mut self.engine2_phase_wins,
+            MatchWinner::Draw => return,
+        };
+        match phase {
+            WinPhase::Midgame => wins.midgame += 1,
+            WinPhase::Endgame => wins.endgame += 1,
+        }
+    }
+
+    /// Record a game played `games_since_restart` games after the engines
+    /// were last restarted by the restart policy.
+    pub fn add_restart_cycle_result(
+        &mut self,
+        winner: MatchWinner,
+        score: i32,
+        games_since_restart: u32,
+    ) {
+        let Some(interval) = self.restart_interval else {
+            return;
+        };
+        let half = usize::from(games_since_restart * 2 >= interval);
+        self.restart_cycle_results[half].add(winner, score);
+    }
+
+    /// Openings ordered from worst to best for engine1, by points and then
+    /// by disc difference.
+    pub fn openings_by_engine1_result(&self) -> Vec<(&str, &ResultTally)> {
+        let mut openings: Vec<(&str, &ResultTally)> = self
+            .opening_results
+            .iter()
+            .map(|(opening, result)| (opening.as_str(), result))
+            .collect();
+        openings.sort_by(|(a_opening, a), (b_opening, b)| {
+            let a_rate = a.engine1_points() / f64::from(a.games());
+            let b_rate = b.engine1_points() / f64::from(b.games());
+            a_rate
+                .total_cmp(&b_rate)
+                .then(a.total_score.cmp(&b.total_score))
+                .then(a_opening.cmp(b_opening))
+        });
+        openings
+    }
+
+    /// Add the move times of one game to each engine's totals.
+    pub fn add_think_times(&mut self, engine1: &ThinkTime, engine2: &ThinkTime) {
+        self.engine1_think_time.merge(engine1);
+        self.engine2_think_time.merge(engine2);
+    }
+
     pub fn total_games(&self) -> u32 {
         self.engine1_wins + self.engine2_wins + self.draws
     }
@@ -120,11 +341,212 @@ impl MatchStatistics {
         self.print_summary();
         println!();
 
+        if self.has_phase_wins() {
+            self.print_phase_breakdown(engine1_name, engine2_name);
+            println!();
+        }
+
+        if self.engine1_think_time.moves > 0 || self.engine2_think_time.moves > 0 {
+            self.print_move_time_distribution(engine1_name, engine2_name);
+            println!();
+        }
+
+        if self.opening_results.len() > 1 {
+            self.print_opening_breakdown(engine1_name);
+            println!();
+        }
+
+        if !self.agreement.is_empty() {
+            self.print_agreement(engine1_name, engine2_name);
+            println!();
+        }
+
         println!("{}", "═".repeat(80).info().bold());
 
         Ok(())
     }
 
+    fn has_phase_wins(&self) -> bool {
+        [self.engine1_phase_wins, self.engine2_phase_wins]
+            .iter()
+            .any(|wins| wins.midgame + wins.endgame > 0)
+    }
+
+    /// Print how many of each engine's wins were decided in each phase.
+    fn print_phase_breakdown(&self, engine1_name: &str, engine2_name: &str) {
+        let name_width = engine1_name
+            .chars()
+            .count()
+            .max(engine2_name.chars().count())
+            .max(6);
+        println!("{}", "Wins by deciding phase:".text().bold());
+        println!(
+            "{}",
+            format!(
+                "  {:<name_width$} {:>10} {:>10}",
+                "Engine", "Midgame", "Endgame"
+            )
+            .text()
+            .bold()
+        );
+        for (name, wins) in [
+            (engine1_name, self.engine1_phase_wins),
+            (engine2_name, self.engine2_phase_wins),
+        ] {
+            println!(
+                "  {:<name_width$} {:>10} {:>10}",
+                name,
+                wins.midgame.to_string().success(),
+                wins.endgame.to_string().warning()
+            );
+        }
+    }
+
+    /// Print the share of each engine's moves in each move time bucket, from
+    /// the fastest to the slowest bucket either engine played a move in.
+    fn print_move_time_distribution(&self, engine1_name: &str, engine2_name: &str) {
+        let times = [&self.engine1_think_time, &self.engine2_think_time];
+        let used = |bucket: &usize| times.iter().any(|time| time.distribution[*bucket] > 0);
+        let buckets = 0..MOVE_TIME_BUCKETS_MS.len() + 1;
+        let (Some(first), Some(last)) = (buckets.clone().find(used), buckets.rev().find(used))
+        else {
+            return;
+        };
+
+        let name_width = engine1_name
+            .chars()
+            .count()
+            .max(engine2_name.chars().count())
+            .max(8);
+        println!("{}", "Move time distribution:".text().bold());
+        println!(
+            "{}",
+            format!(
+                "  {:<12} {:>name_width$} {:>name_width$}",
+                "Move time", engine1_name, engine2_name
+            )
+            .text()
+            .bold()
+        );
+        let format_share = |time: &ThinkTime, bucket: usize| {
+            let share = if time.moves == 0 {
+                0.0
+            } else {
+                f64::from(time.distribution[bucket]) / f64::from(time.moves) * 100.0
+            };
+            format!("{share:.1}%")
+        };
+        for bucket in first..=last {
+            println!(
+                "  {:<12} {:>name_width$} {:>name_width$}",
+                move_time_bucket_label(bucket),
+                format_share(times[0], bucket).info(),
+                format_share(times[1], bucket).info()
+            );
+        }
+    }
+
+    /// Print the openings that went worst for engine1, and how many openings
+    /// it lost or won every game of.
+    fn print_opening_breakdown(&self, engine1_name: &str) {
+        let openings = self.openings_by_engine1_result();
+        let swept = |won: bool| {
+            openings
+                .iter()
+                .filter(|(_, result)| {
+                    let wins = if won {
+                        result.engine1_wins
+                    } else {
+                        result.engine2_wins
+                    };
+                    result.games() >= 2 && wins == result.games()
+                })
+                .count()
+        };
+
+        println!(
+            "{} {} {} {}",
+            format!("Openings for {engine1_name}:").text().bold(),
+            format!("{} lost every game,", swept(false)).failure(),
+            format!("{} won every game,", swept(true)).success(),
+            format!("{} in total", openings.len()).subtext()
+        );
+
+        let rows = &openings[..openings.len().min(MAX_OPENING_ROWS)];
+        let opening_width = rows
+            .iter()
+            .map(|(opening, _)| opening.len())
+            .max()
+            .unwrap_or(0)
+            .max(7);
+        println!(
+            "{}",
+            format!(
+                "  {:<opening_width$} {:>4} {:>4} {:>4} {:>10}",
+                "Opening", "W", "L", "D", "Disc diff"
+            )
+            .text()
+            .bold()
+        );
+        for (opening, result) in rows {
+            println!(
+                "  {:<opening_width$} {:>4} {:>4} {:>4} {:>10}",
+                opening,
+                result.engine1_wins.to_string().success(),
+                result.engine2_wins.to_string().failure(),
+                result.draws.to_string().info(),
+                format!("{:+}", result.total_score).subtext()
+            );
+        }
+    }
+
+    /// Print how often each engine played the other's best move, and how
+    /// closely their scores agree.
+    fn print_agreement(&self, engine1_name: &str, engine2_name: &str) {
+        let agreement = &self.agreement;
+        let format_agreement = |moves: &MoveAgreement| match moves.rate() {
+            Some(rate) => format!("{}/{} ({:.1}%)", moves.matches, moves.moves, rate * 100.0),
+            None => "-".to_string(),
+        };
+
+        println!("{}", "Move agreement:".text().bold());
+        for (mover, analyzer, moves) in [
+            (engine1_name, engine2_name, &agreement.first),
+            (engine2_name, engine1_name, &agreement.second),
+        ] {
+            println!(
+                "  {} {}",
+                format!("{mover} played {analyzer}'s best move:").text(),
+                format_agreement(moves).info()
+            );
+        }
+        if let Some(rate) = agreement.overall_rate() {
+            println!(
+                "  {} {}",
+                "Overall:".text(),
+                format!("{:.1}%", rate * 100.0).info()
+            );
+        }
+
+        match (agreement.correlation(), agreement.mean_abs_diff()) {
+            (Some(correlation), Some(diff)) => println!(
+                "  {} {} {}",
+                "Score correlation:".text(),
+                format!("{correlation:.3}").info(),
+                format!(
+                    "({} positions, mean difference {diff:.2} discs)",
+                    agreement.scored
+                )
+                .subtext()
+            ),
+            _ => println!(
+                "  {} {}",
+                "Score correlation:".text(),
+                "- (needs nr-score from both engines)".subtext()
+            ),
+        }
+    }
+
     fn print_summary(&self) {
         // Calculate pentanomial frequencies
         let freq = self.calculate_pentanomial_frequencies();
@@ -163,7 +585,7 @@ impl MatchStatistics {
         // Calculate and display Elo and LOS
         if !self.paired_results.is_empty() {
             let stats = PentanomialCalculator::calculate(&freq);
-            let los = stats.calculate_los();
+            let los = stats.los;
 
             // Format Elo
             let elo_str = if stats.elo_diff.is_infinite() {
@@ -193,6 +615,21 @@ impl MatchStatistics {
 
             println!("{} {}", "Elo:".text().bold(), elo_str);
 
+            if let Some((lower, upper)) = PentanomialCalculator::bootstrap_interval(&freq) {
+                println!(
+                    "{} {}",
+                    "Elo 95% (bootstrap):".text().bold(),
+                    format!("[{}, {}]", format_elo(lower), format_elo(upper)).subtext()
+                );
+            }
+
+            println!(
+                "{} {} ± {}",
+                "nElo:".text().bold(),
+                format_elo(stats.normalized_elo).text(),
+                format!("{:.1}", stats.normalized_confidence_interval).subtext()
+            );
+
             // Calculate and display expected win rate from Elo
             if !stats.elo_diff.is_infinite() {
                 let win_rate = 1.0 / (1.0 + 10.0_f64.powf(-stats.elo_diff / 400.0));
@@ -230,6 +667,31 @@ impl MatchStatistics {
             println!("{} {}", "LOS:".text().bold(), los_str);
         }
 
+        if let (Some(sprt), Some(llr)) = (self.sprt, self.sprt_llr()) {
+            let (lower, upper) = sprt.bounds();
+            let verdict = match sprt.decision(llr) {
+                SprtDecision::AcceptH1 => "H1 accepted".success().bold(),
+                SprtDecision::AcceptH0 => "H0 accepted".failure().bold(),
+                SprtDecision::Continue => "inconclusive".warning(),
+            };
+            println!(
+                "{} {} {} {} {}",
+                "LLR:".text().bold(),
+                format!("{llr:+.2}").text(),
+                format!("({lower:+.2}, {upper:+.2})").subtext(),
+                format!("[{:.1}, {:.1}]", sprt.elo0, sprt.elo1).subtext(),
+                verdict
+            );
+        }
+
+        if let Some(limit) = self.limit_reached {
+            println!(
+                "{} {}",
+                "Stopped:".text().bold(),
+                limit.description().warning()
+            );
+        }
+
         // Display Disc diff
         let avg_score = if self.total_games() > 0 {
             self.total_score as f64 / self.total_games() as f64
@@ -270,10 +732,98 @@ impl MatchStatistics {
         };
 
         println!("{} {}", "Disc diff:".text().bold(), disc_diff_str);
+
+        if self.engine1_think_time.moves > 0 || self.engine2_think_time.moves > 0 {
+            let format_time =
+                |time: &ThinkTime| format!("{:.0}ms avg, {}ms max", time.average_ms(), time.max_ms);
+            println!(
+                "{} {} / {}",
+                "Move time:".text().bold(),
+                format_time(&self.engine1_think_time).subtext(),
+                format_time(&self.engine2_think_time).subtext()
+            );
+        }
+
+        if self.engine1_time_losses > 0 || self.engine2_time_losses > 0 {
+            println!(
+                "{} {} / {}",
+                "Time losses:".text().bold(),
+                self.engine1_time_losses.to_string().failure(),
+                self.engine2_time_losses.to_string().failure()
+            );
+        }
+
+        let (engine1_violations, engine2_violations) = (
+            self.engine1_think_time.cap_violations,
+            self.engine2_think_time.cap_violations,
+        );
+        if engine1_violations > 0 || engine2_violations > 0 {
+            println!(
+                "{} {} / {}",
+                "Cap violations:".text().bold(),
+                engine1_violations.to_string().warning(),
+                engine2_violations.to_string().warning()
+            );
+        }
+
+        if self.engine1_crashes > 0 || self.engine2_crashes > 0 {
+            println!(
+                "{} {} / {}",
+                "Crashes:".text().bold(),
+                self.engine1_crashes.to_string().failure(),
+                self.engine2_crashes.to_string().failure()
+            );
+        }
+
+        if self.engine1_illegal_moves > 0 || self.engine2_illegal_moves > 0 {
+            println!(
+                "{} {} / {}",
+                "Illegal moves:".text().bold(),
+                self.engine1_illegal_moves.to_string().failure(),
+                self.engine2_illegal_moves.to_string().failure()
+            );
+            for incident in &self.incidents {
+                println!("  {}", incident.subtext());
+            }
+        }
+
+        if let Some(interval) = self.restart_interval
+            && self
+                .restart_cycle_results
+                .iter()
+                .all(|tally| tally.games() > 0)
+        {
+            let first_half = interval.div_ceil(2);
+            let format_tally = |tally: &ResultTally| {
+                format!(
+                    "{:.1}% ({:+.2}/game, {} games)",
+                    tally.engine1_points() / f64::from(tally.games()) * 100.0,
+                    f64::from(tally.total_score) / f64::from(tally.games()),
+                    tally.games()
+                )
+            };
+            println!(
+                "{} {} {} {} {}",
+                "After restart:".text().bold(),
+                format!("games 1-{first_half}").text(),
+                format_tally(&self.restart_cycle_results[0]).subtext(),
+                format!("/ games {}-{interval}", first_half + 1).text(),
+                format_tally(&self.restart_cycle_results[1]).subtext()
+            );
+        }
+
+        if self.adjudicated_games > 0 {
+            println!(
+                "{} {}",
+                "Adjudicated:".text().bold(),
+                format!("{} of {} games", self.adjudicated_games, self.games_played).subtext()
+            );
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MatchWinner {
     Engine1,
     Engine2,
@@ -319,29 +869,12 @@ pub struct PentanomialFrequencies {
 pub struct PentanomialStats {
     pub elo_diff: f64,
     pub confidence_interval: f64,
-}
-
-impl PentanomialStats {
-    pub fn calculate_los(&self) -> f64 {
-        if self.elo_diff.is_infinite() {
-            return if self.elo_diff > 0.0 { 1.0 } else { 0.0 };
-        }
-
-        if self.confidence_interval == 0.0 {
-            // Zero variance: all pairs identical. LOS is determined by sign of elo_diff.
-            return if self.elo_diff > 0.0 {
-                1.0
-            } else if self.elo_diff < 0.0 {
-                0.0
-            } else {
-                0.5
-            };
-        }
-
-        // Using normal approximation
-        let z = self.elo_diff / (self.confidence_interval / 1.96);
-        0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
-    }
+    /// Elo difference per standard deviation of the pair scores, comparable
+    /// across time controls and opening sets
+    pub normalized_elo: f64,
+    pub normalized_confidence_interval: f64,
+    /// Likelihood of superiority of engine1
+    pub los: f64,
 }
 
 pub struct PentanomialCalculator;
@@ -354,6 +887,9 @@ impl PentanomialCalculator {
             return PentanomialStats {
                 elo_diff: 0.0,
                 confidence_interval: 0.0,
+                normalized_elo: 0.0,
+                normalized_confidence_interval: 0.0,
+                los: 0.5,
             };
         }
 
@@ -370,8 +906,8 @@ impl PentanomialCalculator {
             0.0 * p0 + 0.5 * p_half + 1.0 * p1_dd + 1.0 * p1_wl + 1.5 * p_three_half + 2.0 * p2;
         let mu = score / 2.0; // Per game
 
-        // Calculate pentanomial variance per pair using true 5-nomial model
-        // Note: DD and WL both give 1 point but have different variance contributions
+        // Calculate pentanomial variance per pair. DD and WL both score 1 point, so
+        // a color-balanced opening adds no variance, unlike in a per-game model
         let e_t_squared =
             0.0 * p0 + 0.25 * p_half + 1.0 * p1_dd + 1.0 * p1_wl + 2.25 * p_three_half + 4.0 * p2;
         let var_pair = e_t_squared - score * score;
@@ -388,21 +924,216 @@ impl PentanomialCalculator {
         };
 
         // Calculate confidence interval
+        let se_mu = (var_pair / 4.0 / n_pairs).sqrt();
         let se_elo = if mu == 0.0 || mu == 1.0 {
             f64::INFINITY
         } else {
-            let se_mu = (var_pair / 4.0 / n_pairs).sqrt();
             (ELO_K / std::f64::consts::LN_10) * se_mu / (mu * (1.0 - mu))
         };
 
         let z_score = 1.96;
         let confidence_interval = z_score * se_elo;
 
+        // Normalized Elo divides the score excess by the per-game standard
+        // deviation, taken as sqrt(2) times that of the per-game pair score
+        let sigma_game = (var_pair / 4.0).sqrt() * std::f64::consts::SQRT_2;
+        let normalized_elo = if sigma_game > 0.0 {
+            (mu - 0.5) / sigma_game * NELO_PER_T
+        } else {
+            signed_infinity(mu - 0.5)
+        };
+        let normalized_confidence_interval = z_score * NELO_PER_T / (2.0 * n_pairs).sqrt();
+
+        // LOS is computed on the score scale, which stays well-defined at
+        // extreme win rates where the Elo scale diverges
+        let los = if se_mu > 0.0 {
+            let z = (mu - 0.5) / se_mu;
+            0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+        } else if mu > 0.5 {
+            1.0
+        } else if mu < 0.5 {
+            0.0
+        } else {
+            0.5
+        };
+
         PentanomialStats {
             elo_diff,
             confidence_interval,
+            normalized_elo,
+            normalized_confidence_interval,
+            los,
         }
     }
+
+    /// Bootstrap 95% confidence interval of the Elo difference, resampling
+    /// game pairs with replacement. `None` with fewer than two pairs.
+    ///
+    /// Unlike the normal approximation, the interval stays meaningful at
+    /// extreme win rates, where its bounds may be infinite.
+    pub fn bootstrap_interval(freq: &PentanomialFrequencies) -> Option<(f64, f64)> {
+        let counts = [freq.ll, freq.ld, freq.dd, freq.wl, freq.wd, freq.ww];
+        let n_pairs: u32 = counts.iter().sum();
+        if n_pairs < 2 {
+            return None;
+        }
+
+        let mut rng = SmallRng::seed_from_u64(BOOTSTRAP_SEED);
+        let mut elos: Vec<f64> = (0..BOOTSTRAP_SAMPLES)
+            .map(|_| {
+                let mut sample = [0u32; 6];
+                for _ in 0..n_pairs {
+                    let mut pick = rng.random_range(0..n_pairs);
+                    let category = counts
+                        .iter()
+                        .position(|&count| {
+                            let found = pick < count;
+                            pick = pick.saturating_sub(count);
+                            found
+                        })
+                        .unwrap_or(counts.len() - 1);
+                    sample[category] += 1;
+                }
+                let [ll, ld, dd, wl, wd, ww] = sample;
+                Self::calculate(&PentanomialFrequencies {
+                    ll,
+                    ld,
+                    dd,
+                    wl,
+                    wd,
+                    ww,
+                })
+                .elo_diff
+            })
+            .collect();
+        elos.sort_by(f64::total_cmp);
+
+        let percentile = |p: f64| elos[((elos.len() - 1) as f64 * p).round() as usize];
+        Some((percentile(0.025), percentile(0.975)))
+    }
+}
+
+/// Format an Elo value with its sign, showing infinite values as ±∞.
+fn format_elo(elo: f64) -> String {
+    if elo.is_infinite() {
+        format!("{}∞", if elo > 0.0 { "+" } else { "-" })
+    } else {
+        format!("{elo:+.1}")
+    }
+}
+
+/// Label a bucket of [`MOVE_TIME_BUCKETS_MS`] by its range, e.g. `250ms-500ms`.
+fn move_time_bucket_label(bucket: usize) -> String {
+    let format_bound = |ms: u64| {
+        if ms >= 1000 {
+            format!("{}s", ms / 1000)
+        } else {
+            format!("{ms}ms")
+        }
+    };
+    match (
+        bucket.checked_sub(1).map(|i| MOVE_TIME_BUCKETS_MS[i]),
+        MOVE_TIME_BUCKETS_MS.get(bucket),
+    ) {
+        (None, Some(&upper)) => format!("<{}", format_bound(upper)),
+        (Some(lower), Some(&upper)) => format!("{}-{}", format_bound(lower), format_bound(upper)),
+        (Some(lower), None) => format!("≥{}", format_bound(lower)),
+        (None, None) => unreachable!("no move time buckets"),
+    }
+}
+
+fn signed_infinity(x: f64) -> f64 {
+    if x > 0.0 {
+        f64::INFINITY
+    } else if x < 0.0 {
+        f64::NEG_INFINITY
+    } else {
+        0.0
+    }
+}
+
+/// Outcome of a sequential probability ratio test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtDecision {
+    /// Neither bound reached yet
+    Continue,
+    /// The Elo difference is at most `elo0`
+    AcceptH0,
+    /// The Elo difference is at least `elo1`
+    AcceptH1,
+}
+
+/// Sequential probability ratio test on the Elo difference of engine1.
+///
+/// Tests H0: elo = `elo0` against H1: elo = `elo1` with false positive rate
+/// `alpha` and false negative rate `beta`, using the normal approximation of
+/// the log-likelihood ratio over the pentanomial pair scores.
+#[derive(Debug, Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    /// Lower and upper LLR bounds at which H0 or H1 is accepted.
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Log-likelihood ratio of H1 against H0 for the given pair results.
+    pub fn llr(&self, freq: &PentanomialFrequencies) -> f64 {
+        // Per-pair score normalized to 0..1
+        let counts = [
+            (0.0, freq.ll),
+            (0.25, freq.ld),
+            (0.5, freq.dd + freq.wl),
+            (0.75, freq.wd),
+            (1.0, freq.ww),
+        ];
+        let n_pairs: u32 = counts.iter().map(|&(_, n)| n).sum();
+        if n_pairs < 2 {
+            return 0.0;
+        }
+
+        let n = n_pairs as f64;
+        let mean = counts.iter().map(|&(x, k)| x * k as f64).sum::<f64>() / n;
+
+        // Variance of the pair scores with a pseudo-count on every outcome
+        let weights = counts.map(|(x, k)| (x, k as f64 + SPRT_PSEUDO_COUNT));
+        let total: f64 = weights.iter().map(|&(_, w)| w).sum();
+        let regularized_mean = weights.iter().map(|&(x, w)| x * w).sum::<f64>() / total;
+        let var = weights
+            .iter()
+            .map(|&(x, w)| (x - regularized_mean).powi(2) * w)
+            .sum::<f64>()
+            / total;
+
+        let s0 = elo_to_score(self.elo0);
+        let s1 = elo_to_score(self.elo1);
+        n * (s1 - s0) * (2.0 * mean - s0 - s1) / (2.0 * var)
+    }
+
+    /// Decide the test for the given LLR.
+    pub fn decision(&self, llr: f64) -> SprtDecision {
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtDecision::AcceptH1
+        } else if llr <= lower {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        }
+    }
+}
+
+/// Expected score per game for the given Elo difference.
+fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10.0_f64.powf(-elo / ELO_K))
 }
 
 // Error function approximation for LOS calculation
@@ -423,3 +1154,287 @@ fn erf(x: f64) -> f64 {
 
     sign * y
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPRT: Sprt = Sprt {
+        elo0: 0.0,
+        elo1: 10.0,
+        alpha: 0.05,
+        beta: 0.05,
+    };
+
+    #[test]
+    fn test_pentanomial_even_match() {
+        let freq = PentanomialFrequencies {
+            ld: 10,
+            dd: 5,
+            wl: 20,
+            wd: 10,
+            ..Default::default()
+        };
+        let stats = PentanomialCalculator::calculate(&freq);
+        assert_eq!(stats.elo_diff, 0.0);
+        assert!(stats.confidence_interval > 0.0);
+        assert!((stats.los - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pentanomial_ignores_color_balanced_pairs() {
+        // Every opening is won by whichever engine plays the favored color, so
+        // the pairs carry no information about strength and no variance.
+        let freq = PentanomialFrequencies {
+            wl: 50,
+            ..Default::default()
+        };
+        let stats = PentanomialCalculator::calculate(&freq);
+        assert_eq!(stats.elo_diff, 0.0);
+        assert_eq!(stats.confidence_interval, 0.0);
+    }
+
+    #[test]
+    fn test_pentanomial_one_sided_match() {
+        let freq = PentanomialFrequencies {
+            ww: 3,
+            ..Default::default()
+        };
+        let stats = PentanomialCalculator::calculate(&freq);
+        assert!(stats.elo_diff.is_infinite() && stats.elo_diff > 0.0);
+        assert_eq!(stats.los, 1.0);
+    }
+
+    #[test]
+    fn test_pentanomial_normalized_elo_and_los() {
+        let freq = PentanomialFrequencies {
+            ld: 10,
+            wl: 60,
+            wd: 30,
+            ..Default::default()
+        };
+        let stats = PentanomialCalculator::calculate(&freq);
+        // mu = 0.55, per-pair score variance 0.0225 on the 0..1 scale
+        let expected = 0.05 / (0.0225f64.sqrt() * std::f64::consts::SQRT_2) * NELO_PER_T;
+        assert!((stats.normalized_elo - expected).abs() < 1e-9);
+        assert!(
+            (stats.normalized_confidence_interval - 1.96 * NELO_PER_T / 200f64.sqrt()).abs() < 1e-9
+        );
+        assert!(stats.los > 0.99);
+
+        // A nearly one-sided match still has a positive normalized Elo
+        let sweep = PentanomialCalculator::calculate(&PentanomialFrequencies {
+            ww: 9,
+            wd: 1,
+            ..Default::default()
+        });
+        assert!(!sweep.elo_diff.is_infinite() && sweep.normalized_elo > 0.0);
+        assert!(sweep.los > 0.99);
+    }
+
+    #[test]
+    fn test_bootstrap_interval() {
+        assert!(
+            PentanomialCalculator::bootstrap_interval(&PentanomialFrequencies::default()).is_none()
+        );
+
+        let freq = PentanomialFrequencies {
+            ld: 10,
+            dd: 5,
+            wl: 50,
+            wd: 25,
+            ww: 10,
+            ..Default::default()
+        };
+        let stats = PentanomialCalculator::calculate(&freq);
+        let (lower, upper) = PentanomialCalculator::bootstrap_interval(&freq).unwrap();
+        assert!(lower < stats.elo_diff && stats.elo_diff < upper);
+        // Close to the normal approximation away from extreme win rates
+        assert!(
+            (upper - lower - 2.0 * stats.confidence_interval).abs()
+                < 0.2 * stats.confidence_interval
+        );
+        assert_eq!(
+            PentanomialCalculator::bootstrap_interval(&freq),
+            Some((lower, upper))
+        );
+
+        // Every pair won: the interval is infinite rather than ±0
+        let sweep = PentanomialFrequencies {
+            ww: 20,
+            ..Default::default()
+        };
+        let (lower, upper) = PentanomialCalculator::bootstrap_interval(&sweep).unwrap();
+        assert!(lower.is_infinite() && upper.is_infinite() && lower > 0.0);
+    }
+
+    #[test]
+    fn test_pentanomial_frequencies_from_pairs() {
+        let mut stats = MatchStatistics::new();
+        stats.add_paired_result((MatchWinner::Engine1, 4), (MatchWinner::Engine2, -2));
+        stats.add_paired_result((MatchWinner::Draw, 0), (MatchWinner::Engine1, 6));
+        stats.add_paired_result((MatchWinner::Engine2, -8), (MatchWinner::Engine2, -2));
+        let freq = stats.calculate_pentanomial_frequencies();
+        assert_eq!((freq.ll, freq.ld, freq.dd), (1, 0, 0));
+        assert_eq!((freq.wl, freq.wd, freq.ww), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_move_time_bucket_label() {
+        assert_eq!(move_time_bucket_label(0), "<10ms");
+        assert_eq!(move_time_bucket_label(4), "250ms-500ms");
+        assert_eq!(move_time_bucket_label(5), "500ms-1s");
+        assert_eq!(move_time_bucket_label(MOVE_TIME_BUCKETS_MS.len()), "≥10s");
+    }
+
+    #[test]
+    fn test_time_losses_are_charged_to_the_loser() {
+        let mut stats = MatchStatistics::new();
+        stats.add_time_loss(MatchWinner::Engine1);
+        stats.add_time_loss(MatchWinner::Engine1);
+        stats.add_time_loss(MatchWinner::Engine2);
+        assert_eq!(stats.engine1_time_losses, 1);
+        assert_eq!(stats.engine2_time_losses, 2);
+    }
+
+    #[test]
+    fn test_crashes_are_charged_to_the_loser() {
+        let mut stats = MatchStatistics::new();
+        stats.add_crash(MatchWinner::Engine2);
+        assert_eq!((stats.engine1_crashes, stats.engine2_crashes), (1, 0));
+        assert_eq!(stats.engine1_time_losses, 0);
+    }
+
+    #[test]
+    fn test_illegal_moves_are_recorded() {
+        let mut stats = MatchStatistics::new();
+        stats.add_illegal_move(MatchWinner::Engine1, Some("Game 3".to_string()));
+        stats.add_illegal_move(MatchWinner::Engine1, None);
+        assert_eq!(
+            (stats.engine1_illegal_moves, stats.engine2_illegal_moves),
+            (0, 2)
+        );
+        assert_eq!(stats.incidents, ["Game 3"]);
+    }
+
+    #[test]
+    fn test_win_phases_are_credited_to_the_winner() {
+        let mut stats = MatchStatistics::new();
+        stats.add_win_phase(MatchWinner::Engine1, WinPhase::Midgame);
+        stats.add_win_phase(MatchWinner::Engine2, WinPhase::Endgame);
+        stats.add_win_phase(MatchWinner::Engine2, WinPhase::Endgame);
+        stats.add_win_phase(MatchWinner::Draw, WinPhase::Midgame);
+        assert_eq!(
+            (
+                stats.engine1_phase_wins.midgame,
+                stats.engine1_phase_wins.endgame
+            ),
+            (1, 0)
+        );
+        assert_eq!(
+            (
+                stats.engine2_phase_wins.midgame,
+                stats.engine2_phase_wins.endgame
+            ),
+            (0, 2)
+        );
+    }
+
+    #[test]
+    fn test_restart_cycle_halves() {
+        let mut stats = MatchStatistics::new();
+        stats.add_restart_cycle_result(MatchWinner::Engine1, 4, 0);
+        assert_eq!(stats.restart_cycle_results[0].games(), 0);
+
+        stats.restart_interval = Some(5);
+        for games_since_restart in 0..5 {
+            stats.add_restart_cycle_result(MatchWinner::Engine2, -2, games_since_restart);
+        }
+        let [first, second] = stats.restart_cycle_results;
+        assert_eq!((first.games(), second.games()), (3, 2));
+        assert_eq!(second.total_score, -4);
+    }
+
+    #[test]
+    fn test_openings_are_ordered_worst_first() {
+        let mut stats = MatchStatistics::new();
+        stats.add_result(MatchWinner::Engine1, 8, "f5d6".to_string(), true);
+        stats.add_result(MatchWinner::Draw, 0, "f5d6".to_string(), false);
+        stats.add_result(MatchWinner::Engine2, -4, "f5f6".to_string(), true);
+        stats.add_result(MatchWinner::Engine2, -20, "f5f6".to_string(), false);
+        stats.add_result(MatchWinner::Engine1, 2, "f5f4".to_string(), true);
+        stats.add_result(MatchWinner::Engine2, -2, "f5f4".to_string(), false);
+
+        let openings: Vec<&str> = stats
+            .openings_by_engine1_result()
+            .into_iter()
+            .map(|(opening, _)| opening)
+            .collect();
+        assert_eq!(openings, ["f5f6", "f5f4", "f5d6"]);
+
+        let f5f6 = stats.opening_results["f5f6"];
+        assert_eq!((f5f6.engine2_wins, f5f6.total_score), (2, -24));
+    }
+
+    #[test]
+    fn test_sprt_bounds() {
+        let (lower, upper) = SPRT.bounds();
+        assert!((lower + 2.944).abs() < 1e-3);
+        assert!((upper - 2.944).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sprt_llr_follows_results() {
+        let even = PentanomialFrequencies {
+            ld: 100,
+            dd: 50,
+            wl: 200,
+            wd: 100,
+            ..Default::default()
+        };
+        assert!(SPRT.llr(&even) < 0.0);
+
+        let stronger = PentanomialFrequencies {
+            ld: 50,
+            wl: 200,
+            wd: 150,
+            ww: 20,
+            ..Default::default()
+        };
+        let llr = SPRT.llr(&stronger);
+        assert!(llr > 0.0);
+        assert_eq!(SPRT.decision(llr), SprtDecision::AcceptH1);
+    }
+
+    #[test]
+    fn test_sprt_llr_one_sided_results() {
+        // Identical pair results have no sample variance, but still decide
+        // the test once there are enough of them
+        let all_ww = |ww| PentanomialFrequencies {
+            ww,
+            ..Default::default()
+        };
+        assert_eq!(SPRT.decision(SPRT.llr(&all_ww(2))), SprtDecision::Continue);
+        let llr = SPRT.llr(&all_ww(100));
+        assert!(llr.is_finite());
+        assert_eq!(SPRT.decision(llr), SprtDecision::AcceptH1);
+
+        let all_ll = PentanomialFrequencies {
+            ll: 100,
+            ..Default::default()
+        };
+        assert_eq!(SPRT.decision(SPRT.llr(&all_ll)), SprtDecision::AcceptH0);
+
+        let all_dd = PentanomialFrequencies {
+            dd: 200,
+            ..Default::default()
+        };
+        assert!(SPRT.llr(&all_dd) < 0.0);
+    }
+
+    #[test]
+    fn test_sprt_llr_without_data() {
+        assert_eq!(SPRT.llr(&PentanomialFrequencies::default()), 0.0);
+        assert_eq!(SPRT.decision(0.0), SprtDecision::Continue);
+    }
+}
@@ -5,6 +5,17 @@
 
 use std::time::Instant;
 
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Upper bounds in milliseconds of the buckets of the per-move time distribution.
+///
+/// Moves taking at least the last bound fall into an extra, open-ended bucket.
+pub const MOVE_TIME_BUCKETS_MS: [u64; 9] = [10, 50, 100, 250, 500, 1_000, 2_000, 5_000, 10_000];
+
+/// Number of buckets of the per-move time distribution.
+pub const MOVE_TIME_BUCKET_COUNT: usize = MOVE_TIME_BUCKETS_MS.len() + 1;
+
 /// Time control mode, automatically determined from GTP time_settings parameters.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TimeControlMode {
@@ -21,6 +32,81 @@ enum TimeControlMode {
     JapaneseByo,
 }
 
+/// Wall time one side spent generating its moves in a game or match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThinkTime {
+    /// Total time in milliseconds
+    pub total_ms: u64,
+    /// Longest single move in milliseconds
+    pub max_ms: u64,
+    /// Number of moves generated
+    pub moves: u32,
+    /// Number of moves per bucket of [`MOVE_TIME_BUCKETS_MS`]
+    #[serde(default)]
+    pub distribution: [u32; MOVE_TIME_BUCKET_COUNT],
+    /// Number of moves that exceeded the per-move time cap
+    #[serde(default)]
+    pub cap_violations: u32,
+}
+
+impl ThinkTime {
+    /// Records one generated move.
+    pub fn record(&mut self, elapsed_ms: u64) {
+        self.total_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+        self.moves += 1;
+        self.distribution[Self::bucket(elapsed_ms)] += 1;
+    }
+
+    /// Adds the moves of `other`.
+    pub fn merge(&mut self, other: &ThinkTime) {
+        self.total_ms += other.total_ms;
+        self.max_ms = self.max_ms.max(other.max_ms);
+        self.moves += other.moves;
+        for (count, other) in self.distribution.iter_mut().zip(other.distribution) {
+            *count += other;
+        }
+        self.cap_violations += other.cap_violations;
+    }
+
+    /// Returns the index of the distribution bucket of a move taking `elapsed_ms`.
+    pub fn bucket(elapsed_ms: u64) -> usize {
+        MOVE_TIME_BUCKETS_MS
+            .iter()
+            .position(|&bound| elapsed_ms < bound)
+            .unwrap_or(MOVE_TIME_BUCKETS_MS.len())
+    }
+
+    /// Returns the average time per move in milliseconds.
+    pub fn average_ms(&self) -> f64 {
+        if self.moves == 0 {
+            0.0
+        } else {
+            self.total_ms as f64 / self.moves as f64
+        }
+    }
+}
+
+/// What happens when a move exceeds the per-move time cap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapAction {
+    /// The mover loses the game on time
+    #[default]
+    Forfeit,
+    /// The move is counted as a violation and the game goes on
+    Log,
+}
+
+/// Hard limit on the wall time of a single move, independent of the time control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveTimeCap {
+    /// Longest allowed move in milliseconds
+    pub limit_ms: u64,
+    /// What happens when a move takes longer
+    pub action: CapAction,
+}
+
 /// Tracks time for both players during a game.
 #[derive(Debug)]
 pub struct TimeTracker {
@@ -50,6 +136,10 @@ pub struct TimeTracker {
     white_byo_time_used_ms: u64,
     /// Start time of current move
     move_start: Option<Instant>,
+    /// Wall time of the last completed move (ms)
+    last_move_ms: u64,
+    /// Hard limit on the wall time of a single move
+    move_cap: Option<MoveTimeCap>,
 }
 
 impl TimeTracker {
@@ -92,9 +182,17 @@ impl TimeTracker {
             black_byo_time_used_ms: 0,
             white_byo_time_used_ms: 0,
             move_start: None,
+            last_move_ms: 0,
+            move_cap: None,
         }
     }
 
+    /// Sets the per-move time cap, enforced in addition to the time control.
+    pub fn with_move_cap(mut self, move_cap: Option<MoveTimeCap>) -> Self {
+        self.move_cap = move_cap;
+        self
+    }
+
     /// Returns the initial (time_ms, in_byoyomi) state for a player.
     fn initial_player_state(mode: TimeControlMode, main_time_ms: u64) -> (u64, bool) {
         match mode {
@@ -217,9 +315,22 @@ impl TimeTracker {
             .map(|start| start.elapsed().as_millis() as u64)
             .unwrap_or(0);
         self.move_start = None;
+        self.last_move_ms = elapsed_ms;
         self.apply_elapsed(is_black, elapsed_ms)
     }
 
+    /// Returns the wall time of the last move ended with [`Self::end_move`].
+    pub fn last_move_ms(&self) -> u64 {
+        self.last_move_ms
+    }
+
+    /// Returns the action to take if the last move exceeded the per-move time cap.
+    pub fn cap_exceeded(&self) -> Option<CapAction> {
+        self.move_cap
+            .filter(|cap| self.last_move_ms > cap.limit_ms)
+            .map(|cap| cap.action)
+    }
+
     /// Apply elapsed time and update the player's remaining time.
     ///
     /// This is the core time control logic, separated from clock measurement
@@ -357,6 +468,7 @@ impl TimeTracker {
         self.black_byo_time_used_ms = 0;
         self.white_byo_time_used_ms = 0;
         self.move_start = None;
+        self.last_move_ms = 0;
     }
 }
 
@@ -504,6 +616,50 @@ mod tests {
         assert_eq!(tracker.white_time_left().0, 300); // white unchanged
     }
 
+    #[test]
+    fn test_think_time() {
+        let mut time = ThinkTime::default();
+        assert_eq!(time.average_ms(), 0.0);
+        time.record(100);
+        time.record(300);
+
+        let mut total = ThinkTime::default();
+        total.merge(&time);
+        total.record(50);
+        assert_eq!(total.moves, 3);
+        assert_eq!(total.max_ms, 300);
+        assert_eq!(total.average_ms(), 150.0);
+        assert_eq!(total.distribution[ThinkTime::bucket(50)], 1);
+        assert_eq!(total.distribution[ThinkTime::bucket(100)], 1);
+        assert_eq!(total.distribution[ThinkTime::bucket(300)], 1);
+    }
+
+    #[test]
+    fn test_move_time_buckets() {
+        assert_eq!(ThinkTime::bucket(0), 0);
+        assert_eq!(ThinkTime::bucket(9), 0);
+        assert_eq!(ThinkTime::bucket(10), 1);
+        assert_eq!(ThinkTime::bucket(999), 5);
+        assert_eq!(ThinkTime::bucket(10_000), MOVE_TIME_BUCKET_COUNT - 1);
+        assert_eq!(ThinkTime::bucket(u64::MAX), MOVE_TIME_BUCKET_COUNT - 1);
+    }
+
+    #[test]
+    fn test_move_time_cap() {
+        let mut tracker = TimeTracker::new(0, 0, 0);
+        tracker.last_move_ms = 500;
+        assert_eq!(tracker.cap_exceeded(), None);
+
+        let cap = MoveTimeCap {
+            limit_ms: 500,
+            action: CapAction::Log,
+        };
+        let mut tracker = tracker.with_move_cap(Some(cap));
+        assert_eq!(tracker.cap_exceeded(), None);
+        tracker.last_move_ms = 501;
+        assert_eq!(tracker.cap_exceeded(), Some(CapAction::Log));
+    }
+
     #[test]
     fn test_reset() {
         let mut tracker = TimeTracker::new(300, 5, 0);
@@ -0,0 +1,336 @@
+//! Tournaments between more than two engines.
+//!
+//! A tournament is played as a series of ordinary two-engine matches, one per
+//! pairing, each over the full opening set. Pairings either bring every
+//! engine against every other (round-robin) or the first engine against each
+//! of the others (gauntlet). The final report combines the matches into a
+//! cross-table with per-pairing Elo estimates.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use colored::*;
+
+use crate::colors::ThemeColor;
+use crate::config::{Config, SearchLimits};
+use crate::engine::EngineProtocol;
+use crate::error::{MatchRunnerError, Result};
+use crate::match_runner::{MatchReport, MatchRunner};
+use crate::statistics::PentanomialCalculator;
+
+/// How the engines of a tournament are paired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TournamentMode {
+    /// Every engine plays every other engine
+    RoundRobin,
+    /// The first engine plays each of the others
+    Gauntlet,
+}
+
+/// A tournament participant.
+#[derive(Debug, Clone)]
+struct Participant {
+    command: String,
+    working_dir: Option<PathBuf>,
+    protocol: EngineProtocol,
+    init: Vec<String>,
+    limits: SearchLimits,
+}
+
+/// Outcome of a tournament.
+#[derive(Debug, Clone)]
+pub struct TournamentReport {
+    /// Name of each engine, in command-line order
+    pub engine_names: Vec<String>,
+    /// Indices of the two engines of each pairing and the match they played
+    pub pairings: Vec<(usize, usize, MatchReport)>,
+}
+
+/// Run a tournament between all engines of `config`.
+///
+/// Each pairing is played as a separate match with the settings of `config`.
+/// Checkpoint, game and results files get the pairing's engine numbers
+/// inserted before their extension, e.g. `games.1-3.ggf`.
+///
+/// # Errors
+///
+/// Returns an error if an SPRT is requested, which needs exactly two
+/// engines, or if any match fails.
+pub fn run_tournament(config: &Config) -> Result<TournamentReport> {
+    if config.sprt()?.is_some() {
+        return Err(MatchRunnerError::Config(
+            "SPRT requires exactly two engines.".to_string(),
+        ));
+    }
+
+    let participants = participants(config);
+    let mut engine_names: Vec<Option<String>> = vec![None; participants.len()];
+    let mut pairings = Vec::new();
+
+    for (first, second) in pairings_for(config.tournament, participants.len()) {
+        let pairing_config = pairing_config(config, &participants, first, second);
+        let report = MatchRunner::new().run_match(&pairing_config)?;
+
+        engine_names[first].get_or_insert_with(|| report.engine1_name.clone());
+        engine_names[second].get_or_insert_with(|| report.engine2_name.clone());
+        pairings.push((first, second, report));
+    }
+
+    Ok(TournamentReport {
+        engine_names: engine_names
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| name.unwrap_or_else(|| format!("Engine {}", idx + 1)))
+            .collect(),
+        pairings,
+    })
+}
+
+fn participants(config: &Config) -> Vec<Participant> {
+    let mut participants = vec![
+        Participant {
+            command: config.engine1.clone(),
+            working_dir: config.engine1_working_dir.clone(),
+            protocol: config.engine1_protocol,
+            init: config.engine1_init.clone(),
+            limits: config.engine1_limits(),
+        },
+        Participant {
+            command: config.engine2.clone(),
+            working_dir: config.engine2_working_dir.clone(),
+            protocol: config.engine2_protocol,
+            init: config.engine2_init.clone(),
+            limits: config.engine2_limits(),
+        },
+    ];
+    participants.extend(config.engines.iter().map(|command| Participant {
+        command: command.clone(),
+        working_dir: None,
+        protocol: EngineProtocol::Gtp,
+        init: Vec::new(),
+        limits: SearchLimits {
+            nodes: config.nodes,
+            ..SearchLimits::default()
+        },
+    }));
+    participants
+}
+
+/// Engine index pairs played in a tournament of `engine_count` engines.
+fn pairings_for(mode: TournamentMode, engine_count: usize) -> Vec<(usize, usize)> {
+    match mode {
+        TournamentMode::RoundRobin => (0..engine_count)
+            .flat_map(|first| (first + 1..engine_count).map(move |second| (first, second)))
+            .collect(),
+        TournamentMode::Gauntlet => (1..engine_count).map(|second| (0, second)).collect(),
+    }
+}
+
+/// Configuration of the match between two tournament participants.
+fn pairing_config(
+    config: &Config,
+    participants: &[Participant],
+    first: usize,
+    second: usize,
+) -> Config {
+    let suffix = format!("{}-{}", first + 1, second + 1);
+    Config {
+        engine1: participants[first].command.clone(),
+        engine1_working_dir: participants[first].working_dir.clone(),
+        engine1_protocol: participants[first].protocol,
+        engine1_init: participants[first].init.clone(),
+        engine1_depth: participants[first].limits.depth,
+        engine1_nodes: participants[first].limits.nodes,
+        engine2: participants[second].command.clone(),
+        engine2_working_dir: participants[second].working_dir.clone(),
+        engine2_protocol: participants[second].protocol,
+        engine2_init: participants[second].init.clone(),
+        engine2_depth: participants[second].limits.depth,
+        engine2_nodes: participants[second].limits.nodes,
+        engines: Vec::new(),
+        checkpoint: config
+            .checkpoint
+            .as_deref()
+            .map(|path| pairing_path(path, &suffix)),
+        games_file: config
+            .games_file
+            .as_deref()
+            .map(|path| pairing_path(path, &suffix)),
+        results_file: config
+            .results_file
+            .as_deref()
+            .map(|path| pairing_path(path, &suffix)),
+        ..config.clone()
+    }
+}
+
+/// Insert `suffix` before the extension of `path`.
+fn pairing_path(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!("{stem}.{suffix}.{}", ext.to_string_lossy()),
+        None => format!("{stem}.{suffix}"),
+    };
+    path.with_file_name(name)
+}
+
+impl TournamentReport {
+    /// Score of engine `row` against engine `col` as (points, games), from
+    /// `row`'s perspective, if they played.
+    pub fn score(&self, row: usize, col: usize) -> Option<(f64, u32)> {
+        self.pairings.iter().find_map(|(first, second, report)| {
+            let stats = &report.statistics;
+            let draws = f64::from(stats.draws) * 0.5;
+            if (*first, *second) == (row, col) {
+                Some((f64::from(stats.engine1_wins) + draws, stats.games_played))
+            } else if (*first, *second) == (col, row) {
+                Some((f64::from(stats.engine2_wins) + draws, stats.games_played))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Total points and games of engine `idx` over the tournament.
+    pub fn total_score(&self, idx: usize) -> (f64, u32) {
+        (0..self.engine_names.len())
+            .filter_map(|opponent| self.score(idx, opponent))
+            .fold((0.0, 0), |(points, games), (p, g)| (points + p, games + g))
+    }
+
+    /// Print the cross-table and per-pairing results to stdout.
+    pub fn print(&self) -> io::Result<()> {
+        let n = self.engine_names.len();
+
+        println!("\r\x1B[2K");
+        println!("{}", "═".repeat(80).info().bold());
+        println!("{:^80}", "Tournament Results".primary().bold());
+        println!("{}", "═".repeat(80).info().bold());
+        println!();
+
+        // Cross-table, rows ranked by total points
+        let mut ranking: Vec<usize> = (0..n).collect();
+        ranking.sort_by(|&a, &b| self.total_score(b).0.total_cmp(&self.total_score(a).0));
+
+        let name_width = self
+            .engine_names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let mut header = format!("{:>3} {:<name_width$} {:>12}", "#", "Engine", "Score");
+        for col in 0..n {
+            header.push_str(&format!(" {:>7}", col + 1));
+        }
+        println!("{}", header.text().bold());
+
+        for &row in &ranking {
+            let (points, games) = self.total_score(row);
+            let mut line = format!(
+                "{:>3} {:<name_width$} {:>12}",
+                row + 1,
+                self.engine_names[row],
+                format!("{points:.1}/{games}")
+            );
+            for col in 0..n {
+                let cell = match self.score(row, col) {
+                    Some((points, games)) if games > 0 => {
+                        format!("{:.1}%", points / f64::from(games) * 100.0)
+                    }
+                    _ => "-".to_string(),
+                };
+                line.push_str(&format!(" {cell:>7}"));
+            }
+            println!("{}", line.text());
+        }
+        println!();
+
+        for (first, second, report) in &self.pairings {
+            let stats = &report.statistics;
+            let elo = PentanomialCalculator::calculate(&stats.calculate_pentanomial_frequencies());
+            let elo_str = if stats.paired_results.is_empty() {
+                "-".to_string()
+            } else if elo.elo_diff.is_infinite() {
+                format!("{}∞", if elo.elo_diff > 0.0 { "+" } else { "-" })
+            } else {
+                format!("{:+.1} ± {:.1}", elo.elo_diff, elo.confidence_interval)
+            };
+            println!(
+                "{} {} {} {}",
+                format!(
+                    "{} vs {}:",
+                    self.engine_names[*first], self.engine_names[*second]
+                )
+                .text()
+                .bold(),
+                format!(
+                    "W: {} L: {} D: {}",
+                    stats.engine1_wins, stats.engine2_wins, stats.draws
+                )
+                .subtext(),
+                "Elo:".text().bold(),
+                elo_str.warning()
+            );
+        }
+        println!();
+        println!("{}", "═".repeat(80).info().bold());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statistics::{MatchStatistics, MatchWinner};
+
+    #[test]
+    fn test_pairings() {
+        assert_eq!(
+            pairings_for(TournamentMode::RoundRobin, 4),
+            vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+        );
+        assert_eq!(
+            pairings_for(TournamentMode::Gauntlet, 4),
+            vec![(0, 1), (0, 2), (0, 3)]
+        );
+    }
+
+    #[test]
+    fn test_pairing_path() {
+        assert_eq!(
+            pairing_path(Path::new("out/games.ggf"), "1-3"),
+            PathBuf::from("out/games.1-3.ggf")
+        );
+        assert_eq!(
+            pairing_path(Path::new("match"), "2-3"),
+            PathBuf::from("match.2-3")
+        );
+    }
+
+    #[test]
+    fn test_cross_table_scores() {
+        let mut statistics = MatchStatistics::new();
+        statistics.add_result(MatchWinner::Engine1, 10, "f5".to_string(), true);
+        statistics.add_result(MatchWinner::Draw, 0, "f5".to_string(), false);
+        let report = TournamentReport {
+            engine_names: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            pairings: vec![(
+                0,
+                2,
+                MatchReport {
+                    engine1_name: "a".to_string(),
+                    engine2_name: "c".to_string(),
+                    statistics,
+                },
+            )],
+        };
+
+        assert_eq!(report.score(0, 2), Some((1.5, 2)));
+        assert_eq!(report.score(2, 0), Some((0.5, 2)));
+        assert_eq!(report.score(0, 1), None);
+        assert_eq!(report.total_score(2), (0.5, 2));
+    }
+}
@@ -121,6 +121,25 @@ impl Board {
         Ok(Board { player, opponent })
     }
 
+    /// Checks that the board can occur in a game.
+    ///
+    /// The four center squares are occupied from the start and never become
+    /// empty again, so a board with an empty center square is unreachable.
+    ///
+    /// # Errors
+    ///
+    /// - [`BoardError::EmptyCenter`] if a center square is empty.
+    pub fn validate(&self) -> Result<(), BoardError> {
+        let occupied = self.player | self.opponent;
+        match [Square::D4, Square::E4, Square::D5, Square::E5]
+            .into_iter()
+            .find(|&sq| !occupied.contains(sq))
+        {
+            Some(square) => Err(BoardError::EmptyCenter { square }),
+            None => Ok(()),
+        }
+    }
+
     /// Returns the disc at `sq` from the perspective of `side_to_move`.
     #[inline]
     pub fn get_disc_at(&self, sq: Square, side_to_move: Disc) -> Disc {
@@ -449,7 +468,7 @@ impl fmt::Display for Board {
     }
 }
 
-/// Error type for [`Board::from_string`].
+/// Error type for [`Board::from_string`] and [`Board::validate`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BoardError {
     /// Current player is [`Disc::Empty`].
@@ -475,6 +494,11 @@ pub enum BoardError {
         /// Position in the string (0-indexed).
         position: usize,
     },
+    /// A center square is empty, which no game can reach.
+    EmptyCenter {
+        /// The empty center square.
+        square: Square,
+    },
 }
 
 impl fmt::Display for BoardError {
@@ -501,6 +525,9 @@ impl fmt::Display for BoardError {
                     "Invalid character '{char}' at position {position}: must be 'X', 'O', or '-'"
                 )
             }
+            BoardError::EmptyCenter { square } => {
+                write!(f, "Center square {square} is empty")
+            }
         }
     }
 }
@@ -558,6 +585,18 @@ mod tests {
         assert!(board.opponent().contains(Square::E5));
     }
 
+    #[test]
+    fn test_validate_requires_occupied_center() {
+        assert_eq!(Board::new().validate(), Ok(()));
+        assert_eq!(Board::new().make_move(Square::F5).validate(), Ok(()));
+
+        let board = Board::from_bitboards(Square::E4.bitboard(), Square::D5.bitboard());
+        assert_eq!(
+            board.validate(),
+            Err(BoardError::EmptyCenter { square: Square::D4 })
+        );
+    }
+
     #[test]
     fn test_from_string_white_perspective() {
         let board_string = "--------\
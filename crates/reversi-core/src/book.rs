@@ -0,0 +1,408 @@
+//! Opening book.
+//!
+//! A [`Book`] maps positions to the moves played from them in a collection of
+//! games, together with how often each move was played and the average final
+//! outcome for the mover. Positions are keyed exactly as played; symmetric
+//! variations of an opening are stored as separate entries.
+//!
+//! Books are stored in a small little-endian binary format:
+//!
+//! ```text
+//! magic "NRBK", version u32, entry count u32,
+//! per entry: player u64, opponent u64, side u8, move count u8,
+//!   per move: square u8, count u32, scored u32, score sum i64
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use rand::{Rng, RngExt};
+
+use crate::board::Board;
+use crate::disc::Disc;
+use crate::formats::GameRecord;
+use crate::square::Square;
+
+const MAGIC: &[u8; 4] = b"NRBK";
+const VERSION: u32 = 1;
+
+/// Default number of plies from each game added to a book.
+pub const DEFAULT_BOOK_PLY: usize = 20;
+
+/// A move stored in the book for one position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    /// The move.
+    pub sq: Square,
+    /// Number of games in which the move was played.
+    pub count: u32,
+    /// Number of those games with a known final outcome.
+    pub scored: u32,
+    /// Sum of the final disc differences for the mover over scored games.
+    pub score_sum: i64,
+}
+
+impl BookMove {
+    /// Average final disc difference for the mover, or `None` if no game
+    /// that played this move had a known outcome.
+    pub fn average_score(&self) -> Option<f32> {
+        (self.scored > 0).then(|| self.score_sum as f32 / self.scored as f32)
+    }
+
+    /// Adds the statistics of `other`. The counts saturate instead of
+    /// overflowing; outcomes that no longer fit in `scored` are left out so
+    /// that the average stays that of the counted games.
+    fn merge(&mut self, other: &BookMove) {
+        self.count = self.count.saturating_add(other.count);
+        if let Some(scored) = self.scored.checked_add(other.scored) {
+            self.scored = scored;
+            self.score_sum = self.score_sum.saturating_add(other.score_sum);
+        }
+    }
+}
+
+/// Moves known for a single position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookEntry {
+    /// Side to move in the position.
+    pub side_to_move: Disc,
+    /// Moves sorted by descending play count.
+    pub moves: Vec<BookMove>,
+}
+
+impl BookEntry {
+    fn add(&mut self, mv: BookMove) {
+        match self.moves.iter_mut().find(|m| m.sq == mv.sq) {
+            Some(existing) => existing.merge(&mv),
+            None => self.moves.push(mv),
+        }
+        self.moves.sort_by_key(|m| Reverse(m.count));
+    }
+}
+
+/// An opening book built from game records.
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    entries: HashMap<Board, BookEntry>,
+}
+
+impl Book {
+    /// Creates an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of positions in the book.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the book holds no positions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Adds the first `max_ply` moves of a game to the book.
+    ///
+    /// The final outcome is taken from the record's result when present,
+    /// otherwise from the final position if the game was played to the end.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the record contains an illegal move.
+    pub fn add_game(&mut self, record: &GameRecord, max_ply: usize) -> Result<(), String> {
+        let state = record.replay()?;
+
+        let black_score = record.result.or_else(|| {
+            state.is_game_over().then(|| {
+                let board = state.board();
+                let score = board.solve(board.get_empty_count());
+                if state.side_to_move() == Disc::Black {
+                    score
+                } else {
+                    -score
+                }
+            })
+        });
+
+        let plies = state
+            .move_history()
+            .iter()
+            .filter_map(|&(sq, board, side)| sq.map(|sq| (sq, board, side)))
+            .take(max_ply);
+        for (sq, board, side) in plies {
+            let score = black_score.map(|s| if side == Disc::Black { s } else { -s });
+            self.entries
+                .entry(board)
+                .or_insert_with(|| BookEntry {
+                    side_to_move: side,
+                    moves: Vec::new(),
+                })
+                .add(BookMove {
+                    sq,
+                    count: 1,
+                    scored: u32::from(score.is_some()),
+                    score_sum: score.unwrap_or(0) as i64,
+                });
+        }
+        Ok(())
+    }
+
+    /// Merges every entry of `other` into this book, summing statistics of
+    /// moves present in both.
+    pub fn merge(&mut self, other: &Book) {
+        for (board, entry) in &other.entries {
+            let target = self.entries.entry(*board).or_insert_with(|| BookEntry {
+                side_to_move: entry.side_to_move,
+                moves: Vec::new(),
+            });
+            for mv in &entry.moves {
+                target.add(*mv);
+            }
+        }
+    }
+
+    /// Returns the moves stored for `board`, most played first.
+    pub fn probe(&self, board: &Board) -> Option<&[BookMove]> {
+        self.entries.get(board).map(|entry| entry.moves.as_slice())
+    }
+
+    /// Picks a book move for `board` at random, weighted by play count.
+    pub fn choose_move<R: Rng + ?Sized>(&self, board: &Board, rng: &mut R) -> Option<Square> {
+        let moves = self.probe(board)?;
+        let total: u64 = moves.iter().map(|m| m.count as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rng.random_range(0..total);
+        for mv in moves {
+            if pick < mv.count as u64 {
+                return Some(mv.sq);
+            }
+            pick -= mv.count as u64;
+        }
+        None
+    }
+
+    /// Iterates over all positions and their entries in unspecified order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Board, &BookEntry)> {
+        self.entries.iter()
+    }
+
+    /// Reads a book from a file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the book to a file, replacing any existing content.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Reads a book in the binary format described in the module docs.
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not an opening book file",
+            ));
+        }
+        let version = reader.read_u32::<LittleEndian>()?;
+        if version != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported book version {version}"),
+            ));
+        }
+
+        let n_entries = reader.read_u32::<LittleEndian>()? as usize;
+        let mut entries = HashMap::with_capacity(n_entries);
+        for _ in 0..n_entries {
+            let player = reader.read_u64::<LittleEndian>()?;
+            let opponent = reader.read_u64::<LittleEndian>()?;
+            if player & opponent != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "overlapping discs in book position",
+                ));
+            }
+            let side_to_move = match reader.read_u8()? {
+                0 => Disc::Black,
+                1 => Disc::White,
+                other => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid side to move {other}"),
+                    ));
+                }
+            };
+            let n_moves = reader.read_u8()? as usize;
+            let mut moves = Vec::with_capacity(n_moves);
+            for _ in 0..n_moves {
+                let sq = Square::from_u8(reader.read_u8()?).ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid book move")
+                })?;
+                moves.push(BookMove {
+                    sq,
+                    count: reader.read_u32::<LittleEndian>()?,
+                    scored: reader.read_u32::<LittleEndian>()?,
+                    score_sum: reader.read_i64::<LittleEndian>()?,
+                });
+            }
+            moves.sort_by_key(|m| Reverse(m.count));
+            entries.insert(
+                Board::from_bitboards(player, opponent),
+                BookEntry {
+                    side_to_move,
+                    moves,
+                },
+            );
+        }
+        Ok(Self { entries })
+    }
+
+    /// Writes the book in the binary format described in the module docs.
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_u32::<LittleEndian>(VERSION)?;
+        writer.write_u32::<LittleEndian>(self.entries.len() as u32)?;
+        for (board, entry) in &self.entries {
+            writer.write_u64::<LittleEndian>(board.player().bits())?;
+            writer.write_u64::<LittleEndian>(board.opponent().bits())?;
+            writer.write_u8(if entry.side_to_move == Disc::Black {
+                0
+            } else {
+                1
+            })?;
+            writer.write_u8(entry.moves.len() as u8)?;
+            for mv in &entry.moves {
+                writer.write_u8(mv.sq as u8)?;
+                writer.write_u32::<LittleEndian>(mv.count)?;
+                writer.write_u32::<LittleEndian>(mv.scored)?;
+                writer.write_i64::<LittleEndian>(mv.score_sum)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn game(transcript: &str, result: Option<i32>) -> GameRecord {
+        let mut record = GameRecord::parse(transcript).unwrap();
+        record.result = result;
+        record
+    }
+
+    #[test]
+    fn add_game_counts_moves_and_outcomes_per_mover() {
+        let mut book = Book::new();
+        book.add_game(&game("f5d6", Some(10)), DEFAULT_BOOK_PLY)
+            .unwrap();
+        book.add_game(&game("f5f6", Some(-4)), DEFAULT_BOOK_PLY)
+            .unwrap();
+
+        let root = book.probe(&Board::new()).unwrap();
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].sq, Square::F5);
+        assert_eq!(root[0].count, 2);
+        assert_eq!(root[0].average_score(), Some(3.0));
+
+        let after_f5 = book.probe(&Board::new().make_move(Square::F5)).unwrap();
+        let d6 = after_f5.iter().find(|m| m.sq == Square::D6).unwrap();
+        // White's outcome is the negated Black result.
+        assert_eq!(d6.average_score(), Some(-10.0));
+    }
+
+    #[test]
+    fn add_game_stops_at_max_ply() {
+        let mut book = Book::new();
+        book.add_game(&game("f5d6c3", None), 1).unwrap();
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.probe(&Board::new()).unwrap()[0].average_score(), None);
+    }
+
+    #[test]
+    fn merge_sums_statistics() {
+        let mut a = Book::new();
+        a.add_game(&game("f5", Some(2)), 1).unwrap();
+        let mut b = Book::new();
+        b.add_game(&game("f5", Some(6)), 1).unwrap();
+        b.add_game(&game("d3", None), 1).unwrap();
+
+        a.merge(&b);
+        let root = a.probe(&Board::new()).unwrap();
+        assert_eq!(root[0].sq, Square::F5);
+        assert_eq!(root[0].count, 2);
+        assert_eq!(root[0].average_score(), Some(4.0));
+        assert_eq!(root.len(), 2);
+    }
+
+    #[test]
+    fn merge_saturates_counts() {
+        let mut mv = BookMove {
+            sq: Square::F5,
+            count: u32::MAX - 1,
+            scored: u32::MAX - 1,
+            score_sum: 2 * (u32::MAX - 1) as i64,
+        };
+        let other = BookMove {
+            sq: Square::F5,
+            count: 3,
+            scored: 3,
+            score_sum: -30,
+        };
+        mv.merge(&other);
+        assert_eq!(mv.count, u32::MAX);
+        assert_eq!(mv.scored, u32::MAX - 1);
+        assert_eq!(mv.average_score(), Some(2.0));
+    }
+
+    #[test]
+    fn binary_format_round_trips() {
+        let mut book = Book::new();
+        book.add_game(&game("f5d6c3d3c4", Some(12)), DEFAULT_BOOK_PLY)
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        book.write(&mut bytes).unwrap();
+        let loaded = Book::read(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.len(), book.len());
+        for (board, entry) in book.iter() {
+            assert_eq!(loaded.entries.get(board), Some(entry));
+        }
+    }
+
+    #[test]
+    fn read_rejects_foreign_files() {
+        assert!(Book::read(&mut &b"NOPE\x01\x00\x00\x00"[..]).is_err());
+    }
+
+    #[test]
+    fn choose_move_only_returns_book_moves() {
+        let mut book = Book::new();
+        book.add_game(&game("f5", None), 1).unwrap();
+        book.add_game(&game("d3", None), 1).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..16 {
+            let sq = book.choose_move(&Board::new(), &mut rng).unwrap();
+            assert!(sq == Square::F5 || sq == Square::D3);
+        }
+        assert!(
+            book.choose_move(&Board::new().make_move(Square::F5), &mut rng)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! CPU features and the SIMD code paths this build of the engine uses.
+//!
+//! Most SIMD backends are chosen at compile time from the enabled target
+//! features; the network's linear layers additionally pick a VNNI, dot
+//! product or i8mm kernel at runtime. A build that leaves features the CPU
+//! has unused is a common cause of slow searches, so both sides are
+//! reported.
+
+/// SIMD-related target features this binary was compiled with.
+pub fn compiled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    macro_rules! push_enabled {
+        ($($feature:tt),*) => {
+            $(
+                if cfg!(target_feature = $feature) {
+                    features.push($feature);
+                }
+            )*
+        };
+    }
+    push_enabled!(
+        "popcnt",
+        "bmi2",
+        "avx2",
+        "avx512f",
+        "avx512bw",
+        "avx512cd",
+        "avx512vl",
+        "avx512vnni",
+        "avxvnni",
+        "neon",
+        "dotprod",
+        "i8mm",
+        "sha3",
+        "simd128"
+    );
+    features
+}
+
+/// SIMD-related features the running CPU supports.
+///
+/// Without runtime detection (WebAssembly), this is the compiled set.
+pub fn detected_features() -> Vec<&'static str> {
+    cfg_select! {
+        target_arch = "x86_64" => {
+            let mut features = Vec::new();
+            macro_rules! push_detected {
+                ($($feature:tt),*) => {
+                    $(
+                        if std::arch::is_x86_feature_detected!($feature) {
+                            features.push($feature);
+                        }
+                    )*
+                };
+            }
+            push_detected!(
+                "popcnt",
+                "bmi2",
+                "avx2",
+                "avx512f",
+                "avx512bw",
+                "avx512cd",
+                "avx512vl",
+                "avx512vnni",
+                "avxvnni"
+            );
+            features
+        }
+        target_arch = "aarch64" => {
+            let mut features = Vec::new();
+            macro_rules! push_detected {
+                ($($feature:tt),*) => {
+                    $(
+                        if std::arch::is_aarch64_feature_detected!($feature) {
+                            features.push($feature);
+                        }
+                    )*
+                };
+            }
+            push_detected!("neon", "dotprod", "i8mm", "sha3");
+            features
+        }
+        _ => {
+            compiled_features()
+        }
+    }
+}
+
+/// Backend of the bitboard move generation and flips, fixed at compile
+/// time.
+pub fn bitboard_backend() -> &'static str {
+    cfg_select! {
+        all(target_arch = "x86_64", target_feature = "avx512vl") => { "avx512" }
+        all(target_arch = "x86_64", target_feature = "avx2") => { "avx2" }
+        all(target_arch = "aarch64", target_feature = "neon") => { "neon" }
+        all(target_arch = "wasm32", target_feature = "simd128") => { "wasm-simd128" }
+        _ => { "portable" }
+    }
+}
+
+/// Kernel of the network's wide linear layers, as selected at runtime.
+///
+/// Mirrors the dispatch in `LinearLayer::select_forward_fn`.
+pub fn network_backend() -> &'static str {
+    cfg_select! {
+        all(target_arch = "x86_64", target_feature = "avx512bw") => {
+            if std::arch::is_x86_feature_detected!("avx512vnni") {
+                "avx512-vnni"
+            } else {
+                "avx512"
+            }
+        }
+        all(target_arch = "x86_64", target_feature = "avx2") => {
+            if std::arch::is_x86_feature_detected!("avxvnni") {
+                "avx2-vnni"
+            } else {
+                "avx2"
+            }
+        }
+        all(target_arch = "aarch64", target_feature = "neon") => {
+            if std::arch::is_aarch64_feature_detected!("i8mm") {
+                "neon-i8mm"
+            } else if std::arch::is_aarch64_feature_detected!("dotprod") {
+                "neon-dotprod"
+            } else {
+                "neon"
+            }
+        }
+        _ => { "scalar" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compiled_features_are_detected() {
+        // A binary using features its CPU lacks would not get this far.
+        let detected = detected_features();
+        for feature in compiled_features() {
+            assert!(detected.contains(&feature), "{feature} not detected");
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Game record interchange formats.
+//!
+//! Provides a neutral [`GameRecord`] together with readers and writers for
+//! the formats used by other Othello software:
+//!
+//! - [`transcript`]: concatenated move lists such as `f5d6c3d3`;
+//! - [`ggf`]: Generic Game Format records as produced by GGS;
+//! - [`wthor`]: binary WTHOR game databases;
+//! - [`selfplay`]: self-play training records written by `datagen`.
+//!
+//! Passes are never stored in a [`GameRecord`]; they are implied by the
+//! position and reinserted by [`GameRecord::replay`].
+
+pub mod ggf;
+pub mod selfplay;
+pub mod transcript;
+pub mod wthor;
+
+use crate::board::Board;
+use crate::disc::Disc;
+use crate::game_state::GameState;
+use crate::square::Square;
+
+/// A game: a starting position, the moves played from it, and optional
+/// metadata carried by richer formats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// Starting position, from the perspective of [`Self::side_to_move`].
+    pub board: Board,
+    /// Side to move in the starting position.
+    pub side_to_move: Disc,
+    /// Moves in play order, excluding passes.
+    pub moves: Vec<Square>,
+    /// Name of the Black player, if known.
+    pub black_name: Option<String>,
+    /// Name of the White player, if known.
+    pub white_name: Option<String>,
+    /// Free-form date string, if known.
+    pub date: Option<String>,
+    /// Recorded final disc difference from Black's perspective, if known.
+    pub result: Option<i32>,
+    /// Seconds spent on each move in [`Self::moves`], or empty if unknown.
+    pub times: Vec<f64>,
+}
+
+impl Default for GameRecord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameRecord {
+    /// Creates an empty record starting from the standard initial position.
+    pub fn new() -> Self {
+        Self::from_position(Board::new(), Disc::Black)
+    }
+
+    /// Creates an empty record starting from an arbitrary position.
+    pub fn from_position(board: Board, side_to_move: Disc) -> Self {
+        Self {
+            board,
+            side_to_move,
+            moves: Vec::new(),
+            black_name: None,
+            white_name: None,
+            date: None,
+            result: None,
+            times: Vec::new(),
+        }
+    }
+
+    /// Creates a record of `moves` played from the standard initial position.
+    pub fn from_moves(moves: Vec<Square>) -> Self {
+        Self {
+            moves,
+            ..Self::new()
+        }
+    }
+
+    /// Parses a record, detecting GGF by its leading `(;` and treating
+    /// anything else as a move transcript.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first malformed element.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text.starts_with("(;") {
+            ggf::parse(text)
+        } else {
+            transcript::parse(text).map(Self::from_moves)
+        }
+    }
+
+    /// Replays the moves from the starting position, inserting forced passes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the 1-based index of the first illegal move.
+    pub fn replay(&self) -> Result<GameState, String> {
+        let mut state = GameState::from_board(self.board, self.side_to_move);
+        for (i, &sq) in self.moves.iter().enumerate() {
+            if !state.board().has_legal_moves() && !state.is_game_over() {
+                state.make_pass()?;
+            }
+            state
+                .make_move(sq)
+                .map_err(|_| format!("Illegal move at position {}: {sq}", i + 1))?;
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_detects_ggf_and_transcripts() {
+        let from_transcript = GameRecord::parse("f5d6c3").unwrap();
+        assert_eq!(from_transcript.moves.len(), 3);
+
+        let from_ggf = GameRecord::parse("(;GM[Othello]TY[8]B[f5]W[d6]B[c3];)").unwrap();
+        assert_eq!(from_ggf.moves, from_transcript.moves);
+    }
+
+    #[test]
+    fn replay_reports_the_first_illegal_move() {
+        let record = GameRecord::parse("f5f5").unwrap();
+        let err = record.replay().unwrap_err();
+        assert!(err.contains("position 2"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn replay_tracks_side_to_move() {
+        let state = GameRecord::parse("f5d6").unwrap().replay().unwrap();
+        assert_eq!(state.side_to_move(), Disc::Black);
+        assert_eq!(state.move_history().len(), 2);
+    }
+}
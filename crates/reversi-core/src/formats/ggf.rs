@@ -0,0 +1,244 @@
+//! Generic Game Format (GGF) records.
+//!
+//! A GGF game is a `(;` ... `;)` block of `KEY[value]` properties, e.g.
+//!
+//! ```text
+//! (;GM[Othello]PB[alice]PW[bob]RE[+4]TY[8]BO[8 ---...--- *]B[f5//1.2]W[d6];)
+//! ```
+//!
+//! Moves are `B[...]`/`W[...]` with an optional `/eval/time` suffix; `PA`
+//! denotes a pass. The board in `BO` uses `*` for Black, `O` for White and
+//! `-` for empty squares, followed by the side to move.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::disc::Disc;
+use crate::square::Square;
+
+use super::GameRecord;
+
+/// Parses the first GGF game found in `text`.
+///
+/// Unknown properties are ignored. Passes are dropped since
+/// [`GameRecord::replay`] reinserts them. Move times are kept only if every
+/// move has one.
+///
+/// # Errors
+///
+/// Returns an error if no game block is present or a property is malformed.
+pub fn parse(text: &str) -> Result<GameRecord, String> {
+    let start = text
+        .find("(;")
+        .ok_or_else(|| "Missing GGF game start '(;'".to_string())?;
+    let body = &text[start + 2..];
+    let end = body
+        .find(";)")
+        .ok_or_else(|| "Missing GGF game end ';)'".to_string())?;
+    let mut rest = &body[..end];
+
+    let mut record = GameRecord::new();
+    let mut times = Vec::new();
+    while let Some(open) = rest.find('[') {
+        let key = rest[..open].trim();
+        let close = rest[open..]
+            .find(']')
+            .map(|i| open + i)
+            .ok_or_else(|| format!("Unterminated GGF property '{key}'"))?;
+        let value = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+
+        match key {
+            "PB" => record.black_name = Some(value.to_string()),
+            "PW" => record.white_name = Some(value.to_string()),
+            "DT" => record.date = Some(value.to_string()),
+            "RE" => record.result = parse_result(value),
+            "BO" => {
+                let (board, side_to_move) = parse_board(value)?;
+                record.board = board;
+                record.side_to_move = side_to_move;
+            }
+            "B" | "W" => {
+                let mut fields = value.split('/');
+                let token = fields.next().unwrap_or("").trim();
+                if token.eq_ignore_ascii_case("pa") {
+                    continue;
+                }
+                let sq = Square::from_str(token)
+                    .map_err(|e| format!("Invalid GGF move '{value}': {e}"))?;
+                record.moves.push(sq);
+                if let Some(time) = fields.nth(1).and_then(|t| t.trim().parse::<f64>().ok()) {
+                    times.push(time);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if times.len() == record.moves.len() {
+        record.times = times;
+    }
+    Ok(record)
+}
+
+/// Formats `record` as a single-line GGF game.
+///
+/// # Errors
+///
+/// Returns an error if the moves cannot be replayed from the starting
+/// position, since GGF needs the mover's color and explicit passes.
+pub fn format(record: &GameRecord) -> Result<String, String> {
+    let state = record.replay()?;
+
+    let mut out = String::from("(;GM[Othello]PC[Neural Reversi]");
+    if let Some(date) = &record.date {
+        let _ = write!(out, "DT[{date}]");
+    }
+    if let Some(name) = &record.black_name {
+        let _ = write!(out, "PB[{name}]");
+    }
+    if let Some(name) = &record.white_name {
+        let _ = write!(out, "PW[{name}]");
+    }
+    if let Some(result) = record.result {
+        let _ = write!(out, "RE[{result:+}]");
+    }
+    let _ = write!(
+        out,
+        "TY[8]BO[8 {}]",
+        format_board(&record.board, record.side_to_move)
+    );
+
+    let mut times = record.times.iter();
+    let has_times = record.times.len() == record.moves.len();
+    for (sq, _, side) in state.move_history() {
+        let color = if *side == Disc::Black { 'B' } else { 'W' };
+        match sq {
+            Some(sq) => {
+                let _ = write!(out, "{color}[{}", sq.to_string().to_uppercase());
+                if let Some(time) = times.next().filter(|_| has_times) {
+                    let _ = write!(out, "//{time:.2}");
+                }
+                out.push(']');
+            }
+            None => {
+                let _ = write!(out, "{color}[PA]");
+            }
+        }
+    }
+    out.push_str(";)");
+    Ok(out)
+}
+
+fn parse_board(value: &str) -> Result<(Board, Disc), String> {
+    let mut chars = value.split_whitespace().flat_map(str::chars);
+    let size: String = chars.by_ref().take(1).collect();
+    if size != "8" {
+        return Err(format!("Unsupported GGF board size '{size}'"));
+    }
+
+    let cells: Vec<char> = chars.collect();
+    if cells.len() != 65 {
+        return Err(format!(
+            "GGF board needs 64 squares and a side to move, got {} characters",
+            cells.len()
+        ));
+    }
+
+    let side_to_move = match cells[64] {
+        '*' => Disc::Black,
+        'O' => Disc::White,
+        other => return Err(format!("Invalid GGF side to move: '{other}'")),
+    };
+
+    let mut black = Bitboard::new(0);
+    let mut white = Bitboard::new(0);
+    for (sq, &c) in Square::iter().zip(cells.iter()) {
+        match c {
+            '*' => black = black.set(sq),
+            'O' => white = white.set(sq),
+            '-' => {}
+            other => return Err(format!("Invalid GGF board character: '{other}'")),
+        }
+    }
+
+    let board = if side_to_move == Disc::Black {
+        Board::from_bitboards(black, white)
+    } else {
+        Board::from_bitboards(white, black)
+    };
+    Ok((board, side_to_move))
+}
+
+fn format_board(board: &Board, side_to_move: Disc) -> String {
+    let mut out = String::with_capacity(66);
+    for sq in Square::iter() {
+        out.push(match board.get_disc_at(sq, side_to_move) {
+            Disc::Black => '*',
+            Disc::White => 'O',
+            Disc::Empty => '-',
+        });
+    }
+    out.push(' ');
+    out.push(if side_to_move == Disc::Black {
+        '*'
+    } else {
+        'O'
+    });
+    out
+}
+
+/// Parses a GGF result such as `+12.00`, `-4` or `+64:r` into a disc count.
+fn parse_result(value: &str) -> Option<i32> {
+    let numeric = value.split(':').next()?.trim();
+    numeric.parse::<f64>().ok().map(|v| v.round() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "(;GM[Othello]PC[GGS/os]DT[2003.12.15_13:24:03.MST]PB[alice]PW[bob]\
+        RE[+4.000]TI[5:00//02:00]TY[8]\
+        BO[8 ---------------------------O*------*O--------------------------- *]\
+        B[f5//0.01]W[d6/-1.50/2.00]B[c3];)";
+
+    #[test]
+    fn parses_metadata_and_moves() {
+        let record = parse(SAMPLE).unwrap();
+        assert_eq!(record.black_name.as_deref(), Some("alice"));
+        assert_eq!(record.white_name.as_deref(), Some("bob"));
+        assert_eq!(record.result, Some(4));
+        assert_eq!(record.board, Board::new());
+        assert_eq!(record.side_to_move, Disc::Black);
+        let moves: Vec<String> = record.moves.iter().map(Square::to_string).collect();
+        assert_eq!(moves, ["f5", "d6", "c3"]);
+    }
+
+    #[test]
+    fn format_round_trips() {
+        let record = parse(SAMPLE).unwrap();
+        let text = format(&record).unwrap();
+        assert_eq!(parse(&text).unwrap(), record);
+    }
+
+    #[test]
+    fn round_trips_move_times() {
+        let mut record = GameRecord::parse("f5d6c3").unwrap();
+        record.times = vec![0.5, 1.25, 0.0];
+        let text = format(&record).unwrap();
+        assert!(text.contains("B[F5//0.50]W[D6//1.25]B[C3//0.00]"), "{text}");
+        assert_eq!(parse(&text).unwrap(), record);
+
+        // Partially timed games drop their times
+        assert!(parse(SAMPLE).unwrap().times.is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_games() {
+        assert!(parse("(;GM[Othello]B[f5]").is_err());
+        assert!(parse("(;GM[Othello]BO[8 ---];)").is_err());
+    }
+}
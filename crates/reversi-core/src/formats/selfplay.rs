@@ -0,0 +1,259 @@
+//! Self-play training records (`.bin`) written by `datagen`.
+//!
+//! A file is a sequence of fixed 27-byte records, one per position of a game
+//! in play order:
+//!
+//! | Offset | Size | Field                                              |
+//! |-------:|-----:|----------------------------------------------------|
+//! |      0 |    8 | Bitboard of the side to move (little endian)       |
+//! |      8 |    8 | Bitboard of the opponent                           |
+//! |     16 |    4 | Search score for the side to move (`f32`)          |
+//! |     20 |    1 | Final disc difference for the side to move (`i8`)  |
+//! |     21 |    1 | Ply, `60 - empties`                                |
+//! |     22 |    1 | [`MoveKind`]                                       |
+//! |     23 |    1 | Move square                                        |
+//! |     24 |    1 | Side to move, 0 for Black                          |
+//! |     25 |    2 | Game id (little endian)                            |
+//!
+//! The records of a game share its game id and have consecutive plies, as a
+//! pass does not fill a square.
+
+use crate::bitboard::Bitboard;
+use crate::board::Board;
+use crate::disc::Disc;
+use crate::square::Square;
+use crate::types::Scoref;
+
+/// Size of each record in bytes
+pub const RECORD_SIZE: u64 = 27;
+
+/// Byte offsets of individual fields inside a serialized record.
+pub const SCORE_OFFSET: usize = 16;
+pub const GAME_SCORE_OFFSET: usize = 20;
+pub const PLY_OFFSET: usize = 21;
+pub const IS_RANDOM_OFFSET: usize = 22;
+const SQ_OFFSET: usize = 23;
+const SIDE_OFFSET: usize = 24;
+const GAME_ID_OFFSET: usize = 25;
+
+/// Sentinel value for `game_score` when the true game outcome is unavailable
+/// (e.g. positions produced by `score-openings` rather than a full self-play game).
+pub const GAME_SCORE_UNAVAILABLE: i8 = i8::MIN;
+
+/// How the move of a record was chosen, stored in the random move flag byte.
+///
+/// Every kind but `Search` is non-zero, so readers that only distinguish
+/// searched from random moves keep working.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MoveKind {
+    /// Best move of the search
+    Search = 0,
+    /// Move of the random or predefined opening
+    Opening = 1,
+    /// Uniformly random move played with epsilon-greedy noise
+    Epsilon = 2,
+    /// Move sampled from the softmax of the root move scores
+    Temperature = 3,
+    /// Move of an imported game, such as a WTHOR database
+    Imported = 4,
+    /// Move of an external opponent engine in self-play
+    Opponent = 5,
+}
+
+impl MoveKind {
+    /// Converts the stored flag byte back into a move kind.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MoveKind::Search),
+            1 => Some(MoveKind::Opening),
+            2 => Some(MoveKind::Epsilon),
+            3 => Some(MoveKind::Temperature),
+            4 => Some(MoveKind::Imported),
+            5 => Some(MoveKind::Opponent),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a single position record from a self-play game.
+///
+/// For `Opening` moves, `sq` is the search's best move, which the game did
+/// not necessarily play; see [`SelfplayRecord::played_move`].
+#[derive(Clone)]
+pub struct SelfplayRecord {
+    pub game_id: u16,
+    pub ply: u8,
+    pub board: Board,
+    pub score: Scoref,
+    pub game_score: i8,
+    pub side_to_move: Disc,
+    pub move_kind: MoveKind,
+    pub sq: Square,
+}
+
+impl SelfplayRecord {
+    /// Whether the move was not the search's best move.
+    pub fn is_random(&self) -> bool {
+        self.move_kind != MoveKind::Search
+    }
+
+    /// Serializes the record.
+    pub fn to_bytes(&self) -> [u8; RECORD_SIZE as usize] {
+        let mut bytes = [0; RECORD_SIZE as usize];
+        bytes[0..8].copy_from_slice(&self.board.player().bits().to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.board.opponent().bits().to_le_bytes());
+        bytes[SCORE_OFFSET..SCORE_OFFSET + 4].copy_from_slice(&self.score.to_le_bytes());
+        bytes[GAME_SCORE_OFFSET] = self.game_score as u8;
+        bytes[PLY_OFFSET] = self.ply;
+        bytes[IS_RANDOM_OFFSET] = self.move_kind as u8;
+        bytes[SQ_OFFSET] = self.sq as u8;
+        bytes[SIDE_OFFSET] = u8::from(self.side_to_move != Disc::Black);
+        bytes[GAME_ID_OFFSET..GAME_ID_OFFSET + 2].copy_from_slice(&self.game_id.to_le_bytes());
+        bytes
+    }
+
+    /// Parses a serialized record.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bitboards overlap or the move kind or square
+    /// is invalid.
+    pub fn from_bytes(bytes: &[u8; RECORD_SIZE as usize]) -> Result<Self, String> {
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let (player, opponent) = (u64_at(0), u64_at(8));
+        if player & opponent != 0 {
+            return Err("Overlapping bitboards".to_string());
+        }
+        let move_kind = MoveKind::from_u8(bytes[IS_RANDOM_OFFSET])
+            .ok_or_else(|| format!("Invalid move kind: {}", bytes[IS_RANDOM_OFFSET]))?;
+        let sq = Square::from_u8(bytes[SQ_OFFSET])
+            .ok_or_else(|| format!("Invalid square: {}", bytes[SQ_OFFSET]))?;
+
+        Ok(Self {
+            game_id: u16::from_le_bytes([bytes[GAME_ID_OFFSET], bytes[GAME_ID_OFFSET + 1]]),
+            ply: bytes[PLY_OFFSET],
+            board: Board::from_bitboards(Bitboard::new(player), Bitboard::new(opponent)),
+            score: Scoref::from_le_bytes(bytes[SCORE_OFFSET..SCORE_OFFSET + 4].try_into().unwrap()),
+            game_score: bytes[GAME_SCORE_OFFSET] as i8,
+            side_to_move: if bytes[SIDE_OFFSET] == 0 {
+                Disc::Black
+            } else {
+                Disc::White
+            },
+            move_kind,
+            sq,
+        })
+    }
+
+    /// Returns the move played from this record's position, found from the
+    /// position of `next`, the following record of the same game, or `None`
+    /// if `next` cannot follow this position by one move.
+    ///
+    /// Unlike [`sq`](Self::sq), this is the move the game went on with, also
+    /// for `Opening` moves.
+    pub fn played_move(&self, next: &SelfplayRecord) -> Option<Square> {
+        let filled = self.board.get_empty().bits() & !next.board.get_empty().bits();
+        if filled.count_ones() != 1 {
+            return None;
+        }
+        let sq = Square::from_u8(filled.trailing_zeros() as u8)?;
+        if !self.board.is_legal_move(sq) {
+            return None;
+        }
+        let after = self.board.make_move(sq);
+        let expected = if next.side_to_move == self.side_to_move {
+            after.switch_players()
+        } else {
+            after
+        };
+        (expected == next.board).then_some(sq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(board: Board, side_to_move: Disc, sq: Square, move_kind: MoveKind) -> SelfplayRecord {
+        SelfplayRecord {
+            game_id: 513,
+            ply: 60 - board.get_empty_count() as u8,
+            board,
+            score: -2.75,
+            game_score: GAME_SCORE_UNAVAILABLE,
+            side_to_move,
+            move_kind,
+            sq,
+        }
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let original = record(Board::new(), Disc::White, Square::F5, MoveKind::Temperature);
+        let bytes = original.to_bytes();
+        let parsed = SelfplayRecord::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.to_bytes(), bytes);
+        assert_eq!(parsed.board, original.board);
+        assert_eq!(parsed.side_to_move, Disc::White);
+        assert_eq!(parsed.move_kind, MoveKind::Temperature);
+        assert_eq!(parsed.game_id, 513);
+
+        let mut corrupt = bytes;
+        corrupt[IS_RANDOM_OFFSET] = 9;
+        assert!(SelfplayRecord::from_bytes(&corrupt).is_err());
+        let mut corrupt = bytes;
+        corrupt[8..16].copy_from_slice(&Board::new().player().bits().to_le_bytes());
+        assert!(SelfplayRecord::from_bytes(&corrupt).is_err());
+    }
+
+    #[test]
+    fn played_move_follows_the_boards() {
+        // An opening record stores the best move, here D3, while F5 is played
+        let first = record(Board::new(), Disc::Black, Square::D3, MoveKind::Opening);
+        let after_f5 = Board::new().make_move(Square::F5);
+        let second = record(after_f5, Disc::White, Square::D6, MoveKind::Search);
+        assert_eq!(first.played_move(&second), Some(Square::F5));
+
+        // A record that does not follow by one move
+        let after_f5_d6 = after_f5.make_move(Square::D6);
+        let third = record(after_f5_d6, Disc::Black, Square::C3, MoveKind::Search);
+        assert_eq!(first.played_move(&third), None);
+        assert_eq!(second.played_move(&third), Some(Square::D6));
+    }
+
+    #[test]
+    fn played_move_across_a_pass() {
+        // Play the lowest legal square until the opponent has to pass
+        let mut board = Board::new();
+        let mut side = Disc::Black;
+        loop {
+            let sq = board
+                .get_moves()
+                .lsb_square()
+                .expect("no pass before the game ended");
+            let after = board.make_move(sq);
+            if !after.has_legal_moves() && after.switch_players().has_legal_moves() {
+                let before = record(board, side, Square::A1, MoveKind::Search);
+                let next = record(after.switch_players(), side, Square::A1, MoveKind::Search);
+                assert_eq!(before.played_move(&next), Some(sq));
+                // Without the pass, the side to move does not match the board
+                let wrong_side = record(
+                    after.switch_players(),
+                    side.opposite(),
+                    sq,
+                    MoveKind::Search,
+                );
+                assert_eq!(before.played_move(&wrong_side), None);
+                break;
+            }
+            board = after;
+            side = side.opposite();
+            if !board.has_legal_moves() {
+                board = board.switch_players();
+                side = side.opposite();
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! Move transcripts.
+//!
+//! A transcript is a concatenation of two-character squares such as
+//! `f5d6c3d3c4`, optionally separated by whitespace. Passes are implicit.
+
+use crate::disc::Disc;
+use crate::square::Square;
+
+/// Parses a transcript into a move list.
+///
+/// Whitespace anywhere in the input is ignored and both letter cases are
+/// accepted.
+///
+/// # Errors
+///
+/// Returns an error naming the 1-based position of the first invalid move.
+pub fn parse(text: &str) -> Result<Vec<Square>, String> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    Square::parse_sequence(&compact).map_err(|e| e.to_string())
+}
+
+/// Formats a move list as a lowercase transcript.
+pub fn format(moves: &[Square]) -> String {
+    moves.iter().map(Square::to_string).collect()
+}
+
+/// Formats a single move by `side`: lowercase for Black, uppercase for White.
+pub fn format_move(sq: Square, side: Disc) -> String {
+    let s = sq.to_string();
+    if side == Disc::White {
+        s.to_uppercase()
+    } else {
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ignores_whitespace_and_case() {
+        let moves = parse("F5 d6\nC3").unwrap();
+        assert_eq!(format(&moves), "f5d6c3");
+    }
+
+    #[test]
+    fn format_move_cases_by_side() {
+        assert_eq!(format_move(Square::F5, Disc::Black), "f5");
+        assert_eq!(format_move(Square::D6, Disc::White), "D6");
+        assert_eq!(
+            parse(&format_move(Square::D6, Disc::White)).unwrap(),
+            [Square::D6]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_squares() {
+        let err = parse("f5z9").unwrap_err();
+        assert!(err.contains("position 2"), "unexpected error: {err}");
+    }
+}
@@ -0,0 +1,182 @@
+//! WTHOR game databases (`.wtb`).
+//!
+//! A WTHOR file is a 16-byte header followed by fixed 68-byte game records:
+//!
+//! | Offset | Size | Field                                  |
+//! |-------:|-----:|----------------------------------------|
+//! |      0 |    2 | Tournament id (little endian)          |
+//! |      2 |    2 | Black player id                        |
+//! |      4 |    2 | White player id                        |
+//! |      6 |    1 | Final Black disc count                 |
+//! |      7 |    1 | Theoretical Black disc count           |
+//! |      8 |   60 | Moves as `10 * rank + file`, 1-based; 0 pads |
+//!
+//! Games always start from the standard initial position. Player and
+//! tournament names live in separate `.JOU`/`.TRN` files and are not read.
+
+use crate::board::Board;
+use crate::disc::Disc;
+use crate::square::Square;
+
+use super::GameRecord;
+
+const HEADER_SIZE: usize = 16;
+const GAME_SIZE: usize = 68;
+const MAX_MOVES: usize = 60;
+
+/// Parses every game of a WTHOR database.
+///
+/// # Errors
+///
+/// Returns an error if the header is truncated, the board size is not 8, or
+/// a move byte does not name a square.
+pub fn read(bytes: &[u8]) -> Result<Vec<GameRecord>, String> {
+    if bytes.len() < HEADER_SIZE {
+        return Err("WTHOR file is shorter than its 16-byte header".to_string());
+    }
+    let n_games = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+    let year = u16::from_le_bytes([bytes[10], bytes[11]]);
+    if !matches!(bytes[12], 0 | 8) {
+        return Err(format!("Unsupported WTHOR board size {}", bytes[12]));
+    }
+
+    let body = &bytes[HEADER_SIZE..];
+    let available = body.len() / GAME_SIZE;
+    if available < n_games {
+        return Err(format!(
+            "WTHOR header lists {n_games} games but the file holds {available}"
+        ));
+    }
+
+    body.chunks_exact(GAME_SIZE)
+        .take(n_games)
+        .enumerate()
+        .map(|(i, game)| parse_game(game, year).map_err(|e| format!("WTHOR game {}: {e}", i + 1)))
+        .collect()
+}
+
+/// Serializes games into a WTHOR database dated `year`.
+///
+/// Player and tournament ids are written as 0.
+///
+/// # Errors
+///
+/// Returns an error if a game does not start from the initial position,
+/// has more than 60 moves, or cannot be replayed.
+pub fn write(records: &[GameRecord], year: u16) -> Result<Vec<u8>, String> {
+    let n_games = u32::try_from(records.len()).map_err(|_| "Too many games for WTHOR")?;
+    let mut out = Vec::with_capacity(HEADER_SIZE + records.len() * GAME_SIZE);
+    out.push((year / 100) as u8);
+    out.push((year % 100) as u8);
+    out.extend_from_slice(&[1, 1]);
+    out.extend_from_slice(&n_games.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&year.to_le_bytes());
+    out.extend_from_slice(&[8, 0, 0, 0]);
+
+    for (i, record) in records.iter().enumerate() {
+        let game = encode_game(record).map_err(|e| format!("Game {}: {e}", i + 1))?;
+        out.extend_from_slice(&game);
+    }
+    Ok(out)
+}
+
+fn parse_game(game: &[u8], year: u16) -> Result<GameRecord, String> {
+    let mut record = GameRecord::new();
+    record.date = (year != 0).then(|| year.to_string());
+    record.result = Some(2 * game[6] as i32 - 64);
+    for &byte in game[8..].iter().take_while(|&&b| b != 0) {
+        let (rank, file) = (byte / 10, byte % 10);
+        if !(1..=8).contains(&rank) || !(1..=8).contains(&file) {
+            return Err(format!("invalid move byte {byte}"));
+        }
+        record
+            .moves
+            .push(Square::from_file_rank(file - 1, rank - 1));
+    }
+    Ok(record)
+}
+
+fn encode_game(record: &GameRecord) -> Result<[u8; GAME_SIZE], String> {
+    if record.board != Board::new() || record.side_to_move != Disc::Black {
+        return Err("WTHOR games must start from the initial position".to_string());
+    }
+    if record.moves.len() > MAX_MOVES {
+        return Err(format!("{} moves exceed WTHOR's 60", record.moves.len()));
+    }
+
+    let black_discs = match record.result {
+        Some(result) => (result + 64) / 2,
+        None => {
+            let state = record.replay()?;
+            let black = final_black_discs(state.board(), state.side_to_move());
+            if state.is_game_over() {
+                black
+            } else {
+                // Unfinished game: record the current count without awarding empties.
+                disc_count(state.board(), state.side_to_move(), Disc::Black)
+            }
+        }
+    };
+    let black_discs = black_discs.clamp(0, 64) as u8;
+
+    let mut game = [0u8; GAME_SIZE];
+    game[6] = black_discs;
+    game[7] = black_discs;
+    for (slot, sq) in game[8..].iter_mut().zip(&record.moves) {
+        *slot = (10 * (sq.rank() + 1) + sq.file() + 1) as u8;
+    }
+    Ok(game)
+}
+
+/// Black's disc count with empty squares awarded to the winner, as WTHOR
+/// records it.
+fn final_black_discs(board: &Board, side_to_move: Disc) -> i32 {
+    let black = disc_count(board, side_to_move, Disc::Black);
+    let white = disc_count(board, side_to_move, Disc::White);
+    let empties = 64 - black - white;
+    match black.cmp(&white) {
+        std::cmp::Ordering::Greater => black + empties,
+        std::cmp::Ordering::Less => black,
+        std::cmp::Ordering::Equal => black + empties / 2,
+    }
+}
+
+fn disc_count(board: &Board, side_to_move: Disc, disc: Disc) -> i32 {
+    Square::iter()
+        .filter(|&sq| board.get_disc_at(sq, side_to_move) == disc)
+        .count() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_then_read_round_trips() {
+        let mut record = GameRecord::parse("f5d6c3d3c4").unwrap();
+        record.result = Some(10);
+        let bytes = write(std::slice::from_ref(&record), 2024).unwrap();
+        assert_eq!(bytes.len(), HEADER_SIZE + GAME_SIZE);
+        // f5 is rank 5, file 6.
+        assert_eq!(bytes[HEADER_SIZE + 8], 56);
+
+        let games = read(&bytes).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].moves, record.moves);
+        assert_eq!(games[0].result, Some(10));
+        assert_eq!(games[0].date.as_deref(), Some("2024"));
+    }
+
+    #[test]
+    fn rejects_truncated_files_and_custom_starts() {
+        assert!(read(&[0; 8]).is_err());
+
+        let mut header = write(&[], 2024).unwrap();
+        header[4] = 1;
+        assert!(read(&header).is_err());
+
+        let record = GameRecord::from_position(Board::new(), Disc::White);
+        assert!(write(&[record], 2024).is_err());
+    }
+}
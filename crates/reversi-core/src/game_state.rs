@@ -165,6 +165,26 @@ impl GameState {
             None => false,
         }
     }
+
+    /// Takes back the last move of `side` together with everything played
+    /// after it: the opponent's replies and any passes. Afterwards `side` is
+    /// to move in the position where it made that move.
+    ///
+    /// Returns `false`, leaving the game unchanged, if `side` has not moved.
+    pub fn takeback(&mut self, side: Disc) -> bool {
+        let Some(index) = self
+            .history
+            .iter()
+            .rposition(|&(sq, _, mover)| sq.is_some() && mover == side)
+        else {
+            return false;
+        };
+        let (_, board, side_to_move) = self.history[index];
+        self.board = board;
+        self.side_to_move = side_to_move;
+        self.history.truncate(index);
+        true
+    }
 }
 
 #[cfg(test)]
@@ -249,6 +269,42 @@ mod tests {
         assert_eq!(game.get_score(), (2, 2));
     }
 
+    #[test]
+    fn test_takeback_undoes_move_and_reply() {
+        let mut game = GameState::new();
+        game.make_move(Square::D3).unwrap();
+        let after_first = *game.board();
+        game.make_move(Square::C3).unwrap();
+        game.make_move(Square::C4).unwrap();
+
+        // White's last move and Black's reply
+        assert!(game.takeback(Disc::White));
+        assert_eq!(*game.board(), after_first);
+        assert_eq!(game.side_to_move(), Disc::White);
+        assert_eq!(game.move_history().len(), 1);
+
+        assert!(!game.takeback(Disc::White));
+        assert!(game.takeback(Disc::Black));
+        assert_eq!(*game.board(), Board::new());
+        assert!(game.move_history().is_empty());
+    }
+
+    #[test]
+    fn test_takeback_keeps_earlier_passes() {
+        // Black has no move; White does.
+        let board = Board::from_bitboards(0x000000000000ff00, 0x00000000000000ff);
+        let mut game = GameState::from_board(board, Disc::Black);
+        game.make_pass().unwrap();
+        let before_reply = *game.board();
+        let reply = before_reply.get_moves().lsb_square().unwrap();
+        game.make_move(reply).unwrap();
+
+        assert!(game.takeback(Disc::White));
+        assert_eq!(*game.board(), before_reply);
+        assert_eq!(game.side_to_move(), Disc::White);
+        assert_eq!(game.move_history().len(), 1);
+    }
+
     #[test]
     fn test_undo_when_empty() {
         let mut game = GameState::new();
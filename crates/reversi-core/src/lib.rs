@@ -3,12 +3,15 @@
 
 pub mod bitboard;
 pub mod board;
+pub mod book;
 pub mod constants;
 pub mod count_last_flip;
+pub mod cpu;
 pub mod disc;
 pub mod empty_list;
 pub mod eval;
 pub mod flip;
+pub mod formats;
 pub mod game_state;
 pub mod level;
 pub mod move_list;
@@ -16,6 +19,7 @@ pub mod obf;
 pub mod perft;
 pub mod probcut;
 pub mod search;
+pub mod skill;
 pub mod square;
 pub mod stability;
 pub mod transposition_table;
@@ -97,6 +97,23 @@ impl ObfPosition {
         }))
     }
 
+    /// Creates a position from its best moves and their score, for sources
+    /// that do not score the other moves. Without best moves, the position is
+    /// a pass position scoring `score`.
+    pub fn from_best_moves(
+        board: Board,
+        side_to_move: Disc,
+        best_moves: &[Square],
+        score: i32,
+    ) -> Self {
+        Self {
+            board,
+            side_to_move,
+            move_scores: best_moves.iter().map(|&sq| (sq, score)).collect(),
+            pass_score: best_moves.is_empty().then_some(score),
+        }
+    }
+
     /// Returns `true` when no scored moves are listed (typically a `PS:`-only line).
     pub fn is_pass(&self) -> bool {
         self.move_scores.is_empty()
@@ -143,6 +160,16 @@ impl ObfPosition {
     }
 }
 
+/// Formats a position as an OBF board header, `<board64> <side>`.
+///
+/// The inverse of the header parsing done by [`ObfPosition::parse`].
+pub fn format_board_header(board: &Board, side_to_move: Disc) -> String {
+    let mut header = board.to_string_as_board(side_to_move).replace('\n', "");
+    header.push(' ');
+    header.push(side_to_move.to_char());
+    header
+}
+
 fn parse_board_header(header: &str) -> Result<(Board, Disc), String> {
     if !header.is_ascii() {
         return Err(format!(
@@ -185,6 +212,15 @@ fn parse_score(s: &str) -> Result<i32, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn format_board_header_round_trips() {
+        let board = Board::new().make_move(Square::F5);
+        let header = format_board_header(&board, Disc::White);
+        let pos = ObfPosition::parse(&header).unwrap().unwrap();
+        assert_eq!(pos.board, board);
+        assert_eq!(pos.side_to_move, Disc::White);
+    }
+
     const INITIAL_BOARD: &str = "---------------------------OX------XO---------------------------";
 
     /// Helper: parse a line known to produce a valid position.
@@ -338,4 +374,18 @@ mod tests {
         let pass = parse(&format!("{INITIAL_BOARD} X; PS:-4"));
         assert_eq!(pass.best_moves().count(), 0);
     }
+
+    #[test]
+    fn from_best_moves_ranks_only_best_moves() {
+        let board = parse(&format!("{INITIAL_BOARD} X")).board;
+        let pos = ObfPosition::from_best_moves(board, Disc::Black, &[Square::E6, Square::D3], 4);
+        assert!(!pos.is_pass());
+        assert_eq!(pos.expected_score(), Some(4));
+        assert_eq!(pos.rank_of(Square::D3), Some(0));
+        assert_eq!(pos.rank_of(Square::C4), None);
+
+        let pass = ObfPosition::from_best_moves(board, Disc::Black, &[], -4);
+        assert!(pass.is_pass());
+        assert_eq!(pass.expected_score(), Some(-4));
+    }
 }
@@ -4,6 +4,7 @@ use crate::board::Board;
 use crate::eval::pattern_feature::PatternFeatures;
 use crate::move_list::MoveList;
 use crate::search::side_to_move::SideToMove;
+use crate::square::Square;
 
 /// Counts the total nodes reachable from the standard initial position.
 ///
@@ -13,14 +14,58 @@ use crate::search::side_to_move::SideToMove;
 ///
 /// [`PatternFeatures`]: crate::eval::pattern_feature::PatternFeatures
 pub fn perft_root(depth: u32) -> u64 {
+    perft_board(&Board::new(), depth)
+}
+
+/// Counts the total nodes reachable from `board` in `depth` plies.
+///
+/// Passes do not consume depth, matching [`perft_root`].
+pub fn perft_board(board: &Board, depth: u32) -> u64 {
     if depth == 0 {
         return 1;
     }
 
-    let board = Board::new();
-    let mut pattern_features = PatternFeatures::new(&board, 0);
-    let side_to_move = SideToMove::Player;
-    perft(&board, &mut pattern_features, 0, side_to_move, depth)
+    let mut pattern_features = PatternFeatures::new(board, 0);
+    perft(board, &mut pattern_features, 0, SideToMove::Player, depth)
+}
+
+/// Splits the perft count of `board` by root move.
+///
+/// If the side to move must pass, the opponent's replies are listed instead,
+/// since a pass consumes no depth. Returns an empty list at depth 0 or when
+/// the game is over.
+pub fn divide(board: &Board, depth: u32) -> Vec<(Square, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let root = *board;
+    let (board, side_to_move) = if root.has_legal_moves() {
+        (root, SideToMove::Player)
+    } else {
+        (root.switch_players(), SideToMove::Opponent)
+    };
+
+    let move_list = MoveList::new(&board);
+    move_list
+        .iter()
+        .map(|m| {
+            let next = board.make_move_with_flipped(m.flipped, m.sq);
+            let nodes = if depth <= 1 {
+                1
+            } else {
+                let mut pattern_features = PatternFeatures::new(&root, 0);
+                pattern_features.update(m.sq, m.flipped, 0, side_to_move);
+                perft(
+                    &next,
+                    &mut pattern_features,
+                    1,
+                    side_to_move.switch(),
+                    depth - 1,
+                )
+            };
+            (m.sq, nodes)
+        })
+        .collect()
 }
 
 /// Recursively counts nodes in the game tree.
@@ -102,4 +147,14 @@ mod tests {
         let mut pf = PatternFeatures::new(&board, 0);
         assert_eq!(perft(&board, &mut pf, 0, SideToMove::Player, 5), 1);
     }
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let board = Board::new().make_move(Square::F5);
+        for depth in 1..=5 {
+            let total: u64 = divide(&board, depth).iter().map(|&(_, n)| n).sum();
+            assert_eq!(total, perft_board(&board, depth), "depth {depth}");
+        }
+        assert_eq!(divide(&Board::new(), 1).len(), 4);
+    }
 }
@@ -98,6 +98,8 @@ pub struct SearchTask {
     pub time_manager: Option<Arc<TimeManager>>,
     /// Optional override for evaluation mode.
     pub eval_mode: Option<EvalMode>,
+    /// Optional node budget, counted by the thread running the root search.
+    pub max_nodes: Option<u64>,
 }
 
 /// Progress information reported during an ongoing search.
@@ -234,6 +236,7 @@ impl Search {
             callback: callback.clone(),
             time_manager,
             eval_mode: options.eval_mode,
+            max_nodes: options.max_nodes,
         };
 
         let mut result = self.execute_search(task);
@@ -351,6 +354,15 @@ impl Search {
         self.threads.clone()
     }
 
+    /// Returns the static evaluation of `board` from the side to move's
+    /// perspective, in discs.
+    ///
+    /// Uses the main network directly, without searching or touching the
+    /// transposition table.
+    pub fn static_eval(&self, board: &Board) -> Scoref {
+        self.eval.evaluate_simple(board).to_disc_diff_f32()
+    }
+
     /// Selects a move quickly for time-critical situations.
     ///
     /// Performs a shallow 1-ply search to find the best move when there is
@@ -478,6 +490,10 @@ pub fn search<NT: NodeType, SS: SearchStrategy>(
     let all_node = !NT::PV_NODE && !cut_node;
     let org_alpha = alpha;
 
+    if ctx.node_budget_exhausted() {
+        thread.abort_search();
+    }
+
     if NT::PV_NODE {
         if depth == 0 {
             ctx.clear_pv();
@@ -963,6 +979,27 @@ mod tests {
         assert_eq!(search.tt().generation(), 0);
     }
 
+    #[test]
+    fn node_budget_stops_the_search_reproducibly() {
+        const MAX_NODES: u64 = 20_000;
+        let board = Board::new().make_move(Square::D3);
+        let options = SearchRunOptions::with_level(Level::unlimited(), Selectivity::None)
+            .max_nodes(Some(MAX_NODES));
+
+        let results: Vec<SearchResult> = (0..2)
+            .map(|_| Search::new(&one_thread_options()).run(&board, &options))
+            .collect();
+
+        for result in &results {
+            assert!(result.n_nodes() >= MAX_NODES);
+            assert!(result.n_nodes() < MAX_NODES + MAX_NODES / 10);
+            assert!(result.depth() < Level::unlimited().mid_depth);
+        }
+        assert_eq!(results[0].best_move(), results[1].best_move());
+        assert_eq!(results[0].depth(), results[1].depth());
+        assert_eq!(results[0].n_nodes(), results[1].n_nodes());
+    }
+
     #[test]
     fn quick_move_returns_legal_one_ply_result_or_no_legal_move() {
         let search = Search::new(&one_thread_options());
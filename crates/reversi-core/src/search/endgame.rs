@@ -82,6 +82,7 @@ pub fn search_root(task: SearchTask, thread: &Arc<Thread>) -> SearchResult {
     let use_time_control = time_manager.is_some();
 
     let mut ctx = SearchContext::new(&board, task.selectivity, task.tt.clone(), task.eval.clone());
+    ctx.max_nodes = task.max_nodes;
     if ctx.root_moves_count() == 0 {
         // Handle no legal moves
         return SearchResult::new_no_moves();
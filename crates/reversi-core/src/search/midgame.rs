@@ -53,6 +53,7 @@ pub fn search_root(task: SearchTask, thread: &Arc<Thread>) -> SearchResult {
     if let Some(mode) = task.eval_mode {
         ctx.eval_mode = mode;
     }
+    ctx.max_nodes = task.max_nodes;
 
     if ctx.root_moves_count() == 0 {
         return SearchResult::new_no_moves();
@@ -596,6 +597,7 @@ mod schedule_tests {
             })),
             time_manager: None,
             eval_mode: None,
+            max_nodes: None,
         };
 
         let result = search_root(task, pool.main());
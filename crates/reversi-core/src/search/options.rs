@@ -83,6 +83,7 @@ pub struct SearchRunOptions {
     pub multi_pv: bool,
     pub callback: Option<Arc<SearchProgressCallback>>,
     pub eval_mode: Option<EvalMode>,
+    pub max_nodes: Option<u64>,
 }
 
 impl SearchRunOptions {
@@ -95,6 +96,7 @@ impl SearchRunOptions {
             multi_pv: false,
             callback: None,
             eval_mode: None,
+            max_nodes: None,
         }
     }
 
@@ -107,6 +109,7 @@ impl SearchRunOptions {
             multi_pv: false,
             callback: None,
             eval_mode: None,
+            max_nodes: None,
         }
     }
 
@@ -127,6 +130,18 @@ impl SearchRunOptions {
         self
     }
 
+    /// Limits the search to a node budget, on top of the constraint.
+    ///
+    /// The search stops once it has visited `max_nodes` nodes and returns the
+    /// result of the last completed iteration. With a single search thread the
+    /// result only depends on the position, the budget and the transposition
+    /// table, not on the speed of the machine.
+    #[must_use]
+    pub fn max_nodes(mut self, max_nodes: Option<u64>) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
     /// Forces a specific evaluation mode.
     #[must_use]
     pub fn with_eval_mode(mut self, mode: EvalMode) -> Self {
@@ -180,4 +195,11 @@ mod tests {
         assert!(matches!(opts.constraint, SearchConstraint::Time(_)));
         assert_eq!(opts.selectivity, Selectivity::None);
     }
+
+    #[test]
+    fn max_nodes_is_unlimited_by_default() {
+        let opts = SearchRunOptions::with_level(Level::unlimited(), Selectivity::None);
+        assert_eq!(opts.max_nodes, None);
+        assert_eq!(opts.max_nodes(Some(1000)).max_nodes, Some(1000));
+    }
 }
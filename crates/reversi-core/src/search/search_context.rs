@@ -43,6 +43,8 @@ pub struct SearchContext {
     pub stack: SearchStack,
     /// Current evaluation mode (midgame vs endgame).
     pub eval_mode: EvalMode,
+    /// Node budget of the search, only set in the root search context
+    pub max_nodes: Option<u64>,
 }
 
 impl SearchContext {
@@ -66,6 +68,7 @@ impl SearchContext {
             pattern_features: PatternFeatures::new(board, ply),
             stack: SearchStack::new(),
             eval_mode: EvalMode::Main,
+            max_nodes: None,
         }
     }
 
@@ -89,6 +92,7 @@ impl SearchContext {
             pattern_features,
             stack: SearchStack::new(),
             eval_mode: task.eval_mode,
+            max_nodes: None,
         }
     }
 
@@ -150,6 +154,13 @@ impl SearchContext {
         self.empty_list.ply()
     }
 
+    /// Returns whether the search has used up its node budget.
+    #[inline]
+    pub fn node_budget_exhausted(&self) -> bool {
+        self.max_nodes
+            .is_some_and(|max_nodes| self.counters.n_nodes >= max_nodes)
+    }
+
     /// Increments the node counter for search statistics.
     #[inline(always)]
     pub fn increment_nodes(&mut self) {
@@ -1047,6 +1047,11 @@ impl Thread {
         sp.unlock();
     }
 
+    /// Aborts the search on every thread of the pool, e.g. once the node budget is used up.
+    pub fn abort_search(&self) {
+        self.abort_flag.store(true, Ordering::Release);
+    }
+
     /// Returns `true` if the search has been aborted (e.g., by deadline or external request).
     #[inline]
     pub fn is_search_aborted(&self) -> bool {
@@ -1230,9 +1235,15 @@ impl ThreadPool {
         let _ = self.sender.send(Message::Exit);
 
         // Join all threads; panic info is absorbed (the panicking thread has
-        // already logged at the panic site).
+        // already logged at the panic site). The main thread holds the pool
+        // while it runs a search, so if the owner drops its reference first,
+        // this runs on that thread. Joining its own handle would panic, so it
+        // is skipped; the thread leaves its loop on the exit flag instead.
+        let current = std::thread::current().id();
         for handle in self.thread_handles.drain(..) {
-            let _ = handle.join();
+            if handle.thread().id() != current {
+                let _ = handle.join();
+            }
         }
 
         // Clear thread references
@@ -1404,3 +1415,81 @@ impl Drop for ThreadPool {
         self.exit();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::Level;
+    use crate::search::SearchTask;
+    use crate::square::Square;
+    use crate::transposition_table::TranspositionTable;
+    use std::sync::mpsc;
+    use std::thread::ThreadId;
+
+    #[test]
+    fn dropping_the_last_reference_on_a_pool_thread_shuts_down() {
+        let eval = Arc::new(
+            Eval::with_weight_files(None, None).expect("embedded evaluation weights must load"),
+        );
+        let pool = ThreadPool::new(2);
+        let main = pool.main().clone();
+
+        // The progress callback runs on the pool's main thread; it holds the
+        // search there until the caller has dropped its reference, so that the
+        // pool thread ends up dropping the last one.
+        let (dropped_sender, dropped_receiver) = mpsc::channel::<()>();
+        let dropped_receiver = Mutex::new(dropped_receiver);
+        let pool_thread: Arc<Mutex<Option<ThreadId>>> = Arc::new(Mutex::new(None));
+        let callback_thread = pool_thread.clone();
+        let task = SearchTask {
+            board: Board::new().make_move(Square::D3),
+            selectivity: Selectivity::None,
+            tt: Arc::new(TranspositionTable::new(0)),
+            pool: pool.clone(),
+            eval,
+            level: Level {
+                mid_depth: 1,
+                end_depth: [1; 4],
+            },
+            multi_pv: false,
+            callback: Some(Arc::new(move |_| {
+                *callback_thread.lock().unwrap() = Some(std::thread::current().id());
+                let _ = dropped_receiver
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_secs(10));
+            })),
+            time_manager: None,
+            eval_mode: None,
+            max_nodes: None,
+        };
+
+        // A panic on the pool thread, such as joining itself, is recorded
+        let panicked = Arc::new(AtomicBool::new(false));
+        let hook_panicked = panicked.clone();
+        let hook_thread = pool_thread.clone();
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if *hook_thread.lock().unwrap() == Some(std::thread::current().id()) {
+                hook_panicked.store(true, Ordering::Release);
+            }
+            default_hook(info);
+        }));
+
+        let result = pool.start_thinking(task);
+        drop(pool);
+        drop(dropped_sender);
+        assert!(result.recv_timeout(Duration::from_secs(10)).is_ok());
+
+        // The main thread's loop lets go of it once the pool is torn down
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Arc::strong_count(&main) > 1 {
+            assert!(Instant::now() < deadline, "pool was not torn down");
+            sleep(Duration::from_millis(5));
+        }
+        let _ = std::panic::take_hook();
+
+        assert!(pool_thread.lock().unwrap().is_some());
+        assert!(!panicked.load(Ordering::Acquire));
+    }
+}
@@ -0,0 +1,109 @@
+//! Bounded-error move selection for weaker, human-like play.
+//!
+//! Searching shallower alone makes a weak player that still plays the
+//! endgame perfectly. An [`ErrorModel`] instead picks among the scored legal
+//! moves those losing at most a bounded number of discs against the best
+//! one, favoring smaller losses: a weakened player mostly errs slightly,
+//! sometimes clearly, and never by more than the bound.
+
+use rand::{Rng, RngExt};
+
+use crate::square::Square;
+
+/// Strongest skill level, which always plays the best move.
+pub const MAX_SKILL: u8 = 20;
+
+/// Largest loss per skill level below [`MAX_SKILL`], in discs.
+const MAX_LOSS_PER_LEVEL: f32 = 1.5;
+
+/// Temperature per skill level below [`MAX_SKILL`], in discs.
+const TEMPERATURE_PER_LEVEL: f32 = 0.5;
+
+/// How far from the best move a weakened player's choices may stray.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ErrorModel {
+    /// Largest loss, in discs, a chosen move may have against the best one.
+    pub max_loss: f32,
+    /// Loss, in discs, that makes a move e times less likely than the best.
+    pub temperature: f32,
+}
+
+impl ErrorModel {
+    /// Returns the model for `skill` (0 to [`MAX_SKILL`]), or `None` at full
+    /// strength.
+    pub fn for_skill(skill: u8) -> Option<Self> {
+        let weakness = MAX_SKILL.saturating_sub(skill) as f32;
+        (weakness > 0.0).then_some(ErrorModel {
+            max_loss: weakness * MAX_LOSS_PER_LEVEL,
+            temperature: weakness * TEMPERATURE_PER_LEVEL,
+        })
+    }
+
+    /// Chooses a move from `scores`, the score of each legal move for the
+    /// side to move. Returns `None` if there are no moves.
+    pub fn choose<R: Rng + ?Sized>(&self, scores: &[(Square, f32)], rng: &mut R) -> Option<Square> {
+        let best = scores.iter().map(|&(_, score)| score).reduce(f32::max)?;
+        let temperature = self.temperature.max(f32::EPSILON) as f64;
+        let candidates: Vec<(Square, f64)> = scores
+            .iter()
+            .filter(|&&(_, score)| best - score <= self.max_loss)
+            .map(|&(sq, score)| (sq, (-((best - score) as f64) / temperature).exp()))
+            .collect();
+
+        let total: f64 = candidates.iter().map(|&(_, weight)| weight).sum();
+        let mut pick = rng.random_range(0.0..total);
+        for &(sq, weight) in &candidates {
+            if pick < weight {
+                return Some(sq);
+            }
+            pick -= weight;
+        }
+        candidates.last().map(|&(sq, _)| sq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    const SCORES: [(Square, f32); 4] = [
+        (Square::F5, 4.0),
+        (Square::D3, 2.0),
+        (Square::C4, -3.0),
+        (Square::E6, -20.0),
+    ];
+
+    #[test]
+    fn full_strength_has_no_error_model() {
+        assert_eq!(ErrorModel::for_skill(MAX_SKILL), None);
+        assert_eq!(ErrorModel::for_skill(MAX_SKILL + 1), None);
+        assert!(
+            ErrorModel::for_skill(0).unwrap().max_loss
+                > ErrorModel::for_skill(10).unwrap().max_loss
+        );
+    }
+
+    #[test]
+    fn choose_never_exceeds_the_loss_bound() {
+        let model = ErrorModel {
+            max_loss: 8.0,
+            temperature: 4.0,
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut chosen = Vec::new();
+        for _ in 0..200 {
+            let sq = model.choose(&SCORES, &mut rng).unwrap();
+            assert_ne!(sq, Square::E6);
+            chosen.push(sq);
+        }
+        assert!(chosen.contains(&Square::F5));
+        assert!(chosen.contains(&Square::D3));
+    }
+
+    #[test]
+    fn choose_without_moves_returns_none() {
+        let model = ErrorModel::for_skill(0).unwrap();
+        assert_eq!(model.choose(&[], &mut StdRng::seed_from_u64(7)), None);
+    }
+}
@@ -16,6 +16,7 @@ const elements = {
   status: document.getElementById('status'),
   results: document.getElementById('results'),
   resultsContainer: document.getElementById('results-container'),
+  downloadReport: document.getElementById('download-report'),
   inputs: {
     iterations: document.getElementById('iterations'),
     searchDepth: document.getElementById('search-depth'),
@@ -135,10 +136,23 @@ function displayResult(result) {
 
 function clearResults() {
   elements.resultsContainer.innerHTML = '';
+  benchmarkRunner?.clear_results();
+}
+
+// Saves every result shown, with engine and device details, as one JSON file
+function downloadReport() {
+  const blob = new Blob([benchmarkRunner.report_json()], { type: 'application/json' });
+  const url = URL.createObjectURL(blob);
+  const link = document.createElement('a');
+  link.href = url;
+  link.download = `neural-reversi-benchmark-${new Date().toISOString().replace(/[:.]/g, '-')}.json`;
+  link.click();
+  URL.revokeObjectURL(url);
 }
 
 function setButtonsEnabled(enabled) {
   Object.values(elements.buttons).forEach(btn => btn.disabled = !enabled);
+  elements.downloadReport.disabled = !enabled;
 }
 
 async function runBenchmark(name, benchmarkFn) {
@@ -229,6 +243,8 @@ function setupEventListeners() {
     runAllBenchmarks();
   });
 
+  elements.downloadReport.addEventListener('click', downloadReport);
+
   // Setup individual benchmark buttons
   Object.entries(benchmarks).forEach(([key, benchmark]) => {
     elements.buttons[benchmark.buttonId].addEventListener('click', () => {
@@ -14,6 +14,11 @@ let forcedPassBlockId = 0;
 const worker = new Worker(new URL("./reversi-worker.js", import.meta.url), {
   type: "module",
 });
+// Flag the worker's search polls to stop early. The worker cannot receive
+// messages mid-search, so this needs shared memory (cross-origin isolation).
+const abortFlag = self.crossOriginIsolated
+  ? new Int32Array(new SharedArrayBuffer(Int32Array.BYTES_PER_ELEMENT))
+  : null;
 
 const locales = {
   ja: {
@@ -292,7 +297,7 @@ worker.onmessage = async (event) => {
     case "search_progress":
       handleSearchProgress(payload);
       break;
-    case "replay_completed":
+    case "undo_completed":
       syncStateFromGame(payload);
       break;
   }
@@ -300,7 +305,7 @@ worker.onmessage = async (event) => {
 
 const workerApi = {
   init(humanIsBlack, level) {
-    worker.postMessage({ type: "init", payload: { humanIsBlack, level } });
+    worker.postMessage({ type: "init", payload: { humanIsBlack, level, abortFlag } });
   },
   humanMove(index) {
     worker.postMessage({ type: "human_move", payload: { index } });
@@ -308,6 +313,14 @@ const workerApi = {
   aiMove() {
     worker.postMessage({ type: "ai_move" });
   },
+  /** Stops the AI search, which then plays its best move so far. Returns false if unsupported. */
+  abortSearch() {
+    if (!abortFlag) {
+      return false;
+    }
+    Atomics.store(abortFlag, 0, 1);
+    return true;
+  },
   pass() {
     worker.postMessage({ type: "pass" });
   },
@@ -320,8 +333,8 @@ const workerApi = {
   getState() {
     worker.postMessage({ type: "get_state" });
   },
-  replayMoves(humanIsBlack, level, moves) {
-    worker.postMessage({ type: "replay_moves", payload: { humanIsBlack, level, moves } });
+  undo(count) {
+    worker.postMessage({ type: "undo", payload: { count } });
   },
 };
 
@@ -369,6 +382,18 @@ function toNotation(index) {
   return `${file}${rank}`;
 }
 
+function fromNotation(notation) {
+  if (typeof notation !== "string" || notation.length !== 2) {
+    return null;
+  }
+  const file = FILES.indexOf(notation[0].toLowerCase());
+  const rank = Number(notation[1]);
+  if (file < 0 || !(rank >= 1 && rank <= 8)) {
+    return null;
+  }
+  return (rank - 1) * 8 + file;
+}
+
 function cellClasses(index) {
   return {
     black: state.board[index] === 1,
@@ -431,7 +456,8 @@ async function handleStartGame() {
 }
 
 async function handleNewGame() {
-  if (state.aiThinking) {
+  // Cut the AI's search short rather than blocking the new game on it.
+  if (state.aiThinking && !workerApi.abortSearch()) {
     return;
   }
 
@@ -596,13 +622,16 @@ function waitForNextFrame() {
 }
 
 function handleSearchProgress(update) {
+  // With MultiPV, only the best line drives the status display
+  if ((update?.line ?? 1) !== 1) {
+    return;
+  }
   state.searchProgress = {
     depth: Number(update?.depth ?? 0),
     score: Number(update?.score ?? 0),
     nodes: Number(update?.nodes ?? 0),
-    selectivity: Number(update?.selectivity ?? 0),
-    bestMoveIndex:
-      typeof update?.bestMoveIndex === "number" ? update.bestMoveIndex : null,
+    selectivity: Number(update?.probcut ?? 0),
+    bestMoveIndex: fromNotation(update?.bestMove),
   };
   updateSearchStatusText();
 }
@@ -795,10 +824,8 @@ async function handleUndo() {
   state.gameResult = null;
   state.gameOverModalDismissed = false;
 
-  // Replay moves to restore game state
-  // Convert reactive array to plain array for Worker
-  const plainMoves = state.moveHistory.map(move => ({ player: move.player, index: move.index }));
-  workerApi.replayMoves(state.humanIsBlack, state.level, plainMoves);
+  // Take the moves back in the engine to restore game state
+  workerApi.undo(movesToUndo);
 }
 
 // ESC key handler for modals
@@ -1,19 +1,35 @@
 // reversi_web/reversi-worker.js
 // trunk builds the WASM module and places it in the dist directory
 // We dynamically import it when needed
+import { loadWeights } from "./weights-loader.js";
+
 let Game;
 let initModule;
 
 let game;
-let searchStartTime;
+
+// Smallest module using a SIMD instruction; browsers without WebAssembly
+// SIMD (such as Safari before 16.4) reject it.
+const SIMD_PROBE = new Uint8Array([
+  0, 97, 115, 109, 1, 0, 0, 0, 1, 5, 1, 96, 0, 1, 123, 3, 2, 1, 0, 10, 10, 1, 8, 0, 65, 0, 253, 15,
+  253, 98, 11,
+]);
+
+async function importEngine() {
+  // Both paths are literal so the bundler includes both builds
+  if (WebAssembly.validate(SIMD_PROBE)) {
+    return import('/pkg/web.js');
+  }
+  return import('/pkg-scalar/web.js');
+}
 
 self.onmessage = async (event) => {
   const { type, payload } = event.data;
 
   if (type === "init") {
     try {
-      // Dynamically import the WASM module built by wasm-pack
-      const module = await import('/pkg/web.js');
+      // Dynamically import the WASM build the browser supports
+      const module = await importEngine();
       initModule = module.default;
       Game = module.Game;
 
@@ -22,10 +38,12 @@ self.onmessage = async (event) => {
 
       game = new Game(payload.humanIsBlack);
       game.set_level(payload.level);
+      if (payload.hashSizeMb) {
+        game.set_hash_size_mb(payload.hashSizeMb);
+      }
+      game.set_abort_buffer(payload.abortFlag ?? null);
       game.set_progress_callback((progress) => {
-        const elapsed = performance.now() - searchStartTime;
-        const nps = elapsed > 0 ? progress.nodes / (elapsed / 1000) : 0;
-        console.log("Search progress:", progress, "NPS:", nps.toFixed(0));
+        console.log("Search progress:", progress);
         self.postMessage({ type: "search_progress", payload: progress });
       });
       self.postMessage({ type: "initialized", payload: getGameState() });
@@ -50,7 +68,6 @@ self.onmessage = async (event) => {
       break;
     }
     case "ai_move": {
-      searchStartTime = performance.now();
       const move = game.ai_move();
       self.postMessage({
         type: "ai_moved",
@@ -75,21 +92,65 @@ self.onmessage = async (event) => {
       game.set_level(payload.level);
       break;
     }
+    case "load_weights": {
+      try {
+        const bytes = await loadWeights(payload.url, payload.integrity);
+        game.set_eval_weights(bytes);
+        self.postMessage({ type: "weights_loaded", payload: { url: payload.url } });
+      } catch (error) {
+        console.error("Failed to load weights:", error);
+        self.postMessage({ type: "error", payload: { message: error.message ?? String(error) } });
+      }
+      break;
+    }
+    case "set_eval_weights": {
+      // payload.bytes holds a weight file picked by the user, or null to
+      // restore the built-in network
+      try {
+        game.set_eval_weights(payload.bytes ?? undefined);
+        self.postMessage({ type: "weights_loaded", payload: { url: null } });
+      } catch (error) {
+        self.postMessage({ type: "error", payload: { message: error.message ?? String(error) } });
+      }
+      break;
+    }
+    case "set_book_enabled": {
+      game.set_book_enabled(payload.enabled);
+      break;
+    }
+    case "set_skill_level": {
+      game.set_skill_level(payload.skill);
+      break;
+    }
+    case "set_multipv": {
+      game.set_multipv(payload.lines);
+      break;
+    }
+    case "set_hash_size": {
+      game.set_hash_size_mb(payload.mb);
+      break;
+    }
+    case "clear_hash": {
+      game.clear_hash();
+      break;
+    }
+    case "solve": {
+      // Progress arrives as search_progress messages while solving
+      const score = game.solve_score();
+      self.postMessage({ type: "solved", payload: { score: score ?? null } });
+      break;
+    }
     case "get_state": {
       self.postMessage({ type: "state_updated", payload: getGameState() });
       break;
     }
-    case "replay_moves": {
-      // Reset and replay a sequence of moves
-      game.reset(payload.humanIsBlack);
-      game.set_level(payload.level);
-
-      for (const move of payload.moves) {
-        // Use make_move_unchecked to replay moves regardless of whose turn it is
-        game.make_move_unchecked(move.index);
+    case "undo": {
+      // Take back moves in the engine, keeping its search state
+      for (let i = 0; i < payload.count; i++) {
+        game.undo();
       }
 
-      self.postMessage({ type: "replay_completed", payload: getGameState() });
+      self.postMessage({ type: "undo_completed", payload: getGameState() });
       break;
     }
   }
@@ -3,8 +3,10 @@ mod transposition_table;
 mod eval;
 mod level;
 mod move_list;
+mod opening_book;
 mod probcut;
 mod probcut_datagen;
+mod saved_game;
 mod search;
 
 pub use probcut_datagen::{ProbCutDatagen, ProbCutDatagenResult};
@@ -12,28 +14,38 @@ pub use probcut_datagen::{ProbCutDatagen, ProbCutDatagenResult};
 use crate::{
     eval::Eval,
     level::Level,
+    saved_game::{SavedGame, SavedMove},
     search::{Search, search_context::SearchContext},
     transposition_table::TranspositionTable,
 };
-use js_sys::Function;
+use js_sys::{Function, Int32Array, Uint8Array};
 use reversi_core::board::Board;
+use reversi_core::book::Book;
 use reversi_core::constants::INITIAL_EMPTY_COUNT;
 use reversi_core::disc::Disc;
 use reversi_core::eval::pattern_feature::{PatternFeature, PatternFeatures};
+use reversi_core::formats::transcript;
 use reversi_core::move_list::MoveList;
+use reversi_core::obf;
 use reversi_core::probcut::Selectivity;
 use reversi_core::search::side_to_move::SideToMove;
+use reversi_core::skill::{self, ErrorModel};
 use reversi_core::square::{Square, TOTAL_SQUARES};
 use reversi_core::types::Depth;
+use serde::Serialize;
+use std::cell::RefCell;
 use std::hint::black_box;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 
 const DEFAULT_TT_MB: usize = 64;
+/// Largest table `Game::set_hash_size_mb` accepts; wasm32 memory tops out at 4 GiB.
+const MAX_TT_MB: u32 = 1024;
 const DEFAULT_MID_DEPTH: Depth = 7;
 const MIDGAME_SELECTIVITY: Selectivity = Selectivity::Level2;
 const MIN_MID_DEPTH: u8 = 1;
 const MAX_MID_DEPTH: u8 = 15;
+const DEFAULT_ANALYZE_DEPTH: u8 = 4;
 
 struct EngineState {
     search: Search,
@@ -52,6 +64,17 @@ impl EngineState {
         self.tt.clear();
     }
 
+    fn set_eval(&mut self, eval: Eval) {
+        self.search.set_eval(Rc::new(eval));
+        // Stored scores came from the previous network.
+        self.tt.clear();
+    }
+
+    fn resize_tt(&mut self, mb_size: usize) {
+        self.tt = Rc::new(TranspositionTable::new(mb_size));
+        self.search.set_tt(Rc::clone(&self.tt));
+    }
+
     fn search(
         &mut self,
         board: &Board,
@@ -68,6 +91,53 @@ impl EngineState {
         let moves = MoveList::new(board);
         moves.first().map(|mv| mv.sq)
     }
+
+    /// Scores every legal move of `board` for the side to move by searching
+    /// the position after it with `level`. Returns the moves best first.
+    fn analyze(&mut self, board: &Board, level: Level) -> Vec<MoveScore> {
+        let mut scores: Vec<MoveScore> = MoveList::new(board)
+            .iter()
+            .map(|mv| {
+                let next = board.make_move_with_flipped(mv.flipped, mv.sq);
+                let score = if next.has_legal_moves() {
+                    -self
+                        .search
+                        .run(&next, level, MIDGAME_SELECTIVITY, None)
+                        .score
+                } else if next.switch_players().has_legal_moves() {
+                    // The opponent passes and the mover plays again.
+                    self.search
+                        .run(&next.switch_players(), level, MIDGAME_SELECTIVITY, None)
+                        .score
+                } else {
+                    -next.solve(next.get_empty_count()) as f32
+                };
+                MoveScore {
+                    square: mv.sq.index() as u8,
+                    score,
+                }
+            })
+            .collect();
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scores
+    }
+}
+
+/// Score of one legal move, as returned by [`Game::analyze`] and
+/// [`Game::hint`].
+#[derive(Serialize)]
+struct MoveScore {
+    /// Board index of the move, as in [`Game::legal_moves`]
+    square: u8,
+    /// Score for the side to move (disc-difference scale)
+    score: f32,
+}
+
+/// A move played in a [`Game`], with the state before it for takeback.
+struct HistoryEntry {
+    board: Board,
+    current_player: Disc,
+    sq: Square,
 }
 
 /// Game session exposed to JavaScript that pairs a board with the AI engine.
@@ -75,10 +145,21 @@ impl EngineState {
 pub struct Game {
     board: Board,
     current_player: Disc,
+    /// Moves played, oldest first
+    history: Vec<HistoryEntry>,
+    /// Moves taken back, most recent last
+    redo_stack: Vec<HistoryEntry>,
     human_player: Disc,
     ai_player: Disc,
     engine: EngineState,
     mid_depth: Depth,
+    time_limit_ms: Option<u32>,
+    /// Empties from which the AI solves exactly; 0 leaves it to the level
+    perfect_solve_empties: u8,
+    /// Skill of the AI, `skill::MAX_SKILL` for full strength
+    skill: u8,
+    /// Opening book the AI plays from, if enabled
+    book: Option<Book>,
     progress_callback: Option<Function>,
 }
 
@@ -92,20 +173,61 @@ impl Game {
         let mut game = Game {
             board: Board::new(),
             current_player: Disc::Black,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
             human_player: Disc::Black,
             ai_player: Disc::White,
             engine: EngineState::new(),
             mid_depth: DEFAULT_MID_DEPTH,
+            time_limit_ms: None,
+            perfect_solve_empties: 0,
+            skill: skill::MAX_SKILL,
+            book: Some(opening_book::build()),
             progress_callback: None,
         };
         game.set_players(human_is_black);
         game
     }
 
+    /// Sets the callback receiving a progress object (`depth`, `score`,
+    /// `bestMove`, `pv`, `line`, `probcut`, `nodes`, `nps`, `phase`) after each
+    /// completed search iteration.
     pub fn set_progress_callback(&mut self, callback: Option<Function>) {
         self.progress_callback = callback;
     }
 
+    /// Throttles progress reports to at most one every `ms` milliseconds;
+    /// the final report of each search is always sent. `0` reports every
+    /// iteration.
+    pub fn set_progress_interval_ms(&mut self, ms: u32) {
+        self.engine.search.set_progress_interval_ms(ms);
+    }
+
+    /// Makes searches with a progress callback find the best `lines` moves
+    /// instead of only the best one, for analysis boards. Each iteration
+    /// reports every line, ranked by the `line` field; the AI still plays the
+    /// best move, but searches more slowly. `1` restores single-line search.
+    pub fn set_multipv(&mut self, lines: u8) {
+        self.engine.search.set_multi_pv(lines as usize);
+    }
+
+    /// Requests the running AI search to stop; `ai_move` then plays the best
+    /// move found so far.
+    ///
+    /// While `ai_move` runs, the worker cannot call into the game, so the
+    /// request has to come through the buffer set with
+    /// [`Game::set_abort_buffer`].
+    pub fn request_abort(&self) {
+        self.engine.search.abort_signal().request();
+    }
+
+    /// Sets an `Int32Array` over a `SharedArrayBuffer` through which another
+    /// thread aborts the running AI search by storing a non-zero value at
+    /// index 0. The flag is cleared whenever a search starts.
+    pub fn set_abort_buffer(&mut self, buffer: Option<Int32Array>) {
+        self.engine.search.abort_signal().set_shared(buffer);
+    }
+
     pub fn reset(&mut self, human_is_black: bool) {
         self.set_players(human_is_black);
     }
@@ -138,18 +260,9 @@ impl Game {
             return false;
         }
 
-        let square = match Square::from_u8(index) {
-            Some(Square::None) | None => return false,
-            Some(sq) => sq,
-        };
-
-        if let Some(next_board) = self.board.try_make_move(square) {
-            self.board = next_board;
-            self.current_player = self.current_player.opposite();
-            self.handle_forced_passes();
-            true
-        } else {
-            false
+        match Square::from_u8(index) {
+            Some(Square::None) | None => false,
+            Some(sq) => self.play(sq),
         }
     }
 
@@ -180,9 +293,7 @@ impl Game {
         }
 
         let best_square = self.select_ai_move()?;
-        self.board = self.board.make_move(best_square);
-        self.current_player = self.current_player.opposite();
-        self.handle_forced_passes();
+        self.play(best_square);
         Some(best_square.index() as u8)
     }
 
@@ -216,25 +327,315 @@ impl Game {
         self.mid_depth = clamped as Depth;
     }
 
+    /// Limits AI searches to `ms` milliseconds of wall-clock time, deepening
+    /// the midgame search for as long as the budget allows instead of
+    /// stopping at the level's depth. The level still decides from how many
+    /// empties the endgame is solved. `0` restores fixed-depth search.
+    pub fn set_time_limit_ms(&mut self, ms: u32) {
+        self.time_limit_ms = (ms > 0).then_some(ms);
+        self.engine.search.set_time_limit_ms(self.time_limit_ms);
+    }
+
+    /// Scores every legal move for the side to move, searching each at
+    /// `depth` plies (a shallow default when omitted), for heat-map
+    /// overlays. Returns an array of `{ square, score }` objects, best move
+    /// first; empty once the game is over.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result cannot be converted to JavaScript.
+    pub fn analyze(&mut self, depth: Option<u8>) -> Result<JsValue, JsValue> {
+        let depth = depth
+            .unwrap_or(DEFAULT_ANALYZE_DEPTH)
+            .clamp(MIN_MID_DEPTH, MAX_MID_DEPTH);
+        // The move itself is the first ply.
+        let level = level_for_position(depth as Depth - 1);
+        let scores = self.engine.analyze(&self.board, level);
+        serde_wasm_bindgen::to_value(&scores).map_err(JsValue::from)
+    }
+
+    /// Searches the current position at the AI's level and returns the
+    /// suggested move for the side to move as `{ square, score }`, without
+    /// playing it. Returns `null` if there is no legal move.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the result cannot be converted to JavaScript.
+    pub fn hint(&mut self) -> Result<JsValue, JsValue> {
+        if self.board.is_game_over() || !self.board.has_legal_moves() {
+            return Ok(JsValue::NULL);
+        }
+
+        let level = self.search_level();
+        let result = self
+            .engine
+            .search
+            .run(&self.board, level, MIDGAME_SELECTIVITY, None);
+        let Some(best_move) = result.best_move else {
+            return Ok(JsValue::NULL);
+        };
+        let hint = MoveScore {
+            square: best_move.index() as u8,
+            score: result.score,
+        };
+        serde_wasm_bindgen::to_value(&hint).map_err(JsValue::from)
+    }
+
     /// Makes a move without checking whose turn it is (for replay purposes).
     pub fn make_move_unchecked(&mut self, index: u8) -> bool {
         if self.board.is_game_over() {
             return false;
         }
 
-        let square = match Square::from_u8(index) {
-            Some(Square::None) | None => return false,
-            Some(sq) => sq,
+        match Square::from_u8(index) {
+            Some(Square::None) | None => false,
+            Some(sq) => self.play(sq),
+        }
+    }
+
+    /// Takes back the last move, restoring the position before it. Returns
+    /// `false` if no move has been played.
+    pub fn undo(&mut self) -> bool {
+        let Some(entry) = self.history.pop() else {
+            return false;
         };
+        self.board = entry.board;
+        self.current_player = entry.current_player;
+        self.redo_stack.push(entry);
+        true
+    }
 
-        if let Some(next_board) = self.board.try_make_move(square) {
-            self.board = next_board;
-            self.current_player = self.current_player.opposite();
-            self.handle_forced_passes();
-            true
+    /// Replays the last move taken back. Returns `false` if there is none;
+    /// playing a new move discards the moves taken back.
+    pub fn redo(&mut self) -> bool {
+        let Some(entry) = self.redo_stack.pop() else {
+            return false;
+        };
+        self.board = entry.board;
+        self.current_player = entry.current_player;
+        self.apply_move(entry.sq);
+        true
+    }
+
+    /// Replaces the evaluation network with a zstd-compressed weight file,
+    /// such as a community network or one fetched and cached by the page,
+    /// and clears the transposition table. `undefined` restores the network
+    /// embedded in the module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is not a valid weight file; the current
+    /// network is kept in that case.
+    pub fn set_eval_weights(&mut self, weights: Option<Uint8Array>) -> Result<(), JsValue> {
+        let eval = match weights {
+            Some(weights) => Eval::from_bytes(&weights.to_vec()),
+            None => Eval::new(),
+        }
+        .map_err(|e| JsValue::from_str(&format!("Failed to load evaluation network: {}", e)))?;
+        self.engine.set_eval(eval);
+        Ok(())
+    }
+
+    /// Resizes the transposition table to `mb` MiB, discarding its contents;
+    /// `0` restores the default size. Sizes are capped at 1024 MiB and
+    /// rounded down to a power of two.
+    pub fn set_hash_size_mb(&mut self, mb: u32) {
+        let mb = if mb == 0 {
+            DEFAULT_TT_MB
+        } else {
+            mb.min(MAX_TT_MB) as usize
+        };
+        self.engine.resize_tt(mb);
+    }
+
+    /// Clears the transposition table, so the next search starts from
+    /// scratch.
+    pub fn clear_hash(&mut self) {
+        self.engine.reset();
+    }
+
+    /// Sets the AI's skill from 0 to 20 (the default, full strength). Below
+    /// 20 the AI scores every move at its level and picks among those within
+    /// a loss bound of the best, favoring smaller losses, so weak levels make
+    /// human-like mistakes, in the endgame too. Progress reports are not
+    /// sent for these moves.
+    pub fn set_skill_level(&mut self, skill: u8) {
+        self.skill = skill.min(skill::MAX_SKILL);
+    }
+
+    /// Enables or disables the built-in opening book (enabled by default).
+    /// While the position is in the book, the AI plays a book move
+    /// instantly, chosen at random among the known lines.
+    pub fn set_book_enabled(&mut self, enabled: bool) {
+        if enabled {
+            self.book.get_or_insert_with(opening_book::build);
+        } else {
+            self.book = None;
+        }
+    }
+
+    /// Makes the AI solve the endgame exactly once `empties` or fewer
+    /// squares are left, however low its level; `0` restores the level's own
+    /// threshold. Exact solves grow expensive quickly beyond 20 empties.
+    pub fn set_perfect_solve_empties(&mut self, empties: u8) {
+        self.perfect_solve_empties = empties.min(INITIAL_EMPTY_COUNT as u8);
+    }
+
+    /// Solves the current position exactly and returns the proven final
+    /// disc margin for the side to move, reporting each solve stage to the
+    /// progress callback. Ignores the time limit; returns `undefined` if the
+    /// solve is aborted before it is proven, or for the starting position.
+    pub fn solve_score(&mut self) -> Option<i32> {
+        let n_empties = self.board.get_empty_count();
+        if self.board.is_game_over() {
+            return Some(self.board.solve(n_empties));
+        }
+        if n_empties as usize == INITIAL_EMPTY_COUNT {
+            return None;
+        }
+
+        let result = self
+            .engine
+            .search
+            .solve(&self.board, self.progress_callback.clone());
+        (result.selectivity == Selectivity::None).then(|| result.score.round() as i32)
+    }
+
+    /// Sets up a position pasted from another tool: `board` holds 64
+    /// characters, `X` for black, `O` for white and `-` for empty, in a1..h8
+    /// order as in OBF files. Clears the move history; the players keep
+    /// their colors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `board` is malformed.
+    pub fn load_position(&mut self, board: &str, black_to_move: bool) -> Result<(), JsValue> {
+        let side_to_move = if black_to_move {
+            Disc::Black
         } else {
-            false
+            Disc::White
+        };
+        let board = Board::from_string(board.trim(), side_to_move)
+            .map_err(|e| JsValue::from_str(&format!("Invalid position: {e}")))?;
+        self.engine.reset();
+        self.board = board;
+        self.current_player = side_to_move;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.handle_forced_passes();
+        Ok(())
+    }
+
+    /// Plays a transcript such as `f5d6c3` from the starting position; the
+    /// moves can then be taken back with [`Game::undo`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transcript is malformed or contains an
+    /// illegal move; the game is left unchanged.
+    pub fn load_transcript(&mut self, transcript: &str) -> Result<(), JsValue> {
+        let moves = transcript::parse(transcript)
+            .map_err(|e| JsValue::from_str(&format!("Invalid transcript: {e}")))?;
+
+        let (board, current_player) = (self.board, self.current_player);
+        let (history, redo_stack) = (
+            std::mem::take(&mut self.history),
+            std::mem::take(&mut self.redo_stack),
+        );
+        self.board = Board::new();
+        self.current_player = Disc::Black;
+        for (ply, &sq) in moves.iter().enumerate() {
+            if !self.play(sq) {
+                self.board = board;
+                self.current_player = current_player;
+                self.history = history;
+                self.redo_stack = redo_stack;
+                return Err(JsValue::from_str(&format!(
+                    "Illegal move {sq} at move {}",
+                    ply + 1
+                )));
+            }
         }
+        self.engine.reset();
+        Ok(())
+    }
+
+    /// Returns the current position as an OBF line, `<board64> <side>`,
+    /// for pasting into other tools.
+    pub fn export_position(&self) -> String {
+        obf::format_board_header(&self.board, self.current_player)
+    }
+
+    /// Serializes the game (position, history, players and AI settings) to
+    /// JSON for storage; [`Game::from_json`] restores it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        let saved_move = |entry: &HistoryEntry| SavedMove {
+            position: saved_game::encode_position(&entry.board, entry.current_player),
+            sq: entry.sq.to_string(),
+        };
+        let saved = SavedGame {
+            position: saved_game::encode_position(&self.board, self.current_player),
+            human_player: self.human_player.into(),
+            level: self.mid_depth as u8,
+            time_limit_ms: self.time_limit_ms,
+            history: self.history.iter().map(saved_move).collect(),
+            redo: self.redo_stack.iter().map(saved_move).collect(),
+        };
+        serde_json::to_string(&saved).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Restores a game saved by [`Game::to_json`]. The progress callback and
+    /// abort buffer are not part of the saved game and must be set again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not a valid saved game.
+    pub fn from_json(json: &str) -> Result<Game, JsValue> {
+        let error = |e: String| JsValue::from_str(&format!("Invalid saved game: {e}"));
+        let saved: SavedGame = serde_json::from_str(json).map_err(|e| error(e.to_string()))?;
+        let entry = |saved: &SavedMove| {
+            saved
+                .decode()
+                .map(|(board, current_player, sq)| HistoryEntry {
+                    board,
+                    current_player,
+                    sq,
+                })
+        };
+        let history = saved
+            .history
+            .iter()
+            .map(entry)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error)?;
+        let redo_stack = saved
+            .redo
+            .iter()
+            .map(entry)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error)?;
+        let (board, current_player) =
+            saved_game::decode_position(&saved.position).map_err(error)?;
+
+        let mut game = Game::new(saved.human_player == saved_game::SavedColor::Black);
+        game.board = board;
+        game.current_player = current_player;
+        game.history = history;
+        game.redo_stack = redo_stack;
+        game.set_level(saved.level);
+        game.set_time_limit_ms(saved.time_limit_ms.unwrap_or(0));
+        Ok(game)
+    }
+
+    /// Returns the moves played so far as a transcript (e.g. `f5d6c3`),
+    /// passes omitted.
+    pub fn history(&self) -> String {
+        let moves: Vec<Square> = self.history.iter().map(|entry| entry.sq).collect();
+        transcript::format(&moves)
     }
 }
 
@@ -249,6 +650,31 @@ impl Game {
         self.ai_player = self.human_player.opposite();
         self.board = Board::new();
         self.current_player = Disc::Black;
+        self.history.clear();
+        self.redo_stack.clear();
+        self.handle_forced_passes();
+    }
+
+    /// Plays `sq` for the side to move if it is legal, discarding any moves
+    /// taken back. Returns `false` if the move is illegal.
+    fn play(&mut self, sq: Square) -> bool {
+        if self.board.try_make_move(sq).is_none() {
+            return false;
+        }
+        self.redo_stack.clear();
+        self.apply_move(sq);
+        true
+    }
+
+    /// Plays the legal move `sq`, records it and skips forced passes.
+    fn apply_move(&mut self, sq: Square) {
+        self.history.push(HistoryEntry {
+            board: self.board,
+            current_player: self.current_player,
+            sq,
+        });
+        self.board = self.board.make_move(sq);
+        self.current_player = self.current_player.opposite();
         self.handle_forced_passes();
     }
 
@@ -267,8 +693,40 @@ impl Game {
         }
     }
 
+    /// Returns the level the AI searches the current position at.
+    fn search_level(&self) -> Level {
+        let mut level = level_for_position(self.mid_depth);
+        let n_empties = self.board.get_empty_count() as Depth;
+        if self.time_limit_ms.is_some() {
+            // Searching deeper than the empties left gains nothing.
+            level.mid_depth = n_empties;
+        }
+        if n_empties <= self.perfect_solve_empties as Depth {
+            level.end_depth = level.end_depth.max(n_empties);
+            level.perfect_depth = level.perfect_depth.max(n_empties);
+        }
+        level
+    }
+
     fn select_ai_move(&mut self) -> Option<Square> {
-        let level = level_for_position(self.mid_depth);
+        if let Some(sq) = self
+            .book
+            .as_ref()
+            .and_then(|book| opening_book::choose_move(book, &self.board))
+        {
+            return Some(sq);
+        }
+
+        let level = self.search_level();
+        if let Some(model) = ErrorModel::for_skill(self.skill) {
+            let scores: Vec<(Square, f32)> = self
+                .engine
+                .analyze(&self.board, level)
+                .iter()
+                .filter_map(|ms| Some((Square::from_u8(ms.square)?, ms.score)))
+                .collect();
+            return model.choose(&scores, &mut rand::rng());
+        }
         self.engine
             .search(&self.board, level, self.progress_callback.clone())
     }
@@ -325,12 +783,16 @@ impl NetworkBenchInput {
 
 /// Aggregated timing statistics returned by a benchmark run.
 #[wasm_bindgen]
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BenchmarkResult {
     name: String,
     iterations: u32,
     total_time_ms: f64,
     avg_time_us: f64,
     ops_per_sec: f64,
+    /// Nodes visited by one iteration, for benchmarks with a fixed node count
+    nodes: Option<u64>,
 }
 
 #[wasm_bindgen]
@@ -359,6 +821,29 @@ impl BenchmarkResult {
     pub fn ops_per_sec(&self) -> f64 {
         self.ops_per_sec
     }
+
+    /// Nodes visited by one iteration, or `undefined` for benchmarks that
+    /// do not search.
+    #[wasm_bindgen(getter)]
+    pub fn nodes(&self) -> Option<f64> {
+        self.nodes.map(|nodes| nodes as f64)
+    }
+}
+
+/// Report of the benchmarks run so far, as returned by
+/// [`BenchmarkRunner::report_json`].
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BenchmarkReport<'a> {
+    engine_version: &'static str,
+    simd: bool,
+    bitboard_backend: &'static str,
+    user_agent: Option<String>,
+    hardware_concurrency: Option<f64>,
+    /// Sum of the node counts; equal signatures mean the engines searched
+    /// the same trees, so their timings are comparable.
+    node_signature: u64,
+    results: &'a [BenchmarkResult],
 }
 
 /// Driver that runs the WebAssembly micro-benchmarks against a fixed corpus.
@@ -367,6 +852,8 @@ pub struct BenchmarkRunner {
     eval: Rc<Eval>,
     test_boards: Vec<Board>,
     network_inputs: Vec<NetworkBenchInput>,
+    /// Results of the benchmarks run so far, for the report
+    results: RefCell<Vec<BenchmarkResult>>,
 }
 
 #[wasm_bindgen]
@@ -394,6 +881,7 @@ impl BenchmarkRunner {
             eval,
             test_boards,
             network_inputs,
+            results: RefCell::new(Vec::new()),
         })
     }
 
@@ -454,20 +942,56 @@ impl BenchmarkRunner {
             total_time_ms,
             avg_time_us,
             ops_per_sec,
+            nodes: None,
         }
     }
 
+    /// Keeps `result` for the report and returns it.
+    fn record(&self, result: BenchmarkResult) -> BenchmarkResult {
+        self.results.borrow_mut().push(result.clone());
+        result
+    }
+
+    /// Returns a JSON report of every benchmark run since the runner was
+    /// created or last cleared, with the engine build and the device it ran
+    /// on, so that reports from different users can be compared.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the report cannot be serialized.
+    pub fn report_json(&self) -> Result<String, JsValue> {
+        let navigator = web_sys::window().map(|window| window.navigator());
+        let results = self.results.borrow();
+        let report = BenchmarkReport {
+            engine_version: env!("CARGO_PKG_VERSION"),
+            simd: cfg!(target_feature = "simd128"),
+            bitboard_backend: reversi_core::cpu::bitboard_backend(),
+            user_agent: navigator.as_ref().and_then(|n| n.user_agent().ok()),
+            hardware_concurrency: navigator.as_ref().map(|n| n.hardware_concurrency()),
+            node_signature: results.iter().filter_map(|r| r.nodes).sum(),
+            results: &results,
+        };
+        serde_json::to_string_pretty(&report).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Forgets the results collected for the report.
+    pub fn clear_results(&self) {
+        self.results.borrow_mut().clear();
+    }
+
     /// Benchmarks move generation performance.
     pub fn bench_move_generation(&self, iterations: u32) -> BenchmarkResult {
         let boards = &self.test_boards;
 
-        Self::measure_benchmark("Move Generation", iterations, boards.len() as u32, || {
-            for board in boards {
-                let moves = MoveList::new(board);
-                // Force evaluation to prevent optimization
-                let _ = moves.count();
-            }
-        })
+        let result =
+            Self::measure_benchmark("Move Generation", iterations, boards.len() as u32, || {
+                for board in boards {
+                    let moves = MoveList::new(board);
+                    // Force evaluation to prevent optimization
+                    let _ = moves.count();
+                }
+            });
+        self.record(result)
     }
 
     /// Benchmarks neural network evaluation performance.
@@ -476,7 +1000,7 @@ impl BenchmarkRunner {
         let tt = Rc::new(TranspositionTable::new(DEFAULT_TT_MB));
         let eval = Rc::clone(&self.eval);
 
-        Self::measure_benchmark(
+        let result = Self::measure_benchmark(
             "Neural Network Evaluation",
             iterations,
             boards.len() as u32,
@@ -493,7 +1017,8 @@ impl BenchmarkRunner {
                     let _ = eval.evaluate(&ctx, board);
                 }
             },
-        )
+        );
+        self.record(result)
     }
 
     /// Returns how many positions are evaluated by one network-forward iteration.
@@ -522,20 +1047,27 @@ impl BenchmarkRunner {
     }
 
     /// Benchmarks search performance at the given fixed depth.
+    ///
+    /// Searches the first midgame test position: the opening position is
+    /// answered with a random move without searching.
     pub fn bench_search(&self, depth: u8, iterations: u32) -> BenchmarkResult {
         let tt = Rc::new(TranspositionTable::new(DEFAULT_TT_MB));
         let mut search = Search::new(Rc::clone(&tt), Rc::clone(&self.eval));
-        let board = Board::new();
+        let board = self.test_boards[1];
         let level = Level {
             mid_depth: depth as Depth,
             end_depth: depth as Depth,
             perfect_depth: depth as Depth,
         };
 
-        Self::measure_benchmark(&format!("Search (depth {})", depth), iterations, 1, || {
-            let _ = search.run(&board, level, MIDGAME_SELECTIVITY, None);
-            tt.clear();
-        })
+        let mut nodes = 0;
+        let mut result =
+            Self::measure_benchmark(&format!("Search (depth {})", depth), iterations, 1, || {
+                nodes = search.run(&board, level, MIDGAME_SELECTIVITY, None).n_nodes;
+                tt.clear();
+            });
+        result.nodes = Some(nodes);
+        self.record(result)
     }
 
     /// Benchmarks endgame search performance using FFO #40 and #41.
@@ -566,8 +1098,11 @@ impl BenchmarkRunner {
         // Store results for logging outside the benchmark
         let mut result_40_opt: Option<(SearchResult, f64)> = None;
         let mut result_41_opt: Option<(SearchResult, f64)> = None;
+        // Later iterations start from a warm table, so only the first has a
+        // fixed node count
+        let mut first_nodes = None;
 
-        let benchmark_result = Self::measure_benchmark(
+        let mut benchmark_result = Self::measure_benchmark(
             "Endgame Search (FFO #40-41)",
             iterations,
             2, // 2 positions per iteration
@@ -578,12 +1113,14 @@ impl BenchmarkRunner {
                 let start_40 = perf.now();
                 let result_40 = search.run(&board_40, level_40, MIDGAME_SELECTIVITY, None);
                 let elapsed_40 = perf.now() - start_40;
+                let nodes_40 = result_40.n_nodes;
                 result_40_opt = Some((result_40, elapsed_40));
 
                 // FFO #41
                 let start_41 = perf.now();
                 let result_41 = search.run(&board_41, level_41, MIDGAME_SELECTIVITY, None);
                 let elapsed_41 = perf.now() - start_41;
+                first_nodes.get_or_insert(nodes_40 + result_41.n_nodes);
                 result_41_opt = Some((result_41, elapsed_41));
             },
         );
@@ -613,14 +1150,15 @@ impl BenchmarkRunner {
             ).into());
         }
 
-        benchmark_result
+        benchmark_result.nodes = first_nodes;
+        self.record(benchmark_result)
     }
 
     /// Benchmarks perft (performance test) for move generation.
     pub fn bench_perft(&self, depth: u32, iterations: u32) -> BenchmarkResult {
         let board = Board::new();
 
-        let result =
+        let mut result =
             Self::measure_benchmark(&format!("Perft (depth {})", depth), iterations, 1, || {
                 let mut pattern_features = PatternFeatures::new(&board, 0);
                 let side_to_move = SideToMove::Player;
@@ -632,7 +1170,8 @@ impl BenchmarkRunner {
         let nodes = Self::perft(&board, &mut pattern_features, 0, SideToMove::Player, depth);
         web_sys::console::log_1(&format!("Perft depth {} - Nodes: {}", depth, nodes).into());
 
-        result
+        result.nodes = Some(nodes);
+        self.record(result)
     }
 
     /// Recursively counts leaf nodes for perft.
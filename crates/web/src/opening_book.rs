@@ -0,0 +1,72 @@
+//! Small opening book compiled into the module.
+//!
+//! The lines in `opening_book.txt` all start with f5; the positions after
+//! the other first moves are probed through the symmetries that keep the
+//! starting position unchanged.
+
+use reversi_core::board::Board;
+use reversi_core::book::Book;
+use reversi_core::formats::GameRecord;
+use reversi_core::square::Square;
+
+const OPENING_LINES: &str = include_str!("opening_book.txt");
+
+/// Board symmetries keeping the starting position unchanged. Each is its
+/// own inverse.
+const SYMMETRIES: [fn(&Board) -> Board; 4] = [
+    |board| *board,
+    Board::rotate_180_clockwise,
+    Board::flip_diag_a1h8,
+    Board::flip_diag_a8h1,
+];
+
+/// Builds the book from the embedded opening lines.
+///
+/// # Panics
+///
+/// Panics if a line is not a legal transcript.
+pub fn build() -> Book {
+    let mut book = Book::new();
+    for line in OPENING_LINES.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = GameRecord::parse(line).expect("invalid opening line");
+        book.add_game(&record, record.moves.len())
+            .expect("illegal opening line");
+    }
+    book
+}
+
+/// Chooses a book move for `board`, weighted by how many lines play it.
+pub fn choose_move(book: &Book, board: &Board) -> Option<Square> {
+    let mut rng = rand::rng();
+    SYMMETRIES.iter().find_map(|symmetry| {
+        let sq = book.choose_move(&symmetry(board), &mut rng)?;
+        let mirrored = Board::from_bitboards(sq, 0u64);
+        symmetry(&mirrored).player().lsb_square()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_book_covers_every_first_move() {
+        let book = build();
+        assert!(!book.is_empty());
+
+        let board = Board::new();
+        for _ in 0..16 {
+            let sq = choose_move(&book, &board).unwrap();
+            assert!(board.try_make_move(sq).is_some());
+        }
+        for first in [Square::F5, Square::D3, Square::C4, Square::E6] {
+            let next = board.make_move(first);
+            let reply = choose_move(&book, &next).unwrap();
+            assert!(next.try_make_move(reply).is_some(), "{first} {reply}");
+        }
+    }
+}
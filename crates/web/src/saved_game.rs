@@ -0,0 +1,138 @@
+//! JSON form of a [`crate::Game`] for browser storage.
+//!
+//! Positions are stored as OBF board headers (`<board64> <side>`), and every
+//! move keeps the position before it, so a game is restored exactly as it
+//! was, takeback included, without replaying moves.
+
+use reversi_core::board::Board;
+use reversi_core::disc::Disc;
+use reversi_core::obf::{self, ObfPosition};
+use reversi_core::square::Square;
+use serde::{Deserialize, Serialize};
+
+/// A saved game.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedGame {
+    /// Current position
+    pub position: String,
+    pub human_player: SavedColor,
+    /// Midgame search depth of the AI
+    pub level: u8,
+    /// Time limit of the AI's searches, if any
+    #[serde(default)]
+    pub time_limit_ms: Option<u32>,
+    /// Moves played, oldest first
+    pub history: Vec<SavedMove>,
+    /// Moves taken back, most recent last
+    #[serde(default)]
+    pub redo: Vec<SavedMove>,
+}
+
+/// A move and the position it was played from.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedMove {
+    pub position: String,
+    #[serde(rename = "move")]
+    pub sq: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SavedColor {
+    Black,
+    White,
+}
+
+impl From<Disc> for SavedColor {
+    fn from(disc: Disc) -> Self {
+        if disc == Disc::White {
+            SavedColor::White
+        } else {
+            SavedColor::Black
+        }
+    }
+}
+
+impl From<SavedColor> for Disc {
+    fn from(color: SavedColor) -> Self {
+        match color {
+            SavedColor::Black => Disc::Black,
+            SavedColor::White => Disc::White,
+        }
+    }
+}
+
+/// Encodes a position with `side_to_move` to play.
+pub fn encode_position(board: &Board, side_to_move: Disc) -> String {
+    obf::format_board_header(board, side_to_move)
+}
+
+/// Decodes a position encoded by [`encode_position`].
+///
+/// # Errors
+///
+/// Returns an error if `position` is not an OBF board header.
+pub fn decode_position(position: &str) -> Result<(Board, Disc), String> {
+    let parsed = ObfPosition::parse(position)?.ok_or("Empty position")?;
+    Ok((parsed.board, parsed.side_to_move))
+}
+
+impl SavedMove {
+    /// Decodes the move, checking that it is legal in its position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position or move is malformed or the move is
+    /// illegal.
+    pub fn decode(&self) -> Result<(Board, Disc, Square), String> {
+        let (board, side_to_move) = decode_position(&self.position)?;
+        let sq: Square = self
+            .sq
+            .parse()
+            .map_err(|e| format!("Invalid move '{}': {e}", self.sq))?;
+        if board.try_make_move(sq).is_none() {
+            return Err(format!("Illegal move '{}'", self.sq));
+        }
+        Ok((board, side_to_move, sq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_game_round_trip() {
+        let board = Board::new();
+        let saved = SavedGame {
+            position: encode_position(&board.make_move(Square::F5), Disc::White),
+            human_player: SavedColor::Black,
+            level: 7,
+            time_limit_ms: None,
+            history: vec![SavedMove {
+                position: encode_position(&board, Disc::Black),
+                sq: Square::F5.to_string(),
+            }],
+            redo: Vec::new(),
+        };
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(json.contains(r#""humanPlayer":"black""#));
+        assert!(json.contains(r#""move":"f5""#));
+        assert_eq!(serde_json::from_str::<SavedGame>(&json).unwrap(), saved);
+
+        let (decoded, side, sq) = saved.history[0].decode().unwrap();
+        assert_eq!((decoded, side, sq), (board, Disc::Black, Square::F5));
+        let (current, side) = decode_position(&saved.position).unwrap();
+        assert_eq!((current, side), (board.make_move(Square::F5), Disc::White));
+    }
+
+    #[test]
+    fn test_saved_move_rejects_illegal_moves() {
+        let saved = SavedMove {
+            position: encode_position(&Board::new(), Disc::Black),
+            sq: "a1".to_string(),
+        };
+        assert!(saved.decode().is_err());
+    }
+}
@@ -1,9 +1,13 @@
+pub mod abort_signal;
 mod endgame;
 mod endgame_cache;
 pub mod search_context;
+pub mod search_progress;
 pub mod search_result;
 pub(crate) mod search_strategy;
 mod search_task;
+mod stopwatch;
+mod time_budget;
 
 use js_sys::Function;
 use std::rc::Rc;
@@ -16,6 +20,7 @@ use reversi_core::{
     flip,
     probcut::Selectivity,
     search::node_type::{NodeType, NonPV, PV, Root},
+    search::root_move::RootMove,
     square::Square,
     stability,
     types::{Depth, ScaledScore},
@@ -28,7 +33,10 @@ use crate::{
     level::Level,
     move_list::{MoveList, evaluate_moves_fast},
     probcut,
-    search::{search_context::SearchContext, search_result::SearchResult, search_task::SearchTask},
+    search::{
+        abort_signal::AbortSignal, search_context::SearchContext, search_progress::SearchPhase,
+        search_result::SearchResult, search_task::SearchTask, time_budget::TimeBudget,
+    },
 };
 
 /// Depth threshold for switching from midgame to endgame search.
@@ -38,6 +46,10 @@ pub const DEPTH_MIDGAME_TO_ENDGAME: Depth = 12;
 pub struct Search {
     tt: Rc<TranspositionTable>,
     eval: Rc<Eval>,
+    abort_signal: Rc<AbortSignal>,
+    time_limit_ms: Option<u32>,
+    progress_interval_ms: u32,
+    multi_pv: usize,
 }
 
 impl Search {
@@ -46,10 +58,55 @@ impl Search {
         // Ensure probcut tables are initialized.
         probcut::init();
 
-        Search { tt, eval }
+        Search {
+            tt,
+            eval,
+            abort_signal: Rc::new(AbortSignal::default()),
+            time_limit_ms: None,
+            progress_interval_ms: 0,
+            multi_pv: 1,
+        }
+    }
+
+    /// Sends progress reports at most once every `interval_ms` milliseconds;
+    /// the final report of each search is always sent.
+    pub fn set_progress_interval_ms(&mut self, interval_ms: u32) {
+        self.progress_interval_ms = interval_ms;
+    }
+
+    /// Searches the best `multi_pv` moves, each reported as its own line.
+    /// Only searches with a progress callback have lines to report, so the
+    /// others keep searching the best move alone.
+    pub fn set_multi_pv(&mut self, multi_pv: usize) {
+        self.multi_pv = multi_pv.max(1);
+    }
+
+    /// Limits each search to `time_limit_ms` milliseconds of wall-clock
+    /// time, on top of the level's depths; `None` removes the limit.
+    pub fn set_time_limit_ms(&mut self, time_limit_ms: Option<u32>) {
+        self.time_limit_ms = time_limit_ms;
+    }
+
+    /// Replaces the evaluator used by subsequent searches.
+    pub fn set_eval(&mut self, eval: Rc<Eval>) {
+        self.eval = eval;
+    }
+
+    /// Replaces the transposition table used by subsequent searches.
+    pub fn set_tt(&mut self, tt: Rc<TranspositionTable>) {
+        self.tt = tt;
+    }
+
+    /// Returns the signal that aborts the running search. Requests made
+    /// before a search starts are cleared by it.
+    pub fn abort_signal(&self) -> &AbortSignal {
+        &self.abort_signal
     }
 
     /// Runs a search on the given position and returns the best move and score.
+    ///
+    /// An aborted search returns the best move of its last completed
+    /// iteration.
     pub fn run(
         &mut self,
         board: &Board,
@@ -58,6 +115,12 @@ impl Search {
         progress_callback: Option<Function>,
     ) -> SearchResult {
         self.tt.increment_generation();
+        self.abort_signal.reset();
+        let multi_pv = if progress_callback.is_some() {
+            self.multi_pv
+        } else {
+            1
+        };
         let task = SearchTask {
             board: *board,
             level,
@@ -65,10 +128,30 @@ impl Search {
             tt: Rc::clone(&self.tt),
             eval: Rc::clone(&self.eval),
             progress_callback,
+            abort_signal: Rc::clone(&self.abort_signal),
+            time_limit_ms: self.time_limit_ms,
+            progress_interval_ms: self.progress_interval_ms,
+            multi_pv,
         };
         search_root(task)
     }
 
+    /// Solves `board` exactly, ignoring the time limit. The score is proven
+    /// only if the result's selectivity is [`Selectivity::None`]; an aborted
+    /// solve returns its last completed, selective, stage.
+    pub fn solve(&mut self, board: &Board, progress_callback: Option<Function>) -> SearchResult {
+        let n_empties = board.get_empty_count() as Depth;
+        let level = Level {
+            mid_depth: n_empties,
+            end_depth: n_empties,
+            perfect_depth: n_empties,
+        };
+        let time_limit_ms = self.time_limit_ms.take();
+        let result = self.run(board, level, Selectivity::None, progress_callback);
+        self.time_limit_ms = time_limit_ms;
+        result
+    }
+
     /// Clears the transposition table to reset search state.
     #[allow(dead_code)]
     pub fn init(&mut self) {
@@ -87,6 +170,12 @@ pub fn search_root(task: SearchTask) -> SearchResult {
         task.eval.clone(),
         task.progress_callback,
     );
+    ctx.set_abort_signal(task.abort_signal);
+    ctx.start_progress(task.progress_interval_ms);
+    ctx.set_multi_pv(task.multi_pv);
+    if let Some(time_limit_ms) = task.time_limit_ms {
+        ctx.set_time_budget(TimeBudget::start(time_limit_ms));
+    }
     if ctx.root_moves.is_empty() {
         // Handle no legal moves
         return SearchResult {
@@ -121,7 +210,6 @@ pub fn search_root(task: SearchTask) -> SearchResult {
 /// Performs the root search for midgame positions using iterative deepening.
 fn search_root_midgame(board: Board, ctx: &mut SearchContext, level: Level) -> SearchResult {
     const INITIAL_DELTA: ScaledScore = ScaledScore::from_raw(3 * ScaledScore::SCALE);
-    let mut best_score = ScaledScore::ZERO;
     let mut alpha = -ScaledScore::INF;
     let mut beta = ScaledScore::INF;
 
@@ -138,37 +226,76 @@ fn search_root_midgame(board: Board, ctx: &mut SearchContext, level: Level) -> S
     }
 
     let org_selectivity = ctx.selectivity;
+    let pv_count = ctx.pv_count();
     let start_depth = if max_depth.is_multiple_of(2) { 2 } else { 1 };
     let mut depth = start_depth;
-    while depth <= max_depth {
+    // Move and score of each line, and depth of the last completed iteration
+    let mut completed = None;
+    while depth <= max_depth && (completed.is_none() || ctx.allows_next_iteration()) {
         let depth_diff = (max_depth - depth) as u8;
         ctx.selectivity = Selectivity::from_u8(org_selectivity.as_u8().saturating_sub(depth_diff));
 
-        let mut delta = INITIAL_DELTA;
         if depth <= 8 {
             alpha = -ScaledScore::INF;
             beta = ScaledScore::INF;
         }
 
-        loop {
-            best_score = search::<Root, MidGameStrategy>(ctx, &board, depth, alpha, beta);
+        let mut lines: Vec<(RootMove, ScaledScore)> = Vec::with_capacity(pv_count);
+        for pv_idx in 0..pv_count {
+            ctx.set_pv_idx(pv_idx);
+            let (mut line_alpha, mut line_beta) = match lines.last() {
+                Some((_, score)) => initial_line_window(*score),
+                None => (alpha, beta),
+            };
+            let mut delta = INITIAL_DELTA;
+
+            let mut line_score;
+            loop {
+                line_score =
+                    search::<Root, MidGameStrategy>(ctx, &board, depth, line_alpha, line_beta);
+                if ctx.is_aborted() {
+                    break;
+                }
 
-            if best_score <= alpha {
-                beta = alpha;
-                alpha = (best_score - delta).max(-ScaledScore::INF);
-            } else if best_score >= beta {
-                alpha = (beta - delta).max(alpha);
-                beta = (best_score + delta).min(ScaledScore::INF);
-            } else {
+                if line_score <= line_alpha {
+                    line_beta = line_alpha;
+                    line_alpha = (line_score - delta).max(-ScaledScore::INF);
+                } else if line_score >= line_beta {
+                    line_alpha = (line_beta - delta).max(line_alpha);
+                    line_beta = (line_score + delta).min(ScaledScore::INF);
+                } else {
+                    break;
+                }
+
+                delta += delta / 2;
+            }
+            if ctx.is_aborted() {
                 break;
             }
 
-            delta += delta / 2;
+            let line_move = ctx.get_pv_root_move().unwrap();
+            if pv_count > 1 {
+                ctx.promote_pv_root_move();
+            }
+            ctx.notify_progress(
+                depth,
+                line_score.to_disc_diff_f32(),
+                &line_move,
+                ctx.selectivity,
+                SearchPhase::Midgame,
+                pv_idx,
+                false,
+            );
+            lines.push((line_move, line_score));
+        }
+        if ctx.is_aborted() {
+            break;
         }
 
-        let best_move = ctx.get_best_root_move().unwrap();
+        let best_move = &lines[0].0;
         alpha = (best_move.average_score - INITIAL_DELTA).max(-ScaledScore::INF);
         beta = (best_move.average_score + INITIAL_DELTA).min(ScaledScore::INF);
+        completed = Some((lines, depth));
 
         if depth <= 10 {
             depth += 2;
@@ -177,18 +304,30 @@ fn search_root_midgame(board: Board, ctx: &mut SearchContext, level: Level) -> S
         }
     }
 
-    let rm = ctx.get_best_root_move().unwrap();
-    ctx.notify_progress(
-        max_depth,
-        best_score.to_disc_diff_f32(),
-        rm.sq,
-        ctx.selectivity,
-    );
+    ctx.set_pv_idx(0);
+    let (lines, depth) = completed.unwrap_or_else(|| {
+        (
+            vec![(ctx.get_best_root_move().unwrap(), ScaledScore::ZERO)],
+            0,
+        )
+    });
+    for (pv_idx, (rm, score)) in lines.iter().enumerate() {
+        ctx.notify_progress(
+            depth,
+            score.to_disc_diff_f32(),
+            rm,
+            ctx.selectivity,
+            SearchPhase::Midgame,
+            pv_idx,
+            true,
+        );
+    }
+    let (rm, best_score) = &lines[0];
     SearchResult {
         score: best_score.to_disc_diff_f32(),
         best_move: Some(rm.sq),
         n_nodes: ctx.n_nodes,
-        depth: max_depth,
+        depth,
         selectivity: ctx.selectivity,
     }
 }
@@ -203,50 +342,121 @@ fn search_root_endgame(board: &Board, ctx: &mut SearchContext, level: Level) ->
         Selectivity::None
     };
 
-    let mut best_score = ScaledScore::ZERO;
     let mut alpha = score - ScaledScore::from_disc_diff(5);
     let mut beta = score + ScaledScore::from_disc_diff(5);
+    let pv_count = ctx.pv_count();
+    // Move and score of each line, and selectivity of the last completed
+    // iteration
+    let mut completed = None;
 
     for selectivity in 0..=final_selectivity.as_u8() {
+        if completed.is_some() && !ctx.allows_next_iteration() {
+            break;
+        }
         ctx.selectivity = Selectivity::from_u8(selectivity);
-        let mut delta = ScaledScore::from_disc_diff(3);
-
-        loop {
-            best_score = search::<Root, EndGameStrategy>(ctx, board, n_empties, alpha, beta);
-
-            if best_score <= alpha {
-                beta = alpha;
-                alpha = (best_score - delta).max(-ScaledScore::INF);
-            } else if best_score >= beta {
-                alpha = (beta - delta).max(alpha);
-                beta = (best_score + delta).min(ScaledScore::INF);
-            } else {
+
+        let mut lines: Vec<(RootMove, ScaledScore)> = Vec::with_capacity(pv_count);
+        for pv_idx in 0..pv_count {
+            ctx.set_pv_idx(pv_idx);
+            let (mut line_alpha, mut line_beta) = match lines.last() {
+                Some((_, score)) => initial_line_window(*score),
+                None => (alpha, beta),
+            };
+            let mut delta = ScaledScore::from_disc_diff(3);
+
+            let mut line_score;
+            loop {
+                line_score =
+                    search::<Root, EndGameStrategy>(ctx, board, n_empties, line_alpha, line_beta);
+                if ctx.is_aborted() {
+                    break;
+                }
+
+                if line_score <= line_alpha {
+                    line_beta = line_alpha;
+                    line_alpha = (line_score - delta).max(-ScaledScore::INF);
+                } else if line_score >= line_beta {
+                    line_alpha = (line_beta - delta).max(line_alpha);
+                    line_beta = (line_score + delta).min(ScaledScore::INF);
+                } else {
+                    break;
+                }
+
+                delta += delta;
+            }
+            if ctx.is_aborted() {
                 break;
             }
 
-            delta += delta;
+            let line_move = ctx.get_pv_root_move().unwrap();
+            if pv_count > 1 {
+                ctx.promote_pv_root_move();
+            }
+            ctx.notify_progress(
+                n_empties,
+                line_score.to_disc_diff_f32(),
+                &line_move,
+                ctx.selectivity,
+                SearchPhase::Endgame,
+                pv_idx,
+                false,
+            );
+            lines.push((line_move, line_score));
         }
+        if ctx.is_aborted() {
+            break;
+        }
+
+        let best_score = lines[0].1;
+        completed = Some((lines, ctx.selectivity));
 
         alpha = (best_score - ScaledScore::from_disc_diff(2)).max(-ScaledScore::INF);
         beta = (best_score + ScaledScore::from_disc_diff(2)).min(ScaledScore::INF);
     }
 
-    let rm = ctx.get_best_root_move().unwrap();
-    ctx.notify_progress(
-        n_empties,
-        best_score.to_disc_diff_f32(),
-        rm.sq,
-        ctx.selectivity,
-    );
+    ctx.set_pv_idx(0);
+    let (lines, selectivity) = completed.unwrap_or_else(|| {
+        // Stopped before any iteration finished: the move found by the
+        // aspiration estimate is the best guess left.
+        let tt_move = ctx.tt.probe(board.hash()).best_move();
+        let rm = ctx
+            .root_moves
+            .iter()
+            .find(|rm| rm.sq == tt_move)
+            .cloned()
+            .unwrap_or_else(|| ctx.get_best_root_move().unwrap());
+        (vec![(rm, ScaledScore::ZERO)], Selectivity::Level1)
+    });
+    for (pv_idx, (rm, score)) in lines.iter().enumerate() {
+        ctx.notify_progress(
+            n_empties,
+            score.to_disc_diff_f32(),
+            rm,
+            selectivity,
+            SearchPhase::Endgame,
+            pv_idx,
+            true,
+        );
+    }
+    let (rm, best_score) = &lines[0];
     SearchResult {
         score: best_score.to_disc_diff_f32(),
         best_move: Some(rm.sq),
         n_nodes: ctx.n_nodes,
         depth: level.end_depth,
-        selectivity: ctx.selectivity,
+        selectivity,
     }
 }
 
+/// Returns the aspiration window of a MultiPV line after the best one,
+/// which scores no higher than `previous_line_score`.
+fn initial_line_window(previous_line_score: ScaledScore) -> (ScaledScore, ScaledScore) {
+    (
+        -ScaledScore::INF,
+        (previous_line_score + ScaledScore::from_raw(1)).min(ScaledScore::INF),
+    )
+}
+
 /// Estimates a base score to center the aspiration window for endgame search.
 fn estimate_aspiration_base_score(
     ctx: &mut SearchContext,
@@ -330,7 +540,9 @@ pub fn search<NT: NodeType, SS: SearchStrategy>(
         } else {
             return board.solve_scaled(n_empties);
         }
-    } else if let Some(sq) = move_list.wipeout_move() {
+    } else if let Some(sq) = move_list.wipeout_move()
+        && !(NT::ROOT_NODE && ctx.is_excluded_root_move(sq))
+    {
         if NT::ROOT_NODE {
             ctx.update_root_move(sq, ScaledScore::MAX, 1, alpha);
         } else if NT::PV_NODE {
@@ -382,6 +594,9 @@ pub fn search<NT: NodeType, SS: SearchStrategy>(
 
     let mut move_count = 0;
     for mv in move_list.iter() {
+        if NT::ROOT_NODE && ctx.is_excluded_root_move(mv.sq) {
+            continue;
+        }
         move_count += 1;
 
         let next = board.make_move_with_flipped(mv.flipped, mv.sq);
@@ -399,6 +614,10 @@ pub fn search<NT: NodeType, SS: SearchStrategy>(
 
         ctx.undo(mv.sq);
 
+        if ctx.is_aborted() {
+            return ScaledScore::ZERO;
+        }
+
         if NT::ROOT_NODE {
             ctx.update_root_move(mv.sq, score, move_count, alpha);
         }
@@ -422,6 +641,11 @@ pub fn search<NT: NodeType, SS: SearchStrategy>(
         }
     }
 
+    if NT::ROOT_NODE && ctx.pv_idx() > 0 {
+        // The score leaves out the moves of better lines.
+        return best_score;
+    }
+
     ctx.tt.store(
         tt_probe_result.index(),
         tt_key,
@@ -0,0 +1,50 @@
+use js_sys::{Atomics, Int32Array};
+use std::cell::{Cell, RefCell};
+
+/// Abort request for the running search.
+///
+/// The worker running a search cannot handle messages until the search
+/// returns, so besides [`Self::request`] the signal can watch the first
+/// element of an `Int32Array` over a `SharedArrayBuffer`: any non-zero value
+/// stored there by another thread aborts the search.
+#[derive(Default)]
+pub struct AbortSignal {
+    /// Abort requested from this thread.
+    requested: Cell<bool>,
+    /// Flag shared with the thread driving the UI, if any.
+    shared: RefCell<Option<Int32Array>>,
+}
+
+impl AbortSignal {
+    /// Requests the running search to stop.
+    pub fn request(&self) {
+        self.requested.set(true);
+        if let Some(shared) = self.shared.borrow().as_ref() {
+            let _ = Atomics::store(shared, 0, 1);
+        }
+    }
+
+    /// Clears any earlier request before a new search starts.
+    pub fn reset(&self) {
+        self.requested.set(false);
+        if let Some(shared) = self.shared.borrow().as_ref() {
+            let _ = Atomics::store(shared, 0, 0);
+        }
+    }
+
+    /// Returns `true` if an abort has been requested.
+    pub fn is_requested(&self) -> bool {
+        if self.requested.get() {
+            return true;
+        }
+        self.shared
+            .borrow()
+            .as_ref()
+            .is_some_and(|shared| Atomics::load(shared, 0).is_ok_and(|value| value != 0))
+    }
+
+    /// Sets the shared flag to watch, or stops watching one.
+    pub fn set_shared(&self, shared: Option<Int32Array>) {
+        *self.shared.borrow_mut() = shared;
+    }
+}
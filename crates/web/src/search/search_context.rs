@@ -1,4 +1,4 @@
-use js_sys::{Function, Object, Reflect};
+use js_sys::Function;
 use std::rc::Rc;
 
 use reversi_core::{
@@ -13,11 +13,21 @@ use reversi_core::{
     types::{Depth, ScaledScore, Scoref},
 };
 
+use crate::search::{
+    abort_signal::AbortSignal,
+    search_progress::{SearchPhase, SearchProgress},
+    stopwatch::Stopwatch,
+    time_budget::TimeBudget,
+};
 use crate::transposition_table::TranspositionTable;
 
 use crate::{eval::Eval, move_list::MoveList};
 use wasm_bindgen::JsValue;
 
+/// Nodes searched between polls of the abort signal and the clock, minus
+/// one.
+const ABORT_POLL_MASK: u64 = 4095;
+
 /// Maintains all mutable state during a single search operation.
 pub struct SearchContext {
     /// Number of nodes searched so far.
@@ -34,10 +44,30 @@ pub struct SearchContext {
     pub eval: Rc<Eval>,
     /// Root moves being searched.
     pub root_moves: Vec<RootMove>,
+    /// Number of MultiPV lines to search.
+    multi_pv: usize,
+    /// Index of the MultiPV line being searched; the root moves before it
+    /// lead better lines and are skipped.
+    pv_idx: usize,
     /// Pattern features for neural network input.
     pub pattern_features: PatternFeatures,
     /// Optional callback for reporting progress to the JavaScript UI.
     progress_callback: Option<Function>,
+    /// Time since the search started, for nodes per second and throttling.
+    stopwatch: Option<Stopwatch>,
+    /// Minimum milliseconds between two progress reports.
+    progress_interval_ms: f64,
+    /// When the last progress report was sent, in milliseconds since start.
+    last_progress_ms: Option<f64>,
+    /// Whether the report of the current iteration's best line was sent, so
+    /// that its other lines are reported along with it.
+    reporting_lines: bool,
+    /// Signal polled while searching, if the search can be aborted.
+    abort_signal: Option<Rc<AbortSignal>>,
+    /// Time the search may take, if limited.
+    time_budget: Option<TimeBudget>,
+    /// Whether the search has been aborted.
+    aborted: bool,
     /// Search stack for PV and state at each ply.
     stack: [StackRecord; MAX_PLY],
 }
@@ -62,14 +92,84 @@ impl SearchContext {
             tt,
             eval,
             root_moves: Self::create_root_moves(board),
+            multi_pv: 1,
+            pv_idx: 0,
             pattern_features: PatternFeatures::new(board, ply),
             progress_callback,
+            stopwatch: None,
+            progress_interval_ms: 0.0,
+            last_progress_ms: None,
+            reporting_lines: false,
+            abort_signal: None,
+            time_budget: None,
+            aborted: false,
             stack: [StackRecord {
                 pv: [Square::None; MAX_PLY],
             }; MAX_PLY],
         }
     }
 
+    /// Starts timing the search for progress reports, which are sent at most
+    /// once every `interval_ms` milliseconds apart from the final one.
+    pub fn start_progress(&mut self, interval_ms: u32) {
+        self.stopwatch = Some(Stopwatch::start());
+        self.progress_interval_ms = interval_ms as f64;
+    }
+
+    /// Searches the best `multi_pv` moves, each with its own line, instead
+    /// of only the best one.
+    pub fn set_multi_pv(&mut self, multi_pv: usize) {
+        self.multi_pv = multi_pv.max(1);
+    }
+
+    /// Returns the number of MultiPV lines to search.
+    pub fn pv_count(&self) -> usize {
+        self.multi_pv.min(self.root_moves.len())
+    }
+
+    /// Starts searching MultiPV line `pv_idx`.
+    pub fn set_pv_idx(&mut self, pv_idx: usize) {
+        self.pv_idx = pv_idx;
+    }
+
+    /// Returns the index of the MultiPV line being searched.
+    #[inline]
+    pub fn pv_idx(&self) -> usize {
+        self.pv_idx
+    }
+
+    /// Returns `true` if `sq` leads a better MultiPV line than the one being
+    /// searched.
+    #[inline]
+    pub fn is_excluded_root_move(&self, sq: Square) -> bool {
+        self.root_moves[..self.pv_idx].iter().any(|rm| rm.sq == sq)
+    }
+
+    /// Makes the search stop once `signal` is requested.
+    pub fn set_abort_signal(&mut self, signal: Rc<AbortSignal>) {
+        self.abort_signal = Some(signal);
+    }
+
+    /// Makes the search stop once `budget` is used up.
+    pub fn set_time_budget(&mut self, budget: TimeBudget) {
+        self.time_budget = Some(budget);
+    }
+
+    /// Returns `true` unless the time budget is too far spent to start
+    /// another iterative deepening iteration.
+    pub fn allows_next_iteration(&self) -> bool {
+        self.time_budget
+            .as_ref()
+            .is_none_or(TimeBudget::allows_next_iteration)
+    }
+
+    /// Returns `true` if the search has been aborted; scores returned since
+    /// then are meaningless.
+    #[inline]
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
     /// Switches the side to move.
     #[inline]
     fn switch_players(&mut self) {
@@ -129,6 +229,22 @@ impl SearchContext {
     #[inline]
     pub fn increment_nodes(&mut self) {
         self.n_nodes += 1;
+        if self.n_nodes & ABORT_POLL_MASK == 0 {
+            self.poll_abort();
+        }
+    }
+
+    /// Aborts the search if requested or out of time.
+    #[cold]
+    fn poll_abort(&mut self) {
+        self.aborted |= self
+            .abort_signal
+            .as_ref()
+            .is_some_and(|signal| signal.is_requested())
+            || self
+                .time_budget
+                .as_ref()
+                .is_some_and(TimeBudget::is_exhausted);
     }
 
     /// Returns the current pattern feature for neural network evaluation.
@@ -182,6 +298,28 @@ impl SearchContext {
         self.root_moves.iter().max_by_key(|rm| rm.score).cloned()
     }
 
+    /// Returns the best root move of the MultiPV line being searched.
+    pub fn get_pv_root_move(&self) -> Option<RootMove> {
+        self.root_moves[self.pv_idx..]
+            .iter()
+            .max_by_key(|rm| rm.score)
+            .cloned()
+    }
+
+    /// Moves the best root move of the current line to `pv_idx`, so the
+    /// following lines skip it.
+    pub fn promote_pv_root_move(&mut self) {
+        let pv_idx = self.pv_idx;
+        if let Some(offset) = self.root_moves[pv_idx..]
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, rm)| rm.score)
+            .map(|(offset, _)| offset)
+        {
+            self.root_moves[pv_idx..=pv_idx + offset].rotate_right(1);
+        }
+    }
+
     /// Creates the initial list of root moves from the board's legal moves.
     fn create_root_moves(board: &Board) -> Vec<RootMove> {
         let move_list = MoveList::new(board);
@@ -212,42 +350,60 @@ impl SearchContext {
         self.stack[self.ply()].pv.fill(Square::None);
     }
 
-    /// Sends search progress to the JavaScript UI via the registered callback.
+    /// Sends search progress of MultiPV line `pv_idx` to the JavaScript UI
+    /// via the registered callback, unless a report went out less than the
+    /// progress interval ago. Other lines follow the best line of their
+    /// iteration; the final reports of a search are always sent.
+    #[allow(clippy::too_many_arguments)]
     pub fn notify_progress(
-        &self,
+        &mut self,
         depth: Depth,
         score: Scoref,
-        best_move: Square,
+        best_move: &RootMove,
         selectivity: Selectivity,
+        phase: SearchPhase,
+        pv_idx: usize,
+        is_final: bool,
     ) {
         let Some(callback) = &self.progress_callback else {
             return;
         };
 
-        let payload = Object::new();
-        let _ = Reflect::set(&payload, &JsValue::from_str("depth"), &JsValue::from(depth));
-        let _ = Reflect::set(
-            &payload,
-            &JsValue::from_str("score"),
-            &JsValue::from_f64(score as f64),
-        );
-        let _ = Reflect::set(
-            &payload,
-            &JsValue::from_str("probcut"),
-            &JsValue::from_f64(selectivity.probability() as f64),
-        );
-        let _ = Reflect::set(
-            &payload,
-            &JsValue::from_str("nodes"),
-            &JsValue::from_f64(self.n_nodes as f64),
-        );
-        let best_move_value = if best_move == Square::None {
-            JsValue::NULL
+        let elapsed_ms = self.stopwatch.as_ref().map_or(0.0, Stopwatch::elapsed_ms);
+        if !is_final {
+            if pv_idx == 0 {
+                self.reporting_lines = self
+                    .last_progress_ms
+                    .is_none_or(|last_ms| elapsed_ms - last_ms >= self.progress_interval_ms);
+            }
+            if !self.reporting_lines {
+                return;
+            }
+        }
+        self.last_progress_ms = Some(elapsed_ms);
+
+        let pv = if best_move.pv.first() == Some(&best_move.sq) {
+            best_move.pv.iter().map(Square::to_string).collect()
         } else {
-            JsValue::from(best_move.to_string())
+            vec![best_move.sq.to_string()]
         };
-        let _ = Reflect::set(&payload, &JsValue::from_str("bestMove"), &best_move_value);
-
-        let _ = callback.call1(&JsValue::NULL, &payload);
+        let progress = SearchProgress {
+            depth,
+            score,
+            best_move: (best_move.sq != Square::None).then(|| best_move.sq.to_string()),
+            pv,
+            line: pv_idx + 1,
+            probcut: selectivity.probability(),
+            nodes: self.n_nodes,
+            nps: if elapsed_ms > 0.0 {
+                self.n_nodes as f64 * 1000.0 / elapsed_ms
+            } else {
+                0.0
+            },
+            phase,
+        };
+        if let Ok(payload) = serde_wasm_bindgen::to_value(&progress) {
+            let _ = callback.call1(&JsValue::NULL, &payload);
+        }
     }
 }
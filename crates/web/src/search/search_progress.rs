@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+/// Phase of the game a search is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchPhase {
+    Midgame,
+    Endgame,
+}
+
+/// Progress report passed to the JavaScript progress callback.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchProgress {
+    /// Depth of the last completed iteration.
+    pub depth: u32,
+    /// Score of the best move (disc-difference scale).
+    pub score: f32,
+    /// Best move in algebraic notation, or `null` if there is none.
+    pub best_move: Option<String>,
+    /// Principal variation starting with the best move.
+    pub pv: Vec<String>,
+    /// Rank of the line among the MultiPV lines, 1 for the best.
+    pub line: usize,
+    /// ProbCut confidence of the iteration, in percent.
+    pub probcut: i32,
+    /// Nodes searched so far.
+    pub nodes: u64,
+    /// Nodes searched per second so far.
+    pub nps: f64,
+    pub phase: SearchPhase,
+}
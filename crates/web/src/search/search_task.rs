@@ -3,6 +3,7 @@ use std::rc::Rc;
 
 use reversi_core::{board::Board, probcut::Selectivity};
 
+use crate::search::abort_signal::AbortSignal;
 use crate::transposition_table::TranspositionTable;
 
 use crate::{eval::Eval, level::Level};
@@ -21,4 +22,12 @@ pub struct SearchTask {
     pub eval: Rc<Eval>,
     /// Optional JavaScript callback for progress reporting.
     pub progress_callback: Option<Function>,
+    /// Signal that stops the search early.
+    pub abort_signal: Rc<AbortSignal>,
+    /// Wall-clock limit in milliseconds, if any.
+    pub time_limit_ms: Option<u32>,
+    /// Minimum milliseconds between two progress reports.
+    pub progress_interval_ms: u32,
+    /// Number of MultiPV lines to search.
+    pub multi_pv: usize,
 }
@@ -0,0 +1,38 @@
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::Performance;
+
+/// Wall-clock timer measured with `performance.now()`.
+pub struct Stopwatch {
+    /// Clock of the window or worker running the search, if it has one.
+    performance: Option<Performance>,
+    start_ms: f64,
+}
+
+impl Stopwatch {
+    /// Starts a timer at the current time.
+    pub fn start() -> Self {
+        // `performance` lives on the global scope of both windows and
+        // workers; `web_sys::window()` is unavailable in the latter.
+        let performance =
+            js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+                .ok()
+                .and_then(|value| value.dyn_into::<Performance>().ok());
+        let mut stopwatch = Stopwatch {
+            performance,
+            start_ms: 0.0,
+        };
+        stopwatch.start_ms = stopwatch.now_ms();
+        stopwatch
+    }
+
+    fn now_ms(&self) -> f64 {
+        self.performance
+            .as_ref()
+            .map_or_else(js_sys::Date::now, Performance::now)
+    }
+
+    /// Returns the milliseconds elapsed since the timer started.
+    pub fn elapsed_ms(&self) -> f64 {
+        self.now_ms() - self.start_ms
+    }
+}
@@ -0,0 +1,31 @@
+use crate::search::stopwatch::Stopwatch;
+
+/// Share of the budget after which no new iteration is started, since it
+/// would most likely be cut off unfinished.
+const NEXT_ITERATION_RATIO: f64 = 0.5;
+
+/// Wall-clock budget of a single search.
+pub struct TimeBudget {
+    stopwatch: Stopwatch,
+    limit_ms: f64,
+}
+
+impl TimeBudget {
+    /// Starts a budget of `limit_ms` milliseconds.
+    pub fn start(limit_ms: u32) -> Self {
+        TimeBudget {
+            stopwatch: Stopwatch::start(),
+            limit_ms: limit_ms as f64,
+        }
+    }
+
+    /// Returns `true` once the budget is used up.
+    pub fn is_exhausted(&self) -> bool {
+        self.stopwatch.elapsed_ms() >= self.limit_ms
+    }
+
+    /// Returns `true` if there is time left to start another iteration.
+    pub fn allows_next_iteration(&self) -> bool {
+        self.stopwatch.elapsed_ms() < self.limit_ms * NEXT_ITERATION_RATIO
+    }
+}
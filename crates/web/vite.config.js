@@ -26,6 +26,13 @@ function serveWasm() {
   };
 }
 
+// Cross-origin isolation enables SharedArrayBuffer, which the worker needs
+// to see abort requests while it is busy searching.
+const crossOriginIsolationHeaders = {
+  'Cross-Origin-Opener-Policy': 'same-origin',
+  'Cross-Origin-Embedder-Policy': 'require-corp'
+};
+
 export default defineConfig({
   plugins: [
     serveWasm(),
@@ -34,6 +41,7 @@ export default defineConfig({
   ],
   resolve: {
     alias: {
+      '/pkg-scalar': resolve(__dirname, 'pkg-scalar'),
       '/pkg': resolve(__dirname, 'pkg')
     }
   },
@@ -58,7 +66,11 @@ export default defineConfig({
   },
   server: {
     port: 8080,
-    host: '127.0.0.1'
+    host: '127.0.0.1',
+    headers: crossOriginIsolationHeaders
+  },
+  preview: {
+    headers: crossOriginIsolationHeaders
   },
   worker: {
     format: 'es',
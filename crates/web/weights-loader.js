@@ -0,0 +1,65 @@
+// reversi_web/weights-loader.js
+// Fetches evaluation weight files and caches them in IndexedDB, so a network
+// is downloaded once and can be swapped without rebuilding the WASM module.
+
+const DB_NAME = "neural-reversi";
+const STORE_NAME = "weights";
+
+function openDatabase() {
+  return new Promise((resolve, reject) => {
+    const request = indexedDB.open(DB_NAME, 1);
+    request.onupgradeneeded = () => request.result.createObjectStore(STORE_NAME);
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error);
+  });
+}
+
+function runTransaction(db, mode, operation) {
+  return new Promise((resolve, reject) => {
+    const request = operation(db.transaction(STORE_NAME, mode).objectStore(STORE_NAME));
+    request.onsuccess = () => resolve(request.result);
+    request.onerror = () => reject(request.error);
+  });
+}
+
+/**
+ * Loads a weight file, from the IndexedDB cache when possible.
+ *
+ * `integrity` is a Subresource Integrity hash such as "sha256-<base64>".
+ * Downloads that do not match it are rejected by `fetch`; cached files are
+ * keyed by it, so changing the hash fetches the file again.
+ *
+ * @param {string} url
+ * @param {string} [integrity]
+ * @returns {Promise<Uint8Array>}
+ */
+export async function loadWeights(url, integrity) {
+  const key = integrity ? `${url}#${integrity}` : url;
+
+  let db = null;
+  try {
+    db = await openDatabase();
+    const cached = await runTransaction(db, "readonly", (store) => store.get(key));
+    if (cached) {
+      return new Uint8Array(cached);
+    }
+  } catch (error) {
+    // Private browsing can block IndexedDB; fall back to the network.
+    console.warn("Weight cache unavailable:", error);
+  }
+
+  const response = await fetch(url, integrity ? { integrity } : {});
+  if (!response.ok) {
+    throw new Error(`Failed to fetch weights from ${url}: ${response.status}`);
+  }
+  const buffer = await response.arrayBuffer();
+
+  if (db) {
+    try {
+      await runTransaction(db, "readwrite", (store) => store.put(buffer, key));
+    } catch (error) {
+      console.warn("Failed to cache weights:", error);
+    }
+  }
+  return new Uint8Array(buffer);
+}